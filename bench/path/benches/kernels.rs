@@ -0,0 +1,138 @@
+extern crate criterion;
+extern crate lyon;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use lyon::algorithms::aabb::fast_bounding_box;
+use lyon::extra::rust_logo::build_logo_path;
+use lyon::math::{point, Point, Transform};
+use lyon::path::{simd, Path, PathEvent};
+
+const TOLERANCE: f32 = 0.01;
+
+fn logo_path() -> Path {
+    let mut builder = Path::builder().with_svg();
+    build_logo_path(&mut builder);
+    builder.build()
+}
+
+fn bounding_box(c: &mut Criterion) {
+    let path = logo_path();
+    let mut group = c.benchmark_group("bounding_box");
+    group.bench_function("events", |b| {
+        b.iter(|| black_box(fast_bounding_box(path.iter())))
+    });
+    group.bench_function("simd", |b| b.iter(|| black_box(path.fast_bounding_box())));
+    group.finish();
+}
+
+fn transform(c: &mut Criterion) {
+    let path = logo_path();
+    let transform = Transform::new(1.5, 0.3, -0.7, 2.0, 10.0, -5.0);
+    let mut group = c.benchmark_group("transform");
+    group.bench_function("generic", |b| {
+        b.iter(|| black_box(path.clone().transformed(&transform)))
+    });
+    group.bench_function("simd", |b| {
+        b.iter(|| black_box(path.clone().transformed_affine(&transform)))
+    });
+    group.finish();
+}
+
+fn flatten(c: &mut Criterion) {
+    let path = logo_path();
+    let mut output: Vec<Point> = Vec::new();
+    let mut group = c.benchmark_group("flatten");
+    group.bench_function("geom", |b| {
+        b.iter(|| {
+            output.clear();
+            for event in path.iter() {
+                match event {
+                    PathEvent::Line { to, .. } => output.push(to),
+                    PathEvent::Quadratic { from, ctrl, to } => {
+                        let curve = lyon::geom::QuadraticBezierSegment { from, ctrl, to };
+                        curve.flatten_into(&mut output, TOLERANCE);
+                    }
+                    PathEvent::Cubic {
+                        from,
+                        ctrl1,
+                        ctrl2,
+                        to,
+                    } => {
+                        let curve = lyon::geom::CubicBezierSegment {
+                            from,
+                            ctrl1,
+                            ctrl2,
+                            to,
+                        };
+                        curve.flatten_into(&mut output, TOLERANCE);
+                    }
+                    _ => {}
+                }
+            }
+            black_box(output.len())
+        })
+    });
+    group.bench_function("simd", |b| {
+        b.iter(|| {
+            output.clear();
+            for event in path.iter() {
+                match event {
+                    PathEvent::Line { to, .. } => output.push(to),
+                    PathEvent::Quadratic { from, ctrl, to } => {
+                        let curve = lyon::geom::QuadraticBezierSegment { from, ctrl, to };
+                        simd::flatten_quadratic_into(&curve, TOLERANCE, &mut output);
+                    }
+                    PathEvent::Cubic {
+                        from,
+                        ctrl1,
+                        ctrl2,
+                        to,
+                    } => {
+                        let curve = lyon::geom::CubicBezierSegment {
+                            from,
+                            ctrl1,
+                            ctrl2,
+                            to,
+                        };
+                        simd::flatten_cubic_into(&curve, TOLERANCE, &mut output);
+                    }
+                    _ => {}
+                }
+            }
+            black_box(output.len())
+        })
+    });
+    group.finish();
+}
+
+fn flatten_long_curve(c: &mut Criterion) {
+    let curve = lyon::geom::QuadraticBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl: point(1000.0, 0.0),
+        to: point(1000.0, 1000.0),
+    };
+    let mut output: Vec<Point> = Vec::new();
+    let mut group = c.benchmark_group("flatten_long_curve");
+    group.bench_function("geom", |b| {
+        b.iter(|| {
+            output.clear();
+            black_box(curve.flatten_into(&mut output, TOLERANCE))
+        })
+    });
+    group.bench_function("simd", |b| {
+        b.iter(|| {
+            output.clear();
+            black_box(simd::flatten_quadratic_into(&curve, TOLERANCE, &mut output))
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(
+    kernels,
+    bounding_box,
+    transform,
+    flatten,
+    flatten_long_curve
+);
+criterion_main!(kernels);
@@ -582,6 +582,7 @@ fn get_line_join(matches: &ArgMatches) -> LineJoin {
             "MiterClip" => LineJoin::MiterClip,
             "Round" => LineJoin::Round,
             "Bevel" => LineJoin::Bevel,
+            "Arcs" => LineJoin::Arcs,
             _ => LineJoin::Miter,
         }
     } else {
@@ -595,6 +596,7 @@ fn get_line_cap(matches: &ArgMatches) -> LineCap {
             "Butt" => LineCap::Butt,
             "Square" => LineCap::Square,
             "Round" => LineCap::Round,
+            "Arrow" => LineCap::Arrow,
             _ => LineCap::Butt,
         }
     } else {
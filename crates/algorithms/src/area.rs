@@ -1,7 +1,8 @@
-//! Approximate the area of a path.
+//! Compute the area and centroid of a path.
 
-use crate::geom::vector;
-use crate::path::{iterator::PathIterator, PathEvent};
+use crate::geom::{CubicBezierSegment, LineSegment, QuadraticBezierSegment};
+use crate::math::{vector, Point, Vector};
+use crate::path::{iterator::PathIterator, sub_path::ClosedSubPath, PathEvent};
 
 /// Compute the signed area of a path by summing the signed areas of its sub-paths.
 pub fn approximate_signed_area<Iter>(tolerance: f32, path: Iter) -> f32
@@ -60,6 +61,98 @@ where
     None
 }
 
+/// Compute the exact signed area of a path.
+///
+/// Unlike `approximate_signed_area`, the curves are integrated analytically. Sub-paths are
+/// implicitly closed.
+pub fn signed_area<Iter>(path: Iter) -> f32
+where
+    Iter: IntoIterator<Item = PathEvent>,
+{
+    area_and_moment(path).0
+}
+
+/// Compute the centroid of the region enclosed by a path.
+///
+/// Sub-paths are implicitly closed and their contributions are weighted by their signed
+/// areas.
+///
+/// Returns `None` if the path has no area.
+pub fn centroid<Iter>(path: Iter) -> Option<Point>
+where
+    Iter: IntoIterator<Item = PathEvent>,
+{
+    let (area, moment) = area_and_moment(path);
+    if area == 0.0 {
+        return None;
+    }
+
+    Some((moment / area).to_point())
+}
+
+/// Compute the exact signed area enclosed by a closed sub-path.
+///
+/// Unlike `signed_area`, only accepts closed sub-paths (see `Path::sub_paths`), so that the
+/// result can't silently include the implicit closing segment of an open sub-path.
+pub fn enclosed_area(sub_path: ClosedSubPath) -> f32 {
+    area_and_moment(sub_path).0
+}
+
+/// Compute the centroid of the region enclosed by a closed sub-path.
+///
+/// Returns `None` if the sub-path has no area.
+pub fn enclosed_centroid(sub_path: ClosedSubPath) -> Option<Point> {
+    centroid(sub_path)
+}
+
+fn area_and_moment<Iter>(path: Iter) -> (f32, Vector)
+where
+    Iter: IntoIterator<Item = PathEvent>,
+{
+    let mut area = 0.0;
+    let mut moment = vector(0.0, 0.0);
+    for evt in path {
+        match evt {
+            PathEvent::Begin { .. } => {}
+            PathEvent::Line { from, to } => {
+                let segment = LineSegment { from, to };
+                area += segment.signed_area();
+                moment += segment.first_moment();
+            }
+            PathEvent::Quadratic { from, ctrl, to } => {
+                let segment = QuadraticBezierSegment { from, ctrl, to };
+                area += segment.signed_area();
+                moment += segment.first_moment();
+            }
+            PathEvent::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => {
+                let segment = CubicBezierSegment {
+                    from,
+                    ctrl1,
+                    ctrl2,
+                    to,
+                };
+                area += segment.signed_area();
+                moment += segment.first_moment();
+            }
+            PathEvent::End { last, first, .. } => {
+                let segment = LineSegment {
+                    from: last,
+                    to: first,
+                };
+                area += segment.signed_area();
+                moment += segment.first_moment();
+            }
+        }
+    }
+
+    (area, moment)
+}
+
 /// Iterator over the sub-path areas of a path.
 pub struct SignedAreas<Iter = PathEvent>(pub Iter, f32);
 
@@ -72,7 +165,7 @@ impl<Iter: Iterator<Item = PathEvent>> Iterator for SignedAreas<Iter> {
 
 #[test]
 fn sub_path_signed_area() {
-    use crate::geom::point;
+    use crate::math::point;
     let mut path = crate::path::Path::builder();
 
     path.begin(point(0.0, 0.0));
@@ -116,3 +209,64 @@ fn sub_path_signed_area() {
 
     assert_eq!(approximate_signed_area(0.01, path.build().iter()), 5.0);
 }
+
+#[test]
+fn exact_area_and_centroid() {
+    use crate::math::point;
+
+    let mut path = crate::path::Path::builder();
+
+    path.begin(point(0.0, 0.0));
+    path.line_to(point(2.0, 0.0));
+    path.quadratic_bezier_to(point(1.0, 2.0), point(0.0, 0.0));
+    path.end(false);
+
+    path.begin(point(10.0, 10.0));
+    path.line_to(point(12.0, 10.0));
+    path.line_to(point(12.0, 12.0));
+    path.line_to(point(10.0, 12.0));
+    path.close();
+
+    let path = path.build();
+
+    let area = signed_area(path.iter());
+    assert!((area - (4.0 + 4.0 / 3.0)).abs() < 0.0001);
+
+    let expected = (point(1.0, 0.4).to_vector() * 4.0 / 3.0 + vector(11.0, 11.0) * 4.0) / area;
+    assert!((centroid(path.iter()).unwrap() - expected.to_point()).length() < 0.0001);
+
+    let mut empty = crate::path::Path::builder();
+    empty.begin(point(0.0, 0.0));
+    empty.line_to(point(1.0, 1.0));
+    empty.end(false);
+    assert_eq!(centroid(empty.build().iter()), None);
+}
+
+#[test]
+fn enclosed_area_of_closed_sub_paths() {
+    use crate::math::point;
+    use crate::path::sub_path::SubPath;
+
+    let mut path = crate::path::Path::builder();
+    path.begin(point(0.0, 0.0));
+    path.line_to(point(2.0, 0.0));
+    path.line_to(point(2.0, 2.0));
+    path.line_to(point(0.0, 2.0));
+    path.close();
+    // Open sub-paths are skipped.
+    path.begin(point(10.0, 0.0));
+    path.line_to(point(11.0, 0.0));
+    path.line_to(point(11.0, 1.0));
+    path.end(false);
+    let path = path.build();
+
+    let areas: Vec<f32> = path
+        .sub_paths()
+        .filter_map(SubPath::into_closed)
+        .map(enclosed_area)
+        .collect();
+    assert_eq!(areas, vec![4.0]);
+
+    let closed = path.sub_paths().next().unwrap().into_closed().unwrap();
+    assert_eq!(enclosed_centroid(closed), Some(point(1.0, 1.0)));
+}
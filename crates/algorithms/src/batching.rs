@@ -0,0 +1,177 @@
+//! Group paths into batches that can be rendered with a single draw call each.
+//!
+//! Rendering paths in painting order typically requires switching pipelines or textures
+//! between paths of different types. When paths don't overlap, their relative order does
+//! not affect the rendered result, so paths of the same type can be moved into the same
+//! batch to reduce the number of draw calls.
+//!
+//! ## Example
+//!
+//! ```
+//! use lyon_algorithms::batching::{batch_paths, BatchItem};
+//! use lyon_algorithms::math::{point, Box2D};
+//!
+//! let items = [
+//!     // A red and a blue path that don't overlap, followed by a red path.
+//!     BatchItem::new(Box2D::new(point(0.0, 0.0), point(10.0, 10.0)), 0, 1),
+//!     BatchItem::new(Box2D::new(point(20.0, 0.0), point(30.0, 10.0)), 1, 2),
+//!     BatchItem::new(Box2D::new(point(40.0, 0.0), point(50.0, 10.0)), 2, 1),
+//! ];
+//!
+//! let batches = batch_paths(&items, 16);
+//!
+//! // The two red paths are drawn in the same batch.
+//! assert_eq!(batches.len(), 2);
+//! assert_eq!(batches[0].items, vec![0, 2]);
+//! assert_eq!(batches[1].items, vec![1]);
+//! ```
+
+use crate::math::Box2D;
+
+/// A path to batch.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BatchItem {
+    /// A rectangle containing the rendered path.
+    pub bounding_box: Box2D,
+    /// Painting order. Paths with a higher z-index are drawn on top.
+    ///
+    /// Paths with the same z-index are drawn in the order they are provided.
+    pub z_index: i32,
+    /// Only paths with the same key can be part of the same batch.
+    ///
+    /// This typically identifies a pipeline, a shader or a texture.
+    pub key: u32,
+}
+
+impl BatchItem {
+    #[inline]
+    pub fn new(bounding_box: Box2D, z_index: i32, key: u32) -> Self {
+        BatchItem {
+            bounding_box,
+            z_index,
+            key,
+        }
+    }
+}
+
+/// A group of paths that can be drawn together.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Batch {
+    /// The key shared by all paths of the batch.
+    pub key: u32,
+    /// Indices of the paths in the batch, in the order they must be drawn.
+    pub items: Vec<usize>,
+    /// The union of the bounding boxes of the paths in the batch.
+    pub bounding_box: Box2D,
+}
+
+/// Groups paths into batches.
+///
+/// Batches must be drawn in the order they are returned and the paths of each batch in
+/// the order of `Batch::items`. The rendered result is the same as drawing all paths
+/// in painting order.
+///
+/// A path is added to an earlier batch with the same key if it does not overlap any path
+/// of the batches that come after it. At most `max_lookback` batches are considered for
+/// each path, which bounds the cost of the algorithm to `O(n * max_lookback)` bounding box
+/// tests in the common case.
+pub fn batch_paths(items: &[BatchItem], max_lookback: usize) -> Vec<Batch> {
+    let mut order: Vec<usize> = (0..items.len()).collect();
+    // The sort is stable so that paths with the same z-index keep their order.
+    order.sort_by_key(|idx| items[*idx].z_index);
+
+    let mut batches: Vec<Batch> = Vec::new();
+
+    for idx in order {
+        let item = &items[idx];
+        let mut target = None;
+
+        for (batch_idx, batch) in batches.iter().enumerate().rev().take(max_lookback.max(1)) {
+            if batch.key == item.key {
+                target = Some(batch_idx);
+                break;
+            }
+
+            if batch_overlaps(batch, items, &item.bounding_box) {
+                break;
+            }
+        }
+
+        match target {
+            Some(batch_idx) => {
+                let batch = &mut batches[batch_idx];
+                batch.items.push(idx);
+                batch.bounding_box = batch.bounding_box.union(&item.bounding_box);
+            }
+            None => {
+                batches.push(Batch {
+                    key: item.key,
+                    items: vec![idx],
+                    bounding_box: item.bounding_box,
+                });
+            }
+        }
+    }
+
+    batches
+}
+
+fn batch_overlaps(batch: &Batch, items: &[BatchItem], rect: &Box2D) -> bool {
+    if !batch.bounding_box.intersects(rect) {
+        return false;
+    }
+
+    batch
+        .items
+        .iter()
+        .any(|idx| items[*idx].bounding_box.intersects(rect))
+}
+
+#[cfg(test)]
+fn rect(x0: f32, y0: f32, x1: f32, y1: f32) -> Box2D {
+    Box2D::new(crate::math::point(x0, y0), crate::math::point(x1, y1))
+}
+
+#[test]
+fn overlapping_paths_keep_order() {
+    let items = [
+        BatchItem::new(rect(0.0, 0.0, 10.0, 10.0), 0, 1),
+        BatchItem::new(rect(5.0, 5.0, 15.0, 15.0), 1, 2),
+        BatchItem::new(rect(8.0, 8.0, 20.0, 20.0), 2, 1),
+    ];
+
+    let batches = batch_paths(&items, 16);
+
+    let items: Vec<Vec<usize>> = batches.iter().map(|b| b.items.clone()).collect();
+    assert_eq!(items, vec![vec![0], vec![1], vec![2]]);
+}
+
+#[test]
+fn z_index_ordering() {
+    let items = [
+        BatchItem::new(rect(0.0, 0.0, 10.0, 10.0), 5, 1),
+        BatchItem::new(rect(0.0, 0.0, 10.0, 10.0), 0, 2),
+        BatchItem::new(rect(20.0, 0.0, 30.0, 10.0), 0, 1),
+        BatchItem::new(rect(40.0, 0.0, 50.0, 10.0), 1, 2),
+    ];
+
+    let batches = batch_paths(&items, 16);
+
+    // Item 1 is drawn first, item 0 must be drawn after it.
+    let items: Vec<(u32, Vec<usize>)> = batches.iter().map(|b| (b.key, b.items.clone())).collect();
+    assert_eq!(items, vec![(2, vec![1, 3]), (1, vec![2, 0])]);
+    assert_eq!(batches[1].bounding_box, rect(0.0, 0.0, 30.0, 10.0));
+}
+
+#[test]
+fn max_lookback() {
+    let items = [
+        BatchItem::new(rect(0.0, 0.0, 1.0, 1.0), 0, 1),
+        BatchItem::new(rect(2.0, 0.0, 3.0, 1.0), 0, 2),
+        BatchItem::new(rect(4.0, 0.0, 5.0, 1.0), 0, 3),
+        BatchItem::new(rect(6.0, 0.0, 7.0, 1.0), 0, 1),
+    ];
+
+    assert_eq!(batch_paths(&items, 16).len(), 3);
+    assert_eq!(batch_paths(&items, 2).len(), 4);
+}
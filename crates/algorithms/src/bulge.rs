@@ -0,0 +1,481 @@
+//! Conversion between paths and bulge-encoded polylines.
+//!
+//! CAD formats such as DXF describe outlines as polylines where each vertex has a *bulge*
+//! factor describing the segment that starts at this vertex: `0.0` for a straight line,
+//! otherwise a circular arc. The bulge is the tangent of a quarter of the arc's included
+//! angle, positive for arcs going in the positive angle direction (counter-clockwise
+//! in a y-up coordinate system). A bulge of `1.0` is a half circle.
+//!
+//! - [`to_path`](fn.to_path.html) converts polylines into a path, arcs being approximated
+//!   with cubic bézier curves.
+//! - [`from_path`](fn.from_path.html) converts a path into polylines. Cubic bézier curves
+//!   that approximate circular arcs (like the ones produced by `to_path` or by the arc
+//!   methods of the path builders) are converted back into bulges, and consecutive pieces
+//!   of the same arc are merged. Other curves are flattened.
+//!
+//! # Example
+//!
+//! ```
+//! use lyon_algorithms::bulge::{from_path, to_path, BulgePolyline, BulgeVertex};
+//! use lyon_algorithms::math::point;
+//!
+//! // A stadium shape: two half circles connected by straight lines.
+//! let stadium = BulgePolyline {
+//!     vertices: vec![
+//!         BulgeVertex::new(point(0.0, 0.0), 0.0),
+//!         BulgeVertex::new(point(10.0, 0.0), 1.0),
+//!         BulgeVertex::new(point(10.0, 4.0), 0.0),
+//!         BulgeVertex::new(point(0.0, 4.0), 1.0),
+//!     ],
+//!     closed: true,
+//! };
+//!
+//! let path = to_path(&[stadium]);
+//! let polylines = from_path(&path, 0.05);
+//!
+//! assert_eq!(polylines.len(), 1);
+//! assert_eq!(polylines[0].vertices.len(), 4);
+//! assert!((polylines[0].vertices[1].bulge - 1.0).abs() < 0.001);
+//! ```
+
+use crate::geom::{Arc, CubicBezierSegment, LineSegment};
+use crate::math::{vector, Angle, Point, Vector};
+use crate::path::builder::PathBuilder;
+use crate::path::{Path, PathEvent, NO_ATTRIBUTES};
+use std::f32::consts::PI;
+
+/// A vertex of a bulge-encoded polyline.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct BulgeVertex {
+    pub position: Point,
+    /// Describes the segment from this vertex to the next one.
+    ///
+    /// For the last vertex, it describes the closing segment if the polyline is closed,
+    /// and is ignored otherwise.
+    pub bulge: f32,
+}
+
+impl BulgeVertex {
+    #[inline]
+    pub fn new(position: Point, bulge: f32) -> Self {
+        BulgeVertex { position, bulge }
+    }
+}
+
+/// A polyline with straight and circular segments.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct BulgePolyline {
+    pub vertices: Vec<BulgeVertex>,
+    pub closed: bool,
+}
+
+impl BulgePolyline {
+    /// Adds this polyline as a sub-path to the output.
+    pub fn build(&self, output: &mut impl PathBuilder) {
+        let first = match self.vertices.first() {
+            Some(first) => first,
+            None => {
+                return;
+            }
+        };
+
+        output.begin(first.position, NO_ATTRIBUTES);
+        let n = self.vertices.len();
+        let segments = if self.closed { n } else { n - 1 };
+        for i in 0..segments {
+            let from = self.vertices[i];
+            let to = self.vertices[(i + 1) % n].position;
+            build_segment(from.position, to, from.bulge, output);
+        }
+        output.end(self.closed);
+    }
+}
+
+/// Returns a path containing the polylines.
+pub fn to_path(polylines: &[BulgePolyline]) -> Path {
+    let mut builder = Path::builder();
+    for polyline in polylines {
+        polyline.build(&mut builder);
+    }
+
+    builder.build()
+}
+
+/// Converts a path into polylines, one per sub-path.
+///
+/// The tolerance is the maximum distance between the curves of the path and the arcs or
+/// lines that replace them. The cubic bézier approximations of quarter circles produced by
+/// lyon deviate from the circle by about `0.0015` times the radius, so smaller tolerances
+/// cause such arcs to be flattened.
+pub fn from_path(path: impl IntoIterator<Item = PathEvent>, tolerance: f32) -> Vec<BulgePolyline> {
+    let mut polylines = Vec::new();
+    let mut builder = PolylineBuilder::new(tolerance);
+    for event in path {
+        match event {
+            PathEvent::Begin { at } => {
+                builder.begin(at);
+            }
+            PathEvent::Line { to, .. } => {
+                builder.line_to(to);
+            }
+            PathEvent::Quadratic { from, ctrl, to } => {
+                builder
+                    .cubic_to(&crate::geom::QuadraticBezierSegment { from, ctrl, to }.to_cubic());
+            }
+            PathEvent::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => {
+                builder.cubic_to(&CubicBezierSegment {
+                    from,
+                    ctrl1,
+                    ctrl2,
+                    to,
+                });
+            }
+            PathEvent::End { close, .. } => {
+                polylines.push(builder.end(close));
+            }
+        }
+    }
+
+    polylines
+}
+
+fn build_segment(from: Point, to: Point, bulge: f32, output: &mut impl PathBuilder) {
+    if bulge == 0.0 || from == to {
+        output.line_to(to, NO_ATTRIBUTES);
+        return;
+    }
+
+    // Split the arc into pieces of at most a quarter turn, each approximated with a single
+    // cubic bézier curve.
+    let arc = bulge_arc(from, to, bulge);
+    let pieces = (arc.sweep_angle.radians.abs() / (PI * 0.5)).ceil().max(1.0) as u32;
+    let mut curves = Vec::with_capacity(pieces as usize);
+    for i in 0..pieces {
+        let piece = arc.split_range(i as f32 / pieces as f32..(i + 1) as f32 / pieces as f32);
+        piece.for_each_cubic_bezier(&mut |curve: &CubicBezierSegment<f32>| {
+            curves.push(*curve);
+        });
+    }
+
+    // Make sure the segment ends exactly at the next vertex.
+    if let Some(last) = curves.last_mut() {
+        last.ctrl2 += to - last.to;
+        last.to = to;
+    }
+    for curve in &curves {
+        output.cubic_bezier_to(curve.ctrl1, curve.ctrl2, curve.to, NO_ATTRIBUTES);
+    }
+}
+
+/// The arc between two points described by a bulge factor.
+fn bulge_arc(from: Point, to: Point, bulge: f32) -> Arc<f32> {
+    let v = to - from;
+    let chord = v.length();
+    let normal = vector(-v.y, v.x) / chord;
+    // Signed distance from the middle of the chord to the center, and radius.
+    let distance = chord * (1.0 - bulge * bulge) / (4.0 * bulge);
+    let radius = chord * (1.0 + bulge * bulge) / (4.0 * bulge.abs());
+    let center = from.lerp(to, 0.5) + normal * distance;
+
+    let mut arc = Arc {
+        center,
+        radii: vector(radius, radius),
+        start_angle: (from - center).angle_from_x_axis(),
+        sweep_angle: Angle::radians(4.0 * bulge.atan()),
+        x_rotation: Angle::zero(),
+    };
+    // Snap the end of the arc on the next vertex.
+    arc.sweep_angle = Angle::radians(
+        arc.sweep_angle.radians.signum()
+            * positive_angle(
+                (to - center).angle_from_x_axis().radians - arc.start_angle.radians,
+                arc.sweep_angle.radians,
+            ),
+    );
+
+    arc
+}
+
+/// The angle between `0` and `2π` equivalent to `angle` in the direction of `sign`.
+fn positive_angle(angle: f32, sign: f32) -> f32 {
+    let mut angle = angle * sign.signum();
+    while angle < 0.0 {
+        angle += 2.0 * PI;
+    }
+    while angle >= 2.0 * PI {
+        angle -= 2.0 * PI;
+    }
+
+    angle
+}
+
+/// A circular arc recognized in the path.
+#[derive(Copy, Clone, Debug)]
+struct ArcInfo {
+    center: Point,
+    radius: f32,
+    sweep: f32,
+}
+
+struct PolylineBuilder {
+    tolerance: f32,
+    vertices: Vec<BulgeVertex>,
+    /// The arc ending at the last vertex, if any.
+    last_arc: Option<ArcInfo>,
+}
+
+impl PolylineBuilder {
+    fn new(tolerance: f32) -> Self {
+        PolylineBuilder {
+            tolerance,
+            vertices: Vec::new(),
+            last_arc: None,
+        }
+    }
+
+    fn begin(&mut self, at: Point) {
+        self.vertices.clear();
+        self.vertices.push(BulgeVertex::new(at, 0.0));
+        self.last_arc = None;
+    }
+
+    fn line_to(&mut self, to: Point) {
+        if self.vertices.last().map(|v| v.position) == Some(to) {
+            return;
+        }
+        self.vertices.push(BulgeVertex::new(to, 0.0));
+        self.last_arc = None;
+    }
+
+    fn cubic_to(&mut self, curve: &CubicBezierSegment<f32>) {
+        if curve.is_linear(self.tolerance) {
+            self.line_to(curve.to);
+            return;
+        }
+
+        let arc = match recognize_arc(curve, self.tolerance) {
+            Some(arc) => arc,
+            None => {
+                curve.for_each_flattened(self.tolerance, &mut |line: &LineSegment<f32>| {
+                    self.line_to(line.to);
+                });
+                return;
+            }
+        };
+
+        // Merge with the previous piece of the same arc.
+        if let Some(prev) = self.last_arc {
+            let sweep = prev.sweep + arc.sweep;
+            let same_circle = [0.5, 1.0].iter().all(|t| {
+                let distance = (curve.sample(*t) - prev.center).length();
+                (distance - prev.radius).abs() <= self.tolerance
+            });
+            if same_circle && prev.sweep * arc.sweep > 0.0 && sweep.abs() < 2.0 * PI - 0.01 {
+                self.vertices.pop();
+                let start = self.vertices.last_mut().unwrap();
+                start.bulge = (sweep / 4.0).tan();
+                self.vertices.push(BulgeVertex::new(curve.to, 0.0));
+                self.last_arc = Some(ArcInfo { sweep, ..arc });
+                return;
+            }
+        }
+
+        self.vertices.last_mut().unwrap().bulge = (arc.sweep / 4.0).tan();
+        self.vertices.push(BulgeVertex::new(curve.to, 0.0));
+        self.last_arc = Some(arc);
+    }
+
+    fn end(&mut self, close: bool) -> BulgePolyline {
+        let mut vertices = std::mem::take(&mut self.vertices);
+        if close && vertices.len() > 1 {
+            let first = vertices[0].position;
+            let last = vertices[vertices.len() - 1].position;
+            if (last - first).length() <= self.tolerance {
+                // The last segment goes back to the first vertex: it becomes the closing
+                // segment, described by the bulge of the previous vertex.
+                vertices.pop();
+            }
+        }
+        self.last_arc = None;
+
+        BulgePolyline {
+            vertices,
+            closed: close,
+        }
+    }
+}
+
+/// Checks whether a cubic bézier curve approximates a circular arc.
+fn recognize_arc(curve: &CubicBezierSegment<f32>, tolerance: f32) -> Option<ArcInfo> {
+    let t0 = start_tangent(curve)?;
+    let t1 = end_tangent(curve)?;
+
+    // The approximations of arcs have the same tangents as the arc at their endpoints, so
+    // the center is where the normals at the endpoints meet. When the normals are parallel,
+    // fall back to the circle going through the endpoints and the middle of the curve.
+    let (n0, n1) = (vector(-t0.y, t0.x), vector(-t1.y, t1.x));
+    let denom = n0.cross(n1);
+    let center = if denom.abs() > 1e-3 * n0.length() * n1.length() {
+        curve.from + n0 * ((curve.to - curve.from).cross(n1) / denom)
+    } else {
+        circumcenter(curve.from, curve.sample(0.5), curve.to)?
+    };
+
+    let radius = ((curve.from - center).length() + (curve.to - center).length()) * 0.5;
+    for t in [0.0, 0.125, 0.25, 0.375, 0.5, 0.625, 0.75, 0.875, 1.0] {
+        let distance = (curve.sample(t) - center).length();
+        if (distance - radius).abs() > tolerance {
+            return None;
+        }
+    }
+
+    // The tangent at the start of the curve tells in which direction the arc goes.
+    let radial = curve.from - center;
+    let direction = radial.cross(t0).signum();
+    let sweep = direction * positive_angle(radial.angle_to(curve.to - center).radians, direction);
+
+    Some(ArcInfo {
+        center,
+        radius,
+        sweep,
+    })
+}
+
+/// The center of the circle going through three points.
+fn circumcenter(a: Point, b: Point, c: Point) -> Option<Point> {
+    let (b, c) = (b - a, c - a);
+    let d = 2.0 * b.cross(c);
+    if d.abs() < 1e-6 {
+        return None;
+    }
+
+    let (b2, c2) = (b.square_length(), c.square_length());
+    Some(a + vector(c.y * b2 - b.y * c2, b.x * c2 - c.x * b2) / d)
+}
+
+fn start_tangent(curve: &CubicBezierSegment<f32>) -> Option<Vector> {
+    for ctrl in [curve.ctrl1, curve.ctrl2, curve.to] {
+        if ctrl != curve.from {
+            return Some(ctrl - curve.from);
+        }
+    }
+
+    None
+}
+
+fn end_tangent(curve: &CubicBezierSegment<f32>) -> Option<Vector> {
+    for ctrl in [curve.ctrl2, curve.ctrl1, curve.from] {
+        if ctrl != curve.to {
+            return Some(curve.to - ctrl);
+        }
+    }
+
+    None
+}
+
+#[test]
+fn bulge_to_path() {
+    use crate::math::point;
+
+    // A half circle from (0, 0) to (10, 0), going through (5, -5) in the positive
+    // direction.
+    let polyline = BulgePolyline {
+        vertices: vec![
+            BulgeVertex::new(point(0.0, 0.0), 1.0),
+            BulgeVertex::new(point(10.0, 0.0), 0.0),
+        ],
+        closed: false,
+    };
+    let path = to_path(&[polyline]);
+
+    let mut last = point(0.0, 0.0);
+    let mut min_y = 0.0f32;
+    for event in path.iter() {
+        if let PathEvent::Cubic { to, .. } = event {
+            assert!(((to - point(5.0, 0.0)).length() - 5.0).abs() < 1e-4);
+            min_y = min_y.min(to.y);
+        }
+        last = event.from();
+    }
+    assert_eq!(last, point(10.0, 0.0));
+    assert!((min_y + 5.0).abs() < 1e-4);
+
+    // Negative bulges go the other way.
+    let polyline = BulgePolyline {
+        vertices: vec![
+            BulgeVertex::new(point(0.0, 0.0), -0.5),
+            BulgeVertex::new(point(10.0, 0.0), 0.0),
+        ],
+        closed: false,
+    };
+    for event in to_path(&[polyline]).iter() {
+        if let PathEvent::Cubic { ctrl1, .. } = event {
+            assert!(ctrl1.y > 0.0);
+        }
+    }
+}
+
+#[test]
+fn bulge_round_trip() {
+    use crate::math::point;
+
+    let polylines = vec![
+        BulgePolyline {
+            vertices: vec![
+                BulgeVertex::new(point(0.0, 0.0), 0.0),
+                BulgeVertex::new(point(20.0, 0.0), -0.3),
+                BulgeVertex::new(point(20.0, 10.0), 2.0),
+                BulgeVertex::new(point(5.0, 15.0), 0.0),
+            ],
+            closed: true,
+        },
+        BulgePolyline {
+            vertices: vec![
+                BulgeVertex::new(point(30.0, 0.0), 0.4),
+                BulgeVertex::new(point(40.0, 0.0), 0.0),
+                BulgeVertex::new(point(50.0, 5.0), 0.0),
+            ],
+            closed: false,
+        },
+    ];
+
+    let result = from_path(&to_path(&polylines), 0.05);
+    assert_eq!(result.len(), polylines.len());
+    for (a, b) in polylines.iter().zip(result.iter()) {
+        assert_eq!(a.closed, b.closed);
+        assert_eq!(a.vertices.len(), b.vertices.len());
+        let n = if a.closed {
+            a.vertices.len()
+        } else {
+            a.vertices.len() - 1
+        };
+        for (i, (va, vb)) in a.vertices.iter().zip(b.vertices.iter()).enumerate() {
+            assert!((va.position - vb.position).length() < 1e-4);
+            if i < n {
+                assert!((va.bulge - vb.bulge).abs() < 1e-3, "{:?} {:?}", va, vb);
+            }
+        }
+    }
+
+    // Curves that are not arcs are flattened.
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.cubic_bezier_to(point(10.0, 10.0), point(20.0, -10.0), point(30.0, 0.0));
+    builder.end(false);
+    let result = from_path(&builder.build(), 0.1);
+    assert!(result[0].vertices.len() > 3);
+    assert!(result[0].vertices.iter().all(|v| v.bulge == 0.0));
+}
@@ -0,0 +1,322 @@
+//! A bounding volume hierarchy over the bounding rectangles of a scene of paths.
+//!
+//! Hit-testing or culling a document with many paths by iterating over all of them is
+//! slow. The `Bvh` organizes the bounding rectangles of the paths into a tree so that
+//! queries only visit the paths that are near the query.
+//!
+//! The hierarchy only deals with rectangles: queries return the paths whose bounding
+//! rectangle matches, and precise tests (for example with `hit_test_path` or
+//! `raycast_path`) can then be run on these candidates.
+//!
+//! ## Example
+//!
+//! ```
+//! use lyon_algorithms::bvh::Bvh;
+//! use lyon_algorithms::math::{point, Box2D};
+//!
+//! let rects = [
+//!     Box2D::new(point(0.0, 0.0), point(10.0, 10.0)),
+//!     Box2D::new(point(20.0, 0.0), point(30.0, 10.0)),
+//! ];
+//!
+//! let bvh = Bvh::new(&rects);
+//!
+//! let mut hits = Vec::new();
+//! bvh.for_each_at_point(point(25.0, 5.0), &mut |path_index| hits.push(path_index));
+//! assert_eq!(hits, vec![1]);
+//! ```
+
+use crate::math::{Box2D, Point};
+use crate::raycast::Ray;
+
+const MAX_LEAF_SIZE: usize = 4;
+
+#[derive(Copy, Clone, Debug)]
+struct Node {
+    rect: Box2D,
+    // For leaves, the index of the first item in `Bvh::items`, otherwise the index of the
+    // first of the two child nodes.
+    first: u32,
+    // Number of items in a leaf, zero for internal nodes.
+    count: u32,
+}
+
+/// A bounding volume hierarchy over a set of rectangles.
+///
+/// Rectangles are identified by their index in the slice the hierarchy was built with.
+#[derive(Clone, Debug, Default)]
+pub struct Bvh {
+    nodes: Vec<Node>,
+    items: Vec<u32>,
+    rects: Vec<Box2D>,
+}
+
+impl Bvh {
+    /// Builds the hierarchy.
+    pub fn new(rects: &[Box2D]) -> Self {
+        let mut bvh = Bvh {
+            nodes: Vec::with_capacity(rects.len() * 2 / MAX_LEAF_SIZE + 1),
+            items: (0..rects.len() as u32).collect(),
+            rects: rects.to_vec(),
+        };
+
+        if !rects.is_empty() {
+            bvh.nodes.push(Node {
+                rect: Box2D::zero(),
+                first: 0,
+                count: 0,
+            });
+            bvh.build_node(0, 0..rects.len(), rects);
+        }
+
+        bvh
+    }
+
+    fn build_node(&mut self, node_idx: usize, range: std::ops::Range<usize>, rects: &[Box2D]) {
+        let items = &mut self.items[range.clone()];
+        let rect = union(items.iter().map(|idx| &rects[*idx as usize]));
+
+        if items.len() <= MAX_LEAF_SIZE {
+            self.nodes[node_idx] = Node {
+                rect,
+                first: range.start as u32,
+                count: items.len() as u32,
+            };
+            return;
+        }
+
+        // Split along the longest axis of the rectangle centers, at the median.
+        let centers = Box2D::from_points(items.iter().map(|idx| rects[*idx as usize].center()));
+        let size = centers.size();
+        let center = |idx: &u32| {
+            let c = rects[*idx as usize].center();
+            if size.width >= size.height {
+                c.x
+            } else {
+                c.y
+            }
+        };
+        let mid = items.len() / 2;
+        items.select_nth_unstable_by(mid, |a, b| center(a).total_cmp(&center(b)));
+
+        let first_child = self.nodes.len();
+        let placeholder = self.nodes[node_idx];
+        self.nodes.push(placeholder);
+        self.nodes.push(placeholder);
+        self.nodes[node_idx] = Node {
+            rect,
+            first: first_child as u32,
+            count: 0,
+        };
+
+        let mid = range.start + mid;
+        self.build_node(first_child, range.start..mid, rects);
+        self.build_node(first_child + 1, mid..range.end, rects);
+    }
+
+    /// Updates the bounds of the hierarchy after some of the rectangles have moved or
+    /// changed size.
+    ///
+    /// `rects` must contain the same number of rectangles as when the hierarchy was built.
+    /// The structure of the tree is preserved, so queries get slower if the rectangles
+    /// move a lot, in which case it is better to build a new hierarchy.
+    pub fn refit(&mut self, rects: &[Box2D]) {
+        assert_eq!(rects.len(), self.items.len());
+        self.rects.clear();
+        self.rects.extend_from_slice(rects);
+
+        // Children are always stored after their parent.
+        for node_idx in (0..self.nodes.len()).rev() {
+            let node = self.nodes[node_idx];
+            let first = node.first as usize;
+            self.nodes[node_idx].rect = if node.count > 0 {
+                let items = &self.items[first..first + node.count as usize];
+                union(items.iter().map(|idx| &rects[*idx as usize]))
+            } else {
+                self.nodes[first].rect.union(&self.nodes[first + 1].rect)
+            };
+        }
+    }
+
+    /// The rectangle containing all rectangles of the hierarchy.
+    pub fn bounding_box(&self) -> Box2D {
+        self.nodes.first().map_or(Box2D::zero(), |node| node.rect)
+    }
+
+    /// Calls the callback with the index of each rectangle that contains the point.
+    pub fn for_each_at_point(&self, point: Point, callback: &mut dyn FnMut(usize)) {
+        self.traverse(&|rect| rect.contains(point), callback);
+    }
+
+    /// Calls the callback with the index of each rectangle that intersects `rect`.
+    pub fn for_each_in_rect(&self, rect: &Box2D, callback: &mut dyn FnMut(usize)) {
+        self.traverse(&|r| r.intersects(rect), callback);
+    }
+
+    /// Calls the callback with the index of each rectangle that the ray passes through.
+    pub fn for_each_on_ray(&self, ray: &Ray, callback: &mut dyn FnMut(usize)) {
+        self.traverse(&|rect| ray_intersects_rect(ray, rect), callback);
+    }
+
+    fn traverse(&self, test: &dyn Fn(&Box2D) -> bool, callback: &mut dyn FnMut(usize)) {
+        if self.nodes.is_empty() {
+            return;
+        }
+
+        let mut stack = vec![0];
+        while let Some(node_idx) = stack.pop() {
+            let node = &self.nodes[node_idx];
+            if !test(&node.rect) {
+                continue;
+            }
+
+            let first = node.first as usize;
+            if node.count == 0 {
+                stack.push(first + 1);
+                stack.push(first);
+                continue;
+            }
+
+            for idx in &self.items[first..first + node.count as usize] {
+                if test(&self.rects[*idx as usize]) {
+                    callback(*idx as usize);
+                }
+            }
+        }
+    }
+}
+
+fn union<'l>(mut rects: impl Iterator<Item = &'l Box2D>) -> Box2D {
+    let first = match rects.next() {
+        Some(rect) => *rect,
+        None => {
+            return Box2D::zero();
+        }
+    };
+
+    rects.fold(first, |acc, rect| acc.union(rect))
+}
+
+fn ray_intersects_rect(ray: &Ray, rect: &Box2D) -> bool {
+    let mut t_min = 0.0f32;
+    let mut t_max = f32::INFINITY;
+    let origin = [ray.origin.x, ray.origin.y];
+    let direction = [ray.direction.x, ray.direction.y];
+    let min = [rect.min.x, rect.min.y];
+    let max = [rect.max.x, rect.max.y];
+
+    for axis in 0..2 {
+        if direction[axis] == 0.0 {
+            if origin[axis] < min[axis] || origin[axis] > max[axis] {
+                return false;
+            }
+            continue;
+        }
+
+        let inv = 1.0 / direction[axis];
+        let mut t0 = (min[axis] - origin[axis]) * inv;
+        let mut t1 = (max[axis] - origin[axis]) * inv;
+        if t0 > t1 {
+            std::mem::swap(&mut t0, &mut t1);
+        }
+        t_min = t_min.max(t0);
+        t_max = t_max.min(t1);
+        if t_min > t_max {
+            return false;
+        }
+    }
+
+    true
+}
+
+#[cfg(test)]
+fn grid(n: usize) -> Vec<Box2D> {
+    use crate::math::point;
+    let mut rects = Vec::new();
+    for y in 0..n {
+        for x in 0..n {
+            let p = point(x as f32 * 10.0, y as f32 * 10.0);
+            rects.push(Box2D::new(p, p + crate::math::vector(5.0, 5.0)));
+        }
+    }
+    rects
+}
+
+#[cfg(test)]
+fn query(bvh: &Bvh, f: impl Fn(&Bvh, &mut dyn FnMut(usize))) -> Vec<usize> {
+    let mut result = Vec::new();
+    f(bvh, &mut |idx| result.push(idx));
+    result.sort();
+    result
+}
+
+#[test]
+fn bvh_queries() {
+    use crate::math::{point, vector};
+
+    let rects = grid(10);
+    let bvh = Bvh::new(&rects);
+
+    assert_eq!(
+        bvh.bounding_box(),
+        Box2D::new(point(0.0, 0.0), point(95.0, 95.0))
+    );
+
+    let at_point = |p| query(&bvh, |bvh, cb| bvh.for_each_at_point(p, cb));
+    assert_eq!(at_point(point(22.0, 31.0)), vec![32]);
+    assert_eq!(at_point(point(27.0, 31.0)), Vec::<usize>::new());
+
+    let in_rect = query(&bvh, |bvh, cb| {
+        bvh.for_each_in_rect(&Box2D::new(point(8.0, 8.0), point(22.0, 12.0)), cb)
+    });
+    assert_eq!(in_rect, vec![11, 12]);
+
+    let ray = Ray {
+        origin: point(-10.0, 2.0),
+        direction: vector(1.0, 0.0),
+    };
+    let on_ray = query(&bvh, |bvh, cb| bvh.for_each_on_ray(&ray, cb));
+    assert_eq!(on_ray, (0..10).collect::<Vec<_>>());
+
+    // Brute force comparison.
+    for p in [point(1.0, 1.0), point(51.0, 74.0), point(94.0, 94.0)] {
+        let expected: Vec<usize> = (0..rects.len()).filter(|i| rects[*i].contains(p)).collect();
+        assert_eq!(at_point(p), expected);
+    }
+}
+
+#[test]
+fn bvh_refit() {
+    use crate::math::{point, vector};
+
+    let mut rects = grid(4);
+    let mut bvh = Bvh::new(&rects);
+
+    rects[5] = rects[5].translate(vector(100.0, 0.0));
+    bvh.refit(&rects);
+
+    let mut hits = Vec::new();
+    bvh.for_each_at_point(point(112.0, 12.0), &mut |idx| hits.push(idx));
+    assert_eq!(hits, vec![5]);
+
+    hits.clear();
+    bvh.for_each_at_point(point(12.0, 12.0), &mut |idx| hits.push(idx));
+    assert!(hits.is_empty());
+
+    let empty = Bvh::new(&[]);
+    empty.for_each_at_point(point(0.0, 0.0), &mut |_| panic!());
+}
+
+#[test]
+fn bvh_nan_rects() {
+    use crate::math::point;
+
+    let mut rects = grid(4);
+    rects[3] = Box2D::new(point(f32::NAN, 0.0), point(f32::NAN, 5.0));
+    rects[9] = Box2D::new(point(0.0, f32::NAN), point(5.0, f32::NAN));
+    let bvh = Bvh::new(&rects);
+
+    let mut hits = Vec::new();
+    bvh.for_each_at_point(point(12.0, 12.0), &mut |idx| hits.push(idx));
+    assert_eq!(hits, vec![5]);
+}
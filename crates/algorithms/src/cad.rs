@@ -0,0 +1,672 @@
+//! CAD-style editing operations: trim, extend, fillet and chamfer.
+//!
+//! The operations take a path, edit some of its segments and return the edited path.
+//! Segments are identified by their index in the order in which
+//! [`PathEvent::to_segment`](../path/enum.Event.html#method.to_segment) produces them
+//! while iterating over the path, which includes the closing edges of closed sub-paths.
+//!
+//! - [`trim`](fn.trim.html) cuts a segment where it intersects another one.
+//! - [`extend`](fn.extend.html) prolongs a line segment at a free end of an open
+//!   sub-path until it meets another segment.
+//! - [`fillet`](fn.fillet.html) rounds the corner between two consecutive line segments
+//!   with an arc of a given radius.
+//! - [`chamfer`](fn.chamfer.html) cuts the corner between two consecutive line segments
+//!   with a straight line.
+//!
+//! Custom attributes are not preserved.
+//!
+//! # Example
+//!
+//! ```
+//! use lyon_algorithms::cad::{fillet, trim, SegmentEnd};
+//! use lyon_algorithms::math::point;
+//! use lyon_algorithms::path::Path;
+//!
+//! let mut builder = Path::builder();
+//! builder.begin(point(0.0, 0.0));
+//! builder.line_to(point(10.0, 0.0));
+//! builder.line_to(point(10.0, 10.0));
+//! builder.end(false);
+//! builder.begin(point(5.0, -5.0));
+//! builder.line_to(point(5.0, 5.0));
+//! builder.end(false);
+//! let path = builder.build();
+//!
+//! // Round the corner at (10, 0).
+//! let rounded = fillet(&path, 0, 2.0).unwrap();
+//!
+//! // Cut the second sub-path where it crosses the first segment.
+//! let trimmed = trim(&path, 2, 0, SegmentEnd::End).unwrap();
+//! assert_eq!(trimmed.iter().last().unwrap().from(), point(5.0, 0.0));
+//! ```
+
+use crate::geom::{Arc, BezierSegment, CubicBezierSegment, LineSegment, Segment};
+use crate::math::{vector, Angle, Point, Vector};
+use crate::path::{Path, PathEvent};
+
+use std::fmt;
+
+/// Tolerance on the curve parameter, to ignore intersections at the endpoints shared by
+/// consecutive segments.
+const T_EPSILON: f32 = 1e-4;
+
+/// One of the two ends of a segment.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SegmentEnd {
+    Start,
+    End,
+}
+
+/// The errors returned by the editing operations.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum EditError {
+    /// A segment index is out of range or the two segments are the same.
+    InvalidSegment,
+    /// The operation only supports line segments.
+    UnsupportedSegment,
+    /// The segments don't intersect.
+    NoIntersection,
+    /// The end of the segment is connected to another segment.
+    NotAFreeEnd,
+    /// The segment is the last one of an open sub-path and has no corner after it.
+    NoCorner,
+    /// The two segments of the corner are collinear.
+    DegenerateCorner,
+    /// The radius or the distance doesn't fit on the segments of the corner.
+    TooLarge,
+}
+
+impl fmt::Display for EditError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let msg = match self {
+            EditError::InvalidSegment => "Invalid segment",
+            EditError::UnsupportedSegment => "Unsupported segment type",
+            EditError::NoIntersection => "The segments don't intersect",
+            EditError::NotAFreeEnd => "The end of the segment is connected",
+            EditError::NoCorner => "No corner after the segment",
+            EditError::DegenerateCorner => "The segments of the corner are collinear",
+            EditError::TooLarge => "The corner is too small",
+        };
+
+        f.write_str(msg)
+    }
+}
+
+impl std::error::Error for EditError {}
+
+/// Cuts segment `segment` at its intersection with segment `by` and removes the part
+/// on the side of `remove`.
+///
+/// If there are several intersections, the one closest to the removed end is used. If the
+/// segment is connected to other segments on the removed side, its sub-path is split in
+/// two.
+pub fn trim(
+    path: impl IntoIterator<Item = PathEvent>,
+    segment: usize,
+    by: usize,
+    remove: SegmentEnd,
+) -> Result<Path, EditError> {
+    let mut sub_paths = sub_paths(path);
+    let (s, i) = locate(&sub_paths, segment)?;
+    let (by_s, by_i) = locate(&sub_paths, by)?;
+    if segment == by {
+        return Err(EditError::InvalidSegment);
+    }
+
+    let curve = sub_paths[s].segments[i];
+    let ts = intersections_t(&curve, &sub_paths[by_s].segments[by_i]);
+    let ts = ts
+        .iter()
+        .filter(|t| **t > T_EPSILON && **t < 1.0 - T_EPSILON);
+    let t = match remove {
+        SegmentEnd::End => ts.fold(None, |max: Option<f32>, t| {
+            Some(max.map_or(*t, |m| m.max(*t)))
+        }),
+        SegmentEnd::Start => ts.fold(None, |min: Option<f32>, t| {
+            Some(min.map_or(*t, |m| m.min(*t)))
+        }),
+    }
+    .ok_or(EditError::NoIntersection)?;
+
+    let sub_path = sub_paths.remove(s);
+    let mut before = sub_path.segments[..i].to_vec();
+    let mut after = sub_path.segments[i + 1..].to_vec();
+    match remove {
+        SegmentEnd::End => before.push(curve.before_split(t)),
+        SegmentEnd::Start => after.insert(0, curve.after_split(t)),
+    }
+
+    let mut parts = Vec::new();
+    if sub_path.closed {
+        // Opening a closed sub-path: what was after the cut now comes first.
+        after.append(&mut before);
+        parts.push(after);
+    } else {
+        parts.push(before);
+        parts.push(after);
+    }
+
+    for (n, segments) in parts.into_iter().filter(|p| !p.is_empty()).enumerate() {
+        sub_paths.insert(
+            s + n,
+            SubPath {
+                segments,
+                closed: false,
+            },
+        );
+    }
+
+    Ok(build(&sub_paths))
+}
+
+/// Prolongs line segment `segment` at one of its ends until it meets segment `to`.
+///
+/// The end must be a free end of an open sub-path. If the line meets `to` several times,
+/// the nearest intersection is used.
+pub fn extend(
+    path: impl IntoIterator<Item = PathEvent>,
+    segment: usize,
+    to: usize,
+    end: SegmentEnd,
+) -> Result<Path, EditError> {
+    let mut sub_paths = sub_paths(path);
+    let (s, i) = locate(&sub_paths, segment)?;
+    let (to_s, to_i) = locate(&sub_paths, to)?;
+    if segment == to {
+        return Err(EditError::InvalidSegment);
+    }
+
+    let line = match sub_paths[s].segments[i] {
+        BezierSegment::Linear(line) => line,
+        _ => {
+            return Err(EditError::UnsupportedSegment);
+        }
+    };
+
+    let sub_path = &sub_paths[s];
+    let is_free = !sub_path.closed
+        && match end {
+            SegmentEnd::Start => i == 0,
+            SegmentEnd::End => i == sub_path.segments.len() - 1,
+        };
+    if !is_free {
+        return Err(EditError::NotAFreeEnd);
+    }
+
+    let direction = line.to_vector();
+    if direction.square_length() == 0.0 {
+        return Err(EditError::NoIntersection);
+    }
+
+    // Position of the intersections along the segment, 0 at the start and 1 at the end.
+    let target = sub_paths[to_s].segments[to_i];
+    let positions = line_intersections(&line, &target)
+        .into_iter()
+        .map(|p| (p - line.from).dot(direction) / direction.square_length());
+
+    let new_position = match end {
+        SegmentEnd::End => positions
+            .filter(|s| *s > 1.0)
+            .fold(None, |min: Option<f32>, s| {
+                Some(min.map_or(s, |m| m.min(s)))
+            }),
+        SegmentEnd::Start => positions
+            .filter(|s| *s < 0.0)
+            .fold(None, |max: Option<f32>, s| {
+                Some(max.map_or(s, |m| m.max(s)))
+            }),
+    }
+    .ok_or(EditError::NoIntersection)?;
+
+    let p = line.from + direction * new_position;
+    let extended = match end {
+        SegmentEnd::Start => LineSegment {
+            from: p,
+            to: line.to,
+        },
+        SegmentEnd::End => LineSegment {
+            from: line.from,
+            to: p,
+        },
+    };
+    sub_paths[s].segments[i] = BezierSegment::Linear(extended);
+
+    Ok(build(&sub_paths))
+}
+
+/// Rounds the corner between line segment `segment` and the next one in its sub-path with
+/// a circular arc of the given radius.
+///
+/// The arc is tangent to both segments. It is approximated with cubic bézier curves.
+pub fn fillet(
+    path: impl IntoIterator<Item = PathEvent>,
+    segment: usize,
+    radius: f32,
+) -> Result<Path, EditError> {
+    let mut sub_paths = sub_paths(path);
+    let corner = Corner::new(&sub_paths, segment)?;
+
+    // Half of the angle between the two segments.
+    let half_angle = corner.d1.angle_to(corner.d2).radians.abs() * 0.5;
+    let distance = radius / half_angle.tan();
+    let (t1, t2) = corner.cut_points(distance)?;
+
+    let bisector = (corner.d1 + corner.d2).normalize();
+    let center = corner.position + bisector * (radius / half_angle.sin());
+    let start = t1 - center;
+    let end = t2 - center;
+    let arc = Arc {
+        center,
+        radii: vector(radius, radius),
+        start_angle: start.angle_from_x_axis(),
+        sweep_angle: start.angle_to(end),
+        x_rotation: Angle::zero(),
+    };
+
+    let mut curves = Vec::new();
+    arc.for_each_cubic_bezier(&mut |curve: &CubicBezierSegment<f32>| {
+        curves.push(BezierSegment::Cubic(*curve));
+    });
+    // Make sure the curves are exactly connected to the segments.
+    if let Some(BezierSegment::Cubic(first)) = curves.first_mut() {
+        first.from = t1;
+    }
+    if let Some(BezierSegment::Cubic(last)) = curves.last_mut() {
+        last.to = t2;
+    }
+
+    corner.apply(&mut sub_paths, t1, t2, curves);
+
+    Ok(build(&sub_paths))
+}
+
+/// Cuts the corner between line segment `segment` and the next one in its sub-path with a
+/// line segment joining the points at `distance` from the corner on both segments.
+pub fn chamfer(
+    path: impl IntoIterator<Item = PathEvent>,
+    segment: usize,
+    distance: f32,
+) -> Result<Path, EditError> {
+    let mut sub_paths = sub_paths(path);
+    let corner = Corner::new(&sub_paths, segment)?;
+    let (t1, t2) = corner.cut_points(distance)?;
+
+    let cut = BezierSegment::Linear(LineSegment { from: t1, to: t2 });
+    corner.apply(&mut sub_paths, t1, t2, vec![cut]);
+
+    Ok(build(&sub_paths))
+}
+
+struct SubPath {
+    segments: Vec<BezierSegment<f32>>,
+    closed: bool,
+}
+
+fn sub_paths(path: impl IntoIterator<Item = PathEvent>) -> Vec<SubPath> {
+    let mut sub_paths = Vec::new();
+    let mut segments = Vec::new();
+    for event in path {
+        if let Some(segment) = event.to_segment() {
+            segments.push(segment);
+        }
+        if let PathEvent::End { close, .. } = event {
+            sub_paths.push(SubPath {
+                segments: std::mem::take(&mut segments),
+                closed: close,
+            });
+        }
+    }
+
+    sub_paths
+}
+
+fn build(sub_paths: &[SubPath]) -> Path {
+    let mut builder = Path::builder();
+    for sub_path in sub_paths {
+        let mut segments = &sub_path.segments[..];
+        let first = match segments.first() {
+            Some(first) => first.from(),
+            None => {
+                continue;
+            }
+        };
+        if sub_path.closed {
+            // The closing edge is added by the builder.
+            if let Some(BezierSegment::Linear(last)) = segments.last() {
+                if last.to == first {
+                    segments = &segments[..segments.len() - 1];
+                }
+            }
+        }
+
+        builder.begin(first);
+        for segment in segments {
+            match segment {
+                BezierSegment::Linear(s) => {
+                    builder.line_to(s.to);
+                }
+                BezierSegment::Quadratic(s) => {
+                    builder.quadratic_bezier_to(s.ctrl, s.to);
+                }
+                BezierSegment::Cubic(s) => {
+                    builder.cubic_bezier_to(s.ctrl1, s.ctrl2, s.to);
+                }
+                BezierSegment::Arc(arc) => {
+                    arc.for_each_cubic_bezier(&mut |s: &CubicBezierSegment<f32>| {
+                        builder.cubic_bezier_to(s.ctrl1, s.ctrl2, s.to);
+                    });
+                }
+            }
+        }
+        builder.end(sub_path.closed);
+    }
+
+    builder.build()
+}
+
+/// Returns the sub-path and the index in the sub-path of a segment.
+fn locate(sub_paths: &[SubPath], mut segment: usize) -> Result<(usize, usize), EditError> {
+    for (s, sub_path) in sub_paths.iter().enumerate() {
+        if segment < sub_path.segments.len() {
+            return Ok((s, segment));
+        }
+        segment -= sub_path.segments.len();
+    }
+
+    Err(EditError::InvalidSegment)
+}
+
+fn to_cubic(segment: &BezierSegment<f32>) -> Option<CubicBezierSegment<f32>> {
+    match segment {
+        BezierSegment::Quadratic(s) => Some(s.to_cubic()),
+        BezierSegment::Cubic(s) => Some(*s),
+        _ => None,
+    }
+}
+
+/// The parameters of the intersections on segment `a`.
+fn intersections_t(a: &BezierSegment<f32>, b: &BezierSegment<f32>) -> Vec<f32> {
+    match (a, b, to_cubic(a), to_cubic(b)) {
+        (BezierSegment::Linear(a), BezierSegment::Linear(b), _, _) => {
+            a.intersection_t(b).map(|(t, _)| t).into_iter().collect()
+        }
+        (BezierSegment::Linear(a), _, _, Some(b)) => b
+            .line_segment_intersections_t(a)
+            .iter()
+            .map(|(_, t)| *t)
+            .collect(),
+        (_, BezierSegment::Linear(b), Some(a), _) => a
+            .line_segment_intersections_t(b)
+            .iter()
+            .map(|(t, _)| *t)
+            .collect(),
+        (_, _, Some(a), Some(b)) => a
+            .cubic_intersections_t(&b)
+            .iter()
+            .map(|(t, _)| *t)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// The intersections of the infinite line supporting `line` with a segment.
+fn line_intersections(line: &LineSegment<f32>, segment: &BezierSegment<f32>) -> Vec<Point> {
+    let line = line.to_line();
+    match segment {
+        BezierSegment::Linear(s) => s
+            .line_intersection_t(&line)
+            .map(|t| s.sample(t))
+            .into_iter()
+            .collect(),
+        BezierSegment::Quadratic(s) => s
+            .line_intersections_t(&line)
+            .iter()
+            .map(|t| s.sample(*t))
+            .collect(),
+        BezierSegment::Cubic(s) => s
+            .line_intersections_t(&line)
+            .iter()
+            .map(|t| s.sample(*t))
+            .collect(),
+        BezierSegment::Arc(_) => Vec::new(),
+    }
+}
+
+/// The corner between a line segment and the next one in its sub-path.
+struct Corner {
+    sub_path: usize,
+    first: usize,
+    second: usize,
+    position: Point,
+    /// Direction from the corner towards the start of the first segment.
+    d1: Vector,
+    /// Direction from the corner towards the end of the second segment.
+    d2: Vector,
+    length1: f32,
+    length2: f32,
+}
+
+impl Corner {
+    fn new(sub_paths: &[SubPath], segment: usize) -> Result<Self, EditError> {
+        let (s, first) = locate(sub_paths, segment)?;
+        let sub_path = &sub_paths[s];
+        let second = if first + 1 < sub_path.segments.len() {
+            first + 1
+        } else if sub_path.closed && sub_path.segments.len() > 2 {
+            0
+        } else {
+            return Err(EditError::NoCorner);
+        };
+
+        let (a, b) = match (sub_path.segments[first], sub_path.segments[second]) {
+            (BezierSegment::Linear(a), BezierSegment::Linear(b)) => (a, b),
+            _ => {
+                return Err(EditError::UnsupportedSegment);
+            }
+        };
+
+        let v1 = a.from - a.to;
+        let v2 = b.to - b.from;
+        let (length1, length2) = (v1.length(), v2.length());
+        if length1 == 0.0 || length2 == 0.0 {
+            return Err(EditError::DegenerateCorner);
+        }
+        let (d1, d2) = (v1 / length1, v2 / length2);
+        if d1.cross(d2).abs() < 1e-5 {
+            return Err(EditError::DegenerateCorner);
+        }
+
+        Ok(Corner {
+            sub_path: s,
+            first,
+            second,
+            position: a.to,
+            d1,
+            d2,
+            length1,
+            length2,
+        })
+    }
+
+    /// The points at a given distance from the corner on both segments.
+    fn cut_points(&self, distance: f32) -> Result<(Point, Point), EditError> {
+        if distance.is_nan() || distance < 0.0 || distance > self.length1 || distance > self.length2
+        {
+            return Err(EditError::TooLarge);
+        }
+
+        Ok((
+            self.position + self.d1 * distance,
+            self.position + self.d2 * distance,
+        ))
+    }
+
+    /// Shortens the two segments to end at `p1` and start at `p2`, and inserts
+    /// `segments` between them.
+    fn apply(
+        &self,
+        sub_paths: &mut [SubPath],
+        p1: Point,
+        p2: Point,
+        segments: Vec<BezierSegment<f32>>,
+    ) {
+        let sub_path = &mut sub_paths[self.sub_path];
+        if let BezierSegment::Linear(line) = &mut sub_path.segments[self.first] {
+            line.to = p1;
+        }
+        if let BezierSegment::Linear(line) = &mut sub_path.segments[self.second] {
+            line.from = p2;
+        }
+
+        let at = self.first + 1;
+        sub_path.segments.splice(at..at, segments);
+    }
+}
+
+#[test]
+fn trim_and_extend() {
+    use crate::math::point;
+
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.end(false);
+    builder.begin(point(5.0, -5.0));
+    builder.quadratic_bezier_to(point(5.0, 0.0), point(5.0, 5.0));
+    builder.line_to(point(8.0, 5.0));
+    builder.end(false);
+    let path = builder.build();
+
+    // Removing the start of the curve keeps the rest of its sub-path.
+    let trimmed = trim(&path, 1, 0, SegmentEnd::Start).unwrap();
+    let events: Vec<PathEvent> = trimmed.iter().collect();
+    assert_eq!(events.len(), 7);
+    assert_eq!(
+        events[3],
+        PathEvent::Begin {
+            at: point(5.0, 0.0)
+        }
+    );
+
+    // Removing the end of the curve splits its sub-path.
+    let trimmed = trim(&path, 1, 0, SegmentEnd::End).unwrap();
+    let ends = trimmed
+        .iter()
+        .filter(|e| matches!(e, PathEvent::End { .. }))
+        .count();
+    assert_eq!(ends, 3);
+
+    assert_eq!(
+        trim(&path, 2, 0, SegmentEnd::End).err(),
+        Some(EditError::NoIntersection)
+    );
+    assert_eq!(
+        trim(&path, 0, 0, SegmentEnd::End).err(),
+        Some(EditError::InvalidSegment)
+    );
+    assert_eq!(
+        trim(&path, 5, 0, SegmentEnd::End).err(),
+        Some(EditError::InvalidSegment)
+    );
+
+    // Extend the first segment to the curve.
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 1.0));
+    builder.line_to(point(2.0, 1.0));
+    builder.end(false);
+    builder.begin(point(5.0, -5.0));
+    builder.quadratic_bezier_to(point(7.0, 0.0), point(5.0, 5.0));
+    builder.end(false);
+    let path = builder.build();
+
+    let extended = extend(&path, 0, 1, SegmentEnd::End).unwrap();
+    let first = extended.iter().nth(1).unwrap();
+    assert!((first.to() - point(5.96, 1.0)).length() < 1e-4);
+
+    assert_eq!(
+        extend(&path, 0, 1, SegmentEnd::Start).err(),
+        Some(EditError::NoIntersection)
+    );
+    assert_eq!(
+        extend(&path, 1, 0, SegmentEnd::End).err(),
+        Some(EditError::UnsupportedSegment)
+    );
+}
+
+#[test]
+fn fillet_and_chamfer() {
+    use crate::math::point;
+
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    let square = builder.build();
+
+    // Round the corner at (0, 0), between the closing edge and the first segment.
+    let rounded = fillet(&square, 3, 2.0).unwrap();
+    let events: Vec<PathEvent> = rounded.iter().collect();
+    assert_eq!(
+        events[0],
+        PathEvent::Begin {
+            at: point(2.0, 0.0)
+        }
+    );
+    let arc_points: Vec<Point> = events
+        .iter()
+        .filter(|e| matches!(e, PathEvent::Cubic { .. }))
+        .map(|e| e.to())
+        .collect();
+    assert_eq!(arc_points.last(), Some(&point(2.0, 0.0)));
+    for event in &events {
+        if let PathEvent::Cubic { ctrl1, ctrl2, .. } = event {
+            // The arc stays in the corner.
+            assert!(ctrl1.x <= 2.0 && ctrl1.y <= 2.0);
+            assert!(ctrl2.x <= 2.0 && ctrl2.y <= 2.0);
+        }
+    }
+    let start = events
+        .iter()
+        .find(|e| matches!(e, PathEvent::Cubic { .. }))
+        .unwrap()
+        .from();
+    assert!((start - point(0.0, 2.0)).length() < 1e-5);
+    // The middle of the arc is at the radius from the center.
+    let center = point(2.0, 2.0);
+    if let Some(PathEvent::Cubic {
+        from,
+        ctrl1,
+        ctrl2,
+        to,
+    }) = events.iter().find(|e| matches!(e, PathEvent::Cubic { .. }))
+    {
+        let curve = CubicBezierSegment {
+            from: *from,
+            ctrl1: *ctrl1,
+            ctrl2: *ctrl2,
+            to: *to,
+        };
+        assert!(((curve.sample(0.5) - center).length() - 2.0).abs() < 0.01);
+    }
+
+    let cut = chamfer(&square, 0, 3.0).unwrap();
+    let points: Vec<Point> = cut.iter().map(|e| e.to()).collect();
+    assert!(points.contains(&point(7.0, 0.0)));
+    assert!(points.contains(&point(10.0, 3.0)));
+
+    assert_eq!(fillet(&square, 0, 20.0).err(), Some(EditError::TooLarge));
+
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(20.0, 0.0));
+    builder.end(false);
+    let line = builder.build();
+    assert_eq!(
+        chamfer(&line, 0, 1.0).err(),
+        Some(EditError::DegenerateCorner)
+    );
+    assert_eq!(chamfer(&line, 1, 1.0).err(), Some(EditError::NoCorner));
+}
@@ -0,0 +1,332 @@
+//! Rewrite paths into a normal form.
+//!
+//! Many different sequences of events describe the same shape: sub-paths can be listed in
+//! any order, closed sub-paths can start at any of their endpoints and go in either
+//! direction, and so on. Hashing, caching or diffing paths is only reliable if equivalent
+//! paths are first rewritten into the same normal form, which is what
+//! [`canonicalize`](fn.canonicalize.html) does.
+//!
+//! # Example
+//!
+//! ```
+//! use lyon_algorithms::canonicalize::canonicalize;
+//! use lyon_algorithms::math::point;
+//! use lyon_algorithms::path::Path;
+//!
+//! let mut builder = Path::builder();
+//! builder.begin(point(1.0, 0.0));
+//! builder.line_to(point(1.0, 1.0));
+//! builder.line_to(point(0.0, 0.0));
+//! builder.close();
+//! let a = builder.build();
+//!
+//! // The same triangle, starting at another point and going in the other direction.
+//! let mut builder = Path::builder();
+//! builder.begin(point(1.0, 1.0));
+//! builder.line_to(point(1.0, 0.0));
+//! builder.line_to(point(0.0, 0.0));
+//! builder.line_to(point(1.0, 1.0));
+//! builder.close();
+//! let b = builder.build();
+//!
+//! assert!(canonicalize(a.iter()).iter().eq(canonicalize(b.iter()).iter()));
+//! ```
+
+use crate::geom::{CubicBezierSegment, LineSegment, QuadraticBezierSegment};
+use crate::math::Point;
+use crate::path::{Path, PathEvent};
+
+use std::cmp::Ordering;
+
+/// Rewrites a path into a normal form.
+///
+/// The result describes the same shape with the following properties:
+///
+/// - Degenerate line segments (with identical endpoints) and sub-paths without any
+///   segment are removed.
+/// - Closed sub-paths don't end with an explicit line segment back to their first
+///   endpoint, which is redundant with the closing segment.
+/// - Closed sub-paths have a positive signed area and start at their smallest endpoint
+///   (comparing x, then y). Open sub-paths keep their direction.
+/// - Sub-paths are sorted by first endpoint, and exact duplicates are removed.
+///
+/// Normalizing the winding of each sub-path and removing duplicates don't preserve the
+/// result of filling with the non-zero or even-odd rules in general (for example holes
+/// get the same winding as the outer contours). Canonical paths are meant to be compared
+/// and hashed, or used where the fill rule does not matter such as stroking.
+///
+/// Custom attributes are not preserved.
+pub fn canonicalize(path: impl IntoIterator<Item = PathEvent>) -> Path {
+    let mut sub_paths: Vec<SubPath> = Vec::new();
+    let mut current: Option<SubPath> = None;
+    for event in path {
+        match event {
+            PathEvent::Begin { at } => {
+                current = Some(SubPath {
+                    first: nz(at),
+                    segments: Vec::new(),
+                    closed: false,
+                });
+            }
+            PathEvent::Line { from, to } => {
+                if let Some(sp) = &mut current {
+                    if from != to {
+                        sp.segments.push(Seg::Line(nz(to)));
+                    }
+                }
+            }
+            PathEvent::Quadratic { ctrl, to, .. } => {
+                if let Some(sp) = &mut current {
+                    sp.segments.push(Seg::Quadratic(nz(ctrl), nz(to)));
+                }
+            }
+            PathEvent::Cubic {
+                ctrl1, ctrl2, to, ..
+            } => {
+                if let Some(sp) = &mut current {
+                    sp.segments.push(Seg::Cubic(nz(ctrl1), nz(ctrl2), nz(to)));
+                }
+            }
+            PathEvent::End { close, .. } => {
+                if let Some(mut sp) = current.take() {
+                    sp.closed = close;
+                    if close {
+                        sp.normalize_closed();
+                    }
+                    if !sp.segments.is_empty() {
+                        sub_paths.push(sp);
+                    }
+                }
+            }
+        }
+    }
+
+    sub_paths.sort_by(|a, b| cmp_points(a.first, b.first).then_with(|| a.key().cmp(&b.key())));
+    sub_paths.dedup_by(|a, b| a.key() == b.key());
+
+    let mut builder = Path::builder();
+    for sp in &sub_paths {
+        builder.begin(sp.first);
+        let num_segments =
+            if sp.closed && matches!(sp.segments.last(), Some(Seg::Line(to)) if *to == sp.first) {
+                sp.segments.len() - 1
+            } else {
+                sp.segments.len()
+            };
+        for segment in &sp.segments[..num_segments] {
+            match *segment {
+                Seg::Line(to) => {
+                    builder.line_to(to);
+                }
+                Seg::Quadratic(ctrl, to) => {
+                    builder.quadratic_bezier_to(ctrl, to);
+                }
+                Seg::Cubic(ctrl1, ctrl2, to) => {
+                    builder.cubic_bezier_to(ctrl1, ctrl2, to);
+                }
+            }
+        }
+        builder.end(sp.closed);
+    }
+
+    builder.build()
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Seg {
+    Line(Point),
+    Quadratic(Point, Point),
+    Cubic(Point, Point, Point),
+}
+
+impl Seg {
+    fn to(&self) -> Point {
+        match *self {
+            Seg::Line(to) | Seg::Quadratic(_, to) | Seg::Cubic(_, _, to) => to,
+        }
+    }
+
+    fn signed_area(&self, from: Point) -> f32 {
+        match *self {
+            Seg::Line(to) => LineSegment { from, to }.signed_area(),
+            Seg::Quadratic(ctrl, to) => QuadraticBezierSegment { from, ctrl, to }.signed_area(),
+            Seg::Cubic(ctrl1, ctrl2, to) => CubicBezierSegment {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            }
+            .signed_area(),
+        }
+    }
+
+    /// The same segment traversed in the other direction, ending at `from`.
+    fn reversed(&self, from: Point) -> Self {
+        match *self {
+            Seg::Line(_) => Seg::Line(from),
+            Seg::Quadratic(ctrl, _) => Seg::Quadratic(ctrl, from),
+            Seg::Cubic(ctrl1, ctrl2, _) => Seg::Cubic(ctrl2, ctrl1, from),
+        }
+    }
+}
+
+struct SubPath {
+    first: Point,
+    segments: Vec<Seg>,
+    closed: bool,
+}
+
+impl SubPath {
+    fn normalize_closed(&mut self) {
+        // Make the closing segment explicit so that the sub-path can be rotated.
+        let last = self.segments.last().map(Seg::to).unwrap_or(self.first);
+        if last != self.first {
+            self.segments.push(Seg::Line(self.first));
+        }
+        if self.segments.is_empty() {
+            return;
+        }
+
+        let mut from = self.first;
+        let mut area = 0.0;
+        for segment in &self.segments {
+            area += segment.signed_area(from);
+            from = segment.to();
+        }
+
+        if area < 0.0 {
+            let mut reversed = Vec::with_capacity(self.segments.len());
+            for i in (0..self.segments.len()).rev() {
+                let from = if i == 0 {
+                    self.first
+                } else {
+                    self.segments[i - 1].to()
+                };
+                reversed.push(self.segments[i].reversed(from));
+            }
+            self.segments = reversed;
+        }
+
+        // Start at the smallest endpoint. When several endpoints are at the same position,
+        // pick the rotation with the smallest sequence of endpoints.
+        let n = self.segments.len();
+        // Endpoint `i` of the rotation starting after segment `rotation - 1`. The last
+        // segment ends at the first endpoint.
+        let endpoint = |rotation: usize, i: usize| self.segments[(rotation + i + n - 1) % n].to();
+        let mut best = 0;
+        for rotation in 1..n {
+            let ordering = (0..n)
+                .map(|i| cmp_points(endpoint(rotation, i), endpoint(best, i)))
+                .find(|ordering| *ordering != Ordering::Equal)
+                .unwrap_or(Ordering::Equal);
+            if ordering == Ordering::Less {
+                best = rotation;
+            }
+        }
+
+        if best != 0 {
+            self.first = self.segments[best - 1].to();
+            self.segments.rotate_left(best);
+        }
+    }
+
+    fn key(&self) -> Vec<u32> {
+        let mut key = Vec::with_capacity(self.segments.len() * 4 + 3);
+        key.push(self.closed as u32);
+        key.push(self.first.x.to_bits());
+        key.push(self.first.y.to_bits());
+        for segment in &self.segments {
+            let points: &[Point] = match segment {
+                Seg::Line(to) => {
+                    key.push(1);
+                    std::slice::from_ref(to)
+                }
+                Seg::Quadratic(ctrl, to) => {
+                    key.push(2);
+                    key.extend_from_slice(&[ctrl.x.to_bits(), ctrl.y.to_bits()]);
+                    std::slice::from_ref(to)
+                }
+                Seg::Cubic(ctrl1, ctrl2, to) => {
+                    key.push(3);
+                    key.extend_from_slice(&[ctrl1.x.to_bits(), ctrl1.y.to_bits()]);
+                    key.extend_from_slice(&[ctrl2.x.to_bits(), ctrl2.y.to_bits()]);
+                    std::slice::from_ref(to)
+                }
+            };
+            for p in points {
+                key.extend_from_slice(&[p.x.to_bits(), p.y.to_bits()]);
+            }
+        }
+
+        key
+    }
+}
+
+fn cmp_points(a: Point, b: Point) -> Ordering {
+    a.x.total_cmp(&b.x).then_with(|| a.y.total_cmp(&b.y))
+}
+
+// Adding zero turns negative zeros into positive ones, so that they are ordered and
+// hashed like positive zeros.
+fn nz(p: Point) -> Point {
+    Point::new(p.x + 0.0, p.y + 0.0)
+}
+
+#[test]
+fn canonical_form() {
+    use crate::math::point;
+
+    let mut builder = Path::builder();
+    // An open sub-path with a degenerate segment.
+    builder.begin(point(5.0, 5.0));
+    builder.line_to(point(6.0, 5.0));
+    builder.line_to(point(6.0, 5.0));
+    builder.quadratic_bezier_to(point(7.0, 6.0), point(6.0, 7.0));
+    builder.end(false);
+    // An empty sub-path.
+    builder.begin(point(-1.0, -1.0));
+    builder.end(true);
+    // A square with an explicit closing segment, starting at its top-right corner.
+    builder.begin(point(1.0, 1.0));
+    builder.line_to(point(0.0, 1.0));
+    builder.line_to(point(-0.0, 0.0));
+    builder.cubic_bezier_to(point(0.3, -0.2), point(0.6, 0.2), point(1.0, 0.0));
+    builder.line_to(point(1.0, 1.0));
+    builder.close();
+    let path = builder.build();
+
+    let canonical = canonicalize(path.iter());
+
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.cubic_bezier_to(point(0.3, -0.2), point(0.6, 0.2), point(1.0, 0.0));
+    builder.line_to(point(1.0, 1.0));
+    builder.line_to(point(0.0, 1.0));
+    builder.close();
+    builder.begin(point(5.0, 5.0));
+    builder.line_to(point(6.0, 5.0));
+    builder.quadratic_bezier_to(point(7.0, 6.0), point(6.0, 7.0));
+    builder.end(false);
+    let expected = builder.build();
+
+    assert_eq!(
+        canonical.iter().collect::<Vec<_>>(),
+        expected.iter().collect::<Vec<_>>()
+    );
+    assert!(crate::area::signed_area(canonical.iter()) > 0.0);
+
+    // Canonicalization is idempotent.
+    let again = canonicalize(canonical.iter());
+    assert!(again.iter().eq(canonical.iter()));
+
+    // Duplicate sub-paths are removed, whatever their direction and starting point.
+    let mut builder = Path::builder();
+    builder.begin(point(1.0, 0.0));
+    builder.cubic_bezier_to(point(0.6, 0.2), point(0.3, -0.2), point(0.0, 0.0));
+    builder.line_to(point(0.0, 1.0));
+    builder.line_to(point(1.0, 1.0));
+    builder.close();
+    builder.extend_from_paths(&[path.as_slice()]);
+    let doubled = builder.build();
+    assert!(canonicalize(doubled.iter()).iter().eq(canonical.iter()));
+}
@@ -0,0 +1,449 @@
+//! Classify the position of a path relative to another path.
+//!
+//! This answers containment questions such as "is this shape fully inside that one" without
+//! computing boolean operations: the bounding boxes are compared first, then the flattened
+//! edges are tested for intersections and, if they don't cross, the winding number of a few
+//! points of each path is evaluated with respect to the other.
+//!
+//! The paths are considered filled, so each sub-path is treated as closed and the area covered
+//! by a path depends on the fill rule.
+//!
+//! # Example
+//!
+//! ```
+//! use lyon_algorithms::classify::{classify, PathRelation};
+//! use lyon_algorithms::math::{point, Box2D};
+//! use lyon_algorithms::path::{FillRule, Path, Winding};
+//!
+//! let mut builder = Path::builder();
+//! builder.add_rectangle(&Box2D::new(point(0.0, 0.0), point(10.0, 10.0)), Winding::Positive);
+//! let frame = builder.build();
+//!
+//! let mut builder = Path::builder();
+//! builder.add_circle(point(5.0, 5.0), 2.0, Winding::Positive);
+//! let button = builder.build();
+//!
+//! let relation = classify(button.iter(), frame.iter(), FillRule::NonZero, 0.01);
+//! assert_eq!(relation, PathRelation::AInB);
+//! ```
+
+use crate::geom::LineSegment;
+use crate::math::{Box2D, Point};
+use crate::path::iterator::PathIterator;
+use crate::path::{FillRule, PathEvent};
+
+use std::ops::Range;
+
+/// The position of a path `a` relative to a path `b`, see [`classify`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PathRelation {
+    /// The paths don't touch.
+    Disjoint,
+    /// The area of `a` is inside the area of `b`.
+    ///
+    /// The boundary of `a` may touch the boundary of `b`. This is also the relation
+    /// of two paths that cover the same area.
+    AInB,
+    /// The area of `b` is inside the area of `a`.
+    ///
+    /// The boundary of `b` may touch the boundary of `a`.
+    BInA,
+    /// The areas of `a` and `b` partially overlap.
+    Overlapping,
+    /// The boundaries of `a` and `b` touch but their areas don't overlap.
+    Touching,
+}
+
+/// Computes the position of the area covered by `a` relative to the area covered by `b`.
+///
+/// Curves are flattened with the given tolerance, which is also the distance under which
+/// the boundaries of the paths are considered to be touching.
+pub fn classify<A, B>(a: A, b: B, fill_rule: FillRule, tolerance: f32) -> PathRelation
+where
+    A: IntoIterator<Item = PathEvent>,
+    B: IntoIterator<Item = PathEvent>,
+{
+    debug_assert!(tolerance > 0.0);
+
+    let a = Outline::new(a, tolerance);
+    let b = Outline::new(b, tolerance);
+
+    if a.edges.is_empty() || b.edges.is_empty() {
+        return PathRelation::Disjoint;
+    }
+
+    if !a
+        .bounding_box
+        .inflate(tolerance, tolerance)
+        .intersects(&b.bounding_box)
+    {
+        return PathRelation::Disjoint;
+    }
+
+    let touching = match find_contact(&a.edges, &b.edges, tolerance) {
+        Contact::Cross => return PathRelation::Overlapping,
+        Contact::Touch => true,
+        Contact::None => false,
+    };
+
+    // Without any contact, the boundary of each sub-path is either entirely inside or entirely
+    // outside of the other path, so a single point per sub-path is enough. Otherwise the
+    // boundaries can go from one side to the other at the contact points, so we test a point
+    // per edge.
+    let (a_in, a_out) = count_inside(a.samples(touching), &b, fill_rule, tolerance);
+    let (b_in, b_out) = count_inside(b.samples(touching), &a, fill_rule, tolerance);
+
+    if (a_in > 0 && a_out > 0) || (b_in > 0 && b_out > 0) || (a_in > 0 && b_in > 0) {
+        return PathRelation::Overlapping;
+    }
+
+    // Points on the other boundary are skipped so all of the points of a path may be ignored,
+    // for example when both paths cover the same area.
+    if a_out == 0 && b_in == 0 {
+        return PathRelation::AInB;
+    }
+
+    if b_out == 0 && a_in == 0 {
+        return PathRelation::BInA;
+    }
+
+    if touching {
+        PathRelation::Touching
+    } else {
+        PathRelation::Disjoint
+    }
+}
+
+// The flattened and closed sub-paths of a path.
+struct Outline {
+    edges: Vec<LineSegment<f32>>,
+    sub_paths: Vec<Range<usize>>,
+    bounding_box: Box2D,
+}
+
+impl Outline {
+    fn new<Iter>(path: Iter, tolerance: f32) -> Self
+    where
+        Iter: IntoIterator<Item = PathEvent>,
+    {
+        let mut edges = Vec::new();
+        let mut sub_paths = Vec::new();
+        let mut start = 0;
+        for event in path.into_iter().flattened(tolerance) {
+            match event {
+                PathEvent::Begin { .. } => {
+                    start = edges.len();
+                }
+                PathEvent::Line { from, to } => {
+                    if from != to {
+                        edges.push(LineSegment { from, to });
+                    }
+                }
+                PathEvent::End { last, first, .. } => {
+                    if last != first {
+                        edges.push(LineSegment {
+                            from: last,
+                            to: first,
+                        });
+                    }
+                    if edges.len() > start {
+                        sub_paths.push(start..edges.len());
+                    }
+                }
+                PathEvent::Quadratic { .. } | PathEvent::Cubic { .. } => {
+                    debug_assert!(false, "Unexpected curve in a flattened path");
+                }
+            }
+        }
+
+        let bounding_box = Box2D::from_points(edges.iter().map(|edge| edge.from));
+
+        Outline {
+            edges,
+            sub_paths,
+            bounding_box,
+        }
+    }
+
+    // A point per sub-path or a point per edge.
+    fn samples(&self, per_edge: bool) -> Vec<Point> {
+        if per_edge {
+            self.edges.iter().map(|edge| edge.sample(0.5)).collect()
+        } else {
+            self.sub_paths
+                .iter()
+                .map(|range| self.edges[range.start].sample(0.5))
+                .collect()
+        }
+    }
+
+    fn winding_number(&self, point: Point) -> i32 {
+        let mut winding = 0;
+        for edge in &self.edges {
+            if (edge.from.y <= point.y) == (edge.to.y <= point.y) {
+                continue;
+            }
+            let x = edge.solve_x_for_y(point.y);
+            if x < point.x {
+                winding += if edge.to.y > edge.from.y { 1 } else { -1 };
+            }
+        }
+
+        winding
+    }
+
+    fn is_on_boundary(&self, point: Point, tolerance: f32) -> bool {
+        self.edges
+            .iter()
+            .any(|edge| edge.square_distance_to_point(point) <= tolerance * tolerance)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Contact {
+    None,
+    Touch,
+    Cross,
+}
+
+// Finds the strongest contact between the edges of two paths, with a sweep along the x axis
+// over the bounding boxes of the edges.
+fn find_contact(a: &[LineSegment<f32>], b: &[LineSegment<f32>], tolerance: f32) -> Contact {
+    let min_x = |edge: &LineSegment<f32>| edge.from.x.min(edge.to.x);
+    let max_x = |edge: &LineSegment<f32>| edge.from.x.max(edge.to.x);
+    let min_y = |edge: &LineSegment<f32>| edge.from.y.min(edge.to.y);
+    let max_y = |edge: &LineSegment<f32>| edge.from.y.max(edge.to.y);
+
+    // Edges of `a` come first, followed by the edges of `b`.
+    let edge = |idx: usize| {
+        if idx < a.len() {
+            &a[idx]
+        } else {
+            &b[idx - a.len()]
+        }
+    };
+
+    let mut order: Vec<usize> = (0..a.len() + b.len()).collect();
+    order.sort_by(|i, j| min_x(edge(*i)).partial_cmp(&min_x(edge(*j))).unwrap());
+
+    let mut contact = Contact::None;
+    for (i, &e1) in order.iter().enumerate() {
+        let s1 = edge(e1);
+        let max = max_x(s1) + tolerance;
+        for &e2 in &order[i + 1..] {
+            let s2 = edge(e2);
+            if min_x(s2) > max {
+                break;
+            }
+            if (e1 < a.len()) == (e2 < a.len())
+                || min_y(s2) > max_y(s1) + tolerance
+                || max_y(s2) < min_y(s1) - tolerance
+            {
+                continue;
+            }
+
+            match segment_contact(s1, s2, tolerance) {
+                Contact::Cross => {
+                    return Contact::Cross;
+                }
+                Contact::Touch => {
+                    contact = Contact::Touch;
+                }
+                Contact::None => {}
+            }
+        }
+    }
+
+    contact
+}
+
+fn segment_contact(s1: &LineSegment<f32>, s2: &LineSegment<f32>, tolerance: f32) -> Contact {
+    let sq_tolerance = tolerance * tolerance;
+    if s1.square_distance_to_point(s2.from) <= sq_tolerance
+        || s1.square_distance_to_point(s2.to) <= sq_tolerance
+        || s2.square_distance_to_point(s1.from) <= sq_tolerance
+        || s2.square_distance_to_point(s1.to) <= sq_tolerance
+    {
+        return Contact::Touch;
+    }
+
+    let s1 = LineSegment {
+        from: s1.from.to_f64(),
+        to: s1.to.to_f64(),
+    };
+    let s2 = LineSegment {
+        from: s2.from.to_f64(),
+        to: s2.to.to_f64(),
+    };
+    if s1.intersects(&s2) {
+        return Contact::Cross;
+    }
+
+    Contact::None
+}
+
+// Returns the number of points that are inside and outside of the path, ignoring the points
+// that are on its boundary.
+fn count_inside(
+    points: Vec<Point>,
+    path: &Outline,
+    fill_rule: FillRule,
+    tolerance: f32,
+) -> (u32, u32) {
+    let mut inside = 0;
+    let mut outside = 0;
+    for point in points {
+        if path.is_on_boundary(point, tolerance) {
+            continue;
+        }
+        let winding = path.winding_number(point);
+        let is_in = match fill_rule {
+            FillRule::EvenOdd => winding % 2 != 0,
+            FillRule::NonZero => winding != 0,
+        };
+        if is_in {
+            inside += 1;
+        } else {
+            outside += 1;
+        }
+    }
+
+    (inside, outside)
+}
+
+#[cfg(test)]
+fn polygon(points: &[(f32, f32)]) -> crate::path::Path {
+    use crate::math::point;
+
+    let mut builder = crate::path::Path::builder();
+    builder.begin(point(points[0].0, points[0].1));
+    for p in &points[1..] {
+        builder.line_to(point(p.0, p.1));
+    }
+    builder.end(true);
+
+    builder.build()
+}
+
+#[cfg(test)]
+fn square(x: f32, y: f32, size: f32) -> crate::path::Path {
+    polygon(&[(x, y), (x + size, y), (x + size, y + size), (x, y + size)])
+}
+
+#[test]
+fn classify_squares() {
+    let check = |a: &crate::path::Path, b: &crate::path::Path, expected: PathRelation| {
+        assert_eq!(
+            classify(a.iter(), b.iter(), FillRule::NonZero, 0.001),
+            expected
+        );
+    };
+
+    let big = square(0.0, 0.0, 10.0);
+
+    check(&square(20.0, 0.0, 5.0), &big, PathRelation::Disjoint);
+    check(&square(12.0, 2.0, 5.0), &big, PathRelation::Disjoint);
+    check(&square(2.0, 2.0, 5.0), &big, PathRelation::AInB);
+    check(&big, &square(2.0, 2.0, 5.0), PathRelation::BInA);
+    check(&square(5.0, 5.0, 10.0), &big, PathRelation::Overlapping);
+    // Sharing an edge, a corner, and part of an edge.
+    check(&square(10.0, 0.0, 10.0), &big, PathRelation::Touching);
+    check(&square(10.0, 10.0, 5.0), &big, PathRelation::Touching);
+    check(&square(10.0, 2.0, 5.0), &big, PathRelation::Touching);
+    // Inside, touching the boundary.
+    check(&square(0.0, 0.0, 5.0), &big, PathRelation::AInB);
+    check(&big, &square(5.0, 2.0, 5.0), PathRelation::BInA);
+    // Same area.
+    check(&big, &big, PathRelation::AInB);
+    // Crossing through the corners of the other path.
+    check(
+        &polygon(&[(-5.0, -5.0), (15.0, -5.0), (15.0, 15.0)]),
+        &big,
+        PathRelation::Overlapping,
+    );
+    check(
+        &polygon(&[(-5.0, 5.0), (5.0, -5.0), (15.0, 5.0), (5.0, 15.0)]),
+        &big,
+        PathRelation::BInA,
+    );
+}
+
+#[test]
+fn classify_holes() {
+    // A frame with a square hole from 3 to 7.
+    let mut builder = crate::path::Path::builder();
+    builder.extend_from_paths(&[
+        square(0.0, 0.0, 10.0).as_slice(),
+        polygon(&[(3.0, 3.0), (3.0, 7.0), (7.0, 7.0), (7.0, 3.0)]).as_slice(),
+    ]);
+    let frame = builder.build();
+
+    let check = |a: &crate::path::Path, expected: PathRelation| {
+        assert_eq!(
+            classify(a.iter(), frame.iter(), FillRule::NonZero, 0.001),
+            expected
+        );
+        assert_eq!(
+            classify(a.iter(), frame.iter(), FillRule::EvenOdd, 0.001),
+            expected
+        );
+    };
+
+    check(&square(4.0, 4.0, 2.0), PathRelation::Disjoint);
+    check(&square(3.0, 4.0, 2.0), PathRelation::Touching);
+    check(&square(1.0, 1.0, 1.0), PathRelation::AInB);
+    // Covers the hole.
+    check(&square(2.0, 2.0, 6.0), PathRelation::Overlapping);
+    check(&square(-1.0, -1.0, 12.0), PathRelation::BInA);
+}
+
+#[test]
+fn classify_curves() {
+    use crate::math::point;
+    use crate::path::{Path, Winding};
+
+    let circle = |x: f32, y: f32, r: f32| {
+        let mut builder = Path::builder();
+        builder.add_circle(point(x, y), r, Winding::Positive);
+        builder.build()
+    };
+
+    let a = circle(0.0, 0.0, 1.0);
+
+    assert_eq!(
+        classify(
+            a.iter(),
+            circle(1.5, 0.0, 1.0).iter(),
+            FillRule::NonZero,
+            0.01
+        ),
+        PathRelation::Overlapping
+    );
+    assert_eq!(
+        classify(
+            a.iter(),
+            circle(3.0, 0.0, 1.0).iter(),
+            FillRule::NonZero,
+            0.01
+        ),
+        PathRelation::Disjoint
+    );
+    assert_eq!(
+        classify(
+            a.iter(),
+            circle(0.5, 0.0, 3.0).iter(),
+            FillRule::NonZero,
+            0.01
+        ),
+        PathRelation::AInB
+    );
+    assert_eq!(
+        classify(
+            a.iter(),
+            square(-1.0, 1.0, 2.0).iter(),
+            FillRule::NonZero,
+            0.01
+        ),
+        PathRelation::Touching
+    );
+}
@@ -0,0 +1,800 @@
+//! Extract iso-contours from a grid of scalar values (marching squares).
+//!
+//! This is useful to produce the outlines of heatmaps, metaballs or any other
+//! scalar field as paths that can then be filled, stroked or otherwise processed.
+//!
+//! # Example
+//!
+//! ```
+//! use lyon_algorithms::contour::{ContourOptions, ScalarGrid, contour_path};
+//!
+//! // A 4x4 grid with a bump in the middle.
+//! let values = [
+//!     0.0, 0.0, 0.0, 0.0,
+//!     0.0, 1.0, 1.0, 0.0,
+//!     0.0, 1.0, 1.0, 0.0,
+//!     0.0, 0.0, 0.0, 0.0,
+//! ];
+//! let grid = ScalarGrid::new(&values, 4, 4);
+//!
+//! let outline = contour_path(&grid, 0.5, &ContourOptions::DEFAULT);
+//! ```
+//!
+//! # Orientation
+//!
+//! All contours are oriented consistently: the area where the field is above the
+//! iso-value is always on the same side of the contour. As a result, nested contours
+//! (for example a hole in a blob) have opposite windings and the output can be filled
+//! with either fill rule.
+//!
+//! Contours that reach the border of the grid are not closed. Pad the grid with values
+//! below the iso-value to obtain closed contours everywhere.
+//!
+//! # Isobands
+//!
+//! `isoband_path` extracts the regions where the field lies between two iso-values
+//! instead of the lines where it crosses a single one. The outlines of these regions are
+//! always closed (along the border of the grid if need be) and holes are oriented the
+//! opposite way, so they can be passed directly to the fill tessellator. Filling the bands
+//! produced by `isoband_paths` with a different color each gives weather-map style
+//! renderings.
+//!
+//! ```
+//! use lyon_algorithms::contour::{ContourOptions, ScalarGrid, isoband_paths};
+//!
+//! let values = [
+//!     0.0, 0.0, 0.0, 0.0,
+//!     0.0, 1.0, 1.0, 0.0,
+//!     0.0, 1.0, 1.0, 0.0,
+//!     0.0, 0.0, 0.0, 0.0,
+//! ];
+//! let grid = ScalarGrid::new(&values, 4, 4);
+//!
+//! // Bands for [0, 0.25), [0.25, 0.5), [0.5, 0.75) and [0.75, 1.5).
+//! let levels = [0.0, 0.25, 0.5, 0.75, 1.5];
+//! let bands = isoband_paths(&grid, &levels, &ContourOptions::DEFAULT);
+//! assert_eq!(bands.len(), 4);
+//! ```
+
+use crate::math::{point, Point, Vector};
+use crate::path::builder::PathBuilder;
+use crate::path::{Path, NO_ATTRIBUTES};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+/// A read-only view over a row-major grid of scalar values.
+#[derive(Copy, Clone, Debug)]
+pub struct ScalarGrid<'l> {
+    values: &'l [f32],
+    width: usize,
+    height: usize,
+}
+
+impl<'l> ScalarGrid<'l> {
+    /// Constructor.
+    ///
+    /// Panics if `values` contains less than `width * height` elements.
+    pub fn new(values: &'l [f32], width: usize, height: usize) -> Self {
+        assert!(values.len() >= width * height);
+        ScalarGrid {
+            values,
+            width,
+            height,
+        }
+    }
+
+    /// Number of samples in each row.
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Number of rows.
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the value of the sample at the provided coordinates.
+    #[inline]
+    pub fn value(&self, x: usize, y: usize) -> f32 {
+        self.values[y * self.width + x]
+    }
+}
+
+/// Parameters for the contour extraction.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[non_exhaustive]
+pub struct ContourOptions {
+    /// Position of the first sample of the grid.
+    ///
+    /// Default value: `ContourOptions::DEFAULT_ORIGIN`.
+    pub origin: Point,
+    /// Distance between two consecutive samples along each axis.
+    ///
+    /// Default value: `ContourOptions::DEFAULT_CELL_SIZE`.
+    pub cell_size: Vector,
+    /// Whether to fit quadratic bézier curves through the contour points instead
+    /// of producing polylines.
+    ///
+    /// The curves pass through the middle of each segment of the polyline and use
+    /// its vertices as control points.
+    ///
+    /// Default value: `false`.
+    pub curves: bool,
+}
+
+impl Default for ContourOptions {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl ContourOptions {
+    pub const DEFAULT_ORIGIN: Point = Point {
+        x: 0.0,
+        y: 0.0,
+        _unit: PhantomData,
+    };
+    pub const DEFAULT_CELL_SIZE: Vector = Vector {
+        x: 1.0,
+        y: 1.0,
+        _unit: PhantomData,
+    };
+
+    pub const DEFAULT: Self = ContourOptions {
+        origin: Self::DEFAULT_ORIGIN,
+        cell_size: Self::DEFAULT_CELL_SIZE,
+        curves: false,
+    };
+
+    #[inline]
+    pub fn with_origin(mut self, origin: Point) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    #[inline]
+    pub fn with_cell_size(mut self, cell_size: Vector) -> Self {
+        self.cell_size = cell_size;
+        self
+    }
+
+    #[inline]
+    pub fn with_curves(mut self, curves: bool) -> Self {
+        self.curves = curves;
+        self
+    }
+}
+
+const NONE: u32 = u32::MAX;
+
+/// A context object that extracts contours from scalar grids.
+///
+/// Keeping it around between calls allows reusing its internal allocations.
+pub struct ContourExtractor {
+    // For each grid edge, the grid edge the contour goes to next.
+    next: Vec<u32>,
+    // For each grid edge, whether a contour arrives at it.
+    has_previous: Vec<bool>,
+    // For each grid edge, the position where the contour crosses it.
+    positions: Vec<Point>,
+    points: Vec<Point>,
+    // Scratch buffers for the isoband extraction: the clipped polygon of the current cell
+    // along with the interpolated values, and the edges of all clipped polygons.
+    cell: Vec<(Point, f32)>,
+    clipped: Vec<(Point, f32)>,
+    band_edges: Vec<[Point; 2]>,
+}
+
+impl Default for ContourExtractor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ContourExtractor {
+    /// Constructor.
+    pub fn new() -> Self {
+        ContourExtractor {
+            next: Vec::new(),
+            has_previous: Vec::new(),
+            positions: Vec::new(),
+            points: Vec::new(),
+            cell: Vec::new(),
+            clipped: Vec::new(),
+            band_edges: Vec::new(),
+        }
+    }
+
+    /// Extracts the contours of the grid at a given iso-value and adds them to the output.
+    pub fn extract(
+        &mut self,
+        grid: &ScalarGrid,
+        iso_value: f32,
+        options: &ContourOptions,
+        output: &mut impl PathBuilder,
+    ) {
+        if grid.width < 2 || grid.height < 2 {
+            return;
+        }
+
+        self.find_segments(grid, iso_value, options);
+
+        let num_edges = self.next.len();
+
+        // Open contours start at the border of the grid, process them first.
+        for edge in 0..num_edges {
+            if self.next[edge] != NONE && !self.has_previous[edge] {
+                self.follow_contour(edge, options.curves, output);
+            }
+        }
+
+        // Everything that is left is a closed loop.
+        for edge in 0..num_edges {
+            if self.next[edge] != NONE {
+                self.follow_contour(edge, options.curves, output);
+            }
+        }
+    }
+
+    /// Extracts the outlines of the regions of the grid where the value is greater than or
+    /// equal to `low` and less than `high`, and adds them to the output.
+    ///
+    /// The outlines are always closed, including where the band reaches the border of the
+    /// grid. Nothing is produced if `low` is not less than `high`.
+    pub fn extract_band(
+        &mut self,
+        grid: &ScalarGrid,
+        low: f32,
+        high: f32,
+        options: &ContourOptions,
+        output: &mut impl PathBuilder,
+    ) {
+        if grid.width < 2 || grid.height < 2 || low.is_nan() || high.is_nan() || low >= high {
+            return;
+        }
+
+        self.find_band_edges(grid, low, high, options);
+
+        // Edges that are shared by two neighbor cells appear once in each direction and
+        // cancel each other out, leaving only the outline of the band.
+        let num_edges = self.band_edges.len();
+        let mut removed = vec![false; num_edges];
+        let mut unmatched: HashMap<(Key, Key), Vec<usize>> = HashMap::new();
+        for (idx, edge) in self.band_edges.iter().enumerate() {
+            let from = key(edge[0]);
+            let to = key(edge[1]);
+            if let Some(other) = unmatched.get_mut(&(to, from)).and_then(Vec::pop) {
+                removed[idx] = true;
+                removed[other] = true;
+                continue;
+            }
+            unmatched.entry((from, to)).or_default().push(idx);
+        }
+
+        let mut outgoing: HashMap<Key, Vec<usize>> = HashMap::new();
+        for (idx, edge) in self.band_edges.iter().enumerate() {
+            if !removed[idx] {
+                outgoing.entry(key(edge[0])).or_default().push(idx);
+            }
+        }
+
+        // Chain the remaining edges into closed loops.
+        for first in 0..num_edges {
+            if removed[first] {
+                continue;
+            }
+
+            self.points.clear();
+            let start = key(self.band_edges[first][0]);
+            let mut edge = first;
+            loop {
+                removed[edge] = true;
+                self.points.push(self.band_edges[edge][0]);
+                let to = key(self.band_edges[edge][1]);
+                if to == start {
+                    break;
+                }
+                let next = outgoing
+                    .get_mut(&to)
+                    .and_then(|edges| pop_remaining(edges, &removed));
+                match next {
+                    Some(next) => edge = next,
+                    None => break,
+                }
+            }
+
+            remove_collinear_points(&mut self.points);
+            if self.points.len() < 3 {
+                continue;
+            }
+
+            build_contour(&self.points, true, options.curves, output);
+        }
+    }
+
+    // Clips each cell of the grid against the band and collects the edges of the resulting
+    // polygons. All polygons have the same orientation as the cells.
+    fn find_band_edges(
+        &mut self,
+        grid: &ScalarGrid,
+        low: f32,
+        high: f32,
+        options: &ContourOptions,
+    ) {
+        self.band_edges.clear();
+
+        for y in 0..(grid.height - 1) {
+            for x in 0..(grid.width - 1) {
+                self.cell.clear();
+                for (cx, cy) in [(x, y), (x + 1, y), (x + 1, y + 1), (x, y + 1)] {
+                    let position = point(
+                        options.origin.x + cx as f32 * options.cell_size.x,
+                        options.origin.y + cy as f32 * options.cell_size.y,
+                    );
+                    self.cell.push((position, grid.value(cx, cy)));
+                }
+
+                clip_polygon(&self.cell, low, true, &mut self.clipped);
+                clip_polygon(&self.clipped, high, false, &mut self.cell);
+
+                self.cell.dedup_by(|a, b| a.0 == b.0);
+                while self.cell.len() > 1 && self.cell[0].0 == self.cell[self.cell.len() - 1].0 {
+                    self.cell.pop();
+                }
+
+                let n = self.cell.len();
+                if n < 3 {
+                    continue;
+                }
+
+                for i in 0..n {
+                    self.band_edges
+                        .push([self.cell[i].0, self.cell[(i + 1) % n].0]);
+                }
+            }
+        }
+    }
+
+    // Grid edges are identified by `(y * width + x) * 2` for the horizontal edge going from
+    // sample (x, y) to (x + 1, y) and `(y * width + x) * 2 + 1` for the vertical edge going
+    // from sample (x, y) to (x, y + 1).
+    fn find_segments(&mut self, grid: &ScalarGrid, iso_value: f32, options: &ContourOptions) {
+        let w = grid.width;
+        let h = grid.height;
+        let num_edges = w * h * 2;
+
+        self.next.clear();
+        self.next.resize(num_edges, NONE);
+        self.has_previous.clear();
+        self.has_previous.resize(num_edges, false);
+        self.positions.clear();
+        self.positions.resize(num_edges, point(0.0, 0.0));
+
+        let sample_position = |x: usize, y: usize| {
+            point(
+                options.origin.x + x as f32 * options.cell_size.x,
+                options.origin.y + y as f32 * options.cell_size.y,
+            )
+        };
+
+        for y in 0..(h - 1) {
+            for x in 0..(w - 1) {
+                // Corners and edges of the cell, in clockwise order (with y pointing down).
+                let corners = [(x, y), (x + 1, y), (x + 1, y + 1), (x, y + 1)];
+                let edges = [
+                    (y * w + x) * 2,
+                    (y * w + x + 1) * 2 + 1,
+                    ((y + 1) * w + x) * 2,
+                    (y * w + x) * 2 + 1,
+                ];
+                let values = [
+                    grid.value(x, y),
+                    grid.value(x + 1, y),
+                    grid.value(x + 1, y + 1),
+                    grid.value(x, y + 1),
+                ];
+                let inside = [
+                    values[0] >= iso_value,
+                    values[1] >= iso_value,
+                    values[2] >= iso_value,
+                    values[3] >= iso_value,
+                ];
+
+                let mut crossings = 0;
+                for i in 0..4 {
+                    let j = (i + 1) % 4;
+                    if inside[i] == inside[j] {
+                        continue;
+                    }
+                    crossings += 1;
+
+                    let t = (iso_value - values[i]) / (values[j] - values[i]);
+                    let a = sample_position(corners[i].0, corners[i].1);
+                    let b = sample_position(corners[j].0, corners[j].1);
+                    self.positions[edges[i]] = a.lerp(b, t);
+                }
+
+                if crossings == 0 {
+                    continue;
+                }
+
+                // A contour starts at edges where we leave the inside when going around
+                // the cell clockwise and ends where we enter it, so that the inside is
+                // always on the same side of the contour.
+                let saddle = crossings == 4;
+                let center_inside = values.iter().sum::<f32>() * 0.25 >= iso_value;
+                for i in 0..4 {
+                    if !inside[i] || inside[(i + 1) % 4] {
+                        continue;
+                    }
+
+                    let end = if saddle {
+                        if center_inside {
+                            (i + 1) % 4
+                        } else {
+                            (i + 3) % 4
+                        }
+                    } else {
+                        (1..4)
+                            .map(|offset| (i + offset) % 4)
+                            .find(|&j| !inside[j] && inside[(j + 1) % 4])
+                            .unwrap()
+                    };
+
+                    self.next[edges[i]] = edges[end] as u32;
+                    self.has_previous[edges[end]] = true;
+                }
+            }
+        }
+    }
+
+    fn follow_contour(&mut self, first: usize, curves: bool, output: &mut impl PathBuilder) {
+        self.points.clear();
+        let mut edge = first;
+        let mut closed = false;
+        loop {
+            self.points.push(self.positions[edge]);
+            let next = self.next[edge];
+            self.next[edge] = NONE;
+            if next == NONE {
+                break;
+            }
+            if next as usize == first {
+                closed = true;
+                break;
+            }
+            edge = next as usize;
+        }
+
+        if self.points.len() < 2 {
+            return;
+        }
+
+        build_contour(&self.points, closed, curves, output);
+    }
+}
+
+type Key = (u32, u32);
+
+fn key(p: Point) -> Key {
+    // Adding zero turns negative zeros into positive ones.
+    ((p.x + 0.0).to_bits(), (p.y + 0.0).to_bits())
+}
+
+fn pop_remaining(edges: &mut Vec<usize>, removed: &[bool]) -> Option<usize> {
+    while let Some(edge) = edges.pop() {
+        if !removed[edge] {
+            return Some(edge);
+        }
+    }
+
+    None
+}
+
+// Sutherland-Hodgman clipping of a polygon against the half-space where the interpolated
+// value is above (or below) the iso-value.
+fn clip_polygon(
+    input: &[(Point, f32)],
+    iso_value: f32,
+    keep_above: bool,
+    output: &mut Vec<(Point, f32)>,
+) {
+    output.clear();
+    let inside = |value: f32| {
+        if keep_above {
+            value >= iso_value
+        } else {
+            value < iso_value
+        }
+    };
+
+    let n = input.len();
+    for i in 0..n {
+        let (a, a_value) = input[i];
+        let (b, b_value) = input[(i + 1) % n];
+        if inside(a_value) {
+            output.push((a, a_value));
+        }
+        if inside(a_value) != inside(b_value) {
+            // Interpolate in a canonical order so that the two cells sharing a grid edge
+            // produce exactly the same point.
+            let ((p0, v0), (p1, v1)) = if (a.x, a.y) < (b.x, b.y) {
+                ((a, a_value), (b, b_value))
+            } else {
+                ((b, b_value), (a, a_value))
+            };
+            let t = (iso_value - v0) / (v1 - v0);
+            output.push((p0.lerp(p1, t), iso_value));
+        }
+    }
+}
+
+// Removes the points of a closed polyline that lie in the middle of a straight line.
+fn remove_collinear_points(points: &mut Vec<Point>) {
+    let n = points.len();
+    if n < 3 {
+        return;
+    }
+
+    let first = points[0];
+    let mut previous = points[n - 1];
+    let mut count = 0;
+    for i in 0..n {
+        let current = points[i];
+        let next = if i + 1 < n { points[i + 1] } else { first };
+        let d0 = current - previous;
+        let d1 = next - current;
+        if d0.cross(d1) != 0.0 || d0.dot(d1) <= 0.0 {
+            points[count] = current;
+            count += 1;
+        }
+        previous = current;
+    }
+
+    points.truncate(count);
+}
+
+fn build_contour(points: &[Point], closed: bool, curves: bool, output: &mut impl PathBuilder) {
+    let n = points.len();
+
+    if !curves || n < 3 {
+        output.begin(points[0], NO_ATTRIBUTES);
+        for p in &points[1..] {
+            output.line_to(*p, NO_ATTRIBUTES);
+        }
+        output.end(closed);
+        return;
+    }
+
+    if closed {
+        output.begin(points[n - 1].lerp(points[0], 0.5), NO_ATTRIBUTES);
+        for i in 0..n {
+            let ctrl = points[i];
+            let to = ctrl.lerp(points[(i + 1) % n], 0.5);
+            output.quadratic_bezier_to(ctrl, to, NO_ATTRIBUTES);
+        }
+        output.end(true);
+    } else {
+        output.begin(points[0], NO_ATTRIBUTES);
+        output.line_to(points[0].lerp(points[1], 0.5), NO_ATTRIBUTES);
+        for i in 1..(n - 1) {
+            let ctrl = points[i];
+            let to = ctrl.lerp(points[i + 1], 0.5);
+            output.quadratic_bezier_to(ctrl, to, NO_ATTRIBUTES);
+        }
+        output.line_to(points[n - 1], NO_ATTRIBUTES);
+        output.end(false);
+    }
+}
+
+/// Extracts the contours of the grid at a given iso-value and adds them to the output.
+///
+/// See also `ContourExtractor` to reuse allocations across calls.
+pub fn build_contours(
+    grid: &ScalarGrid,
+    iso_value: f32,
+    options: &ContourOptions,
+    output: &mut impl PathBuilder,
+) {
+    ContourExtractor::new().extract(grid, iso_value, options, output);
+}
+
+/// Returns a path containing the contours of the grid at a given iso-value.
+pub fn contour_path(grid: &ScalarGrid, iso_value: f32, options: &ContourOptions) -> Path {
+    let mut builder = Path::builder();
+    build_contours(grid, iso_value, options, &mut builder);
+
+    builder.build()
+}
+
+/// Returns a path for each of the provided iso-values.
+pub fn contour_paths(grid: &ScalarGrid, iso_values: &[f32], options: &ContourOptions) -> Vec<Path> {
+    let mut extractor = ContourExtractor::new();
+    iso_values
+        .iter()
+        .map(|&iso_value| {
+            let mut builder = Path::builder();
+            extractor.extract(grid, iso_value, options, &mut builder);
+            builder.build()
+        })
+        .collect()
+}
+
+/// Extracts the outlines of the regions where the value of the grid is greater than or
+/// equal to `low` and less than `high`, and adds them to the output.
+///
+/// See also `ContourExtractor` to reuse allocations across calls.
+pub fn build_isoband(
+    grid: &ScalarGrid,
+    low: f32,
+    high: f32,
+    options: &ContourOptions,
+    output: &mut impl PathBuilder,
+) {
+    ContourExtractor::new().extract_band(grid, low, high, options, output);
+}
+
+/// Returns a path containing the outlines of the regions where the value of the grid is
+/// greater than or equal to `low` and less than `high`.
+pub fn isoband_path(grid: &ScalarGrid, low: f32, high: f32, options: &ContourOptions) -> Path {
+    let mut builder = Path::builder();
+    build_isoband(grid, low, high, options, &mut builder);
+
+    builder.build()
+}
+
+/// Returns a path for each band between two consecutive values of `levels`.
+pub fn isoband_paths(grid: &ScalarGrid, levels: &[f32], options: &ContourOptions) -> Vec<Path> {
+    let mut extractor = ContourExtractor::new();
+    levels
+        .windows(2)
+        .map(|range| {
+            let mut builder = Path::builder();
+            extractor.extract_band(grid, range[0], range[1], options, &mut builder);
+            builder.build()
+        })
+        .collect()
+}
+
+#[test]
+fn single_bump() {
+    use crate::area::approximate_signed_area;
+
+    #[rustfmt::skip]
+    let values = [
+        0.0, 0.0, 0.0, 0.0,
+        0.0, 1.0, 1.0, 0.0,
+        0.0, 1.0, 1.0, 0.0,
+        0.0, 0.0, 0.0, 0.0,
+    ];
+    let grid = ScalarGrid::new(&values, 4, 4);
+    let path = contour_path(&grid, 0.5, &ContourOptions::DEFAULT);
+
+    let mut num_sub_paths = 0;
+    for evt in path.iter() {
+        if let crate::path::PathEvent::End { close, .. } = evt {
+            assert!(close);
+            num_sub_paths += 1;
+        }
+    }
+    assert_eq!(num_sub_paths, 1);
+
+    // The contour is an octagon around the 2x2 block of samples.
+    let area = approximate_signed_area(0.01, path.iter());
+    assert!((area.abs() - 3.5).abs() < 0.001, "area {:?}", area);
+
+    let scaled = contour_path(
+        &grid,
+        0.5,
+        &ContourOptions::DEFAULT.with_cell_size(crate::math::vector(2.0, 2.0)),
+    );
+    let scaled_area = approximate_signed_area(0.01, scaled.iter());
+    assert!((scaled_area - area * 4.0).abs() < 0.001);
+}
+
+#[test]
+fn hole_has_opposite_winding() {
+    use crate::area::approximate_sub_path_signed_area;
+
+    #[rustfmt::skip]
+    let values = [
+        0.0, 0.0, 0.0, 0.0, 0.0,
+        0.0, 1.0, 1.0, 1.0, 0.0,
+        0.0, 1.0, 0.0, 1.0, 0.0,
+        0.0, 1.0, 1.0, 1.0, 0.0,
+        0.0, 0.0, 0.0, 0.0, 0.0,
+    ];
+    let grid = ScalarGrid::new(&values, 5, 5);
+
+    for curves in [false, true] {
+        let options = ContourOptions::DEFAULT.with_curves(curves);
+        let path = contour_path(&grid, 0.5, &options);
+        let mut iter = path.iter();
+        let mut areas = Vec::new();
+        while let Some(area) = approximate_sub_path_signed_area(0.01, &mut iter) {
+            areas.push(area);
+        }
+        assert_eq!(areas.len(), 2);
+        assert!(areas[0] * areas[1] < 0.0);
+    }
+}
+
+#[test]
+fn open_contours_at_border() {
+    #[rustfmt::skip]
+    let values = [
+        1.0, 1.0, 0.0,
+        1.0, 0.0, 0.0,
+    ];
+    let grid = ScalarGrid::new(&values, 3, 2);
+    let paths = contour_paths(&grid, &[0.5, 2.0], &ContourOptions::DEFAULT);
+    assert_eq!(paths.len(), 2);
+
+    let events: Vec<_> = paths[0].iter().collect();
+    assert!(matches!(
+        events.last(),
+        Some(crate::path::PathEvent::End { close: false, .. })
+    ));
+    assert_eq!(paths[1].iter().count(), 0);
+}
+
+#[test]
+fn isobands() {
+    use crate::area::approximate_sub_path_signed_area;
+
+    #[rustfmt::skip]
+    let values = [
+        0.0, 0.0, 0.0, 0.0,
+        0.0, 1.0, 1.0, 0.0,
+        0.0, 1.0, 1.0, 0.0,
+        0.0, 0.0, 0.0, 0.0,
+    ];
+    let grid = ScalarGrid::new(&values, 4, 4);
+    let options = ContourOptions::DEFAULT;
+
+    let sub_path_areas = |path: &Path| {
+        let mut iter = path.iter();
+        let mut areas = Vec::new();
+        while let Some(area) = approximate_sub_path_signed_area(0.01, &mut iter) {
+            areas.push(area);
+        }
+        areas
+    };
+
+    // A band that contains every value covers the whole grid with a single rectangle.
+    let all = isoband_path(&grid, -1.0, 2.0, &options);
+    assert_eq!(all.iter().count(), 5);
+    assert_eq!(sub_path_areas(&all).len(), 1);
+    assert!((sub_path_areas(&all)[0].abs() - 9.0).abs() < 0.001);
+
+    // A ring around the bump: the area between the contours at both iso-values, with
+    // the hole oriented the opposite way.
+    let ring = isoband_path(&grid, 0.25, 0.75, &options);
+    let areas = sub_path_areas(&ring);
+    assert_eq!(areas.len(), 2);
+    assert!(areas[0] * areas[1] < 0.0);
+
+    let outer = sub_path_areas(&contour_path(&grid, 0.25, &options))[0].abs();
+    let inner = sub_path_areas(&contour_path(&grid, 0.75, &options))[0].abs();
+    let band: f32 = areas.iter().sum();
+    assert!((band.abs() - (outer - inner)).abs() < 0.001);
+
+    // The bands between consecutive levels tile the grid.
+    let levels = [0.0, 0.3, 0.6, 1.5];
+    let total: f32 = isoband_paths(&grid, &levels, &options)
+        .iter()
+        .flat_map(sub_path_areas)
+        .sum();
+    assert!((total.abs() - 9.0).abs() < 0.001);
+
+    assert!(isoband_path(&grid, 0.5, 0.5, &options)
+        .iter()
+        .next()
+        .is_none());
+}
@@ -0,0 +1,295 @@
+//! Visualize the geometric quality of a path.
+//!
+//! These are debugging tools, useful to tune curve fitting or flattening tolerances
+//! interactively:
+//!
+//! - `build_curvature_comb` produces a "curvature comb": teeth perpendicular to each curve,
+//!   with a length proportional to the curvature, and optionally the outline joining their
+//!   tips. Discontinuities in the comb reveal curvature discontinuities that are hard to
+//!   see on the curve itself.
+//! - `flatness` flattens a path and reports how far each line segment deviates from the
+//!   curve it approximates, which can be displayed as a heat map.
+//!
+//! # Example
+//!
+//! ```
+//! use lyon_algorithms::curvature::{curvature_comb, flatness, CurvatureCombOptions};
+//! use lyon_algorithms::math::point;
+//! use lyon_algorithms::path::Path;
+//!
+//! let mut builder = Path::builder();
+//! builder.begin(point(0.0, 0.0));
+//! builder.cubic_bezier_to(point(10.0, 0.0), point(10.0, 10.0), point(20.0, 10.0));
+//! builder.end(false);
+//! let path = builder.build();
+//!
+//! // Comb to draw on top of the path.
+//! let comb = curvature_comb(path.iter(), &CurvatureCombOptions::scale(50.0));
+//!
+//! // One entry per flattened line segment.
+//! let tolerance = 0.1;
+//! for sample in flatness(path.iter(), tolerance) {
+//!     assert!(sample.error <= tolerance * 1.01);
+//!     // Color `sample.segment` based on `sample.error / tolerance`.
+//! }
+//! ```
+
+use crate::geom::{CubicBezierSegment, LineSegment, QuadraticBezierSegment, Segment};
+use crate::math::{vector, Point, Vector};
+use crate::path::builder::PathBuilder;
+use crate::path::{Path, PathEvent, NO_ATTRIBUTES};
+
+/// Parameters for the curvature comb.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[non_exhaustive]
+pub struct CurvatureCombOptions {
+    /// Number of intervals between teeth on each curve.
+    ///
+    /// Default value: `CurvatureCombOptions::DEFAULT_SAMPLES`.
+    pub samples: u32,
+    /// Length of the teeth per unit of curvature.
+    ///
+    /// Teeth point away from the center of curvature. Use a negative scale to
+    /// point them towards it.
+    ///
+    /// Default value: `CurvatureCombOptions::DEFAULT_SCALE`.
+    pub scale: f32,
+    /// Whether to add a sub-path joining the tips of the teeth of each curve.
+    ///
+    /// Default value: `true`.
+    pub outline: bool,
+}
+
+impl Default for CurvatureCombOptions {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl CurvatureCombOptions {
+    pub const DEFAULT_SAMPLES: u32 = 16;
+    pub const DEFAULT_SCALE: f32 = 1.0;
+
+    pub const DEFAULT: Self = CurvatureCombOptions {
+        samples: Self::DEFAULT_SAMPLES,
+        scale: Self::DEFAULT_SCALE,
+        outline: true,
+    };
+
+    #[inline]
+    pub fn scale(scale: f32) -> Self {
+        Self::DEFAULT.with_scale(scale)
+    }
+
+    #[inline]
+    pub fn with_samples(mut self, samples: u32) -> Self {
+        self.samples = samples;
+        self
+    }
+
+    #[inline]
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    #[inline]
+    pub fn with_outline(mut self, outline: bool) -> Self {
+        self.outline = outline;
+        self
+    }
+}
+
+/// Builds the curvature comb of the curves of a path.
+///
+/// Each tooth is an open sub-path starting on the curve. Line segments have no curvature
+/// and no teeth.
+pub fn build_curvature_comb<Iter>(
+    path: Iter,
+    options: &CurvatureCombOptions,
+    output: &mut impl PathBuilder,
+) where
+    Iter: IntoIterator<Item = PathEvent>,
+{
+    let samples = options.samples.max(1);
+    let mut tips = Vec::with_capacity(samples as usize + 1);
+    for event in path {
+        tips.clear();
+        for i in 0..=samples {
+            let t = i as f32 / samples as f32;
+            let (position, d1, d2) = match derivatives(&event, t) {
+                Some(derivatives) => derivatives,
+                None => break,
+            };
+
+            let speed = d1.length();
+            if speed == 0.0 {
+                continue;
+            }
+            let curvature = d1.cross(d2) / (speed * speed * speed);
+            // The center of curvature is on the left of the curve when the curvature
+            // is positive.
+            let left = vector(-d1.y, d1.x) / speed;
+            let tip = position - left * (curvature * options.scale);
+
+            output.begin(position, NO_ATTRIBUTES);
+            output.line_to(tip, NO_ATTRIBUTES);
+            output.end(false);
+            tips.push(tip);
+        }
+
+        if options.outline && tips.len() > 1 {
+            output.begin(tips[0], NO_ATTRIBUTES);
+            for tip in &tips[1..] {
+                output.line_to(*tip, NO_ATTRIBUTES);
+            }
+            output.end(false);
+        }
+    }
+}
+
+/// Returns the curvature comb of the curves of a path.
+pub fn curvature_comb<Iter>(path: Iter, options: &CurvatureCombOptions) -> Path
+where
+    Iter: IntoIterator<Item = PathEvent>,
+{
+    let mut builder = Path::builder();
+    build_curvature_comb(path, options, &mut builder);
+
+    builder.build()
+}
+
+/// A line segment of a flattened path along with the distance to the curve it approximates.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct FlatnessSample {
+    pub segment: LineSegment<f32>,
+    /// Index of the path event that the segment approximates.
+    pub event: u32,
+    /// Approximation of the largest distance between the segment and the curve.
+    pub error: f32,
+}
+
+/// Flattens a path and measures the error of each line segment.
+///
+/// Line segments of the path, including the ones closing sub-paths, are reported with no
+/// error so that the output covers the whole path.
+pub fn flatness<Iter>(path: Iter, tolerance: f32) -> Vec<FlatnessSample>
+where
+    Iter: IntoIterator<Item = PathEvent>,
+{
+    // Number of intervals between the points used to measure the error of each segment.
+    const STEPS: u32 = 8;
+
+    let mut output = Vec::new();
+    for (idx, event) in path.into_iter().enumerate() {
+        let segment = match event.to_segment() {
+            Some(segment) => segment,
+            None => continue,
+        };
+        segment.for_each_flattened_with_t(tolerance, &mut |line, t| {
+            let mut error: f32 = 0.0;
+            if !matches!(event, PathEvent::Line { .. } | PathEvent::End { .. }) {
+                for i in 1..STEPS {
+                    let s = t.start + (t.end - t.start) * i as f32 / STEPS as f32;
+                    error = error.max(line.distance_to_point(segment.sample(s)));
+                }
+            }
+            output.push(FlatnessSample {
+                segment: *line,
+                event: idx as u32,
+                error,
+            });
+        });
+    }
+
+    output
+}
+
+// Position, first and second derivatives of a curve, or `None` for other events.
+fn derivatives(event: &PathEvent, t: f32) -> Option<(Point, Vector, Vector)> {
+    match *event {
+        PathEvent::Quadratic { from, ctrl, to } => {
+            let curve = QuadraticBezierSegment { from, ctrl, to };
+            let d2 = (from.to_vector() - ctrl.to_vector() * 2.0 + to.to_vector()) * 2.0;
+            Some((curve.sample(t), curve.derivative(t), d2))
+        }
+        PathEvent::Cubic {
+            from,
+            ctrl1,
+            ctrl2,
+            to,
+        } => {
+            let curve = CubicBezierSegment {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            };
+            let (from, ctrl1, ctrl2, to) = (
+                from.to_vector(),
+                ctrl1.to_vector(),
+                ctrl2.to_vector(),
+                to.to_vector(),
+            );
+            let d2 =
+                ((from - ctrl1 * 2.0 + ctrl2) * (1.0 - t) + (ctrl1 - ctrl2 * 2.0 + to) * t) * 6.0;
+            Some((curve.sample(t), curve.derivative(t), d2))
+        }
+        _ => None,
+    }
+}
+
+#[test]
+fn comb_and_flatness() {
+    use crate::math::point;
+
+    // A quarter of a circle of radius 10 (approximately).
+    let k = 0.5522847 * 10.0;
+    let mut builder = Path::builder();
+    builder.begin(point(10.0, 0.0));
+    builder.cubic_bezier_to(point(10.0, k), point(k, 10.0), point(0.0, 10.0));
+    builder.line_to(point(0.0, 0.0));
+    builder.end(true);
+    let path = builder.build();
+
+    // The curvature of a circle is the inverse of its radius, so teeth have (almost) the
+    // same length and point away from the center.
+    let options = CurvatureCombOptions::scale(20.0).with_samples(4);
+    let comb = curvature_comb(path.iter(), &options);
+    let mut teeth = 0;
+    let mut sub_paths = 0;
+    for event in comb.iter() {
+        if let PathEvent::End { first, last, .. } = event {
+            sub_paths += 1;
+            if (first.to_vector().length() - 10.0).abs() < 0.01 {
+                teeth += 1;
+                assert!((last.to_vector().length() - 12.0).abs() < 0.1);
+                assert!(((last - first).length() - 2.0).abs() < 0.1);
+            }
+        }
+    }
+    assert_eq!(teeth, 5);
+    // The teeth and the outline.
+    assert_eq!(sub_paths, 6);
+
+    let without_outline = curvature_comb(path.iter(), &options.with_outline(false));
+    assert_eq!(without_outline.iter().count(), 15);
+
+    let tolerance = 0.05;
+    let samples = flatness(path.iter(), tolerance);
+    // Each sample approximates a part of the path.
+    assert!(samples.iter().all(|s| s.error <= tolerance * 1.01));
+    assert!(samples.iter().any(|s| s.error > tolerance * 0.25));
+    let events: Vec<u32> = samples.iter().map(|s| s.event).collect();
+    assert_eq!(events[0], 1);
+    assert_eq!(events[events.len() - 2..], [2, 3]);
+    assert_eq!(samples[samples.len() - 1].error, 0.0);
+}
@@ -0,0 +1,478 @@
+//! Geometry of measurement annotations (dimensions).
+//!
+//! A dimension is made of a dimension line drawn at some offset from the measured
+//! geometry, extension lines connecting the measured points to the dimension line, and
+//! terminators (ticks or arrowheads) at both ends of the dimension line.
+//!
+//! - [`build_linear_dimension`](fn.build_linear_dimension.html) measures the distance
+//!   between two points, with a dimension line parallel to the segment between them.
+//! - [`build_path_dimension`](fn.build_path_dimension.html) measures the length of a
+//!   span of a path, with a dimension line that follows the path.
+//!
+//! The dimension line, the extension lines and ticks are open sub-paths meant to be
+//! stroked, while arrowheads are closed triangles meant to be filled. Both functions
+//! return a [`DimensionLabel`](struct.DimensionLabel.html) describing where to place
+//! the text of the measurement.
+//!
+//! # Example
+//!
+//! ```
+//! use lyon_algorithms::dimension::{linear_dimension, DimensionOptions, Terminator};
+//! use lyon_algorithms::math::point;
+//!
+//! let options = DimensionOptions::offset(5.0).with_terminator(Terminator::Tick { size: 2.0 });
+//! let (path, label) = linear_dimension(point(0.0, 0.0), point(100.0, 0.0), &options);
+//!
+//! assert_eq!(label.value, 100.0);
+//! assert_eq!(label.position, point(50.0, 5.0));
+//! ```
+
+use crate::math::{point, vector, Angle, Point, Vector};
+use crate::measure::{PathMeasurements, SampleType};
+use crate::path::builder::PathBuilder;
+use crate::path::iterator::PathIterator;
+use crate::path::{Path, PathEvent, NO_ATTRIBUTES};
+use std::ops::Range;
+
+/// The shape at the ends of a dimension line.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum Terminator {
+    None,
+    /// A short line crossing the dimension line at 45 degrees.
+    Tick {
+        size: f32,
+    },
+    /// A closed triangle pointing towards the extension line.
+    Arrow {
+        length: f32,
+        width: f32,
+    },
+}
+
+/// Parameters for the dimension generators.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[non_exhaustive]
+pub struct DimensionOptions {
+    /// Distance between the measured geometry and the dimension line.
+    ///
+    /// Positive offsets place the dimension line on the side of the normal obtained by
+    /// rotating the direction `d` of the measured geometry into `vector(-d.y, d.x)`.
+    /// Negative offsets place it on the other side.
+    ///
+    /// Default value: `DimensionOptions::DEFAULT_OFFSET`.
+    pub offset: f32,
+    /// Gap between the measured points and the start of the extension lines.
+    ///
+    /// Default value: `DimensionOptions::DEFAULT_EXTENSION_GAP`.
+    pub extension_gap: f32,
+    /// How far the extension lines go past the dimension line.
+    ///
+    /// Default value: `DimensionOptions::DEFAULT_EXTENSION_OVERSHOOT`.
+    pub extension_overshoot: f32,
+    /// Default value: `DimensionOptions::DEFAULT_TERMINATOR`.
+    pub terminator: Terminator,
+    /// Maximum allowed distance to the path when flattening a path span.
+    ///
+    /// Default value: `DimensionOptions::DEFAULT_TOLERANCE`.
+    pub tolerance: f32,
+}
+
+impl Default for DimensionOptions {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl DimensionOptions {
+    pub const DEFAULT_OFFSET: f32 = 10.0;
+    pub const DEFAULT_EXTENSION_GAP: f32 = 2.0;
+    pub const DEFAULT_EXTENSION_OVERSHOOT: f32 = 2.0;
+    pub const DEFAULT_TERMINATOR: Terminator = Terminator::Arrow {
+        length: 6.0,
+        width: 4.0,
+    };
+    pub const DEFAULT_TOLERANCE: f32 = 0.1;
+
+    pub const DEFAULT: Self = DimensionOptions {
+        offset: Self::DEFAULT_OFFSET,
+        extension_gap: Self::DEFAULT_EXTENSION_GAP,
+        extension_overshoot: Self::DEFAULT_EXTENSION_OVERSHOOT,
+        terminator: Self::DEFAULT_TERMINATOR,
+        tolerance: Self::DEFAULT_TOLERANCE,
+    };
+
+    #[inline]
+    pub fn offset(offset: f32) -> Self {
+        Self::DEFAULT.with_offset(offset)
+    }
+
+    #[inline]
+    pub fn with_offset(mut self, offset: f32) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    #[inline]
+    pub fn with_extension_gap(mut self, gap: f32) -> Self {
+        self.extension_gap = gap;
+        self
+    }
+
+    #[inline]
+    pub fn with_extension_overshoot(mut self, overshoot: f32) -> Self {
+        self.extension_overshoot = overshoot;
+        self
+    }
+
+    #[inline]
+    pub fn with_terminator(mut self, terminator: Terminator) -> Self {
+        self.terminator = terminator;
+        self
+    }
+
+    #[inline]
+    pub fn with_tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+}
+
+/// Where to place the text of a dimension.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DimensionLabel {
+    /// The middle of the dimension line.
+    pub position: Point,
+    /// The direction of the dimension line at the label position.
+    ///
+    /// The angle is between -90 and 90 degrees so that text rotated by this angle is
+    /// never upside down.
+    pub angle: Angle,
+    /// The measured distance.
+    pub value: f32,
+}
+
+/// Adds the dimension of the distance between two points to the output.
+///
+/// Nothing is added if the two points are equal.
+pub fn build_linear_dimension(
+    from: Point,
+    to: Point,
+    options: &DimensionOptions,
+    output: &mut impl PathBuilder,
+) -> DimensionLabel {
+    let v = to - from;
+    let length = v.length();
+    if length == 0.0 {
+        return DimensionLabel {
+            position: from,
+            angle: Angle::zero(),
+            value: 0.0,
+        };
+    }
+
+    let d = v / length;
+    let n = vector(-d.y, d.x);
+    let a = from + n * options.offset;
+    let b = to + n * options.offset;
+
+    build_extension_line(from, n, options, output);
+    build_extension_line(to, n, options, output);
+
+    output.begin(a, NO_ATTRIBUTES);
+    output.line_to(b, NO_ATTRIBUTES);
+    output.end(false);
+
+    build_terminator(a, -d, n, options, output);
+    build_terminator(b, d, n, options, output);
+
+    DimensionLabel {
+        position: a.lerp(b, 0.5),
+        angle: upright_angle(d),
+        value: length,
+    }
+}
+
+/// Returns a path containing the dimension of the distance between two points.
+pub fn linear_dimension(
+    from: Point,
+    to: Point,
+    options: &DimensionOptions,
+) -> (Path, DimensionLabel) {
+    let mut builder = Path::builder();
+    let label = build_linear_dimension(from, to, options, &mut builder);
+
+    (builder.build(), label)
+}
+
+/// Adds the dimension of a span of a path to the output.
+///
+/// The span is a range of distances along the path, and the measurements must have been
+/// computed from the same path. The dimension line follows the path at a constant offset.
+///
+/// Nothing is added if the span is empty.
+pub fn build_path_dimension(
+    path: &Path,
+    measurements: &PathMeasurements,
+    range: Range<f32>,
+    options: &DimensionOptions,
+    output: &mut impl PathBuilder,
+) -> DimensionLabel {
+    let mut span = Path::builder();
+    if path.iter().next().is_some() {
+        let mut sampler = measurements.create_sampler(path, SampleType::Distance);
+        sampler.split_range(range, &mut span);
+    }
+    let span = span.build();
+
+    let mut points: Vec<Point> = Vec::new();
+    for event in span.iter().flattened(options.tolerance) {
+        let p = match event {
+            PathEvent::Begin { at } => at,
+            PathEvent::Line { to, .. } => to,
+            _ => {
+                continue;
+            }
+        };
+        if points.last() != Some(&p) {
+            points.push(p);
+        }
+    }
+
+    if points.len() < 2 {
+        return DimensionLabel {
+            position: points.first().cloned().unwrap_or(point(0.0, 0.0)),
+            angle: Angle::zero(),
+            value: 0.0,
+        };
+    }
+
+    // Offset each vertex along the average of the normals of its two edges, scaled so that
+    // the offset edges stay parallel to the original ones (capped at sharp corners).
+    let normal = |a: Point, b: Point| {
+        let d = (b - a).normalize();
+        vector(-d.y, d.x)
+    };
+    let last = points.len() - 1;
+    let mut offset_points = Vec::with_capacity(points.len());
+    for i in 0..points.len() {
+        let n0 = normal(points[i.max(1) - 1], points[i.max(1)]);
+        let n1 = normal(points[i.min(last - 1)], points[i.min(last - 1) + 1]);
+        let n = (n0 + n1).normalize();
+        let scale = 1.0 / n.dot(n1).max(0.5);
+        offset_points.push(points[i] + n * (options.offset * scale));
+    }
+
+    let n_start = normal(points[0], points[1]);
+    let n_end = normal(points[last - 1], points[last]);
+    build_extension_line(points[0], n_start, options, output);
+    build_extension_line(points[last], n_end, options, output);
+
+    output.begin(offset_points[0], NO_ATTRIBUTES);
+    for p in &offset_points[1..] {
+        output.line_to(*p, NO_ATTRIBUTES);
+    }
+    output.end(false);
+
+    let d_start = (offset_points[1] - offset_points[0]).normalize();
+    let d_end = (offset_points[last] - offset_points[last - 1]).normalize();
+    build_terminator(offset_points[0], -d_start, n_start, options, output);
+    build_terminator(offset_points[last], d_end, n_end, options, output);
+
+    // Place the label halfway along the dimension line.
+    let mut lengths = Vec::with_capacity(last);
+    for edge in offset_points.windows(2) {
+        lengths.push((edge[1] - edge[0]).length());
+    }
+    let mut remaining = lengths.iter().sum::<f32>() * 0.5;
+    let mut position = offset_points[last];
+    let mut direction = d_end;
+    for (i, length) in lengths.iter().enumerate() {
+        if remaining <= *length && *length > 0.0 {
+            position = offset_points[i].lerp(offset_points[i + 1], remaining / length);
+            direction = (offset_points[i + 1] - offset_points[i]) / *length;
+            break;
+        }
+        remaining -= length;
+    }
+
+    let mut value = 0.0;
+    for edge in points.windows(2) {
+        value += (edge[1] - edge[0]).length();
+    }
+
+    DimensionLabel {
+        position,
+        angle: upright_angle(direction),
+        value,
+    }
+}
+
+/// Returns a path containing the dimension of a span of a path.
+///
+/// See [`build_path_dimension`](fn.build_path_dimension.html).
+pub fn path_dimension(
+    path: &Path,
+    measurements: &PathMeasurements,
+    range: Range<f32>,
+    options: &DimensionOptions,
+) -> (Path, DimensionLabel) {
+    let mut builder = Path::builder();
+    let label = build_path_dimension(path, measurements, range, options, &mut builder);
+
+    (builder.build(), label)
+}
+
+/// Adds an extension line from a measured point towards the dimension line, `n` being the
+/// normal of the measured geometry.
+fn build_extension_line(
+    at: Point,
+    n: Vector,
+    options: &DimensionOptions,
+    output: &mut impl PathBuilder,
+) {
+    let distance = options.offset.abs();
+    if distance <= options.extension_gap {
+        return;
+    }
+
+    let n = if options.offset < 0.0 { -n } else { n };
+    output.begin(at + n * options.extension_gap, NO_ATTRIBUTES);
+    output.line_to(
+        at + n * (distance + options.extension_overshoot),
+        NO_ATTRIBUTES,
+    );
+    output.end(false);
+}
+
+/// Adds a terminator at an end of the dimension line, `outward` being the direction in
+/// which the dimension line ends.
+fn build_terminator(
+    at: Point,
+    outward: Vector,
+    n: Vector,
+    options: &DimensionOptions,
+    output: &mut impl PathBuilder,
+) {
+    match options.terminator {
+        Terminator::None => {}
+        Terminator::Tick { size } => {
+            // Ticks have the same orientation at both ends.
+            let d = if outward.dot(vector(n.y, -n.x)) >= 0.0 {
+                outward
+            } else {
+                -outward
+            };
+            let k = (d + n).normalize() * (size * 0.5);
+            output.begin(at - k, NO_ATTRIBUTES);
+            output.line_to(at + k, NO_ATTRIBUTES);
+            output.end(false);
+        }
+        Terminator::Arrow { length, width } => {
+            let base = at - outward * length;
+            let side = vector(-outward.y, outward.x) * (width * 0.5);
+            output.begin(at, NO_ATTRIBUTES);
+            output.line_to(base + side, NO_ATTRIBUTES);
+            output.line_to(base - side, NO_ATTRIBUTES);
+            output.end(true);
+        }
+    }
+}
+
+fn upright_angle(direction: Vector) -> Angle {
+    let angle = direction.angle_from_x_axis().signed();
+    let half_turn = Angle::pi();
+    if angle.radians > half_turn.radians * 0.5 + 1e-5 {
+        angle - half_turn
+    } else if angle.radians <= -half_turn.radians * 0.5 + 1e-5 {
+        angle + half_turn
+    } else {
+        angle
+    }
+}
+
+#[test]
+fn linear() {
+    let options = DimensionOptions::offset(-10.0)
+        .with_extension_gap(1.0)
+        .with_extension_overshoot(3.0)
+        .with_terminator(Terminator::Arrow {
+            length: 4.0,
+            width: 2.0,
+        });
+    let (path, label) = linear_dimension(point(50.0, 0.0), point(0.0, 0.0), &options);
+
+    // Extension lines, dimension line and two arrows.
+    let sub_paths: Vec<(bool, Vec<Point>)> = {
+        let mut result = Vec::new();
+        let mut points = Vec::new();
+        for event in path.iter() {
+            match event {
+                PathEvent::Begin { at } => points.push(at),
+                PathEvent::End { close, .. } => result.push((close, std::mem::take(&mut points))),
+                _ => points.push(event.to()),
+            }
+        }
+        result
+    };
+    assert_eq!(sub_paths.len(), 5);
+    // The direction is (-1, 0) and the normal (0, -1): a negative offset goes towards +y.
+    assert_eq!(sub_paths[0].1, vec![point(50.0, 1.0), point(50.0, 13.0)]);
+    assert_eq!(sub_paths[2].1, vec![point(50.0, 10.0), point(0.0, 10.0)]);
+    // The arrows point outwards and are filled.
+    assert!(sub_paths[3].0 && sub_paths[4].0);
+    assert_eq!(sub_paths[3].1[0], point(50.0, 10.0));
+    assert_eq!(sub_paths[3].1[1].x, 46.0);
+    assert_eq!(sub_paths[4].1[1].x, 4.0);
+
+    assert_eq!(label.value, 50.0);
+    assert_eq!(label.position, point(25.0, 10.0));
+    assert!(label.angle.radians.abs() < 1e-5);
+
+    let (path, label) = linear_dimension(point(1.0, 1.0), point(1.0, 1.0), &options);
+    assert!(path.iter().next().is_none());
+    assert_eq!(label.value, 0.0);
+}
+
+#[test]
+fn along_path() {
+    use crate::path::Winding;
+
+    let mut builder = Path::builder();
+    builder.add_circle(point(0.0, 0.0), 10.0, Winding::Positive);
+    let circle = builder.build();
+    let measurements = PathMeasurements::from_path(&circle, 0.01);
+    let quarter = measurements.length() * 0.25;
+
+    // The normal of a circle with a positive winding points inwards, so a negative offset
+    // places the dimension line outside.
+    let options = DimensionOptions::offset(-2.0)
+        .with_terminator(Terminator::None)
+        .with_tolerance(0.01);
+    let (path, label) = path_dimension(&circle, &measurements, 0.0..quarter, &options);
+
+    assert!((label.value - quarter).abs() < 0.01);
+    assert!((label.position.to_vector().length() - 12.0).abs() < 0.01);
+    assert!((label.position.x.abs() - label.position.y.abs()).abs() < 0.1);
+
+    let mut sub_paths = 0;
+    for event in path.iter() {
+        match event {
+            PathEvent::End { .. } => sub_paths += 1,
+            // The dimension line is 2 units outside of the circle.
+            PathEvent::Line { to, .. } if sub_paths == 0 => {
+                assert!((to.to_vector().length() - 12.0).abs() < 0.01);
+            }
+            _ => {}
+        }
+    }
+    // No extension lines: the offset is not larger than the gap.
+    assert_eq!(sub_paths, 1);
+}
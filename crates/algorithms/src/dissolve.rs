@@ -0,0 +1,463 @@
+//! Merge a large set of polygons into the outlines of their union.
+//!
+//! Dissolving removes the edges that are shared between touching or overlapping polygons,
+//! for example to draw the outline of a selection of map parcels or of adjacent tiles.
+//! All polygons are processed at once with a sweep, which is much cheaper than combining
+//! them pairwise with boolean operations.
+//!
+//! The polygons are first snap-rounded to a grid (see the
+//! [snap_rounding](../snap_rounding/index.html) module) so that shared edges and vertices
+//! match exactly even if the input has small numerical differences. Touching polygons
+//! should therefore have vertices that are closer than `grid_size` where they touch, and
+//! `grid_size` should be small compared to the features of the polygons.
+//!
+//! # Output
+//!
+//! Each sub-path of the output is closed and simple. Outer outlines have a positive signed
+//! area and holes a negative one (see the [area](../area/index.html) module), so the output
+//! can be filled with either fill rule. Consecutive collinear edges are merged. Polygons
+//! that only touch at a vertex stay separate sub-paths.
+//!
+//! # Example
+//!
+//! ```
+//! use lyon_algorithms::dissolve::dissolved;
+//! use lyon_algorithms::math::point;
+//! use lyon_algorithms::path::{FillRule, Path};
+//!
+//! // Two adjacent squares.
+//! let mut builder = Path::builder();
+//! builder.begin(point(0.0, 0.0));
+//! builder.line_to(point(1.0, 0.0));
+//! builder.line_to(point(1.0, 1.0));
+//! builder.line_to(point(0.0, 1.0));
+//! builder.end(true);
+//! builder.begin(point(1.0, 0.0));
+//! builder.line_to(point(2.0, 0.0));
+//! builder.line_to(point(2.0, 1.0));
+//! builder.line_to(point(1.0, 1.0));
+//! builder.end(true);
+//! let path = builder.build();
+//!
+//! // A single rectangle.
+//! let outline = dissolved(path.iter(), FillRule::NonZero, 0.001, 0.01);
+//! assert_eq!(outline.iter().count(), 5);
+//! ```
+
+use crate::path::builder::PathBuilder;
+use crate::path::{FillRule, Path, PathEvent, NO_ATTRIBUTES};
+use crate::snap_rounding::{build_snap_rounded, cell, to_point, Cell};
+
+use std::collections::HashMap;
+
+/// Computes the outlines of the area covered by a set of polygons, writing them into a path
+/// builder.
+///
+/// Each sub-path of the input is a polygon and is treated as closed. The covered area is
+/// the area that is inside the path according to the fill rule, so with
+/// `FillRule::NonZero`, polygons should have the same orientation and holes the opposite
+/// one. Curves are flattened with the given tolerance and vertices are snapped to a grid of
+/// size `grid_size`.
+///
+/// Custom attributes are not preserved.
+pub fn build_dissolved<Iter>(
+    path: Iter,
+    fill_rule: FillRule,
+    grid_size: f32,
+    tolerance: f32,
+    output: &mut impl PathBuilder,
+) where
+    Iter: IntoIterator<Item = PathEvent>,
+{
+    debug_assert!(grid_size > 0.0);
+
+    let closed = path.into_iter().map(|event| match event {
+        PathEvent::End { last, first, .. } => PathEvent::End {
+            last,
+            first,
+            close: true,
+        },
+        event => event,
+    });
+    let mut snapped = Path::builder();
+    build_snap_rounded(closed, grid_size, tolerance, &mut snapped);
+    let snapped = snapped.build();
+
+    // Sum the multiplicities of the edges, oriented from the smaller to the larger cell.
+    // Edges shared by polygons with opposite orientations cancel out.
+    let mut multiplicities: HashMap<(Cell, Cell), i32> = HashMap::new();
+    for event in snapped.iter() {
+        let (from, to) = match event {
+            PathEvent::Line { from, to } => (from, to),
+            PathEvent::End { last, first, .. } => (last, first),
+            _ => continue,
+        };
+        let (from, to) = (cell(from, grid_size), cell(to, grid_size));
+        if from < to {
+            *multiplicities.entry((from, to)).or_insert(0) += 1;
+        } else if to < from {
+            *multiplicities.entry((to, from)).or_insert(0) -= 1;
+        }
+    }
+
+    let mut edges: Vec<Edge> = multiplicities
+        .into_iter()
+        .filter(|(_, multiplicity)| *multiplicity != 0)
+        .map(|((from, to), multiplicity)| Edge {
+            from,
+            to,
+            multiplicity,
+        })
+        .collect();
+    // Hash map iteration order is random, sort the edges to produce deterministic output.
+    edges.sort_unstable_by_key(|edge| (edge.from, edge.to));
+
+    let boundary = boundary_edges(&edges, fill_rule);
+    build_outlines(&boundary, grid_size, output);
+}
+
+/// Computes the outlines of the area covered by a set of polygons.
+///
+/// See [`build_dissolved`](fn.build_dissolved.html).
+pub fn dissolved<Iter>(path: Iter, fill_rule: FillRule, grid_size: f32, tolerance: f32) -> Path
+where
+    Iter: IntoIterator<Item = PathEvent>,
+{
+    let mut builder = Path::builder();
+    build_dissolved(path, fill_rule, grid_size, tolerance, &mut builder);
+
+    builder.build()
+}
+
+// An edge between two grid points that only meets other edges at its endpoints.
+struct Edge {
+    from: Cell,
+    to: Cell,
+    // Number of times the edge goes from `from` to `to`, minus the number of times it goes
+    // the other way.
+    multiplicity: i32,
+}
+
+// An edge in the coordinate system of a sweep, with `from.y < to.y`.
+struct SweepEdge {
+    from: (i64, i64),
+    to: (i64, i64),
+    // What crossing the edge adds to the winding number when going in the negative x
+    // direction.
+    winding: i32,
+    // Whether to compute the winding number next to this edge.
+    query: bool,
+}
+
+// Returns the edges that separate the inside of the path from the outside, oriented so
+// that the inside is on their positive side (the left side if the y axis points up).
+fn boundary_edges(edges: &[Edge], fill_rule: FillRule) -> Vec<(Cell, Cell)> {
+    let is_in = |winding: i32| match fill_rule {
+        FillRule::EvenOdd => winding % 2 != 0,
+        FillRule::NonZero => winding != 0,
+    };
+
+    // The winding number on each side of an edge is computed by casting a ray from its
+    // middle in the negative x direction, or in the negative y direction for horizontal
+    // edges. The second sweep swaps the axes, which mirrors the plane, so its winding
+    // numbers are computed in the original coordinates.
+    let mut result = Vec::new();
+    for &horizontal in &[false, true] {
+        let transform = |c: Cell| -> (i64, i64) {
+            if horizontal {
+                (c.1 as i64, c.0 as i64)
+            } else {
+                (c.0 as i64, c.1 as i64)
+            }
+        };
+
+        let mut sweep_edges = Vec::new();
+        for edge in edges {
+            let (from, to) = (transform(edge.from), transform(edge.to));
+            if from.1 == to.1 {
+                continue;
+            }
+            let direction = if horizontal {
+                (edge.to.0 - edge.from.0).signum()
+            } else {
+                (edge.from.1 - edge.to.1).signum()
+            };
+            let (from, to) = if from.1 < to.1 {
+                (from, to)
+            } else {
+                (to, from)
+            };
+            sweep_edges.push(SweepEdge {
+                from,
+                to,
+                winding: edge.multiplicity * direction,
+                query: !horizontal || edge.from.1 == edge.to.1,
+            });
+        }
+
+        for (edge, winding) in sweep_edges.iter().zip(sweep(&sweep_edges)) {
+            if !edge.query {
+                continue;
+            }
+            let positive_side_in = is_in(winding + edge.winding);
+            if is_in(winding) == positive_side_in {
+                continue;
+            }
+
+            let untransform = |p: (i64, i64)| -> Cell {
+                if horizontal {
+                    (p.1 as i32, p.0 as i32)
+                } else {
+                    (p.0 as i32, p.1 as i32)
+                }
+            };
+            // With the inside on the positive side of a vertical edge, the edge goes in the
+            // negative y direction. With the inside on the positive side of a horizontal
+            // edge, it goes in the positive x direction.
+            let (low, high) = (untransform(edge.from), untransform(edge.to));
+            if positive_side_in ^ horizontal {
+                result.push((high, low));
+            } else {
+                result.push((low, high));
+            }
+        }
+    }
+
+    result
+}
+
+// Computes the winding number on the negative x side of the middle of the queried edges.
+fn sweep(edges: &[SweepEdge]) -> Vec<i32> {
+    // Coordinates are doubled so that the middle of the edges are integers.
+    let mut queries: Vec<usize> = (0..edges.len()).filter(|&i| edges[i].query).collect();
+    queries.sort_unstable_by_key(|&i| edges[i].from.1 + edges[i].to.1);
+    let mut order: Vec<usize> = (0..edges.len()).collect();
+    order.sort_unstable_by_key(|&i| edges[i].from.1);
+
+    let mut windings = vec![0; edges.len()];
+    let mut active: Vec<usize> = Vec::new();
+    let mut next = 0;
+    for &query in &queries {
+        let edge = &edges[query];
+        let y = edge.from.1 + edge.to.1;
+        let x = edge.from.0 + edge.to.0;
+
+        while next < order.len() && edges[order[next]].from.1 * 2 <= y {
+            active.push(order[next]);
+            next += 1;
+        }
+        active.retain(|&i| edges[i].to.1 * 2 > y);
+
+        let mut winding = 0;
+        for &i in &active {
+            if i != query && is_on_negative_side(&edges[i], x, y) {
+                winding += edges[i].winding;
+            }
+        }
+        windings[query] = winding;
+    }
+
+    windings
+}
+
+// Whether an edge crosses the line at (doubled) ordinate y on the negative x side of the
+// (doubled) abscissa x.
+fn is_on_negative_side(edge: &SweepEdge, x: i64, y: i64) -> bool {
+    let (from, to) = (edge.from, edge.to);
+    let dx = (to.0 - from.0) as i128;
+    let dy = (to.1 - from.1) as i128;
+    let offset_x = (from.0 * 2 - x) as i128;
+    let offset_y = (y - from.1 * 2) as i128;
+
+    offset_x * dy + dx * offset_y < 0
+}
+
+// Chains the oriented boundary edges into closed outlines.
+fn build_outlines(edges: &[(Cell, Cell)], grid_size: f32, output: &mut impl PathBuilder) {
+    let mut outgoing: HashMap<Cell, Vec<usize>> = HashMap::new();
+    for (i, edge) in edges.iter().enumerate() {
+        outgoing.entry(edge.0).or_default().push(i);
+    }
+
+    let mut used = vec![false; edges.len()];
+    let mut points: Vec<Cell> = Vec::new();
+    for start in 0..edges.len() {
+        if used[start] {
+            continue;
+        }
+
+        points.clear();
+        let mut current = start;
+        loop {
+            used[current] = true;
+            let (from, to) = edges[current];
+            points.push(from);
+
+            // Take the left-most turn so that polygons that touch at a vertex are not
+            // merged into a single self-touching outline.
+            let incoming = ((to.0 - from.0) as f64, (to.1 - from.1) as f64);
+            let mut best: Option<(f64, usize)> = None;
+            for &i in outgoing.get(&to).map(|v| v.as_slice()).unwrap_or(&[]) {
+                if used[i] {
+                    continue;
+                }
+                let next = edges[i].1;
+                let v = ((next.0 - to.0) as f64, (next.1 - to.1) as f64);
+                let angle = (incoming.0 * v.1 - incoming.1 * v.0)
+                    .atan2(incoming.0 * v.0 + incoming.1 * v.1);
+                if !matches!(best, Some((best_angle, _)) if best_angle >= angle) {
+                    best = Some((angle, i));
+                }
+            }
+
+            match best {
+                Some((_, next)) => current = next,
+                None => break,
+            }
+        }
+
+        remove_collinear_points(&mut points);
+        if points.len() < 3 {
+            continue;
+        }
+
+        output.begin(to_point(points[0], grid_size), NO_ATTRIBUTES);
+        for p in &points[1..] {
+            output.line_to(to_point(*p, grid_size), NO_ATTRIBUTES);
+        }
+        output.end(true);
+    }
+}
+
+// Removes the points of a closed polygon that are in the middle of a straight line.
+fn remove_collinear_points(points: &mut Vec<Cell>) {
+    let is_collinear = |prev: Cell, p: Cell, next: Cell| {
+        let a = ((p.0 - prev.0) as i64, (p.1 - prev.1) as i64);
+        let b = ((next.0 - p.0) as i64, (next.1 - p.1) as i64);
+        a.0 * b.1 - a.1 * b.0 == 0 && a.0 * b.0 + a.1 * b.1 > 0
+    };
+
+    let mut i = 0;
+    while points.len() > 2 && i < points.len() {
+        let n = points.len();
+        let prev = points[(i + n - 1) % n];
+        let next = points[(i + 1) % n];
+        if is_collinear(prev, points[i], next) {
+            points.remove(i);
+            i = i.saturating_sub(1);
+        } else {
+            i += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+fn polygon(builder: &mut impl PathBuilder, points: &[(f32, f32)]) {
+    use crate::math::point;
+
+    builder.begin(point(points[0].0, points[0].1), NO_ATTRIBUTES);
+    for p in &points[1..] {
+        builder.line_to(point(p.0, p.1), NO_ATTRIBUTES);
+    }
+    builder.end(true);
+}
+
+#[cfg(test)]
+fn sub_path_areas(path: &Path) -> Vec<f32> {
+    let mut iter = path.iter();
+    let mut areas = Vec::new();
+    while let Some(area) = crate::area::approximate_sub_path_signed_area(0.01, &mut iter) {
+        areas.push(area);
+    }
+    areas.sort_by(|a, b| b.partial_cmp(a).unwrap());
+
+    areas
+}
+
+#[test]
+fn dissolve_parcels() {
+    // A 3x3 grid of unit squares without the middle one.
+    let mut builder = Path::builder();
+    for i in 0..3 {
+        for j in 0..3 {
+            if i == 1 && j == 1 {
+                continue;
+            }
+            let (x, y) = (i as f32, j as f32);
+            polygon(
+                &mut builder,
+                &[(x, y), (x + 1.0, y), (x + 1.0, y + 1.0), (x, y + 1.0)],
+            );
+        }
+    }
+    let path = builder.build();
+
+    let outline = dissolved(path.iter(), FillRule::NonZero, 0.01, 0.01);
+    assert_eq!(sub_path_areas(&outline), [9.0, -1.0]);
+    // Four corners for the outer outline and four for the hole.
+    assert_eq!(outline.iter().count(), 10);
+
+    // Two triangles sharing a diagonal.
+    let mut builder = Path::builder();
+    polygon(&mut builder, &[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0)]);
+    polygon(&mut builder, &[(0.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+    let path = builder.build();
+
+    let outline = dissolved(path.iter(), FillRule::NonZero, 0.01, 0.01);
+    assert_eq!(sub_path_areas(&outline), [1.0]);
+    assert_eq!(outline.iter().count(), 5);
+}
+
+#[test]
+fn dissolve_t_junction_and_overlap() {
+    // A square on top of a wider rectangle: the shared edge is only part of the
+    // rectangle's top edge. The vertices are slightly off to exercise the snapping.
+    let mut builder = Path::builder();
+    polygon(
+        &mut builder,
+        &[(0.0, 0.0), (2.0, 0.0), (2.0, 1.0001), (0.0, 0.9999)],
+    );
+    polygon(
+        &mut builder,
+        &[(0.0, 1.0), (1.0, 1.0), (1.0, 2.0), (0.0, 2.0)],
+    );
+    // An overlapping square on the right.
+    polygon(
+        &mut builder,
+        &[(1.5, 0.5), (3.0, 0.5), (3.0, 1.5), (1.5, 1.5)],
+    );
+    let path = builder.build();
+
+    let outline = dissolved(path.iter(), FillRule::NonZero, 0.01, 0.01);
+    let areas = sub_path_areas(&outline);
+    assert_eq!(areas.len(), 1);
+    assert!((areas[0] - 4.25).abs() < 0.001, "{:?}", areas);
+
+    // With the even-odd rule, the overlap is removed.
+    let outline = dissolved(path.iter(), FillRule::EvenOdd, 0.01, 0.01);
+    let area: f32 = sub_path_areas(&outline).iter().sum();
+    assert!((area - 4.0).abs() < 0.001, "{:?}", area);
+}
+
+#[test]
+fn dissolve_touching_corners() {
+    // Two squares touching at a vertex, one of them with the opposite orientation.
+    let mut builder = Path::builder();
+    polygon(
+        &mut builder,
+        &[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)],
+    );
+    polygon(
+        &mut builder,
+        &[(1.0, 1.0), (1.0, 2.0), (2.0, 2.0), (2.0, 1.0)],
+    );
+    let path = builder.build();
+
+    let outline = dissolved(path.iter(), FillRule::NonZero, 0.01, 0.01);
+    assert_eq!(sub_path_areas(&outline), [1.0, 1.0]);
+
+    assert!(dissolved(Path::new().iter(), FillRule::NonZero, 0.01, 0.01)
+        .iter()
+        .next()
+        .is_none());
+}
@@ -0,0 +1,342 @@
+//! Extend open paths beyond their endpoints.
+//!
+//! This is useful to render a stroke slightly ahead of the input while it is being drawn
+//! (predictive rendering), or to add lead-in and lead-out moves to toolpaths.
+//!
+//! Two modes are available:
+//!
+//! - `ExtensionMode::Tangent` continues the path in a straight line along the tangent at
+//!   its endpoints,
+//! - `ExtensionMode::Curvature` continues the path with a circular arc that matches the
+//!   tangent and the curvature at its endpoints.
+//!
+//! # Example
+//!
+//! ```
+//! use lyon_algorithms::extrapolate::{extend_path, ExtensionMode};
+//! use lyon_algorithms::math::point;
+//! use lyon_algorithms::path::Path;
+//!
+//! let mut builder = Path::builder();
+//! builder.begin(point(0.0, 0.0));
+//! builder.line_to(point(10.0, 0.0));
+//! builder.end(false);
+//! let path = builder.build();
+//!
+//! let extended = extend_path(&path, 2.0, ExtensionMode::Tangent);
+//! let first = extended.first_endpoint().unwrap().0;
+//! let last = extended.last_endpoint().unwrap().0;
+//! assert_eq!(first, point(-2.0, 0.0));
+//! assert_eq!(last, point(12.0, 0.0));
+//! ```
+
+use crate::geom::Arc;
+use crate::math::{vector, Angle, Point, Vector};
+use crate::path::builder::PathBuilder;
+use crate::path::{Path, PathEvent, NO_ATTRIBUTES};
+
+use std::f32::consts::PI;
+
+/// How paths are extended.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum ExtensionMode {
+    /// Continue with a line segment along the tangent.
+    Tangent,
+    /// Continue with a circular arc of the same curvature as the path.
+    ///
+    /// Falls back to a line segment if the path is straight at its endpoint. Arcs don't
+    /// go beyond a full turn.
+    Curvature,
+}
+
+/// Extends the open sub-paths of a path at both ends by the given length.
+///
+/// Closed sub-paths are not modified. Custom attributes are not preserved.
+pub fn extend_path(path: &Path, length: f32, mode: ExtensionMode) -> Path {
+    let mut builder = Path::builder();
+    build_extended_path(path.iter(), length, length, mode, &mut builder);
+
+    builder.build()
+}
+
+/// Extends the open sub-paths of a path, writing the result into a path builder.
+///
+/// `start_length` and `end_length` are the lengths of the extensions before the first
+/// endpoint and after the last endpoint of each open sub-path. The extensions are omitted
+/// if their length is not positive.
+///
+/// Closed sub-paths and sub-paths without segments are not modified.
+pub fn build_extended_path<Iter>(
+    path: Iter,
+    start_length: f32,
+    end_length: f32,
+    mode: ExtensionMode,
+    output: &mut impl PathBuilder,
+) where
+    Iter: IntoIterator<Item = PathEvent>,
+{
+    let mut segments = Vec::new();
+    let mut start_extension = Vec::new();
+    for event in path {
+        match event {
+            PathEvent::Begin { .. } => {
+                segments.clear();
+            }
+            PathEvent::End { first, close, .. } => {
+                let first_segment = segments.first().copied();
+                let last_segment = segments.last().copied();
+                let (first_segment, last_segment) = match (first_segment, last_segment) {
+                    (Some(first), Some(last)) if !close => (first, last),
+                    _ => {
+                        output.begin(first, NO_ATTRIBUTES);
+                        for segment in &segments {
+                            add_segment(segment, output);
+                        }
+                        output.end(close);
+                        continue;
+                    }
+                };
+
+                // The start extension goes backwards from the first endpoint, so it is
+                // built in reverse.
+                start_extension.clear();
+                let mut start = first;
+                if start_length > 0.0 {
+                    if let Some((tangent, curvature)) = end_state(&first_segment, false) {
+                        extension(first, -tangent, -curvature, start_length, mode, &mut |s| {
+                            start_extension.push(reversed(s));
+                        });
+                    }
+                    if let Some(s) = start_extension.last() {
+                        start = s.from();
+                    }
+                }
+
+                output.begin(start, NO_ATTRIBUTES);
+                for s in start_extension.iter().rev() {
+                    add_segment(s, output);
+                }
+                for segment in &segments {
+                    add_segment(segment, output);
+                }
+                if end_length > 0.0 {
+                    if let Some((tangent, curvature)) = end_state(&last_segment, true) {
+                        let last = last_segment.to();
+                        extension(last, tangent, curvature, end_length, mode, &mut |s| {
+                            add_segment(&s, output);
+                        });
+                    }
+                }
+                output.end(false);
+            }
+            segment => {
+                segments.push(segment);
+            }
+        }
+    }
+}
+
+fn reversed(segment: PathEvent) -> PathEvent {
+    match segment {
+        PathEvent::Line { from, to } => PathEvent::Line { from: to, to: from },
+        PathEvent::Quadratic { from, ctrl, to } => PathEvent::Quadratic {
+            from: to,
+            ctrl,
+            to: from,
+        },
+        _ => segment,
+    }
+}
+
+fn add_segment(segment: &PathEvent, output: &mut impl PathBuilder) {
+    match *segment {
+        PathEvent::Line { from, to } if from == to => {}
+        PathEvent::Line { to, .. } => {
+            output.line_to(to, NO_ATTRIBUTES);
+        }
+        PathEvent::Quadratic { from, ctrl, to } if from == ctrl && ctrl == to => {}
+        PathEvent::Quadratic { ctrl, to, .. } => {
+            output.quadratic_bezier_to(ctrl, to, NO_ATTRIBUTES);
+        }
+        PathEvent::Cubic {
+            ctrl1, ctrl2, to, ..
+        } => {
+            output.cubic_bezier_to(ctrl1, ctrl2, to, NO_ATTRIBUTES);
+        }
+        _ => {}
+    }
+}
+
+// Calls `cb` with the line segment or the quadratic bézier curves of an extension
+// starting at `from` in the direction of `tangent` (a unit vector).
+fn extension(
+    from: Point,
+    tangent: Vector,
+    curvature: f32,
+    length: f32,
+    mode: ExtensionMode,
+    cb: &mut dyn FnMut(PathEvent),
+) {
+    let sweep = (length * curvature).clamp(-2.0 * PI, 2.0 * PI);
+    if mode == ExtensionMode::Tangent || sweep.abs() < 1e-4 {
+        cb(PathEvent::Line {
+            from,
+            to: from + tangent * length,
+        });
+        return;
+    }
+
+    // The center of curvature is on the left of the tangent when the curvature is positive,
+    // in which case the arc goes in the positive angle direction.
+    let left = vector(-tangent.y, tangent.x);
+    let center = from + left / curvature;
+    let radius = 1.0 / curvature.abs();
+    let arc = Arc {
+        center,
+        radii: vector(radius, radius),
+        start_angle: (from - center).angle_from_x_axis(),
+        sweep_angle: Angle::radians(sweep),
+        x_rotation: Angle::zero(),
+    };
+    arc.for_each_quadratic_bezier(&mut |s| {
+        cb(PathEvent::Quadratic {
+            from: s.from,
+            ctrl: s.ctrl,
+            to: s.to,
+        })
+    });
+}
+
+// Returns the unit tangent and the signed curvature at the start or end of a segment.
+fn end_state(segment: &PathEvent, at_end: bool) -> Option<(Vector, f32)> {
+    let (points, n) = match *segment {
+        PathEvent::Line { from, to } => ([from, to, to, to], 2),
+        PathEvent::Quadratic { from, ctrl, to } => ([from, ctrl, to, to], 3),
+        PathEvent::Cubic {
+            from,
+            ctrl1,
+            ctrl2,
+            to,
+        } => ([from, ctrl1, ctrl2, to], 4),
+        _ => return None,
+    };
+    let mut points = points;
+    if at_end {
+        // Look at the reversed segment, and reverse the result.
+        points[..n].reverse();
+        let (tangent, curvature) = start_state(&points[..n])?;
+        return Some((-tangent, -curvature));
+    }
+
+    start_state(&points[..n])
+}
+
+// Tangent and curvature at the start of a bézier curve given its control points.
+fn start_state(points: &[Point]) -> Option<(Vector, f32)> {
+    let degree = (points.len() - 1) as f32;
+    let first = points[0];
+    // If the first control point is on the start, the tangent is given by the next one.
+    let (idx, d1) = points[1..]
+        .iter()
+        .map(|p| *p - first)
+        .enumerate()
+        .find(|(_, v)| v.square_length() > 0.0)?;
+    let tangent = d1.normalize();
+    if idx > 0 || points.len() < 3 {
+        return Some((tangent, 0.0));
+    }
+
+    let d1 = d1 * degree;
+    let d2 = (points[2].to_vector() - points[1].to_vector() * 2.0 + first.to_vector())
+        * degree
+        * (degree - 1.0);
+    let speed = d1.length();
+    let curvature = d1.cross(d2) / (speed * speed * speed);
+
+    Some((tangent, curvature))
+}
+
+#[test]
+fn extrapolation() {
+    use crate::math::point;
+
+    let last_point = |path: &Path| path.last_endpoint().unwrap().0;
+
+    // A quarter of a circle of radius 10 centered on the origin, followed by a line.
+    let k = 0.5522847 * 10.0;
+    let mut builder = Path::builder();
+    builder.begin(point(10.0, 0.0));
+    builder.cubic_bezier_to(point(10.0, k), point(k, 10.0), point(0.0, 10.0));
+    builder.end(false);
+    let arc = builder.build();
+
+    // The tangent at the end points towards -x.
+    let extended = extend_path(&arc, 5.0, ExtensionMode::Tangent);
+    assert!((last_point(&extended) - point(-5.0, 10.0)).length() < 0.001);
+    assert!((extended.first_endpoint().unwrap().0 - point(10.0, -5.0)).length() < 0.001);
+
+    // Following the curvature continues around the circle (approximately, the curvature of
+    // the cubic bézier approximation is slightly different at its endpoints).
+    let quarter = PI * 5.0;
+    let extended = extend_path(&arc, quarter, ExtensionMode::Curvature);
+    assert!(
+        (last_point(&extended) - point(-10.0, 0.0)).length() < 0.5,
+        "{:?}",
+        last_point(&extended)
+    );
+    assert!(
+        (extended.first_endpoint().unwrap().0 - point(0.0, -10.0)).length() < 0.5,
+        "{:?}",
+        extended.first_endpoint()
+    );
+    // The original curve is preserved.
+    assert!(extended.iter().any(|event| event
+        == PathEvent::Cubic {
+            from: point(10.0, 0.0),
+            ctrl1: point(10.0, k),
+            ctrl2: point(k, 10.0),
+            to: point(0.0, 10.0)
+        }));
+
+    // Straight segments and closed sub-paths.
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.end(false);
+    builder.begin(point(0.0, 5.0));
+    builder.line_to(point(5.0, 5.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.end(true);
+    let path = builder.build();
+    let mut extended = Path::builder();
+    build_extended_path(
+        path.iter(),
+        0.0,
+        3.0,
+        ExtensionMode::Curvature,
+        &mut extended,
+    );
+    let events: Vec<PathEvent> = extended.build().iter().collect();
+    let expected: Vec<PathEvent> = path.iter().collect();
+    // Only the end of the open sub-path is extended.
+    assert_eq!(events.len(), expected.len() + 1);
+    assert_eq!(
+        events[1],
+        PathEvent::Line {
+            from: point(0.0, 0.0),
+            to: point(10.0, 0.0)
+        }
+    );
+    assert_eq!(
+        events[2],
+        PathEvent::Line {
+            from: point(10.0, 0.0),
+            to: point(13.0, 0.0)
+        }
+    );
+    assert_eq!(events[4..], expected[3..]);
+}
@@ -0,0 +1,317 @@
+//! Export paths to the flat encoding used by compute rasterizers.
+//!
+//! GPU compute rasterizers typically consume paths as two parallel streams rather than
+//! as a sequence of events:
+//!
+//! - a tag stream with one byte per segment, describing its kind and whether it ends a
+//!   sub-path or a path,
+//! - a point stream in which consecutive segments share their endpoints: the first
+//!   segment of a sub-path is preceded by its start point, and each segment then adds its
+//!   control points and its endpoint.
+//!
+//! Curves are split into monotonic pieces (along both axes) so that each segment can be
+//! binned and rasterized without looking for extrema on the GPU. Closing segments are
+//! explicit.
+//!
+//! # Example
+//!
+//! ```
+//! use lyon_algorithms::gpu_encoding::{tag, GpuPathEncoding};
+//! use lyon_algorithms::math::point;
+//! use lyon_algorithms::path::Path;
+//!
+//! let mut builder = Path::builder();
+//! builder.begin(point(0.0, 0.0));
+//! builder.line_to(point(10.0, 0.0));
+//! builder.quadratic_bezier_to(point(10.0, 10.0), point(0.0, 10.0));
+//! builder.close();
+//! let path = builder.build();
+//!
+//! let mut encoding = GpuPathEncoding::new();
+//! encoding.encode_path(path.iter());
+//!
+//! // The line, the quadratic curve and the closing line.
+//! assert_eq!(
+//!     encoding.tags,
+//!     vec![tag::LINE, tag::QUADRATIC, tag::LINE | tag::SUBPATH_END | tag::PATH_END],
+//! );
+//! assert_eq!(encoding.points.len(), 5);
+//! ```
+
+use crate::geom::{CubicBezierSegment, QuadraticBezierSegment};
+use crate::math::{point, Point};
+use crate::path::{Path, PathEvent};
+
+/// The values of the tag stream.
+///
+/// The two lowest bits contain the kind of the segment, which is also the number of
+/// points it adds to the point stream.
+pub mod tag {
+    pub const LINE: u8 = 1;
+    pub const QUADRATIC: u8 = 2;
+    pub const CUBIC: u8 = 3;
+    /// Mask of the bits containing the kind of the segment.
+    pub const SEGMENT_MASK: u8 = 3;
+    /// Set on the last segment of each sub-path. The next segment starts a new sub-path
+    /// and is preceded by its start point in the point stream.
+    pub const SUBPATH_END: u8 = 4;
+    /// Set on the last segment of each path.
+    pub const PATH_END: u8 = 8;
+}
+
+/// Paths in the tag stream + point stream encoding.
+///
+/// See the [module documentation](index.html).
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct GpuPathEncoding {
+    /// One tag per segment, see the [`tag`](tag/index.html) module.
+    pub tags: Vec<u8>,
+    pub points: Vec<[f32; 2]>,
+    /// Whether open sub-paths are closed with an explicit line segment, as required for
+    /// filling. True by default.
+    pub close_sub_paths: bool,
+}
+
+impl GpuPathEncoding {
+    pub fn new() -> Self {
+        GpuPathEncoding {
+            tags: Vec::new(),
+            points: Vec::new(),
+            close_sub_paths: true,
+        }
+    }
+
+    /// An encoding that does not close open sub-paths, for example for stroking.
+    pub fn new_for_strokes() -> Self {
+        GpuPathEncoding {
+            close_sub_paths: false,
+            ..GpuPathEncoding::new()
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.tags.clear();
+        self.points.clear();
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+
+    #[inline]
+    pub fn num_segments(&self) -> usize {
+        self.tags.len()
+    }
+
+    /// Appends a path to the encoding.
+    ///
+    /// Sub-paths without any segment are skipped. If the path has no segment, nothing is
+    /// added.
+    pub fn encode_path(&mut self, path: impl IntoIterator<Item = PathEvent>) {
+        let first_tag = self.tags.len();
+        let mut sub_path_start = None;
+        for event in path {
+            match event {
+                PathEvent::Begin { at } => {
+                    sub_path_start = Some((self.tags.len(), self.points.len()));
+                    self.points.push(at.to_array());
+                }
+                PathEvent::Line { to, .. } => {
+                    self.push(tag::LINE, &[to]);
+                }
+                PathEvent::Quadratic { from, ctrl, to } => {
+                    let curve = QuadraticBezierSegment { from, ctrl, to };
+                    curve.for_each_monotonic_range(&mut |range| {
+                        let last = range.end == 1.0;
+                        let sub = curve.split_range(range);
+                        let sub_to = if last { to } else { sub.to };
+                        self.push(tag::QUADRATIC, &[clamp(sub.ctrl, sub.from, sub_to), sub_to]);
+                    });
+                }
+                PathEvent::Cubic {
+                    from,
+                    ctrl1,
+                    ctrl2,
+                    to,
+                } => {
+                    let curve = CubicBezierSegment {
+                        from,
+                        ctrl1,
+                        ctrl2,
+                        to,
+                    };
+                    curve.for_each_monotonic_range(&mut |range| {
+                        let last = range.end == 1.0;
+                        let sub = curve.split_range(range);
+                        let sub_to = if last { to } else { sub.to };
+                        self.push(
+                            tag::CUBIC,
+                            &[
+                                clamp(sub.ctrl1, sub.from, sub_to),
+                                clamp(sub.ctrl2, sub.from, sub_to),
+                                sub_to,
+                            ],
+                        );
+                    });
+                }
+                PathEvent::End { last, first, close } => {
+                    let (start_tag, start_point) = match sub_path_start.take() {
+                        Some(start) => start,
+                        None => continue,
+                    };
+                    if (close || self.close_sub_paths) && last != first {
+                        self.push(tag::LINE, &[first]);
+                    }
+                    if self.tags.len() == start_tag {
+                        // No segment, remove the start point.
+                        self.points.truncate(start_point);
+                    } else {
+                        *self.tags.last_mut().unwrap() |= tag::SUBPATH_END;
+                    }
+                }
+            }
+        }
+
+        if self.tags.len() > first_tag {
+            *self.tags.last_mut().unwrap() |= tag::PATH_END;
+        }
+    }
+
+    /// Converts the encoding back into a path.
+    ///
+    /// All sub-paths are open, since closing segments are explicit.
+    pub fn to_path(&self) -> Path {
+        let mut builder = Path::builder();
+        let mut points = self.points.iter().map(|p| point(p[0], p[1]));
+        let mut in_sub_path = false;
+        for &t in &self.tags {
+            if !in_sub_path {
+                builder.begin(points.next().unwrap());
+                in_sub_path = true;
+            }
+            match t & tag::SEGMENT_MASK {
+                tag::LINE => {
+                    builder.line_to(points.next().unwrap());
+                }
+                tag::QUADRATIC => {
+                    let ctrl = points.next().unwrap();
+                    builder.quadratic_bezier_to(ctrl, points.next().unwrap());
+                }
+                _ => {
+                    let ctrl1 = points.next().unwrap();
+                    let ctrl2 = points.next().unwrap();
+                    builder.cubic_bezier_to(ctrl1, ctrl2, points.next().unwrap());
+                }
+            }
+            if t & tag::SUBPATH_END != 0 {
+                builder.end(false);
+                in_sub_path = false;
+            }
+        }
+
+        builder.build()
+    }
+
+    fn push(&mut self, segment: u8, points: &[Point]) {
+        self.tags.push(segment);
+        self.points.extend(points.iter().map(|p| p.to_array()));
+    }
+}
+
+/// Encodes a single path.
+pub fn encode(path: impl IntoIterator<Item = PathEvent>) -> GpuPathEncoding {
+    let mut encoding = GpuPathEncoding::new();
+    encoding.encode_path(path);
+
+    encoding
+}
+
+// Due to finite precision, control points of split curves can be slightly outside of the
+// box of their endpoints, which would make the curve non-monotonic.
+fn clamp(ctrl: Point, from: Point, to: Point) -> Point {
+    point(
+        ctrl.x.max(from.x.min(to.x)).min(from.x.max(to.x)),
+        ctrl.y.max(from.y.min(to.y)).min(from.y.max(to.y)),
+    )
+}
+
+#[test]
+fn gpu_encoding() {
+    use crate::geom::Segment;
+    use crate::path::Event;
+
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.cubic_bezier_to(point(10.0, -10.0), point(20.0, 10.0), point(30.0, 0.0));
+    builder.line_to(point(30.0, 30.0));
+    builder.end(false);
+    // Empty sub-path.
+    builder.begin(point(100.0, 100.0));
+    builder.end(true);
+    builder.begin(point(50.0, 50.0));
+    builder.quadratic_bezier_to(point(60.0, 40.0), point(70.0, 50.0));
+    builder.close();
+    let path = builder.build();
+
+    let mut encoding = GpuPathEncoding::new_for_strokes();
+    encoding.encode_path(path.iter());
+    encoding.encode_path(Path::new().iter());
+    encoding.encode_path(path.iter());
+
+    // The cubic has an extremum in y on both sides of its inflection point.
+    let sub_path = [
+        tag::CUBIC,
+        tag::CUBIC,
+        tag::CUBIC,
+        tag::LINE | tag::SUBPATH_END,
+        tag::QUADRATIC,
+        tag::QUADRATIC,
+        tag::LINE | tag::SUBPATH_END | tag::PATH_END,
+    ];
+    assert_eq!(encoding.tags, [sub_path, sub_path].concat());
+
+    let num_points: usize = encoding
+        .tags
+        .iter()
+        .map(|t| (t & tag::SEGMENT_MASK) as usize)
+        .sum::<usize>()
+        + 4;
+    assert_eq!(encoding.points.len(), num_points);
+
+    let decoded = encoding.to_path();
+    let mut sub_paths = 0;
+    for event in decoded.iter() {
+        if let Event::End { close, .. } = event {
+            assert!(!close);
+            sub_paths += 1;
+        }
+        if let Some(segment) = event.to_segment() {
+            // Every segment is monotonic.
+            let bounds = segment.bounding_box();
+            let (from, to) = (segment.from(), segment.to());
+            assert!(bounds.width() == (to.x - from.x).abs());
+            assert!(bounds.height() == (to.y - from.y).abs());
+        }
+    }
+    assert_eq!(sub_paths, 4);
+    let decoded_events: Vec<PathEvent> = decoded.iter().collect();
+    assert_eq!(
+        decoded_events[4],
+        PathEvent::Line {
+            from: point(30.0, 0.0),
+            to: point(30.0, 30.0)
+        }
+    );
+
+    // Open sub-paths are closed when filling.
+    let filled = encode(path.iter());
+    assert_eq!(filled.tags.len(), 8);
+    assert_eq!(filled.points[filled.points.len() - 1], [50.0, 50.0]);
+    // Start point, three cubics, the line and the closing line.
+    assert_eq!(filled.points[11], [0.0, 0.0]);
+}
@@ -0,0 +1,381 @@
+//! Find good positions for labels inside filled shapes.
+//!
+//! - `pole_of_inaccessibility` computes the visual center of a shape: the point inside
+//!   the shape that is the farthest from its outline (the center of the largest inscribed
+//!   circle). Unlike the centroid, it is always inside the shape, even for concave shapes
+//!   or shapes with holes. This is the algorithm popularized by the polylabel library.
+//! - `longest_inscribed_segment` computes the longest straight segment that fits inside
+//!   the shape, which is a good baseline for horizontal or rotated text along elongated
+//!   shapes.
+//!
+//! Paths are considered filled: sub-paths are implicitly closed.
+//!
+//! # Example
+//!
+//! ```
+//! use lyon_algorithms::label::{pole_of_inaccessibility, longest_inscribed_segment};
+//! use lyon_algorithms::math::point;
+//! use lyon_algorithms::path::{FillRule, Path};
+//!
+//! // An L shape.
+//! let mut builder = Path::builder();
+//! builder.begin(point(0.0, 0.0));
+//! builder.line_to(point(10.0, 0.0));
+//! builder.line_to(point(10.0, 2.0));
+//! builder.line_to(point(2.0, 2.0));
+//! builder.line_to(point(2.0, 10.0));
+//! builder.line_to(point(0.0, 10.0));
+//! builder.end(true);
+//! let path = builder.build();
+//!
+//! // The largest circle fits in the corner of the L.
+//! let circle = pole_of_inaccessibility(path.iter(), FillRule::NonZero, 0.01, 0.01).unwrap();
+//! assert!(circle.radius > 1.0);
+//! assert!(circle.center.x < 2.0 && circle.center.y < 2.0);
+//!
+//! let segment = longest_inscribed_segment(path.iter(), FillRule::NonZero, 0.01, 64).unwrap();
+//! assert!(segment.length() > 10.0);
+//! ```
+
+use crate::geom::LineSegment;
+use crate::math::{point, vector, Box2D, Point};
+use crate::path::{FillRule, PathEvent};
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::f32::consts::PI;
+
+/// The largest circle that fits inside a shape.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct InscribedCircle {
+    pub center: Point,
+    pub radius: f32,
+}
+
+/// Computes the point inside the filled path that is the farthest away from its outline.
+///
+/// The search stops when the radius of the circle can't be improved by more than
+/// `precision`. Curves are flattened with the provided tolerance.
+///
+/// Distances are measured to all edges of the path, including edges that are inside the
+/// filled area where sub-paths overlap.
+///
+/// Returns `None` if the path does not enclose any area.
+pub fn pole_of_inaccessibility<Iter>(
+    path: Iter,
+    fill_rule: FillRule,
+    tolerance: f32,
+    precision: f32,
+) -> Option<InscribedCircle>
+where
+    Iter: IntoIterator<Item = PathEvent>,
+{
+    let edges = flattened_edges(path, tolerance);
+    if edges.is_empty() {
+        return None;
+    }
+
+    let bounds = Box2D::from_points(edges.iter().map(|edge| edge.from));
+    let size = bounds.size();
+    let cell_size = size.width.min(size.height);
+    if cell_size <= 0.0 {
+        return None;
+    }
+    // Avoid subdividing forever if the precision is too small to be reached with floats.
+    let precision = precision.max(size.width.max(size.height) * 1e-5);
+
+    let new_cell = |center: Point, half_size: f32| {
+        let distance = signed_distance(&edges, center, fill_rule);
+        Cell {
+            center,
+            half_size,
+            distance,
+            max_distance: distance + half_size * std::f32::consts::SQRT_2,
+        }
+    };
+
+    // Cover the shape with square cells.
+    let half_size = cell_size * 0.5;
+    let mut queue = BinaryHeap::new();
+    let mut y = bounds.min.y;
+    while y < bounds.max.y {
+        let mut x = bounds.min.x;
+        while x < bounds.max.x {
+            queue.push(new_cell(point(x + half_size, y + half_size), half_size));
+            x += cell_size;
+        }
+        y += cell_size;
+    }
+
+    // Start from the centroid, which is the answer for many simple shapes.
+    let mut best = new_cell(centroid(&edges).unwrap_or(bounds.center()), 0.0);
+    let center = new_cell(bounds.center(), 0.0);
+    if center.distance > best.distance {
+        best = center;
+    }
+
+    while let Some(cell) = queue.pop() {
+        if cell.distance > best.distance {
+            best = cell;
+        }
+
+        // Skip the cell if it can't contain a better solution.
+        if cell.max_distance - best.distance <= precision {
+            continue;
+        }
+
+        let h = cell.half_size * 0.5;
+        for offset in [vector(-h, -h), vector(h, -h), vector(-h, h), vector(h, h)] {
+            queue.push(new_cell(cell.center + offset, h));
+        }
+    }
+
+    if best.distance <= 0.0 {
+        return None;
+    }
+
+    Some(InscribedCircle {
+        center: best.center,
+        radius: best.distance,
+    })
+}
+
+/// Computes the longest straight segment inside the filled path.
+///
+/// `num_directions` orientations evenly distributed over half a turn are considered,
+/// so the result is exact up to the angle between two consecutive directions. Curves
+/// are flattened with the provided tolerance.
+///
+/// Returns `None` if the path does not enclose any area.
+pub fn longest_inscribed_segment<Iter>(
+    path: Iter,
+    fill_rule: FillRule,
+    tolerance: f32,
+    num_directions: u32,
+) -> Option<LineSegment<f32>>
+where
+    Iter: IntoIterator<Item = PathEvent>,
+{
+    let edges = flattened_edges(path, tolerance);
+
+    let mut best: Option<LineSegment<f32>> = None;
+    let mut best_length = 0.0;
+    let mut crossings = Vec::new();
+
+    let num_directions = num_directions.max(1);
+    for i in 0..num_directions {
+        let angle = PI * i as f32 / num_directions as f32;
+        let (sin, cos) = angle.sin_cos();
+        let direction = vector(cos, sin);
+        let normal = vector(-sin, cos);
+
+        // For a given direction, the length of the inside intervals varies linearly between
+        // the vertices of the outline, so the longest one passes through a vertex.
+        for origin in edges.iter().map(|edge| edge.from) {
+            crossings.clear();
+            for edge in &edges {
+                let sa = (edge.from - origin).dot(normal);
+                let sb = (edge.to - origin).dot(normal);
+                if (sa > 0.0) == (sb > 0.0) {
+                    continue;
+                }
+                let p = edge.from.lerp(edge.to, sa / (sa - sb));
+                let winding = if sb > 0.0 { 1 } else { -1 };
+                crossings.push(((p - origin).dot(direction), winding));
+            }
+
+            crossings.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+
+            let mut winding = 0;
+            let mut start = 0.0;
+            for &(t, w) in &crossings {
+                let was_inside = is_inside(winding, fill_rule);
+                winding += w;
+                let inside = is_inside(winding, fill_rule);
+                if inside && !was_inside {
+                    start = t;
+                } else if was_inside && !inside && t - start > best_length {
+                    best_length = t - start;
+                    best = Some(LineSegment {
+                        from: origin + direction * start,
+                        to: origin + direction * t,
+                    });
+                }
+            }
+        }
+    }
+
+    best
+}
+
+#[derive(Copy, Clone)]
+struct Cell {
+    center: Point,
+    half_size: f32,
+    // Signed distance from the center to the outline, positive inside.
+    distance: f32,
+    // Upper bound of the distance for any point in the cell.
+    max_distance: f32,
+}
+
+impl PartialEq for Cell {
+    fn eq(&self, other: &Self) -> bool {
+        self.max_distance == other.max_distance
+    }
+}
+
+impl Eq for Cell {}
+
+impl PartialOrd for Cell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Cell {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.max_distance
+            .partial_cmp(&other.max_distance)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+fn is_inside(winding: i32, fill_rule: FillRule) -> bool {
+    match fill_rule {
+        FillRule::EvenOdd => winding % 2 != 0,
+        FillRule::NonZero => winding != 0,
+    }
+}
+
+// Flattens the path into line segments, closing all sub-paths.
+fn flattened_edges<Iter>(path: Iter, tolerance: f32) -> Vec<LineSegment<f32>>
+where
+    Iter: IntoIterator<Item = PathEvent>,
+{
+    let mut edges = Vec::new();
+    for event in path {
+        match event {
+            PathEvent::Begin { .. } => {}
+            PathEvent::End { last, first, .. } => {
+                if last != first {
+                    edges.push(LineSegment {
+                        from: last,
+                        to: first,
+                    });
+                }
+            }
+            _ => {
+                event
+                    .to_segment()
+                    .unwrap()
+                    .for_each_flattened(tolerance, &mut |line| {
+                        edges.push(*line);
+                    });
+            }
+        }
+    }
+
+    edges
+}
+
+// Distance to the closest edge, positive inside the shape and negative outside.
+fn signed_distance(edges: &[LineSegment<f32>], p: Point, fill_rule: FillRule) -> f32 {
+    let mut min_sq_distance = f32::MAX;
+    let mut winding = 0;
+    for edge in edges {
+        let closest = edge.closest_point(p);
+        min_sq_distance = min_sq_distance.min((closest - p).square_length());
+
+        // Winding number from the edges crossing the horizontal half-line on the right
+        // of the point.
+        if (edge.from.y <= p.y) != (edge.to.y <= p.y) {
+            let t = (p.y - edge.from.y) / (edge.to.y - edge.from.y);
+            if edge.from.x + (edge.to.x - edge.from.x) * t > p.x {
+                winding += if edge.to.y > edge.from.y { 1 } else { -1 };
+            }
+        }
+    }
+
+    let distance = min_sq_distance.sqrt();
+    if is_inside(winding, fill_rule) {
+        distance
+    } else {
+        -distance
+    }
+}
+
+fn centroid(edges: &[LineSegment<f32>]) -> Option<Point> {
+    let mut area = 0.0;
+    let mut sum = vector(0.0, 0.0);
+    for edge in edges {
+        let cross = edge.from.to_vector().cross(edge.to.to_vector());
+        area += cross;
+        sum += (edge.from.to_vector() + edge.to.to_vector()) * cross;
+    }
+
+    if area == 0.0 {
+        return None;
+    }
+
+    Some((sum / (3.0 * area)).to_point())
+}
+
+#[test]
+fn label_placement() {
+    use crate::path::Path;
+
+    // A 10x10 square with a 6x6 hole.
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.end(true);
+    builder.begin(point(2.0, 2.0));
+    builder.line_to(point(2.0, 8.0));
+    builder.line_to(point(8.0, 8.0));
+    builder.line_to(point(8.0, 2.0));
+    builder.end(true);
+    let path = builder.build();
+
+    // The label goes in a corner of the ring around the hole, even though the centroid is
+    // in the hole.
+    let corner_radius = 2.0 * 2.0f32.sqrt() / (1.0 + 2.0f32.sqrt());
+    for fill_rule in [FillRule::EvenOdd, FillRule::NonZero] {
+        let ring = pole_of_inaccessibility(path.iter(), fill_rule, 0.01, 0.001).unwrap();
+        assert!((ring.radius - corner_radius).abs() < 0.01, "{:?}", ring);
+        assert!(!Box2D::new(point(2.0, 2.0), point(8.0, 8.0)).contains(ring.center));
+    }
+
+    let outer = path.iter().take(6);
+    let square = pole_of_inaccessibility(outer, FillRule::NonZero, 0.01, 0.001).unwrap();
+    assert!((square.center - point(5.0, 5.0)).length() < 0.01);
+    assert!((square.radius - 5.0).abs() < 0.01);
+
+    // The longest segment in a rectangle is its diagonal.
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 4.0));
+    builder.line_to(point(0.0, 4.0));
+    builder.end(false);
+    let rectangle = builder.build();
+
+    let diagonal = vector(10.0, 4.0).length();
+    let segment =
+        longest_inscribed_segment(rectangle.iter(), FillRule::NonZero, 0.01, 180).unwrap();
+    assert!(segment.length() <= diagonal + 0.001);
+    assert!(segment.length() > diagonal - 0.1, "{:?}", segment);
+
+    // In the ring, the segment runs along one side.
+    let segment = longest_inscribed_segment(path.iter(), FillRule::EvenOdd, 0.01, 180).unwrap();
+    assert!(segment.length() > 10.0);
+
+    let empty = crate::path::Path::new();
+    assert!(pole_of_inaccessibility(empty.iter(), FillRule::NonZero, 0.01, 0.01).is_none());
+    assert!(longest_inscribed_segment(empty.iter(), FillRule::NonZero, 0.01, 16).is_none());
+}
@@ -51,6 +51,117 @@ where
     length
 }
 
+/// Lower and upper bounds of the length of a path.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LengthBounds {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl LengthBounds {
+    /// A length estimate within `max_error()` of the actual length.
+    #[inline]
+    pub fn estimate(&self) -> f32 {
+        (self.min + self.max) * 0.5
+    }
+
+    /// The maximum distance between `estimate()` and the actual length.
+    #[inline]
+    pub fn max_error(&self) -> f32 {
+        (self.max - self.min) * 0.5
+    }
+}
+
+const MAX_SUBDIVISIONS: u32 = 16;
+
+/// Computes a quick estimate of the length of a path along with an error bound.
+///
+/// This is faster than [`approximate_length`](fn.approximate_length.html) when
+/// a coarse result is enough (dash counts, animation durations, etc.), because
+/// curves are not flattened. The length of a curve is bounded by the length of
+/// its chord and the length of its control polygon. Curves are split until the
+/// gap between the two bounds fits in the tolerance, so the error of the estimate
+/// is at most half of the tolerance per curve.
+///
+/// The returned bounds are always valid, even in the rare cases where the tolerance
+/// could not be reached within the maximum number of subdivisions.
+pub fn fast_approximate_length<Iter>(path: Iter, tolerance: f32) -> LengthBounds
+where
+    Iter: IntoIterator<Item = PathEvent>,
+{
+    let tolerance = tolerance.max(1e-4);
+
+    let mut bounds = LengthBounds { min: 0.0, max: 0.0 };
+
+    for evt in path.into_iter() {
+        match evt {
+            PathEvent::Line { from, to } => {
+                let length = (to - from).length();
+                bounds.min += length;
+                bounds.max += length;
+            }
+            PathEvent::Quadratic { from, ctrl, to } => {
+                let curve = QuadraticBezierSegment { from, ctrl, to }.to_cubic();
+                cubic_length_bounds(&curve, tolerance, &mut bounds);
+            }
+            PathEvent::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => {
+                let curve = CubicBezierSegment {
+                    from,
+                    ctrl1,
+                    ctrl2,
+                    to,
+                };
+                cubic_length_bounds(&curve, tolerance, &mut bounds);
+            }
+            PathEvent::End {
+                last,
+                first,
+                close: true,
+            } => {
+                let length = (first - last).length();
+                bounds.min += length;
+                bounds.max += length;
+            }
+            _ => {}
+        }
+    }
+
+    bounds
+}
+
+fn cubic_length_bounds(curve: &CubicBezierSegment<f32>, tolerance: f32, bounds: &mut LengthBounds) {
+    // Each split gives half of the tolerance to each half so that the sum of
+    // the gaps over the whole curve stays within the tolerance.
+    let mut stack = [(*curve, tolerance, 0); MAX_SUBDIVISIONS as usize + 1];
+    let mut stack_len = 1;
+
+    while stack_len > 0 {
+        stack_len -= 1;
+        let (curve, tolerance, depth) = stack[stack_len];
+
+        let chord = (curve.to - curve.from).length();
+        let polygon = (curve.ctrl1 - curve.from).length()
+            + (curve.ctrl2 - curve.ctrl1).length()
+            + (curve.to - curve.ctrl2).length();
+
+        if polygon - chord <= tolerance || depth >= MAX_SUBDIVISIONS {
+            bounds.min += chord;
+            bounds.max += polygon;
+            continue;
+        }
+
+        let (a, b) = curve.split(0.5);
+        stack[stack_len] = (b, tolerance * 0.5, depth + 1);
+        stack[stack_len + 1] = (a, tolerance * 0.5, depth + 1);
+        stack_len += 2;
+    }
+}
+
 #[test]
 fn approx_length() {
     use crate::geom::point;
@@ -66,3 +177,27 @@ fn approx_length() {
 
     assert!((approximate_length(&path, 0.01) - 4.0).abs() < 0.0001);
 }
+
+#[test]
+fn fast_approx_length() {
+    use crate::geom::point;
+
+    let mut builder = crate::path::Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.cubic_bezier_to(point(20.0, 0.0), point(20.0, 10.0), point(10.0, 10.0));
+    builder.quadratic_bezier_to(point(0.0, 10.0), point(0.0, 5.0));
+    builder.end(true);
+    let path = builder.build();
+
+    let reference = approximate_length(&path, 0.0001);
+
+    for tolerance in [10.0, 1.0, 0.1, 0.01] {
+        let bounds = fast_approximate_length(&path, tolerance);
+        assert!(bounds.min <= reference + 0.001);
+        assert!(bounds.max >= reference - 0.001);
+        // Two curves, each within half of the tolerance.
+        assert!(bounds.max_error() <= tolerance);
+        assert!((bounds.estimate() - reference).abs() <= bounds.max_error() + 0.001);
+    }
+}
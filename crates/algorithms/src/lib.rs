@@ -10,13 +10,32 @@ pub extern crate lyon_path as path;
 
 pub mod aabb;
 pub mod area;
+pub mod batching;
+pub mod bulge;
+pub mod bvh;
+pub mod cad;
+pub mod canonicalize;
+pub mod classify;
+pub mod contour;
+pub mod curvature;
+pub mod dimension;
+pub mod dissolve;
+pub mod extrapolate;
 pub mod fit;
+pub mod gpu_encoding;
 pub mod hatching;
 pub mod hit_test;
+pub mod label;
 pub mod length;
+pub mod loft;
+pub mod marker_placement;
+pub mod markers;
 pub mod measure;
+pub mod occlusion;
 pub mod raycast;
 pub mod rect;
+pub mod simplify;
+pub mod snap_rounding;
 pub mod walk;
 pub mod winding;
 
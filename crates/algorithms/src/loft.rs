@@ -0,0 +1,349 @@
+//! Blend between two paths.
+//!
+//! A loft matches the points of two open paths by their relative distance along each path
+//! (the point halfway along the first path corresponds to the point halfway along the
+//! second one). This can be used to:
+//!
+//! - generate intermediate paths, for example to preview a morph between two shapes,
+//! - generate the surface between the two paths as a strip of triangles with texture
+//!   coordinates, for example to render ribbons with a gradient across their width.
+//!
+//! The paths are flattened and resampled so that the corners of both paths are preserved
+//! in the intermediate paths and in the surface.
+//!
+//! # Example
+//!
+//! ```
+//! use lyon_algorithms::loft::Loft;
+//! use lyon_algorithms::math::point;
+//! use lyon_algorithms::path::Path;
+//!
+//! let mut a = Path::builder();
+//! a.begin(point(0.0, 0.0));
+//! a.line_to(point(10.0, 0.0));
+//! a.end(false);
+//! let a = a.build();
+//!
+//! let mut b = Path::builder();
+//! b.begin(point(0.0, 10.0));
+//! b.line_to(point(20.0, 10.0));
+//! b.end(false);
+//! let b = b.build();
+//!
+//! let loft = Loft::new(a.iter(), b.iter(), 0.1);
+//!
+//! let middle = loft.blend(0.5);
+//! assert_eq!(middle.first_endpoint().unwrap().0, point(0.0, 5.0));
+//! assert_eq!(middle.last_endpoint().unwrap().0, point(15.0, 5.0));
+//!
+//! // Two triangles between the two line segments.
+//! let surface = loft.surface();
+//! assert_eq!(surface.vertices.len(), 4);
+//! assert_eq!(surface.indices.len(), 6);
+//! ```
+
+use crate::math::{point, Point};
+use crate::path::builder::PathBuilder;
+use crate::path::iterator::PathIterator;
+use crate::path::{Path, PathEvent, NO_ATTRIBUTES};
+
+/// A vertex of the surface between the two paths of a loft.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct LoftVertex {
+    pub position: Point,
+    /// The relative distance along the paths in `x`, between 0 and 1, and the blend
+    /// parameter in `y`: 0 on the first path and 1 on the second.
+    pub uv: Point,
+}
+
+/// The triangles of the surface between the two paths of a loft.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct LoftSurface {
+    pub vertices: Vec<LoftVertex>,
+    pub indices: Vec<u32>,
+}
+
+/// Matching points along two paths.
+///
+/// See the [module documentation](index.html).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Loft {
+    from: Vec<Point>,
+    to: Vec<Point>,
+    params: Vec<f32>,
+}
+
+impl Loft {
+    /// Creates a loft between two paths, flattening curves with the given tolerance.
+    ///
+    /// Only the first sub-path of each path is used. Closed sub-paths are treated as open
+    /// ones that end with their closing segment. If either sub-path is missing, the loft is
+    /// empty.
+    pub fn new<A, B>(from: A, to: B, tolerance: f32) -> Self
+    where
+        A: IntoIterator<Item = PathEvent>,
+        B: IntoIterator<Item = PathEvent>,
+    {
+        let (from, from_params) = flattened_polyline(from, tolerance);
+        let (to, to_params) = flattened_polyline(to, tolerance);
+        if from.is_empty() || to.is_empty() {
+            return Loft::default();
+        }
+
+        // Sample both polylines at the parameters of the points of both.
+        let mut params: Vec<f32> = from_params.iter().chain(&to_params).cloned().collect();
+        params.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        params.dedup_by(|a, b| (*a - *b).abs() < 1e-5);
+
+        Loft {
+            from: resample(&from, &from_params, &params),
+            to: resample(&to, &to_params, &params),
+            params,
+        }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+
+    /// Writes the intermediate path at a given blend parameter into a path builder.
+    ///
+    /// The first path is obtained with `t = 0.0` and the second one with `t = 1.0`.
+    /// Other values outside of this range extrapolate.
+    pub fn build_blend(&self, t: f32, output: &mut impl PathBuilder) {
+        if self.is_empty() {
+            return;
+        }
+
+        output.begin(self.from[0].lerp(self.to[0], t), NO_ATTRIBUTES);
+        for (from, to) in self.from.iter().zip(&self.to).skip(1) {
+            output.line_to(from.lerp(*to, t), NO_ATTRIBUTES);
+        }
+        output.end(false);
+    }
+
+    /// Returns the intermediate path at a given blend parameter.
+    ///
+    /// See [`build_blend`](#method.build_blend).
+    pub fn blend(&self, t: f32) -> Path {
+        let mut builder = Path::builder();
+        self.build_blend(t, &mut builder);
+
+        builder.build()
+    }
+
+    /// Writes `count` evenly spaced intermediate paths into a path builder, each as a
+    /// sub-path.
+    ///
+    /// The two input paths are not included.
+    pub fn build_intermediate_paths(&self, count: u32, output: &mut impl PathBuilder) {
+        for i in 1..=count {
+            self.build_blend(i as f32 / (count + 1) as f32, output);
+        }
+    }
+
+    /// Appends the triangles of the surface between the two paths.
+    ///
+    /// The surface is a strip of two triangles per segment. Indices are offset by the
+    /// number of vertices already in `output`.
+    pub fn build_surface(&self, output: &mut LoftSurface) {
+        let offset = output.vertices.len() as u32;
+        for ((from, to), u) in self.from.iter().zip(&self.to).zip(&self.params) {
+            output.vertices.push(LoftVertex {
+                position: *from,
+                uv: point(*u, 0.0),
+            });
+            output.vertices.push(LoftVertex {
+                position: *to,
+                uv: point(*u, 1.0),
+            });
+        }
+
+        for i in 1..self.params.len() as u32 {
+            let a = offset + (i - 1) * 2;
+            let b = a + 1;
+            let c = a + 2;
+            let d = a + 3;
+            output.indices.extend_from_slice(&[a, c, b, b, c, d]);
+        }
+    }
+
+    /// Returns the triangles of the surface between the two paths.
+    ///
+    /// See [`build_surface`](#method.build_surface).
+    pub fn surface(&self) -> LoftSurface {
+        let mut surface = LoftSurface::default();
+        self.build_surface(&mut surface);
+
+        surface
+    }
+}
+
+// Flattens the first sub-path of a path, returning its points and their relative distance
+// along the sub-path.
+fn flattened_polyline<Iter>(path: Iter, tolerance: f32) -> (Vec<Point>, Vec<f32>)
+where
+    Iter: IntoIterator<Item = PathEvent>,
+{
+    let mut points = Vec::new();
+    for event in path.into_iter().flattened(tolerance) {
+        match event {
+            PathEvent::Begin { at } => {
+                points.push(at);
+            }
+            PathEvent::Line { to, .. } => {
+                points.push(to);
+            }
+            PathEvent::End {
+                last, first, close, ..
+            } => {
+                if close && last != first {
+                    points.push(first);
+                }
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    let mut params = Vec::with_capacity(points.len());
+    let mut length = 0.0;
+    for (i, p) in points.iter().enumerate() {
+        if i > 0 {
+            length += (*p - points[i - 1]).length();
+        }
+        params.push(length);
+    }
+
+    if length > 0.0 {
+        for param in &mut params {
+            *param /= length;
+        }
+    } else if points.len() > 1 {
+        // All points are at the same position, distribute them evenly.
+        let n = (points.len() - 1) as f32;
+        for (i, param) in params.iter_mut().enumerate() {
+            *param = i as f32 / n;
+        }
+    }
+
+    (points, params)
+}
+
+// Evaluates a polyline at each of the (sorted) parameters.
+fn resample(points: &[Point], params: &[f32], at: &[f32]) -> Vec<Point> {
+    let mut result = Vec::with_capacity(at.len());
+    let mut segment = 0;
+    for &u in at {
+        while segment + 2 < points.len() && params[segment + 1] < u {
+            segment += 1;
+        }
+        if points.len() == 1 {
+            result.push(points[0]);
+            continue;
+        }
+        let (u0, u1) = (params[segment], params[segment + 1]);
+        let t = if u1 > u0 {
+            ((u - u0) / (u1 - u0)).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        result.push(points[segment].lerp(points[segment + 1], t));
+    }
+
+    result
+}
+
+#[test]
+fn loft() {
+    // A horizontal line and an L shape of the same length.
+    let mut a = Path::builder();
+    a.begin(point(0.0, 0.0));
+    a.line_to(point(20.0, 0.0));
+    a.end(false);
+    let a = a.build();
+
+    let mut b = Path::builder();
+    b.begin(point(0.0, 10.0));
+    b.line_to(point(10.0, 10.0));
+    b.line_to(point(10.0, 20.0));
+    b.end(false);
+    let b = b.build();
+
+    let loft = Loft::new(a.iter(), b.iter(), 0.1);
+
+    // The corner of the L shape matches the middle of the line.
+    let points = |path: &Path| -> Vec<Point> {
+        path.iter()
+            .filter_map(|event| match event {
+                PathEvent::Begin { at } => Some(at),
+                PathEvent::Line { to, .. } => Some(to),
+                _ => None,
+            })
+            .collect()
+    };
+    assert_eq!(
+        points(&loft.blend(0.0)),
+        [point(0.0, 0.0), point(10.0, 0.0), point(20.0, 0.0)]
+    );
+    assert_eq!(
+        points(&loft.blend(1.0)),
+        [point(0.0, 10.0), point(10.0, 10.0), point(10.0, 20.0)]
+    );
+    assert_eq!(
+        points(&loft.blend(0.5)),
+        [point(0.0, 5.0), point(10.0, 5.0), point(15.0, 10.0)]
+    );
+
+    let mut builder = Path::builder();
+    loft.build_intermediate_paths(3, &mut builder);
+    let intermediate = builder.build();
+    let begins = intermediate
+        .iter()
+        .filter(|event| matches!(event, PathEvent::Begin { .. }))
+        .count();
+    assert_eq!(begins, 3);
+    assert!(intermediate.iter().any(|event| event
+        == PathEvent::Begin {
+            at: point(0.0, 5.0)
+        }));
+
+    let surface = loft.surface();
+    assert_eq!(surface.vertices.len(), 6);
+    assert_eq!(surface.indices, [0, 2, 1, 1, 2, 3, 2, 4, 3, 3, 4, 5]);
+    assert_eq!(
+        surface.vertices[3],
+        LoftVertex {
+            position: point(10.0, 10.0),
+            uv: point(0.5, 1.0)
+        }
+    );
+    let mut twice = surface.clone();
+    loft.build_surface(&mut twice);
+    assert_eq!(twice.indices[12], 6);
+
+    // Curves are flattened and missing paths give an empty loft.
+    let mut c = Path::builder();
+    c.begin(point(0.0, 0.0));
+    c.quadratic_bezier_to(point(10.0, 10.0), point(20.0, 0.0));
+    c.end(true);
+    let c = c.build();
+    let loft = Loft::new(a.iter(), c.iter(), 0.1);
+    assert!(loft.surface().vertices.len() > 6);
+    assert_eq!(loft.blend(1.0).last_endpoint().unwrap().0, point(0.0, 0.0));
+    assert!(Loft::new(a.iter(), Path::new().iter(), 0.1).is_empty());
+    assert!(Loft::new(a.iter(), Path::new().iter(), 0.1)
+        .blend(0.5)
+        .iter()
+        .next()
+        .is_none());
+}
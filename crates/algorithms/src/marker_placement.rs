@@ -0,0 +1,285 @@
+//! Place markers at the vertices of a path, following the semantics of SVG markers.
+//!
+//! SVG's `marker-start`, `marker-mid` and `marker-end` properties draw a graphic (for
+//! example an arrowhead or a dot) at each vertex of a path, rotated to follow its
+//! direction. `place_markers` computes the position and orientation of each of these
+//! markers so that they can be instanced by the application:
+//!
+//! - the first vertex of the path gets a `Start` marker and the last one an `End` marker,
+//! - every other vertex, including the ends of the other sub-paths, gets a `Mid` marker,
+//! - closing a sub-path adds a vertex at its first point,
+//! - markers are oriented along the bisector of the incoming and outgoing directions of
+//!   their vertex (SVG's `orient="auto"`). At the first vertex of a closed sub-path, the
+//!   incoming direction is the one of the closing segment.
+//!
+//! For `orient="auto-start-reverse"`, rotate the `Start` marker by 180 degrees.
+//!
+//! # Example
+//!
+//! ```
+//! use lyon_algorithms::marker_placement::{place_markers, MarkerKind};
+//! use lyon_algorithms::math::point;
+//! use lyon_algorithms::path::Path;
+//!
+//! let mut builder = Path::builder();
+//! builder.begin(point(0.0, 0.0));
+//! builder.line_to(point(10.0, 0.0));
+//! builder.line_to(point(10.0, 10.0));
+//! builder.end(false);
+//! let path = builder.build();
+//!
+//! let markers = place_markers(path.iter(), 0.01);
+//! assert_eq!(markers.len(), 3);
+//! assert_eq!(markers[2].kind, MarkerKind::End);
+//!
+//! // The arrowhead at the end points down, along the last segment.
+//! let tip = markers[2].transform().transform_point(point(1.0, 0.0));
+//! assert!((tip - point(10.0, 11.0)).length() < 0.001);
+//! ```
+
+use crate::math::{Angle, Point, Transform, Vector};
+use crate::path::PathEvent;
+
+/// Which of the SVG marker properties applies to a vertex.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum MarkerKind {
+    /// The first vertex of the path (`marker-start`).
+    Start,
+    /// Any vertex that is neither the first nor the last one (`marker-mid`).
+    Mid,
+    /// The last vertex of the path (`marker-end`).
+    End,
+}
+
+/// The position and orientation of a marker.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct MarkerPlacement {
+    pub kind: MarkerKind,
+    pub position: Point,
+    /// Direction of the path at the marker, measured from the x axis.
+    pub angle: Angle,
+}
+
+impl MarkerPlacement {
+    /// Returns the transform from the marker's local space to the space of the path.
+    ///
+    /// The marker's x axis is aligned with the direction of the path and its origin is
+    /// placed on the vertex.
+    pub fn transform(&self) -> Transform {
+        Transform::rotation(self.angle).then_translate(self.position.to_vector())
+    }
+}
+
+/// Computes the markers of a path, in order.
+///
+/// Control points that are closer than `tolerance` to an endpoint don't contribute to the
+/// direction of a curve at this endpoint, and segments that are shorter than `tolerance`
+/// in every direction are considered zero-length: they take the direction of the previous
+/// segment (or the next one at the beginning of a sub-path).
+pub fn place_markers(
+    path: impl IntoIterator<Item = PathEvent>,
+    tolerance: f32,
+) -> Vec<MarkerPlacement> {
+    let mut markers = Vec::new();
+    let mut positions = Vec::new();
+    let mut segments = Vec::new();
+
+    for event in path {
+        let (points, n) = match event {
+            PathEvent::Begin { at } => {
+                positions.clear();
+                segments.clear();
+                positions.push(at);
+                continue;
+            }
+            PathEvent::Line { from, to } => ([from, to, to, to], 2),
+            PathEvent::Quadratic { from, ctrl, to } => ([from, ctrl, to, to], 3),
+            PathEvent::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => ([from, ctrl1, ctrl2, to], 4),
+            PathEvent::End { last, first, close } => {
+                if close {
+                    segments.push(segment_directions(&[last, first], tolerance));
+                    positions.push(first);
+                }
+                add_sub_path_markers(&positions, &mut segments, close, &mut markers);
+                positions.clear();
+                continue;
+            }
+        };
+
+        positions.push(points[n - 1]);
+        segments.push(segment_directions(&points[..n], tolerance));
+    }
+
+    if let Some(first) = markers.first_mut() {
+        first.kind = MarkerKind::Start;
+    }
+    if markers.len() == 1 {
+        // A path with a single vertex gets both a start and an end marker.
+        let mut end = markers[0];
+        end.kind = MarkerKind::End;
+        markers.push(end);
+    } else if let Some(last) = markers.last_mut() {
+        last.kind = MarkerKind::End;
+    }
+
+    markers
+}
+
+// Returns the direction at the start and end of a segment given its control points, or
+// `None` if the segment has zero length.
+fn segment_directions(points: &[Point], tolerance: f32) -> Option<(Vector, Vector)> {
+    let first = points[0];
+    let last = points[points.len() - 1];
+    let start = points[1..]
+        .iter()
+        .map(|p| *p - first)
+        .find(|v| v.length() > tolerance)?;
+    let end = points[..points.len() - 1]
+        .iter()
+        .rev()
+        .map(|p| last - *p)
+        .find(|v| v.length() > tolerance)?;
+
+    Some((start, end))
+}
+
+// Segment `i` goes from `positions[i]` to `positions[i + 1]`.
+fn add_sub_path_markers(
+    positions: &[Point],
+    segments: &mut [Option<(Vector, Vector)>],
+    closed: bool,
+    output: &mut Vec<MarkerPlacement>,
+) {
+    // Zero-length segments take the direction at the end of the previous segment...
+    let mut previous = None;
+    for segment in segments.iter_mut() {
+        match *segment {
+            Some((_, end)) => previous = Some(end),
+            None => *segment = previous.map(|v| (v, v)),
+        }
+    }
+    // ... or at the start of the next one if there is none.
+    let mut next = None;
+    for segment in segments.iter_mut().rev() {
+        match *segment {
+            Some((start, _)) => next = Some(start),
+            None => *segment = next.map(|v| (v, v)),
+        }
+    }
+
+    let num_segments = segments.len();
+    for (idx, position) in positions.iter().enumerate() {
+        let incoming = if idx > 0 {
+            segments[idx - 1]
+        } else if closed {
+            segments[num_segments - 1]
+        } else {
+            None
+        };
+        let outgoing = if idx < num_segments {
+            segments[idx]
+        } else if closed {
+            segments[0]
+        } else {
+            None
+        };
+
+        let angle = match (incoming, outgoing) {
+            (Some((_, incoming)), Some((outgoing, _))) => bisector(incoming, outgoing),
+            (Some((_, v)), None) | (None, Some((v, _))) => v.angle_from_x_axis(),
+            (None, None) => Angle::zero(),
+        };
+
+        output.push(MarkerPlacement {
+            kind: MarkerKind::Mid,
+            position: *position,
+            angle: angle.signed(),
+        });
+    }
+}
+
+fn bisector(incoming: Vector, outgoing: Vector) -> Angle {
+    let a = incoming.angle_from_x_axis();
+    let b = outgoing.angle_from_x_axis();
+
+    a + (b - a).signed() / 2.0
+}
+
+#[test]
+fn svg_marker_semantics() {
+    use crate::math::point;
+    use crate::path::Path;
+
+    let assert_angle = |marker: &MarkerPlacement, degrees: f32| {
+        assert!(
+            (marker.angle.to_degrees() - degrees).abs() < 0.01,
+            "{:?} expected {:?}",
+            marker,
+            degrees
+        );
+    };
+
+    // A closed square followed by an open sub-path.
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.end(true);
+    builder.begin(point(20.0, 0.0));
+    builder.line_to(point(30.0, 0.0));
+    builder.end(false);
+    let path = builder.build();
+
+    let markers = place_markers(path.iter(), 0.01);
+    let kinds: Vec<MarkerKind> = markers.iter().map(|m| m.kind).collect();
+    use MarkerKind::*;
+    assert_eq!(kinds, [Start, Mid, Mid, Mid, Mid, Mid, End]);
+
+    // The start of the closed sub-path bisects the closing segment and the first one.
+    assert_eq!(markers[0].position, point(0.0, 0.0));
+    assert_angle(&markers[0], -45.0);
+    assert_angle(&markers[1], 45.0);
+    assert_angle(&markers[2], 135.0);
+    assert_angle(&markers[3], -135.0);
+    // The closing vertex.
+    assert_eq!(markers[4].position, point(0.0, 0.0));
+    assert_angle(&markers[4], -45.0);
+    assert_angle(&markers[5], 0.0);
+    assert_angle(&markers[6], 0.0);
+
+    // Curves with control points on their endpoints and zero-length segments.
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.cubic_bezier_to(point(0.0, 0.0), point(0.0, 10.0), point(10.0, 10.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(10.0, 20.0));
+    builder.end(false);
+    let path = builder.build();
+
+    let markers = place_markers(path.iter(), 0.01);
+    assert_eq!(markers.len(), 4);
+    assert_angle(&markers[0], 90.0);
+    // The zero-length line continues the curve.
+    assert_angle(&markers[1], 0.0);
+    assert_angle(&markers[2], 45.0);
+    assert_angle(&markers[3], 90.0);
+
+    let tip = markers[3].transform().transform_point(point(2.0, 0.0));
+    assert!((tip - point(10.0, 22.0)).length() < 0.001);
+
+    assert!(place_markers(Path::new().iter(), 0.01).is_empty());
+}
@@ -0,0 +1,336 @@
+//! Named markers anchored to stable locations of a path.
+//!
+//! Applications often need to attach labels, handles or constraints to a specific place
+//! of a path. Positions are not stable: they change whenever the path is transformed.
+//! A [`PathLocation`](struct.PathLocation.html) instead refers to an event of the path
+//! (by index in iteration order) and a parameter on its segment, which are not affected
+//! by transformations.
+//!
+//! [`PathMarkers`](struct.PathMarkers.html) stores named locations and keeps them valid
+//! across modifications of the path:
+//!
+//! - transformations don't require any change,
+//! - edits described by a [`PathPatch`](../path/diff/struct.PathPatch.html) are followed
+//!   with `apply_patch`,
+//! - modifications that change the events of the path but preserve its overall shape
+//!   (for example simplification or re-fitting) can be followed with `relocate`, which
+//!   preserves the relative arc-length position of each marker.
+//!
+//! # Example
+//!
+//! ```
+//! use lyon_algorithms::markers::PathMarkers;
+//! use lyon_algorithms::measure::PathMeasurements;
+//! use lyon_algorithms::math::{point, Transform};
+//! use lyon_algorithms::path::Path;
+//!
+//! let mut builder = Path::builder();
+//! builder.begin(point(0.0, 0.0));
+//! builder.line_to(point(10.0, 0.0));
+//! builder.line_to(point(10.0, 10.0));
+//! builder.end(false);
+//! let path = builder.build();
+//!
+//! let measurements = PathMeasurements::from_path(&path, 0.01);
+//! let mut markers = PathMarkers::new();
+//! markers.insert_at_distance("label", 15.0, &measurements);
+//!
+//! let position = markers.position("label", path.iter()).unwrap();
+//! assert!((position - point(10.0, 5.0)).length() < 0.001);
+//!
+//! // Markers follow transformations.
+//! let transform = Transform::scale(2.0, 2.0);
+//! let path = path.transformed(&transform);
+//! let position = markers.position("label", path.iter()).unwrap();
+//! assert!((position - point(20.0, 10.0)).length() < 0.001);
+//! ```
+
+use crate::geom::Segment;
+use crate::math::Point;
+use crate::measure::PathMeasurements;
+use crate::path::diff::{PatchOp, PathPatch};
+use crate::path::{Event, PathEvent};
+
+/// A location on a path that does not depend on the positions of its points.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct PathLocation {
+    /// Index of the event in iteration order.
+    pub event: u32,
+    /// Parameter on the segment of the event, between 0 and 1.
+    ///
+    /// For `End` events that don't close their sub-path, the location is the last endpoint
+    /// of the sub-path. For `Begin` events, it is the first endpoint.
+    pub t: f32,
+}
+
+impl PathLocation {
+    /// Returns the position of this location on a path.
+    ///
+    /// Returns `None` if the path has fewer events.
+    pub fn position(&self, events: impl IntoIterator<Item = PathEvent>) -> Option<Point> {
+        let event = events.into_iter().nth(self.event as usize)?;
+        Some(match event.to_segment() {
+            Some(segment) => segment.sample(self.t),
+            None => match event {
+                Event::Begin { at } => at,
+                Event::End { last, .. } => last,
+                _ => unreachable!(),
+            },
+        })
+    }
+}
+
+/// A named location on a path.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct Marker {
+    pub name: String,
+    pub location: PathLocation,
+}
+
+/// A set of named markers attached to a path.
+///
+/// See the [module documentation](index.html).
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct PathMarkers {
+    markers: Vec<Marker>,
+}
+
+impl PathMarkers {
+    pub fn new() -> Self {
+        PathMarkers::default()
+    }
+
+    /// Adds a marker, or moves it if a marker with the same name already exists.
+    pub fn insert(&mut self, name: impl Into<String>, location: PathLocation) {
+        let name = name.into();
+        match self.markers.iter_mut().find(|marker| marker.name == name) {
+            Some(marker) => {
+                marker.location = location;
+            }
+            None => {
+                self.markers.push(Marker { name, location });
+            }
+        }
+    }
+
+    /// Adds a marker at a distance along the path.
+    ///
+    /// Returns false and does not add the marker if the path has no segment.
+    pub fn insert_at_distance(
+        &mut self,
+        name: impl Into<String>,
+        distance: f32,
+        measurements: &PathMeasurements,
+    ) -> bool {
+        match measurements.location(distance) {
+            Some(location) => {
+                self.insert(name, location);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes a marker and returns its location.
+    pub fn remove(&mut self, name: &str) -> Option<PathLocation> {
+        let idx = self.markers.iter().position(|marker| marker.name == name)?;
+        Some(self.markers.remove(idx).location)
+    }
+
+    pub fn get(&self, name: &str) -> Option<PathLocation> {
+        self.markers
+            .iter()
+            .find(|marker| marker.name == name)
+            .map(|marker| marker.location)
+    }
+
+    /// Returns the position of a marker on a path.
+    pub fn position(
+        &self,
+        name: &str,
+        events: impl IntoIterator<Item = PathEvent>,
+    ) -> Option<Point> {
+        self.get(name)?.position(events)
+    }
+
+    /// Returns the distance of a marker along the path.
+    pub fn distance(&self, name: &str, measurements: &PathMeasurements) -> Option<f32> {
+        measurements.distance(self.get(name)?)
+    }
+
+    /// The markers, in insertion order.
+    #[inline]
+    pub fn markers(&self) -> &[Marker] {
+        &self.markers
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.markers.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.markers.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.markers.clear();
+    }
+
+    /// Updates the markers after a patch was applied to the path.
+    ///
+    /// Markers on moved events keep their parameter. Markers on removed events are
+    /// removed.
+    pub fn apply_patch(&mut self, patch: &PathPatch) {
+        self.markers.retain_mut(|marker| {
+            let event = marker.location.event;
+            let mut shift: i64 = 0;
+            for op in patch.ops() {
+                match *op {
+                    PatchOp::Insert { index, .. } if index <= event => {
+                        shift += 1;
+                    }
+                    PatchOp::Remove { index, count } if index <= event => {
+                        if event < index + count {
+                            return false;
+                        }
+                        shift -= count as i64;
+                    }
+                    _ => {}
+                }
+            }
+            marker.location.event = (event as i64 + shift) as u32;
+
+            true
+        });
+    }
+
+    /// Moves the markers from a path to a modified version of it, preserving their
+    /// relative distance along the path.
+    ///
+    /// This is meant for modifications that change the events of the path but not
+    /// its overall shape, such as simplification. Markers that can't be located in the
+    /// new path are removed.
+    pub fn relocate(&mut self, old: &PathMeasurements, new: &PathMeasurements) {
+        let old_length = old.length();
+        let new_length = new.length();
+        self.markers.retain_mut(|marker| {
+            let distance = match old.distance(marker.location) {
+                Some(distance) => distance,
+                None => return false,
+            };
+            let ratio = if old_length > 0.0 {
+                distance / old_length
+            } else {
+                0.0
+            };
+            match new.location(ratio * new_length) {
+                Some(location) => {
+                    marker.location = location;
+                    true
+                }
+                None => false,
+            }
+        });
+    }
+}
+
+#[test]
+fn markers_follow_edits() {
+    use crate::math::point;
+    use crate::path::Path;
+
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(4.0, 0.0));
+    builder.quadratic_bezier_to(point(6.0, 0.0), point(6.0, 2.0));
+    builder.line_to(point(6.0, 6.0));
+    builder.end(false);
+    let path = builder.build();
+
+    let measurements = PathMeasurements::from_path(&path, 0.001);
+    let mut markers = PathMarkers::new();
+    markers.insert_at_distance("start", 0.0, &measurements);
+    markers.insert_at_distance("a", 2.0, &measurements);
+    markers.insert("curve", PathLocation { event: 2, t: 0.5 });
+    markers.insert_at_distance("end", 1000.0, &measurements);
+    assert_eq!(markers.len(), 4);
+    assert_eq!(markers.get("a"), Some(PathLocation { event: 1, t: 0.5 }));
+    assert_eq!(markers.position("end", path.iter()), Some(point(6.0, 6.0)));
+    assert_eq!(
+        markers.position("start", path.iter()),
+        Some(point(0.0, 0.0))
+    );
+
+    // Distances and locations are consistent.
+    let d = markers.distance("curve", &measurements).unwrap();
+    let location = measurements.location(d).unwrap();
+    assert_eq!(location.event, 2);
+    assert!((location.t - 0.5).abs() < 0.01);
+    assert!((markers.distance("end", &measurements).unwrap() - measurements.length()).abs() < 1e-4);
+
+    // Insert a point before the curve and remove the last segment.
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(4.0, 0.0));
+    builder.line_to(point(5.0, -1.0));
+    builder.quadratic_bezier_to(point(6.0, 0.0), point(6.0, 2.0));
+    builder.end(false);
+    let modified = builder.build();
+
+    let patch = PathPatch::diff(path.iter(), modified.iter());
+    let curve_position = markers.position("curve", path.iter()).unwrap();
+    markers.apply_patch(&patch);
+    assert_eq!(markers.get("curve").unwrap().event, 3);
+    assert_eq!(
+        markers.position("a", modified.iter()),
+        Some(point(2.0, 0.0))
+    );
+    // The curve changed but the marker stays on it.
+    assert!(markers.position("curve", modified.iter()).unwrap() != curve_position);
+    assert!(markers.get("end").is_none());
+
+    // Relocate to a flattened version of the path.
+    let mut builder = Path::builder();
+    for event in modified.iter() {
+        match event {
+            Event::Begin { at } => {
+                builder.begin(at);
+            }
+            Event::End { close, .. } => {
+                builder.end(close);
+            }
+            _ => {
+                event
+                    .to_segment()
+                    .unwrap()
+                    .for_each_flattened(0.01, &mut |line| {
+                        builder.line_to(line.to);
+                    });
+            }
+        }
+    }
+    let flattened = builder.build();
+
+    let old = PathMeasurements::from_path(&modified, 0.001);
+    let new = PathMeasurements::from_path(&flattened, 0.001);
+    let before = markers.position("curve", modified.iter()).unwrap();
+    markers.relocate(&old, &new);
+    let after = markers.position("curve", flattened.iter()).unwrap();
+    assert!((before - after).length() < 0.05);
+    let a = markers.position("a", flattened.iter()).unwrap();
+    assert!((a - point(2.0, 0.0)).length() < 0.01);
+}
@@ -1,6 +1,7 @@
 //! Perform cached measurements and split operations on a path.
 //!
 use crate::geom::{CubicBezierSegment, LineSegment, QuadraticBezierSegment, Segment};
+use crate::markers::PathLocation;
 use crate::math::*;
 use crate::path::{
     builder::PathBuilder, AttributeStore, Attributes, EndpointId, IdEvent, Path, PathSlice,
@@ -285,6 +286,70 @@ impl PathMeasurements {
         }
     }
 
+    /// Returns the event and the parameter on its segment at a given distance along the path.
+    ///
+    /// The distance is clamped to the beginning and end of the path.
+    /// Returns `None` if the path has no segment.
+    pub fn location(&self, dist: f32) -> Option<PathLocation> {
+        if self.edges.len() < 2 {
+            return None;
+        }
+
+        let dist = dist.max(0.0).min(self.length());
+        let idx = self
+            .edges
+            .partition_point(|edge| edge.distance < dist)
+            .max(1);
+        let prev = &self.edges[idx - 1];
+        let cur = &self.edges[idx];
+        let t_begin = if prev.index == cur.index { prev.t } else { 0.0 };
+        let ratio = if cur.distance > prev.distance {
+            (dist - prev.distance) / (cur.distance - prev.distance)
+        } else {
+            1.0
+        };
+
+        Some(PathLocation {
+            event: cur.index as u32,
+            t: t_begin + (cur.t - t_begin) * ratio,
+        })
+    }
+
+    /// Returns the distance along the path of a location.
+    ///
+    /// Returns `None` if the location refers to an event past the end of the path.
+    pub fn distance(&self, location: PathLocation) -> Option<f32> {
+        let event = location.event as usize;
+        if event >= self.events.len() {
+            return None;
+        }
+
+        let first = self.edges.partition_point(|edge| edge.index < event);
+        if first == 0 {
+            return Some(0.0);
+        }
+
+        let mut prev_t = 0.0;
+        let mut prev_distance = self.edges[first - 1].distance;
+        for edge in self.edges[first..]
+            .iter()
+            .take_while(|edge| edge.index == event)
+        {
+            if location.t <= edge.t {
+                let ratio = if edge.t > prev_t {
+                    (location.t - prev_t) / (edge.t - prev_t)
+                } else {
+                    1.0
+                };
+                return Some(prev_distance + (edge.distance - prev_distance) * ratio.max(0.0));
+            }
+            prev_t = edge.t;
+            prev_distance = edge.distance;
+        }
+
+        Some(prev_distance)
+    }
+
     /// Create an object that can perform fast sample queries on a path using the cached measurements.
     ///
     /// The returned sampler does not compute interpolated attributes.
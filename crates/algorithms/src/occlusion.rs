@@ -0,0 +1,241 @@
+//! Detect paths that are entirely hidden by opaque paths drawn on top of them.
+//!
+//! In deep scenes (for example SVG documents with many layers) a lot of paths can end up
+//! completely covered. Detecting them before tessellation avoids producing geometry that
+//! will never be visible.
+//!
+//! The test is conservative: a path is only reported as occluded if it is entirely contained
+//! in the fill of a single opaque path drawn after it. Paths covered by the union of several
+//! paths are not detected.
+
+use crate::geom::LineSegment;
+use crate::hit_test::hit_test_path;
+use crate::math::{Box2D, Point};
+use crate::path::iterator::PathIterator;
+use crate::path::{FillRule, PathEvent, PathSlice};
+use crate::rect::{to_axis_aligned_rectangle, ToRectangleOptions};
+
+/// A filled path in the painting order of a scene.
+#[derive(Copy, Clone, Debug)]
+pub struct OcclusionItem<'l> {
+    pub path: PathSlice<'l>,
+    pub fill_rule: FillRule,
+    /// Whether the path fully hides what is below it, which requires the path to be
+    /// rendered with an opaque color and no blending or filter.
+    pub opaque: bool,
+}
+
+impl<'l> OcclusionItem<'l> {
+    #[inline]
+    pub fn new(path: PathSlice<'l>, fill_rule: FillRule, opaque: bool) -> Self {
+        OcclusionItem {
+            path,
+            fill_rule,
+            opaque,
+        }
+    }
+}
+
+/// Returns, for each item, whether it is entirely covered by an opaque item that comes
+/// after it in `items`.
+///
+/// Items are expected in painting order, back to front. Curves are approximated with the
+/// provided tolerance, so a path that is covered with a margin smaller than the tolerance
+/// may be reported as occluded.
+pub fn find_occluded_paths(items: &[OcclusionItem], tolerance: f32) -> Vec<bool> {
+    let shapes: Vec<Shape> = items
+        .iter()
+        .map(|item| Shape::new(item, tolerance))
+        .collect();
+
+    let mut occluded = vec![false; items.len()];
+    for (i, shape) in shapes.iter().enumerate() {
+        for j in (i + 1)..items.len() {
+            if !items[j].opaque || !shapes[j].bounding_box.contains_box(&shape.bounding_box) {
+                continue;
+            }
+
+            if covers(&items[j], &shapes[j], &items[i], shape, tolerance) {
+                occluded[i] = true;
+                break;
+            }
+        }
+    }
+
+    occluded
+}
+
+struct Shape {
+    bounding_box: Box2D,
+    edges: Vec<LineSegment<f32>>,
+    // Set if the path is an axis-aligned rectangle.
+    rectangle: Option<Box2D>,
+}
+
+impl Shape {
+    fn new(item: &OcclusionItem, tolerance: f32) -> Self {
+        let mut edges = Vec::new();
+        for evt in item.path.iter().flattened(tolerance) {
+            match evt {
+                PathEvent::Line { from, to } => {
+                    edges.push(LineSegment { from, to });
+                }
+                // Sub-paths are implicitly closed when filling.
+                PathEvent::End { last, first, .. } if last != first => {
+                    edges.push(LineSegment {
+                        from: last,
+                        to: first,
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        let bounding_box = Box2D::from_points(edges.iter().map(|edge| edge.from));
+        let rectangle =
+            to_axis_aligned_rectangle(item.path.iter(), &ToRectangleOptions::fill(tolerance));
+
+        Shape {
+            bounding_box,
+            edges,
+            rectangle,
+        }
+    }
+
+    fn vertices(&self) -> impl Iterator<Item = Point> + '_ {
+        self.edges.iter().map(|edge| edge.from)
+    }
+}
+
+/// Whether the fill of `outer` entirely contains the fill of `inner`.
+fn covers(
+    outer_item: &OcclusionItem,
+    outer: &Shape,
+    inner_item: &OcclusionItem,
+    inner: &Shape,
+    tolerance: f32,
+) -> bool {
+    if let Some(rect) = &outer.rectangle {
+        return rect.contains_box(&inner.bounding_box);
+    }
+
+    // The boundary of the inner shape must be inside the outer shape...
+    let inside =
+        |p: Point| hit_test_path(&p, outer_item.path.iter(), outer_item.fill_rule, tolerance);
+    if !inner.vertices().all(inside) {
+        return false;
+    }
+
+    // ...without crossing its boundary...
+    for a in &inner.edges {
+        let a_rect = a.bounding_box();
+        for b in &outer.edges {
+            if a_rect.intersects(&b.bounding_box()) && a.intersects(b) {
+                return false;
+            }
+        }
+    }
+
+    // ...and the outer shape must not have holes within the inner shape.
+    let in_inner =
+        |p: Point| hit_test_path(&p, inner_item.path.iter(), inner_item.fill_rule, tolerance);
+    !outer
+        .vertices()
+        .filter(|p| inner.bounding_box.contains(*p))
+        .any(in_inner)
+}
+
+#[cfg(test)]
+use crate::path::Path;
+
+#[cfg(test)]
+fn square(builder: &mut crate::path::path::Builder, x: f32, y: f32, size: f32) {
+    use crate::math::point;
+    builder.begin(point(x, y));
+    builder.line_to(point(x + size, y));
+    builder.line_to(point(x + size, y + size));
+    builder.line_to(point(x, y + size));
+    builder.end(true);
+}
+
+#[cfg(test)]
+fn circle(center: Point, radius: f32) -> Path {
+    use crate::path::Winding;
+    let mut builder = Path::builder();
+    builder.add_circle(center, radius, Winding::Positive);
+    builder.build()
+}
+
+#[test]
+fn covered_by_rectangle() {
+    use crate::math::point;
+
+    let mut builder = Path::builder();
+    square(&mut builder, 0.0, 0.0, 100.0);
+    let background = builder.build();
+    let small = circle(point(50.0, 50.0), 10.0);
+    let cover = background.clone();
+
+    let items = [
+        OcclusionItem::new(background.as_slice(), FillRule::NonZero, true),
+        OcclusionItem::new(small.as_slice(), FillRule::NonZero, true),
+        OcclusionItem::new(cover.as_slice(), FillRule::NonZero, false),
+    ];
+    // The cover is not opaque.
+    assert_eq!(find_occluded_paths(&items, 0.1), vec![false, false, false]);
+
+    let items = [
+        OcclusionItem::new(background.as_slice(), FillRule::NonZero, true),
+        OcclusionItem::new(small.as_slice(), FillRule::NonZero, true),
+        OcclusionItem::new(cover.as_slice(), FillRule::NonZero, true),
+    ];
+    assert_eq!(find_occluded_paths(&items, 0.1), vec![true, true, false]);
+}
+
+#[test]
+fn covered_by_circle() {
+    use crate::math::point;
+
+    let big = circle(point(0.0, 0.0), 100.0);
+    let mut builder = Path::builder();
+    square(&mut builder, -10.0, -10.0, 20.0);
+    let inner = builder.build();
+    let mut builder = Path::builder();
+    square(&mut builder, 60.0, 60.0, 30.0);
+    let corner = builder.build();
+
+    let items = [
+        OcclusionItem::new(inner.as_slice(), FillRule::NonZero, true),
+        OcclusionItem::new(corner.as_slice(), FillRule::NonZero, true),
+        OcclusionItem::new(big.as_slice(), FillRule::NonZero, true),
+    ];
+
+    // The corner square sticks out of the circle.
+    assert_eq!(find_occluded_paths(&items, 0.1), vec![true, false, false]);
+}
+
+#[test]
+fn hole_in_cover() {
+    use crate::math::point;
+
+    // A diamond with a hole in the middle, on top of a square.
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, -100.0));
+    builder.line_to(point(100.0, 0.0));
+    builder.line_to(point(0.0, 100.0));
+    builder.line_to(point(-100.0, 0.0));
+    builder.end(true);
+    square(&mut builder, -5.0, -5.0, 10.0);
+    let cover = builder.build();
+
+    let mut builder = Path::builder();
+    square(&mut builder, -20.0, -20.0, 40.0);
+    let below = builder.build();
+
+    let items = [
+        OcclusionItem::new(below.as_slice(), FillRule::EvenOdd, true),
+        OcclusionItem::new(cover.as_slice(), FillRule::EvenOdd, true),
+    ];
+
+    assert_eq!(find_occluded_paths(&items, 0.1), vec![false, false]);
+}
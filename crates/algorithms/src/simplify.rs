@@ -0,0 +1,390 @@
+//! Simplify polylines and collections of polygons sharing boundaries.
+//!
+//! Simplifying each polygon of a map independently creates slivers: the two sides of a
+//! shared border are simplified differently and neighbors end up with gaps or overlaps.
+//! [`simplify_shared_polygons`](fn.simplify_shared_polygons.html) detects the shared
+//! boundaries and simplifies each of them once, so that neighbors stay in contact.
+//!
+//! # Example
+//!
+//! ```
+//! use lyon_algorithms::simplify::simplify_shared_polygons;
+//! use lyon_algorithms::math::point;
+//! use lyon_algorithms::path::Polygon;
+//!
+//! // Two squares sharing a slightly jagged border.
+//! let left = [
+//!     point(0.0, 0.0),
+//!     point(10.0, 0.0),
+//!     point(10.1, 5.0),
+//!     point(10.0, 10.0),
+//!     point(0.0, 10.0),
+//! ];
+//! let right = [
+//!     point(10.0, 0.0),
+//!     point(20.0, 0.0),
+//!     point(20.0, 10.0),
+//!     point(10.0, 10.0),
+//!     point(10.1, 5.0),
+//! ];
+//!
+//! let simplified = simplify_shared_polygons(
+//!     &[
+//!         Polygon { points: &left, closed: true },
+//!         Polygon { points: &right, closed: true },
+//!     ],
+//!     0.5,
+//! );
+//!
+//! // The jagged point is removed from both sides of the border.
+//! assert_eq!(simplified[0].len(), 4);
+//! assert_eq!(simplified[1].len(), 4);
+//! ```
+
+use crate::geom::LineSegment;
+use crate::math::Point;
+use crate::path::Polygon;
+
+use std::collections::HashMap;
+
+/// Simplifies a polyline with the Ramer-Douglas-Peucker algorithm and appends the result
+/// to `output`.
+///
+/// The first and last points are always kept. The simplified polyline stays within
+/// `tolerance` of the original one.
+pub fn simplify_polyline(points: &[Point], tolerance: f32, output: &mut Vec<Point>) {
+    if points.len() <= 2 {
+        output.extend_from_slice(points);
+        return;
+    }
+
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+
+    let sq_tolerance = tolerance * tolerance;
+    let mut stack = vec![(0, points.len() - 1)];
+    while let Some((first, last)) = stack.pop() {
+        if last - first < 2 {
+            continue;
+        }
+
+        let segment = LineSegment {
+            from: points[first],
+            to: points[last],
+        };
+        let mut max_sq_dist = 0.0;
+        let mut max_idx = first;
+        for (idx, p) in points.iter().enumerate().take(last).skip(first + 1) {
+            let sq_dist = if segment.from == segment.to {
+                (*p - segment.from).square_length()
+            } else {
+                segment.square_distance_to_point(*p)
+            };
+            if sq_dist > max_sq_dist {
+                max_sq_dist = sq_dist;
+                max_idx = idx;
+            }
+        }
+
+        if max_sq_dist > sq_tolerance {
+            keep[max_idx] = true;
+            stack.push((first, max_idx));
+            stack.push((max_idx, last));
+        }
+    }
+
+    output.extend(
+        points
+            .iter()
+            .zip(keep.iter())
+            .filter(|(_, keep)| **keep)
+            .map(|(p, _)| *p),
+    );
+}
+
+/// Simplifies a collection of polygons and polylines while preserving their shared
+/// boundaries.
+///
+/// Vertices are matched by position: wherever several polygons run through the same
+/// sequence of points, the shared chain is simplified once and used by all of them,
+/// so that neighbors stay free of gaps and overlaps. Junctions (points where more than
+/// two boundaries meet) and the endpoints of open polylines are always kept.
+///
+/// Returns the simplified points of each polygon, in the same order. As with the input,
+/// closed polygons don't repeat their first point at the end.
+///
+/// Only the shared boundaries are guaranteed to be consistent: boundaries that don't touch
+/// are simplified independently and may intersect if `tolerance` is large compared to the
+/// distance between them.
+pub fn simplify_shared_polygons(polygons: &[Polygon<Point>], tolerance: f32) -> Vec<Vec<Point>> {
+    let rings: Vec<Vec<Point>> = polygons
+        .iter()
+        .map(|polygon| remove_duplicates(polygon.points, polygon.closed))
+        .collect();
+
+    // Collect the neighbors of each position to find the junctions.
+    let mut neighbors: HashMap<Key, Vec<Key>> = HashMap::new();
+    let mut forced_junctions: Vec<Key> = Vec::new();
+    for (ring, polygon) in rings.iter().zip(polygons) {
+        let n = ring.len();
+        for i in 0..n {
+            let mut add = |a: Point, b: Point| {
+                let list = neighbors.entry(key(a)).or_default();
+                if !list.contains(&key(b)) {
+                    list.push(key(b));
+                }
+            };
+            if i + 1 < n {
+                add(ring[i], ring[i + 1]);
+                add(ring[i + 1], ring[i]);
+            } else if polygon.closed && n > 1 {
+                add(ring[i], ring[0]);
+                add(ring[0], ring[i]);
+            }
+        }
+
+        if !polygon.closed && n > 0 {
+            forced_junctions.push(key(ring[0]));
+            forced_junctions.push(key(ring[n - 1]));
+        }
+    }
+
+    let is_junction = |p: Point| {
+        let k = key(p);
+        neighbors.get(&k).map(|n| n.len() != 2).unwrap_or(true) || forced_junctions.contains(&k)
+    };
+
+    let mut cache: HashMap<(Key, Key), Vec<Point>> = HashMap::new();
+    let mut chain = Vec::new();
+    let mut result = Vec::with_capacity(rings.len());
+    for (ring, polygon) in rings.iter().zip(polygons) {
+        let n = ring.len();
+        let mut output = Vec::new();
+        if n < 3 {
+            output.extend_from_slice(ring);
+            result.push(output);
+            continue;
+        }
+
+        // Start at a junction if there is any, otherwise at an arbitrary but deterministic
+        // point so that identical rings are simplified the same way.
+        let start = if polygon.closed {
+            (0..n)
+                .find(|&i| is_junction(ring[i]))
+                .unwrap_or_else(|| (0..n).min_by_key(|&i| key(ring[i])).unwrap())
+        } else {
+            0
+        };
+
+        let num_points = if polygon.closed { n + 1 } else { n };
+        chain.clear();
+        for i in 0..num_points {
+            let p = ring[(start + i) % n];
+            chain.push(p);
+            let is_last = i == num_points - 1;
+            if chain.len() > 1 && (is_last || is_junction(p)) {
+                simplify_chain(&chain, tolerance, &mut cache, &mut output);
+                if !is_last {
+                    output.pop();
+                }
+                chain.clear();
+                chain.push(p);
+            }
+        }
+
+        if polygon.closed {
+            // The last point is the first one.
+            output.pop();
+        }
+
+        result.push(output);
+    }
+
+    result
+}
+
+type Key = (u32, u32);
+
+fn key(p: Point) -> Key {
+    // Adding zero turns negative zeros into positive ones so that they compare equal.
+    ((p.x + 0.0).to_bits(), (p.y + 0.0).to_bits())
+}
+
+fn remove_duplicates(points: &[Point], closed: bool) -> Vec<Point> {
+    let mut result: Vec<Point> = Vec::with_capacity(points.len());
+    for p in points {
+        if result.last().map(|last| key(*last)) != Some(key(*p)) {
+            result.push(*p);
+        }
+    }
+
+    if closed {
+        while result.len() > 1 && key(result[0]) == key(*result.last().unwrap()) {
+            result.pop();
+        }
+    }
+
+    result
+}
+
+/// Simplifies a chain of points between two junctions, including both of them.
+///
+/// The chain is simplified in a canonical direction so that the neighbors sharing it,
+/// which traverse it in opposite directions, get the same result.
+fn simplify_chain(
+    chain: &[Point],
+    tolerance: f32,
+    cache: &mut HashMap<(Key, Key), Vec<Point>>,
+    output: &mut Vec<Point>,
+) {
+    let n = chain.len();
+    let first = key(chain[0]);
+    let last = key(chain[n - 1]);
+    let reversed = if first == last {
+        // A loop: use the direction of the second point.
+        n > 2 && key(chain[1]) > key(chain[n - 2])
+    } else {
+        first > last
+    };
+
+    let canonical: Vec<Point> = if reversed {
+        chain.iter().rev().cloned().collect()
+    } else {
+        chain.to_vec()
+    };
+
+    // Since all interior points of a chain have exactly two neighbors, the first two points
+    // identify it.
+    let simplified = cache
+        .entry((key(canonical[0]), key(canonical[1])))
+        .or_insert_with(|| {
+            let mut simplified = Vec::with_capacity(canonical.len());
+            if first == last && n > 3 {
+                // Split loops at a deterministic point, otherwise they would collapse.
+                let split = (1..n - 1).max_by_key(|&i| key(canonical[i])).unwrap();
+                simplify_polyline(&canonical[..=split], tolerance, &mut simplified);
+                simplified.pop();
+                simplify_polyline(&canonical[split..], tolerance, &mut simplified);
+            } else {
+                simplify_polyline(&canonical, tolerance, &mut simplified);
+            }
+            simplified
+        });
+
+    if reversed {
+        output.extend(simplified.iter().rev());
+    } else {
+        output.extend_from_slice(simplified);
+    }
+}
+
+#[test]
+fn simplify_polyline_basic() {
+    use crate::math::point;
+
+    let points = [
+        point(0.0, 0.0),
+        point(1.0, 0.1),
+        point(2.0, -0.1),
+        point(3.0, 5.0),
+        point(4.0, 6.0),
+        point(5.0, 7.0),
+    ];
+
+    let mut output = Vec::new();
+    simplify_polyline(&points, 0.5, &mut output);
+    assert_eq!(
+        output,
+        vec![
+            point(0.0, 0.0),
+            point(2.0, -0.1),
+            point(3.0, 5.0),
+            point(5.0, 7.0)
+        ]
+    );
+
+    // Only the collinear point is removed.
+    output.clear();
+    simplify_polyline(&points, 0.0, &mut output);
+    assert_eq!(output.len(), points.len() - 1);
+    assert!(!output.contains(&point(4.0, 6.0)));
+}
+
+#[test]
+fn simplify_shared_border() {
+    use crate::math::point;
+
+    // A wiggly border between two regions, traversed in opposite directions.
+    let border: Vec<Point> = (0..=20)
+        .map(|i| {
+            let offset = if i == 0 || i == 20 {
+                0.0
+            } else if i % 2 == 0 {
+                0.2
+            } else {
+                -0.2
+            };
+            point(10.0 + offset, i as f32)
+        })
+        .collect();
+
+    let mut left = vec![point(0.0, 20.0), point(0.0, 0.0)];
+    left.extend(border.iter().cloned());
+    let mut right = vec![point(20.0, 0.0), point(20.0, 20.0)];
+    right.extend(border.iter().rev().cloned());
+    // A third polygon touching the others at a single point.
+    let top = [point(10.0, 20.0), point(15.0, 25.0), point(5.0, 25.0)];
+
+    let polygons = [
+        Polygon {
+            points: &left,
+            closed: true,
+        },
+        Polygon {
+            points: &right,
+            closed: true,
+        },
+        Polygon {
+            points: &top,
+            closed: true,
+        },
+    ];
+    let simplified = simplify_shared_polygons(&polygons, 0.5);
+
+    let border_of = |ring: &[Point]| -> Vec<Point> {
+        let mut points: Vec<Point> = ring
+            .iter()
+            .filter(|p| p.x > 5.0 && p.x < 15.0)
+            .cloned()
+            .collect();
+        points.sort_by(|a, b| a.y.partial_cmp(&b.y).unwrap());
+        points
+    };
+
+    let left_border = border_of(&simplified[0]);
+    let right_border = border_of(&simplified[1]);
+    assert_eq!(left_border, right_border);
+    assert!(left_border.len() < border.len());
+    assert_eq!(left_border[0], border[0]);
+    assert_eq!(*left_border.last().unwrap(), border[20]);
+
+    assert_eq!(simplified[2], top.to_vec());
+
+    // Rings without any junction keep enough points to not collapse.
+    let square = [
+        point(0.0, 0.0),
+        point(5.0, 0.0),
+        point(10.0, 0.0),
+        point(10.0, 10.0),
+        point(0.0, 10.0),
+    ];
+    let simplified = simplify_shared_polygons(
+        &[Polygon {
+            points: &square,
+            closed: true,
+        }],
+        0.1,
+    );
+    assert_eq!(simplified[0].len(), 4);
+}
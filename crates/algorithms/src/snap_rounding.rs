@@ -0,0 +1,414 @@
+//! Snap the vertices of a path to a grid without changing its topology.
+//!
+//! Rounding the endpoints of line segments to a grid (for example to the precision of
+//! `f32` or of integer coordinates) moves them independently, which can create new
+//! intersections or make a segment pass on the wrong side of a vertex. Algorithms that
+//! compute intersections, such as boolean operations, then produce inconsistent results.
+//!
+//! Snap rounding avoids this using "hot pixels": every grid cell that contains an endpoint
+//! or an intersection of the input segments is hot, and each segment is rerouted through
+//! the center of every hot pixel it crosses. In the output:
+//!
+//! - all endpoints are on the grid,
+//! - segments only intersect at their endpoints,
+//! - every vertex is within half a grid cell (in each direction) of the input.
+//!
+//! Curves are flattened before snapping.
+//!
+//! # Example
+//!
+//! ```
+//! use lyon_algorithms::math::point;
+//! use lyon_algorithms::path::Path;
+//! use lyon_algorithms::snap_rounding::snap_rounded;
+//!
+//! let mut builder = Path::builder();
+//! builder.begin(point(0.0, 0.0));
+//! builder.line_to(point(10.0, 1.0));
+//! builder.end(false);
+//! builder.begin(point(5.2, -3.0));
+//! builder.line_to(point(5.2, 3.0));
+//! builder.end(false);
+//! let path = builder.build();
+//!
+//! // Both segments now go through the grid point closest to their intersection.
+//! let snapped = snap_rounded(path.iter(), 1.0, 0.01);
+//! let vertices = snapped.iter().filter(|event| event.to().y == 1.0 && event.to().x == 5.0);
+//! assert_eq!(vertices.count(), 2);
+//! ```
+
+use crate::geom::LineSegment;
+use crate::math::{point, Box2D, Point};
+use crate::path::builder::PathBuilder;
+use crate::path::iterator::PathIterator;
+use crate::path::{Path, PathEvent, NO_ATTRIBUTES};
+
+/// Snap-rounds a path to a grid, writing the result into a path builder.
+///
+/// The grid has a point at each multiple of `grid_size` in both directions. Curves are
+/// flattened with the given tolerance. Segments with non-finite endpoints are ignored and
+/// sub-paths that collapse into a single point are removed.
+///
+/// Custom attributes are not preserved.
+pub fn build_snap_rounded<Iter>(
+    path: Iter,
+    grid_size: f32,
+    tolerance: f32,
+    output: &mut impl PathBuilder,
+) where
+    Iter: IntoIterator<Item = PathEvent>,
+{
+    debug_assert!(grid_size > 0.0);
+
+    // Flatten the path into a list of segments, remembering the sub-path boundaries.
+    let mut segments: Vec<LineSegment<f32>> = Vec::new();
+    let mut sub_paths: Vec<SubPath> = Vec::new();
+    for event in path.into_iter().flattened(tolerance) {
+        match event {
+            PathEvent::Begin { at } => {
+                sub_paths.push(SubPath {
+                    first: at,
+                    segments: segments.len()..segments.len(),
+                    closed: false,
+                });
+            }
+            // Segments with non-finite endpoints can't be snapped to the grid.
+            PathEvent::Line { from, to }
+                if from.to_vector().is_finite() && to.to_vector().is_finite() =>
+            {
+                segments.push(LineSegment { from, to });
+            }
+            PathEvent::End {
+                last, first, close, ..
+            } => {
+                if close
+                    && last != first
+                    && last.to_vector().is_finite()
+                    && first.to_vector().is_finite()
+                {
+                    segments.push(LineSegment {
+                        from: last,
+                        to: first,
+                    });
+                }
+                if let Some(sub_path) = sub_paths.last_mut() {
+                    sub_path.segments.end = segments.len();
+                    sub_path.closed = close;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let hot_pixels = find_hot_pixels(&segments, &sub_paths, grid_size);
+
+    let mut crossed = Vec::new();
+    let mut points: Vec<Cell> = Vec::new();
+    for sub_path in &sub_paths {
+        points.clear();
+        points.push(cell(sub_path.first, grid_size));
+        for segment in &segments[sub_path.segments.clone()] {
+            crossed.clear();
+            crossed_pixels(segment, &hot_pixels, grid_size, &mut crossed);
+            crossed.sort_by(|a: &(f32, f32, Cell), b| {
+                a.0.total_cmp(&b.0).then_with(|| a.1.total_cmp(&b.1))
+            });
+            for &(_, _, c) in &crossed {
+                if points.last() != Some(&c) {
+                    points.push(c);
+                }
+            }
+        }
+
+        if sub_path.closed && points.len() > 1 && points.first() == points.last() {
+            points.pop();
+        }
+        if points.len() < 2 {
+            continue;
+        }
+
+        output.begin(to_point(points[0], grid_size), NO_ATTRIBUTES);
+        for c in &points[1..] {
+            output.line_to(to_point(*c, grid_size), NO_ATTRIBUTES);
+        }
+        output.end(sub_path.closed);
+    }
+}
+
+/// Snap-rounds a path to a grid.
+///
+/// See [`build_snap_rounded`](fn.build_snap_rounded.html).
+pub fn snap_rounded<Iter>(path: Iter, grid_size: f32, tolerance: f32) -> Path
+where
+    Iter: IntoIterator<Item = PathEvent>,
+{
+    let mut builder = Path::builder();
+    build_snap_rounded(path, grid_size, tolerance, &mut builder);
+
+    builder.build()
+}
+
+struct SubPath {
+    first: Point,
+    segments: std::ops::Range<usize>,
+    closed: bool,
+}
+
+// Integer coordinates of a grid cell.
+pub(crate) type Cell = (i32, i32);
+
+pub(crate) fn cell(p: Point, grid_size: f32) -> Cell {
+    (
+        (p.x / grid_size).round() as i32,
+        (p.y / grid_size).round() as i32,
+    )
+}
+
+pub(crate) fn to_point(c: Cell, grid_size: f32) -> Point {
+    point(c.0 as f32 * grid_size, c.1 as f32 * grid_size)
+}
+
+// Returns the sorted and deduplicated hot pixels of a set of segments.
+fn find_hot_pixels(
+    segments: &[LineSegment<f32>],
+    sub_paths: &[SubPath],
+    grid_size: f32,
+) -> Vec<Cell> {
+    let mut hot_pixels: Vec<Cell> = Vec::with_capacity(segments.len() * 2);
+    for sub_path in sub_paths {
+        hot_pixels.push(cell(sub_path.first, grid_size));
+    }
+    for segment in segments {
+        hot_pixels.push(cell(segment.from, grid_size));
+        hot_pixels.push(cell(segment.to, grid_size));
+    }
+
+    // Find the intersections with a sweep along the x axis over the bounding boxes.
+    let mut order: Vec<usize> = (0..segments.len()).collect();
+    order.sort_by(|a, b| {
+        let a = segments[*a].from.x.min(segments[*a].to.x);
+        let b = segments[*b].from.x.min(segments[*b].to.x);
+        a.total_cmp(&b)
+    });
+    for (i, &a) in order.iter().enumerate() {
+        let sa = &segments[a];
+        let max_x = sa.from.x.max(sa.to.x);
+        let sa64 = LineSegment {
+            from: sa.from.to_f64(),
+            to: sa.to.to_f64(),
+        };
+        for &b in &order[i + 1..] {
+            let sb = &segments[b];
+            if sb.from.x.min(sb.to.x) > max_x {
+                break;
+            }
+            let sb64 = LineSegment {
+                from: sb.from.to_f64(),
+                to: sb.to.to_f64(),
+            };
+            // Shared endpoints are already hot.
+            if let Some(p) = sa64.intersection(&sb64) {
+                hot_pixels.push(cell(p.to_f32(), grid_size));
+            }
+        }
+    }
+
+    hot_pixels.sort_unstable();
+    hot_pixels.dedup();
+
+    hot_pixels
+}
+
+// Collects the hot pixels that a segment crosses with the range of the segment's parameter
+// inside each of them.
+fn crossed_pixels(
+    segment: &LineSegment<f32>,
+    hot_pixels: &[Cell],
+    grid_size: f32,
+    output: &mut Vec<(f32, f32, Cell)>,
+) {
+    let bounds = segment.bounding_box();
+    let min = cell(bounds.min, grid_size);
+    let max = cell(bounds.max, grid_size);
+    // Hot pixels are sorted by x then y, so only look at the columns overlapping the
+    // bounding box.
+    let start = hot_pixels.partition_point(|c| c.0 < min.0);
+    for &c in &hot_pixels[start..] {
+        if c.0 > max.0 {
+            break;
+        }
+        if c.1 < min.1 || c.1 > max.1 {
+            continue;
+        }
+        let center = to_point(c, grid_size);
+        let half = grid_size * 0.5;
+        let pixel = Box2D {
+            min: point(center.x - half, center.y - half),
+            max: point(center.x + half, center.y + half),
+        };
+        if let Some((t0, t1)) = clip(segment, &pixel) {
+            output.push((t0, t1, c));
+        }
+    }
+}
+
+// Liang-Barsky clipping of a segment against a box, returning the range of the segment's
+// parameter inside the box.
+fn clip(segment: &LineSegment<f32>, bounds: &Box2D) -> Option<(f32, f32)> {
+    let v = segment.to_vector();
+    let mut t0: f32 = 0.0;
+    let mut t1: f32 = 1.0;
+    for (p, q) in [
+        (-v.x, segment.from.x - bounds.min.x),
+        (v.x, bounds.max.x - segment.from.x),
+        (-v.y, segment.from.y - bounds.min.y),
+        (v.y, bounds.max.y - segment.from.y),
+    ] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+            continue;
+        }
+        let t = q / p;
+        if p < 0.0 {
+            t0 = t0.max(t);
+        } else {
+            t1 = t1.min(t);
+        }
+    }
+
+    if t0 > t1 {
+        return None;
+    }
+
+    Some((t0, t1))
+}
+
+#[test]
+fn hot_pixels() {
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 1.0));
+    builder.end(false);
+    builder.begin(point(5.2, -3.0));
+    builder.line_to(point(5.2, 3.0));
+    builder.end(false);
+    // Ends in a pixel that the first segment goes through.
+    builder.begin(point(2.9, 0.4));
+    builder.line_to(point(2.9, -2.0));
+    builder.end(false);
+    // Collapses into a single point.
+    builder.begin(point(7.1, 5.1));
+    builder.line_to(point(6.9, 4.9));
+    builder.line_to(point(7.2, 5.0));
+    builder.end(true);
+    let path = builder.build();
+
+    let snapped = snap_rounded(path.iter(), 1.0, 0.01);
+    let mut sub_paths = Vec::new();
+    let mut points = Vec::new();
+    for event in snapped.iter() {
+        match event {
+            PathEvent::Begin { at } => points.push(at),
+            PathEvent::Line { to, .. } => points.push(to),
+            PathEvent::End { .. } => sub_paths.push(std::mem::take(&mut points)),
+            _ => panic!(),
+        }
+    }
+
+    assert_eq!(
+        sub_paths,
+        [
+            vec![
+                point(0.0, 0.0),
+                point(3.0, 0.0),
+                point(5.0, 1.0),
+                point(10.0, 1.0)
+            ],
+            vec![point(5.0, -3.0), point(5.0, 1.0), point(5.0, 3.0)],
+            vec![point(3.0, 0.0), point(3.0, -2.0)],
+        ]
+    );
+
+    // A closed square that is not aligned with the grid.
+    let mut builder = Path::builder();
+    builder.begin(point(0.4, 0.4));
+    builder.line_to(point(4.4, 0.4));
+    builder.line_to(point(4.4, 4.4));
+    builder.line_to(point(0.4, 4.4));
+    builder.end(true);
+    let path = builder.build();
+    let snapped = snap_rounded(path.iter(), 2.0, 0.01);
+    let events: Vec<PathEvent> = snapped.iter().collect();
+    assert_eq!(events.len(), 5);
+    assert_eq!(
+        events[4],
+        PathEvent::End {
+            last: point(0.0, 4.0),
+            first: point(0.0, 0.0),
+            close: true
+        }
+    );
+}
+
+#[test]
+fn non_finite_segments() {
+    let nan = point(f32::NAN, 1.0);
+    let inf = point(4.0, f32::INFINITY);
+    let path = [
+        PathEvent::Begin {
+            at: point(0.0, 0.0),
+        },
+        PathEvent::Line {
+            from: point(0.0, 0.0),
+            to: nan,
+        },
+        PathEvent::Line {
+            from: nan,
+            to: point(4.0, 0.0),
+        },
+        PathEvent::Line {
+            from: point(4.0, 0.0),
+            to: inf,
+        },
+        PathEvent::End {
+            last: inf,
+            first: point(0.0, 0.0),
+            close: true,
+        },
+        PathEvent::Begin {
+            at: point(1.2, -1.0),
+        },
+        PathEvent::Line {
+            from: point(1.2, -1.0),
+            to: point(1.2, 3.0),
+        },
+        PathEvent::End {
+            last: point(1.2, 3.0),
+            first: point(1.2, -1.0),
+            close: false,
+        },
+    ];
+
+    // Doesn't panic, and the finite segments are still snapped.
+    let snapped = snap_rounded(path.iter().cloned(), 1.0, 0.01);
+    let events: Vec<PathEvent> = snapped.iter().collect();
+    assert_eq!(
+        &events[events.len() - 3..],
+        &[
+            PathEvent::Begin {
+                at: point(1.0, -1.0)
+            },
+            PathEvent::Line {
+                from: point(1.0, -1.0),
+                to: point(1.0, 3.0)
+            },
+            PathEvent::End {
+                last: point(1.0, 3.0),
+                first: point(1.0, -1.0),
+                close: false
+            },
+        ]
+    );
+}
@@ -0,0 +1,214 @@
+//! Compare the coverage of two tessellations.
+//!
+//! Changing the tolerance or simplifying a mesh changes its shape by amounts that are hard
+//! to reason about from the triangles alone. This module rasterizes triangles into a grid of
+//! per-pixel coverage values and compares two rasterizations, which gives a measure of
+//! the error as it would appear on screen.
+//!
+//! Each pixel is sampled on a regular 4x4 grid. Overlapping triangles don't accumulate
+//! coverage, and the winding order of the triangles doesn't matter.
+//!
+//! # Example
+//!
+//! ```
+//! use lyon_extra::coverage::{compare, triangles};
+//! use lyon_extra::math::{point, Box2D};
+//!
+//! let square = [point(0.0, 0.0), point(10.0, 0.0), point(10.0, 10.0), point(0.0, 10.0)];
+//! let a = triangles(&square, &[0u16, 1, 2, 0, 2, 3]);
+//! // Only the lower-right half of the square.
+//! let b = triangles(&square, &[0u16, 1, 2]);
+//!
+//! let area = Box2D::new(point(0.0, 0.0), point(10.0, 10.0));
+//! let diff = compare(&a, &b, &area, 10, 10);
+//! assert_eq!(diff.max_difference, 1.0);
+//! assert!((diff.mean_difference - 0.5).abs() < 0.05);
+//! ```
+
+use crate::reference::Triangle;
+use path::math::{point, Box2D, Point};
+
+const SAMPLES_PER_AXIS: u32 = 4;
+
+/// Per-pixel coverage values between 0 and 1, in row-major order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CoverageMap {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<f32>,
+}
+
+impl CoverageMap {
+    pub fn get(&self, x: u32, y: u32) -> f32 {
+        self.pixels[(y * self.width + x) as usize]
+    }
+}
+
+/// The result of [`compare`](fn.compare.html).
+#[derive(Clone, Debug, PartialEq)]
+pub struct CoverageDiff {
+    /// The absolute coverage difference of each pixel.
+    pub differences: CoverageMap,
+    /// The largest difference of a pixel, between 0 and 1.
+    pub max_difference: f32,
+    /// The average difference over all pixels.
+    pub mean_difference: f32,
+    /// The number of pixels with a non-zero difference.
+    pub differing_pixels: u32,
+}
+
+/// Collects the triangles of an indexed mesh, for example from the `VertexBuffers`
+/// of `lyon_tessellation`.
+pub fn triangles<Index: Copy + Into<u32>>(vertices: &[Point], indices: &[Index]) -> Vec<Triangle> {
+    indices
+        .chunks_exact(3)
+        .map(|t| {
+            let vertex = |i: Index| vertices[i.into() as usize];
+            [vertex(t[0]), vertex(t[1]), vertex(t[2])]
+        })
+        .collect()
+}
+
+/// Rasterizes triangles into a `width` x `height` grid of pixels covering `area`.
+pub fn rasterize(triangles: &[Triangle], area: &Box2D, width: u32, height: u32) -> CoverageMap {
+    let samples_x = width * SAMPLES_PER_AXIS;
+    let samples_y = height * SAMPLES_PER_AXIS;
+    let sample_w = area.width() / samples_x as f32;
+    let sample_h = area.height() / samples_y as f32;
+    let mut samples = vec![false; (samples_x * samples_y) as usize];
+
+    // Converts a position into a sample coordinate.
+    let to_sample = |v: f32, min: f32, step: f32, count: u32| -> u32 {
+        (((v - min) / step - 0.5).max(0.0) as u32).min(count)
+    };
+
+    for triangle in triangles {
+        let [a, b, c] = *triangle;
+        let min = a.min(b).min(c);
+        let max = a.max(b).max(c);
+        if max.x < area.min.x || max.y < area.min.y || min.x > area.max.x || min.y > area.max.y {
+            continue;
+        }
+
+        let x0 = to_sample(min.x, area.min.x, sample_w, samples_x);
+        let x1 = to_sample(max.x, area.min.x, sample_w, samples_x) + 1;
+        let y0 = to_sample(min.y, area.min.y, sample_h, samples_y);
+        let y1 = to_sample(max.y, area.min.y, sample_h, samples_y) + 1;
+        for y in y0..y1.min(samples_y) {
+            for x in x0..x1.min(samples_x) {
+                let p = point(
+                    area.min.x + (x as f32 + 0.5) * sample_w,
+                    area.min.y + (y as f32 + 0.5) * sample_h,
+                );
+                if contains(triangle, p) {
+                    samples[(y * samples_x + x) as usize] = true;
+                }
+            }
+        }
+    }
+
+    let samples_per_pixel = (SAMPLES_PER_AXIS * SAMPLES_PER_AXIS) as f32;
+    let mut pixels = vec![0.0; (width * height) as usize];
+    for y in 0..samples_y {
+        for x in 0..samples_x {
+            if samples[(y * samples_x + x) as usize] {
+                let pixel = (y / SAMPLES_PER_AXIS) * width + x / SAMPLES_PER_AXIS;
+                pixels[pixel as usize] += 1.0 / samples_per_pixel;
+            }
+        }
+    }
+
+    CoverageMap {
+        width,
+        height,
+        pixels,
+    }
+}
+
+/// Rasterizes two sets of triangles over the same area and compares their coverage.
+pub fn compare(
+    a: &[Triangle],
+    b: &[Triangle],
+    area: &Box2D,
+    width: u32,
+    height: u32,
+) -> CoverageDiff {
+    let a = rasterize(a, area, width, height);
+    let b = rasterize(b, area, width, height);
+
+    let pixels: Vec<f32> = a
+        .pixels
+        .iter()
+        .zip(b.pixels.iter())
+        .map(|(a, b)| (a - b).abs())
+        .collect();
+
+    let max_difference = pixels.iter().cloned().fold(0.0, f32::max);
+    let mean_difference = if pixels.is_empty() {
+        0.0
+    } else {
+        pixels.iter().sum::<f32>() / pixels.len() as f32
+    };
+    let differing_pixels = pixels.iter().filter(|d| **d > 0.0).count() as u32;
+
+    CoverageDiff {
+        differences: CoverageMap {
+            width,
+            height,
+            pixels,
+        },
+        max_difference,
+        mean_difference,
+        differing_pixels,
+    }
+}
+
+// Points on the edges are contained, so that samples on the edges shared by two triangles
+// are not missed.
+fn contains(triangle: &Triangle, p: Point) -> bool {
+    let [a, b, c] = *triangle;
+    let s1 = (b - a).cross(p - a);
+    let s2 = (c - b).cross(p - b);
+    let s3 = (a - c).cross(p - c);
+
+    (s1 >= 0.0 && s2 >= 0.0 && s3 >= 0.0) || (s1 <= 0.0 && s2 <= 0.0 && s3 <= 0.0)
+}
+
+#[test]
+fn coverage_diff() {
+    use crate::reference;
+    use path::{FillRule, Path, Winding};
+
+    let area = Box2D::new(point(-12.0, -12.0), point(12.0, 12.0));
+
+    let mut builder = Path::builder();
+    builder.add_circle(point(0.0, 0.0), 10.0, Winding::Positive);
+    let circle = builder.build();
+
+    let fine = reference::fill(circle.iter(), 0.001, FillRule::NonZero);
+    let coarse = reference::fill(circle.iter(), 1.0, FillRule::NonZero);
+
+    let map = rasterize(&fine, &area, 24, 24);
+    assert_eq!(map.get(12, 12), 1.0);
+    assert_eq!(map.get(0, 0), 0.0);
+    let total: f32 = map.pixels.iter().sum();
+    let expected = std::f32::consts::PI * 100.0;
+    assert!((total - expected).abs() < expected * 0.02);
+
+    // The same geometry split differently has the same coverage.
+    let self_diff = compare(
+        &fine,
+        &reference::fill(circle.iter(), 0.001, FillRule::EvenOdd),
+        &area,
+        24,
+        24,
+    );
+    assert_eq!(self_diff.differing_pixels, 0);
+
+    // A coarser tolerance only affects pixels near the edge.
+    let diff = compare(&fine, &coarse, &area, 24, 24);
+    assert!(diff.differing_pixels > 0);
+    assert!(diff.max_difference < 1.0);
+    assert_eq!(diff.differences.get(12, 12), 0.0);
+    assert!(diff.mean_difference < compare(&fine, &[], &area, 24, 24).mean_difference);
+}
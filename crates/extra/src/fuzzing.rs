@@ -0,0 +1,420 @@
+//! Generation of randomized paths for property testing.
+//!
+//! Uniformly random points rarely exercise the difficult cases of geometric algorithms.
+//! The [`PathGenerator`](struct.PathGenerator.html) produces random paths with a
+//! structure chosen to stress them (see [`PathKind`](enum.PathKind.html)): self
+//! intersections, collinear and coincident points, very short edges, huge coordinates,
+//! and so on.
+//!
+//! The generator is deterministic: a given seed always produces the same sequence of paths,
+//! so failures found with a seed can be reproduced.
+//!
+//! # Example
+//!
+//! ```
+//! use lyon_extra::fuzzing::{PathGenerator, PathKind};
+//!
+//! let mut generator = PathGenerator::new(42);
+//! for _ in 0..10 {
+//!     let path = generator.next_path();
+//!     // Run the algorithm to test on `path`.
+//! }
+//!
+//! let star = generator.path(PathKind::Star);
+//! assert!(star.iter().count() > 0);
+//!
+//! // Same seed, same paths.
+//! let a = PathGenerator::new(7).path(PathKind::Spiral);
+//! let b = PathGenerator::new(7).path(PathKind::Spiral);
+//! assert!(a.iter().eq(b.iter()));
+//! ```
+
+use path::math::{point, vector, Point};
+use path::Path;
+
+use std::f32::consts::PI;
+
+/// A small seedable pseudo-random number generator (SplitMix64).
+///
+/// It is not suitable for cryptographic purposes.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a value between 0 (inclusive) and 1 (exclusive).
+    pub fn unit(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Returns a value between `min` (inclusive) and `max` (exclusive).
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.unit()
+    }
+
+    /// Returns an integer between `min` and `max` (both inclusive).
+    pub fn int(&mut self, min: u32, max: u32) -> u32 {
+        debug_assert!(min <= max);
+        min + (self.next_u64() % (max - min + 1) as u64) as u32
+    }
+
+    /// Returns true with probability `p`.
+    pub fn chance(&mut self, p: f32) -> bool {
+        self.unit() < p
+    }
+
+    /// Returns a point in the square of half-size `extent` centered on the origin.
+    pub fn point(&mut self, extent: f32) -> Point {
+        point(self.range(-extent, extent), self.range(-extent, extent))
+    }
+
+    /// Picks an element of a slice.
+    pub fn pick<'l, T>(&mut self, items: &'l [T]) -> &'l T {
+        &items[self.int(0, items.len() as u32 - 1) as usize]
+    }
+}
+
+/// The kinds of paths produced by the `PathGenerator`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PathKind {
+    /// Sub-paths made of random line and curve segments.
+    Random,
+    /// Spirals, which produce many nested and nearly parallel edges. Some of them are
+    /// closed, which makes them self-intersect.
+    Spiral,
+    /// Regular and self-intersecting star polygons, with many edges meeting near the
+    /// center.
+    Star,
+    /// Polygons with collinear points, very short edges, slivers and nearly coincident
+    /// points.
+    NearDegenerate,
+    /// Sub-paths that share points or edges, repeated points and duplicated sub-paths.
+    CoincidentPoints,
+    /// Coordinates with very large magnitudes, combined with small details.
+    HugeCoordinates,
+}
+
+impl PathKind {
+    pub const ALL: [PathKind; 6] = [
+        PathKind::Random,
+        PathKind::Spiral,
+        PathKind::Star,
+        PathKind::NearDegenerate,
+        PathKind::CoincidentPoints,
+        PathKind::HugeCoordinates,
+    ];
+}
+
+/// Generates randomized but structured paths.
+///
+/// See the [module documentation](index.html).
+#[derive(Clone, Debug)]
+pub struct PathGenerator {
+    rng: Rng,
+    /// Half-size of the area the paths are generated in, except for
+    /// `PathKind::HugeCoordinates`.
+    ///
+    /// Default value: `100.0`.
+    pub extent: f32,
+}
+
+impl PathGenerator {
+    pub fn new(seed: u64) -> Self {
+        PathGenerator {
+            rng: Rng::new(seed),
+            extent: 100.0,
+        }
+    }
+
+    /// The random number generator, to generate other parameters of the test
+    /// reproducibly.
+    pub fn rng(&mut self) -> &mut Rng {
+        &mut self.rng
+    }
+
+    /// Generates a path of a random kind.
+    pub fn next_path(&mut self) -> Path {
+        let kind = *self.rng.pick(&PathKind::ALL);
+        self.path(kind)
+    }
+
+    /// Generates a path of the given kind.
+    pub fn path(&mut self, kind: PathKind) -> Path {
+        match kind {
+            PathKind::Random => self.random(),
+            PathKind::Spiral => self.spiral(),
+            PathKind::Star => self.star(),
+            PathKind::NearDegenerate => self.near_degenerate(),
+            PathKind::CoincidentPoints => self.coincident_points(),
+            PathKind::HugeCoordinates => self.huge_coordinates(),
+        }
+    }
+
+    fn random(&mut self) -> Path {
+        let extent = self.extent;
+        let rng = &mut self.rng;
+        let mut builder = Path::builder();
+        for _ in 0..rng.int(1, 3) {
+            builder.begin(rng.point(extent));
+            for _ in 0..rng.int(1, 12) {
+                match rng.int(0, 2) {
+                    0 => {
+                        builder.line_to(rng.point(extent));
+                    }
+                    1 => {
+                        builder.quadratic_bezier_to(rng.point(extent), rng.point(extent));
+                    }
+                    _ => {
+                        builder.cubic_bezier_to(
+                            rng.point(extent),
+                            rng.point(extent),
+                            rng.point(extent),
+                        );
+                    }
+                }
+            }
+            builder.end(rng.chance(0.7));
+        }
+
+        builder.build()
+    }
+
+    fn spiral(&mut self) -> Path {
+        let extent = self.extent;
+        let rng = &mut self.rng;
+        let center = rng.point(extent * 0.5);
+        let turns = rng.range(1.0, 8.0);
+        let steps_per_turn = rng.int(6, 40) as f32;
+        let max_radius = rng.range(extent * 0.1, extent * 0.5);
+        let curved = rng.chance(0.5);
+        let num_steps = (turns * steps_per_turn) as u32;
+        let position = |i: u32| {
+            let t = i as f32 / num_steps as f32;
+            let angle = t * turns * 2.0 * PI;
+            center + vector(angle.cos(), angle.sin()) * (t * max_radius)
+        };
+
+        let mut builder = Path::builder();
+        builder.begin(position(0));
+        for i in 1..=num_steps {
+            if curved {
+                // Overshoot the control point to get a rounder spiral.
+                let mid = position(i - 1).lerp(position(i), 0.5);
+                let ctrl = center + (mid - center) * 1.1;
+                builder.quadratic_bezier_to(ctrl, position(i));
+            } else {
+                builder.line_to(position(i));
+            }
+        }
+        builder.end(rng.chance(0.5));
+
+        builder.build()
+    }
+
+    fn star(&mut self) -> Path {
+        let extent = self.extent;
+        let rng = &mut self.rng;
+        let center = rng.point(extent * 0.5);
+        let num_points = rng.int(3, 40);
+        let outer = rng.range(extent * 0.1, extent * 0.5);
+        let angle_offset = rng.range(0.0, 2.0 * PI);
+        // The point at `fraction` of a turn around the center.
+        let at = |fraction: f32, radius: f32| {
+            let angle = angle_offset + fraction * 2.0 * PI;
+            center + vector(angle.cos(), angle.sin()) * radius
+        };
+
+        let mut builder = Path::builder();
+        if rng.chance(0.5) {
+            // A star with alternating inner and outer points. The inner radius can be
+            // very small.
+            let inner = outer * rng.unit() * rng.unit();
+            let n = num_points * 2;
+            builder.begin(at(0.0, outer));
+            for i in 1..n {
+                let radius = if i % 2 == 0 { outer } else { inner };
+                builder.line_to(at(i as f32 / n as f32, radius));
+            }
+        } else {
+            // A star polygon connecting every `step`-th point of a regular polygon.
+            let step = rng.int(1, (num_points - 1) / 2 + 1);
+            builder.begin(at(0.0, outer));
+            for i in 1..num_points {
+                builder.line_to(at((i * step) as f32 / num_points as f32, outer));
+            }
+        }
+        builder.close();
+
+        builder.build()
+    }
+
+    fn near_degenerate(&mut self) -> Path {
+        let extent = self.extent;
+        let rng = &mut self.rng;
+        let tiny = *rng.pick(&[0.0, 1e-7, 1e-5, 1e-3]);
+        let mut builder = Path::builder();
+        for _ in 0..rng.int(1, 4) {
+            let mut p = rng.point(extent);
+            builder.begin(p);
+            for _ in 0..rng.int(2, 10) {
+                p = match rng.int(0, 4) {
+                    // A point collinear with a random direction, and eventually with the
+                    // previous edge if the next point is a sliver.
+                    0 => {
+                        let direction = p - rng.point(extent);
+                        p + direction * rng.range(0.0, 0.2)
+                    }
+                    // A very short edge.
+                    1 => p + vector(rng.range(-tiny, tiny), rng.range(-tiny, tiny)),
+                    // A horizontal or vertical edge.
+                    2 => {
+                        if rng.chance(0.5) {
+                            point(rng.range(-extent, extent), p.y)
+                        } else {
+                            point(p.x, rng.range(-extent, extent))
+                        }
+                    }
+                    // A sliver going back to nearly the same position.
+                    3 => {
+                        let far = rng.point(extent);
+                        builder.line_to(far);
+                        far + (p - far) * (1.0 - tiny)
+                    }
+                    _ => rng.point(extent),
+                };
+                builder.line_to(p);
+            }
+            builder.end(rng.chance(0.8));
+        }
+
+        builder.build()
+    }
+
+    fn coincident_points(&mut self) -> Path {
+        let extent = self.extent;
+        let rng = &mut self.rng;
+        // A small pool of points shared by all sub-paths, snapped to a grid so that
+        // exact coincidences and collinearities are likely.
+        let grid = extent / 4.0;
+        let pool: Vec<Point> = (0..rng.int(3, 8))
+            .map(|_| {
+                let p = rng.point(extent);
+                point((p.x / grid).round() * grid, (p.y / grid).round() * grid)
+            })
+            .collect();
+
+        let mut sub_paths: Vec<Vec<Point>> = Vec::new();
+        for _ in 0..rng.int(1, 5) {
+            if !sub_paths.is_empty() && rng.chance(0.2) {
+                // Duplicate a sub-path, possibly reversed.
+                let mut sub_path = rng.pick(&sub_paths).clone();
+                if rng.chance(0.5) {
+                    sub_path.reverse();
+                }
+                sub_paths.push(sub_path);
+                continue;
+            }
+
+            let mut sub_path = Vec::new();
+            for _ in 0..rng.int(2, 8) {
+                let p = *rng.pick(&pool);
+                sub_path.push(p);
+                if rng.chance(0.1) {
+                    // Repeated point.
+                    sub_path.push(p);
+                }
+            }
+            sub_paths.push(sub_path);
+        }
+
+        let mut builder = Path::builder();
+        for sub_path in &sub_paths {
+            builder.begin(sub_path[0]);
+            for p in &sub_path[1..] {
+                builder.line_to(*p);
+            }
+            builder.close();
+        }
+
+        builder.build()
+    }
+
+    fn huge_coordinates(&mut self) -> Path {
+        let rng = &mut self.rng;
+        let magnitude = *rng.pick(&[1e5, 1e7, 1e9, 1e12]);
+        let offset = rng.point(magnitude);
+        let mut builder = Path::builder();
+        for _ in 0..rng.int(1, 3) {
+            // Mix large and small scale features around a far away offset.
+            let scale = if rng.chance(0.5) { magnitude } else { 1.0 };
+            builder.begin(offset + rng.point(scale).to_vector());
+            for _ in 0..rng.int(2, 8) {
+                let to = offset + rng.point(scale).to_vector();
+                if rng.chance(0.3) {
+                    let ctrl = offset + rng.point(scale).to_vector();
+                    builder.quadratic_bezier_to(ctrl, to);
+                } else {
+                    builder.line_to(to);
+                }
+            }
+            builder.end(rng.chance(0.8));
+        }
+
+        builder.build()
+    }
+}
+
+#[test]
+fn generated_paths() {
+    use path::PathEvent;
+
+    for seed in 0..50 {
+        let mut generator = PathGenerator::new(seed);
+        for kind in PathKind::ALL {
+            let path = generator.path(kind);
+            let mut sub_paths = 0;
+            for event in path.iter() {
+                match event {
+                    PathEvent::Begin { at } => {
+                        assert!(at.x.is_finite() && at.y.is_finite(), "{:?}", kind);
+                        sub_paths += 1;
+                    }
+                    PathEvent::End { last, .. } => {
+                        assert!(last.x.is_finite() && last.y.is_finite(), "{:?}", kind);
+                    }
+                    _ => {}
+                }
+            }
+            assert!(sub_paths > 0, "{:?}", kind);
+        }
+    }
+
+    // Generators are deterministic.
+    let mut a = PathGenerator::new(123);
+    let mut b = PathGenerator::new(123);
+    for _ in 0..20 {
+        assert!(a.next_path().iter().eq(b.next_path().iter()));
+    }
+    let mut c = PathGenerator::new(124);
+    assert!(!a.next_path().iter().eq(c.next_path().iter()));
+
+    let mut rng = Rng::new(0);
+    for _ in 0..1000 {
+        let v = rng.unit();
+        assert!((0.0..1.0).contains(&v));
+        assert!(rng.int(3, 5) >= 3 && rng.int(3, 5) <= 5);
+    }
+}
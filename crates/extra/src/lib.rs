@@ -3,11 +3,14 @@
 #![allow(dead_code)]
 #![allow(unused_variables)]
 
-extern crate lyon_path as path;
+pub extern crate lyon_path as path;
 
 pub use path::geom::euclid;
 pub use path::math;
 
+pub mod coverage;
 pub mod debugging;
+pub mod fuzzing;
 pub mod parser;
+pub mod reference;
 pub mod rust_logo;
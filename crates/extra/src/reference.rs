@@ -0,0 +1,337 @@
+//! Slow but simple reference tessellators for differential testing.
+//!
+//! The tessellators of `lyon_tessellation` are optimized and handle many special cases,
+//! which makes them hard to validate by inspection. The functions in this module produce
+//! triangles with straightforward algorithms whose correctness is easy to reason about,
+//! at the cost of performance. Fuzzers and tests can compare the output of the optimized
+//! tessellators against them.
+//!
+//! - [`fill`](fn.fill.html) flattens the path and decomposes the plane into vertical
+//!   slabs at every vertex and edge intersection. Edges don't cross within a slab, so
+//!   the filled regions of each slab are trapezoids between consecutive edges.
+//! - [`stroke`](fn.stroke.html) generates a quad for each flattened segment. It doesn't
+//!   produce joins or caps, so its coverage is contained in the coverage of a stroke
+//!   with the same line width and any line join.
+//! - [`winding_number`](fn.winding_number.html) and [`coverage`](fn.coverage.html)
+//!   sample the expected and the actual coverage at a given point.
+//!
+//! The triangles are returned as triples of points rather than indexed meshes.
+//!
+//! # Example
+//!
+//! ```
+//! use lyon_extra::reference;
+//! use lyon_extra::math::point;
+//! use lyon_extra::path::{FillRule, Path};
+//!
+//! let mut builder = Path::builder();
+//! builder.begin(point(0.0, 0.0));
+//! builder.line_to(point(10.0, 0.0));
+//! builder.line_to(point(10.0, 10.0));
+//! builder.line_to(point(0.0, 10.0));
+//! builder.close();
+//! let path = builder.build();
+//!
+//! let triangles = reference::fill(path.iter(), 0.1, FillRule::NonZero);
+//! assert!((reference::area(&triangles) - 100.0).abs() < 0.001);
+//!
+//! // Compare the coverage at a point with the winding number of the path.
+//! let p = point(5.0, 3.0);
+//! let winding = reference::winding_number(path.iter(), p, 0.1);
+//! assert_eq!(reference::coverage(&triangles, p), 1);
+//! assert!(FillRule::NonZero.is_in(winding as i16));
+//! ```
+
+use path::iterator::PathIterator;
+use path::math::{point, Point};
+use path::{FillRule, PathEvent};
+
+/// A triangle, with its vertices in positive winding order.
+pub type Triangle = [Point; 3];
+
+/// Fills a path with a slab decomposition.
+///
+/// Open sub-paths are implicitly closed. The triangles have a positive winding order.
+///
+/// The complexity is quadratic in the number of edges after flattening.
+pub fn fill(
+    path: impl IntoIterator<Item = PathEvent>,
+    tolerance: f32,
+    fill_rule: FillRule,
+) -> Vec<Triangle> {
+    let edges = flattened_edges(path, tolerance, true);
+
+    let mut xs: Vec<f64> = Vec::with_capacity(edges.len() * 2);
+    for (i, e1) in edges.iter().enumerate() {
+        xs.push(e1.from.0);
+        xs.push(e1.to.0);
+        for e2 in &edges[i + 1..] {
+            if let Some(x) = intersection_x(e1, e2) {
+                xs.push(x);
+            }
+        }
+    }
+    xs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    xs.dedup();
+
+    let mut triangles = Vec::new();
+    let mut active: Vec<(f64, f64, f64, i32)> = Vec::new();
+    for slab in xs.windows(2) {
+        let (x0, x1) = (slab[0], slab[1]);
+        let mid = (x0 + x1) * 0.5;
+
+        active.clear();
+        for edge in &edges {
+            let (min, max, winding) = if edge.from.0 < edge.to.0 {
+                (edge.from.0, edge.to.0, 1)
+            } else {
+                (edge.to.0, edge.from.0, -1)
+            };
+            if min <= x0 && max >= x1 {
+                active.push((edge.y_at(mid), edge.y_at(x0), edge.y_at(x1), winding));
+            }
+        }
+        active.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+        let mut winding = 0;
+        for pair in active.windows(2) {
+            let (below, above) = (pair[0], pair[1]);
+            winding += below.3;
+            if fill_rule.is_out(winding as i16) {
+                continue;
+            }
+
+            let a = point(x0 as f32, below.1 as f32);
+            let b = point(x1 as f32, below.2 as f32);
+            let c = point(x1 as f32, above.2 as f32);
+            let d = point(x0 as f32, above.1 as f32);
+            if a != d {
+                triangles.push([a, b, d]);
+            }
+            if b != c {
+                triangles.push([b, c, d]);
+            }
+        }
+    }
+
+    triangles
+}
+
+/// Strokes a path with one quad per flattened segment.
+///
+/// There are no joins or caps. The triangles have a positive winding order.
+pub fn stroke(
+    path: impl IntoIterator<Item = PathEvent>,
+    tolerance: f32,
+    line_width: f32,
+) -> Vec<Triangle> {
+    let half_width = line_width as f64 * 0.5;
+    let mut triangles = Vec::new();
+    for edge in flattened_edges(path, tolerance, false) {
+        let (dx, dy) = (edge.to.0 - edge.from.0, edge.to.1 - edge.from.1);
+        let length = (dx * dx + dy * dy).sqrt();
+        if length == 0.0 {
+            continue;
+        }
+        let (nx, ny) = (-dy / length * half_width, dx / length * half_width);
+
+        let p = |(x, y): (f64, f64)| point(x as f32, y as f32);
+        let a = p((edge.from.0 - nx, edge.from.1 - ny));
+        let b = p((edge.to.0 - nx, edge.to.1 - ny));
+        let c = p((edge.to.0 + nx, edge.to.1 + ny));
+        let d = p((edge.from.0 + nx, edge.from.1 + ny));
+        triangles.push([a, b, c]);
+        triangles.push([a, c, d]);
+    }
+
+    triangles
+}
+
+/// Computes the winding number of a path at a given point.
+///
+/// Open sub-paths are implicitly closed. Edges oriented in the positive direction around
+/// the point count positively.
+pub fn winding_number(
+    path: impl IntoIterator<Item = PathEvent>,
+    position: Point,
+    tolerance: f32,
+) -> i32 {
+    let (px, py) = (position.x as f64, position.y as f64);
+    let mut winding = 0;
+    for edge in flattened_edges(path, tolerance, true) {
+        let (from, to) = (edge.from, edge.to);
+        let side = (to.0 - from.0) * (py - from.1) - (to.1 - from.1) * (px - from.0);
+        if from.1 <= py && to.1 > py && side > 0.0 {
+            winding += 1;
+        } else if to.1 <= py && from.1 > py && side < 0.0 {
+            winding -= 1;
+        }
+    }
+
+    winding
+}
+
+/// Returns the number of triangles that contain a point, in either winding order.
+///
+/// Points on the edges of a triangle are not considered to be contained.
+pub fn coverage(triangles: &[Triangle], position: Point) -> u32 {
+    let (px, py) = (position.x as f64, position.y as f64);
+    let side = |a: Point, b: Point| {
+        (b.x as f64 - a.x as f64) * (py - a.y as f64)
+            - (b.y as f64 - a.y as f64) * (px - a.x as f64)
+    };
+
+    let mut count = 0;
+    for &[a, b, c] in triangles {
+        let (s1, s2, s3) = (side(a, b), side(b, c), side(c, a));
+        if (s1 > 0.0 && s2 > 0.0 && s3 > 0.0) || (s1 < 0.0 && s2 < 0.0 && s3 < 0.0) {
+            count += 1;
+        }
+    }
+
+    count
+}
+
+/// Returns the sum of the areas of the triangles, regardless of their winding order.
+pub fn area(triangles: &[Triangle]) -> f32 {
+    triangles
+        .iter()
+        .map(|&[a, b, c]| ((b - a).cross(c - a) as f64).abs() * 0.5)
+        .sum::<f64>() as f32
+}
+
+/// A flattened edge in double precision.
+struct Edge {
+    from: (f64, f64),
+    to: (f64, f64),
+}
+
+impl Edge {
+    fn y_at(&self, x: f64) -> f64 {
+        let dx = self.to.0 - self.from.0;
+        if dx == 0.0 {
+            return self.from.1;
+        }
+        let t = ((x - self.from.0) / dx).clamp(0.0, 1.0);
+        self.from.1 + (self.to.1 - self.from.1) * t
+    }
+}
+
+fn flattened_edges(
+    path: impl IntoIterator<Item = PathEvent>,
+    tolerance: f32,
+    close: bool,
+) -> Vec<Edge> {
+    let mut edges = Vec::new();
+    let mut push = |from: Point, to: Point| {
+        if from != to {
+            edges.push(Edge {
+                from: (from.x as f64, from.y as f64),
+                to: (to.x as f64, to.y as f64),
+            });
+        }
+    };
+
+    for event in path.into_iter().flattened(tolerance) {
+        match event {
+            PathEvent::Line { from, to } => push(from, to),
+            PathEvent::End {
+                last,
+                first,
+                close: closed,
+            } if close || closed => {
+                push(last, first);
+            }
+            _ => {}
+        }
+    }
+
+    edges
+}
+
+/// Returns the x coordinate of the intersection of two edges, if they cross.
+fn intersection_x(e1: &Edge, e2: &Edge) -> Option<f64> {
+    let (ax, ay) = (e1.to.0 - e1.from.0, e1.to.1 - e1.from.1);
+    let (bx, by) = (e2.to.0 - e2.from.0, e2.to.1 - e2.from.1);
+    let denom = ax * by - ay * bx;
+    if denom == 0.0 {
+        return None;
+    }
+
+    let (cx, cy) = (e2.from.0 - e1.from.0, e2.from.1 - e1.from.1);
+    let t = (cx * by - cy * bx) / denom;
+    let u = (cx * ay - cy * ax) / denom;
+    if (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u) {
+        return Some(e1.from.0 + ax * t);
+    }
+
+    None
+}
+
+#[test]
+fn reference_fill() {
+    use path::Path;
+
+    // A self-intersecting pentagram: the center has a winding number of 2.
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 10.0));
+    for i in 1..5 {
+        let angle = std::f32::consts::PI * 0.5 + i as f32 * std::f32::consts::PI * 0.8;
+        builder.line_to(point(angle.cos() * 10.0, angle.sin() * 10.0));
+    }
+    builder.close();
+    let star = builder.build();
+
+    // Slightly off the vertical line through the top vertex, which is a slab boundary.
+    let center = point(0.1, 0.5);
+    assert_eq!(winding_number(star.iter(), center, 0.1).abs(), 2);
+    assert_eq!(winding_number(star.iter(), point(0.0, 20.0), 0.1), 0);
+
+    let non_zero = fill(star.iter(), 0.1, FillRule::NonZero);
+    let even_odd = fill(star.iter(), 0.1, FillRule::EvenOdd);
+    assert_eq!(coverage(&non_zero, center), 1);
+    assert_eq!(coverage(&even_odd, center), 0);
+    assert!(area(&non_zero) > area(&even_odd));
+
+    // The triangles don't overlap and have a positive winding order.
+    for triangles in [&non_zero, &even_odd] {
+        for &[a, b, c] in triangles.iter() {
+            assert!((b - a).cross(c - a) >= 0.0);
+        }
+        for i in 0..20 {
+            for j in 0..20 {
+                let p = point(i as f32 - 9.77, j as f32 - 9.83);
+                assert!(coverage(triangles, p) <= 1);
+            }
+        }
+    }
+
+    // A circle, made of curves.
+    let mut builder = Path::builder();
+    builder.add_circle(point(0.0, 0.0), 10.0, path::Winding::Positive);
+    let circle = builder.build();
+    let triangles = fill(circle.iter(), 0.01, FillRule::EvenOdd);
+    let expected = std::f32::consts::PI * 100.0;
+    assert!((area(&triangles) - expected).abs() < expected * 0.01);
+}
+
+#[test]
+fn reference_stroke() {
+    use path::Path;
+
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.end(false);
+    let path = builder.build();
+
+    let triangles = stroke(path.iter(), 0.1, 2.0);
+    assert_eq!(triangles.len(), 4);
+    assert!((area(&triangles) - 40.0).abs() < 0.001);
+    for &[a, b, c] in &triangles {
+        assert!((b - a).cross(c - a) > 0.0);
+    }
+    assert_eq!(coverage(&triangles, point(5.0, 0.5)), 1);
+    assert_eq!(coverage(&triangles, point(5.0, 1.5)), 0);
+}
@@ -43,7 +43,34 @@ impl<S: Scalar> Arc<S> {
     }
 
     /// Convert from the SVG arc notation.
+    ///
+    /// Panics if the arc should be rendered as a straight line (see
+    /// `SvgArc::is_straight_line`). Use `try_from_svg_arc` to handle this case.
     pub fn from_svg_arc(arc: &SvgArc<S>) -> Arc<S> {
+        // The SVG spec specifies what we should do if one of the two
+        // radii is zero and not the other, but it's better to handle
+        // this out of arc code and generate a line_to instead of an arc.
+        assert!(!arc.is_straight_line());
+
+        Self::from_svg_arc_unchecked(arc)
+    }
+
+    /// Convert from the SVG arc notation.
+    ///
+    /// Radii that are too small for the arc to connect its endpoints are scaled up as
+    /// specified by SVG (see `SvgArc::corrected_radii`).
+    ///
+    /// Returns `None` if the arc should be rendered as a straight line instead (see
+    /// `SvgArc::is_straight_line`).
+    pub fn try_from_svg_arc(arc: &SvgArc<S>) -> Option<Arc<S>> {
+        if arc.is_straight_line() {
+            return None;
+        }
+
+        Some(Self::from_svg_arc_unchecked(arc))
+    }
+
+    fn from_svg_arc_unchecked(arc: &SvgArc<S>) -> Arc<S> {
         debug_assert!(!arc.from.x.is_nan());
         debug_assert!(!arc.from.y.is_nan());
         debug_assert!(!arc.to.x.is_nan());
@@ -51,40 +78,14 @@ impl<S: Scalar> Arc<S> {
         debug_assert!(!arc.radii.x.is_nan());
         debug_assert!(!arc.radii.y.is_nan());
         debug_assert!(!arc.x_rotation.get().is_nan());
-        // The SVG spec specifies what we should do if one of the two
-        // radii is zero and not the other, but it's better to handle
-        // this out of arc code and generate a line_to instead of an arc.
-        assert!(!arc.is_straight_line());
 
-        let mut rx = S::abs(arc.radii.x);
-        let mut ry = S::abs(arc.radii.y);
-
-        let xr = arc.x_rotation.get() % (S::TWO * S::PI());
-        let cos_phi = Float::cos(xr);
-        let sin_phi = Float::sin(xr);
-        let hd_x = (arc.from.x - arc.to.x) / S::TWO;
-        let hd_y = (arc.from.y - arc.to.y) / S::TWO;
+        let (cos_phi, sin_phi, p) = arc.transformed_half_chord();
         let hs_x = (arc.from.x + arc.to.x) / S::TWO;
         let hs_y = (arc.from.y + arc.to.y) / S::TWO;
 
-        // F6.5.1
-        let p = Point::new(
-            cos_phi * hd_x + sin_phi * hd_y,
-            -sin_phi * hd_x + cos_phi * hd_y,
-        );
-
-        // Sanitize the radii.
-        // If rf > 1 it means the radii are too small for the arc to
-        // possibly connect the end points. In this situation we scale
-        // them up according to the formula provided by the SVG spec.
-
-        // F6.6.2
-        let rf = p.x * p.x / (rx * rx) + p.y * p.y / (ry * ry);
-        if rf > S::ONE {
-            let scale = S::sqrt(rf);
-            rx *= scale;
-            ry *= scale;
-        }
+        let radii = arc.corrected_radii();
+        let rx = radii.x;
+        let ry = radii.y;
 
         let rxry = rx * ry;
         let rxpy = rx * p.y;
@@ -134,6 +135,9 @@ impl<S: Scalar> Arc<S> {
     }
 
     /// Convert to the SVG arc notation.
+    ///
+    /// The endpoint notation can't represent arcs sweeping a full turn or more: the
+    /// endpoints of the resulting `SvgArc` coincide in this case.
     pub fn to_svg_arc(&self) -> SvgArc<S> {
         let from = self.sample(S::ZERO);
         let to = self.sample(S::ONE);
@@ -177,6 +181,44 @@ impl<S: Scalar> Arc<S> {
         arc_to_cubic_beziers(self, cb);
     }
 
+    /// Approximate the arc with the smallest number of cubic bézier curves that keeps
+    /// the approximation within `tolerance` of the arc.
+    ///
+    /// Unlike `for_each_cubic_bezier` which always splits the arc into pieces of at most
+    /// 90°, this can produce significantly fewer curves when a coarse approximation is
+    /// acceptable, and more of them when the required precision is very high.
+    pub fn for_each_cubic_bezier_with_tolerance<F>(&self, tolerance: S, cb: &mut F)
+    where
+        F: FnMut(&CubicBezierSegment<S>),
+    {
+        arc_to_cubic_beziers_with_tolerance(self, tolerance, cb);
+    }
+
+    /// Returns the number of curves produced by `for_each_cubic_bezier_with_tolerance`.
+    pub fn num_cubic_beziers(&self, tolerance: S) -> u32 {
+        let sweep_angle = S::abs(self.sweep_angle.get()).min(S::PI() * S::TWO);
+        let radius = S::max(S::abs(self.radii.x), S::abs(self.radii.y));
+
+        // Pieces of more than half a turn are never used, the error grows very quickly past that.
+        let mut n = S::max(S::ceil(sweep_angle / S::PI()), S::ONE);
+        loop {
+            // The approximation of an elliptic arc is the affine image of the approximation of
+            // a unit circular arc, for which the maximum radial error of a piece of angle a is
+            // 2 * sin(a / 4)^6 / (27 * cos(a / 4)^2).
+            // Scaling by the largest radius gives a conservative bound.
+            let quarter = sweep_angle / n / S::FOUR;
+            let sin = Float::sin(quarter);
+            let cos = Float::cos(quarter);
+            let sin3 = sin * sin * sin;
+            let error = radius * S::TWO * sin3 * sin3 / (S::value(27.0) * cos * cos);
+            // The number of pieces is capped to avoid looping forever with a tolerance of zero.
+            if error <= tolerance || n >= S::value(1024.0) {
+                return cast(n).unwrap();
+            }
+            n += S::ONE;
+        }
+    }
+
     /// Sample the curve at t (expecting t between 0 and 1).
     #[inline]
     pub fn sample(&self, t: S) -> Point<S> {
@@ -318,6 +360,20 @@ impl<S: Scalar> Arc<S> {
         });
     }
 
+    /// Approximates the curve with a sequence of line segments and appends the end point
+    /// of each of them to `output`.
+    ///
+    /// The start of the curve is not added, so that flattening consecutive segments into
+    /// the same buffer produces a polyline without duplicate points.
+    ///
+    /// Returns the range of the appended points in `output`.
+    pub fn flatten_into(&self, output: &mut Vec<Point<S>>, tolerance: S) -> Range<usize> {
+        let start = output.len();
+        self.for_each_flattened(tolerance, &mut |segment| output.push(segment.to));
+
+        start..output.len()
+    }
+
     /// Approximates the curve with sequence of line segments.
     ///
     /// The `tolerance` parameter defines the maximum distance between the curve and
@@ -516,6 +572,56 @@ impl<S: Scalar> SvgArc<S> {
         Arc::from_svg_arc(self)
     }
 
+    /// Converts this arc from endpoints to center notation.
+    ///
+    /// Returns `None` if the arc should be rendered as a straight line instead.
+    pub fn try_to_arc(&self) -> Option<Arc<S>> {
+        Arc::try_from_svg_arc(self)
+    }
+
+    /// Returns the absolute values of the radii, scaled up if they are too small for
+    /// the arc to connect its endpoints.
+    ///
+    /// This implements the out-of-range radii correction of the SVG specification (F.6.6).
+    pub fn corrected_radii(&self) -> Vector<S> {
+        let mut rx = S::abs(self.radii.x);
+        let mut ry = S::abs(self.radii.y);
+
+        let (_, _, p) = self.transformed_half_chord();
+
+        // If rf > 1 it means the radii are too small for the arc to
+        // possibly connect the end points. In this situation we scale
+        // them up according to the formula provided by the SVG spec.
+
+        // F6.6.2
+        let rf = p.x * p.x / (rx * rx) + p.y * p.y / (ry * ry);
+        if rf > S::ONE {
+            let scale = S::sqrt(rf);
+            rx *= scale;
+            ry *= scale;
+        }
+
+        vector(rx, ry)
+    }
+
+    /// Returns the cosine and sine of the x rotation and the half chord between the
+    /// endpoints in the ellipse's coordinate system.
+    fn transformed_half_chord(&self) -> (S, S, Point<S>) {
+        let xr = self.x_rotation.get() % (S::TWO * S::PI());
+        let cos_phi = Float::cos(xr);
+        let sin_phi = Float::sin(xr);
+        let hd_x = (self.from.x - self.to.x) / S::TWO;
+        let hd_y = (self.from.y - self.to.y) / S::TWO;
+
+        // F6.5.1
+        let p = Point::new(
+            cos_phi * hd_x + sin_phi * hd_y,
+            -sin_phi * hd_x + cos_phi * hd_y,
+        );
+
+        (cos_phi, sin_phi, p)
+    }
+
     /// Per SVG spec, this arc should be rendered as a line_to segment.
     ///
     /// Do not convert an `SvgArc` into an `arc` if this returns true.
@@ -580,6 +686,25 @@ impl<S: Scalar> SvgArc<S> {
         Arc::from_svg_arc(self).for_each_cubic_bezier(cb);
     }
 
+    /// Approximates the arc with the smallest number of cubic bézier segments that keeps
+    /// the approximation within `tolerance` of the arc.
+    pub fn for_each_cubic_bezier_with_tolerance<F>(&self, tolerance: S, cb: &mut F)
+    where
+        F: FnMut(&CubicBezierSegment<S>),
+    {
+        if self.is_straight_line() {
+            cb(&CubicBezierSegment {
+                from: self.from,
+                ctrl1: self.from,
+                ctrl2: self.to,
+                to: self.to,
+            });
+            return;
+        }
+
+        Arc::from_svg_arc(self).for_each_cubic_bezier_with_tolerance(tolerance, cb);
+    }
+
     /// Approximates the curve with sequence of line segments.
     ///
     /// The `tolerance` parameter defines the maximum distance between the curve and
@@ -596,6 +721,20 @@ impl<S: Scalar> SvgArc<S> {
         Arc::from_svg_arc(self).for_each_flattened(tolerance, cb);
     }
 
+    /// Approximates the curve with a sequence of line segments and appends the end point
+    /// of each of them to `output`.
+    ///
+    /// The start of the curve is not added, so that flattening consecutive segments into
+    /// the same buffer produces a polyline without duplicate points.
+    ///
+    /// Returns the range of the appended points in `output`.
+    pub fn flatten_into(&self, output: &mut Vec<Point<S>>, tolerance: S) -> Range<usize> {
+        let start = output.len();
+        self.for_each_flattened(tolerance, &mut |segment| output.push(segment.to));
+
+        start..output.len()
+    }
+
     /// Approximates the curve with sequence of line segments.
     ///
     /// The `tolerance` parameter defines the maximum distance between the curve and
@@ -728,6 +867,42 @@ where
     }
 }
 
+fn arc_to_cubic_beziers_with_tolerance<S, F>(arc: &Arc<S>, tolerance: S, callback: &mut F)
+where
+    S: Scalar,
+    F: FnMut(&CubicBezierSegment<S>),
+{
+    let n = arc.num_cubic_beziers(tolerance);
+    let sign = arc.sweep_angle.get().signum();
+    let sweep_angle = S::abs(arc.sweep_angle.get()).min(S::PI() * S::TWO);
+    let step = Angle::radians(sweep_angle / cast(n).unwrap() * sign);
+
+    // Place the control points so that the middle of each curve is on the ellipse,
+    // which minimizes the error for circular arcs.
+    let k = S::FOUR / S::THREE * Float::tan(step.get() / S::FOUR);
+
+    let mut a1 = arc.start_angle;
+    let mut from = arc.from();
+    for i in 1..=n {
+        let a2 = arc.start_angle + step * cast(i).unwrap();
+        let to = if i == n {
+            arc.to()
+        } else {
+            arc.center + sample_ellipse(arc.radii, arc.x_rotation, a2).to_vector()
+        };
+
+        callback(&CubicBezierSegment {
+            from,
+            ctrl1: from + arc.tangent_at_angle(a1) * k,
+            ctrl2: to - arc.tangent_at_angle(a2) * k,
+            to,
+        });
+
+        a1 = a2;
+        from = to;
+    }
+}
+
 fn sample_ellipse<S: Scalar>(radii: Vector<S>, x_rotation: Angle<S>, angle: Angle<S>) -> Point<S> {
     Rotation::new(x_rotation).transform_point(point(
         radii.x * Float::cos(angle.get()),
@@ -774,6 +949,10 @@ impl<S: Scalar> Segment for Arc<S> {
         self.approximate_length(tolerance)
     }
 
+    fn flatten_into(&self, output: &mut Vec<Point<S>>, tolerance: S) -> Range<usize> {
+        self.flatten_into(output, tolerance)
+    }
+
     fn for_each_flattened_with_t(
         &self,
         tolerance: Self::Scalar,
@@ -1176,3 +1355,93 @@ fn negative_flattening_step() {
 
     assert!(flattened.len() > 1);
 }
+
+#[test]
+fn svg_arc_conversions() {
+    let svg_arc = SvgArc {
+        from: point(0.0f64, 0.0),
+        to: point(80.0, 60.0),
+        radii: vector(-40.0, 40.0),
+        x_rotation: Angle::radians(0.0),
+        flags: ArcFlags::default(),
+    };
+
+    // The radii are too small to connect the endpoints: they are scaled up so that
+    // the endpoints are on opposite sides of the ellipse.
+    let radii = svg_arc.corrected_radii();
+    assert!((radii - vector(50.0, 50.0)).length() < 1e-9);
+
+    let arc = Arc::try_from_svg_arc(&svg_arc).unwrap();
+    assert!((arc.radii - radii).length() < 1e-9);
+    assert!((arc.center - point(40.0, 30.0)).length() < 1e-9);
+
+    // The angles are computed with a fast atan2 approximation.
+    let round_trip = arc.to_svg_arc();
+    assert!((round_trip.from - svg_arc.from).length() < 0.01);
+    assert!((round_trip.to - svg_arc.to).length() < 0.01);
+    assert_eq!(round_trip.radii, arc.radii);
+
+    // Radii that are large enough are kept.
+    let svg_arc = SvgArc {
+        radii: vector(100.0, 60.0),
+        ..svg_arc
+    };
+    assert_eq!(svg_arc.corrected_radii(), vector(100.0, 60.0));
+
+    assert_eq!(
+        SvgArc {
+            radii: vector(0.0, 10.0),
+            ..svg_arc
+        }
+        .try_to_arc(),
+        None
+    );
+    assert_eq!(
+        SvgArc {
+            to: svg_arc.from,
+            ..svg_arc
+        }
+        .try_to_arc(),
+        None
+    );
+}
+
+#[test]
+fn cubic_beziers_with_tolerance() {
+    let arc = Arc {
+        center: point(10.0f64, -5.0),
+        radii: vector(100.0, 60.0),
+        start_angle: Angle::radians(0.3),
+        sweep_angle: Angle::radians(-5.0),
+        x_rotation: Angle::radians(0.7),
+    };
+
+    for &tolerance in &[10.0, 1.0, 0.1, 0.01, 0.0001] {
+        let mut curves = Vec::new();
+        arc.for_each_cubic_bezier_with_tolerance(tolerance, &mut |curve| curves.push(*curve));
+
+        assert_eq!(curves.len() as u32, arc.num_cubic_beziers(tolerance));
+        assert_eq!(curves[0].from, arc.from());
+        assert_eq!(curves.last().unwrap().to, arc.to());
+
+        for (i, curve) in curves.iter().enumerate() {
+            if i > 0 {
+                assert_eq!(curve.from, curves[i - 1].to);
+            }
+            for j in 0..=16 {
+                // Measure the error in the space where the ellipse is a unit circle and scale it
+                // back by the largest radius, which bounds the distance to the ellipse.
+                let p = Rotation::new(-arc.x_rotation)
+                    .transform_vector(curve.sample(j as f64 / 16.0) - arc.center);
+                let error =
+                    (vector(p.x / arc.radii.x, p.y / arc.radii.y).length() - 1.0).abs() * 100.0;
+                assert!(error <= tolerance, "{} > {}", error, tolerance);
+            }
+        }
+    }
+
+    // A full circle with a coarse tolerance only needs two curves instead of four.
+    let circle = Arc::circle(point(0.0f64, 0.0), 10.0);
+    assert_eq!(circle.num_cubic_beziers(1.0), 2);
+    assert_eq!(circle.num_cubic_beziers(0.01), 4);
+}
@@ -0,0 +1,212 @@
+use crate::scalar::Scalar;
+use crate::segment::{BoundingBox, Segment};
+use crate::{Arc, Box2D, CubicBezierSegment, LineSegment, Point, QuadraticBezierSegment, Vector};
+
+use std::ops::Range;
+
+/// A segment of any of the types supported by this crate.
+///
+/// This makes it possible to write per-segment algorithms once against the
+/// `Segment` trait, without going through generics or handling each segment
+/// type separately.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum BezierSegment<S> {
+    Linear(LineSegment<S>),
+    Quadratic(QuadraticBezierSegment<S>),
+    Cubic(CubicBezierSegment<S>),
+    Arc(Arc<S>),
+}
+
+macro_rules! dispatch {
+    ($this:expr, $segment:ident => $e:expr) => {
+        match $this {
+            BezierSegment::Linear($segment) => $e,
+            BezierSegment::Quadratic($segment) => $e,
+            BezierSegment::Cubic($segment) => $e,
+            BezierSegment::Arc($segment) => $e,
+        }
+    };
+}
+
+macro_rules! dispatch_map {
+    ($this:expr, $segment:ident => $e:expr) => {
+        match $this {
+            BezierSegment::Linear($segment) => BezierSegment::Linear($e),
+            BezierSegment::Quadratic($segment) => BezierSegment::Quadratic($e),
+            BezierSegment::Cubic($segment) => BezierSegment::Cubic($e),
+            BezierSegment::Arc($segment) => BezierSegment::Arc($e),
+        }
+    };
+}
+
+impl<S: Scalar> BezierSegment<S> {
+    /// Returns the smallest rectangle that contains the segment.
+    pub fn bounding_box(&self) -> Box2D<S> {
+        dispatch!(self, s => BoundingBox::bounding_box(s))
+    }
+
+    /// Returns a conservative rectangle that contains the segment.
+    ///
+    /// This does not necessarily return the smallest possible bounding rectangle.
+    pub fn fast_bounding_box(&self) -> Box2D<S> {
+        dispatch!(self, s => BoundingBox::fast_bounding_box(s))
+    }
+
+    /// Approximates the segment with a sequence of line segments.
+    pub fn for_each_flattened<F>(&self, tolerance: S, callback: &mut F)
+    where
+        F: FnMut(&LineSegment<S>),
+    {
+        self.for_each_flattened_with_t(tolerance, &mut |segment, _| callback(segment));
+    }
+}
+
+impl<S: Scalar> Segment for BezierSegment<S> {
+    type Scalar = S;
+    fn from(&self) -> Point<S> {
+        dispatch!(self, s => Segment::from(s))
+    }
+    fn to(&self) -> Point<S> {
+        dispatch!(self, s => Segment::to(s))
+    }
+    fn sample(&self, t: S) -> Point<S> {
+        dispatch!(self, s => Segment::sample(s, t))
+    }
+    fn x(&self, t: S) -> S {
+        dispatch!(self, s => Segment::x(s, t))
+    }
+    fn y(&self, t: S) -> S {
+        dispatch!(self, s => Segment::y(s, t))
+    }
+    fn derivative(&self, t: S) -> Vector<S> {
+        dispatch!(self, s => Segment::derivative(s, t))
+    }
+    fn dx(&self, t: S) -> S {
+        dispatch!(self, s => Segment::dx(s, t))
+    }
+    fn dy(&self, t: S) -> S {
+        dispatch!(self, s => Segment::dy(s, t))
+    }
+    fn split(&self, t: S) -> (Self, Self) {
+        match self {
+            BezierSegment::Linear(s) => {
+                let (a, b) = Segment::split(s, t);
+                (BezierSegment::Linear(a), BezierSegment::Linear(b))
+            }
+            BezierSegment::Quadratic(s) => {
+                let (a, b) = Segment::split(s, t);
+                (BezierSegment::Quadratic(a), BezierSegment::Quadratic(b))
+            }
+            BezierSegment::Cubic(s) => {
+                let (a, b) = Segment::split(s, t);
+                (BezierSegment::Cubic(a), BezierSegment::Cubic(b))
+            }
+            BezierSegment::Arc(s) => {
+                let (a, b) = Segment::split(s, t);
+                (BezierSegment::Arc(a), BezierSegment::Arc(b))
+            }
+        }
+    }
+    fn before_split(&self, t: S) -> Self {
+        dispatch_map!(self, s => Segment::before_split(s, t))
+    }
+    fn after_split(&self, t: S) -> Self {
+        dispatch_map!(self, s => Segment::after_split(s, t))
+    }
+    fn split_range(&self, t_range: Range<S>) -> Self {
+        dispatch_map!(self, s => Segment::split_range(s, t_range))
+    }
+    fn flip(&self) -> Self {
+        dispatch_map!(self, s => Segment::flip(s))
+    }
+    fn approximate_length(&self, tolerance: S) -> S {
+        dispatch!(self, s => Segment::approximate_length(s, tolerance))
+    }
+    fn for_each_flattened_with_t(
+        &self,
+        tolerance: S,
+        callback: &mut dyn FnMut(&LineSegment<S>, Range<S>),
+    ) {
+        dispatch!(self, s => Segment::for_each_flattened_with_t(s, tolerance, callback))
+    }
+    fn flatten_into(&self, output: &mut Vec<Point<S>>, tolerance: S) -> Range<usize> {
+        dispatch!(self, s => Segment::flatten_into(s, output, tolerance))
+    }
+}
+
+impl<S: Scalar> BoundingBox for BezierSegment<S> {
+    type Scalar = S;
+    fn bounding_range_x(&self) -> (S, S) {
+        dispatch!(self, s => BoundingBox::bounding_range_x(s))
+    }
+    fn bounding_range_y(&self) -> (S, S) {
+        dispatch!(self, s => BoundingBox::bounding_range_y(s))
+    }
+    fn fast_bounding_range_x(&self) -> (S, S) {
+        dispatch!(self, s => BoundingBox::fast_bounding_range_x(s))
+    }
+    fn fast_bounding_range_y(&self) -> (S, S) {
+        dispatch!(self, s => BoundingBox::fast_bounding_range_y(s))
+    }
+}
+
+impl<S> From<LineSegment<S>> for BezierSegment<S> {
+    fn from(s: LineSegment<S>) -> Self {
+        BezierSegment::Linear(s)
+    }
+}
+
+impl<S> From<QuadraticBezierSegment<S>> for BezierSegment<S> {
+    fn from(s: QuadraticBezierSegment<S>) -> Self {
+        BezierSegment::Quadratic(s)
+    }
+}
+
+impl<S> From<CubicBezierSegment<S>> for BezierSegment<S> {
+    fn from(s: CubicBezierSegment<S>) -> Self {
+        BezierSegment::Cubic(s)
+    }
+}
+
+impl<S> From<Arc<S>> for BezierSegment<S> {
+    fn from(s: Arc<S>) -> Self {
+        BezierSegment::Arc(s)
+    }
+}
+
+#[test]
+fn bezier_segment_dispatch() {
+    use crate::point;
+
+    let cubic = CubicBezierSegment {
+        from: point(0.0f32, 0.0),
+        ctrl1: point(1.0, 3.0),
+        ctrl2: point(4.0, -2.0),
+        to: point(5.0, 1.0),
+    };
+    let segment: BezierSegment<f32> = cubic.into();
+
+    assert_eq!(segment.from(), cubic.from);
+    assert_eq!(segment.to(), cubic.to);
+    assert_eq!(segment.sample(0.3), cubic.sample(0.3));
+    assert_eq!(segment.flip(), BezierSegment::Cubic(cubic.flip()));
+    assert_eq!(segment.bounding_box(), cubic.bounding_box());
+
+    let (a, b) = segment.split(0.5);
+    assert_eq!(a, BezierSegment::Cubic(cubic.before_split(0.5)));
+    assert_eq!(b, BezierSegment::Cubic(cubic.after_split(0.5)));
+
+    let mut expected = Vec::new();
+    let mut actual = Vec::new();
+    cubic.flatten_into(&mut expected, 0.01);
+    segment.flatten_into(&mut actual, 0.01);
+    assert_eq!(actual, expected);
+
+    let line: BezierSegment<f32> = LineSegment {
+        from: point(0.0, 0.0),
+        to: point(3.0, 4.0),
+    }
+    .into();
+    assert_eq!(line.approximate_length(0.1), 5.0);
+}
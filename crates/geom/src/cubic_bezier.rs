@@ -2,7 +2,7 @@ use crate::cubic_bezier_intersections::cubic_bezier_intersections_t;
 use crate::scalar::Scalar;
 use crate::segment::{BoundingBox, Segment};
 use crate::traits::Transformation;
-use crate::utils::{cubic_polynomial_roots, min_max};
+use crate::utils::{cubic_polynomial_roots, min_max, polynomial_area_and_moment};
 use crate::{point, Box2D, Point, Vector};
 use crate::{Line, LineEquation, LineSegment, QuadraticBezierSegment};
 use arrayvec::ArrayVec;
@@ -548,6 +548,20 @@ impl<S: Scalar> CubicBezierSegment<S> {
         });
     }
 
+    /// Approximates the curve with a sequence of line segments and appends the end point
+    /// of each of them to `output`.
+    ///
+    /// The start of the curve is not added, so that flattening consecutive segments into
+    /// the same buffer produces a polyline without duplicate points.
+    ///
+    /// Returns the range of the appended points in `output`.
+    pub fn flatten_into(&self, output: &mut Vec<Point<S>>, tolerance: S) -> Range<usize> {
+        let start = output.len();
+        self.for_each_flattened(tolerance, &mut |segment| output.push(segment.to));
+
+        start..output.len()
+    }
+
     /// Approximates the curve with sequence of line segments.
     ///
     /// The `tolerance` parameter defines the maximum distance between the curve and
@@ -591,6 +605,50 @@ impl<S: Scalar> CubicBezierSegment<S> {
         length
     }
 
+    /// Returns the coefficients of the curve in power basis, relative to `origin`.
+    fn polynomial_coefficients(&self, origin: Point<S>) -> [Vector<S>; 4] {
+        [
+            self.from - origin,
+            (self.ctrl1 - self.from) * S::THREE,
+            (self.from.to_vector() - self.ctrl1.to_vector() * S::TWO + self.ctrl2.to_vector())
+                * S::THREE,
+            self.to - self.from + (self.ctrl1 - self.ctrl2) * S::THREE,
+        ]
+    }
+
+    /// Computes the signed area of the region swept by the segment with respect to the origin.
+    ///
+    /// Summing this over the segments of a closed path gives the signed area enclosed by
+    /// the path. The sign depends on the winding order.
+    pub fn signed_area(&self) -> S {
+        polynomial_area_and_moment(&self.polynomial_coefficients(Point::origin())).0
+    }
+
+    /// Computes the first moment of area of the region swept by the segment with respect
+    /// to the origin.
+    ///
+    /// Summing this over the segments of a closed path and dividing by the path's signed
+    /// area gives the centroid of the enclosed region.
+    pub fn first_moment(&self) -> Vector<S> {
+        polynomial_area_and_moment(&self.polynomial_coefficients(Point::origin())).1
+    }
+
+    /// Computes the centroid of the region enclosed by the curve and its baseline.
+    ///
+    /// If the curve self-intersects, the signed areas of its loops partially cancel out.
+    ///
+    /// Returns `None` if this region has no area.
+    pub fn centroid(&self) -> Option<Point<S>> {
+        let (area, moment) = polynomial_area_and_moment(&self.polynomial_coefficients(self.from));
+        let extent = self.fast_bounding_box().size();
+        if area.abs() <= S::EPSILON * (extent.width * extent.width + extent.height * extent.height)
+        {
+            return None;
+        }
+
+        Some(self.from + moment / area)
+    }
+
     /// Invokes a callback at each inflection point if any.
     pub fn for_each_inflection_t<F>(&self, cb: &mut F)
     where
@@ -2040,3 +2098,87 @@ fn test_cubic_to_quadratics() {
     assert!(count < 10);
     assert!(count > 4);
 }
+
+#[test]
+fn flatten_into() {
+    use crate::{Angle, Arc};
+
+    fn check<T: Segment<Scalar = f32>>(segment: T, output: &mut Vec<Point<f32>>) {
+        let mut expected = Vec::new();
+        segment.for_each_flattened_with_t(0.01, &mut |line, _| expected.push(line.to));
+
+        let range = segment.flatten_into(output, 0.01);
+        assert_eq!(&output[range.clone()], &expected[..]);
+        assert_eq!(range.end, output.len());
+        assert_eq!(output.last(), Some(&segment.to()));
+    }
+
+    let cubic = CubicBezierSegment {
+        from: point(0.0f32, 0.0),
+        ctrl1: point(10.0, 0.0),
+        ctrl2: point(0.0, 10.0),
+        to: point(10.0, 10.0),
+    };
+
+    let mut output = vec![cubic.from];
+    check(cubic, &mut output);
+    check(
+        QuadraticBezierSegment {
+            from: cubic.to,
+            ctrl: point(20.0, 10.0),
+            to: point(20.0, 20.0),
+        },
+        &mut output,
+    );
+    check(
+        LineSegment {
+            from: point(20.0, 20.0),
+            to: point(0.0, 20.0),
+        },
+        &mut output,
+    );
+    check(
+        Arc {
+            center: point(0.0, 15.0),
+            radii: crate::vector(5.0, 5.0),
+            start_angle: Angle::frac_pi_2(),
+            sweep_angle: Angle::pi(),
+            x_rotation: Angle::zero(),
+        },
+        &mut output,
+    );
+
+    // The inherent methods match the trait's.
+    let mut a = Vec::new();
+    let mut b = Vec::new();
+    cubic.flatten_into(&mut a, 0.1);
+    Segment::flatten_into(&cubic, &mut b, 0.1);
+    assert_eq!(a, b);
+}
+
+#[test]
+fn area_and_centroid() {
+    let quadratic = QuadraticBezierSegment {
+        from: point(0.0f64, 0.0),
+        ctrl: point(1.0, 2.0),
+        to: point(2.0, 0.0),
+    };
+    let cubic = quadratic.to_cubic();
+    assert!((cubic.signed_area() - quadratic.signed_area()).abs() < 1e-12);
+    assert!((cubic.centroid().unwrap() - point(1.0, 0.4)).length() < 1e-12);
+
+    let curve = CubicBezierSegment {
+        from: point(1.0f64, 1.0),
+        ctrl1: point(10.0, -3.0),
+        ctrl2: point(-2.0, 7.0),
+        to: point(5.0, 4.0),
+    };
+    let mut area = 0.0;
+    let mut moment = crate::vector(0.0, 0.0);
+    curve.for_each_flattened(0.000001, &mut |line| {
+        area += line.signed_area();
+        moment += line.first_moment();
+    });
+    assert!((area - curve.signed_area()).abs() < 1e-4);
+    assert!((moment - curve.first_moment()).length() < 1e-4);
+}
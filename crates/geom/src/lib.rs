@@ -88,6 +88,7 @@ pub extern crate serde;
 #[macro_use]
 mod segment;
 pub mod arc;
+mod bezier_segment;
 pub mod cubic_bezier;
 mod cubic_bezier_intersections;
 mod line;
@@ -98,6 +99,8 @@ pub mod utils;
 #[doc(inline)]
 pub use crate::arc::{Arc, ArcFlags, SvgArc};
 #[doc(inline)]
+pub use crate::bezier_segment::BezierSegment;
+#[doc(inline)]
 pub use crate::cubic_bezier::CubicBezierSegment;
 #[doc(inline)]
 pub use crate::line::{Line, LineEquation, LineSegment};
@@ -172,6 +172,36 @@ impl<S: Scalar> LineSegment<S> {
         self.to_vector().length()
     }
 
+    /// Computes the signed area of the triangle formed by the origin and the segment.
+    ///
+    /// Summing this over the segments of a closed path gives the signed area enclosed by
+    /// the path. The sign depends on the winding order.
+    #[inline]
+    pub fn signed_area(&self) -> S {
+        self.from.to_vector().cross(self.to.to_vector()) * S::HALF
+    }
+
+    /// Computes the first moment of area of the triangle formed by the origin and the segment.
+    ///
+    /// Summing this over the segments of a closed path and dividing by the path's signed
+    /// area gives the centroid of the enclosed region.
+    #[inline]
+    pub fn first_moment(&self) -> Vector<S> {
+        (self.from.to_vector() + self.to.to_vector()) * (self.signed_area() / S::THREE)
+    }
+
+    /// Appends the end of the segment to `output`.
+    ///
+    /// This is the equivalent of the curves' `flatten_into`, which don't add their start
+    /// point either. The tolerance is ignored.
+    ///
+    /// Returns the range of the appended points in `output`.
+    pub fn flatten_into(&self, output: &mut Vec<Point<S>>, _tolerance: S) -> Range<usize> {
+        output.push(self.to);
+
+        output.len() - 1..output.len()
+    }
+
     /// Changes the segment's length, moving destination point.
     pub fn set_length(&mut self, new_length: S) {
         let v = self.to_vector();
@@ -512,6 +542,9 @@ impl<S: Scalar> Segment for LineSegment<S> {
     fn approximate_length(&self, _tolerance: S) -> S {
         self.length()
     }
+    fn flatten_into(&self, output: &mut Vec<Point<S>>, tolerance: S) -> Range<usize> {
+        self.flatten_into(output, tolerance)
+    }
 
     fn for_each_flattened_with_t(
         &self,
@@ -1,6 +1,7 @@
 use crate::scalar::Scalar;
 use crate::segment::{BoundingBox, Segment};
 use crate::traits::Transformation;
+use crate::utils::polynomial_area_and_moment;
 use crate::{point, Box2D, Point, Vector};
 use crate::{CubicBezierSegment, Line, LineEquation, LineSegment, Triangle};
 use arrayvec::ArrayVec;
@@ -335,6 +336,20 @@ impl<S: Scalar> QuadraticBezierSegment<S> {
         self.for_each_flattened_with_t(tolerance, &mut |segment, _| callback(segment));
     }
 
+    /// Approximates the curve with a sequence of line segments and appends the end point
+    /// of each of them to `output`.
+    ///
+    /// The start of the curve is not added, so that flattening consecutive segments into
+    /// the same buffer produces a polyline without duplicate points.
+    ///
+    /// Returns the range of the appended points in `output`.
+    pub fn flatten_into(&self, output: &mut Vec<Point<S>>, tolerance: S) -> Range<usize> {
+        let start = output.len();
+        self.for_each_flattened(tolerance, &mut |segment| output.push(segment.to));
+
+        start..output.len()
+    }
+
     /// Compute a flattened approximation of the curve, invoking a callback at
     /// each step.
     ///
@@ -610,11 +625,12 @@ impl<S: Scalar> QuadraticBezierSegment<S> {
 
         if a == S::ZERO {
             // Linear equation bt + c = 0.
-            let t = c / b;
+            let t = -c / b;
             if t >= S::ZERO && t <= S::ONE {
                 result.push(t);
-                return result;
             }
+
+            return result;
         }
 
         let delta = b * b - S::FOUR * a * c;
@@ -843,6 +859,46 @@ impl<S: Scalar> QuadraticBezierSegment<S> {
         }
     }
 
+    /// Returns the coefficients of the curve in power basis, relative to `origin`.
+    fn polynomial_coefficients(&self, origin: Point<S>) -> [Vector<S>; 3] {
+        [
+            self.from - origin,
+            (self.ctrl - self.from) * S::TWO,
+            self.from.to_vector() - self.ctrl.to_vector() * S::TWO + self.to.to_vector(),
+        ]
+    }
+
+    /// Computes the signed area of the region swept by the segment with respect to the origin.
+    ///
+    /// Summing this over the segments of a closed path gives the signed area enclosed by
+    /// the path. The sign depends on the winding order.
+    pub fn signed_area(&self) -> S {
+        polynomial_area_and_moment(&self.polynomial_coefficients(Point::origin())).0
+    }
+
+    /// Computes the first moment of area of the region swept by the segment with respect
+    /// to the origin.
+    ///
+    /// Summing this over the segments of a closed path and dividing by the path's signed
+    /// area gives the centroid of the enclosed region.
+    pub fn first_moment(&self) -> Vector<S> {
+        polynomial_area_and_moment(&self.polynomial_coefficients(Point::origin())).1
+    }
+
+    /// Computes the centroid of the region enclosed by the curve and its baseline.
+    ///
+    /// Returns `None` if this region has no area.
+    pub fn centroid(&self) -> Option<Point<S>> {
+        let (area, moment) = polynomial_area_and_moment(&self.polynomial_coefficients(self.from));
+        let extent = self.fast_bounding_box().size();
+        if area.abs() <= S::EPSILON * (extent.width * extent.width + extent.height * extent.height)
+        {
+            return None;
+        }
+
+        Some(self.from + moment / area)
+    }
+
     // This is to conform to the `impl_segment!` macro
     fn approximate_length(&self, _tolerance: S) -> S {
         self.length()
@@ -1462,6 +1518,21 @@ fn line_intersections_t() {
     }
     assert_eq!(i2.len(), 2);
     assert_eq!(i1.len(), 2);
+
+    // The control point is halfway between the endpoints along the line's normal,
+    // which makes the equation linear.
+    let curve = QuadraticBezierSegment {
+        from: point(5.0f64, -5.0),
+        ctrl: point(7.0, 0.0),
+        to: point(5.0, 5.0),
+    };
+    let line = Line {
+        point: point(0.0, 1.0),
+        vector: crate::vector(1.0, 0.0),
+    };
+    let i = curve.line_intersections_t(&line);
+    assert_eq!(i.len(), 1);
+    assert!((curve.sample(i[0]).y - 1.0).abs() < 1e-9);
 }
 
 #[test]
@@ -1524,3 +1595,37 @@ fn arc_length() {
         );
     }
 }
+
+#[test]
+fn area_and_centroid() {
+    let curve = QuadraticBezierSegment {
+        from: point(0.0f64, 0.0),
+        ctrl: point(1.0, 2.0),
+        to: point(2.0, 0.0),
+    };
+
+    // Area of a parabolic segment: 2/3 of the enclosing rectangle.
+    let closed_area = curve.signed_area() + curve.baseline().flip().signed_area();
+    assert!((closed_area.abs() - 4.0 / 3.0).abs() < 1e-12);
+
+    let c = curve.centroid().unwrap();
+    assert!((c - point(1.0, 0.4)).length() < 1e-12);
+
+    // Compare the swept area and first moment against a fine flattening.
+    let curve = curve.transformed(&crate::Translation::new(3.0, -5.0));
+    let mut area = 0.0;
+    let mut moment = crate::vector(0.0, 0.0);
+    curve.for_each_flattened(0.000001, &mut |line| {
+        area += line.signed_area();
+        moment += line.first_moment();
+    });
+    assert!((area - curve.signed_area()).abs() < 1e-4);
+    assert!((moment - curve.first_moment()).length() < 1e-4);
+
+    let flat = QuadraticBezierSegment {
+        from: point(0.0f64, 0.0),
+        ctrl: point(1.0, 0.0),
+        to: point(3.0, 0.0),
+    };
+    assert_eq!(flat.centroid(), None);
+}
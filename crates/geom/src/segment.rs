@@ -70,6 +70,24 @@ pub trait Segment: Copy + Sized {
         tolerance: Self::Scalar,
         callback: &mut dyn FnMut(&LineSegment<Self::Scalar>, Range<Self::Scalar>),
     );
+
+    /// Approximates the curve with a sequence of line segments and appends the end point
+    /// of each of them to `output`.
+    ///
+    /// The start of the curve is not added, so that flattening consecutive segments into
+    /// the same buffer produces a polyline without duplicate points.
+    ///
+    /// Returns the range of the appended points in `output`.
+    fn flatten_into(
+        &self,
+        output: &mut Vec<Point<Self::Scalar>>,
+        tolerance: Self::Scalar,
+    ) -> Range<usize> {
+        let start = output.len();
+        self.for_each_flattened_with_t(tolerance, &mut |segment, _| output.push(segment.to));
+
+        start..output.len()
+    }
 }
 
 pub trait BoundingBox {
@@ -157,5 +175,8 @@ macro_rules! impl_segment {
         fn approximate_length(&self, tolerance: $S) -> $S {
             self.approximate_length(tolerance)
         }
+        fn flatten_into(&self, output: &mut Vec<Point<$S>>, tolerance: $S) -> Range<usize> {
+            self.flatten_into(output, tolerance)
+        }
     };
 }
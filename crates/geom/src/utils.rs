@@ -59,6 +59,30 @@ pub fn directed_angle2<S: Scalar>(center: Point<S>, a: Point<S>, b: Point<S>) ->
     directed_angle(a - center, b - center)
 }
 
+/// Signed area and first moment of the region swept by a polynomial curve with
+/// respect to the origin.
+///
+/// The curve is given in power basis: `P(t) = coeffs[0] + coeffs[1] * t + coeffs[2] * t² + ...`
+/// with `t` in `[0, 1]`. By Green's theorem, summing these values over the segments of a closed
+/// path gives the area and first moment of the enclosed region.
+pub(crate) fn polynomial_area_and_moment<S: Scalar>(coeffs: &[Vector<S>]) -> (S, Vector<S>) {
+    // Area: 1/2 ∫ P × P' dt
+    // First moment: 1/3 ∫ P (P × P') dt
+    let mut area = S::ZERO;
+    let mut moment = vector(S::ZERO, S::ZERO);
+    for (i, ci) in coeffs.iter().enumerate() {
+        for (j, cj) in coeffs.iter().enumerate().skip(1) {
+            let c = ci.cross(*cj) * S::value(j as f32);
+            area += c / S::value((i + j) as f32);
+            for (k, ck) in coeffs.iter().enumerate() {
+                moment += *ck * (c / S::value((i + j + k) as f32));
+            }
+        }
+    }
+
+    (area * S::HALF, moment / S::THREE)
+}
+
 pub fn cubic_polynomial_roots<S: Scalar>(a: S, b: S, c: S, d: S) -> ArrayVec<S, 3> {
     let mut result = ArrayVec::new();
 
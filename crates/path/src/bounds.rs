@@ -0,0 +1,302 @@
+//! Per-sub-path bounding rectangles maintained across edits.
+//!
+//! Hit-testing and culling large paths is cheaper when each sub-path has its own bounding
+//! rectangle. While the user drags points of a path, recomputing all of them after each
+//! modification would be wasteful: [`SubPathBounds`](struct.SubPathBounds.html) marks the
+//! rectangles affected by a [`PathPatch`](../diff/struct.PathPatch.html) as dirty and only
+//! recomputes those on the next update.
+//!
+//! Moving events only invalidates the sub-paths they belong to. Patches that insert or
+//! remove events change the structure of the path and invalidate all rectangles.
+//!
+//! [`PathHistory`](../history/struct.PathHistory.html) maintains the rectangles of the
+//! path it edits.
+
+use crate::diff::{PatchOp, PathPatch};
+use crate::math::{point, Box2D, Point};
+use crate::{Event, Path};
+
+use std::ops::Range;
+
+/// The bounding rectangles of the sub-paths of a path.
+#[derive(Clone, Debug)]
+pub struct SubPathBounds {
+    // Index of the first event of each sub-path, followed by the number of events.
+    starts: Vec<u32>,
+    rects: Vec<Box2D>,
+    dirty: Vec<bool>,
+    any_dirty: bool,
+    rebuild: bool,
+}
+
+impl SubPathBounds {
+    /// Creates the rectangles of an empty path.
+    ///
+    /// Call `invalidate_all` and `update` to use it with another path.
+    pub fn new() -> Self {
+        SubPathBounds {
+            starts: vec![0],
+            rects: Vec::new(),
+            dirty: Vec::new(),
+            any_dirty: false,
+            rebuild: false,
+        }
+    }
+
+    /// Computes the rectangles of the sub-paths of a path.
+    pub fn from_path(path: &Path) -> Self {
+        let mut bounds = SubPathBounds::new();
+        bounds.invalidate_all();
+        bounds.update(path);
+
+        bounds
+    }
+
+    /// Marks the rectangles affected by a patch as dirty.
+    ///
+    /// The patch must be expressed in terms of the events of the path before or after
+    /// the modification: both are equivalent for patches that only move events.
+    pub fn invalidate(&mut self, patch: &PathPatch) {
+        if self.rebuild {
+            return;
+        }
+
+        for op in patch.ops() {
+            match op {
+                PatchOp::Move { index, .. } => match self.sub_path_containing(*index) {
+                    Some(sub_path) => {
+                        self.dirty[sub_path] = true;
+                        self.any_dirty = true;
+                    }
+                    None => {
+                        self.invalidate_all();
+                        return;
+                    }
+                },
+                PatchOp::Insert { .. } | PatchOp::Remove { .. } => {
+                    self.invalidate_all();
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Marks all rectangles as dirty, for example after the path was replaced.
+    pub fn invalidate_all(&mut self) {
+        self.rebuild = true;
+    }
+
+    /// Returns true if some rectangles need to be recomputed.
+    #[inline]
+    pub fn is_dirty(&self) -> bool {
+        self.rebuild || self.any_dirty
+    }
+
+    /// Recomputes the dirty rectangles.
+    pub fn update(&mut self, path: &Path) {
+        if self.rebuild {
+            self.starts.clear();
+            self.rects.clear();
+            self.starts.push(0);
+            let mut rect = Box2D::zero();
+            let mut num_events = 0;
+            for event in path.iter() {
+                num_events += 1;
+                if let Event::Begin { at } = event {
+                    rect = Box2D { min: at, max: at };
+                    continue;
+                }
+                add_event(&mut rect, &event);
+                if let Event::End { .. } = event {
+                    self.rects.push(rect);
+                    self.starts.push(num_events);
+                }
+            }
+            self.dirty.clear();
+            self.dirty.resize(self.rects.len(), false);
+            self.rebuild = false;
+            self.any_dirty = false;
+
+            return;
+        }
+
+        if !self.any_dirty {
+            return;
+        }
+
+        let mut events = path.iter();
+        let mut position = 0;
+        for sub_path in 0..self.rects.len() {
+            if !self.dirty[sub_path] {
+                continue;
+            }
+
+            let range = self.event_range(sub_path);
+            let mut rect = Box2D::zero();
+            for event in events
+                .by_ref()
+                .skip(range.start - position)
+                .take(range.len())
+            {
+                match event {
+                    Event::Begin { at } => {
+                        rect = Box2D { min: at, max: at };
+                    }
+                    _ => {
+                        add_event(&mut rect, &event);
+                    }
+                }
+            }
+            position = range.end;
+
+            self.rects[sub_path] = rect;
+            self.dirty[sub_path] = false;
+        }
+        self.any_dirty = false;
+    }
+
+    /// The rectangles of the sub-paths, in order.
+    ///
+    /// The rectangles that were invalidated are outdated until the next call to `update`.
+    #[inline]
+    pub fn rects(&self) -> &[Box2D] {
+        &self.rects
+    }
+
+    /// The range of events of a sub-path.
+    #[inline]
+    pub fn event_range(&self, sub_path: usize) -> Range<usize> {
+        self.starts[sub_path] as usize..self.starts[sub_path + 1] as usize
+    }
+
+    /// Returns the index of the sub-path containing an event.
+    pub fn sub_path_containing(&self, event: u32) -> Option<usize> {
+        if event >= *self.starts.last().unwrap() {
+            return None;
+        }
+
+        Some(self.starts.partition_point(|start| *start <= event) - 1)
+    }
+
+    /// Returns the indices of the sub-paths whose rectangle contains a position.
+    pub fn hit_test(&self, position: Point) -> impl Iterator<Item = usize> + '_ {
+        let area = Box2D {
+            min: position,
+            max: position,
+        };
+        self.rects
+            .iter()
+            .enumerate()
+            .filter(move |(_, rect)| intersects_inclusive(rect, &area))
+            .map(|(idx, _)| idx)
+    }
+
+    /// Returns the indices of the sub-paths whose rectangle intersects `area`.
+    pub fn intersecting<'l>(&'l self, area: &'l Box2D) -> impl Iterator<Item = usize> + 'l {
+        self.rects
+            .iter()
+            .enumerate()
+            .filter(move |(_, rect)| intersects_inclusive(rect, area))
+            .map(|(idx, _)| idx)
+    }
+}
+
+impl Default for SubPathBounds {
+    fn default() -> Self {
+        SubPathBounds::new()
+    }
+}
+
+fn add_event(rect: &mut Box2D, event: &crate::PathEvent) {
+    if let Some(segment) = event.to_segment() {
+        let bounds = segment.bounding_box();
+        rect.min = point(rect.min.x.min(bounds.min.x), rect.min.y.min(bounds.min.y));
+        rect.max = point(rect.max.x.max(bounds.max.x), rect.max.y.max(bounds.max.y));
+    }
+}
+
+// Unlike `Box2D::intersects`, counts touching rectangles and degenerate ones such as
+// the rectangles of horizontal lines.
+fn intersects_inclusive(a: &Box2D, b: &Box2D) -> bool {
+    a.min.x <= b.max.x && b.min.x <= a.max.x && a.min.y <= b.max.y && b.min.y <= a.max.y
+}
+
+#[test]
+fn incremental_sub_path_bounds() {
+    use crate::history::PathHistory;
+
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.line_to(point(1.0, 1.0));
+    builder.close();
+    builder.begin(point(5.0, 5.0));
+    builder.quadratic_bezier_to(point(6.0, 7.0), point(7.0, 5.0));
+    builder.end(false);
+    builder.begin(point(10.0, 0.0));
+    builder.line_to(point(11.0, 0.0));
+    builder.end(false);
+    let path = builder.build();
+
+    let bounds = SubPathBounds::from_path(&path);
+    assert_eq!(bounds.rects().len(), 3);
+    assert_eq!(
+        bounds.rects()[0],
+        Box2D {
+            min: point(0.0, 0.0),
+            max: point(1.0, 1.0)
+        }
+    );
+    assert_eq!(
+        bounds.rects()[1],
+        Box2D {
+            min: point(5.0, 5.0),
+            max: point(7.0, 6.0)
+        }
+    );
+    assert_eq!(bounds.event_range(1), 4..7);
+    assert_eq!(bounds.sub_path_containing(6), Some(1));
+    assert_eq!(bounds.sub_path_containing(10), None);
+    assert_eq!(
+        bounds.hit_test(point(10.5, 0.0)).collect::<Vec<_>>(),
+        vec![2]
+    );
+    let area = Box2D {
+        min: point(0.5, 0.5),
+        max: point(5.0, 5.0),
+    };
+    assert_eq!(bounds.intersecting(&area).collect::<Vec<_>>(), vec![0, 1]);
+
+    // Drag the endpoint of the quadratic curve.
+    let mut history = PathHistory::new(path);
+    let drag = PathPatch::from_ops(vec![PatchOp::Move {
+        index: 5,
+        event: Event::Quadratic {
+            from: point(5.0, 5.0),
+            ctrl: point(6.0, 7.0),
+            to: point(9.0, 3.0),
+        },
+    }]);
+    history.apply(&drag).unwrap();
+    let rects = history.sub_path_bounds().rects().to_vec();
+    assert_eq!(rects, SubPathBounds::from_path(history.path()).rects());
+    assert_eq!(rects[1].max.x, 9.0);
+
+    history.undo();
+    assert_eq!(history.sub_path_bounds().rects(), bounds.rects());
+
+    // Structural changes.
+    history.append(|builder| {
+        builder.begin(point(20.0, 20.0));
+        builder.end(false);
+    });
+    let rects = history.sub_path_bounds().rects();
+    assert_eq!(rects.len(), 4);
+    assert_eq!(
+        rects[3],
+        Box2D {
+            min: point(20.0, 20.0),
+            max: point(20.0, 20.0)
+        }
+    );
+}
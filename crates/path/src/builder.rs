@@ -80,11 +80,14 @@
 //!
 
 use crate::events::{Event, PathEvent};
-use crate::geom::{traits::Transformation, Arc, ArcFlags, LineSegment, SvgArc};
+use crate::geom::{
+    traits::Transformation, Arc, ArcFlags, CubicBezierSegment, LineSegment, QuadraticBezierSegment,
+    SvgArc,
+};
 use crate::math::*;
 use crate::path::Verb;
 use crate::polygon::Polygon;
-use crate::{Attributes, EndpointId, Winding, NO_ATTRIBUTES};
+use crate::{AttributeIndex, Attributes, EndpointId, Winding, NO_ATTRIBUTES};
 
 use std::marker::Sized;
 
@@ -236,6 +239,45 @@ impl<B: PathBuilder> NoAttributes<B> {
         self.inner.add_polygon(polygon, NO_ATTRIBUTES);
     }
 
+    /// Adds a closed sub-path from a ring of points, oriented with the requested winding.
+    ///
+    /// The points are reversed if their orientation does not match `winding`. If the
+    /// last point is equal to the first one (as is the case in GeoJSON and Shapefile
+    /// rings), it is skipped. Rings with less than three distinct points are ignored.
+    ///
+    /// There must be no sub-path in progress when this method is called.
+    /// No sub-path is in progress after the method is called.
+    pub fn add_ring(&mut self, ring: &[Point], winding: Winding) {
+        let mut ring = ring;
+        if ring.len() > 1 && ring[0] == ring[ring.len() - 1] {
+            ring = &ring[..ring.len() - 1];
+        }
+
+        if ring.len() < 3 {
+            return;
+        }
+
+        let orientation = if ring_signed_area(ring) >= 0.0 {
+            Winding::Positive
+        } else {
+            Winding::Negative
+        };
+
+        self.reserve(ring.len(), 0);
+        if orientation == winding {
+            self.begin(ring[0]);
+            for p in &ring[1..] {
+                self.line_to(*p);
+            }
+        } else {
+            self.begin(ring[ring.len() - 1]);
+            for p in ring[..ring.len() - 1].iter().rev() {
+                self.line_to(*p);
+            }
+        }
+        self.close();
+    }
+
     /// Adds a sub-path containing a single point.
     ///
     /// There must be no sub-path in progress when this method is called.
@@ -331,6 +373,25 @@ impl<B: PathBuilder> NoAttributes<B> {
         }
     }
 
+    /// Returns a builder that subdivides segments so that they follow the provided
+    /// interpolation (for example a great circle in a map projection).
+    ///
+    /// See `Densified`.
+    #[inline]
+    pub fn densified<Interpolate>(
+        self,
+        tolerance: f32,
+        interpolate: Interpolate,
+    ) -> NoAttributes<Densified<B, Interpolate>>
+    where
+        B: Sized,
+        Interpolate: Fn(Point, Point, f32) -> Point,
+    {
+        NoAttributes {
+            inner: Densified::new(self.inner, tolerance, interpolate),
+        }
+    }
+
     /// Returns a builder that support SVG commands.
     ///
     /// This must be called before starting to add any sub-path.
@@ -702,6 +763,33 @@ pub trait PathBuilder {
         Transformed::new(self, transform)
     }
 
+    /// Returns a builder that subdivides segments so that they follow the provided
+    /// interpolation.
+    ///
+    /// See `Densified`.
+    fn densified<Interpolate>(
+        self,
+        tolerance: f32,
+        interpolate: Interpolate,
+    ) -> Densified<Self, Interpolate>
+    where
+        Self: Sized,
+        Interpolate: Fn(Point, Point, f32) -> Point,
+    {
+        Densified::new(self, tolerance, interpolate)
+    }
+
+    /// Returns a builder that writes a per-sub-path order value into the last
+    /// custom attribute of each endpoint.
+    ///
+    /// See `WithSubPathOrder`.
+    fn with_sub_path_order(self) -> WithSubPathOrder<Self>
+    where
+        Self: Sized,
+    {
+        WithSubPathOrder::new(self)
+    }
+
     /// Returns a builder that support SVG commands.
     ///
     /// This must be called before starting to add any sub-path.
@@ -1131,6 +1219,357 @@ where
     }
 }
 
+/// A builder that subdivides segments according to a user-provided interpolation.
+///
+/// Positions are provided in a source space (for example longitude and latitude) and
+/// the `interpolate(from, to, t)` callback returns the position in the output space
+/// of the point at `t` (between 0 and 1) along the source segment from `from` to `to`.
+/// Using a great circle interpolation followed by a web-mercator projection, long edges
+/// of map geometry are turned into curves that stay accurate after projection.
+///
+/// Segments are recursively split in the middle until the projected middle point is
+/// within `tolerance` of the projected chord. Curves are flattened in the source space
+/// with the same tolerance before being subdivided. Custom attributes are linearly
+/// interpolated along the subdivided segments.
+///
+/// # Example
+///
+/// ```
+/// use lyon_path::Path;
+/// use lyon_path::math::point;
+///
+/// // Project the x coordinate onto a parabola.
+/// let mut builder = Path::builder().densified(0.01, |from, to, t| {
+///     let p = from.lerp(to, t);
+///     point(p.x, p.y + p.x * p.x)
+/// });
+///
+/// builder.begin(point(-1.0, 0.0));
+/// builder.line_to(point(1.0, 0.0));
+/// builder.end(false);
+///
+/// let path = builder.build();
+/// ```
+pub struct Densified<Builder, Interpolate> {
+    builder: Builder,
+    interpolate: Interpolate,
+    tolerance: f32,
+    current_position: Point,
+    current_projected_position: Point,
+    prev_attributes: Vec<f32>,
+    curve_attributes: Vec<f32>,
+    attribute_buffer: Vec<f32>,
+    splits: Vec<(f32, Point)>,
+    flattened: Vec<(f32, Point)>,
+}
+
+/// Maximum number of recursive subdivisions of a single segment.
+const MAX_DENSIFY_DEPTH: u32 = 16;
+
+impl<Builder, Interpolate> Densified<Builder, Interpolate>
+where
+    Builder: PathBuilder,
+    Interpolate: Fn(Point, Point, f32) -> Point,
+{
+    pub fn new(builder: Builder, tolerance: f32, interpolate: Interpolate) -> Self {
+        let n = builder.num_attributes();
+        Densified {
+            builder,
+            interpolate,
+            tolerance,
+            current_position: point(0.0, 0.0),
+            current_projected_position: point(0.0, 0.0),
+            prev_attributes: vec![0.0; n],
+            curve_attributes: vec![0.0; n],
+            attribute_buffer: vec![0.0; n],
+            splits: Vec::new(),
+            flattened: Vec::new(),
+        }
+    }
+
+    pub fn build(self) -> Builder::PathType
+    where
+        Builder: Build,
+    {
+        self.builder.build()
+    }
+
+    pub fn set_tolerance(&mut self, tolerance: f32) {
+        self.tolerance = tolerance
+    }
+
+    fn densify_line(&mut self, to: Point, attributes: Attributes) -> EndpointId {
+        let from = self.current_position;
+        let p0 = self.current_projected_position;
+        let p1 = (self.interpolate)(from, to, 1.0);
+
+        self.splits.clear();
+        let mut stack = vec![(0.0, 1.0, p0, p1, 0)];
+        while let Some((t0, t1, p0, p1, depth)) = stack.pop() {
+            let t = (t0 + t1) * 0.5;
+            let mid = (self.interpolate)(from, to, t);
+            if depth >= MAX_DENSIFY_DEPTH || (mid - p0.lerp(p1, 0.5)).length() <= self.tolerance {
+                continue;
+            }
+            // Push the second half first so that the splits come out in order.
+            stack.push((t, t1, mid, p1, depth + 1));
+            stack.push((t0, t, p0, mid, depth + 1));
+            self.splits.push((t, mid));
+        }
+        self.splits.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+        for &(t, position) in &self.splits {
+            for (i, attr) in self.attribute_buffer.iter_mut().enumerate() {
+                *attr = self.prev_attributes[i] * (1.0 - t) + attributes[i] * t;
+            }
+            self.builder.line_to(position, &self.attribute_buffer);
+        }
+
+        let id = self.builder.line_to(p1, attributes);
+        self.current_position = to;
+        self.current_projected_position = p1;
+        self.prev_attributes.copy_from_slice(attributes);
+
+        id
+    }
+
+    fn densify_flattened_curve(&mut self, attributes: Attributes) -> EndpointId {
+        self.curve_attributes.copy_from_slice(&self.prev_attributes);
+        let flattened = std::mem::take(&mut self.flattened);
+        let mut attribute_buffer = vec![0.0; attributes.len()];
+        let mut id = EndpointId::INVALID;
+        for &(t, to) in &flattened {
+            if t == 1.0 {
+                id = self.densify_line(to, attributes);
+            } else {
+                for (i, attr) in attribute_buffer.iter_mut().enumerate() {
+                    *attr = self.curve_attributes[i] * (1.0 - t) + attributes[i] * t;
+                }
+                id = self.densify_line(to, &attribute_buffer);
+            }
+        }
+        self.flattened = flattened;
+
+        id
+    }
+}
+
+impl<Builder: Build, Interpolate> Build for Densified<Builder, Interpolate> {
+    type PathType = Builder::PathType;
+
+    #[inline]
+    fn build(self) -> Builder::PathType {
+        self.builder.build()
+    }
+}
+
+impl<Builder, Interpolate> PathBuilder for Densified<Builder, Interpolate>
+where
+    Builder: PathBuilder,
+    Interpolate: Fn(Point, Point, f32) -> Point,
+{
+    fn num_attributes(&self) -> usize {
+        self.builder.num_attributes()
+    }
+
+    fn begin(&mut self, at: Point, attributes: Attributes) -> EndpointId {
+        self.current_position = at;
+        self.current_projected_position = (self.interpolate)(at, at, 0.0);
+        self.prev_attributes.copy_from_slice(attributes);
+        self.builder
+            .begin(self.current_projected_position, attributes)
+    }
+
+    fn end(&mut self, close: bool) {
+        self.builder.end(close)
+    }
+
+    fn line_to(&mut self, to: Point, attributes: Attributes) -> EndpointId {
+        self.densify_line(to, attributes)
+    }
+
+    fn quadratic_bezier_to(
+        &mut self,
+        ctrl: Point,
+        to: Point,
+        attributes: Attributes,
+    ) -> EndpointId {
+        let curve = QuadraticBezierSegment {
+            from: self.current_position,
+            ctrl,
+            to,
+        };
+        self.flattened.clear();
+        let flattened = &mut self.flattened;
+        curve.for_each_flattened_with_t(self.tolerance, &mut |line, t| {
+            flattened.push((t.end, line.to));
+        });
+
+        self.densify_flattened_curve(attributes)
+    }
+
+    fn cubic_bezier_to(
+        &mut self,
+        ctrl1: Point,
+        ctrl2: Point,
+        to: Point,
+        attributes: Attributes,
+    ) -> EndpointId {
+        let curve = CubicBezierSegment {
+            from: self.current_position,
+            ctrl1,
+            ctrl2,
+            to,
+        };
+        self.flattened.clear();
+        let flattened = &mut self.flattened;
+        curve.for_each_flattened_with_t(self.tolerance, &mut |line, t| {
+            flattened.push((t.end, line.to));
+        });
+
+        self.densify_flattened_curve(attributes)
+    }
+
+    fn reserve(&mut self, endpoints: usize, ctrl_points: usize) {
+        self.builder.reserve(endpoints + ctrl_points * 4, 0);
+    }
+}
+
+/// Assigns an order value (for example a depth) to each sub-path.
+///
+/// The order is written into the last custom attribute of the wrapped builder, which
+/// must therefore have at least one custom attribute. That attribute is hidden from
+/// the users of this adapter: a wrapped builder with `n + 1` attributes is used as a
+/// builder with `n` attributes.
+///
+/// Since all endpoints of a sub-path share the same value, the order is preserved by
+/// the interpolation of custom attributes in the tessellators. See
+/// `FillVertex::sub_path_order` and `StrokeVertex::sub_path_order`.
+///
+/// # Example
+///
+/// ```
+/// use lyon_path::{Path, geom::point};
+/// use lyon_path::builder::PathBuilder;
+///
+/// let mut builder = Path::builder_with_attributes(1).with_sub_path_order();
+///
+/// builder.set_order(1.0);
+/// builder.begin(point(0.0, 0.0), &[]);
+/// builder.line_to(point(1.0, 0.0), &[]);
+/// builder.end(false);
+///
+/// builder.set_order(2.0);
+/// builder.begin(point(0.0, 1.0), &[]);
+/// builder.line_to(point(1.0, 1.0), &[]);
+/// builder.end(false);
+///
+/// let path = builder.build();
+/// ```
+pub struct WithSubPathOrder<Builder> {
+    builder: Builder,
+    order: f32,
+    attributes: Vec<f32>,
+}
+
+impl<Builder: PathBuilder> WithSubPathOrder<Builder> {
+    pub fn new(builder: Builder) -> Self {
+        let n = builder.num_attributes();
+        assert!(n > 0, "The sub-path order requires a custom attribute");
+        WithSubPathOrder {
+            builder,
+            order: 0.0,
+            attributes: vec![0.0; n],
+        }
+    }
+
+    /// Sets the order of the sub-paths that are started after this call.
+    #[inline]
+    pub fn set_order(&mut self, order: f32) {
+        self.order = order;
+    }
+
+    /// Returns the current order.
+    #[inline]
+    pub fn order(&self) -> f32 {
+        self.order
+    }
+
+    /// Index of the custom attribute containing the order in the built path.
+    #[inline]
+    pub fn order_attribute(&self) -> AttributeIndex {
+        self.attributes.len() - 1
+    }
+
+    pub fn build(self) -> Builder::PathType
+    where
+        Builder: Build,
+    {
+        self.builder.build()
+    }
+
+    fn set_attributes(&mut self, attributes: Attributes) {
+        let n = self.attributes.len() - 1;
+        self.attributes[..n].copy_from_slice(attributes);
+        self.attributes[n] = self.order;
+    }
+}
+
+impl<Builder: Build> Build for WithSubPathOrder<Builder> {
+    type PathType = Builder::PathType;
+
+    #[inline]
+    fn build(self) -> Builder::PathType {
+        self.builder.build()
+    }
+}
+
+impl<Builder: PathBuilder> PathBuilder for WithSubPathOrder<Builder> {
+    fn num_attributes(&self) -> usize {
+        self.attributes.len() - 1
+    }
+
+    fn begin(&mut self, at: Point, attributes: Attributes) -> EndpointId {
+        self.set_attributes(attributes);
+        self.builder.begin(at, &self.attributes)
+    }
+
+    fn end(&mut self, close: bool) {
+        self.builder.end(close)
+    }
+
+    fn line_to(&mut self, to: Point, attributes: Attributes) -> EndpointId {
+        self.set_attributes(attributes);
+        self.builder.line_to(to, &self.attributes)
+    }
+
+    fn quadratic_bezier_to(
+        &mut self,
+        ctrl: Point,
+        to: Point,
+        attributes: Attributes,
+    ) -> EndpointId {
+        self.set_attributes(attributes);
+        self.builder.quadratic_bezier_to(ctrl, to, &self.attributes)
+    }
+
+    fn cubic_bezier_to(
+        &mut self,
+        ctrl1: Point,
+        ctrl2: Point,
+        to: Point,
+        attributes: Attributes,
+    ) -> EndpointId {
+        self.set_attributes(attributes);
+        self.builder
+            .cubic_bezier_to(ctrl1, ctrl2, to, &self.attributes)
+    }
+
+    fn reserve(&mut self, endpoints: usize, ctrl_points: usize) {
+        self.builder.reserve(endpoints, ctrl_points);
+    }
+}
+
 /// Implements an SVG-like building interface on top of a PathBuilder.
 pub struct WithSvg<Builder: PathBuilder> {
     builder: Builder,
@@ -1496,6 +1935,18 @@ impl<Builder: PathBuilder> SvgPathBuilder for WithSvg<Builder> {
 }
 
 /// Tessellate the stroke for an axis-aligned rounded rectangle.
+/// Twice the signed area of a ring, positive for positive winding.
+fn ring_signed_area(ring: &[Point]) -> f32 {
+    let mut area = 0.0;
+    let mut prev = ring[ring.len() - 1];
+    for p in ring {
+        area += prev.x * p.y - p.x * prev.y;
+        prev = *p;
+    }
+
+    area
+}
+
 fn add_circle<Builder: PathBuilder>(
     builder: &mut Builder,
     center: Point,
@@ -1795,3 +2246,44 @@ fn straight_line_arc() {
         point(100.0, 0.0),
     );
 }
+
+#[test]
+fn densified_line() {
+    use crate::Path;
+
+    let mut builder = Path::builder_with_attributes(1).densified(0.01, |from, to, t| {
+        let p = from.lerp(to, t);
+        point(p.x, p.y + p.x * p.x)
+    });
+
+    builder.begin(point(-1.0, 0.0), &[0.0]);
+    builder.line_to(point(1.0, 0.0), &[1.0]);
+    builder.line_to(point(1.0, 1.0), &[2.0]);
+    builder.end(false);
+
+    let path = builder.build();
+
+    let mut num_edges = 0;
+    for evt in path.iter_with_attributes() {
+        match evt {
+            Event::Begin { at } => {
+                assert_eq!(at, (point(-1.0, 1.0), &[0.0][..]));
+            }
+            Event::Line { to: (to, attr), .. } => {
+                num_edges += 1;
+                if to.y <= 1.0 {
+                    // Points follow the projected curve and attributes are
+                    // interpolated along the source segment.
+                    assert!((to.y - to.x * to.x).abs() < 0.0001);
+                    assert!((attr[0] - (to.x + 1.0) * 0.5).abs() < 0.0001);
+                }
+            }
+            Event::End { last, .. } => {
+                assert_eq!(last, (point(1.0, 2.0), &[2.0][..]));
+            }
+            _ => panic!(),
+        }
+    }
+
+    assert!(num_edges > 8);
+}
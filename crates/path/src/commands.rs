@@ -323,20 +323,7 @@ impl<'l> PathCommandsSlice<'l> {
 
 impl<'l> fmt::Debug for PathCommandsSlice<'l> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "\"")?;
-        for evt in self.iter() {
-            match evt {
-                IdEvent::Line { to, .. } => write!(f, "L {:?}", to),
-                IdEvent::Quadratic { ctrl, to, .. } => write!(f, "Q {:?} {:?} ", ctrl, to),
-                IdEvent::Cubic {
-                    ctrl1, ctrl2, to, ..
-                } => write!(f, "C {:?} {:?} {:?} ", ctrl1, ctrl2, to),
-                IdEvent::Begin { at, .. } => write!(f, "M {:?} ", at),
-                IdEvent::End { close: true, .. } => write!(f, "Z "),
-                IdEvent::End { close: false, .. } => Ok(()),
-            }?;
-        }
-        write!(f, "\"")
+        crate::events::debug_events(f, self.iter())
     }
 }
 
@@ -393,20 +380,18 @@ where
     ControlPoint: fmt::Debug,
 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{{ ")?;
-        for evt in self.events() {
-            match evt {
-                Event::Line { to, .. } => write!(f, "L {:?}", to),
-                Event::Quadratic { ctrl, to, .. } => write!(f, "Q {:?} {:?} ", ctrl, to),
-                Event::Cubic {
-                    ctrl1, ctrl2, to, ..
-                } => write!(f, "C {:?} {:?} {:?} ", ctrl1, ctrl2, to),
-                Event::Begin { at, .. } => write!(f, "M {:?} ", at),
-                Event::End { close: true, .. } => write!(f, "Z "),
-                Event::End { close: false, .. } => Ok(()),
-            }?;
-        }
-        write!(f, "}}")
+        crate::events::debug_events(f, self.events())
+    }
+}
+
+/// Writes the path with the SVG path syntax, see the `Display` implementation of `Path`.
+impl<'l, Endpoint, ControlPoint> fmt::Display for CommandsPathSlice<'l, Endpoint, ControlPoint>
+where
+    Endpoint: Position,
+    ControlPoint: Position,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        crate::events::write_svg(f, self.events().map(|event| event.with_points()))
     }
 }
 
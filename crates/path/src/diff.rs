@@ -0,0 +1,507 @@
+//! Structural differences between paths.
+//!
+//! Collaborative editors and undo systems typically modify a handful of points of a
+//! large path at a time. Rather than transmitting or storing the whole path after each
+//! modification, a [`PathPatch`](struct.PathPatch.html) describes the events that were
+//! added, removed or moved, referring to the events of the original path by index.
+//!
+//! Events are compared by their verb and the positions they introduce: the `from`
+//! position of an event follows from the previous event and is ignored.
+//! Custom attributes are not supported.
+//!
+//! # Example
+//!
+//! ```
+//! use lyon_path::Path;
+//! use lyon_path::diff::PathPatch;
+//! use lyon_path::math::point;
+//!
+//! let mut builder = Path::builder();
+//! builder.begin(point(0.0, 0.0));
+//! builder.line_to(point(10.0, 0.0));
+//! builder.line_to(point(10.0, 10.0));
+//! builder.close();
+//! let before = builder.build();
+//!
+//! let mut builder = Path::builder();
+//! builder.begin(point(0.0, 0.0));
+//! builder.line_to(point(10.0, 0.0));
+//! builder.line_to(point(12.0, 10.0));
+//! builder.line_to(point(0.0, 10.0));
+//! builder.close();
+//! let after = builder.build();
+//!
+//! let patch = PathPatch::diff(before.iter(), after.iter());
+//! // One moved event and one inserted event.
+//! assert_eq!(patch.ops().len(), 2);
+//!
+//! let patched = patch.apply(before.iter()).unwrap();
+//! assert!(patched.iter().eq(after.iter()));
+//! ```
+
+use crate::{Event, Path, PathEvent};
+
+use std::fmt;
+
+/// A modification of the events of a path.
+///
+/// Indices refer to the events of the original path. Events that are not mentioned
+/// by any operation are kept unchanged.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum PatchOp {
+    /// Removes `count` events, starting at the event `index`.
+    Remove { index: u32, count: u32 },
+    /// Inserts an event before the event `index`.
+    ///
+    /// An index equal to the number of events appends the event at the end.
+    Insert { index: u32, event: PathEvent },
+    /// Replaces the event `index` with an event of the same kind at different positions.
+    Move { index: u32, event: PathEvent },
+}
+
+impl PatchOp {
+    /// Index of the event of the original path this operation applies to.
+    #[inline]
+    pub fn index(&self) -> u32 {
+        match *self {
+            PatchOp::Remove { index, .. }
+            | PatchOp::Insert { index, .. }
+            | PatchOp::Move { index, .. } => index,
+        }
+    }
+}
+
+/// Errors that can happen while applying a patch.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PatchError {
+    /// The operation refers to an event past the end of the original path, or
+    /// operations are not sorted by index.
+    OutOfBounds { op: usize },
+    /// The operation moves an event of a different kind.
+    Mismatch { op: usize },
+    /// The patched sequence of events does not form a valid path.
+    InvalidSequence { op: usize },
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PatchError::OutOfBounds { op } => write!(f, "Operation {} is out of bounds", op),
+            PatchError::Mismatch { op } => {
+                write!(f, "Operation {} does not match the original event", op)
+            }
+            PatchError::InvalidSequence { op } => {
+                write!(f, "Invalid sequence of events after operation {}", op)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+/// A set of changes transforming a path into another one.
+///
+/// See the [module documentation](index.html).
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct PathPatch {
+    ops: Vec<PatchOp>,
+}
+
+impl PathPatch {
+    /// Creates an empty patch.
+    pub fn new() -> Self {
+        PathPatch::default()
+    }
+
+    /// Creates a patch from a sequence of operations sorted by index.
+    ///
+    /// Operations are validated when the patch is applied.
+    pub fn from_ops(ops: Vec<PatchOp>) -> Self {
+        PathPatch { ops }
+    }
+
+    /// Computes the changes between two sequences of path events.
+    ///
+    /// Unchanged events at the beginning and end of the paths are skipped in linear
+    /// time. The remaining range is compared in time and memory proportional to the
+    /// product of its lengths in both paths, so localized edits of large paths are cheap.
+    pub fn diff(
+        original: impl IntoIterator<Item = PathEvent>,
+        modified: impl IntoIterator<Item = PathEvent>,
+    ) -> Self {
+        let a: Vec<PathEvent> = original.into_iter().collect();
+        let b: Vec<PathEvent> = modified.into_iter().collect();
+
+        let prefix = a
+            .iter()
+            .zip(b.iter())
+            .take_while(|(ea, eb)| same_event(ea, eb))
+            .count();
+        let suffix = a[prefix..]
+            .iter()
+            .rev()
+            .zip(b[prefix..].iter().rev())
+            .take_while(|(ea, eb)| same_event(ea, eb))
+            .count();
+
+        let a_mid = &a[prefix..a.len() - suffix];
+        let b_mid = &b[prefix..b.len() - suffix];
+        let n = a_mid.len();
+        let m = b_mid.len();
+
+        // Longest common subsequence of events of the same kind, favoring identical
+        // events over moved ones.
+        let weight = |i: usize, j: usize| {
+            if same_event(&a_mid[i], &b_mid[j]) {
+                2
+            } else if kind(&a_mid[i]) == kind(&b_mid[j]) {
+                1
+            } else {
+                0
+            }
+        };
+        let w = m + 1;
+        let mut scores = vec![0u32; (n + 1) * (m + 1)];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                let mut score = scores[(i + 1) * w + j].max(scores[i * w + j + 1]);
+                let pair = weight(i, j);
+                if pair > 0 {
+                    score = score.max(scores[(i + 1) * w + j + 1] + pair);
+                }
+                scores[i * w + j] = score;
+            }
+        }
+
+        let mut patch = PathPatch::new();
+        let (mut i, mut j) = (0, 0);
+        while i < n || j < m {
+            let index = (prefix + i) as u32;
+            if i < n && j < m {
+                let pair = weight(i, j);
+                if pair > 0 && scores[i * w + j] == scores[(i + 1) * w + j + 1] + pair {
+                    if pair == 1 {
+                        patch.ops.push(PatchOp::Move {
+                            index,
+                            event: b_mid[j],
+                        });
+                    }
+                    i += 1;
+                    j += 1;
+                    continue;
+                }
+            }
+
+            if j == m || (i < n && scores[(i + 1) * w + j] >= scores[i * w + j + 1]) {
+                patch.remove(index);
+                i += 1;
+            } else {
+                patch.ops.push(PatchOp::Insert {
+                    index,
+                    event: b_mid[j],
+                });
+                j += 1;
+            }
+        }
+
+        patch
+    }
+
+    /// The operations of the patch, sorted by index.
+    #[inline]
+    pub fn ops(&self) -> &[PatchOp] {
+        &self.ops
+    }
+
+    /// Returns true if the patch does not change anything.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// Applies the patch to the events of the original path and builds the result.
+    pub fn apply(&self, original: impl IntoIterator<Item = PathEvent>) -> Result<Path, PatchError> {
+        let original: Vec<PathEvent> = original.into_iter().collect();
+        let mut output = Output {
+            builder: Path::builder(),
+            in_sub_path: false,
+        };
+
+        let mut cursor = 0;
+        for (op_idx, op) in self.ops.iter().enumerate() {
+            let index = op.index() as usize;
+            if index < cursor || index > original.len() {
+                return Err(PatchError::OutOfBounds { op: op_idx });
+            }
+
+            // Copy the unchanged events preceding this operation.
+            for event in &original[cursor..index] {
+                output.push(event, op_idx)?;
+            }
+            cursor = index;
+
+            match op {
+                PatchOp::Remove { count, .. } => {
+                    cursor = index + *count as usize;
+                    if cursor > original.len() {
+                        return Err(PatchError::OutOfBounds { op: op_idx });
+                    }
+                }
+                PatchOp::Insert { event, .. } => {
+                    output.push(event, op_idx)?;
+                }
+                PatchOp::Move { event, .. } => {
+                    if index == original.len() {
+                        return Err(PatchError::OutOfBounds { op: op_idx });
+                    }
+                    if kind(&original[index]) != kind(event) {
+                        return Err(PatchError::Mismatch { op: op_idx });
+                    }
+                    output.push(event, op_idx)?;
+                    cursor = index + 1;
+                }
+            }
+        }
+
+        let last_op = self.ops.len().saturating_sub(1);
+        for event in &original[cursor..] {
+            output.push(event, last_op)?;
+        }
+        if output.in_sub_path {
+            return Err(PatchError::InvalidSequence { op: last_op });
+        }
+
+        Ok(output.builder.build())
+    }
+
+    /// Computes the patch that undoes this one.
+    ///
+    /// `original` is the sequence of events this patch applies to. The returned patch
+    /// applies to the result of this patch and restores the original events.
+    pub fn inverse(
+        &self,
+        original: impl IntoIterator<Item = PathEvent>,
+    ) -> Result<PathPatch, PatchError> {
+        let original: Vec<PathEvent> = original.into_iter().collect();
+        let mut inverse = PathPatch::new();
+        // Number of events inserted minus the number of events removed so far.
+        let mut offset: i64 = 0;
+        let mut cursor = 0;
+        for (op_idx, op) in self.ops.iter().enumerate() {
+            let index = op.index() as usize;
+            if index < cursor || index > original.len() {
+                return Err(PatchError::OutOfBounds { op: op_idx });
+            }
+            cursor = index;
+
+            let new_index = (index as i64 + offset) as u32;
+            match *op {
+                PatchOp::Remove { count, .. } => {
+                    let end = index + count as usize;
+                    if end > original.len() {
+                        return Err(PatchError::OutOfBounds { op: op_idx });
+                    }
+                    for event in &original[index..end] {
+                        inverse.ops.push(PatchOp::Insert {
+                            index: new_index,
+                            event: *event,
+                        });
+                    }
+                    offset -= count as i64;
+                    cursor = end;
+                }
+                PatchOp::Insert { .. } => {
+                    inverse.remove(new_index);
+                    offset += 1;
+                }
+                PatchOp::Move { .. } => {
+                    if index == original.len() {
+                        return Err(PatchError::OutOfBounds { op: op_idx });
+                    }
+                    inverse.ops.push(PatchOp::Move {
+                        index: new_index,
+                        event: original[index],
+                    });
+                    cursor = index + 1;
+                }
+            }
+        }
+
+        Ok(inverse)
+    }
+
+    fn remove(&mut self, index: u32) {
+        if let Some(PatchOp::Remove {
+            index: first,
+            count,
+        }) = self.ops.last_mut()
+        {
+            if *first + *count == index {
+                *count += 1;
+                return;
+            }
+        }
+
+        self.ops.push(PatchOp::Remove { index, count: 1 });
+    }
+}
+
+struct Output {
+    builder: crate::path::Builder,
+    in_sub_path: bool,
+}
+
+impl Output {
+    fn push(&mut self, event: &PathEvent, op: usize) -> Result<(), PatchError> {
+        let is_begin = matches!(event, Event::Begin { .. });
+        if is_begin == self.in_sub_path {
+            return Err(PatchError::InvalidSequence { op });
+        }
+
+        match *event {
+            Event::Begin { at } => {
+                self.builder.begin(at);
+                self.in_sub_path = true;
+            }
+            Event::Line { to, .. } => {
+                self.builder.line_to(to);
+            }
+            Event::Quadratic { ctrl, to, .. } => {
+                self.builder.quadratic_bezier_to(ctrl, to);
+            }
+            Event::Cubic {
+                ctrl1, ctrl2, to, ..
+            } => {
+                self.builder.cubic_bezier_to(ctrl1, ctrl2, to);
+            }
+            Event::End { close, .. } => {
+                self.builder.end(close);
+                self.in_sub_path = false;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn kind(event: &PathEvent) -> u8 {
+    match event {
+        Event::Begin { .. } => 0,
+        Event::Line { .. } => 1,
+        Event::Quadratic { .. } => 2,
+        Event::Cubic { .. } => 3,
+        Event::End { close: false, .. } => 4,
+        Event::End { close: true, .. } => 5,
+    }
+}
+
+/// Compares the verbs and the positions introduced by two events.
+fn same_event(a: &PathEvent, b: &PathEvent) -> bool {
+    match (a, b) {
+        (Event::Begin { at: a }, Event::Begin { at: b }) => a == b,
+        (Event::Line { to: a, .. }, Event::Line { to: b, .. }) => a == b,
+        (
+            Event::Quadratic {
+                ctrl: ca, to: ta, ..
+            },
+            Event::Quadratic {
+                ctrl: cb, to: tb, ..
+            },
+        ) => ca == cb && ta == tb,
+        (
+            Event::Cubic {
+                ctrl1: c1a,
+                ctrl2: c2a,
+                to: ta,
+                ..
+            },
+            Event::Cubic {
+                ctrl1: c1b,
+                ctrl2: c2b,
+                to: tb,
+                ..
+            },
+        ) => c1a == c1b && c2a == c2b && ta == tb,
+        (Event::End { close: a, .. }, Event::End { close: b, .. }) => a == b,
+        _ => false,
+    }
+}
+
+#[test]
+fn diff_and_apply() {
+    use crate::math::point;
+
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.quadratic_bezier_to(point(2.0, 0.0), point(2.0, 1.0));
+    builder.line_to(point(1.0, 2.0));
+    builder.close();
+    builder.begin(point(5.0, 5.0));
+    builder.line_to(point(6.0, 5.0));
+    builder.end(false);
+    let original = builder.build();
+
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.quadratic_bezier_to(point(3.0, 0.0), point(2.0, 1.0));
+    builder.close();
+    builder.begin(point(5.0, 5.0));
+    builder.line_to(point(6.0, 5.0));
+    builder.line_to(point(6.0, 6.0));
+    builder.end(false);
+    let modified = builder.build();
+
+    let patch = PathPatch::diff(original.iter(), modified.iter());
+    assert_eq!(
+        patch.ops(),
+        &[
+            PatchOp::Move {
+                index: 2,
+                event: modified.iter().nth(2).unwrap(),
+            },
+            PatchOp::Remove { index: 3, count: 1 },
+            PatchOp::Insert {
+                index: 7,
+                event: modified.iter().nth(6).unwrap(),
+            },
+        ]
+    );
+
+    let patched = patch.apply(original.iter()).unwrap();
+    assert!(patched.iter().eq(modified.iter()));
+
+    // The reverse patch undoes the modification.
+    let undo = PathPatch::diff(modified.iter(), original.iter());
+    let restored = undo.apply(patched.iter()).unwrap();
+    assert!(restored.iter().eq(original.iter()));
+
+    let inverse = patch.inverse(original.iter()).unwrap();
+    let restored = inverse.apply(patched.iter()).unwrap();
+    assert!(restored.iter().eq(original.iter()));
+
+    assert!(PathPatch::diff(original.iter(), original.iter()).is_empty());
+
+    // Patches are validated against the path they are applied to.
+    let bad_move = PathPatch::from_ops(vec![PatchOp::Move {
+        index: 0,
+        event: modified.iter().nth(1).unwrap(),
+    }]);
+    assert_eq!(
+        bad_move.apply(original.iter()).err(),
+        Some(PatchError::Mismatch { op: 0 })
+    );
+    let out_of_bounds = PathPatch::from_ops(vec![PatchOp::Remove { index: 8, count: 1 }]);
+    assert_eq!(
+        out_of_bounds.apply(original.iter()).err(),
+        Some(PatchError::OutOfBounds { op: 0 })
+    );
+    let unbalanced = PathPatch::from_ops(vec![PatchOp::Remove { index: 0, count: 1 }]);
+    assert_eq!(
+        unbalanced.apply(original.iter()).err(),
+        Some(PatchError::InvalidSequence { op: 0 })
+    );
+}
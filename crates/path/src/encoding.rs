@@ -0,0 +1,556 @@
+//! A compact binary representation for paths.
+//!
+//! The generic serde representation of a `Path` stores each coordinate as a 32 bits float
+//! and each verb as a full value, which is convenient but several times larger than
+//! necessary when shipping large sets of paths (for example vector tiles).
+//!
+//! This module provides a compact format in which:
+//!
+//! - coordinates are quantized to fixed-point numbers with a configurable number of
+//!   fractional bits,
+//! - each position is stored as the difference with the previous one,
+//! - deltas are zigzag-encoded and written as variable-length integers (LEB128), so
+//!   that small deltas fit in a single byte.
+//!
+//! Custom attributes are not encoded.
+//!
+//! # Format
+//!
+//! The encoded data starts with a four bytes header: the `b"LP"` magic number, the
+//! format version and the number of fractional bits. It is followed by a sequence of
+//! verbs, stored as one byte each, followed by the deltas of the positions they introduce
+//! (x then y, for each control point and endpoint in order). `End` events do not store
+//! positions.
+//!
+//! # Example
+//!
+//! ```
+//! use lyon_path::Path;
+//! use lyon_path::encoding::{encode, decode};
+//! use lyon_path::math::point;
+//!
+//! let mut builder = Path::builder();
+//! builder.begin(point(0.0, 0.0));
+//! builder.line_to(point(10.0, 0.0));
+//! builder.quadratic_bezier_to(point(10.0, 10.0), point(0.0, 10.0));
+//! builder.close();
+//! let path = builder.build();
+//!
+//! let mut data = Vec::new();
+//! encode(path.iter(), 4, &mut data);
+//!
+//! let decoded = decode(&data).unwrap();
+//! ```
+
+use crate::math::{point, Point};
+use crate::{Event, Path, PathEvent};
+
+use std::fmt;
+
+const MAGIC: [u8; 2] = *b"LP";
+const VERSION: u8 = 1;
+const HEADER_SIZE: usize = 4;
+
+mod verb {
+    pub const BEGIN: u8 = 0;
+    pub const LINE: u8 = 1;
+    pub const QUADRATIC: u8 = 2;
+    pub const CUBIC: u8 = 3;
+    pub const END: u8 = 4;
+    pub const CLOSE: u8 = 5;
+}
+
+/// The maximum number of fractional bits supported by the format.
+pub const MAX_FRACT_BITS: u32 = 24;
+
+/// Errors that can happen while decoding a path.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The data does not start with a valid header.
+    InvalidHeader,
+    /// The format version is not supported.
+    UnsupportedVersion(u8),
+    /// An unknown verb was found at the given byte offset.
+    InvalidVerb { offset: usize },
+    /// The sequence of verbs does not form a valid path at the given byte offset.
+    InvalidSequence { offset: usize },
+    /// A variable-length integer is too large at the given byte offset.
+    Overflow { offset: usize },
+    /// The data ended in the middle of an event or of a sub-path.
+    UnexpectedEnd,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::InvalidHeader => write!(f, "Invalid header"),
+            DecodeError::UnsupportedVersion(v) => write!(f, "Unsupported version {}", v),
+            DecodeError::InvalidVerb { offset } => write!(f, "Invalid verb at offset {}", offset),
+            DecodeError::InvalidSequence { offset } => {
+                write!(f, "Invalid sequence of events at offset {}", offset)
+            }
+            DecodeError::Overflow { offset } => {
+                write!(f, "Integer overflow at offset {}", offset)
+            }
+            DecodeError::UnexpectedEnd => write!(f, "Unexpected end of data"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Encodes a sequence of path events, appending the result to `output`.
+///
+/// Coordinates are rounded to the nearest multiple of `1 / 2^fract_bits`.
+pub fn encode(events: impl IntoIterator<Item = PathEvent>, fract_bits: u32, output: &mut Vec<u8>) {
+    assert!(fract_bits <= MAX_FRACT_BITS);
+
+    output.extend_from_slice(&MAGIC);
+    output.push(VERSION);
+    output.push(fract_bits as u8);
+
+    let mut encoder = PointEncoder {
+        scale: (1u32 << fract_bits) as f32,
+        prev: (0, 0),
+        output,
+    };
+
+    for event in events {
+        match event {
+            Event::Begin { at } => {
+                encoder.output.push(verb::BEGIN);
+                encoder.point(at);
+            }
+            Event::Line { to, .. } => {
+                encoder.output.push(verb::LINE);
+                encoder.point(to);
+            }
+            Event::Quadratic { ctrl, to, .. } => {
+                encoder.output.push(verb::QUADRATIC);
+                encoder.point(ctrl);
+                encoder.point(to);
+            }
+            Event::Cubic {
+                ctrl1, ctrl2, to, ..
+            } => {
+                encoder.output.push(verb::CUBIC);
+                encoder.point(ctrl1);
+                encoder.point(ctrl2);
+                encoder.point(to);
+            }
+            Event::End { close, .. } => {
+                encoder
+                    .output
+                    .push(if close { verb::CLOSE } else { verb::END });
+            }
+        }
+    }
+}
+
+/// Decodes a path.
+///
+/// See also [`Decoder`](struct.Decoder.html) to consume the events without building
+/// a `Path`.
+pub fn decode(data: &[u8]) -> Result<Path, DecodeError> {
+    let mut decoder = Decoder::new(data)?;
+    let mut builder = Path::builder();
+    for event in &mut decoder {
+        builder.path_event(event);
+    }
+
+    if let Some(error) = decoder.error() {
+        return Err(error);
+    }
+
+    Ok(builder.build())
+}
+
+/// An iterator of `PathEvent` decoding the compact binary format on the fly.
+///
+/// This allows passing encoded paths directly to the tessellators without storing the
+/// decoded path in memory.
+///
+/// If the data is malformed, the iteration stops at the first error and the
+/// error can be retrieved with [`error`](#method.error). In that case an `End` event is
+/// produced if a sub-path was in progress, so that the event sequence is always valid.
+///
+/// # Example
+///
+/// ```
+/// use lyon_path::{Path, PathEvent};
+/// use lyon_path::encoding::{encode, Decoder};
+/// use lyon_path::math::point;
+///
+/// let mut builder = Path::builder();
+/// builder.begin(point(0.0, 0.0));
+/// builder.line_to(point(10.0, 0.0));
+/// builder.line_to(point(10.0, 10.0));
+/// builder.close();
+/// let path = builder.build();
+///
+/// let mut data = Vec::new();
+/// encode(path.iter(), 4, &mut data);
+///
+/// let mut decoder = Decoder::new(&data).unwrap();
+/// for event in &mut decoder {
+///     // ...
+/// }
+/// assert!(decoder.error().is_none());
+/// ```
+pub struct Decoder<'l> {
+    points: PointDecoder<'l>,
+    first: Point,
+    current: Point,
+    in_sub_path: bool,
+    error: Option<DecodeError>,
+}
+
+impl<'l> Decoder<'l> {
+    /// Creates a decoder, checking the header of the encoded data.
+    pub fn new(data: &'l [u8]) -> Result<Self, DecodeError> {
+        Ok(Decoder {
+            points: PointDecoder::new(data)?,
+            first: point(0.0, 0.0),
+            current: point(0.0, 0.0),
+            in_sub_path: false,
+            error: None,
+        })
+    }
+
+    /// Returns the error that interrupted the decoding, if any.
+    pub fn error(&self) -> Option<DecodeError> {
+        self.error
+    }
+
+    fn next_event(&mut self) -> Result<Option<PathEvent>, DecodeError> {
+        let offset = self.points.offset;
+        let verb = match self.points.data.get(offset) {
+            Some(verb) => *verb,
+            None if self.in_sub_path => {
+                return Err(DecodeError::UnexpectedEnd);
+            }
+            None => {
+                return Ok(None);
+            }
+        };
+        self.points.offset += 1;
+
+        if verb > verb::CLOSE {
+            return Err(DecodeError::InvalidVerb { offset });
+        }
+        if (verb == verb::BEGIN) == self.in_sub_path {
+            return Err(DecodeError::InvalidSequence { offset });
+        }
+
+        let from = self.current;
+        let event = match verb {
+            verb::BEGIN => {
+                let at = self.points.point()?;
+                self.first = at;
+                self.current = at;
+                self.in_sub_path = true;
+                PathEvent::Begin { at }
+            }
+            verb::LINE => {
+                let to = self.points.point()?;
+                self.current = to;
+                PathEvent::Line { from, to }
+            }
+            verb::QUADRATIC => {
+                let ctrl = self.points.point()?;
+                let to = self.points.point()?;
+                self.current = to;
+                PathEvent::Quadratic { from, ctrl, to }
+            }
+            verb::CUBIC => {
+                let ctrl1 = self.points.point()?;
+                let ctrl2 = self.points.point()?;
+                let to = self.points.point()?;
+                self.current = to;
+                PathEvent::Cubic {
+                    from,
+                    ctrl1,
+                    ctrl2,
+                    to,
+                }
+            }
+            _ => {
+                self.in_sub_path = false;
+                PathEvent::End {
+                    last: from,
+                    first: self.first,
+                    close: verb == verb::CLOSE,
+                }
+            }
+        };
+
+        Ok(Some(event))
+    }
+}
+
+impl<'l> Iterator for Decoder<'l> {
+    type Item = PathEvent;
+
+    fn next(&mut self) -> Option<PathEvent> {
+        if self.error.is_some() {
+            return None;
+        }
+
+        match self.next_event() {
+            Ok(event) => event,
+            Err(error) => {
+                self.error = Some(error);
+                if self.in_sub_path {
+                    self.in_sub_path = false;
+                    return Some(PathEvent::End {
+                        last: self.current,
+                        first: self.first,
+                        close: false,
+                    });
+                }
+
+                None
+            }
+        }
+    }
+}
+
+struct PointEncoder<'l> {
+    scale: f32,
+    prev: (i64, i64),
+    output: &'l mut Vec<u8>,
+}
+
+impl<'l> PointEncoder<'l> {
+    fn point(&mut self, p: Point) {
+        let x = (p.x * self.scale).round() as i32 as i64;
+        let y = (p.y * self.scale).round() as i32 as i64;
+        write_varint(zigzag(x - self.prev.0), self.output);
+        write_varint(zigzag(y - self.prev.1), self.output);
+        self.prev = (x, y);
+    }
+}
+
+struct PointDecoder<'l> {
+    data: &'l [u8],
+    offset: usize,
+    inv_scale: f32,
+    prev: (i64, i64),
+}
+
+impl<'l> PointDecoder<'l> {
+    fn new(data: &'l [u8]) -> Result<Self, DecodeError> {
+        if data.len() < HEADER_SIZE || data[0..2] != MAGIC {
+            return Err(DecodeError::InvalidHeader);
+        }
+        if data[2] != VERSION {
+            return Err(DecodeError::UnsupportedVersion(data[2]));
+        }
+        let fract_bits = data[3] as u32;
+        if fract_bits > MAX_FRACT_BITS {
+            return Err(DecodeError::InvalidHeader);
+        }
+
+        Ok(PointDecoder {
+            data,
+            offset: HEADER_SIZE,
+            inv_scale: 1.0 / (1u32 << fract_bits) as f32,
+            prev: (0, 0),
+        })
+    }
+
+    fn point(&mut self) -> Result<Point, DecodeError> {
+        let dx = unzigzag(self.varint()?);
+        let dy = unzigzag(self.varint()?);
+        let x = self.prev.0.wrapping_add(dx);
+        let y = self.prev.1.wrapping_add(dy);
+        self.prev = (x, y);
+
+        Ok(point(x as f32 * self.inv_scale, y as f32 * self.inv_scale))
+    }
+
+    fn varint(&mut self) -> Result<u64, DecodeError> {
+        let start = self.offset;
+        let mut result: u64 = 0;
+        let mut shift = 0;
+        loop {
+            let byte = *self
+                .data
+                .get(self.offset)
+                .ok_or(DecodeError::UnexpectedEnd)?;
+            self.offset += 1;
+
+            if shift >= 64 || (shift == 63 && byte > 1) {
+                return Err(DecodeError::Overflow { offset: start });
+            }
+
+            result |= ((byte & 0x7F) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+            shift += 7;
+        }
+    }
+}
+
+fn zigzag(val: i64) -> u64 {
+    ((val << 1) ^ (val >> 63)) as u64
+}
+
+fn unzigzag(val: u64) -> i64 {
+    ((val >> 1) as i64) ^ -((val & 1) as i64)
+}
+
+fn write_varint(mut val: u64, output: &mut Vec<u8>) {
+    while val >= 0x80 {
+        output.push((val as u8) | 0x80);
+        val >>= 7;
+    }
+    output.push(val as u8);
+}
+
+#[test]
+fn encode_decode() {
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.5, -3.25));
+    builder.cubic_bezier_to(point(1.0, 2.0), point(3.0, 4.0), point(5.0, 6.0));
+    builder.end(false);
+    builder.begin(point(1000.0, -1000.0));
+    builder.quadratic_bezier_to(point(1001.5, -1000.0), point(1000.0, -999.0));
+    builder.close();
+    let path = builder.build();
+
+    let mut data = Vec::new();
+    encode(path.iter(), 4, &mut data);
+
+    // 4 bytes of header, 6 verbs, 8 points of at most 3 bytes per coordinate.
+    assert!(data.len() <= 4 + 6 + 8 * 2 * 3);
+    // Smaller than the 8 points stored as f32.
+    assert!(data.len() < 8 * 8);
+
+    let decoded = decode(&data).unwrap();
+    let expected: Vec<PathEvent> = path.iter().collect();
+    let events: Vec<PathEvent> = decoded.iter().collect();
+    assert_eq!(events, expected);
+}
+
+#[test]
+fn streaming_decoder_errors() {
+    let mut data = Vec::new();
+    encode(
+        vec![
+            PathEvent::Begin {
+                at: point(0.0, 0.0),
+            },
+            PathEvent::Line {
+                from: point(0.0, 0.0),
+                to: point(1.0, 0.0),
+            },
+            PathEvent::Line {
+                from: point(1.0, 0.0),
+                to: point(1.0, 1.0),
+            },
+            PathEvent::End {
+                last: point(1.0, 1.0),
+                first: point(0.0, 0.0),
+                close: true,
+            },
+        ],
+        0,
+        &mut data,
+    );
+
+    let events: Vec<PathEvent> = Decoder::new(&data).unwrap().collect();
+    assert_eq!(events.len(), 4);
+    assert_eq!(
+        events[3],
+        PathEvent::End {
+            last: point(1.0, 1.0),
+            first: point(0.0, 0.0),
+            close: true
+        }
+    );
+
+    // Truncated data still produces a valid sequence of events.
+    let mut decoder = Decoder::new(&data[..data.len() - 2]).unwrap();
+    let events: Vec<PathEvent> = (&mut decoder).collect();
+    assert_eq!(decoder.error(), Some(DecodeError::UnexpectedEnd));
+    assert_eq!(
+        events,
+        vec![
+            PathEvent::Begin {
+                at: point(0.0, 0.0)
+            },
+            PathEvent::Line {
+                from: point(0.0, 0.0),
+                to: point(1.0, 0.0),
+            },
+            PathEvent::End {
+                last: point(1.0, 0.0),
+                first: point(0.0, 0.0),
+                close: false,
+            },
+        ]
+    );
+}
+
+#[test]
+fn decode_errors() {
+    assert_eq!(decode(&[]).err(), Some(DecodeError::InvalidHeader));
+    assert_eq!(decode(b"LP").err(), Some(DecodeError::InvalidHeader));
+    assert_eq!(
+        decode(&[b'L', b'P', 42, 0]).err(),
+        Some(DecodeError::UnsupportedVersion(42))
+    );
+
+    let mut data = Vec::new();
+    encode(
+        vec![
+            PathEvent::Begin {
+                at: point(0.0, 0.0),
+            },
+            PathEvent::Line {
+                from: point(0.0, 0.0),
+                to: point(100.0, 100.0),
+            },
+            PathEvent::End {
+                last: point(100.0, 100.0),
+                first: point(0.0, 0.0),
+                close: false,
+            },
+        ],
+        8,
+        &mut data,
+    );
+    assert!(decode(&data).is_ok());
+
+    // Truncated in the middle of a position.
+    assert_eq!(
+        decode(&data[..data.len() - 2]).err(),
+        Some(DecodeError::UnexpectedEnd)
+    );
+    // Truncated before the end of the sub-path.
+    assert_eq!(
+        decode(&data[..data.len() - 1]).err(),
+        Some(DecodeError::UnexpectedEnd)
+    );
+
+    // Line outside of a sub-path.
+    assert_eq!(
+        decode(&[b'L', b'P', VERSION, 0, verb::LINE, 0, 0]).err(),
+        Some(DecodeError::InvalidSequence { offset: 4 })
+    );
+    // Unknown verb.
+    assert_eq!(
+        decode(&[b'L', b'P', VERSION, 0, verb::BEGIN, 0, 0, 42]).err(),
+        Some(DecodeError::InvalidVerb { offset: 7 })
+    );
+    // Varint too long.
+    let mut overflow = vec![b'L', b'P', VERSION, 0, verb::BEGIN];
+    overflow.extend_from_slice(&[0xFF; 11]);
+    assert_eq!(
+        decode(&overflow).err(),
+        Some(DecodeError::Overflow { offset: 5 })
+    );
+}
@@ -1,7 +1,10 @@
 use crate::geom::traits::Transformation;
+use crate::geom::{BezierSegment, CubicBezierSegment, LineSegment, QuadraticBezierSegment};
 use crate::math::Point;
 use crate::{ControlPointId, EndpointId, Position};
 
+use std::fmt;
+
 /// Represents an event or edge of path.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
@@ -111,6 +114,45 @@ impl<Ep, Cp> Event<Ep, Cp> {
 }
 
 impl PathEvent {
+    /// Returns the segment corresponding to this event, if any.
+    ///
+    /// `End` events produce the closing line segment if the sub-path is closed.
+    /// `Begin` events and `End` events of open sub-paths produce `None`.
+    pub fn to_segment(&self) -> Option<BezierSegment<f32>> {
+        match *self {
+            Event::Line { from, to } => Some(LineSegment { from, to }.into()),
+            Event::Quadratic { from, ctrl, to } => {
+                Some(QuadraticBezierSegment { from, ctrl, to }.into())
+            }
+            Event::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => Some(
+                CubicBezierSegment {
+                    from,
+                    ctrl1,
+                    ctrl2,
+                    to,
+                }
+                .into(),
+            ),
+            Event::End {
+                last,
+                first,
+                close: true,
+            } => Some(
+                LineSegment {
+                    from: last,
+                    to: first,
+                }
+                .into(),
+            ),
+            Event::Begin { .. } | Event::End { close: false, .. } => None,
+        }
+    }
+
     pub fn transformed<T: Transformation<f32>>(&self, mat: &T) -> Self {
         match self {
             Event::Line { from, to } => Event::Line {
@@ -144,3 +186,170 @@ impl PathEvent {
         }
     }
 }
+
+/// Writes path events with the SVG path syntax, for the `Display` implementation of the
+/// path types.
+///
+/// The precision of the formatter, if any, is the number of decimals of the coordinates, with
+/// the trailing zeros removed.
+pub(crate) fn write_svg<Iter>(formatter: &mut fmt::Formatter, events: Iter) -> fmt::Result
+where
+    Iter: IntoIterator<Item = PathEvent>,
+{
+    let mut separator = "";
+    let mut write = |formatter: &mut fmt::Formatter, cmd: &str, points: &[Point]| {
+        write!(formatter, "{}{}", separator, cmd)?;
+        separator = " ";
+        for p in points {
+            write!(formatter, " ")?;
+            write_svg_number(formatter, p.x)?;
+            write!(formatter, " ")?;
+            write_svg_number(formatter, p.y)?;
+        }
+
+        Ok(())
+    };
+
+    for event in events {
+        match event {
+            Event::Begin { at } => write(formatter, "M", &[at])?,
+            Event::Line { to, .. } => write(formatter, "L", &[to])?,
+            Event::Quadratic { ctrl, to, .. } => write(formatter, "Q", &[ctrl, to])?,
+            Event::Cubic {
+                ctrl1, ctrl2, to, ..
+            } => write(formatter, "C", &[ctrl1, ctrl2, to])?,
+            Event::End { close: true, .. } => write(formatter, "Z", &[])?,
+            Event::End { close: false, .. } => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn write_svg_number(formatter: &mut fmt::Formatter, value: f32) -> fmt::Result {
+    let precision = match formatter.precision() {
+        Some(precision) => precision,
+        None => return write!(formatter, "{}", value),
+    };
+
+    let s = format!("{:.*}", precision, value);
+    let s = if s.contains('.') {
+        s.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &s
+    };
+    if s == "-0" {
+        return write!(formatter, "0");
+    }
+
+    write!(formatter, "{}", s)
+}
+
+/// Writes path events in the `Debug` format of the path types: the SVG commands in a string,
+/// with the `Debug` formatting of the endpoints and control points.
+pub(crate) fn debug_events<Ep, Cp, Iter>(
+    formatter: &mut fmt::Formatter,
+    events: Iter,
+) -> fmt::Result
+where
+    Ep: fmt::Debug,
+    Cp: fmt::Debug,
+    Iter: IntoIterator<Item = Event<Ep, Cp>>,
+{
+    let mut separator = "";
+    write!(formatter, "\"")?;
+    for event in events {
+        match event {
+            Event::Begin { at } => write!(formatter, "{}M {:?}", separator, at),
+            Event::Line { to, .. } => write!(formatter, "{}L {:?}", separator, to),
+            Event::Quadratic { ctrl, to, .. } => {
+                write!(formatter, "{}Q {:?} {:?}", separator, ctrl, to)
+            }
+            Event::Cubic {
+                ctrl1, ctrl2, to, ..
+            } => write!(formatter, "{}C {:?} {:?} {:?}", separator, ctrl1, ctrl2, to),
+            Event::End { close: true, .. } => write!(formatter, "{}Z", separator),
+            Event::End { close: false, .. } => continue,
+        }?;
+        separator = " ";
+    }
+
+    write!(formatter, "\"")
+}
+
+#[test]
+fn event_to_segment() {
+    use crate::geom::Segment;
+    use crate::math::point;
+
+    let mut builder = crate::Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.quadratic_bezier_to(point(1.0, 1.0), point(2.0, 0.0));
+    builder.close();
+    builder.begin(point(5.0, 5.0));
+    builder.line_to(point(6.0, 5.0));
+    builder.end(false);
+    let path = builder.build();
+
+    let segments: Vec<_> = path.iter().filter_map(|evt| evt.to_segment()).collect();
+    assert_eq!(segments.len(), 3);
+    assert!(matches!(segments[0], BezierSegment::Quadratic(_)));
+    assert_eq!(segments[1].from(), point(2.0, 0.0));
+    assert_eq!(segments[1].to(), point(0.0, 0.0));
+    assert_eq!(segments[2].to(), point(6.0, 5.0));
+}
+
+#[test]
+fn svg_syntax() {
+    use crate::math::point;
+
+    let mut builder = crate::Path::builder();
+    builder.begin(point(0.0, -0.25));
+    builder.line_to(point(10.0, 0.5));
+    builder.quadratic_bezier_to(point(1.0 / 3.0, 2.0), point(-0.0001, 2.0));
+    builder.cubic_bezier_to(point(1.0, 2.0), point(3.0, 4.0), point(5.0, 6.0));
+    builder.close();
+    builder.begin(point(1.5, 1.5));
+    builder.line_to(point(2.0, 1.5));
+    builder.end(false);
+    let path = builder.build();
+
+    assert_eq!(
+        format!("{}", path),
+        "M 0 -0.25 L 10 0.5 Q 0.33333334 2 -0.0001 2 C 1 2 3 4 5 6 Z M 1.5 1.5 L 2 1.5"
+    );
+    assert_eq!(
+        format!("{:.2}", path),
+        "M 0 -0.25 L 10 0.5 Q 0.33 2 0 2 C 1 2 3 4 5 6 Z M 1.5 1.5 L 2 1.5"
+    );
+    assert_eq!(format!("{}", crate::Path::new()), "");
+    assert_eq!(
+        format!("{:?}", path),
+        "\"M 0.0 -0.25 L 10.0 0.5 Q 0.33333334 2.0 -0.0001 2.0 C 1.0 2.0 3.0 4.0 5.0 6.0 Z \
+         M 1.5 1.5 L 2.0 1.5\""
+    );
+
+    let polygon = crate::Polygon {
+        points: &[point(0.0, 0.0), point(1.0, 0.0), point(1.0, 1.0)],
+        closed: true,
+    };
+    assert_eq!(format!("{}", polygon), "M 0 0 L 1 0 L 1 1 Z");
+
+    let buffer: crate::PathBuffer = vec![path.as_slice(), path.as_slice()].into_iter().collect();
+    assert_eq!(
+        format!("{:.1}", buffer),
+        "M 0 -0.2 L 10 0.5 Q 0.3 2 0 2 C 1 2 3 4 5 6 Z M 1.5 1.5 L 2 1.5\n\
+         M 0 -0.2 L 10 0.5 Q 0.3 2 0 2 C 1 2 3 4 5 6 Z M 1.5 1.5 L 2 1.5"
+    );
+
+    let mut commands = crate::commands::PathCommands::builder();
+    commands.begin(EndpointId(0));
+    commands.line_to(EndpointId(1));
+    commands.end(true);
+    let commands = commands.build();
+    assert_eq!(format!("{:?}", commands), "\"M #0 L #1 Z\"");
+
+    let points = [point(0.0, 0.0), point(2.0, 0.0)];
+    let slice = commands.path_slice(&points, &points);
+    assert_eq!(format!("{}", slice), "M 0 0 L 2 0 Z");
+}
@@ -0,0 +1,281 @@
+//! Fixed-point storage for endpoint and control point positions.
+//!
+//! [`PathCommands`](../commands/struct.PathCommands.html) only stores IDs, which makes it
+//! possible to store positions in a more compact or more predictable format than
+//! `f32` points. [`FixedPointPositions`](struct.FixedPointPositions.html) stores them as
+//! pairs of integers with a configurable number of fractional bits (for example 26.6
+//! fixed-point numbers in `i32`, or tile-local coordinates in `i16`) and converts them
+//! on the fly through the [`PositionStore`](../trait.PositionStore.html) interface.
+//!
+//! With `i16` coordinates, positions use half of the memory of `f32` points. Since the
+//! coordinates are integers, positions can be compared and hashed exactly.
+//!
+//! # Example
+//!
+//! ```
+//! use lyon_path::{EndpointId, Path, PositionStore};
+//! use lyon_path::fixed_point::FixedPointPositions;
+//! use lyon_path::math::point;
+//!
+//! let mut builder = Path::builder();
+//! builder.begin(point(0.0, 0.0));
+//! builder.line_to(point(10.5, 0.0));
+//! builder.quadratic_bezier_to(point(10.5, 10.5), point(0.0, 10.0));
+//! builder.close();
+//! let path = builder.build();
+//!
+//! // 26.6 fixed point numbers.
+//! let (commands, positions) = FixedPointPositions::<i32>::from_path_events(path.iter(), 6);
+//!
+//! // Positions are converted back to f32 points when queried.
+//! let p = positions.get_endpoint(EndpointId(1));
+//! assert_eq!(p, point(10.5, 0.0));
+//!
+//! for event in positions.path_events(&commands) {
+//!     // ...
+//! }
+//! ```
+
+use crate::commands::PathCommands;
+use crate::math::{point, Point};
+use crate::{ControlPointId, EndpointId, Event, PathEvent, PositionStore};
+
+use std::hash::Hash;
+
+/// Integer types that can be used to store fixed-point coordinates.
+pub trait FixedPointScalar: Copy + PartialEq + Eq + Hash + std::fmt::Debug {
+    /// Converts an already scaled value, rounding to the nearest integer and
+    /// saturating if it is out of range.
+    fn from_scaled_f32(val: f32) -> Self;
+
+    fn to_f32(self) -> f32;
+}
+
+impl FixedPointScalar for i16 {
+    #[inline]
+    fn from_scaled_f32(val: f32) -> Self {
+        val.round() as i16
+    }
+
+    #[inline]
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
+}
+
+impl FixedPointScalar for i32 {
+    #[inline]
+    fn from_scaled_f32(val: f32) -> Self {
+        val.round() as i32
+    }
+
+    #[inline]
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
+}
+
+/// Stores endpoints and control points as fixed-point integer coordinates.
+///
+/// Positions are quantized with `fract_bits` fractional bits, meaning that the
+/// precision is `1 / 2^fract_bits`. Values outside of the range of the integer type
+/// saturate.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FixedPointPositions<T: FixedPointScalar> {
+    endpoints: Vec<[T; 2]>,
+    control_points: Vec<[T; 2]>,
+    fract_bits: u32,
+}
+
+impl<T: FixedPointScalar> FixedPointPositions<T> {
+    /// Creates an empty store with the given number of fractional bits.
+    pub fn new(fract_bits: u32) -> Self {
+        assert!(fract_bits < 31);
+        FixedPointPositions {
+            endpoints: Vec::new(),
+            control_points: Vec::new(),
+            fract_bits,
+        }
+    }
+
+    /// Builds the commands and fixed-point positions corresponding to a sequence of
+    /// path events.
+    pub fn from_path_events(
+        events: impl IntoIterator<Item = PathEvent>,
+        fract_bits: u32,
+    ) -> (PathCommands, Self) {
+        let mut positions = FixedPointPositions::new(fract_bits);
+        let mut commands = PathCommands::builder();
+        for event in events {
+            match event {
+                Event::Begin { at } => {
+                    commands.begin(positions.add_endpoint(at));
+                }
+                Event::Line { to, .. } => {
+                    commands.line_to(positions.add_endpoint(to));
+                }
+                Event::Quadratic { ctrl, to, .. } => {
+                    let ctrl = positions.add_control_point(ctrl);
+                    commands.quadratic_bezier_to(ctrl, positions.add_endpoint(to));
+                }
+                Event::Cubic {
+                    ctrl1, ctrl2, to, ..
+                } => {
+                    let ctrl1 = positions.add_control_point(ctrl1);
+                    let ctrl2 = positions.add_control_point(ctrl2);
+                    commands.cubic_bezier_to(ctrl1, ctrl2, positions.add_endpoint(to));
+                }
+                Event::End { close, .. } => {
+                    commands.end(close);
+                }
+            }
+        }
+
+        (commands.build(), positions)
+    }
+
+    /// The number of fractional bits of the stored coordinates.
+    #[inline]
+    pub fn fract_bits(&self) -> u32 {
+        self.fract_bits
+    }
+
+    /// Quantizes a position into fixed-point coordinates.
+    #[inline]
+    pub fn quantize(&self, position: Point) -> [T; 2] {
+        let scale = (1u32 << self.fract_bits) as f32;
+        [
+            T::from_scaled_f32(position.x * scale),
+            T::from_scaled_f32(position.y * scale),
+        ]
+    }
+
+    /// Converts fixed-point coordinates back into a position.
+    #[inline]
+    pub fn dequantize(&self, position: [T; 2]) -> Point {
+        let inv_scale = 1.0 / (1u32 << self.fract_bits) as f32;
+        point(
+            position[0].to_f32() * inv_scale,
+            position[1].to_f32() * inv_scale,
+        )
+    }
+
+    /// Quantizes and adds an endpoint, returning its ID.
+    pub fn add_endpoint(&mut self, position: Point) -> EndpointId {
+        let id = EndpointId(self.endpoints.len() as u32);
+        let position = self.quantize(position);
+        self.endpoints.push(position);
+        id
+    }
+
+    /// Quantizes and adds a control point, returning its ID.
+    pub fn add_control_point(&mut self, position: Point) -> ControlPointId {
+        let id = ControlPointId(self.control_points.len() as u32);
+        let position = self.quantize(position);
+        self.control_points.push(position);
+        id
+    }
+
+    /// The raw fixed-point endpoint coordinates.
+    #[inline]
+    pub fn endpoints(&self) -> &[[T; 2]] {
+        &self.endpoints
+    }
+
+    /// The raw fixed-point control point coordinates.
+    #[inline]
+    pub fn control_points(&self) -> &[[T; 2]] {
+        &self.control_points
+    }
+
+    /// Returns an iterator of path events for commands referring to the positions of this store.
+    pub fn path_events<'l>(
+        &'l self,
+        commands: &'l PathCommands,
+    ) -> impl Iterator<Item = PathEvent> + 'l {
+        commands.iter().map(move |event| match event {
+            Event::Begin { at } => Event::Begin {
+                at: self.get_endpoint(at),
+            },
+            Event::Line { from, to } => Event::Line {
+                from: self.get_endpoint(from),
+                to: self.get_endpoint(to),
+            },
+            Event::Quadratic { from, ctrl, to } => Event::Quadratic {
+                from: self.get_endpoint(from),
+                ctrl: self.get_control_point(ctrl),
+                to: self.get_endpoint(to),
+            },
+            Event::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => Event::Cubic {
+                from: self.get_endpoint(from),
+                ctrl1: self.get_control_point(ctrl1),
+                ctrl2: self.get_control_point(ctrl2),
+                to: self.get_endpoint(to),
+            },
+            Event::End { last, first, close } => Event::End {
+                last: self.get_endpoint(last),
+                first: self.get_endpoint(first),
+                close,
+            },
+        })
+    }
+
+    pub fn clear(&mut self) {
+        self.endpoints.clear();
+        self.control_points.clear();
+    }
+}
+
+impl<T: FixedPointScalar> PositionStore for FixedPointPositions<T> {
+    #[inline]
+    fn get_endpoint(&self, id: EndpointId) -> Point {
+        self.dequantize(self.endpoints[id.to_usize()])
+    }
+
+    #[inline]
+    fn get_control_point(&self, id: ControlPointId) -> Point {
+        self.dequantize(self.control_points[id.to_usize()])
+    }
+}
+
+#[test]
+fn fixed_point_round_trip() {
+    use crate::Path;
+
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.25, -3.5));
+    builder.cubic_bezier_to(point(1.0, 2.0), point(3.0, 4.0), point(5.0, 6.0));
+    builder.end(false);
+    builder.begin(point(100.0, 100.0));
+    builder.quadratic_bezier_to(point(101.5, 100.0), point(1.0 / 3.0, 0.0));
+    builder.close();
+    let path = builder.build();
+
+    let (commands, positions) = FixedPointPositions::<i16>::from_path_events(path.iter(), 6);
+    let events: Vec<PathEvent> = positions.path_events(&commands).collect();
+    let expected: Vec<PathEvent> = path.iter().collect();
+    assert_eq!(events.len(), expected.len());
+
+    // Exactly representable values are preserved, others are rounded to the precision.
+    assert_eq!(events[..4], expected[..4]);
+    if let Event::Quadratic { to, .. } = events[5] {
+        assert_eq!(to, point(21.0 / 64.0, 0.0));
+    } else {
+        panic!();
+    }
+
+    // Values outside of the range of the integer type saturate.
+    let mut positions = FixedPointPositions::<i16>::new(8);
+    let id = positions.add_endpoint(point(1000.0, -1000.0));
+    assert_eq!(positions.endpoints()[0], [i16::MAX, i16::MIN]);
+    assert_eq!(
+        positions.get_endpoint(id),
+        point(i16::MAX as f32 / 256.0, i16::MIN as f32 / 256.0)
+    );
+}
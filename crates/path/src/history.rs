@@ -0,0 +1,246 @@
+//! Undo and redo for path editing.
+//!
+//! A [`PathHistory`](struct.PathHistory.html) owns the path being edited and records
+//! each editing step as the [`PathPatch`](../diff/struct.PathPatch.html) that reverts it.
+//! Undoing a step applies the recorded patch and keeps its inverse for redo, so the
+//! memory used by the history is proportional to the size of the edits rather than to
+//! the size of the path.
+//!
+//! The history also maintains the bounding rectangles of the sub-paths (see
+//! [`SubPathBounds`](../bounds/struct.SubPathBounds.html)), only recomputing the ones
+//! affected by each step.
+//!
+//! # Example
+//!
+//! ```
+//! use lyon_path::Path;
+//! use lyon_path::history::PathHistory;
+//! use lyon_path::math::point;
+//!
+//! let mut history = PathHistory::new(Path::new());
+//!
+//! history.append(|builder| {
+//!     builder.begin(point(0.0, 0.0));
+//!     builder.line_to(point(1.0, 0.0));
+//!     builder.line_to(point(1.0, 1.0));
+//!     builder.close();
+//! });
+//! history.append(|builder| {
+//!     builder.begin(point(5.0, 5.0));
+//!     builder.line_to(point(6.0, 5.0));
+//!     builder.end(false);
+//! });
+//! assert_eq!(history.path().iter().count(), 7);
+//!
+//! history.undo();
+//! assert_eq!(history.path().iter().count(), 4);
+//!
+//! history.redo();
+//! assert_eq!(history.path().iter().count(), 7);
+//! ```
+
+use crate::bounds::SubPathBounds;
+use crate::diff::{PatchError, PathPatch};
+use crate::path::Builder;
+use crate::Path;
+
+/// A path along with the history of its modifications.
+///
+/// See the [module documentation](index.html).
+#[derive(Clone, Debug, Default)]
+pub struct PathHistory {
+    path: Path,
+    undo: Vec<PathPatch>,
+    redo: Vec<PathPatch>,
+    bounds: SubPathBounds,
+}
+
+impl PathHistory {
+    /// Starts editing a path, with an empty history.
+    pub fn new(path: Path) -> Self {
+        PathHistory {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            bounds: SubPathBounds::from_path(&path),
+            path,
+        }
+    }
+
+    /// The current state of the path.
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The bounding rectangles of the sub-paths of the current path.
+    ///
+    /// Only the rectangles affected by the editing steps since the last call are
+    /// recomputed.
+    pub fn sub_path_bounds(&mut self) -> &SubPathBounds {
+        self.bounds.update(&self.path);
+        &self.bounds
+    }
+
+    /// Applies a patch to the path as a new editing step.
+    ///
+    /// The path and the history are left unchanged if the patch does not apply.
+    pub fn apply(&mut self, patch: &PathPatch) -> Result<(), PatchError> {
+        let path = patch.apply(self.path.iter())?;
+        let inverse = patch.inverse(self.path.iter())?;
+        self.push_step(path, inverse);
+
+        Ok(())
+    }
+
+    /// Replaces the path as a new editing step.
+    ///
+    /// Only the differences with the current path are recorded.
+    pub fn set(&mut self, path: Path) {
+        let inverse = PathPatch::diff(path.iter(), self.path.iter());
+        self.push_step(path, inverse);
+    }
+
+    /// Adds sub-paths at the end of the path as a new editing step.
+    ///
+    /// The builder passed to the callback already contains the current path.
+    pub fn append(&mut self, callback: impl FnOnce(&mut Builder)) {
+        let mut builder = Path::builder();
+        builder.extend_from_paths(&[self.path.as_slice()]);
+        callback(&mut builder);
+        self.set(builder.build());
+    }
+
+    /// Reverts the last editing step.
+    ///
+    /// Returns false if there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        match self.undo.pop() {
+            Some(patch) => {
+                let redo = self.revert(&patch);
+                self.redo.push(redo);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Reapplies the last undone editing step.
+    ///
+    /// Returns false if there is nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        match self.redo.pop() {
+            Some(patch) => {
+                let undo = self.revert(&patch);
+                self.undo.push(undo);
+                true
+            }
+            None => false,
+        }
+    }
+
+    #[inline]
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    #[inline]
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Forgets the recorded editing steps, keeping the current path.
+    pub fn clear_history(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    /// Consumes the history and returns the current path.
+    pub fn into_path(self) -> Path {
+        self.path
+    }
+
+    fn push_step(&mut self, path: Path, inverse: PathPatch) {
+        self.path = path;
+        self.bounds.invalidate(&inverse);
+        if !inverse.is_empty() {
+            self.undo.push(inverse);
+            self.redo.clear();
+        }
+    }
+
+    /// Applies a recorded patch and returns the patch that reverts it.
+    fn revert(&mut self, patch: &PathPatch) -> PathPatch {
+        // Recorded patches were computed against the current state, so they always apply.
+        let inverse = patch.inverse(self.path.iter()).unwrap();
+        self.path = patch.apply(self.path.iter()).unwrap();
+        self.bounds.invalidate(patch);
+
+        inverse
+    }
+}
+
+#[test]
+fn undo_redo() {
+    use crate::diff::PatchOp;
+    use crate::math::point;
+
+    let mut history = PathHistory::new(Path::new());
+    assert!(!history.undo());
+
+    history.append(|builder| {
+        builder.begin(point(0.0, 0.0));
+        builder.line_to(point(1.0, 0.0));
+        builder.line_to(point(1.0, 1.0));
+        builder.close();
+    });
+    let triangle = history.path().clone();
+
+    // Move a point.
+    let moved = PathPatch::from_ops(vec![PatchOp::Move {
+        index: 2,
+        event: crate::Event::Line {
+            from: point(1.0, 0.0),
+            to: point(2.0, 2.0),
+        },
+    }]);
+    history.apply(&moved).unwrap();
+    let moved_triangle = history.path().clone();
+    assert_eq!(history.path().iter().nth(2).unwrap().to(), point(2.0, 2.0));
+
+    // Invalid patches don't modify the path or the history.
+    let invalid = PathPatch::from_ops(vec![PatchOp::Remove {
+        index: 0,
+        count: 10,
+    }]);
+    assert!(history.apply(&invalid).is_err());
+    assert!(history.path().iter().eq(moved_triangle.iter()));
+
+    // Replace the path entirely.
+    let mut builder = Path::builder();
+    builder.begin(point(3.0, 3.0));
+    builder.line_to(point(4.0, 3.0));
+    builder.end(false);
+    history.set(builder.build());
+
+    assert!(history.undo());
+    assert!(history.path().iter().eq(moved_triangle.iter()));
+    assert!(history.undo());
+    assert!(history.path().iter().eq(triangle.iter()));
+    assert!(history.undo());
+    assert_eq!(history.path().iter().count(), 0);
+    assert!(!history.can_undo());
+
+    assert!(history.redo());
+    assert!(history.redo());
+    assert!(history.path().iter().eq(moved_triangle.iter()));
+
+    // A new step discards the undone ones.
+    history.append(|builder| {
+        builder.begin(point(10.0, 10.0));
+        builder.line_to(point(11.0, 10.0));
+        builder.end(false);
+    });
+    assert!(!history.can_redo());
+    assert!(history.undo());
+    assert!(history.path().iter().eq(moved_triangle.iter()));
+}
@@ -0,0 +1,304 @@
+//! Paths referring to the positions of a shared vertex pool.
+//!
+//! Datasets such as administrative borders or map tiles contain many paths sharing
+//! boundaries. Storing each of them as a `Path` duplicates every shared point.
+//! An [`IndexedPath`](struct.IndexedPath.html) only stores commands (see
+//! [`PathCommands`](../commands/struct.PathCommands.html)) with `u32` indices into a
+//! vertex pool owned by the user and shared by any number of paths. Endpoints and
+//! control points are indices into the same pool.
+//!
+//! The positions are read through the [`PositionStore`](../trait.PositionStore.html)
+//! interface, which is implemented by [`VertexPool`](struct.VertexPool.html) and by
+//! [`PoolPositions`](struct.PoolPositions.html) for plain slices of points.
+//!
+//! # Example
+//!
+//! ```
+//! use lyon_path::indexed::{IndexedPath, VertexPool};
+//! use lyon_path::math::point;
+//!
+//! let mut pool = VertexPool::new();
+//! let a = pool.add(point(0.0, 0.0));
+//! let b = pool.add(point(1.0, 0.0));
+//! let c = pool.add(point(1.0, 1.0));
+//! let d = pool.add(point(0.0, 1.0));
+//!
+//! // Two triangles sharing the a-c edge.
+//! let mut builder = IndexedPath::builder();
+//! builder.begin(a);
+//! builder.line_to(b);
+//! builder.line_to(c);
+//! builder.end(true);
+//! let first = builder.build();
+//!
+//! let mut builder = IndexedPath::builder();
+//! builder.begin(a);
+//! builder.line_to(c);
+//! builder.line_to(d);
+//! builder.end(true);
+//! let second = builder.build();
+//!
+//! for event in first.events(pool.points()).chain(second.events(pool.points())) {
+//!     // ...
+//! }
+//!
+//! // The commands can be passed to the tessellators along with the pool as position store:
+//! // tessellator.tessellate_with_ids(first.id_iter(), &pool, None, &options, &mut output);
+//! ```
+
+use crate::commands::{self, CommandsPathSlice, PathCommands, PathCommandsBuilder, PointEvents};
+use crate::math::Point;
+use crate::{ControlPointId, EndpointId, Event, EventId, PathEvent, PositionStore};
+
+use std::collections::HashMap;
+
+/// A growable pool of positions shared by several indexed paths.
+#[derive(Clone, Debug, Default)]
+pub struct VertexPool {
+    points: Vec<Point>,
+    lookup: HashMap<(u32, u32), u32>,
+}
+
+impl VertexPool {
+    /// Creates an empty pool.
+    pub fn new() -> Self {
+        VertexPool::default()
+    }
+
+    /// Adds a position to the pool and returns its index.
+    pub fn add(&mut self, position: Point) -> u32 {
+        let idx = self.points.len() as u32;
+        self.points.push(position);
+
+        idx
+    }
+
+    /// Adds a position to the pool unless an identical one was already added
+    /// with this method, and returns its index.
+    ///
+    /// Positions are compared exactly.
+    pub fn add_unique(&mut self, position: Point) -> u32 {
+        // Adding zero turns negative zeros into positive ones so that they compare equal.
+        let key = ((position.x + 0.0).to_bits(), (position.y + 0.0).to_bits());
+        if let Some(idx) = self.lookup.get(&key) {
+            return *idx;
+        }
+
+        let idx = self.add(position);
+        self.lookup.insert(key, idx);
+
+        idx
+    }
+
+    /// The positions of the pool.
+    #[inline]
+    pub fn points(&self) -> &[Point] {
+        &self.points
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.points.clear();
+        self.lookup.clear();
+    }
+}
+
+impl PositionStore for VertexPool {
+    #[inline]
+    fn get_endpoint(&self, id: EndpointId) -> Point {
+        self.points[id.to_usize()]
+    }
+
+    #[inline]
+    fn get_control_point(&self, id: ControlPointId) -> Point {
+        self.points[id.to_usize()]
+    }
+}
+
+/// Adapter implementing `PositionStore` for a slice of positions used as vertex pool.
+#[derive(Copy, Clone, Debug)]
+pub struct PoolPositions<'l>(pub &'l [Point]);
+
+impl<'l> PositionStore for PoolPositions<'l> {
+    #[inline]
+    fn get_endpoint(&self, id: EndpointId) -> Point {
+        self.0[id.to_usize()]
+    }
+
+    #[inline]
+    fn get_control_point(&self, id: ControlPointId) -> Point {
+        self.0[id.to_usize()]
+    }
+}
+
+/// A path whose endpoints and control points are indices into a vertex pool.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct IndexedPath {
+    commands: PathCommands,
+}
+
+impl IndexedPath {
+    /// Creates an [IndexedPathBuilder](struct.IndexedPathBuilder.html).
+    pub fn builder() -> IndexedPathBuilder {
+        IndexedPathBuilder::new()
+    }
+
+    /// Builds an indexed path from a sequence of path events, adding its positions
+    /// to the pool.
+    ///
+    /// Positions that are already in the pool (added with `VertexPool::add_unique`)
+    /// are reused.
+    pub fn from_path_events(
+        events: impl IntoIterator<Item = PathEvent>,
+        pool: &mut VertexPool,
+    ) -> Self {
+        let mut builder = IndexedPath::builder();
+        for event in events {
+            match event {
+                Event::Begin { at } => {
+                    builder.begin(pool.add_unique(at));
+                }
+                Event::Line { to, .. } => {
+                    builder.line_to(pool.add_unique(to));
+                }
+                Event::Quadratic { ctrl, to, .. } => {
+                    let ctrl = pool.add_unique(ctrl);
+                    builder.quadratic_bezier_to(ctrl, pool.add_unique(to));
+                }
+                Event::Cubic {
+                    ctrl1, ctrl2, to, ..
+                } => {
+                    let ctrl1 = pool.add_unique(ctrl1);
+                    let ctrl2 = pool.add_unique(ctrl2);
+                    builder.cubic_bezier_to(ctrl1, ctrl2, pool.add_unique(to));
+                }
+                Event::End { close, .. } => {
+                    builder.end(close);
+                }
+            }
+        }
+
+        builder.build()
+    }
+
+    /// The commands of the path.
+    ///
+    /// Both the endpoint and the control point IDs are indices into the vertex pool.
+    #[inline]
+    pub fn commands(&self) -> &PathCommands {
+        &self.commands
+    }
+
+    /// Returns an iterator over the events of the path using IDs.
+    pub fn id_iter(&self) -> commands::Iter<'_> {
+        self.commands.iter()
+    }
+
+    /// Returns an iterator over the events of the path, reading positions from the pool.
+    pub fn events<'l>(&'l self, pool: &'l [Point]) -> PointEvents<'l, Point, Point> {
+        self.commands.events(pool, pool).points()
+    }
+
+    /// Returns a view on the path and the pool, which implements `PositionStore`.
+    pub fn with_pool<'l>(&'l self, pool: &'l [Point]) -> CommandsPathSlice<'l, Point, Point> {
+        self.commands.path_slice(pool, pool)
+    }
+}
+
+impl From<PathCommands> for IndexedPath {
+    fn from(commands: PathCommands) -> Self {
+        IndexedPath { commands }
+    }
+}
+
+/// Builds an [`IndexedPath`](struct.IndexedPath.html) from indices into a vertex pool.
+#[derive(Clone, Default)]
+pub struct IndexedPathBuilder {
+    commands: PathCommandsBuilder,
+}
+
+impl IndexedPathBuilder {
+    pub fn new() -> Self {
+        IndexedPathBuilder::default()
+    }
+
+    pub fn begin(&mut self, at: u32) -> EventId {
+        self.commands.begin(EndpointId(at))
+    }
+
+    pub fn end(&mut self, close: bool) -> Option<EventId> {
+        self.commands.end(close)
+    }
+
+    pub fn line_to(&mut self, to: u32) -> EventId {
+        self.commands.line_to(EndpointId(to))
+    }
+
+    pub fn quadratic_bezier_to(&mut self, ctrl: u32, to: u32) -> EventId {
+        self.commands
+            .quadratic_bezier_to(ControlPointId(ctrl), EndpointId(to))
+    }
+
+    pub fn cubic_bezier_to(&mut self, ctrl1: u32, ctrl2: u32, to: u32) -> EventId {
+        self.commands
+            .cubic_bezier_to(ControlPointId(ctrl1), ControlPointId(ctrl2), EndpointId(to))
+    }
+
+    /// Consumes the builder and returns the path.
+    pub fn build(self) -> IndexedPath {
+        IndexedPath {
+            commands: self.commands.build(),
+        }
+    }
+}
+
+#[test]
+fn shared_vertex_pool() {
+    use crate::math::point;
+    use crate::Path;
+
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.quadratic_bezier_to(point(2.0, 1.0), point(1.0, 1.0));
+    builder.close();
+    let first = builder.build();
+
+    // Shares an edge with the first path.
+    let mut builder = Path::builder();
+    builder.begin(point(1.0, 1.0));
+    builder.line_to(point(-0.0, 0.0));
+    builder.line_to(point(0.0, 1.0));
+    builder.close();
+    let second = builder.build();
+
+    let mut pool = VertexPool::new();
+    let indexed_first = IndexedPath::from_path_events(first.iter(), &mut pool);
+    let indexed_second = IndexedPath::from_path_events(second.iter(), &mut pool);
+
+    // Five distinct positions instead of seven.
+    assert_eq!(pool.len(), 5);
+
+    let events: Vec<PathEvent> = indexed_first.events(pool.points()).collect();
+    assert_eq!(events, first.iter().collect::<Vec<_>>());
+    let events: Vec<PathEvent> = indexed_second.events(pool.points()).collect();
+    assert_eq!(events, second.iter().collect::<Vec<_>>());
+
+    let positions = PoolPositions(pool.points());
+    let view = indexed_second.with_pool(pool.points());
+    for event in indexed_second.id_iter() {
+        let at = event.to();
+        assert_eq!(positions.get_endpoint(at), pool.get_endpoint(at));
+        assert_eq!(view.get_endpoint(at), pool.get_endpoint(at));
+    }
+}
@@ -79,10 +79,14 @@
 //! ```
 
 use crate::geom::traits::Transformation;
-use crate::geom::{cubic_bezier, quadratic_bezier, CubicBezierSegment, QuadraticBezierSegment};
+use crate::geom::{
+    cubic_bezier, quadratic_bezier, BezierSegment, CubicBezierSegment, QuadraticBezierSegment,
+};
 use crate::math::*;
 use crate::{Attributes, Event, PathEvent};
 
+use std::ops::Range;
+
 // TODO: It would be great to add support for attributes in PathItertor.
 
 /// An extension trait for `PathEvent` iterators.
@@ -92,10 +96,21 @@ pub trait PathIterator: Iterator<Item = PathEvent> + Sized {
         Flattened::new(tolerance, self)
     }
 
+    /// Returns an iterator that turns curves into line segments and reports, for each
+    /// emitted event, the source event and the curve parameter range it comes from.
+    fn flattened_with_sources(self, tolerance: f32) -> FlattenedWithSources<Self> {
+        FlattenedWithSources::new(tolerance, self)
+    }
+
     /// Returns an iterator applying a 2D transform to all of its events.
     fn transformed<T: Transformation<f32>>(self, mat: &T) -> Transformed<Self, T> {
         Transformed::new(mat, self)
     }
+
+    /// Returns an iterator over the segments of the path.
+    fn segments(self) -> Segments<Self> {
+        Segments::new(self)
+    }
 }
 
 impl<Iter> PathIterator for Iter where Iter: Iterator<Item = PathEvent> {}
@@ -224,6 +239,120 @@ where
     }
 }
 
+/// Describes which part of the source path a flattened event was generated from.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SegmentSource {
+    /// Index of the source event in the original sequence of events.
+    pub event: u32,
+    /// Range of the source curve's parameter covered by the flattened event.
+    ///
+    /// It is `0.0..1.0` for events that are not curves (the closing segment for
+    /// `End` events) and `0.0..0.0` for `Begin` events.
+    pub t: Range<f32>,
+}
+
+/// An iterator that turns curves into line segments, yielding each flattened event
+/// along with its [`SegmentSource`](struct.SegmentSource.html).
+///
+/// This is useful to map flattened geometry back to the original curves, for example
+/// for dashing, hit-testing or anti-aliasing.
+pub struct FlattenedWithSources<Iter> {
+    it: Iter,
+    tolerance: f32,
+    event_index: u32,
+    current_position: Point,
+    // Flattened segments of the current curve: end position and parameter range.
+    segments: Vec<(Point, Range<f32>)>,
+    cursor: usize,
+}
+
+impl<Iter: Iterator<Item = PathEvent>> FlattenedWithSources<Iter> {
+    /// Create the iterator.
+    pub fn new(tolerance: f32, it: Iter) -> Self {
+        FlattenedWithSources {
+            it,
+            tolerance,
+            event_index: 0,
+            current_position: point(0.0, 0.0),
+            segments: Vec::new(),
+            cursor: 0,
+        }
+    }
+}
+
+impl<Iter> Iterator for FlattenedWithSources<Iter>
+where
+    Iter: Iterator<Item = PathEvent>,
+{
+    type Item = (PathEvent, SegmentSource);
+
+    fn next(&mut self) -> Option<(PathEvent, SegmentSource)> {
+        if let Some((to, t)) = self.segments.get(self.cursor).cloned() {
+            self.cursor += 1;
+            let from = self.current_position;
+            self.current_position = to;
+            return Some((
+                PathEvent::Line { from, to },
+                SegmentSource {
+                    // The curve event was already counted.
+                    event: self.event_index - 1,
+                    t,
+                },
+            ));
+        }
+
+        let event = self.it.next()?;
+        let event_index = self.event_index;
+        self.event_index += 1;
+
+        let source = |t| SegmentSource {
+            event: event_index,
+            t,
+        };
+
+        match event {
+            PathEvent::Begin { .. } => Some((event, source(0.0..0.0))),
+            PathEvent::Line { .. } | PathEvent::End { .. } => Some((event, source(0.0..1.0))),
+            PathEvent::Quadratic { from, ctrl, to } => {
+                self.start_curve(from);
+                let segments = &mut self.segments;
+                QuadraticBezierSegment { from, ctrl, to }
+                    .for_each_flattened_with_t(self.tolerance, &mut |segment, t| {
+                        segments.push((segment.to, t))
+                    });
+                self.next()
+            }
+            PathEvent::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => {
+                self.start_curve(from);
+                let segments = &mut self.segments;
+                CubicBezierSegment {
+                    from,
+                    ctrl1,
+                    ctrl2,
+                    to,
+                }
+                .for_each_flattened_with_t(self.tolerance, &mut |segment, t| {
+                    segments.push((segment.to, t))
+                });
+                self.next()
+            }
+        }
+    }
+}
+
+impl<Iter> FlattenedWithSources<Iter> {
+    fn start_curve(&mut self, from: Point) {
+        self.current_position = from;
+        self.segments.clear();
+        self.cursor = 0;
+    }
+}
+
 /// Applies a 2D transform to a path iterator and yields the resulting path iterator.
 pub struct Transformed<'l, I, T> {
     it: I,
@@ -255,6 +384,42 @@ where
     }
 }
 
+/// An iterator that consumes a path iterator and yields its segments.
+///
+/// `Begin` events and `End` events of open sub-paths are skipped. `End` events of
+/// closed sub-paths produce the closing line segment.
+pub struct Segments<Iter> {
+    it: Iter,
+}
+
+impl<Iter: Iterator<Item = PathEvent>> Segments<Iter> {
+    /// Creates a segment iterator from a path iterator.
+    #[inline]
+    pub fn new(it: Iter) -> Self {
+        Segments { it }
+    }
+}
+
+impl<Iter> Iterator for Segments<Iter>
+where
+    Iter: Iterator<Item = PathEvent>,
+{
+    type Item = BezierSegment<f32>;
+    fn next(&mut self) -> Option<BezierSegment<f32>> {
+        for evt in &mut self.it {
+            if let Some(segment) = evt.to_segment() {
+                return Some(segment);
+            }
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.it.size_hint().1)
+    }
+}
+
 /// An iterator that consumes an iterator of `Point`s and produces `Event`s.
 ///
 /// # Example
@@ -434,3 +599,119 @@ fn test_from_polyline_closed() {
     );
     assert_eq!(evts.next(), None);
 }
+
+#[test]
+fn flattened_with_sources() {
+    use crate::Path;
+
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.quadratic_bezier_to(point(10.0, 10.0), point(0.0, 10.0));
+    builder.cubic_bezier_to(point(-5.0, 10.0), point(-5.0, 5.0), point(0.0, 5.0));
+    builder.close();
+    let path = builder.build();
+
+    let with_sources: Vec<(PathEvent, SegmentSource)> =
+        path.iter().flattened_with_sources(0.01).collect();
+    let events: Vec<PathEvent> = with_sources.iter().map(|(evt, _)| *evt).collect();
+
+    let source_events: Vec<PathEvent> = path.iter().collect();
+    assert_eq!(events.len(), path.iter().flattened(0.01).count());
+
+    // Each flattened line ends near the source curve at the end of its parameter range.
+    for (event, source) in &with_sources {
+        let expected = match source_events[source.event as usize] {
+            PathEvent::Quadratic { from, ctrl, to } => {
+                QuadraticBezierSegment { from, ctrl, to }.sample(source.t.end)
+            }
+            PathEvent::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => CubicBezierSegment {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            }
+            .sample(source.t.end),
+            other => other.to(),
+        };
+        assert!((event.to() - expected).length() <= 0.01);
+    }
+
+    let curve_t_ranges = |event_index| {
+        with_sources
+            .iter()
+            .filter(|(_, src)| src.event == event_index)
+            .map(|(_, src)| src.t.clone())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        with_sources[0].1,
+        SegmentSource {
+            event: 0,
+            t: 0.0..0.0
+        }
+    );
+    assert_eq!(
+        with_sources[1].1,
+        SegmentSource {
+            event: 1,
+            t: 0.0..1.0
+        }
+    );
+    for event_index in [2, 3] {
+        // The parameter ranges of each curve are contiguous and cover the whole curve.
+        let ranges = curve_t_ranges(event_index);
+        assert!(ranges.len() > 1);
+        assert_eq!(ranges[0].start, 0.0);
+        assert_eq!(ranges.last().unwrap().end, 1.0);
+        for pair in ranges.windows(2) {
+            assert_eq!(pair[0].end, pair[1].start);
+        }
+    }
+    assert_eq!(
+        with_sources.last().unwrap().1,
+        SegmentSource {
+            event: 4,
+            t: 0.0..1.0
+        }
+    );
+}
+
+#[test]
+fn segments() {
+    use crate::geom::Segment;
+    use crate::Path;
+
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.cubic_bezier_to(point(2.0, 0.0), point(2.0, 1.0), point(1.0, 1.0));
+    builder.close();
+    builder.begin(point(5.0, 5.0));
+    builder.quadratic_bezier_to(point(6.0, 6.0), point(7.0, 5.0));
+    builder.end(false);
+    let path = builder.build();
+
+    let segments: Vec<BezierSegment<f32>> = path.segments().collect();
+    assert_eq!(segments.len(), 4);
+    assert!(matches!(segments[0], BezierSegment::Linear(_)));
+    assert!(matches!(segments[1], BezierSegment::Cubic(_)));
+    assert_eq!(segments[2].from(), point(1.0, 1.0));
+    assert_eq!(segments[2].to(), point(0.0, 0.0));
+    assert!(matches!(segments[3], BezierSegment::Quadratic(_)));
+
+    assert_eq!(path.as_slice().segments().count(), 4);
+    assert_eq!(
+        path.iter()
+            .transformed(&Translation::new(1.0, 0.0))
+            .segments()
+            .count(),
+        4
+    );
+}
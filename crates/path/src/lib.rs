@@ -46,13 +46,22 @@ pub use lyon_geom as geom;
 #[macro_use]
 pub extern crate serde;
 
+pub mod bounds;
 pub mod builder;
 pub mod commands;
+pub mod diff;
+pub mod encoding;
 mod events;
+pub mod fixed_point;
+pub mod history;
+pub mod indexed;
 pub mod iterator;
 pub mod path;
 pub mod path_buffer;
 pub mod polygon;
+pub mod simd;
+pub mod sub_path;
+pub mod typed;
 
 #[doc(hidden)]
 pub mod private;
@@ -174,6 +183,13 @@ pub enum LineCap {
     /// If a sub-path has zero length, then the resulting effect is that the stroke for
     /// that sub-path consists solely of a full circle centered at the sub-path's point.
     Round,
+    /// At each end of each sub-path, the shape representing the stroke will be extended
+    /// by a triangular arrowhead pointing away from the sub-path.
+    ///
+    /// The size of the arrowhead is set by the stroke options of the tessellator (see
+    /// `StrokeOptions::arrow_length` and `StrokeOptions::arrow_width` in `lyon_tessellation`).
+    /// A zero length sub-path has no direction and therefore no arrowhead.
+    Arrow,
 }
 
 /// Line join as defined by the SVG specification.
@@ -194,6 +210,14 @@ pub enum LineJoin {
     /// The bevel shape is a triangle that fills the area between the two stroked
     /// segments.
     Bevel,
+    /// The outer edges of the two stroked segments are extended with circular arcs that
+    /// have the curvature of the edges at the join, and the join is the area enclosed by
+    /// the arcs up to their intersection (SVG 2).
+    ///
+    /// Between straight segments the arcs are lines, so this is the same as a miter join.
+    /// Falls back to a miter join when the arcs don't intersect or when their intersection
+    /// exceeds the miter limit.
+    Arcs,
 }
 
 /// The positive or negative side of a vector or segment.
@@ -5,11 +5,14 @@ use crate::builder::*;
 use crate::geom::traits::Transformation;
 use crate::geom::{CubicBezierSegment, QuadraticBezierSegment};
 use crate::iterator::NoAttributes as IterNoAttributes;
+use crate::iterator::{PathIterator, Segments};
 use crate::math::*;
 use crate::private::DebugValidator;
+use crate::simd;
+use crate::sub_path::SubPaths;
 use crate::{
     AttributeStore, Attributes, ControlPointId, EndpointId, Event, IdEvent, PathEvent,
-    PositionStore, NO_ATTRIBUTES,
+    PositionStore, Winding, NO_ATTRIBUTES,
 };
 
 use std::fmt;
@@ -113,6 +116,46 @@ impl Path {
         }
     }
 
+    /// Creates a `Path` from a sequence of polygons, each described as a sequence of rings.
+    ///
+    /// This follows the GeoJSON and Shapefile conventions: the first ring of each polygon
+    /// is its exterior and the following rings are its holes. Exteriors are oriented with
+    /// the provided winding and holes with the opposite one, regardless of the orientation
+    /// of the input, so that the result can be filled with either fill rule.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use lyon_path::{Path, Winding};
+    /// use lyon_path::math::point;
+    ///
+    /// let exterior = [point(0.0, 0.0), point(10.0, 0.0), point(10.0, 10.0), point(0.0, 10.0), point(0.0, 0.0)];
+    /// let hole = [point(2.0, 2.0), point(8.0, 2.0), point(8.0, 8.0), point(2.0, 8.0), point(2.0, 2.0)];
+    ///
+    /// let path = Path::from_multi_polygon(&[vec![&exterior[..], &hole[..]]], Winding::Positive);
+    /// ```
+    pub fn from_multi_polygon<Polygons, Rings, Ring>(polygons: Polygons, winding: Winding) -> Path
+    where
+        Polygons: IntoIterator<Item = Rings>,
+        Rings: IntoIterator<Item = Ring>,
+        Ring: AsRef<[Point]>,
+    {
+        let hole_winding = match winding {
+            Winding::Positive => Winding::Negative,
+            Winding::Negative => Winding::Positive,
+        };
+
+        let mut builder = Path::builder();
+        for rings in polygons {
+            for (idx, ring) in rings.into_iter().enumerate() {
+                let ring_winding = if idx == 0 { winding } else { hole_winding };
+                builder.add_ring(ring.as_ref(), ring_winding);
+            }
+        }
+
+        builder.build()
+    }
+
     /// Returns a view on this `Path`.
     #[inline]
     pub fn as_slice(&self) -> PathSlice {
@@ -134,6 +177,18 @@ impl Path {
         Iter::new(self.num_attributes, &self.points[..], &self.verbs[..])
     }
 
+    /// Iterates over the segments of the `Path`, skipping the sub-path bookkeeping events.
+    ///
+    /// Closed sub-paths produce their closing line segment.
+    pub fn segments(&self) -> Segments<Iter<'_>> {
+        self.iter().segments()
+    }
+
+    /// Iterates over the sub-paths of the path, typed according to whether they are closed.
+    pub fn sub_paths(&self) -> SubPaths<'_> {
+        SubPaths::new(self.as_slice())
+    }
+
     /// Iterates over the endpoint and control point ids of the `Path`.
     pub fn id_iter(&self) -> IdIter {
         IdIter::new(self.num_attributes, &self.verbs[..])
@@ -152,6 +207,28 @@ impl Path {
         self
     }
 
+    /// Applies an affine transform to all endpoints and control points of this path and
+    /// returns the result.
+    ///
+    /// Equivalent to `transformed`, but uses a vectorized kernel when the path has no
+    /// custom attributes.
+    pub fn transformed_affine(mut self, transform: &Transform) -> Self {
+        if self.num_attributes == 0 {
+            simd::transform_points(&mut self.points, transform);
+        } else {
+            self.apply_transform(transform);
+        }
+
+        self
+    }
+
+    /// Computes a conservative axis-aligned rectangle that contains the path.
+    ///
+    /// See [`PathSlice::fast_bounding_box`](struct.PathSlice.html#method.fast_bounding_box).
+    pub fn fast_bounding_box(&self) -> Box2D {
+        self.as_slice().fast_bounding_box()
+    }
+
     /// Returns a reversed version of this path in the form of an iterator
     pub fn reversed(&self) -> IterNoAttributes<Reversed> {
         IterNoAttributes(Reversed::new(self.as_slice()))
@@ -270,6 +347,16 @@ impl fmt::Debug for Path {
     }
 }
 
+/// Writes the path with the SVG path syntax.
+///
+/// The precision of the formatter sets the number of decimals of the coordinates, for example
+/// `format!("{:.2}", path)`. Custom attributes are ignored.
+impl fmt::Display for Path {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.as_slice(), formatter)
+    }
+}
+
 /// An immutable view over a Path.
 impl<'l> PathSlice<'l> {
     pub fn first_endpoint(&self) -> Option<(Point, Attributes<'l>)> {
@@ -303,6 +390,18 @@ impl<'l> PathSlice<'l> {
         Iter::new(self.num_attributes, self.points, self.verbs)
     }
 
+    /// Iterates over the segments of the path, skipping the sub-path bookkeeping events.
+    ///
+    /// Closed sub-paths produce their closing line segment.
+    pub fn segments(&self) -> Segments<Iter<'l>> {
+        self.iter().segments()
+    }
+
+    /// Iterates over the sub-paths of the path, typed according to whether they are closed.
+    pub fn sub_paths(&self) -> SubPaths<'l> {
+        SubPaths::new(*self)
+    }
+
     /// Iterates over the endpoint and control point ids of the `Path`.
     pub fn id_iter(&self) -> IdIter {
         IdIter::new(self.num_attributes, self.verbs)
@@ -317,6 +416,47 @@ impl<'l> PathSlice<'l> {
         self.verbs.is_empty()
     }
 
+    /// Computes a conservative axis-aligned rectangle that contains the path.
+    ///
+    /// The rectangle contains all endpoints and control points. It is computed with a
+    /// vectorized kernel when the path has no custom attributes.
+    pub fn fast_bounding_box(&self) -> Box2D {
+        if self.num_attributes == 0 {
+            return simd::bounding_box(self.points);
+        }
+
+        let mut min = point(f32::MAX, f32::MAX);
+        let mut max = point(f32::MIN, f32::MIN);
+        let mut add = |p: Point| {
+            min = min.min(p);
+            max = max.max(p);
+        };
+        for evt in self.iter() {
+            match evt {
+                Event::Begin { at } => add(at),
+                Event::Line { to, .. } => add(to),
+                Event::Quadratic { ctrl, to, .. } => {
+                    add(ctrl);
+                    add(to);
+                }
+                Event::Cubic {
+                    ctrl1, ctrl2, to, ..
+                } => {
+                    add(ctrl1);
+                    add(ctrl2);
+                    add(to);
+                }
+                Event::End { .. } => {}
+            }
+        }
+
+        if min == point(f32::MAX, f32::MAX) {
+            return Box2D::zero();
+        }
+
+        Box2D { min, max }
+    }
+
     /// Returns a slice over an endpoint's custom attributes.
     #[inline]
     pub fn attributes(&self, endpoint: EndpointId) -> Attributes<'l> {
@@ -349,25 +489,26 @@ impl<'l> fmt::Debug for PathSlice<'l> {
 
         write!(formatter, "\"")?;
 
+        let mut separator = "";
         for evt in self.iter_with_attributes() {
             match evt {
                 Event::Begin {
                     at: (at, attributes),
                 } => {
-                    write!(formatter, " M")?;
+                    write!(formatter, "{}M", separator)?;
                     write_point(formatter, at)?;
                     write_attributes(formatter, attributes)?;
                 }
                 Event::End { close, .. } => {
                     if close {
-                        write!(formatter, " Z")?;
+                        write!(formatter, "{}Z", separator)?;
                     }
                 }
                 Event::Line {
                     to: (to, attributes),
                     ..
                 } => {
-                    write!(formatter, " L")?;
+                    write!(formatter, "{}L", separator)?;
                     write_point(formatter, to)?;
                     write_attributes(formatter, attributes)?;
                 }
@@ -376,7 +517,7 @@ impl<'l> fmt::Debug for PathSlice<'l> {
                     to: (to, attributes),
                     ..
                 } => {
-                    write!(formatter, " Q")?;
+                    write!(formatter, "{}Q", separator)?;
                     write_point(formatter, ctrl)?;
                     write_point(formatter, to)?;
                     write_attributes(formatter, attributes)?;
@@ -387,19 +528,27 @@ impl<'l> fmt::Debug for PathSlice<'l> {
                     to: (to, attributes),
                     ..
                 } => {
-                    write!(formatter, " C")?;
+                    write!(formatter, "{}C", separator)?;
                     write_point(formatter, ctrl1)?;
                     write_point(formatter, ctrl2)?;
                     write_point(formatter, to)?;
                     write_attributes(formatter, attributes)?;
                 }
             }
+            separator = " ";
         }
 
         write!(formatter, "\"")
     }
 }
 
+/// Writes the path with the SVG path syntax, see the `Display` implementation of `Path`.
+impl<'l> fmt::Display for PathSlice<'l> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        crate::events::write_svg(formatter, self.iter())
+    }
+}
+
 impl<'l> std::ops::Index<EndpointId> for PathSlice<'l> {
     type Output = Point;
     fn index(&self, id: EndpointId) -> &Point {
@@ -2187,3 +2336,93 @@ fn id_events() {
 
     assert_eq!(iter.next(), None);
 }
+
+#[test]
+fn multi_polygon_winding() {
+    fn signed_area(path: &Path) -> Vec<f32> {
+        let mut areas = Vec::new();
+        let mut area = 0.0;
+        for evt in path.iter() {
+            match evt {
+                PathEvent::Begin { .. } => {
+                    area = 0.0;
+                }
+                PathEvent::Line { from, to } => {
+                    area += from.x * to.y - to.x * from.y;
+                }
+                PathEvent::End { last, first, .. } => {
+                    area += last.x * first.y - first.x * last.y;
+                    areas.push(area * 0.5);
+                }
+                _ => panic!(),
+            }
+        }
+        areas
+    }
+
+    let exterior = [
+        point(0.0, 0.0),
+        point(0.0, 10.0),
+        point(10.0, 10.0),
+        point(10.0, 0.0),
+        point(0.0, 0.0),
+    ];
+    let hole = [
+        point(2.0, 2.0),
+        point(8.0, 2.0),
+        point(8.0, 8.0),
+        point(2.0, 8.0),
+    ];
+    let other = [point(20.0, 0.0), point(30.0, 0.0), point(20.0, 10.0)];
+    let degenerate = [point(40.0, 0.0), point(41.0, 0.0), point(40.0, 0.0)];
+
+    let path = Path::from_multi_polygon(
+        &[
+            vec![&exterior[..], &hole[..]],
+            vec![&other[..]],
+            vec![&degenerate[..]],
+        ],
+        Winding::Positive,
+    );
+
+    assert_eq!(signed_area(&path), vec![100.0, -36.0, 50.0]);
+    // The closing point is not duplicated.
+    assert_eq!(path.iter().count(), 5 + 5 + 4);
+
+    let path = Path::from_multi_polygon(&[vec![&exterior[..], &hole[..]]], Winding::Negative);
+    assert_eq!(signed_area(&path), vec![-100.0, 36.0]);
+}
+
+#[test]
+fn fast_bounding_box_and_affine_transform() {
+    let transform = Transform::new(2.0, 0.5, -1.0, 1.5, 3.0, -4.0);
+
+    let mut builder = Path::builder();
+    let mut builder_attr = Path::builder_with_attributes(1);
+    builder.begin(point(0.0, 1.0));
+    builder_attr.begin(point(0.0, 1.0), &[100.0]);
+    builder.line_to(point(5.0, -2.0));
+    builder_attr.line_to(point(5.0, -2.0), &[-100.0]);
+    builder.cubic_bezier_to(point(7.0, 8.0), point(-3.0, 2.0), point(1.0, 1.0));
+    builder_attr.cubic_bezier_to(point(7.0, 8.0), point(-3.0, 2.0), point(1.0, 1.0), &[50.0]);
+    builder.quadratic_bezier_to(point(2.0, 10.0), point(4.0, 4.0));
+    builder_attr.quadratic_bezier_to(point(2.0, 10.0), point(4.0, 4.0), &[0.0]);
+    builder.close();
+    builder_attr.close();
+    let path = builder.build();
+    let path_attr = builder_attr.build();
+
+    let expected = Box2D {
+        min: point(-3.0, -2.0),
+        max: point(7.0, 10.0),
+    };
+    assert_eq!(path.fast_bounding_box(), expected);
+    assert_eq!(path_attr.fast_bounding_box(), expected);
+    assert_eq!(Path::new().fast_bounding_box(), Box2D::zero());
+
+    let a: Vec<PathEvent> = path.clone().transformed(&transform).iter().collect();
+    let b: Vec<PathEvent> = path.transformed_affine(&transform).iter().collect();
+    let c: Vec<PathEvent> = path_attr.transformed_affine(&transform).iter().collect();
+    assert_eq!(a, b);
+    assert_eq!(a, c);
+}
@@ -108,6 +108,14 @@ impl fmt::Debug for PathBuffer {
     }
 }
 
+/// Writes each path with the SVG path syntax on its own line, see the `Display`
+/// implementation of `Path`.
+impl fmt::Display for PathBuffer {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.as_slice(), formatter)
+    }
+}
+
 impl<'l> FromIterator<PathSlice<'l>> for PathBuffer {
     fn from_iter<T: IntoIterator<Item = PathSlice<'l>>>(iter: T) -> PathBuffer {
         iter.into_iter()
@@ -184,6 +192,19 @@ impl<'l> fmt::Debug for PathBufferSlice<'l> {
     }
 }
 
+impl<'l> fmt::Display for PathBufferSlice<'l> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for idx in self.indices() {
+            if idx > 0 {
+                writeln!(formatter)?;
+            }
+            fmt::Display::fmt(&self.get(idx), formatter)?;
+        }
+
+        Ok(())
+    }
+}
+
 /// A Builder that appends a path to an existing PathBuffer.
 ///
 /// Implements the `PathBuilder` trait.
@@ -83,6 +83,13 @@ impl<'l, T> Polygon<'l, T> {
     }
 }
 
+/// Writes the polygon with the SVG path syntax, see the `Display` implementation of `Path`.
+impl<'l, T: Position> std::fmt::Display for Polygon<'l, T> {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        crate::events::write_svg(formatter, self.path_events())
+    }
+}
+
 impl<'l, T> std::ops::Index<EndpointId> for Polygon<'l, T> {
     type Output = T;
     fn index(&self, id: EndpointId) -> &T {
@@ -0,0 +1,456 @@
+//! Vectorized kernels for loops over all of the points of a path.
+//!
+//! The implementation is selected at runtime: on x86_64 CPUs that support AVX, four
+//! points are processed per instruction. Other targets use a scalar loop.
+//!
+//! These functions operate on flat slices of positions, which is how the points of
+//! paths without custom attributes are stored. See `Path::fast_bounding_box` and
+//! `Path::transformed_affine` for convenient entry points.
+//!
+//! The flattening functions evaluate the points of a curve in batches of eight instead of
+//! one at a time, and append them to a vector. Curves that are flattened into fewer points
+//! than that, which is common at coarse tolerances, go through the scalar loop.
+//!
+//! The results are identical to the scalar code's. The kernels don't use fused
+//! multiply-add, so transformed points are bit-identical to `Transform::transform_point`
+//! and flattened points are bit-identical to the ones produced by `flatten_into` in
+//! `lyon_geom`.
+
+use crate::geom::{CubicBezierSegment, QuadraticBezierSegment};
+use crate::math::{point, Box2D, Point, Transform};
+use std::ops::Range;
+
+/// Computes the smallest axis-aligned rectangle that contains all of the points.
+///
+/// Returns an empty rectangle at the origin if the slice is empty.
+pub fn bounding_box(points: &[Point]) -> Box2D {
+    if points.is_empty() {
+        return Box2D::zero();
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("avx") {
+            return unsafe { avx::bounding_box(points) };
+        }
+    }
+
+    scalar::bounding_box(points)
+}
+
+/// Applies an affine transform to all of the points in place.
+pub fn transform_points(points: &mut [Point], transform: &Transform) {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("avx") {
+            unsafe {
+                avx::transform_points(points, transform);
+            }
+            return;
+        }
+    }
+
+    scalar::transform_points(points, transform);
+}
+
+/// Approximates a quadratic bézier curve with a sequence of line segments and appends the
+/// end point of each of them to `output`.
+///
+/// Produces the same points as `QuadraticBezierSegment::flatten_into`: the start of the
+/// curve is not added and the last point is exactly `curve.to`.
+///
+/// Returns the range of the appended points in `output`.
+pub fn flatten_quadratic_into(
+    curve: &QuadraticBezierSegment<f32>,
+    tolerance: f32,
+    output: &mut Vec<Point>,
+) -> Range<usize> {
+    let start = output.len();
+    flatten_quadratic(curve, tolerance, output);
+
+    start..output.len()
+}
+
+/// Approximates a cubic bézier curve with a sequence of line segments and appends the
+/// end point of each of them to `output`.
+///
+/// Produces the same points as `CubicBezierSegment::flatten_into`.
+///
+/// Returns the range of the appended points in `output`.
+pub fn flatten_cubic_into(
+    curve: &CubicBezierSegment<f32>,
+    tolerance: f32,
+    output: &mut Vec<Point>,
+) -> Range<usize> {
+    let start = output.len();
+    // Same split of the tolerance as `CubicBezierSegment::for_each_flattened`.
+    let quadratics_tolerance = tolerance * 0.4;
+    let flattening_tolerance = tolerance * 0.8;
+    curve.for_each_quadratic_bezier(quadratics_tolerance, &mut |quad| {
+        flatten_quadratic(quad, flattening_tolerance, output);
+    });
+
+    start..output.len()
+}
+
+#[inline]
+fn flatten_quadratic(curve: &QuadraticBezierSegment<f32>, tolerance: f32, output: &mut Vec<Point>) {
+    let params = FlatteningParameters::new(curve, tolerance);
+    if params.count > 1 {
+        let n = params.count as usize - 1;
+
+        // Most curves of typical paths are flattened into a handful of points, which
+        // wouldn't fill a batch.
+        #[cfg(target_arch = "x86_64")]
+        {
+            if n >= avx::BATCH && is_x86_feature_detected!("avx") {
+                unsafe {
+                    avx::flatten_quadratic(curve, &params, n, output);
+                }
+                output.push(curve.to);
+                return;
+            }
+        }
+
+        scalar::flatten_quadratic(curve, &params, 1..=n, output);
+    }
+
+    output.push(curve.to);
+}
+
+/// The parameters of the flattening algorithm for a quadratic bézier curve.
+///
+/// This mirrors `FlatteningParameters` in `lyon_geom`, which doesn't expose its fields,
+/// with the same operations in the same order so that the results are identical.
+///
+/// See <https://raphlinus.github.io/graphics/curves/2019/12/23/flatten-quadbez.html>
+struct FlatteningParameters {
+    count: u32,
+    integral_from: f32,
+    integral_step: f32,
+    inv_integral_from: f32,
+    div_inv_integral_diff: f32,
+}
+
+impl FlatteningParameters {
+    fn new(curve: &QuadraticBezierSegment<f32>, tolerance: f32) -> Self {
+        if curve.is_linear(tolerance) {
+            return FlatteningParameters {
+                count: 0,
+                integral_from: 0.0,
+                integral_step: 0.0,
+                inv_integral_from: 0.0,
+                div_inv_integral_diff: 0.0,
+            };
+        }
+
+        let ddx = 2.0 * curve.ctrl.x - curve.from.x - curve.to.x;
+        let ddy = 2.0 * curve.ctrl.y - curve.from.y - curve.to.y;
+        let cross = (curve.to.x - curve.from.x) * ddy - (curve.to.y - curve.from.y) * ddx;
+        let inv_cross = 1.0 / cross;
+        let parabola_from =
+            ((curve.ctrl.x - curve.from.x) * ddx + (curve.ctrl.y - curve.from.y) * ddy) * inv_cross;
+        let parabola_to =
+            ((curve.to.x - curve.ctrl.x) * ddx + (curve.to.y - curve.ctrl.y) * ddy) * inv_cross;
+        let scale =
+            cross.abs() / (f32::sqrt(ddx * ddx + ddy * ddy) * (parabola_to - parabola_from).abs());
+
+        let integral_from = approx_parabola_integral(parabola_from);
+        let integral_to = approx_parabola_integral(parabola_to);
+        let integral_diff = integral_to - integral_from;
+
+        let inv_integral_from = scalar::approx_parabola_inv_integral(integral_from);
+        let inv_integral_to = scalar::approx_parabola_inv_integral(integral_to);
+        let div_inv_integral_diff = 1.0 / (inv_integral_to - inv_integral_from);
+
+        let mut count = (0.5 * integral_diff.abs() * (scale / tolerance).sqrt()).ceil();
+        if !count.is_finite() {
+            count = 0.0;
+        }
+
+        let integral_step = integral_diff / count;
+
+        FlatteningParameters {
+            count: count as u32,
+            integral_from,
+            integral_step,
+            inv_integral_from,
+            div_inv_integral_diff,
+        }
+    }
+}
+
+fn approx_parabola_integral(x: f32) -> f32 {
+    let d: f32 = 0.67;
+    let quarter = 0.5 * 0.5;
+    x / (1.0 - d + (d.powi(4) + quarter * x * x).sqrt().sqrt())
+}
+
+/// Constant of `approx_parabola_inv_integral`.
+const INV_INTEGRAL_B: f32 = 0.39;
+
+mod scalar {
+    use super::*;
+    use std::ops::RangeInclusive;
+
+    pub fn bounding_box(points: &[Point]) -> Box2D {
+        let mut min = point(f32::MAX, f32::MAX);
+        let mut max = point(f32::MIN, f32::MIN);
+        for p in points {
+            min = min.min(*p);
+            max = max.max(*p);
+        }
+
+        Box2D { min, max }
+    }
+
+    pub fn transform_points(points: &mut [Point], transform: &Transform) {
+        for p in points {
+            *p = transform.transform_point(*p);
+        }
+    }
+
+    pub fn approx_parabola_inv_integral(x: f32) -> f32 {
+        let b = INV_INTEGRAL_B;
+        let quarter = 0.5 * 0.5;
+        x * (1.0 - b + (b * b + quarter * x * x).sqrt())
+    }
+
+    /// Appends the points of the curve at the given iterations of the flattening.
+    pub fn flatten_quadratic(
+        curve: &QuadraticBezierSegment<f32>,
+        params: &FlatteningParameters,
+        iterations: RangeInclusive<usize>,
+        output: &mut Vec<Point>,
+    ) {
+        for i in iterations {
+            let u = approx_parabola_inv_integral(
+                params.integral_from + params.integral_step * i as f32,
+            );
+            let t = (u - params.inv_integral_from) * params.div_inv_integral_diff;
+            output.push(curve.sample(t));
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod avx {
+    use super::*;
+    use std::arch::x86_64::*;
+
+    /// Number of points per 256 bits vector.
+    const LANES: usize = 4;
+    /// Number of flattening iterations evaluated at once, one per lane of a 256 bits vector.
+    pub const BATCH: usize = 8;
+
+    // Safety: the caller must check that the CPU supports AVX.
+    #[target_feature(enable = "avx")]
+    pub unsafe fn bounding_box(points: &[Point]) -> Box2D {
+        let chunks = points.len() / LANES;
+        // Points are stored as consecutive (x, y) pairs of f32 values.
+        let ptr = points.as_ptr() as *const f32;
+
+        let mut vmin = _mm256_set1_ps(f32::MAX);
+        let mut vmax = _mm256_set1_ps(f32::MIN);
+        for i in 0..chunks {
+            let v = _mm256_loadu_ps(ptr.add(i * LANES * 2));
+            vmin = _mm256_min_ps(vmin, v);
+            vmax = _mm256_max_ps(vmax, v);
+        }
+
+        let mut min_lanes = [0.0f32; 8];
+        let mut max_lanes = [0.0f32; 8];
+        _mm256_storeu_ps(min_lanes.as_mut_ptr(), vmin);
+        _mm256_storeu_ps(max_lanes.as_mut_ptr(), vmax);
+
+        let mut result = scalar::bounding_box(&points[chunks * LANES..]);
+        for i in 0..LANES {
+            result.min = result
+                .min
+                .min(point(min_lanes[i * 2], min_lanes[i * 2 + 1]));
+            result.max = result
+                .max
+                .max(point(max_lanes[i * 2], max_lanes[i * 2 + 1]));
+        }
+
+        result
+    }
+
+    // Safety: the caller must check that the CPU supports AVX.
+    #[target_feature(enable = "avx")]
+    pub unsafe fn transform_points(points: &mut [Point], transform: &Transform) {
+        let chunks = points.len() / LANES;
+        let ptr = points.as_mut_ptr() as *mut f32;
+
+        let t = transform;
+        // x' = x * m11 + y * m21 + m31
+        // y' = y * m22 + x * m12 + m32
+        let a = _mm256_setr_ps(t.m11, t.m22, t.m11, t.m22, t.m11, t.m22, t.m11, t.m22);
+        let b = _mm256_setr_ps(t.m21, t.m12, t.m21, t.m12, t.m21, t.m12, t.m21, t.m12);
+        let c = _mm256_setr_ps(t.m31, t.m32, t.m31, t.m32, t.m31, t.m32, t.m31, t.m32);
+
+        for i in 0..chunks {
+            let p = ptr.add(i * LANES * 2);
+            let v = _mm256_loadu_ps(p);
+            // Swap x and y in each point.
+            let swapped = _mm256_permute_ps(v, 0b10_11_00_01);
+            let r = _mm256_add_ps(
+                _mm256_add_ps(_mm256_mul_ps(v, a), _mm256_mul_ps(swapped, b)),
+                c,
+            );
+            _mm256_storeu_ps(p, r);
+        }
+
+        scalar::transform_points(&mut points[chunks * LANES..], transform);
+    }
+
+    /// Appends the points of the curve at iterations `1..=n` of the flattening.
+    ///
+    /// Eight consecutive iterations are evaluated at once, one per lane.
+    // Safety: the caller must check that the CPU supports AVX.
+    #[target_feature(enable = "avx")]
+    pub unsafe fn flatten_quadratic(
+        curve: &QuadraticBezierSegment<f32>,
+        params: &FlatteningParameters,
+        n: usize,
+        output: &mut Vec<Point>,
+    ) {
+        let batches = n / BATCH;
+        output.reserve(n + 1);
+
+        let one = _mm256_set1_ps(1.0);
+        let two = _mm256_set1_ps(2.0);
+        let quarter = _mm256_set1_ps(0.5 * 0.5);
+        let b = _mm256_set1_ps(INV_INTEGRAL_B);
+        let one_minus_b = _mm256_sub_ps(one, b);
+        let b2 = _mm256_mul_ps(b, b);
+        let integral_from = _mm256_set1_ps(params.integral_from);
+        let integral_step = _mm256_set1_ps(params.integral_step);
+        let inv_integral_from = _mm256_set1_ps(params.inv_integral_from);
+        let div_inv_integral_diff = _mm256_set1_ps(params.div_inv_integral_diff);
+        let from_x = _mm256_set1_ps(curve.from.x);
+        let from_y = _mm256_set1_ps(curve.from.y);
+        let ctrl_x = _mm256_mul_ps(_mm256_set1_ps(curve.ctrl.x), two);
+        let ctrl_y = _mm256_mul_ps(_mm256_set1_ps(curve.ctrl.y), two);
+        let to_x = _mm256_set1_ps(curve.to.x);
+        let to_y = _mm256_set1_ps(curve.to.y);
+
+        let mut iteration = _mm256_setr_ps(1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0);
+        let batch_step = _mm256_set1_ps(BATCH as f32);
+
+        let mut dst = output.as_mut_ptr().add(output.len()) as *mut f32;
+        for _ in 0..batches {
+            // t = (approx_parabola_inv_integral(x) - inv_integral_from) * div_inv_integral_diff
+            let x = _mm256_add_ps(integral_from, _mm256_mul_ps(integral_step, iteration));
+            let x2 = _mm256_mul_ps(_mm256_mul_ps(quarter, x), x);
+            let u = _mm256_mul_ps(
+                x,
+                _mm256_add_ps(one_minus_b, _mm256_sqrt_ps(_mm256_add_ps(b2, x2))),
+            );
+            let t = _mm256_mul_ps(_mm256_sub_ps(u, inv_integral_from), div_inv_integral_diff);
+
+            // Same as QuadraticBezierSegment::sample.
+            let t2 = _mm256_mul_ps(t, t);
+            let one_t = _mm256_sub_ps(one, t);
+            let one_t2 = _mm256_mul_ps(one_t, one_t);
+            let one_t_t = |c| _mm256_mul_ps(_mm256_mul_ps(c, one_t), t);
+            let x = _mm256_add_ps(
+                _mm256_add_ps(_mm256_mul_ps(from_x, one_t2), one_t_t(ctrl_x)),
+                _mm256_mul_ps(to_x, t2),
+            );
+            let y = _mm256_add_ps(
+                _mm256_add_ps(_mm256_mul_ps(from_y, one_t2), one_t_t(ctrl_y)),
+                _mm256_mul_ps(to_y, t2),
+            );
+
+            // Interleave the coordinates: lo = (x0 y0 x1 y1 | x4 y4 x5 y5),
+            // hi = (x2 y2 x3 y3 | x6 y6 x7 y7).
+            let lo = _mm256_unpacklo_ps(x, y);
+            let hi = _mm256_unpackhi_ps(x, y);
+            _mm256_storeu_ps(dst, _mm256_permute2f128_ps(lo, hi, 0x20));
+            _mm256_storeu_ps(dst.add(BATCH), _mm256_permute2f128_ps(lo, hi, 0x31));
+            dst = dst.add(BATCH * 2);
+
+            iteration = _mm256_add_ps(iteration, batch_step);
+        }
+        output.set_len(output.len() + batches * BATCH);
+
+        scalar::flatten_quadratic(curve, params, batches * BATCH + 1..=n, output);
+    }
+}
+
+#[test]
+fn simd_matches_scalar() {
+    let mut points = Vec::new();
+    for i in 0..37 {
+        let f = i as f32;
+        points.push(point(f * 1.3 - 20.0, (f * 0.7).sin() * 100.0));
+    }
+
+    for n in [0, 1, 3, 4, 5, 8, 37] {
+        let points = &points[..n];
+        if n > 0 {
+            assert_eq!(bounding_box(points), scalar::bounding_box(points));
+        } else {
+            assert_eq!(bounding_box(points), Box2D::zero());
+        }
+
+        let transform = Transform::new(1.5, 0.3, -0.7, 2.0, 10.0, -5.0);
+        let mut a = points.to_vec();
+        let mut b = points.to_vec();
+        transform_points(&mut a, &transform);
+        scalar::transform_points(&mut b, &transform);
+        assert_eq!(a, b);
+    }
+}
+
+#[test]
+fn flattening_matches_geom() {
+    let curves = [
+        QuadraticBezierSegment {
+            from: point(0.0, 0.0),
+            ctrl: point(100.0, 0.0),
+            to: point(100.0, 100.0),
+        },
+        QuadraticBezierSegment {
+            from: point(-3.5, 20.0),
+            ctrl: point(400.0, -250.0),
+            to: point(10.0, 300.0),
+        },
+        // Linear and degenerate curves.
+        QuadraticBezierSegment {
+            from: point(0.0, 0.0),
+            ctrl: point(1.0, 1.0),
+            to: point(2.0, 2.0),
+        },
+        QuadraticBezierSegment {
+            from: point(5.0, 5.0),
+            ctrl: point(5.0, 5.0),
+            to: point(5.0, 5.0),
+        },
+    ];
+
+    for curve in &curves {
+        // Covers point counts below, at and above the batch size.
+        for &tolerance in &[10.0, 1.0, 0.1, 0.01, 0.001] {
+            let mut expected = vec![point(1.0, 2.0)];
+            let mut actual = vec![point(1.0, 2.0)];
+            let expected_range = curve.flatten_into(&mut expected, tolerance);
+            let actual_range = flatten_quadratic_into(curve, tolerance, &mut actual);
+            assert_eq!(actual, expected);
+            assert_eq!(actual_range, expected_range);
+
+            let cubic = CubicBezierSegment {
+                from: curve.from,
+                ctrl1: curve.ctrl,
+                ctrl2: point(curve.to.x, curve.from.y),
+                to: curve.to,
+            };
+            expected.clear();
+            actual.clear();
+            cubic.flatten_into(&mut expected, tolerance);
+            flatten_cubic_into(&cubic, tolerance, &mut actual);
+            assert_eq!(actual, expected);
+        }
+    }
+}
@@ -0,0 +1,267 @@
+//! Typed views over the sub-paths of a path.
+//!
+//! Some algorithms only make sense for closed sub-paths (for example computing the
+//! enclosed area), others treat open and closed sub-paths differently. Rather than
+//! checking the `close` flag of `End` events at runtime, they can take a
+//! [`ClosedSubPath`](struct.ClosedSubPath.html) or an
+//! [`OpenSubPath`](struct.OpenSubPath.html), which can only be obtained from sub-paths
+//! of the corresponding kind.
+//!
+//! # Example
+//!
+//! ```
+//! use lyon_path::Path;
+//! use lyon_path::math::point;
+//! use lyon_path::sub_path::{SubPath, SubPathKind};
+//!
+//! let mut builder = Path::builder();
+//! builder.begin(point(0.0, 0.0));
+//! builder.line_to(point(1.0, 0.0));
+//! builder.line_to(point(1.0, 1.0));
+//! builder.close();
+//! builder.begin(point(5.0, 5.0));
+//! builder.line_to(point(6.0, 5.0));
+//! builder.end(false);
+//! let path = builder.build();
+//!
+//! for sub_path in path.sub_paths() {
+//!     match sub_path {
+//!         SubPath::Closed(closed) => {
+//!             // `closed` can be passed to algorithms that require closed input.
+//!             assert_eq!(closed.iter().count(), 4);
+//!         }
+//!         SubPath::Open(open) => {
+//!             assert_eq!(open.kind(), SubPathKind::Open);
+//!         }
+//!     }
+//! }
+//!
+//! assert_eq!(path.sub_paths().filter_map(SubPath::into_closed).count(), 1);
+//! ```
+
+use crate::iterator::Segments;
+use crate::path::{Iter, PathSlice, Verb};
+use crate::PathEvent;
+
+/// Whether a sub-path is open or closed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum SubPathKind {
+    Open,
+    Closed,
+}
+
+macro_rules! sub_path_view {
+    ($name:ident, $kind:expr) => {
+        impl<'l> $name<'l> {
+            #[inline]
+            pub fn kind(&self) -> SubPathKind {
+                $kind
+            }
+
+            /// The sub-path as a regular path slice.
+            #[inline]
+            pub fn as_slice(&self) -> PathSlice<'l> {
+                self.0
+            }
+
+            /// Iterates over the events of the sub-path.
+            #[inline]
+            pub fn iter(&self) -> Iter<'l> {
+                self.0.iter()
+            }
+
+            /// Iterates over the segments of the sub-path.
+            #[inline]
+            pub fn segments(&self) -> Segments<Iter<'l>> {
+                self.0.segments()
+            }
+        }
+
+        impl<'l> IntoIterator for $name<'l> {
+            type Item = PathEvent;
+            type IntoIter = Iter<'l>;
+            fn into_iter(self) -> Iter<'l> {
+                self.iter()
+            }
+        }
+
+        impl<'l> From<$name<'l>> for PathSlice<'l> {
+            fn from(sub_path: $name<'l>) -> Self {
+                sub_path.0
+            }
+        }
+    };
+}
+
+/// A sub-path that ends with a `close` event.
+///
+/// Its events end with `Event::End { close: true, .. }`.
+#[derive(Copy, Clone, Debug)]
+pub struct ClosedSubPath<'l>(PathSlice<'l>);
+
+/// A sub-path that does not end with a `close` event.
+///
+/// Its events end with `Event::End { close: false, .. }`.
+#[derive(Copy, Clone, Debug)]
+pub struct OpenSubPath<'l>(PathSlice<'l>);
+
+sub_path_view!(ClosedSubPath, SubPathKind::Closed);
+sub_path_view!(OpenSubPath, SubPathKind::Open);
+
+/// A sub-path of a path, either open or closed.
+#[derive(Copy, Clone, Debug)]
+pub enum SubPath<'l> {
+    Open(OpenSubPath<'l>),
+    Closed(ClosedSubPath<'l>),
+}
+
+impl<'l> SubPath<'l> {
+    #[inline]
+    pub fn kind(&self) -> SubPathKind {
+        match self {
+            SubPath::Open(_) => SubPathKind::Open,
+            SubPath::Closed(_) => SubPathKind::Closed,
+        }
+    }
+
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        self.kind() == SubPathKind::Closed
+    }
+
+    /// The sub-path as a regular path slice.
+    #[inline]
+    pub fn as_slice(&self) -> PathSlice<'l> {
+        match self {
+            SubPath::Open(sub_path) => sub_path.0,
+            SubPath::Closed(sub_path) => sub_path.0,
+        }
+    }
+
+    /// Iterates over the events of the sub-path.
+    #[inline]
+    pub fn iter(&self) -> Iter<'l> {
+        self.as_slice().iter()
+    }
+
+    /// Returns the sub-path if it is closed.
+    #[inline]
+    pub fn into_closed(self) -> Option<ClosedSubPath<'l>> {
+        match self {
+            SubPath::Closed(sub_path) => Some(sub_path),
+            SubPath::Open(_) => None,
+        }
+    }
+
+    /// Returns the sub-path if it is open.
+    #[inline]
+    pub fn into_open(self) -> Option<OpenSubPath<'l>> {
+        match self {
+            SubPath::Open(sub_path) => Some(sub_path),
+            SubPath::Closed(_) => None,
+        }
+    }
+}
+
+/// An iterator over the sub-paths of a path.
+///
+/// Created by `Path::sub_paths` and `PathSlice::sub_paths`.
+#[derive(Clone)]
+pub struct SubPaths<'l> {
+    path: PathSlice<'l>,
+    verb_idx: usize,
+    point_idx: usize,
+}
+
+impl<'l> SubPaths<'l> {
+    pub(crate) fn new(path: PathSlice<'l>) -> Self {
+        SubPaths {
+            path,
+            verb_idx: 0,
+            point_idx: 0,
+        }
+    }
+}
+
+impl<'l> Iterator for SubPaths<'l> {
+    type Item = SubPath<'l>;
+    fn next(&mut self) -> Option<SubPath<'l>> {
+        let verbs = self.path.verbs;
+        let attrib_stride = self.path.num_attributes.div_ceil(2);
+        let first_verb = self.verb_idx;
+        let first_point = self.point_idx;
+        while self.verb_idx < verbs.len() {
+            let verb = verbs[self.verb_idx];
+            self.verb_idx += 1;
+            self.point_idx += match verb {
+                Verb::Begin | Verb::LineTo | Verb::Close => 1 + attrib_stride,
+                Verb::QuadraticTo => 2 + attrib_stride,
+                Verb::CubicTo => 3 + attrib_stride,
+                Verb::End => 0,
+            };
+
+            if verb == Verb::Close || verb == Verb::End {
+                let slice = PathSlice {
+                    points: &self.path.points[first_point..self.point_idx],
+                    verbs: &verbs[first_verb..self.verb_idx],
+                    num_attributes: self.path.num_attributes,
+                };
+                return Some(if verb == Verb::Close {
+                    SubPath::Closed(ClosedSubPath(slice))
+                } else {
+                    SubPath::Open(OpenSubPath(slice))
+                });
+            }
+        }
+
+        None
+    }
+}
+
+#[test]
+fn typed_sub_paths() {
+    use crate::math::point;
+    use crate::Path;
+
+    let mut builder = Path::builder_with_attributes(1);
+    builder.begin(point(0.0, 0.0), &[1.0]);
+    builder.quadratic_bezier_to(point(1.0, 0.0), point(1.0, 1.0), &[2.0]);
+    builder.end(true);
+    builder.begin(point(5.0, 5.0), &[3.0]);
+    builder.cubic_bezier_to(point(6.0, 5.0), point(6.0, 6.0), point(5.0, 6.0), &[4.0]);
+    builder.line_to(point(5.0, 7.0), &[5.0]);
+    builder.end(false);
+    builder.begin(point(9.0, 9.0), &[6.0]);
+    builder.line_to(point(9.0, 10.0), &[7.0]);
+    builder.end(true);
+    let path = builder.build();
+
+    let sub_paths: Vec<SubPath> = path.sub_paths().collect();
+    assert_eq!(sub_paths.len(), 3);
+    assert_eq!(
+        sub_paths.iter().map(SubPath::kind).collect::<Vec<_>>(),
+        vec![SubPathKind::Closed, SubPathKind::Open, SubPathKind::Closed]
+    );
+
+    // The events of the sub-paths are the events of the path.
+    let events: Vec<PathEvent> = sub_paths.iter().flat_map(|sp| sp.iter()).collect();
+    assert_eq!(events, path.iter().collect::<Vec<_>>());
+
+    let open = sub_paths[1].into_open().unwrap();
+    assert!(sub_paths[1].into_closed().is_none());
+    assert_eq!(open.as_slice().first_endpoint().unwrap().1, &[3.0]);
+    assert_eq!(open.as_slice().last_endpoint().unwrap().1, &[5.0]);
+    assert_eq!(open.segments().count(), 2);
+
+    let closed: Vec<ClosedSubPath> = path.sub_paths().filter_map(SubPath::into_closed).collect();
+    assert_eq!(closed.len(), 2);
+    assert_eq!(
+        closed[1].iter().next(),
+        Some(PathEvent::Begin {
+            at: point(9.0, 9.0)
+        })
+    );
+
+    assert_eq!(Path::new().sub_paths().count(), 0);
+}
@@ -0,0 +1,316 @@
+//! Paths with typed units.
+//!
+//! The `Path` data structure uses euclid's `UnknownUnit`. Applications that mix several
+//! coordinate spaces (for example local, world and screen space) can use `TypedPath` to
+//! get compile-time protection against mixing paths from different spaces.
+//!
+//! `TypedPath` is a thin wrapper around `Path`: converting to and from an untyped path
+//! is free, and typed paths can be passed directly to the tessellators.
+//!
+//! # Example
+//!
+//! ```
+//! use lyon_path::typed::TypedPath;
+//! use lyon_path::geom::euclid::{Point2D, Transform2D};
+//!
+//! struct LocalSpace;
+//! struct WorldSpace;
+//!
+//! let mut builder = TypedPath::<LocalSpace>::builder();
+//! builder.begin(Point2D::new(0.0, 0.0));
+//! builder.line_to(Point2D::new(1.0, 0.0));
+//! builder.line_to(Point2D::new(1.0, 1.0));
+//! builder.close();
+//! let local: TypedPath<LocalSpace> = builder.build();
+//!
+//! let transform: Transform2D<f32, LocalSpace, WorldSpace> = Transform2D::scale(2.0, 2.0);
+//! let world: TypedPath<WorldSpace> = local.transformed(&transform);
+//!
+//! for event in world.iter() {
+//!     // Events contain `Point2D<f32, WorldSpace>` positions.
+//! }
+//! ```
+
+use crate::builder::NoAttributes;
+use crate::geom::euclid::{Point2D, Transform2D, UnknownUnit};
+use crate::path::{BuilderImpl, Iter, Path, PathSlice};
+use crate::{EndpointId, Event, PathEvent};
+
+use std::fmt;
+use std::marker::PhantomData;
+
+/// A path in a coordinate space identified by the unit `U`.
+pub struct TypedPath<U = UnknownUnit> {
+    path: Path,
+    _unit: PhantomData<U>,
+}
+
+/// A path event with positions in a typed coordinate space.
+pub type TypedPathEvent<U> = Event<Point2D<f32, U>, Point2D<f32, U>>;
+
+impl<U> TypedPath<U> {
+    /// Creates a builder for typed paths.
+    pub fn builder() -> TypedBuilder<U> {
+        TypedBuilder::new()
+    }
+
+    /// Creates an empty path.
+    #[inline]
+    pub fn new() -> Self {
+        TypedPath::from_untyped(Path::new())
+    }
+
+    /// Tags an untyped path with the unit `U`.
+    #[inline]
+    pub fn from_untyped(path: Path) -> Self {
+        TypedPath {
+            path,
+            _unit: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the underlying untyped path.
+    #[inline]
+    pub fn as_untyped(&self) -> &Path {
+        &self.path
+    }
+
+    /// Drops the unit and returns the underlying untyped path.
+    #[inline]
+    pub fn into_untyped(self) -> Path {
+        self.path
+    }
+
+    /// Returns an untyped view on this path, for example to pass it to a tessellator.
+    #[inline]
+    pub fn as_slice(&self) -> PathSlice<'_> {
+        self.path.as_slice()
+    }
+
+    /// Iterates over the path events with typed positions.
+    pub fn iter(&self) -> TypedIter<'_, U> {
+        TypedIter {
+            iter: self.path.iter(),
+            _unit: PhantomData,
+        }
+    }
+
+    /// Applies a transform from the space `U` to the space `Dst` and returns the result.
+    pub fn transformed<Dst>(self, transform: &Transform2D<f32, U, Dst>) -> TypedPath<Dst> {
+        TypedPath::from_untyped(self.path.transformed(&transform.to_untyped()))
+    }
+
+    /// Returns the same path tagged with a different unit.
+    #[inline]
+    pub fn cast_unit<V>(self) -> TypedPath<V> {
+        TypedPath::from_untyped(self.path)
+    }
+}
+
+impl<U> Default for TypedPath<U> {
+    fn default() -> Self {
+        TypedPath::new()
+    }
+}
+
+impl<U> Clone for TypedPath<U> {
+    fn clone(&self) -> Self {
+        TypedPath::from_untyped(self.path.clone())
+    }
+}
+
+impl<U> fmt::Debug for TypedPath<U> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.path.fmt(formatter)
+    }
+}
+
+impl<U> fmt::Display for TypedPath<U> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.path, formatter)
+    }
+}
+
+impl<'l, U> From<&'l TypedPath<U>> for PathSlice<'l> {
+    fn from(path: &'l TypedPath<U>) -> Self {
+        path.as_slice()
+    }
+}
+
+impl<'l, U> IntoIterator for &'l TypedPath<U> {
+    type Item = TypedPathEvent<U>;
+    type IntoIter = TypedIter<'l, U>;
+
+    fn into_iter(self) -> TypedIter<'l, U> {
+        self.iter()
+    }
+}
+
+/// An iterator of `TypedPathEvent`.
+pub struct TypedIter<'l, U> {
+    iter: Iter<'l>,
+    _unit: PhantomData<U>,
+}
+
+impl<'l, U> Iterator for TypedIter<'l, U> {
+    type Item = TypedPathEvent<U>;
+
+    fn next(&mut self) -> Option<TypedPathEvent<U>> {
+        self.iter.next().map(typed_event)
+    }
+}
+
+fn typed_event<U>(event: PathEvent) -> TypedPathEvent<U> {
+    match event {
+        Event::Begin { at } => Event::Begin {
+            at: Point2D::from_untyped(at),
+        },
+        Event::Line { from, to } => Event::Line {
+            from: Point2D::from_untyped(from),
+            to: Point2D::from_untyped(to),
+        },
+        Event::Quadratic { from, ctrl, to } => Event::Quadratic {
+            from: Point2D::from_untyped(from),
+            ctrl: Point2D::from_untyped(ctrl),
+            to: Point2D::from_untyped(to),
+        },
+        Event::Cubic {
+            from,
+            ctrl1,
+            ctrl2,
+            to,
+        } => Event::Cubic {
+            from: Point2D::from_untyped(from),
+            ctrl1: Point2D::from_untyped(ctrl1),
+            ctrl2: Point2D::from_untyped(ctrl2),
+            to: Point2D::from_untyped(to),
+        },
+        Event::End { last, first, close } => Event::End {
+            last: Point2D::from_untyped(last),
+            first: Point2D::from_untyped(first),
+            close,
+        },
+    }
+}
+
+/// Builds a `TypedPath` from positions in the coordinate space `U`.
+pub struct TypedBuilder<U = UnknownUnit> {
+    builder: NoAttributes<BuilderImpl>,
+    _unit: PhantomData<U>,
+}
+
+impl<U> TypedBuilder<U> {
+    pub fn new() -> Self {
+        TypedBuilder {
+            builder: Path::builder(),
+            _unit: PhantomData,
+        }
+    }
+
+    /// Starts a new sub-path at a given position.
+    ///
+    /// There must be no sub-path in progress when this method is called.
+    #[inline]
+    pub fn begin(&mut self, at: Point2D<f32, U>) -> EndpointId {
+        self.builder.begin(at.to_untyped())
+    }
+
+    /// Ends the current sub path.
+    ///
+    /// A sub-path must be in progress when this method is called.
+    #[inline]
+    pub fn end(&mut self, close: bool) {
+        self.builder.end(close);
+    }
+
+    /// Closes the current sub path.
+    ///
+    /// Shorthand for `builder.end(true)`.
+    #[inline]
+    pub fn close(&mut self) {
+        self.builder.close();
+    }
+
+    /// Adds a line segment to the current sub-path.
+    ///
+    /// A sub-path must be in progress when this method is called.
+    #[inline]
+    pub fn line_to(&mut self, to: Point2D<f32, U>) -> EndpointId {
+        self.builder.line_to(to.to_untyped())
+    }
+
+    /// Adds a quadratic bézier curve to the current sub-path.
+    ///
+    /// A sub-path must be in progress when this method is called.
+    #[inline]
+    pub fn quadratic_bezier_to(
+        &mut self,
+        ctrl: Point2D<f32, U>,
+        to: Point2D<f32, U>,
+    ) -> EndpointId {
+        self.builder
+            .quadratic_bezier_to(ctrl.to_untyped(), to.to_untyped())
+    }
+
+    /// Adds a cubic bézier curve to the current sub-path.
+    ///
+    /// A sub-path must be in progress when this method is called.
+    #[inline]
+    pub fn cubic_bezier_to(
+        &mut self,
+        ctrl1: Point2D<f32, U>,
+        ctrl2: Point2D<f32, U>,
+        to: Point2D<f32, U>,
+    ) -> EndpointId {
+        self.builder
+            .cubic_bezier_to(ctrl1.to_untyped(), ctrl2.to_untyped(), to.to_untyped())
+    }
+
+    /// Builds the typed path, consuming the builder.
+    pub fn build(self) -> TypedPath<U> {
+        TypedPath::from_untyped(self.builder.build())
+    }
+}
+
+impl<U> Default for TypedBuilder<U> {
+    fn default() -> Self {
+        TypedBuilder::new()
+    }
+}
+
+#[test]
+fn typed_path_transform() {
+    use crate::geom::euclid::{point2, Transform2D};
+
+    struct Local;
+    struct World;
+
+    let mut builder = TypedPath::<Local>::builder();
+    builder.begin(point2(0.0, 0.0));
+    builder.line_to(point2(1.0, 0.0));
+    builder.quadratic_bezier_to(point2(1.0, 1.0), point2(0.0, 1.0));
+    builder.close();
+    let path = builder.build();
+
+    let transform: Transform2D<f32, Local, World> = Transform2D::translation(1.0, 2.0);
+    let path: TypedPath<World> = path.transformed(&transform);
+
+    let events: Vec<TypedPathEvent<World>> = path.iter().collect();
+    assert_eq!(
+        events[0],
+        Event::Begin {
+            at: point2(1.0, 2.0)
+        }
+    );
+    assert_eq!(
+        events[2],
+        Event::Quadratic {
+            from: point2(2.0, 2.0),
+            ctrl: point2(2.0, 3.0),
+            to: point2(1.0, 3.0),
+        }
+    );
+
+    let slice: PathSlice = (&path).into();
+    assert_eq!(slice.iter().count(), events.len());
+}
@@ -1,15 +1,24 @@
 use crate::event_queue::{EventQueue, INVALID_EVENT_ID};
+use crate::geom::arrayvec::ArrayVec;
+use crate::geometry_builder::Oriented;
 use crate::math::*;
+use crate::stroke::MAX_ARC_RECURSIONS;
 use crate::{
     FillGeometryBuilder, FillOptions, FillVertex, TessellationError, TessellationResult, VertexId,
 };
 
 use std::f32::consts::PI;
 
-pub fn fill_rectangle(rect: &Box2D, output: &mut dyn FillGeometryBuilder) -> TessellationResult {
+pub fn fill_rectangle(
+    rect: &Box2D,
+    options: &FillOptions,
+    output: &mut dyn FillGeometryBuilder,
+) -> TessellationResult {
+    let output: &mut dyn FillGeometryBuilder = &mut Oriented::fill(options, output);
     output.begin_geometry();
 
     let dummy_queue = EventQueue::new();
+    let uv_transform = &uv_transform(options, rect);
 
     let vertex = &mut |position| {
         output.add_fill_vertex(FillVertex {
@@ -17,7 +26,9 @@ pub fn fill_rectangle(rect: &Box2D, output: &mut dyn FillGeometryBuilder) -> Tes
             events: &dummy_queue,
             current_event: INVALID_EVENT_ID,
             attrib_store: None,
+            attrib_interpolation: None,
             attrib_buffer: &mut [],
+            uv_transform,
         })
     };
 
@@ -40,6 +51,7 @@ pub fn fill_circle(
     options: &FillOptions,
     output: &mut dyn FillGeometryBuilder,
 ) -> TessellationResult {
+    let output: &mut dyn FillGeometryBuilder = &mut Oriented::fill(options, output);
     let radius = radius.abs();
     if radius == 0.0 {
         return Ok(());
@@ -55,6 +67,13 @@ pub fn fill_circle(
     let events = &EventQueue::new();
     let attrib_store = None;
     let current_event = INVALID_EVENT_ID;
+    let uv_transform = &uv_transform(
+        options,
+        &Box2D::new(
+            center - vector(radius, radius),
+            center + vector(radius, radius),
+        ),
+    );
 
     let v = [
         output.add_fill_vertex(FillVertex {
@@ -62,28 +81,36 @@ pub fn fill_circle(
             events,
             current_event,
             attrib_store,
+            attrib_interpolation: None,
             attrib_buffer: &mut [],
+            uv_transform,
         })?,
         output.add_fill_vertex(FillVertex {
             position: center + (up * radius),
             events,
             current_event,
             attrib_store,
+            attrib_interpolation: None,
             attrib_buffer: &mut [],
+            uv_transform,
         })?,
         output.add_fill_vertex(FillVertex {
             position: center + (right * radius),
             events,
             current_event,
             attrib_store,
+            attrib_interpolation: None,
             attrib_buffer: &mut [],
+            uv_transform,
         })?,
         output.add_fill_vertex(FillVertex {
             position: center + (down * radius),
             events,
             current_event,
             attrib_store,
+            attrib_interpolation: None,
             attrib_buffer: &mut [],
+            uv_transform,
         })?,
     ];
 
@@ -111,6 +138,7 @@ pub fn fill_circle(
             v[(i + 1) % 4],
             num_recursions,
             events,
+            uv_transform,
             output,
         )?;
     }
@@ -120,6 +148,13 @@ pub fn fill_circle(
     Ok(())
 }
 
+fn uv_transform(options: &FillOptions, bounds: &Box2D) -> Transform {
+    match options.uv_mapping {
+        Some(mapping) => mapping.to_transform(bounds),
+        None => Transform::identity(),
+    }
+}
+
 fn bottom_left(rect: &Box2D) -> Point {
     point(rect.min.x, rect.max.y)
 }
@@ -151,7 +186,9 @@ pub(crate) fn circle_flattening_step(radius: f32, mut tolerance: f32) -> f32 {
     2.0 * f32::sqrt(2.0 * tolerance * radius - tolerance * tolerance)
 }
 
-// recursively tessellate the rounded corners.
+// Tessellate the rounded corners by recursive subdivision, using an explicit
+// stack to keep stack usage bounded.
+#[allow(clippy::too_many_arguments)]
 fn fill_border_radius(
     center: Point,
     angle: (f32, f32),
@@ -160,47 +197,41 @@ fn fill_border_radius(
     vb: VertexId,
     num_recursions: u32,
     dummy_queue: &EventQueue,
+    uv_transform: &Transform,
     output: &mut dyn FillGeometryBuilder,
 ) -> Result<(), TessellationError> {
-    if num_recursions == 0 {
-        return Ok(());
+    // Like in `tessellate_arc`, the stack needs at most one entry per level plus one.
+    let mut stack: ArrayVec<_, { MAX_ARC_RECURSIONS as usize + 1 }> = ArrayVec::new();
+    stack.push((angle, va, vb, num_recursions.min(MAX_ARC_RECURSIONS)));
+    while let Some((angle, va, vb, num_recursions)) = stack.pop() {
+        if num_recursions == 0 {
+            continue;
+        }
+
+        let mid_angle = (angle.0 + angle.1) * 0.5;
+
+        let normal = vector(mid_angle.cos(), mid_angle.sin());
+        let position = center + normal * radius;
+
+        let vertex = output.add_fill_vertex(FillVertex {
+            position,
+            events: dummy_queue,
+            current_event: INVALID_EVENT_ID,
+            attrib_store: None,
+            attrib_interpolation: None,
+            attrib_buffer: &mut [],
+            uv_transform,
+        })?;
+
+        output.add_triangle(vb, vertex, va);
+
+        // Push the second half first so that vertices are produced in the same
+        // order as a depth-first recursion.
+        stack.push(((mid_angle, angle.1), vertex, vb, num_recursions - 1));
+        stack.push(((angle.0, mid_angle), va, vertex, num_recursions - 1));
     }
 
-    let mid_angle = (angle.0 + angle.1) * 0.5;
-
-    let normal = vector(mid_angle.cos(), mid_angle.sin());
-    let position = center + normal * radius;
-
-    let vertex = output.add_fill_vertex(FillVertex {
-        position,
-        events: dummy_queue,
-        current_event: INVALID_EVENT_ID,
-        attrib_store: None,
-        attrib_buffer: &mut [],
-    })?;
-
-    output.add_triangle(vb, vertex, va);
-
-    fill_border_radius(
-        center,
-        (angle.0, mid_angle),
-        radius,
-        va,
-        vertex,
-        num_recursions - 1,
-        dummy_queue,
-        output,
-    )?;
-    fill_border_radius(
-        center,
-        (mid_angle, angle.1),
-        radius,
-        vertex,
-        vb,
-        num_recursions - 1,
-        dummy_queue,
-        output,
-    )
+    Ok(())
 }
 
 #[test]
@@ -0,0 +1,223 @@
+//! Enforcement of the `max_vertices` and `max_triangles` options.
+
+use crate::geometry_builder::{
+    FillGeometryBuilder, GeometryBuilder, GeometryBuilderError, StrokeGeometryBuilder, StrokePart,
+};
+use crate::{
+    BudgetPolicy, FillVertex, StrokeVertex, TessellationError, TessellationResult, VertexId,
+};
+
+/// The output limits of a tessellation.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) struct OutputLimits {
+    pub max_vertices: u32,
+    pub max_triangles: u32,
+    pub policy: BudgetPolicy,
+}
+
+impl OutputLimits {
+    pub fn is_unlimited(&self) -> bool {
+        self.max_vertices == u32::MAX && self.max_triangles == u32::MAX
+    }
+}
+
+/// Runs a tessellation within output limits.
+///
+/// `tessellate` is called with a tolerance and a geometry builder that stops the
+/// tessellation as soon as the limits are exceeded. Depending on the policy, it is called
+/// again with coarser tolerances.
+pub(crate) fn tessellate_within_limits<B: ?Sized + GeometryBuilder>(
+    limits: OutputLimits,
+    tolerance: f32,
+    output: &mut B,
+    mut tessellate: impl FnMut(f32, &mut OutputBudget<B>) -> TessellationResult,
+) -> TessellationResult {
+    let (factor, max_retries) = match limits.policy {
+        BudgetPolicy::Fail => (1.0, 0),
+        BudgetPolicy::CoarsenTolerance {
+            factor,
+            max_retries,
+        } => (factor, max_retries),
+    };
+
+    let mut tolerance = tolerance;
+    for _ in 0..=max_retries {
+        let mut budget = OutputBudget {
+            output: &mut *output,
+            max_vertices: limits.max_vertices,
+            max_triangles: limits.max_triangles,
+            num_vertices: 0,
+            num_triangles: 0,
+            exceeded: false,
+        };
+        let result = tessellate(tolerance, &mut budget);
+        if !budget.exceeded {
+            return result;
+        }
+
+        tolerance *= factor;
+    }
+
+    Err(TessellationError::OutputLimitExceeded)
+}
+
+/// A geometry builder wrapper that fails when too many vertices or triangles are produced.
+pub(crate) struct OutputBudget<'l, B: ?Sized> {
+    output: &'l mut B,
+    max_vertices: u32,
+    max_triangles: u32,
+    num_vertices: u32,
+    num_triangles: u32,
+    exceeded: bool,
+}
+
+impl<'l, B: ?Sized> OutputBudget<'l, B> {
+    fn add_vertex(&mut self) -> Result<(), GeometryBuilderError> {
+        if self.exceeded || self.num_vertices >= self.max_vertices {
+            self.exceeded = true;
+            return Err(GeometryBuilderError::TooManyVertices);
+        }
+        self.num_vertices += 1;
+
+        Ok(())
+    }
+}
+
+impl<'l, B: ?Sized + GeometryBuilder> GeometryBuilder for OutputBudget<'l, B> {
+    fn begin_geometry(&mut self) {
+        self.num_vertices = 0;
+        self.num_triangles = 0;
+        self.exceeded = false;
+        self.output.begin_geometry();
+    }
+
+    fn end_geometry(&mut self) {
+        if self.exceeded {
+            self.output.abort_geometry();
+        } else {
+            self.output.end_geometry();
+        }
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        if self.exceeded {
+            return;
+        }
+        if self.num_triangles >= self.max_triangles {
+            // Triangles can't fail, the next vertex will.
+            self.exceeded = true;
+            return;
+        }
+        self.num_triangles += 1;
+        self.output.add_triangle(a, b, c);
+    }
+
+    fn abort_geometry(&mut self) {
+        self.output.abort_geometry();
+    }
+}
+
+impl<'l, B: ?Sized + FillGeometryBuilder> FillGeometryBuilder for OutputBudget<'l, B> {
+    fn add_fill_vertex(&mut self, vertex: FillVertex) -> Result<VertexId, GeometryBuilderError> {
+        self.add_vertex()?;
+        self.output.add_fill_vertex(vertex)
+    }
+}
+
+impl<'l, B: ?Sized + StrokeGeometryBuilder> StrokeGeometryBuilder for OutputBudget<'l, B> {
+    fn add_stroke_vertex(
+        &mut self,
+        vertex: StrokeVertex,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        self.add_vertex()?;
+        self.output.add_stroke_vertex(vertex)
+    }
+
+    fn set_stroke_part(&mut self, part: StrokePart) {
+        self.output.set_stroke_part(part);
+    }
+}
+
+#[test]
+fn output_limits() {
+    use crate::geometry_builder::{simple_builder, VertexBuffers};
+    use crate::math::{point, Point};
+    use crate::path::Path;
+    use crate::{FillOptions, FillTessellator, StrokeOptions, StrokeTessellator};
+
+    let mut builder = Path::builder();
+    builder.add_circle(point(0.0, 0.0), 100.0, crate::path::Winding::Positive);
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    let options = FillOptions::tolerance(0.01);
+    let mut tessellator = FillTessellator::new();
+    tessellator
+        .tessellate_path(&path, &options, &mut simple_builder(&mut buffers))
+        .unwrap();
+    let num_vertices = buffers.vertices.len() as u32;
+    let num_triangles = buffers.indices.len() as u32 / 3;
+
+    // Retry with coarser tolerances.
+    let mut limited: VertexBuffers<Point, u16> = VertexBuffers::new();
+    tessellator
+        .tessellate_path(
+            &path,
+            &options.with_max_vertices(num_vertices / 2),
+            &mut simple_builder(&mut limited),
+        )
+        .unwrap();
+    assert!(!limited.vertices.is_empty());
+    assert!(limited.vertices.len() as u32 <= num_vertices / 2);
+    assert_eq!(limited.indices.len(), limited.vertices.len() * 3 - 6);
+
+    let mut limited: VertexBuffers<Point, u16> = VertexBuffers::new();
+    tessellator
+        .tessellate_path(
+            &path,
+            &options.with_max_triangles(num_triangles / 2),
+            &mut simple_builder(&mut limited),
+        )
+        .unwrap();
+    assert!(!limited.indices.is_empty());
+    assert!(limited.indices.len() as u32 / 3 <= num_triangles / 2);
+
+    // Fail without touching the existing content of the output.
+    let result = tessellator.tessellate_path(
+        &path,
+        &options
+            .with_max_vertices(num_vertices - 1)
+            .with_budget_policy(BudgetPolicy::Fail),
+        &mut simple_builder(&mut buffers),
+    );
+    assert_eq!(result, Err(TessellationError::OutputLimitExceeded));
+    assert_eq!(buffers.vertices.len() as u32, num_vertices);
+    assert_eq!(buffers.indices.len() as u32, num_triangles * 3);
+
+    // Not enough retries.
+    let result = tessellator.tessellate_path(
+        &path,
+        &options.with_max_vertices(3),
+        &mut simple_builder(&mut buffers),
+    );
+    assert_eq!(result, Err(TessellationError::OutputLimitExceeded));
+
+    let options = StrokeOptions::tolerance(0.01).with_line_width(5.0);
+    let mut tessellator = StrokeTessellator::new();
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    tessellator
+        .tessellate_path(&path, &options, &mut simple_builder(&mut buffers))
+        .unwrap();
+    let num_vertices = buffers.vertices.len() as u32;
+
+    let mut limited: VertexBuffers<Point, u16> = VertexBuffers::new();
+    tessellator
+        .tessellate_path(
+            &path,
+            &options.with_max_vertices(num_vertices / 2),
+            &mut simple_builder(&mut limited),
+        )
+        .unwrap();
+    assert!(!limited.vertices.is_empty());
+    assert!(limited.vertices.len() as u32 <= num_vertices / 2);
+}
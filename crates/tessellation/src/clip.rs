@@ -0,0 +1,1268 @@
+use crate::geom::euclid::default::Point2D;
+use crate::geometry_builder::{
+    BuffersBuilder, FillVertexConstructor, MaxIndex, Positions, StrokeVertexConstructor,
+    VertexBuffers,
+};
+use crate::math::*;
+use crate::path::builder::PathBuilder;
+use crate::path::{AttributeStore, PathEvent, PathSlice, NO_ATTRIBUTES};
+use crate::stroke::StrokeVertexData;
+use crate::{
+    FillOptions, FillTessellator, GeometryBuilder, GeometryBuilderError, Order, Side,
+    StrokeGeometryBuilder, StrokeOptions, StrokeTessellator, StrokeVertex, TessellationError,
+    TessellationResult, VertexId, VertexSource,
+};
+
+use std::collections::HashMap;
+use std::ops::{Add, Range};
+
+/// A context object that tessellates strokes clipped to the fill of another path.
+///
+/// This is a cheaper alternative to computing the intersection of the two shapes with
+/// path boolean operations for the common case of masking a stroke with a shape.
+///
+/// ## Overview
+///
+/// The stroke and the fill of the clip path are tessellated separately, after which
+/// each triangle of the stroke is clipped against the triangles of the fill that are
+/// near it, which are found with a uniform grid. The vertices of the resulting geometry
+/// are produced as `StrokeVertex` values, so existing stroke vertex constructors and
+/// geometry builders can be used as is.
+///
+/// Vertices introduced by the clipping are interpolated from the vertices of the
+/// stroke triangle they belong to (position on the path, normal, line width, advancement
+/// and custom attributes). Their side and source are the ones of the closest vertex
+/// of that triangle. Where an edge of the fill crosses an edge of the stroke, the
+/// triangles on both sides share the same vertex so that the output has no T-junctions.
+///
+/// Since the stroke tessellator does not remove overlaps, the clipped stroke has the
+/// same overlapping triangles as the unclipped stroke would have.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate lyon_tessellation as tess;
+/// # use tess::path::Path;
+/// # use tess::math::*;
+/// # use tess::geometry_builder::{VertexBuffers, simple_builder};
+/// # use tess::*;
+/// # fn main() {
+/// let mut builder = Path::builder();
+/// builder.begin(point(0.0, 5.0));
+/// builder.line_to(point(20.0, 5.0));
+/// builder.end(false);
+/// let line = builder.build();
+///
+/// let mut builder = Path::builder();
+/// builder.add_circle(point(10.0, 5.0), 4.0, path::Winding::Positive);
+/// let circle = builder.build();
+///
+/// let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+/// let mut tessellator = ClippedStrokeTessellator::new();
+/// tessellator.tessellate(
+///     line.iter(),
+///     &StrokeOptions::default().with_line_width(2.0),
+///     circle.iter(),
+///     &FillOptions::default(),
+///     &mut simple_builder(&mut buffers),
+/// ).unwrap();
+/// # }
+/// ```
+#[derive(Default)]
+pub struct ClippedStrokeTessellator {
+    stroker: StrokeTessellator,
+    filler: FillTessellator,
+    stroke: StrokeCapture,
+    clip: VertexBuffers<Point, u32>,
+    clip_rects: Vec<Box2D>,
+    clip_grid: ClipGrid,
+    candidates: Vec<u32>,
+    polygon: Vec<ClipPoint>,
+    tmp: Vec<ClipPoint>,
+    vertex_ids: Vec<VertexId>,
+    intersections: HashMap<IntersectionKey, VertexId>,
+    attrib_buffer: Vec<f32>,
+}
+
+impl ClippedStrokeTessellator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tessellates the stroke of a path iterator clipped to the fill of another path
+    /// iterator.
+    pub fn tessellate(
+        &mut self,
+        stroke: impl IntoIterator<Item = PathEvent>,
+        stroke_options: &StrokeOptions,
+        clip: impl IntoIterator<Item = PathEvent>,
+        clip_options: &FillOptions,
+        output: &mut dyn StrokeGeometryBuilder,
+    ) -> TessellationResult {
+        self.stroke.clear();
+        self.stroker
+            .tessellate(stroke, stroke_options, &mut self.stroke)?;
+
+        self.tessellate_clip(clip, clip_options)?;
+
+        self.clip_to_geometry(output)
+    }
+
+    /// Tessellates the stroke of a path slice clipped to the fill of a path iterator.
+    ///
+    /// The custom attributes of the stroked path are interpolated at the vertices
+    /// introduced by the clipping.
+    pub fn tessellate_path<'l>(
+        &mut self,
+        stroke: impl Into<PathSlice<'l>>,
+        stroke_options: &StrokeOptions,
+        clip: impl IntoIterator<Item = PathEvent>,
+        clip_options: &FillOptions,
+        output: &mut dyn StrokeGeometryBuilder,
+    ) -> TessellationResult {
+        let stroke = stroke.into();
+        self.stroke.clear();
+        self.stroke.num_attributes = stroke.num_attributes();
+        self.stroker
+            .tessellate_path(stroke, stroke_options, &mut self.stroke)?;
+
+        self.tessellate_clip(clip, clip_options)?;
+
+        self.clip_to_geometry(output)
+    }
+
+    /// Computes the stroke of a path iterator clipped to the fill of another path
+    /// iterator and writes it as a path.
+    ///
+    /// The output is made of small convex polygons with a positive winding order which
+    /// may overlap each other, it is meant to be filled using the `NonZero` fill rule.
+    pub fn clipped_outline(
+        &mut self,
+        stroke: impl IntoIterator<Item = PathEvent>,
+        stroke_options: &StrokeOptions,
+        clip: impl IntoIterator<Item = PathEvent>,
+        clip_options: &FillOptions,
+        output: &mut impl PathBuilder,
+    ) -> TessellationResult {
+        self.stroke.clear();
+        self.stroker
+            .tessellate(stroke, stroke_options, &mut self.stroke)?;
+
+        self.tessellate_clip(clip, clip_options)?;
+
+        let num_triangles = self.stroke.indices.len() / 3;
+        for triangle in 0..num_triangles {
+            let corners = self.stroke.triangle(triangle);
+            if self.stroke.is_degenerate(corners) {
+                continue;
+            }
+            let stroke_rect =
+                Box2D::from_points(corners.iter().map(|&idx| self.stroke.position(idx)));
+            self.clip_grid.query(&stroke_rect, &mut self.candidates);
+            for candidate in 0..self.candidates.len() {
+                let clip_triangle = self.candidates[candidate] as usize;
+                if !self.clip_rects[clip_triangle].intersects(&stroke_rect) {
+                    continue;
+                }
+
+                if !self.clip_triangle(corners, triangle as u32, clip_triangle) {
+                    continue;
+                }
+
+                let mut double_area = 0.0;
+                let n = self.polygon.len();
+                for i in 0..n {
+                    let a = self.polygon[i].position;
+                    let b = self.polygon[(i + 1) % n].position;
+                    double_area += a.to_vector().cross(b.to_vector());
+                }
+
+                let mut points = self.polygon.iter().map(|p| p.position);
+                if double_area >= 0.0 {
+                    output.begin(points.next().unwrap(), NO_ATTRIBUTES);
+                    for p in points {
+                        output.line_to(p, NO_ATTRIBUTES);
+                    }
+                } else {
+                    let mut points = points.rev();
+                    output.begin(points.next().unwrap(), NO_ATTRIBUTES);
+                    for p in points {
+                        output.line_to(p, NO_ATTRIBUTES);
+                    }
+                }
+                output.end(true);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn tessellate_clip(
+        &mut self,
+        clip: impl IntoIterator<Item = PathEvent>,
+        options: &FillOptions,
+    ) -> TessellationResult {
+        self.clip.vertices.clear();
+        self.clip.indices.clear();
+        self.filler.tessellate(
+            clip,
+            options,
+            &mut BuffersBuilder::new(&mut self.clip, Positions),
+        )?;
+
+        self.clip_rects.clear();
+        for triangle in self.clip.indices.chunks(3) {
+            let rect =
+                Box2D::from_points(triangle.iter().map(|&idx| self.clip.vertices[idx as usize]));
+            self.clip_rects.push(rect);
+        }
+        self.clip_grid.build(&self.clip_rects);
+
+        Ok(())
+    }
+
+    // Computes the intersection of a stroke triangle with a clip triangle and stores it
+    // in `self.polygon`. Returns false if the intersection is empty.
+    fn clip_triangle(
+        &mut self,
+        corners: [u32; 3],
+        stroke_triangle: u32,
+        clip_triangle: usize,
+    ) -> bool {
+        self.polygon.clear();
+        for (i, &idx) in corners.iter().enumerate() {
+            self.polygon.push(ClipPoint {
+                position: self.stroke.position(idx),
+                corner: Some(i as u8),
+                key: None,
+                edge: PolygonEdge::Stroke(sorted(idx, corners[(i + 1) % 3])),
+            });
+        }
+
+        let ci = &self.clip.indices[clip_triangle * 3..clip_triangle * 3 + 3];
+        let ci = [ci[0], ci[1], ci[2]];
+        let c = ci.map(|idx| self.clip.vertices[idx as usize]);
+        let sign = if (c[1] - c[0]).cross(c[2] - c[0]) >= 0.0 {
+            1.0
+        } else {
+            -1.0
+        };
+
+        for i in 0..3 {
+            let edge_from = c[i];
+            let edge = c[(i + 1) % 3] - edge_from;
+            let clip_edge = sorted(ci[i], ci[(i + 1) % 3]);
+            let distance = |p: Point| sign * edge.cross(p - edge_from);
+
+            self.tmp.clear();
+            let n = self.polygon.len();
+            for j in 0..n {
+                let current = self.polygon[j];
+                let next = self.polygon[(j + 1) % n];
+                let d0 = distance(current.position);
+                let d1 = distance(next.position);
+                if d0 >= 0.0 {
+                    self.tmp.push(current);
+                }
+                if (d0 >= 0.0) != (d1 >= 0.0) {
+                    let t = d0 / (d0 - d1);
+                    let mut position = current.position.lerp(next.position, t);
+                    let key = match current.edge {
+                        PolygonEdge::Stroke(stroke) => IntersectionKey::Edges {
+                            clip: clip_edge,
+                            stroke,
+                        },
+                        // The two edges of the clip triangle meet at their common vertex.
+                        PolygonEdge::Clip((a, b)) => {
+                            let vertex = if a == clip_edge.0 || a == clip_edge.1 {
+                                a
+                            } else {
+                                b
+                            };
+                            position = self.clip.vertices[vertex as usize];
+                            IntersectionKey::ClipVertex {
+                                vertex,
+                                stroke_triangle,
+                            }
+                        }
+                    };
+                    // Leaving the clip triangle continues along the clip edge, entering it
+                    // continues along the current edge.
+                    let edge = if d0 >= 0.0 {
+                        PolygonEdge::Clip(clip_edge)
+                    } else {
+                        current.edge
+                    };
+                    self.tmp.push(ClipPoint {
+                        position,
+                        corner: None,
+                        key: Some(key),
+                        edge,
+                    });
+                }
+            }
+
+            std::mem::swap(&mut self.polygon, &mut self.tmp);
+            if self.polygon.len() < 3 {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    fn clip_to_geometry(&mut self, output: &mut dyn StrokeGeometryBuilder) -> TessellationResult {
+        output.begin_geometry();
+        match self.clip_to_geometry_impl(output) {
+            Ok(()) => {
+                output.end_geometry();
+                Ok(())
+            }
+            Err(e) => {
+                output.abort_geometry();
+                Err(e.into())
+            }
+        }
+    }
+
+    fn clip_to_geometry_impl(
+        &mut self,
+        output: &mut dyn StrokeGeometryBuilder,
+    ) -> Result<(), GeometryBuilderError> {
+        self.vertex_ids.clear();
+        self.vertex_ids
+            .resize(self.stroke.vertices.len(), VertexId::INVALID);
+        self.intersections.clear();
+
+        let num_triangles = self.stroke.indices.len() / 3;
+        for triangle in 0..num_triangles {
+            let corners = self.stroke.triangle(triangle);
+            if self.stroke.is_degenerate(corners) {
+                continue;
+            }
+            let stroke_rect =
+                Box2D::from_points(corners.iter().map(|&idx| self.stroke.position(idx)));
+            self.clip_grid.query(&stroke_rect, &mut self.candidates);
+            for candidate in 0..self.candidates.len() {
+                let clip_triangle = self.candidates[candidate] as usize;
+                if !self.clip_rects[clip_triangle].intersects(&stroke_rect) {
+                    continue;
+                }
+
+                if !self.clip_triangle(corners, triangle as u32, clip_triangle) {
+                    continue;
+                }
+
+                let first = self.add_vertex(corners, 0, output)?;
+                let mut prev = self.add_vertex(corners, 1, output)?;
+                for i in 2..self.polygon.len() {
+                    let current = self.add_vertex(corners, i, output)?;
+                    if first != prev && prev != current && current != first {
+                        output.add_triangle(first, prev, current);
+                    }
+                    prev = current;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn add_vertex(
+        &mut self,
+        corners: [u32; 3],
+        polygon_idx: usize,
+        output: &mut dyn StrokeGeometryBuilder,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        let point = self.polygon[polygon_idx];
+
+        if let Some(corner) = point.corner {
+            let idx = corners[corner as usize] as usize;
+            if self.vertex_ids[idx] == VertexId::INVALID {
+                let v = &self.stroke.vertices[idx];
+                self.attrib_buffer.clear();
+                self.attrib_buffer
+                    .extend_from_slice(self.stroke.attributes(idx as u32));
+                let mut data = StrokeVertexData {
+                    position_on_path: v.position_on_path,
+                    half_width: v.half_width,
+                    normal: v.normal,
+                    advancement: v.advancement,
+                    side: v.side,
+                    convex_side: v.convex_side,
+                    order: v.order,
+                    src: v.src,
+                    coverage: 1.0,
+                    buffer: &mut self.attrib_buffer,
+                    buffer_is_valid: true,
+                    interpolation: None,
+                    origin: Point2D::origin(),
+                    position_on_path_f64: None,
+                };
+                self.vertex_ids[idx] = output.add_stroke_vertex(StrokeVertex(&mut data, &()))?;
+            }
+
+            return Ok(self.vertex_ids[idx]);
+        }
+
+        if let Some(&id) = point.key.and_then(|key| self.intersections.get(&key)) {
+            return Ok(id);
+        }
+
+        let v = [
+            &self.stroke.vertices[corners[0] as usize],
+            &self.stroke.vertices[corners[1] as usize],
+            &self.stroke.vertices[corners[2] as usize],
+        ];
+        let p = [v[0].position(), v[1].position(), v[2].position()];
+
+        // Barycentric coordinates of the new vertex in the stroke triangle.
+        let area = (p[1] - p[0]).cross(p[2] - p[0]);
+        let mut w = [
+            (p[2] - p[1]).cross(point.position - p[1]) / area,
+            (p[0] - p[2]).cross(point.position - p[2]) / area,
+            0.0,
+        ];
+        w[2] = 1.0 - w[0] - w[1];
+
+        let mut closest = 0;
+        for i in 1..3 {
+            if w[i] > w[closest] {
+                closest = i;
+            }
+        }
+
+        let mut position_on_path = Point::zero();
+        let mut offset = Vector::zero();
+        let mut half_width = 0.0;
+        let mut advancement = 0.0;
+        for i in 0..3 {
+            position_on_path += v[i].position_on_path.to_vector() * w[i];
+            offset += v[i].normal * v[i].half_width * w[i];
+            half_width += v[i].half_width * w[i];
+            advancement += v[i].advancement * w[i];
+        }
+        let normal = if half_width > 0.0 {
+            offset / half_width
+        } else {
+            Vector::zero()
+        };
+
+        self.attrib_buffer.clear();
+        let num_attributes = self.stroke.num_attributes;
+        for a in 0..num_attributes {
+            let mut value = 0.0;
+            for i in 0..3 {
+                value += self.stroke.attributes(corners[i])[a] * w[i];
+            }
+            self.attrib_buffer.push(value);
+        }
+
+        let mut data = StrokeVertexData {
+            position_on_path,
+            half_width,
+            normal,
+            advancement,
+            side: v[closest].side,
+            convex_side: v[closest].convex_side,
+            order: None,
+            src: v[closest].src,
+            coverage: 1.0,
+            buffer: &mut self.attrib_buffer,
+            buffer_is_valid: true,
+            interpolation: None,
+            origin: Point2D::origin(),
+            position_on_path_f64: None,
+        };
+
+        let id = output.add_stroke_vertex(StrokeVertex(&mut data, &()))?;
+        if let Some(key) = point.key {
+            self.intersections.insert(key, id);
+        }
+
+        Ok(id)
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct ClipPoint {
+    position: Point,
+    // Which corner of the stroke triangle this point is, if any.
+    corner: Option<u8>,
+    // Identifies the point if it was introduced by the clipping.
+    key: Option<IntersectionKey>,
+    // The edge that the segment from this point to the next one lies on.
+    edge: PolygonEdge,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum PolygonEdge {
+    // An edge of the stroke triangle, as sorted stroke vertex indices.
+    Stroke((u32, u32)),
+    // An edge of the clip triangle, as sorted clip vertex indices.
+    Clip((u32, u32)),
+}
+
+/// Identifies the vertices introduced by the clipping, so that they are shared by all of
+/// the polygons they belong to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum IntersectionKey {
+    /// Where an edge of the clip triangles crosses an edge of the stroke triangles.
+    Edges {
+        clip: (u32, u32),
+        stroke: (u32, u32),
+    },
+    /// A vertex of the clip triangles inside a stroke triangle.
+    ClipVertex { vertex: u32, stroke_triangle: u32 },
+}
+
+fn sorted(a: u32, b: u32) -> (u32, u32) {
+    if a < b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+/// A uniform grid over the bounding rectangles of the clip triangles, so that each
+/// stroke triangle is only tested against the clip triangles near it.
+#[derive(Default)]
+struct ClipGrid {
+    bounds: Box2D,
+    columns: usize,
+    rows: usize,
+    cells_per_unit: Vector,
+    // The triangles of cell `i` are `triangles[cell_starts[i]..cell_starts[i + 1]]`.
+    cell_starts: Vec<u32>,
+    triangles: Vec<u32>,
+}
+
+impl ClipGrid {
+    fn build(&mut self, rects: &[Box2D]) {
+        self.cell_starts.clear();
+        self.triangles.clear();
+        self.columns = 0;
+        self.rows = 0;
+        if rects.is_empty() {
+            return;
+        }
+
+        self.bounds = rects[1..]
+            .iter()
+            .fold(rects[0], |bounds, r| bounds.union(r));
+
+        // About one cell per triangle, unless the triangles are large enough to be
+        // referenced by many cells each, in which case a coarser grid is used.
+        let mut resolution = (rects.len() as f32).sqrt().ceil() as usize;
+        loop {
+            self.set_resolution(resolution);
+            let entries: usize = rects
+                .iter()
+                .map(|r| {
+                    let (columns, rows) = self.cell_range(r);
+                    columns.len() * rows.len()
+                })
+                .sum();
+            if entries <= rects.len() * 4 || resolution == 1 {
+                break;
+            }
+            resolution /= 2;
+        }
+
+        // Counting sort of the triangles by cell, in increasing order in each cell.
+        let num_cells = self.columns * self.rows;
+        self.cell_starts.resize(num_cells + 1, 0);
+        for r in rects {
+            let (columns, rows) = self.cell_range(r);
+            for y in rows {
+                for x in columns.clone() {
+                    self.cell_starts[y * self.columns + x] += 1;
+                }
+            }
+        }
+        for i in 1..=num_cells {
+            self.cell_starts[i] += self.cell_starts[i - 1];
+        }
+        self.triangles
+            .resize(self.cell_starts[num_cells] as usize, 0);
+        for (triangle, r) in rects.iter().enumerate().rev() {
+            let (columns, rows) = self.cell_range(r);
+            for y in rows {
+                for x in columns.clone() {
+                    let start = &mut self.cell_starts[y * self.columns + x];
+                    *start -= 1;
+                    self.triangles[*start as usize] = triangle as u32;
+                }
+            }
+        }
+    }
+
+    fn set_resolution(&mut self, resolution: usize) {
+        self.columns = resolution;
+        self.rows = resolution;
+        let size = self.bounds.size();
+        let per_unit = |length: f32| {
+            if length > 0.0 {
+                resolution as f32 / length
+            } else {
+                0.0
+            }
+        };
+        self.cells_per_unit = vector(per_unit(size.width), per_unit(size.height));
+    }
+
+    fn cell_range(&self, rect: &Box2D) -> (Range<usize>, Range<usize>) {
+        let column = |x: f32| {
+            (((x - self.bounds.min.x) * self.cells_per_unit.x).max(0.0) as usize)
+                .min(self.columns - 1)
+        };
+        let row = |y: f32| {
+            (((y - self.bounds.min.y) * self.cells_per_unit.y).max(0.0) as usize).min(self.rows - 1)
+        };
+
+        (
+            column(rect.min.x)..column(rect.max.x) + 1,
+            row(rect.min.y)..row(rect.max.y) + 1,
+        )
+    }
+
+    /// Writes the clip triangles in the cells overlapping `rect` into `output`, in
+    /// increasing order.
+    fn query(&self, rect: &Box2D, output: &mut Vec<u32>) {
+        output.clear();
+        if self.columns == 0 {
+            return;
+        }
+
+        let (columns, rows) = self.cell_range(rect);
+        for y in rows {
+            let cells = y * self.columns + columns.start..y * self.columns + columns.end;
+            let triangles =
+                self.cell_starts[cells.start] as usize..self.cell_starts[cells.end] as usize;
+            output.extend_from_slice(&self.triangles[triangles]);
+        }
+        output.sort_unstable();
+        output.dedup();
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct CapturedVertex {
+    position_on_path: Point,
+    normal: Vector,
+    half_width: f32,
+    advancement: f32,
+    side: Side,
+    convex_side: Option<Side>,
+    order: Option<Order>,
+    src: VertexSource,
+}
+
+impl CapturedVertex {
+    fn position(&self) -> Point {
+        self.position_on_path + self.normal * self.half_width
+    }
+}
+
+/// Records the output of the stroke tessellator.
+#[derive(Default)]
+struct StrokeCapture {
+    vertices: Vec<CapturedVertex>,
+    attributes: Vec<f32>,
+    num_attributes: usize,
+    indices: Vec<u32>,
+    first_vertex: usize,
+    first_index: usize,
+}
+
+impl StrokeCapture {
+    fn clear(&mut self) {
+        self.vertices.clear();
+        self.attributes.clear();
+        self.indices.clear();
+        self.num_attributes = 0;
+    }
+
+    fn position(&self, idx: u32) -> Point {
+        self.vertices[idx as usize].position()
+    }
+
+    fn attributes(&self, idx: u32) -> &[f32] {
+        let start = idx as usize * self.num_attributes;
+        &self.attributes[start..start + self.num_attributes]
+    }
+
+    fn is_degenerate(&self, triangle: [u32; 3]) -> bool {
+        let a = self.position(triangle[0]);
+        let b = self.position(triangle[1]);
+        let c = self.position(triangle[2]);
+        (b - a).cross(c - a) == 0.0
+    }
+
+    fn triangle(&self, triangle: usize) -> [u32; 3] {
+        let idx = triangle * 3;
+        [
+            self.indices[idx],
+            self.indices[idx + 1],
+            self.indices[idx + 2],
+        ]
+    }
+}
+
+impl GeometryBuilder for StrokeCapture {
+    fn begin_geometry(&mut self) {
+        self.first_vertex = self.vertices.len();
+        self.first_index = self.indices.len();
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        self.indices.push(a.0);
+        self.indices.push(b.0);
+        self.indices.push(c.0);
+    }
+
+    fn abort_geometry(&mut self) {
+        self.vertices.truncate(self.first_vertex);
+        self.attributes
+            .truncate(self.first_vertex * self.num_attributes);
+        self.indices.truncate(self.first_index);
+    }
+}
+
+impl StrokeGeometryBuilder for StrokeCapture {
+    fn add_stroke_vertex(
+        &mut self,
+        mut vertex: StrokeVertex,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        if self.vertices.len() >= u32::MAX as usize {
+            return Err(GeometryBuilderError::TooManyVertices);
+        }
+
+        self.vertices.push(CapturedVertex {
+            position_on_path: vertex.0.position_on_path,
+            normal: vertex.0.normal,
+            half_width: vertex.0.half_width,
+            advancement: vertex.0.advancement,
+            side: vertex.0.side,
+            convex_side: vertex.0.convex_side,
+            order: vertex.0.order,
+            src: vertex.0.src,
+        });
+
+        let attributes = vertex.interpolated_attributes();
+        debug_assert_eq!(attributes.len(), self.num_attributes);
+        self.attributes.extend_from_slice(attributes);
+
+        Ok(VertexId(self.vertices.len() as u32 - 1))
+    }
+}
+
+/// The index and vertex ranges of the geometry produced by `MaskedTessellator`.
+///
+/// Renderers implementing stencil clipping typically draw the `mask` range into the
+/// stencil buffer and then draw the `content` range with the stencil test enabled.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MaskedGeometry {
+    /// Range of the vertices of the clip mask in the vertex buffer.
+    pub mask_vertices: Range<u32>,
+    /// Range of the indices of the clip mask in the index buffer.
+    pub mask_indices: Range<u32>,
+    /// Range of the vertices of the content in the vertex buffer.
+    pub content_vertices: Range<u32>,
+    /// Range of the indices of the content in the index buffer.
+    pub content_indices: Range<u32>,
+}
+
+/// A context object that tessellates a content path along with the fill of a clip path
+/// into the same vertex and index buffers.
+///
+/// The mask is always written first, followed by the content. Both use the same
+/// vertex type so that a single pipeline can render them.
+///
+/// Unlike `ClippedStrokeTessellator`, the content is not clipped on the CPU: the two
+/// ranges are meant to be combined at render time, for example with a stencil buffer.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate lyon_tessellation as tess;
+/// # use tess::path::{Path, Winding};
+/// # use tess::math::*;
+/// # use tess::geometry_builder::VertexBuffers;
+/// # use tess::*;
+/// # fn main() {
+/// let mut builder = Path::builder();
+/// builder.add_rectangle(&Box2D { min: point(0.0, 0.0), max: point(10.0, 10.0) }, Winding::Positive);
+/// let content = builder.build();
+///
+/// let mut builder = Path::builder();
+/// builder.add_circle(point(10.0, 10.0), 5.0, Winding::Positive);
+/// let clip = builder.build();
+///
+/// let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+/// let mut tessellator = MaskedTessellator::new();
+/// let ranges = tessellator.tessellate_fill(
+///     content.iter(),
+///     &FillOptions::default(),
+///     clip.iter(),
+///     &FillOptions::default(),
+///     &mut buffers,
+///     |v: FillVertex| v.position(),
+///     |v: FillVertex| v.position(),
+/// ).unwrap();
+///
+/// assert_eq!(ranges.mask_indices.end, ranges.content_indices.start);
+/// # }
+/// ```
+#[derive(Default)]
+pub struct MaskedTessellator {
+    fill: FillTessellator,
+    stroke: StrokeTessellator,
+}
+
+impl MaskedTessellator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tessellates the fill of the content path and the fill of the clip path.
+    ///
+    /// If the tessellation fails, nothing is added to the output.
+    pub fn tessellate_fill<OutputVertex, OutputIndex>(
+        &mut self,
+        content: impl IntoIterator<Item = PathEvent>,
+        content_options: &FillOptions,
+        clip: impl IntoIterator<Item = PathEvent>,
+        clip_options: &FillOptions,
+        output: &mut VertexBuffers<OutputVertex, OutputIndex>,
+        content_vertex: impl FillVertexConstructor<OutputVertex>,
+        mask_vertex: impl FillVertexConstructor<OutputVertex>,
+    ) -> Result<MaskedGeometry, TessellationError>
+    where
+        OutputIndex: Add + From<VertexId> + MaxIndex,
+    {
+        let (mask_vertices, mask_indices) =
+            self.tessellate_mask(clip, clip_options, output, mask_vertex)?;
+
+        let first_vertex = output.vertices.len() as u32;
+        let first_index = output.indices.len() as u32;
+        let result = self.fill.tessellate(
+            content,
+            content_options,
+            &mut BuffersBuilder::new(output, content_vertex),
+        );
+        if let Err(e) = result {
+            // Don't leave the mask without its content in the output.
+            output.vertices.truncate(mask_vertices.start as usize);
+            output.indices.truncate(mask_indices.start as usize);
+            return Err(e);
+        }
+
+        Ok(MaskedGeometry {
+            mask_vertices,
+            mask_indices,
+            content_vertices: first_vertex..output.vertices.len() as u32,
+            content_indices: first_index..output.indices.len() as u32,
+        })
+    }
+
+    /// Tessellates the stroke of the content path and the fill of the clip path.
+    ///
+    /// If the tessellation fails, nothing is added to the output.
+    pub fn tessellate_stroke<OutputVertex, OutputIndex>(
+        &mut self,
+        content: impl IntoIterator<Item = PathEvent>,
+        content_options: &StrokeOptions,
+        clip: impl IntoIterator<Item = PathEvent>,
+        clip_options: &FillOptions,
+        output: &mut VertexBuffers<OutputVertex, OutputIndex>,
+        content_vertex: impl StrokeVertexConstructor<OutputVertex>,
+        mask_vertex: impl FillVertexConstructor<OutputVertex>,
+    ) -> Result<MaskedGeometry, TessellationError>
+    where
+        OutputIndex: Add + From<VertexId> + MaxIndex,
+    {
+        let (mask_vertices, mask_indices) =
+            self.tessellate_mask(clip, clip_options, output, mask_vertex)?;
+
+        let first_vertex = output.vertices.len() as u32;
+        let first_index = output.indices.len() as u32;
+        let result = self.stroke.tessellate(
+            content,
+            content_options,
+            &mut BuffersBuilder::new(output, content_vertex),
+        );
+        if let Err(e) = result {
+            // Don't leave the mask without its content in the output.
+            output.vertices.truncate(mask_vertices.start as usize);
+            output.indices.truncate(mask_indices.start as usize);
+            return Err(e);
+        }
+
+        Ok(MaskedGeometry {
+            mask_vertices,
+            mask_indices,
+            content_vertices: first_vertex..output.vertices.len() as u32,
+            content_indices: first_index..output.indices.len() as u32,
+        })
+    }
+
+    fn tessellate_mask<OutputVertex, OutputIndex>(
+        &mut self,
+        clip: impl IntoIterator<Item = PathEvent>,
+        clip_options: &FillOptions,
+        output: &mut VertexBuffers<OutputVertex, OutputIndex>,
+        mask_vertex: impl FillVertexConstructor<OutputVertex>,
+    ) -> Result<(Range<u32>, Range<u32>), TessellationError>
+    where
+        OutputIndex: Add + From<VertexId> + MaxIndex,
+    {
+        let first_vertex = output.vertices.len() as u32;
+        let first_index = output.indices.len() as u32;
+        self.fill.tessellate(
+            clip,
+            clip_options,
+            &mut BuffersBuilder::new(output, mask_vertex),
+        )?;
+
+        Ok((
+            first_vertex..output.vertices.len() as u32,
+            first_index..output.indices.len() as u32,
+        ))
+    }
+}
+
+#[cfg(test)]
+fn triangles_area(buffers: &VertexBuffers<Point, u16>) -> f32 {
+    let mut area = 0.0;
+    for tri in buffers.indices.chunks(3) {
+        let a = buffers.vertices[tri[0] as usize];
+        let b = buffers.vertices[tri[1] as usize];
+        let c = buffers.vertices[tri[2] as usize];
+        area += (b - a).cross(c - a).abs() * 0.5;
+    }
+
+    area
+}
+
+#[test]
+fn clip_line_to_rectangle() {
+    use crate::geometry_builder::simple_builder;
+    use crate::path::{Path, Winding};
+
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(20.0, 0.0));
+    builder.end(false);
+    let line = builder.build();
+
+    let mut builder = Path::builder();
+    builder.add_rectangle(
+        &Box2D {
+            min: point(5.0, -10.0),
+            max: point(15.0, 10.0),
+        },
+        Winding::Positive,
+    );
+    let rect = builder.build();
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    let mut tess = ClippedStrokeTessellator::new();
+    tess.tessellate(
+        line.iter(),
+        &StrokeOptions::default().with_line_width(2.0),
+        rect.iter(),
+        &FillOptions::default(),
+        &mut simple_builder(&mut buffers),
+    )
+    .unwrap();
+
+    assert!((triangles_area(&buffers) - 20.0).abs() < 0.001);
+    for v in &buffers.vertices {
+        assert!(v.x >= 5.0 - 0.001 && v.x <= 15.0 + 0.001);
+    }
+
+    let mut outline = Path::builder();
+    tess.clipped_outline(
+        line.iter(),
+        &StrokeOptions::default().with_line_width(2.0),
+        rect.iter(),
+        &FillOptions::default(),
+        &mut outline,
+    )
+    .unwrap();
+    let outline = outline.build();
+    assert!(outline.iter().count() > 0);
+}
+
+#[test]
+fn clip_no_overlap() {
+    use crate::geometry_builder::simple_builder;
+    use crate::path::{Path, Winding};
+
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(20.0, 0.0));
+    builder.end(false);
+    let line = builder.build();
+
+    let mut builder = Path::builder();
+    builder.add_circle(point(10.0, 50.0), 5.0, Winding::Positive);
+    let circle = builder.build();
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    ClippedStrokeTessellator::new()
+        .tessellate(
+            line.iter(),
+            &StrokeOptions::default(),
+            circle.iter(),
+            &FillOptions::default(),
+            &mut simple_builder(&mut buffers),
+        )
+        .unwrap();
+
+    assert!(buffers.indices.is_empty());
+}
+
+#[test]
+fn clip_shares_vertices() {
+    use crate::geometry_builder::simple_builder;
+    use crate::path::{Path, Winding};
+
+    // Collinear edges so that the stroke triangles don't overlap.
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    for i in 1..=10 {
+        builder.line_to(point(i as f32 * 2.0, 0.0));
+    }
+    builder.end(false);
+    let line = builder.build();
+
+    let mut builder = Path::builder();
+    builder.add_circle(point(10.0, 0.5), 7.0, Winding::Positive);
+    let circle = builder.build();
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    ClippedStrokeTessellator::new()
+        .tessellate(
+            line.iter(),
+            &StrokeOptions::default().with_line_width(4.0),
+            circle.iter(),
+            &FillOptions::tolerance(0.5),
+            &mut simple_builder(&mut buffers),
+        )
+        .unwrap();
+
+    let vertices = &buffers.vertices;
+    for (i, a) in vertices.iter().enumerate() {
+        for b in &vertices[i + 1..] {
+            assert!((*a - *b).length() > 1e-4, "duplicate vertex {:?}", a);
+        }
+    }
+
+    // No vertex lies within an edge of a triangle.
+    for tri in buffers.indices.chunks(3) {
+        for e in 0..3 {
+            let a = vertices[tri[e] as usize];
+            let b = vertices[tri[(e + 1) % 3] as usize];
+            for v in vertices {
+                let t = (*v - a).dot(b - a) / (b - a).square_length();
+                let d = (*v - a).cross(b - a).abs() / (b - a).length();
+                assert!(!(t > 0.01 && t < 0.99 && d < 1e-4), "T-junction at {:?}", v);
+            }
+        }
+    }
+}
+
+#[test]
+fn clip_grid_query() {
+    let mut rects = Vec::new();
+    for i in 0..100 {
+        let min = point((i % 10) as f32 * 3.0, (i / 10) as f32 * 2.0);
+        rects.push(Box2D {
+            min,
+            max: min + vector(4.0, 1.0),
+        });
+    }
+    // Large triangles are referenced by all of the cells they overlap.
+    rects.push(Box2D {
+        min: point(-5.0, -5.0),
+        max: point(50.0, 3.0),
+    });
+
+    let mut grid = ClipGrid::default();
+    grid.build(&rects);
+
+    let mut candidates = Vec::new();
+    for x in 0..12 {
+        for y in 0..8 {
+            let query = Box2D {
+                min: point(x as f32 * 2.5 - 2.0, y as f32 * 2.5 - 2.0),
+                max: point(x as f32 * 2.5 + 1.0, y as f32 * 2.5 + 0.5),
+            };
+            grid.query(&query, &mut candidates);
+            for (i, rect) in rects.iter().enumerate() {
+                if rect.intersects(&query) {
+                    assert!(candidates.binary_search(&(i as u32)).is_ok());
+                }
+            }
+        }
+    }
+
+    grid.build(&[]);
+    grid.query(&rects[0], &mut candidates);
+    assert!(candidates.is_empty());
+}
+
+#[test]
+fn clip_interpolates_attributes() {
+    use crate::geometry_builder::BuffersBuilder;
+    use crate::path::{Path, Winding};
+
+    let mut builder = Path::builder_with_attributes(1);
+    builder.begin(point(0.0, 0.0), &[0.0]);
+    builder.line_to(point(20.0, 0.0), &[20.0]);
+    builder.end(false);
+    let line = builder.build();
+
+    let mut builder = Path::builder();
+    builder.add_rectangle(
+        &Box2D {
+            min: point(5.0, -10.0),
+            max: point(15.0, 10.0),
+        },
+        Winding::Positive,
+    );
+    let rect = builder.build();
+
+    let mut buffers: VertexBuffers<(Point, f32), u16> = VertexBuffers::new();
+    ClippedStrokeTessellator::new()
+        .tessellate_path(
+            &line,
+            &StrokeOptions::default(),
+            rect.iter(),
+            &FillOptions::default(),
+            &mut BuffersBuilder::new(&mut buffers, |mut v: StrokeVertex| {
+                (v.position(), v.interpolated_attributes()[0])
+            }),
+        )
+        .unwrap();
+
+    assert!(!buffers.vertices.is_empty());
+    for (position, attribute) in &buffers.vertices {
+        assert!((position.x - attribute).abs() < 0.001);
+    }
+}
+
+#[test]
+fn masked_stroke_ranges() {
+    use crate::path::{Path, Winding};
+    use crate::FillVertex;
+
+    let mut builder = Path::builder();
+    builder.add_rectangle(
+        &Box2D {
+            min: point(0.0, 0.0),
+            max: point(10.0, 10.0),
+        },
+        Winding::Positive,
+    );
+    let content = builder.build();
+
+    let mut builder = Path::builder();
+    builder.add_rectangle(
+        &Box2D {
+            min: point(5.0, 5.0),
+            max: point(20.0, 20.0),
+        },
+        Winding::Positive,
+    );
+    let clip = builder.build();
+
+    let mut buffers: VertexBuffers<(Point, bool), u16> = VertexBuffers::new();
+    let mut tess = MaskedTessellator::new();
+    let ranges = tess
+        .tessellate_stroke(
+            content.iter(),
+            &StrokeOptions::default(),
+            clip.iter(),
+            &FillOptions::default(),
+            &mut buffers,
+            |v: StrokeVertex| (v.position(), false),
+            |v: FillVertex| (v.position(), true),
+        )
+        .unwrap();
+
+    assert_eq!(ranges.mask_vertices.start, 0);
+    assert_eq!(ranges.mask_indices.start, 0);
+    assert_eq!(ranges.mask_vertices.end, ranges.content_vertices.start);
+    assert_eq!(ranges.mask_indices.end, ranges.content_indices.start);
+    assert_eq!(ranges.content_vertices.end as usize, buffers.vertices.len());
+    assert_eq!(ranges.content_indices.end as usize, buffers.indices.len());
+
+    for i in ranges.mask_indices.clone() {
+        let idx = buffers.indices[i as usize] as u32;
+        assert!(ranges.mask_vertices.contains(&idx));
+        assert!(buffers.vertices[idx as usize].1);
+    }
+    for i in ranges.content_indices.clone() {
+        let idx = buffers.indices[i as usize] as u32;
+        assert!(ranges.content_vertices.contains(&idx));
+        assert!(!buffers.vertices[idx as usize].1);
+    }
+}
+
+#[test]
+fn masked_tessellation_error() {
+    use crate::path::{Path, Winding};
+    use crate::FillVertex;
+
+    let mut builder = Path::builder();
+    builder.add_rectangle(
+        &Box2D {
+            min: point(0.0, 0.0),
+            max: point(10.0, 10.0),
+        },
+        Winding::Positive,
+    );
+    let clip = builder.build();
+
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.end(true);
+    let content = builder.build();
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    buffers.vertices.push(point(0.0, 0.0));
+    buffers.indices.push(0);
+
+    // The content exceeds its budget.
+    let mut tess = MaskedTessellator::new();
+    let result = tess.tessellate_fill(
+        content.iter(),
+        &FillOptions::default().with_max_vertices(1),
+        clip.iter(),
+        &FillOptions::default(),
+        &mut buffers,
+        |v: FillVertex| v.position(),
+        |v: FillVertex| v.position(),
+    );
+    assert!(result.is_err());
+    assert_eq!(buffers.vertices.len(), 1);
+    assert_eq!(buffers.indices.len(), 1);
+
+    let result = tess.tessellate_stroke(
+        content.iter(),
+        &StrokeOptions::default().with_max_vertices(1),
+        clip.iter(),
+        &FillOptions::default(),
+        &mut buffers,
+        |v: StrokeVertex| v.position(),
+        |v: FillVertex| v.position(),
+    );
+    assert!(result.is_err());
+    assert_eq!(buffers.vertices.len(), 1);
+    assert_eq!(buffers.indices.len(), 1);
+}
@@ -0,0 +1,312 @@
+//! Conventions for storing colors in custom attributes.
+//!
+//! Custom attributes are plain `f32` values interpolated by the tessellators. This module
+//! provides two ways of storing a color in them:
+//!
+//! - As four consecutive attributes (red, green, blue, alpha), see `Color::write_attributes`
+//!   and `Color::from_attributes`. Colors stored this way are interpolated correctly along
+//!   edges, curves and at intersections.
+//! - As a single attribute containing the bits of an `0xRRGGBBAA` packed `u32`, see
+//!   `Color::to_packed_attribute` and `Color::from_packed_attribute`. This is more compact
+//!   but the packed value can't be interpolated. With the default attribute interpolation,
+//!   the tessellators keep the exact bits of an attribute on vertices where all of the
+//!   endpoints it is computed from have the same value: vertices on flattened curves, at
+//!   intersections and inserted by `FillOptions::max_triangle_edge_length`. So the packed
+//!   color is only valid everywhere for flat colors, for example one color per path. With
+//!   one color per sub-path, vertices at the intersection of sub-paths of different colors
+//!   get a meaningless value.
+//!
+//! The `ColorAttribute` and `SolidColor` vertex constructors in the `geometry_builder`
+//! module produce `ColoredVertex` values using these conventions.
+
+use crate::path::{AttributeIndex, Attributes};
+
+/// Number of custom attributes used by a color stored as four floats.
+pub const COLOR_ATTRIBUTES: usize = 4;
+
+/// A color with floating point red, green, blue and alpha components.
+///
+/// This type does not specify a color space. Use `to_linear` and `to_srgb` to convert
+/// between sRGB and linear encodings.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct Color {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+impl Color {
+    pub const BLACK: Self = Color::new(0.0, 0.0, 0.0, 1.0);
+    pub const WHITE: Self = Color::new(1.0, 1.0, 1.0, 1.0);
+    pub const TRANSPARENT: Self = Color::new(0.0, 0.0, 0.0, 0.0);
+
+    #[inline]
+    pub const fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Color { r, g, b, a }
+    }
+
+    /// Creates a color from 8 bits per channel values.
+    #[inline]
+    pub fn from_rgba8(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Color::new(
+            r as f32 / 255.0,
+            g as f32 / 255.0,
+            b as f32 / 255.0,
+            a as f32 / 255.0,
+        )
+    }
+
+    /// Returns the 8 bits per channel values of this color.
+    ///
+    /// Components are clamped to the `[0, 1]` range.
+    #[inline]
+    pub fn to_rgba8(&self) -> [u8; 4] {
+        [
+            unorm8(self.r),
+            unorm8(self.g),
+            unorm8(self.b),
+            unorm8(self.a),
+        ]
+    }
+
+    /// Creates a color from a `0xRRGGBBAA` packed value.
+    #[inline]
+    pub fn from_packed(rgba: u32) -> Self {
+        let [r, g, b, a] = rgba.to_be_bytes();
+        Color::from_rgba8(r, g, b, a)
+    }
+
+    /// Returns this color as a `0xRRGGBBAA` packed value.
+    #[inline]
+    pub fn to_packed(&self) -> u32 {
+        u32::from_be_bytes(self.to_rgba8())
+    }
+
+    #[inline]
+    pub fn from_array(rgba: [f32; 4]) -> Self {
+        Color::new(rgba[0], rgba[1], rgba[2], rgba[3])
+    }
+
+    #[inline]
+    pub fn to_array(&self) -> [f32; 4] {
+        [self.r, self.g, self.b, self.a]
+    }
+
+    /// Reads a color stored in four consecutive custom attributes starting at `index`.
+    #[inline]
+    pub fn from_attributes(attributes: Attributes, index: AttributeIndex) -> Self {
+        Color::new(
+            attributes[index],
+            attributes[index + 1],
+            attributes[index + 2],
+            attributes[index + 3],
+        )
+    }
+
+    /// Writes this color into four consecutive custom attributes starting at `index`.
+    #[inline]
+    pub fn write_attributes(&self, attributes: &mut [f32], index: AttributeIndex) {
+        attributes[index..index + COLOR_ATTRIBUTES].copy_from_slice(&self.to_array());
+    }
+
+    /// Returns a single custom attribute containing the bits of the packed color.
+    ///
+    /// The returned value is not meant to be interpreted as a number and can't be
+    /// interpolated. See the [module documentation](index.html) for the vertices on which
+    /// it is preserved.
+    #[inline]
+    pub fn to_packed_attribute(&self) -> f32 {
+        f32::from_bits(self.to_packed())
+    }
+
+    /// Reads a color stored with `to_packed_attribute`.
+    #[inline]
+    pub fn from_packed_attribute(attribute: f32) -> Self {
+        Color::from_packed(attribute.to_bits())
+    }
+
+    /// Converts the color components from the sRGB encoding to linear values.
+    ///
+    /// The alpha component is not affected.
+    #[inline]
+    pub fn to_linear(&self) -> Self {
+        Color::new(
+            srgb_to_linear(self.r),
+            srgb_to_linear(self.g),
+            srgb_to_linear(self.b),
+            self.a,
+        )
+    }
+
+    /// Converts the color components from linear values to the sRGB encoding.
+    ///
+    /// The alpha component is not affected.
+    #[inline]
+    pub fn to_srgb(&self) -> Self {
+        Color::new(
+            linear_to_srgb(self.r),
+            linear_to_srgb(self.g),
+            linear_to_srgb(self.b),
+            self.a,
+        )
+    }
+
+    /// Returns the color with its red, green and blue components multiplied by alpha.
+    #[inline]
+    pub fn premultiplied(&self) -> Self {
+        Color::new(self.r * self.a, self.g * self.a, self.b * self.a, self.a)
+    }
+
+    /// Linearly interpolates between two colors.
+    #[inline]
+    pub fn lerp(&self, other: Self, t: f32) -> Self {
+        let one_t = 1.0 - t;
+        Color::new(
+            self.r * one_t + other.r * t,
+            self.g * one_t + other.g * t,
+            self.b * one_t + other.b * t,
+            self.a * one_t + other.a * t,
+        )
+    }
+}
+
+impl From<[f32; 4]> for Color {
+    fn from(rgba: [f32; 4]) -> Self {
+        Color::from_array(rgba)
+    }
+}
+
+impl From<Color> for [f32; 4] {
+    fn from(color: Color) -> Self {
+        color.to_array()
+    }
+}
+
+/// Converts an sRGB encoded component into a linear value.
+#[inline]
+pub fn srgb_to_linear(v: f32) -> f32 {
+    if v <= 0.04045 {
+        v / 12.92
+    } else {
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Converts a linear component into an sRGB encoded value.
+#[inline]
+pub fn linear_to_srgb(v: f32) -> f32 {
+    if v <= 0.0031308 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+#[inline]
+fn unorm8(v: f32) -> u8 {
+    (v.clamp(0.0, 1.0) * 255.0 + 0.5) as u8
+}
+
+#[test]
+fn packed_round_trip() {
+    let color = Color::from_rgba8(10, 20, 200, 255);
+    assert_eq!(color.to_rgba8(), [10, 20, 200, 255]);
+    assert_eq!(color.to_packed(), 0x0A14C8FF);
+    assert_eq!(Color::from_packed(0x0A14C8FF), color);
+    assert_eq!(
+        Color::from_packed_attribute(color.to_packed_attribute()),
+        color
+    );
+}
+
+#[test]
+fn attributes_round_trip() {
+    let color = Color::new(0.1, 0.2, 0.3, 0.4);
+    let mut attributes = [0.0; 6];
+    color.write_attributes(&mut attributes, 2);
+    assert_eq!(attributes, [0.0, 0.0, 0.1, 0.2, 0.3, 0.4]);
+    assert_eq!(Color::from_attributes(&attributes, 2), color);
+}
+
+#[test]
+fn srgb_round_trip() {
+    for i in 0..=255 {
+        let v = i as f32 / 255.0;
+        assert!((linear_to_srgb(srgb_to_linear(v)) - v).abs() < 0.0001);
+    }
+
+    assert_eq!(srgb_to_linear(0.0), 0.0);
+    assert!((srgb_to_linear(1.0) - 1.0).abs() < 0.0001);
+    assert!((srgb_to_linear(0.5) - 0.214).abs() < 0.001);
+}
+
+#[test]
+fn packed_attributes_on_generated_vertices() {
+    use crate::geometry_builder::{BuffersBuilder, VertexBuffers};
+    use crate::math::point;
+    use crate::path::Path;
+    use crate::{FillOptions, FillRule, FillTessellator, FillVertex, StrokeOptions};
+    use crate::{StrokeTessellator, StrokeVertex, VertexSource};
+
+    // White has the bits of a NaN.
+    for color in [Color::WHITE, Color::from_packed(0x3F3F3FFF)] {
+        let packed = color.to_packed_attribute();
+        let other = Color::BLACK.to_packed_attribute();
+
+        // A self-intersecting curve, and a sub-path with another color.
+        let mut builder = Path::builder_with_attributes(2);
+        builder.begin(point(0.0, 0.0), &[packed, 0.0]);
+        builder.quadratic_bezier_to(point(150.0, 50.0), point(0.0, 100.0), &[packed, 1.0]);
+        builder.line_to(point(100.0, 0.0), &[packed, 2.0]);
+        builder.end(true);
+        builder.begin(point(200.0, 0.0), &[other, 0.0]);
+        builder.line_to(point(300.0, 0.0), &[other, 1.0]);
+        builder.line_to(point(300.0, 100.0), &[other, 2.0]);
+        builder.end(true);
+        let path = builder.build();
+
+        let mut buffers: VertexBuffers<(f32, bool, bool), u16> = VertexBuffers::new();
+        FillTessellator::new()
+            .tessellate_path(
+                &path,
+                &FillOptions::tolerance(0.01)
+                    .with_fill_rule(FillRule::NonZero)
+                    .with_max_triangle_edge_length(20.0),
+                &mut BuffersBuilder::new(&mut buffers, |mut v: FillVertex| {
+                    let sources: Vec<VertexSource> = v.sources().collect();
+                    let is_intersection = sources.len() > 1;
+                    let is_inserted = sources.is_empty();
+                    (v.interpolated_attributes()[0], is_intersection, is_inserted)
+                }),
+            )
+            .unwrap();
+
+        assert!(buffers.vertices.iter().any(|v| v.1));
+        assert!(buffers.vertices.iter().any(|v| v.2));
+        for (attribute, _, _) in &buffers.vertices {
+            let bits = attribute.to_bits();
+            assert!(
+                bits == packed.to_bits() || bits == other.to_bits(),
+                "{:x}",
+                bits
+            );
+        }
+
+        let mut buffers: VertexBuffers<f32, u16> = VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate_path(
+                &path,
+                &StrokeOptions::tolerance(0.01),
+                &mut BuffersBuilder::new(&mut buffers, |mut v: StrokeVertex| {
+                    v.interpolated_attributes()[0]
+                }),
+            )
+            .unwrap();
+        for attribute in &buffers.vertices {
+            let bits = attribute.to_bits();
+            assert!(bits == packed.to_bits() || bits == other.to_bits());
+        }
+    }
+}
@@ -3,6 +3,7 @@ use crate::geom::{CubicBezierSegment, LineSegment, QuadraticBezierSegment};
 use crate::math::{point, Point};
 use crate::path::private::DebugValidator;
 use crate::path::{EndpointId, IdEvent, PathEvent, PositionStore};
+use crate::trace::trace_span;
 use crate::Orientation;
 
 use std::cmp::Ordering;
@@ -29,12 +30,87 @@ pub(crate) struct Event {
 pub(crate) struct EdgeData {
     pub to: Point,
     pub range: std::ops::Range<f32>,
-    pub winding: i16,
+    pub winding: EdgeWinding,
     pub is_edge: bool,
     pub from_id: EndpointId,
     pub to_id: EndpointId,
 }
 
+/// The contribution of an edge to the winding numbers of the painted and clip areas
+/// (see `FillOptions::clip_attribute`).
+///
+/// The two winding numbers are counted separately so that one of them can't overflow
+/// into the other.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct EdgeWinding {
+    pub paint: i16,
+    pub clip: i16,
+}
+
+impl EdgeWinding {
+    pub const ZERO: Self = EdgeWinding { paint: 0, clip: 0 };
+    /// The winding of the edges of painted sub-paths.
+    pub const PAINT: Self = EdgeWinding { paint: 1, clip: 0 };
+    /// The winding of the edges of clip sub-paths.
+    pub const CLIP: Self = EdgeWinding { paint: 0, clip: 1 };
+}
+
+impl std::ops::Neg for EdgeWinding {
+    type Output = Self;
+    #[inline]
+    fn neg(self) -> Self {
+        EdgeWinding {
+            paint: -self.paint,
+            clip: -self.clip,
+        }
+    }
+}
+
+impl std::ops::Add for EdgeWinding {
+    type Output = Self;
+    #[inline]
+    fn add(self, other: Self) -> Self {
+        EdgeWinding {
+            paint: self.paint + other.paint,
+            clip: self.clip + other.clip,
+        }
+    }
+}
+
+impl std::ops::AddAssign for EdgeWinding {
+    #[inline]
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl std::ops::SubAssign for EdgeWinding {
+    #[inline]
+    fn sub_assign(&mut self, other: Self) {
+        *self += -other;
+    }
+}
+
+/// Below this number of events the merge sort is faster than the radix sort.
+const RADIX_SORT_THRESHOLD: usize = 256;
+
+/// Maps a float to an integer with the same ordering.
+///
+/// Negative and positive zero map to the same value since they compare equal.
+#[inline]
+fn ordered_bits(val: f32) -> u32 {
+    let bits = (val + 0.0).to_bits();
+    if bits & 0x8000_0000 != 0 {
+        !bits
+    } else {
+        bits | 0x8000_0000
+    }
+}
+
+/// Approximate number of bytes used by the event queue per event.
+pub(crate) const EVENT_MEMORY_SIZE: usize =
+    std::mem::size_of::<Event>() + std::mem::size_of::<EdgeData>();
+
 #[doc(hidden)]
 /// A queue of sorted events for the fill tessellator's sweep-line algorithm.
 pub struct EventQueue {
@@ -42,6 +118,9 @@ pub struct EventQueue {
     pub(crate) edge_data: Vec<EdgeData>,
     first: TessEventId,
     sorted: bool,
+    pub(crate) max_events: usize,
+    pub(crate) max_events_exceeded: bool,
+    pub(crate) has_clip_edges: bool,
 }
 
 impl Default for EventQueue {
@@ -57,6 +136,9 @@ impl EventQueue {
             edge_data: Vec::new(),
             first: INVALID_EVENT_ID,
             sorted: false,
+            max_events: usize::MAX,
+            max_events_exceeded: false,
+            has_clip_edges: false,
         }
     }
 
@@ -66,6 +148,9 @@ impl EventQueue {
             edge_data: Vec::with_capacity(cap),
             first: 0,
             sorted: false,
+            max_events: usize::MAX,
+            max_events_exceeded: false,
+            has_clip_edges: false,
         }
     }
 
@@ -74,6 +159,8 @@ impl EventQueue {
         self.edge_data.clear();
         self.first = INVALID_EVENT_ID;
         self.sorted = false;
+        self.max_events_exceeded = false;
+        self.has_clip_edges = false;
     }
 
     /// Creates an `EventQueue` from an iterator of path event and a tolerance threshold.
@@ -123,6 +210,7 @@ impl EventQueue {
             nth: 0,
             tolerance,
             prev_endpoint_id: EndpointId(std::u32::MAX),
+            winding_unit: EdgeWinding::PAINT,
             validator: DebugValidator::new(),
         }
     }
@@ -240,7 +328,7 @@ impl EventQueue {
         self.edge_data.push(EdgeData {
             to: point(f32::NAN, f32::NAN),
             range: 0.0..0.0,
-            winding: 0,
+            winding: EdgeWinding::ZERO,
             is_edge: false,
             from_id: endpoint_id,
             to_id: endpoint_id,
@@ -311,8 +399,76 @@ impl EventQueue {
             return;
         }
 
-        let range = 0..self.events.len();
-        self.first = self.merge_sort(range);
+        if self.events.len() < RADIX_SORT_THRESHOLD || !self.radix_sort() {
+            let range = 0..self.events.len();
+            self.first = self.merge_sort(range);
+        }
+    }
+
+    /// LSD radix sort on the bits of the positions, used for large queues where
+    /// the comparison-based merge sort dominates the setup time.
+    ///
+    /// The floating point coordinates are mapped to integers with the same ordering
+    /// so the result is identical to the merge sort's, including the order of events
+    /// in sibling lists (the sort is stable).
+    ///
+    /// Returns false without modifying the queue if some positions can't be
+    /// represented as keys (NaN), in which case the caller falls back to the merge sort.
+    fn radix_sort(&mut self) -> bool {
+        let mut keys = Vec::with_capacity(self.events.len());
+        for (idx, evt) in self.events.iter().enumerate() {
+            let (x, y) = (evt.position.x, evt.position.y);
+            if x.is_nan() || y.is_nan() {
+                return false;
+            }
+            let key = (ordered_bits(y) as u64) << 32 | ordered_bits(x) as u64;
+            keys.push((key, idx as TessEventId));
+        }
+
+        let mut tmp = vec![(0u64, 0 as TessEventId); keys.len()];
+        let mut counts = [0usize; 256];
+        for pass in 0..8 {
+            let shift = pass * 8;
+            counts.iter_mut().for_each(|c| *c = 0);
+            for &(key, _) in &keys {
+                counts[(key >> shift) as usize & 0xFF] += 1;
+            }
+
+            // All keys have the same byte, this pass would not change anything.
+            if counts.contains(&keys.len()) {
+                continue;
+            }
+
+            let mut offset = 0;
+            for c in counts.iter_mut() {
+                let n = *c;
+                *c = offset;
+                offset += n;
+            }
+            for &item in &keys {
+                let bucket = (item.0 >> shift) as usize & 0xFF;
+                tmp[counts[bucket]] = item;
+                counts[bucket] += 1;
+            }
+            swap(&mut keys, &mut tmp);
+        }
+
+        // Link the events in sorted order, grouping equal positions into sibling lists.
+        self.first = keys[0].1;
+        let mut current = keys[0];
+        let mut last_sibling = keys[0].1;
+        for &(key, id) in &keys[1..] {
+            if key == current.0 {
+                self.events[last_sibling as usize].next_sibling = id;
+                last_sibling = id;
+            } else {
+                self.events[current.1 as usize].next_event = id;
+                current = (key, id);
+                last_sibling = id;
+            }
+        }
+
+        true
     }
 
     /// Merge sort with two twists:
@@ -462,6 +618,7 @@ pub struct EventQueueBuilder {
     queue: EventQueue,
     tolerance: f32,
     prev_endpoint_id: EndpointId,
+    winding_unit: EdgeWinding,
     validator: DebugValidator,
 }
 
@@ -478,6 +635,25 @@ impl EventQueueBuilder {
         self.tolerance = tolerance;
     }
 
+    /// Sets the maximum number of events of the queue.
+    ///
+    /// Events past this limit are dropped and the queue is flagged so that the
+    /// tessellator can report an error instead of producing incorrect results.
+    pub fn set_max_events(&mut self, max_events: usize) {
+        self.queue.max_events = max_events;
+    }
+
+    /// Returns false and flags the queue if the maximum number of events is reached.
+    #[inline]
+    fn check_max_events(&mut self) -> bool {
+        if self.queue.events.len() >= self.queue.max_events {
+            self.queue.max_events_exceeded = true;
+            return false;
+        }
+
+        true
+    }
+
     pub fn build(mut self) -> EventQueue {
         self.validator.build();
 
@@ -562,6 +738,19 @@ impl EventQueueBuilder {
         sweep_orientation: Orientation,
         path_events: impl IntoIterator<Item = IdEvent>,
         points: &impl PositionStore,
+    ) {
+        self.set_path_with_clip(tolerance, sweep_orientation, path_events, points, |_| false);
+    }
+
+    /// Similar to `set_path_with_ids`, `is_clip` tells whether the sub-path starting at
+    /// an endpoint is a clip sub-path.
+    pub(crate) fn set_path_with_clip(
+        &mut self,
+        tolerance: f32,
+        sweep_orientation: Orientation,
+        path_events: impl IntoIterator<Item = IdEvent>,
+        points: &impl PositionStore,
+        is_clip: impl Fn(EndpointId) -> bool,
     ) {
         self.reset();
 
@@ -571,6 +760,7 @@ impl EventQueueBuilder {
                 for evt in path_events {
                     match evt {
                         IdEvent::Begin { at } => {
+                            self.set_clip(is_clip(at));
                             self.begin(points.get_endpoint(at), at);
                         }
                         IdEvent::Line { to, .. } => {
@@ -604,6 +794,7 @@ impl EventQueueBuilder {
                 for evt in path_events {
                     match evt {
                         IdEvent::Begin { at } => {
+                            self.set_clip(is_clip(at));
                             self.begin(reorient(points.get_endpoint(at)), at);
                         }
                         IdEvent::Line { to, .. } => {
@@ -638,14 +829,29 @@ impl EventQueueBuilder {
     fn reset(&mut self) {
         self.queue.reset();
         self.nth = 0;
+        self.winding_unit = EdgeWinding::PAINT;
+    }
+
+    /// Sets whether the next sub-paths are clip sub-paths (see `FillOptions::clip_attribute`).
+    pub(crate) fn set_clip(&mut self, is_clip: bool) {
+        if is_clip {
+            self.winding_unit = EdgeWinding::CLIP;
+            self.queue.has_clip_edges = true;
+        } else {
+            self.winding_unit = EdgeWinding::PAINT;
+        }
     }
 
     fn vertex_event(&mut self, at: Point, endpoint_id: EndpointId) {
+        if !self.check_max_events() {
+            return;
+        }
+
         self.queue.push_unsorted(at);
         self.queue.edge_data.push(EdgeData {
             to: point(f32::NAN, f32::NAN),
             range: 0.0..0.0,
-            winding: 0,
+            winding: EdgeWinding::ZERO,
             is_edge: false,
             from_id: endpoint_id,
             to_id: endpoint_id,
@@ -653,11 +859,15 @@ impl EventQueueBuilder {
     }
 
     fn vertex_event_on_curve(&mut self, at: Point, t: f32, from_id: EndpointId, to_id: EndpointId) {
+        if !self.check_max_events() {
+            return;
+        }
+
         self.queue.push_unsorted(at);
         self.queue.edge_data.push(EdgeData {
             to: point(f32::NAN, f32::NAN),
             range: t..t,
-            winding: 0,
+            winding: EdgeWinding::ZERO,
             is_edge: false,
             from_id,
             to_id,
@@ -699,13 +909,13 @@ impl EventQueueBuilder {
     fn add_edge(
         &mut self,
         edge: &LineSegment<f32>,
-        mut winding: i16,
+        mut winding: EdgeWinding,
         from_id: EndpointId,
         to_id: EndpointId,
         mut t0: f32,
         mut t1: f32,
     ) {
-        if edge.from == edge.to {
+        if edge.from == edge.to || !self.check_max_events() {
             return;
         }
 
@@ -715,7 +925,7 @@ impl EventQueueBuilder {
             evt_to = evt_pos;
             evt_pos = edge.to;
             swap(&mut t0, &mut t1);
-            winding *= -1;
+            winding = -winding;
         }
 
         self.queue.push_unsorted(evt_pos);
@@ -749,7 +959,7 @@ impl EventQueueBuilder {
 
         self.add_edge(
             &LineSegment { from, to },
-            1,
+            self.winding_unit,
             self.prev_endpoint_id,
             to_id,
             t0,
@@ -762,6 +972,7 @@ impl EventQueueBuilder {
     }
 
     pub fn quadratic_bezier_segment(&mut self, ctrl: Point, to: Point, to_id: EndpointId) {
+        trace_span!("flatten");
         self.validator.edge();
         // Swap the curve so that it always goes downwards. This way if two
         // paths share the same edge with different windings, the flattening will
@@ -779,10 +990,10 @@ impl EventQueueBuilder {
         let needs_swap = is_after(original.from, original.to);
 
         let mut segment = original;
-        let mut winding = 1;
+        let mut winding = self.winding_unit;
         if needs_swap {
             swap(&mut segment.from, &mut segment.to);
-            winding = -1;
+            winding = -self.winding_unit;
         }
 
         let mut prev = segment.from;
@@ -836,6 +1047,7 @@ impl EventQueueBuilder {
         to: Point,
         to_id: EndpointId,
     ) {
+        trace_span!("flatten");
         self.validator.edge();
         // Swap the curve so that it always goes downwards. This way if two
         // paths share the same edge with different windings, the flattening will
@@ -854,11 +1066,11 @@ impl EventQueueBuilder {
         let needs_swap = is_after(original.from, original.to);
 
         let mut segment = original;
-        let mut winding = 1;
+        let mut winding = self.winding_unit;
         if needs_swap {
             swap(&mut segment.from, &mut segment.to);
             swap(&mut segment.ctrl1, &mut segment.ctrl2);
-            winding = -1;
+            winding = -self.winding_unit;
         }
 
         let mut prev = segment.from;
@@ -985,6 +1197,59 @@ fn test_event_queue_sort_5() {
     queue.assert_sorted();
 }
 
+#[test]
+fn test_event_queue_radix_sort() {
+    let mut positions = Vec::new();
+    let mut v: u32 = 1;
+    for i in 0..1000 {
+        // Cheap deterministic pseudo-random coordinates with a lot of duplicates.
+        v = v.wrapping_mul(1103515245).wrapping_add(12345);
+        let x = ((v >> 16) % 50) as f32 - 25.0;
+        let y = ((v >> 8) % 70) as f32 * 0.5 - 10.0;
+        positions.push(if i % 100 == 0 {
+            point(-0.0, 0.0)
+        } else {
+            point(x, y)
+        });
+        if i % 100 == 50 {
+            positions.push(point(0.0, -0.0));
+        }
+    }
+
+    let mut merge = EventQueue::new();
+    let mut radix = EventQueue::new();
+    for &p in &positions {
+        merge.push_unsorted(p);
+        radix.push_unsorted(p);
+    }
+    merge.sorted = true;
+    merge.first = merge.merge_sort(0..positions.len());
+    radix.sorted = true;
+    assert!(radix.radix_sort());
+    radix.assert_sorted();
+
+    let (mut a, mut b) = (merge.first, radix.first);
+    while merge.valid_id(a) {
+        let (mut sa, mut sb) = (a, b);
+        while merge.valid_id(sa) {
+            assert_eq!(sa, sb);
+            sa = merge.next_sibling_id(sa);
+            sb = radix.next_sibling_id(sb);
+        }
+        assert!(!radix.valid_id(sb));
+        a = merge.next_id(a);
+        b = radix.next_id(b);
+    }
+    assert!(!radix.valid_id(b));
+
+    let mut nan = EventQueue::new();
+    for &p in &positions {
+        nan.push_unsorted(p);
+    }
+    nan.push_unsorted(point(f32::NAN, 0.0));
+    assert!(!nan.radix_sort());
+}
+
 #[test]
 fn test_event_queue_push_sorted() {
     let mut queue = EventQueue::new();
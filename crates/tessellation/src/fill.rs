@@ -1,20 +1,25 @@
+use crate::budget::tessellate_within_limits;
 use crate::event_queue::*;
 use crate::geom::LineSegment;
+use crate::geometry_builder::Oriented;
 use crate::math::*;
 use crate::monotone::*;
 use crate::path::polygon::Polygon;
 use crate::path::traits::{Build, PathBuilder};
 use crate::path::{
-    builder::NoAttributes, AttributeStore, Attributes, EndpointId, FillRule, IdEvent, PathEvent,
-    PathSlice, PositionStore, Winding, NO_ATTRIBUTES,
+    builder::NoAttributes, commands::PathCommandsSlice, AttributeStore, Attributes, EndpointId,
+    FillRule, IdEvent, PathEvent, PathSlice, PositionStore, Winding, NO_ATTRIBUTES,
 };
-use crate::{FillGeometryBuilder, Orientation, VertexId};
+use crate::trace::{trace_output_span, trace_span};
 use crate::{
-    FillOptions, InternalError, SimpleAttributeStore, TessellationError, TessellationResult,
+    interpolate_attributes, lerp_attribute, AttributeIndex, AttributeInterpolation, FillOptions,
+    InternalError, SimpleAttributeStore, TessellationError, TessellationResult,
     UnsupportedParamater, VertexSource,
 };
+use crate::{FillGeometryBuilder, GeometryBuilder, GeometryBuilderError, Orientation, VertexId};
 use float_next_after::NextAfter;
 use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::f32;
 use std::mem;
 use std::ops::Range;
@@ -60,7 +65,10 @@ fn fmax(a: f32, b: f32) -> f32 {
 }
 
 fn slope(v: Vector) -> f32 {
-    v.x / (v.y.max(std::f32::MIN))
+    // Edges below the current position point downwards or to the right, so horizontal
+    // edges must sort last. Adding zero turns a negative zero (for example between
+    // 0.0 and -0.0) into a positive one so that they don't get a slope of -infinity.
+    v.x / (v.y.max(std::f32::MIN) + 0.0)
 }
 
 #[cfg(debug_assertions)]
@@ -86,7 +94,7 @@ macro_rules! tess_log {
 #[derive(Copy, Clone, Debug)]
 struct WindingState {
     span_index: SpanIdx,
-    number: i16,
+    number: EdgeWinding,
     is_in: bool,
 }
 
@@ -96,12 +104,12 @@ impl WindingState {
         // it to zero.
         WindingState {
             span_index: -1,
-            number: 0,
+            number: EdgeWinding::ZERO,
             is_in: false,
         }
     }
 
-    fn update(&mut self, fill_rule: FillRule, edge_winding: i16) {
+    fn update(&mut self, fill_rule: WindingRule, edge_winding: EdgeWinding) {
         self.number += edge_winding;
         self.is_in = fill_rule.is_in(self.number);
         if self.is_in {
@@ -110,6 +118,25 @@ impl WindingState {
     }
 }
 
+/// The fill rule, and whether the paint is restricted to the inside of clip sub-paths
+/// (see `FillOptions::clip_attribute`).
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct WindingRule {
+    fill_rule: FillRule,
+    clip: bool,
+}
+
+impl WindingRule {
+    #[inline]
+    fn is_in(self, winding: EdgeWinding) -> bool {
+        if self.clip {
+            self.fill_rule.is_in(winding.clip) && self.fill_rule.is_in(winding.paint)
+        } else {
+            self.fill_rule.is_in(winding.paint)
+        }
+    }
+}
+
 struct ActiveEdgeScan {
     vertex_events: Vec<(SpanIdx, Side)>,
     edges_to_split: Vec<ActiveEdgeIdx>,
@@ -152,7 +179,7 @@ struct ActiveEdge {
     from: Point,
     to: Point,
 
-    winding: i16,
+    winding: EdgeWinding,
     is_merge: bool,
 
     from_id: VertexId,
@@ -163,8 +190,9 @@ struct ActiveEdge {
 
 #[test]
 fn active_edge_size() {
-    // We want to be careful about the size of the struct.
-    assert_eq!(std::mem::size_of::<ActiveEdge>(), 32);
+    // We want to be careful about the size of the struct. The paint and clip windings
+    // take 4 bytes which pushes it past 32.
+    assert_eq!(std::mem::size_of::<ActiveEdge>(), 36);
 }
 
 impl ActiveEdge {
@@ -303,7 +331,7 @@ struct PendingEdge {
     sort_key: f32,
     // Index in events.edge_data
     src_edge: TessEventId,
-    winding: i16,
+    winding: EdgeWinding,
     range_end: f32,
 }
 
@@ -524,16 +552,29 @@ pub struct FillTessellator {
     current_event_id: TessEventId,
     active: ActiveEdges,
     edges_below: Vec<PendingEdge>,
-    fill_rule: FillRule,
+    fill_rule: WindingRule,
     orientation: Orientation,
     tolerance: f32,
     fill: Spans,
     log: bool,
     assume_no_intersection: bool,
     attrib_buffer: Vec<f32>,
+    uv_transform: Transform,
+    attrib_interpolation: Option<AttributeInterpolation>,
+    memory_limit: usize,
 
     scan: ActiveEdgeScan,
     events: EventQueue,
+    stream: Option<FillStream>,
+}
+
+/// The state of a tessellation fed via `FillTessellator::begin_sub_path`.
+struct FillStream {
+    events: EventQueueBuilder,
+    options: FillOptions,
+    next_id: EndpointId,
+    first_id: EndpointId,
+    first_position: Point,
 }
 
 impl Default for FillTessellator {
@@ -556,7 +597,10 @@ impl FillTessellator {
             current_event_id: INVALID_EVENT_ID,
             active: ActiveEdges { edges: Vec::new() },
             edges_below: Vec::new(),
-            fill_rule: FillRule::EvenOdd,
+            fill_rule: WindingRule {
+                fill_rule: FillRule::EvenOdd,
+                clip: false,
+            },
             orientation: Orientation::Vertical,
             tolerance: FillOptions::DEFAULT_TOLERANCE,
             fill: Spans {
@@ -566,9 +610,13 @@ impl FillTessellator {
             log,
             assume_no_intersection: false,
             attrib_buffer: Vec::new(),
+            uv_transform: Transform::identity(),
+            attrib_interpolation: None,
+            memory_limit: usize::MAX,
 
             scan: ActiveEdgeScan::new(),
             events: EventQueue::new(),
+            stream: None,
         }
     }
 
@@ -579,16 +627,41 @@ impl FillTessellator {
         options: &FillOptions,
         output: &mut dyn FillGeometryBuilder,
     ) -> TessellationResult {
-        let event_queue = std::mem::replace(&mut self.events, EventQueue::new());
-        let mut queue_builder = event_queue.into_builder(options.tolerance);
+        let limits = options.output_limits();
+        if limits.is_unlimited() {
+            return self.tessellate_events(path, options, output);
+        }
 
-        queue_builder.set_path(
-            options.tolerance,
-            options.sweep_orientation,
-            path.into_iter(),
-        );
+        let events: Vec<PathEvent> = path.into_iter().collect();
+        tessellate_within_limits(limits, options.tolerance, output, |tolerance, output| {
+            let options = options.with_tolerance(tolerance);
+            self.tessellate_events(events.iter().cloned(), &options, output)
+        })
+    }
+
+    fn tessellate_events(
+        &mut self,
+        path: impl IntoIterator<Item = PathEvent>,
+        options: &FillOptions,
+        output: &mut dyn FillGeometryBuilder,
+    ) -> TessellationResult {
+        // Discard a streamed tessellation that wasn't finished.
+        self.stream = None;
+
+        {
+            trace_span!("event_queue");
+            let event_queue = std::mem::replace(&mut self.events, EventQueue::new());
+            let mut queue_builder = event_queue.into_builder(options.tolerance);
+            queue_builder.set_max_events(max_events(options));
+
+            queue_builder.set_path(
+                options.tolerance,
+                options.sweep_orientation,
+                path.into_iter(),
+            );
 
-        self.events = queue_builder.build();
+            self.events = queue_builder.build();
+        }
 
         self.tessellate_impl(options, None, output)
     }
@@ -604,25 +677,94 @@ impl FillTessellator {
         options: &FillOptions,
         output: &mut dyn FillGeometryBuilder,
     ) -> TessellationResult {
-        let event_queue = std::mem::replace(&mut self.events, EventQueue::new());
-        let mut queue_builder = event_queue.into_builder(options.tolerance);
+        let limits = options.output_limits();
+        if limits.is_unlimited() {
+            return self.tessellate_id_events(path, positions, custom_attributes, options, output);
+        }
+
+        let events: Vec<IdEvent> = path.into_iter().collect();
+        tessellate_within_limits(limits, options.tolerance, output, |tolerance, output| {
+            let options = options.with_tolerance(tolerance);
+            self.tessellate_id_events(
+                events.iter().cloned(),
+                positions,
+                custom_attributes,
+                &options,
+                output,
+            )
+        })
+    }
 
-        queue_builder.set_path_with_ids(
-            options.tolerance,
-            options.sweep_orientation,
-            path.into_iter(),
-            positions,
-        );
+    /// Similar to `tessellate_with_ids` for iterators that can be restarted, such
+    /// as the ones of path slices.
+    ///
+    /// The events are read from the path's storage again when the output limits
+    /// require another attempt, instead of being copied into a vector first.
+    fn tessellate_id_path(
+        &mut self,
+        path: impl Iterator<Item = IdEvent> + Clone,
+        positions: &impl PositionStore,
+        custom_attributes: Option<&dyn AttributeStore>,
+        options: &FillOptions,
+        output: &mut dyn FillGeometryBuilder,
+    ) -> TessellationResult {
+        let limits = options.output_limits();
+        if limits.is_unlimited() {
+            return self.tessellate_id_events(path, positions, custom_attributes, options, output);
+        }
 
-        self.events = queue_builder.build();
+        tessellate_within_limits(limits, options.tolerance, output, |tolerance, output| {
+            let options = options.with_tolerance(tolerance);
+            self.tessellate_id_events(path.clone(), positions, custom_attributes, &options, output)
+        })
+    }
+
+    fn tessellate_id_events(
+        &mut self,
+        path: impl IntoIterator<Item = IdEvent>,
+        positions: &impl PositionStore,
+        custom_attributes: Option<&dyn AttributeStore>,
+        options: &FillOptions,
+        output: &mut dyn FillGeometryBuilder,
+    ) -> TessellationResult {
+        // Discard a streamed tessellation that wasn't finished.
+        self.stream = None;
+
+        {
+            trace_span!("event_queue");
+            let event_queue = std::mem::replace(&mut self.events, EventQueue::new());
+            let mut queue_builder = event_queue.into_builder(options.tolerance);
+            queue_builder.set_max_events(max_events(options));
+
+            match (options.clip_attribute, custom_attributes) {
+                (Some(attribute), Some(store)) => queue_builder.set_path_with_clip(
+                    options.tolerance,
+                    options.sweep_orientation,
+                    path,
+                    positions,
+                    |id| store.get(id)[attribute] != 0.0,
+                ),
+                _ => queue_builder.set_path_with_ids(
+                    options.tolerance,
+                    options.sweep_orientation,
+                    path,
+                    positions,
+                ),
+            }
+
+            self.events = queue_builder.build();
+        }
 
         self.tessellate_impl(options, custom_attributes, output)
     }
 
     /// Compute the tessellation from a path slice.
     ///
-    /// The tessellator will internally only track vertex sources and interpolated
-    /// attributes if the path has interpolated attributes.
+    /// The event queue is built from the endpoint and control point ids of the path,
+    /// looking the positions up in the path's storage.
+    ///
+    /// The tessellator will internally only track interpolated attributes if the path
+    /// has interpolated attributes.
     pub fn tessellate_path<'l>(
         &'l mut self,
         path: impl Into<PathSlice<'l>>,
@@ -630,12 +772,36 @@ impl FillTessellator {
         builder: &'l mut dyn FillGeometryBuilder,
     ) -> TessellationResult {
         let path = path.into();
-
-        if path.num_attributes() > 0 {
-            self.tessellate_with_ids(path.id_iter(), &path, Some(&path), options, builder)
+        let custom_attributes = if path.num_attributes() > 0 {
+            Some(&path as &dyn AttributeStore)
         } else {
-            self.tessellate(path.iter(), options, builder)
-        }
+            None
+        };
+
+        self.tessellate_id_path(path.id_iter(), &path, custom_attributes, options, builder)
+    }
+
+    /// Compute the tessellation from a slice of path commands, storage for the positions
+    /// and, optionally, storage for custom endpoint attributes.
+    ///
+    /// Like `tessellate_path`, the event queue is built by looking the positions up by id
+    /// in `positions`.
+    pub fn tessellate_commands<'l>(
+        &mut self,
+        commands: impl Into<PathCommandsSlice<'l>>,
+        positions: &impl PositionStore,
+        custom_attributes: Option<&dyn AttributeStore>,
+        options: &FillOptions,
+        output: &mut dyn FillGeometryBuilder,
+    ) -> TessellationResult {
+        let commands = commands.into();
+        self.tessellate_id_path(
+            commands.iter(),
+            positions,
+            custom_attributes,
+            options,
+            output,
+        )
     }
 
     /// Tessellate a `Polygon`.
@@ -652,10 +818,10 @@ impl FillTessellator {
     pub fn tessellate_rectangle(
         &mut self,
         rect: &Box2D,
-        _options: &FillOptions,
+        options: &FillOptions,
         output: &mut dyn FillGeometryBuilder,
     ) -> TessellationResult {
-        crate::basic_shapes::fill_rectangle(rect, output)
+        crate::basic_shapes::fill_rectangle(rect, options, output)
     }
 
     /// Tessellate a circle.
@@ -744,6 +910,112 @@ impl FillTessellator {
         FillBuilder::new(num_attributes, self, options, output)
     }
 
+    /// Starts a sub-path of a tessellation that is streamed in edge by edge.
+    ///
+    /// This is a lower level alternative to `FillTessellator::builder` for input
+    /// that is produced incrementally (for example by a decoder). Each sub-path is
+    /// made of a `begin_sub_path`, any number of `edge` calls, and an `end_sub_path`.
+    /// Sub-paths are always closed. The tessellation is computed by `finish`.
+    ///
+    /// The options passed to the first sub-path of a tessellation apply to the whole
+    /// tessellation and are ignored for the following sub-paths. In particular, edges
+    /// that exceed the `memory_limit` are not buffered.
+    ///
+    /// Returns the endpoint id of `at`, which vertex sources refer to.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lyon_tessellation::{FillTessellator, FillOptions};
+    /// use lyon_tessellation::geometry_builder::{simple_builder, VertexBuffers};
+    /// use lyon_tessellation::math::{Point, point};
+    ///
+    /// let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    /// let mut tessellator = FillTessellator::new();
+    ///
+    /// tessellator.begin_sub_path(point(0.0, 0.0), &FillOptions::default());
+    /// tessellator.edge(point(10.0, 0.0));
+    /// tessellator.edge(point(10.0, 10.0));
+    /// tessellator.edge(point(0.0, 10.0));
+    /// tessellator.end_sub_path();
+    ///
+    /// let result = tessellator.finish(&mut simple_builder(&mut buffers));
+    /// assert!(result.is_ok());
+    /// ```
+    pub fn begin_sub_path(&mut self, at: Point, options: &FillOptions) -> EndpointId {
+        if self.stream.is_none() {
+            let mut events = mem::take(&mut self.events).into_builder(options.tolerance);
+            events.set_max_events(max_events(options));
+            self.stream = Some(FillStream {
+                events,
+                options: *options,
+                next_id: EndpointId(0),
+                first_id: EndpointId(0),
+                first_position: at,
+            });
+        }
+
+        let stream = self.stream.as_mut().unwrap();
+        let id = stream.next_id;
+        stream.next_id.0 += 1;
+        stream.first_id = id;
+        stream.first_position = at;
+        stream.events.begin(at, id);
+
+        id
+    }
+
+    /// Adds a straight edge from the previous point of the current sub-path to `to`.
+    ///
+    /// Returns the endpoint id of `to`.
+    ///
+    /// Panics if there is no sub-path in progress.
+    pub fn edge(&mut self, to: Point) -> EndpointId {
+        let stream = self
+            .stream
+            .as_mut()
+            .expect("FillTessellator::edge must be called after begin_sub_path");
+        let id = stream.next_id;
+        stream.next_id.0 += 1;
+        stream.events.line_segment(to, id, 0.0, 1.0);
+
+        id
+    }
+
+    /// Ends the current sub-path, adding an edge back to its first point if needed.
+    ///
+    /// Panics if there is no sub-path in progress.
+    pub fn end_sub_path(&mut self) {
+        let stream = self
+            .stream
+            .as_mut()
+            .expect("FillTessellator::end_sub_path must be called after begin_sub_path");
+        stream.events.end(stream.first_position, stream.first_id);
+    }
+
+    /// Computes the tessellation of the sub-paths streamed in since the last call,
+    /// with the options passed to the first `begin_sub_path`.
+    ///
+    /// Streamed edges are always tessellated with a vertical sweep: the
+    /// `sweep_orientation` of the options is ignored.
+    pub fn finish(&mut self, output: &mut dyn FillGeometryBuilder) -> TessellationResult {
+        let (event_queue, options) = match self.stream.take() {
+            Some(stream) => (stream.events.build(), stream.options),
+            None => {
+                let options = FillOptions::default();
+                let events = mem::take(&mut self.events)
+                    .into_builder(options.tolerance)
+                    .build();
+                (events, options)
+            }
+        };
+
+        self.events = event_queue;
+
+        let options = options.with_sweep_orientation(Orientation::Vertical);
+        self.tessellate_impl(&options, None, output)
+    }
+
     fn tessellate_impl(
         &mut self,
         options: &FillOptions,
@@ -756,6 +1028,37 @@ impl FillTessellator {
             ));
         }
 
+        if !(options.max_triangle_edge_length > 0.0) {
+            return Err(TessellationError::UnsupportedParamater(
+                UnsupportedParamater::MaxTriangleEdgeLengthIsNotPositive,
+            ));
+        }
+
+        if self.events.max_events_exceeded {
+            return Err(TessellationError::MemoryLimitExceeded);
+        }
+
+        self.uv_transform = match options.uv_mapping {
+            Some(mapping) => mapping.to_transform(&self.events_bounding_box(options)),
+            None => Transform::identity(),
+        };
+        self.attrib_interpolation = options.attribute_interpolation;
+
+        if options.max_triangle_edge_length.is_finite() {
+            let max_length = options.max_triangle_edge_length;
+            let options = options.with_max_triangle_edge_length(f32::INFINITY);
+            let mut output =
+                TriangleSubdivider::new(max_length, attrib_store, self.uv_transform, builder);
+            let result = self.tessellate_impl(&options, attrib_store, &mut output);
+
+            return match output.error {
+                Some(e) => Err(e.into()),
+                None => result,
+            };
+        }
+
+        let builder: &mut dyn FillGeometryBuilder = &mut Oriented::fill(options, builder);
+
         self.reset();
 
         if let Some(store) = attrib_store {
@@ -764,10 +1067,16 @@ impl FillTessellator {
             self.attrib_buffer.clear();
         }
 
-        self.fill_rule = options.fill_rule;
+        self.fill_rule = WindingRule {
+            fill_rule: options.fill_rule,
+            clip: self.events.has_clip_edges,
+        };
         self.orientation = options.sweep_orientation;
         self.tolerance = options.tolerance * 0.5;
         self.assume_no_intersection = !options.handle_intersections;
+        self.memory_limit = options.memory_limit;
+
+        trace_output_span!("sweep", builder: dyn FillGeometryBuilder);
 
         builder.begin_geometry();
 
@@ -805,6 +1114,26 @@ impl FillTessellator {
         Ok(())
     }
 
+    /// Approximate number of bytes used by the event queue, the active edges and the spans,
+    /// which is what `FillOptions::memory_limit` bounds.
+    fn sweep_memory_usage(&self) -> usize {
+        self.events.events.len() * EVENT_MEMORY_SIZE
+            + self.active.edges.len() * mem::size_of::<ActiveEdge>()
+            + (self.fill.spans.len() + self.fill.pool.len()) * SPAN_MEMORY_SIZE
+    }
+
+    /// The bounding box of the event queue's positions, in the path's coordinate space.
+    fn events_bounding_box(&self, options: &FillOptions) -> Box2D {
+        let horizontal = options.sweep_orientation == Orientation::Horizontal;
+        Box2D::from_points(self.events.events.iter().map(|evt| {
+            if horizontal {
+                reorient(evt.position)
+            } else {
+                evt.position
+            }
+        }))
+    }
+
     /// Enable/disable some verbose logging during the tessellation, for
     /// debugging purposes.
     pub fn set_logging(&mut self, is_enabled: bool) {
@@ -845,6 +1174,12 @@ impl FillTessellator {
             #[cfg(debug_assertions)]
             self.check_active_edges();
 
+            // Intersections insert new events during the sweep, and the active edges and
+            // spans grow with the number of edges crossing the sweep line.
+            if self.sweep_memory_usage() > self.memory_limit {
+                return Err(TessellationError::MemoryLimitExceeded);
+            }
+
             self.current_event_id = self.events.next_id(self.current_event_id);
         }
 
@@ -883,6 +1218,8 @@ impl FillTessellator {
             current_event,
             attrib_store,
             attrib_buffer: &mut self.attrib_buffer,
+            attrib_interpolation: self.attrib_interpolation,
+            uv_transform: &self.uv_transform,
         })?;
 
         let mut current_sibling = current_event;
@@ -970,7 +1307,7 @@ impl FillTessellator {
             } else {
                 tess_log!(
                     self,
-                    r#"  <path d="M {:.5?} {:.5?} L {:.5?} {:.5?}" class="edge", winding="{:>2}"/>"#,
+                    r#"  <path d="M {:.5?} {:.5?} L {:.5?} {:.5?}" class="edge", winding="{:?}"/>"#,
                     e.from.x,
                     e.from.y,
                     e.to.x,
@@ -1000,7 +1337,7 @@ impl FillTessellator {
                 );
             }
         }
-        assert_eq!(winding.number, 0);
+        assert_eq!(winding.number, EdgeWinding::ZERO);
         let expected_span_count = (winding.span_index + 1) as usize;
         assert_eq!(self.fill.spans.len(), expected_span_count);
     }
@@ -1421,7 +1758,7 @@ impl FillTessellator {
             let edge = &mut self.active.edges[scan.above.start];
             edge.is_merge = true;
             edge.from = edge.to;
-            edge.winding = 0;
+            edge.winding = EdgeWinding::ZERO;
             edge.from_id = self.current_vertex;
 
             // take the merge edge out of the range so that it isn't removed later.
@@ -1899,7 +2236,7 @@ impl FillTessellator {
             return;
         }
 
-        let mut winding_number = 0;
+        let mut winding_number = EdgeWinding::ZERO;
         for i in 0..self.active.edges.len() {
             let needs_swap = {
                 let edge = &self.active.edges[i];
@@ -2043,7 +2380,8 @@ impl FillTessellator {
             self.edges_below[upper_idx].winding,
             self.edges_below[upper_idx].winding + self.edges_below[lower_idx].winding
         );
-        self.edges_below[upper_idx].winding += self.edges_below[lower_idx].winding;
+        let lower_winding = self.edges_below[lower_idx].winding;
+        self.edges_below[upper_idx].winding += lower_winding;
         let split_point = self.edges_below[upper_idx].to;
 
         tess_log!(
@@ -2131,6 +2469,171 @@ fn reorient(p: Point) -> Point {
     point(p.y, -p.x)
 }
 
+/// A geometry builder adapter that subdivides triangles until none of their edges is longer
+/// than a given length (see `FillOptions::max_triangle_edge_length`).
+///
+/// The longest edge of a triangle is split at its midpoint, up to
+/// `FillOptions::MAX_SUBDIVISION_DEPTH` times. Midpoints are shared between the triangles on
+/// both sides of an edge so that the resulting mesh has no T-junction.
+struct TriangleSubdivider<'l> {
+    output: &'l mut dyn FillGeometryBuilder,
+    attrib_store: Option<&'l dyn AttributeStore>,
+    max_length_sq: f32,
+    // Position and custom attributes of each vertex, indexed via `vertices`.
+    vertices: HashMap<VertexId, usize>,
+    positions: Vec<Point>,
+    attributes: Vec<f32>,
+    midpoints: HashMap<(VertexId, VertexId), VertexId>,
+    attrib_buffer: Vec<f32>,
+    // Inserted vertices don't have any source, so they refer to an empty queue.
+    no_events: EventQueue,
+    uv_transform: Transform,
+    error: Option<GeometryBuilderError>,
+}
+
+impl<'l> TriangleSubdivider<'l> {
+    fn new(
+        max_length: f32,
+        attrib_store: Option<&'l dyn AttributeStore>,
+        uv_transform: Transform,
+        output: &'l mut dyn FillGeometryBuilder,
+    ) -> Self {
+        let num_attributes = attrib_store.map_or(0, |store| store.num_attributes());
+        TriangleSubdivider {
+            output,
+            attrib_store,
+            max_length_sq: max_length * max_length,
+            vertices: HashMap::new(),
+            positions: Vec::new(),
+            attributes: Vec::new(),
+            midpoints: HashMap::new(),
+            attrib_buffer: vec![0.0; num_attributes],
+            no_events: EventQueue::new(),
+            uv_transform,
+            error: None,
+        }
+    }
+
+    fn record_vertex(&mut self, id: VertexId, position: Point, attributes: Attributes) {
+        self.vertices.insert(id, self.positions.len());
+        self.positions.push(position);
+        self.attributes.extend_from_slice(attributes);
+    }
+
+    fn midpoint(&mut self, a: VertexId, b: VertexId) -> Result<VertexId, GeometryBuilderError> {
+        let key = if a.0 < b.0 { (a, b) } else { (b, a) };
+        if let Some(id) = self.midpoints.get(&key) {
+            return Ok(*id);
+        }
+
+        let ia = self.vertices[&a];
+        let ib = self.vertices[&b];
+        let position = self.positions[ia].lerp(self.positions[ib], 0.5);
+        let n = self.attrib_buffer.len();
+        for i in 0..n {
+            self.attrib_buffer[i] = lerp_attribute(
+                self.attributes[ia * n + i],
+                self.attributes[ib * n + i],
+                0.5,
+            );
+        }
+
+        let id = self.output.add_fill_vertex(FillVertex {
+            position,
+            events: &self.no_events,
+            current_event: INVALID_EVENT_ID,
+            attrib_buffer: &mut self.attrib_buffer,
+            attrib_store: self.attrib_store,
+            attrib_interpolation: None,
+            uv_transform: &self.uv_transform,
+        })?;
+
+        let attributes = std::mem::take(&mut self.attrib_buffer);
+        self.record_vertex(id, position, &attributes);
+        self.attrib_buffer = attributes;
+        self.midpoints.insert(key, id);
+
+        Ok(id)
+    }
+
+    fn subdivide(&mut self, triangle: [VertexId; 3]) -> Result<(), GeometryBuilderError> {
+        let mut stack = vec![(triangle, 0)];
+        while let Some((tri, depth)) = stack.pop() {
+            let p = tri.map(|id| self.positions[self.vertices[&id]]);
+            let mut longest = 0;
+            let mut longest_sq = 0.0;
+            for i in 0..3 {
+                let length_sq = (p[(i + 1) % 3] - p[i]).square_length();
+                if length_sq > longest_sq {
+                    longest = i;
+                    longest_sq = length_sq;
+                }
+            }
+
+            if longest_sq <= self.max_length_sq || depth >= FillOptions::MAX_SUBDIVISION_DEPTH {
+                self.output.add_triangle(tri[0], tri[1], tri[2]);
+                continue;
+            }
+
+            let a = tri[longest];
+            let b = tri[(longest + 1) % 3];
+            let c = tri[(longest + 2) % 3];
+            let m = self.midpoint(a, b)?;
+            stack.push(([a, m, c], depth + 1));
+            stack.push(([m, b, c], depth + 1));
+        }
+
+        Ok(())
+    }
+}
+
+impl<'l> GeometryBuilder for TriangleSubdivider<'l> {
+    fn begin_geometry(&mut self) {
+        self.vertices.clear();
+        self.positions.clear();
+        self.attributes.clear();
+        self.midpoints.clear();
+        self.error = None;
+        self.output.begin_geometry();
+    }
+
+    fn end_geometry(&mut self) {
+        if self.error.is_some() {
+            self.output.abort_geometry();
+        } else {
+            self.output.end_geometry();
+        }
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        if self.error.is_some() {
+            return;
+        }
+
+        if let Err(e) = self.subdivide([a, b, c]) {
+            self.error = Some(e);
+        }
+    }
+
+    fn abort_geometry(&mut self) {
+        self.output.abort_geometry();
+    }
+}
+
+impl<'l> FillGeometryBuilder for TriangleSubdivider<'l> {
+    fn add_fill_vertex(
+        &mut self,
+        mut vertex: FillVertex,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        let position = vertex.position();
+        let attributes = vertex.interpolated_attributes().to_vec();
+        let id = self.output.add_fill_vertex(vertex)?;
+        self.record_vertex(id, position, &attributes);
+
+        Ok(id)
+    }
+}
+
 /// Extra vertex information from the `FillTessellator`, accessible when building vertices.
 pub struct FillVertex<'l> {
     pub(crate) position: Point,
@@ -2138,6 +2641,8 @@ pub struct FillVertex<'l> {
     pub(crate) current_event: TessEventId,
     pub(crate) attrib_buffer: &'l mut [f32],
     pub(crate) attrib_store: Option<&'l dyn AttributeStore>,
+    pub(crate) attrib_interpolation: Option<AttributeInterpolation>,
+    pub(crate) uv_transform: &'l Transform,
 }
 
 impl<'l> FillVertex<'l> {
@@ -2145,6 +2650,13 @@ impl<'l> FillVertex<'l> {
         self.position
     }
 
+    /// Texture coordinates of the vertex, computed as specified by `FillOptions::uv_mapping`.
+    ///
+    /// Equal to the position if no mapping is specified.
+    pub fn uv(&self) -> Point {
+        self.uv_transform.transform_point(self.position)
+    }
+
     /// Return an iterator over the sources of the vertex.
     pub fn sources(&self) -> VertexSourceIterator {
         VertexSourceIterator {
@@ -2181,6 +2693,32 @@ impl<'l> FillVertex<'l> {
         None
     }
 
+    /// Returns the order of the sub-path this vertex belongs to.
+    ///
+    /// `attribute` is the index of the custom attribute containing the order (see
+    /// `lyon_path::builder::WithSubPathOrder`). When several sub-paths meet at this
+    /// vertex, the greatest order is returned.
+    ///
+    /// Returns `0.0` if the path does not have custom attributes.
+    pub fn sub_path_order(&self, attribute: AttributeIndex) -> f32 {
+        let store = match self.attrib_store {
+            Some(store) => store,
+            None => {
+                return 0.0;
+            }
+        };
+
+        self.sources()
+            .map(|src| match src {
+                VertexSource::Endpoint { id } => store.get(id)[attribute],
+                VertexSource::Edge { from, .. } => store.get(from)[attribute],
+            })
+            .fold(None, |max: Option<f32>, order| {
+                Some(max.map_or(order, |max| max.max(order)))
+            })
+            .unwrap_or(0.0)
+    }
+
     /// Fetch or interpolate the custom attribute values at this vertex.
     pub fn interpolated_attributes(&mut self) -> Attributes {
         if self.attrib_store.is_none() {
@@ -2197,7 +2735,14 @@ impl<'l> FillVertex<'l> {
 
         let num_attributes = store.num_attributes();
 
-        let first = sources.next().unwrap();
+        let first = match sources.next() {
+            Some(src) => src,
+            // Vertices inserted by the tessellator (see `FillOptions::max_triangle_edge_length`)
+            // have no source and their attributes are already in the buffer.
+            None => {
+                return self.attrib_buffer;
+            }
+        };
         let mut next = sources.next();
 
         // Fast path for the single-source-single-endpoint common case.
@@ -2221,47 +2766,37 @@ impl<'l> FillVertex<'l> {
                 assert!(a.len() == num_attributes);
                 assert!(b.len() == num_attributes);
                 assert!(self.attrib_buffer.len() == num_attributes);
-                for i in 0..num_attributes {
-                    self.attrib_buffer[i] = a[i] * (1.0 - t) + b[i] * t;
-                }
+                interpolate_attributes(self.attrib_interpolation, a, b, t, self.attrib_buffer);
             }
         }
 
-        let mut div = 1.0;
-        loop {
-            match next {
-                Some(VertexSource::Endpoint { id }) => {
-                    let a = store.get(id);
-                    assert!(a.len() == num_attributes);
-                    assert!(self.attrib_buffer.len() == num_attributes);
-                    for (i, &att) in a.iter().enumerate() {
-                        self.attrib_buffer[i] += att;
+        // Average the values of the sources, keeping the exact bits of attributes that are
+        // the same for all sources (see `lerp_attribute`).
+        let mut count = 1.0;
+        while let Some(src) = next {
+            count += 1.0;
+            for i in 0..num_attributes {
+                let value = match src {
+                    VertexSource::Endpoint { id } => store.get(id)[i],
+                    VertexSource::Edge { from, to, t } => {
+                        let a = store.get(from)[i];
+                        let b = store.get(to)[i];
+                        match self.attrib_interpolation {
+                            Some(AttributeInterpolation(interpolate)) => {
+                                interpolate(i as AttributeIndex, a, b, t)
+                            }
+                            None => lerp_attribute(a, b, t),
+                        }
                     }
-                }
-                Some(VertexSource::Edge { from, to, t }) => {
-                    let a = store.get(from);
-                    let b = store.get(to);
-                    assert!(a.len() == num_attributes);
-                    assert!(b.len() == num_attributes);
-                    assert!(self.attrib_buffer.len() == num_attributes);
-                    for i in 0..num_attributes {
-                        self.attrib_buffer[i] += a[i] * (1.0 - t) + b[i] * t;
-                    }
-                }
-                None => {
-                    break;
+                };
+                let mean = &mut self.attrib_buffer[i];
+                if value.to_bits() != mean.to_bits() {
+                    *mean += (value - *mean) / count;
                 }
             }
-            div += 1.0;
             next = sources.next();
         }
 
-        if div > 1.0 {
-            for attribute in self.attrib_buffer.iter_mut() {
-                *attribute /= div;
-            }
-        }
-
         self.attrib_buffer
     }
 }
@@ -2322,6 +2857,14 @@ fn remap_t_in_range(val: f32, range: Range<f32>) -> f32 {
     }
 }
 
+/// Approximate number of bytes used by a span, not counting the vertices and triangles
+/// that its monotone tessellator buffers.
+const SPAN_MEMORY_SIZE: usize = mem::size_of::<Span>() + mem::size_of::<MonotoneTessellator>();
+
+fn max_events(options: &FillOptions) -> usize {
+    options.memory_limit / EVENT_MEMORY_SIZE
+}
+
 pub struct FillBuilder<'l> {
     events: EventQueueBuilder,
     next_id: EndpointId,
@@ -2341,8 +2884,10 @@ impl<'l> FillBuilder<'l> {
         options: &'l FillOptions,
         output: &'l mut dyn FillGeometryBuilder,
     ) -> Self {
-        let events = std::mem::replace(&mut tessellator.events, EventQueue::new())
+        tessellator.stream = None;
+        let mut events = std::mem::replace(&mut tessellator.events, EventQueue::new())
             .into_builder(options.tolerance);
+        events.set_max_events(max_events(options));
 
         FillBuilder {
             events,
@@ -2371,6 +2916,10 @@ impl<'l> FillBuilder<'l> {
     }
 
     pub fn begin(&mut self, at: Point, attributes: Attributes) -> EndpointId {
+        if let Some(attribute) = self.options.clip_attribute {
+            self.events.set_clip(attributes[attribute] != 0.0);
+        }
+
         let at = self.position(at);
         let id = self.attrib_store.add(attributes);
         self.first_id = id;
@@ -2625,9 +3174,6 @@ fn log_svg_preamble(_tess: &FillTessellator) {
     );
 }
 
-#[cfg(test)]
-use crate::geometry_builder::*;
-
 #[cfg(test)]
 fn eq(a: Point, b: Point) -> bool {
     (a.x - b.x).abs() < 0.00001 && (a.y - b.y).abs() < 0.00001
@@ -3003,3 +3549,266 @@ fn fill_builder_vertex_source() {
         }
     }
 }
+
+#[test]
+fn fill_streaming_edges() {
+    use crate::geometry_builder::{simple_builder, VertexBuffers};
+    use crate::path::Path;
+
+    let outer = [
+        point(0.0, 0.0),
+        point(10.0, 1.0),
+        point(12.0, 9.0),
+        point(4.0, 12.0),
+        point(-1.0, 6.0),
+    ];
+    let inner = [point(3.0, 3.0), point(8.0, 4.0), point(6.0, 8.0)];
+
+    let mut builder = Path::builder();
+    for sub_path in [&outer[..], &inner[..]] {
+        builder.begin(sub_path[0]);
+        for p in &sub_path[1..] {
+            builder.line_to(*p);
+        }
+        builder.close();
+    }
+    let path = builder.build();
+
+    let options = FillOptions::default();
+    let mut tess = FillTessellator::new();
+
+    let mut expected: VertexBuffers<Point, u16> = VertexBuffers::new();
+    tess.tessellate_path(&path, &options, &mut simple_builder(&mut expected))
+        .unwrap();
+
+    // Run twice to check that the tessellator can be reused.
+    for _ in 0..2 {
+        let mut next_id = 0;
+        for sub_path in [&outer[..], &inner[..]] {
+            assert_eq!(
+                tess.begin_sub_path(sub_path[0], &options),
+                EndpointId(next_id)
+            );
+            next_id += 1;
+            for p in &sub_path[1..] {
+                assert_eq!(tess.edge(*p), EndpointId(next_id));
+                next_id += 1;
+            }
+            tess.end_sub_path();
+        }
+
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        tess.finish(&mut simple_builder(&mut buffers)).unwrap();
+
+        assert_eq!(buffers.vertices, expected.vertices);
+        assert_eq!(buffers.indices, expected.indices);
+    }
+
+    tess.begin_sub_path(point(0.0, 0.0), &options.with_memory_limit(1024));
+    for i in 0..100 {
+        tess.edge(point(i as f32, (i % 2) as f32 * 10.0));
+    }
+    tess.end_sub_path();
+    let result = tess.finish(&mut simple_builder(&mut VertexBuffers::<Point, u16>::new()));
+    assert_eq!(result, Err(TessellationError::MemoryLimitExceeded));
+
+    // A tessellation that isn't streamed discards the sub-paths of an unfinished stream.
+    tess.begin_sub_path(point(100.0, 100.0), &options);
+    tess.edge(point(110.0, 100.0));
+    tess.edge(point(110.0, 110.0));
+    tess.tessellate_path(
+        &path,
+        &options,
+        &mut simple_builder(&mut VertexBuffers::<Point, u16>::new()),
+    )
+    .unwrap();
+
+    for sub_path in [&outer[..], &inner[..]] {
+        tess.begin_sub_path(sub_path[0], &options);
+        for p in &sub_path[1..] {
+            tess.edge(*p);
+        }
+        tess.end_sub_path();
+    }
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    tess.finish(&mut simple_builder(&mut buffers)).unwrap();
+    assert_eq!(buffers.vertices, expected.vertices);
+    assert_eq!(buffers.indices, expected.indices);
+}
+
+#[test]
+fn fill_uv_mapping() {
+    use crate::geometry_builder::{BuffersBuilder, VertexBuffers};
+    use crate::path::Path;
+    use crate::UvMapping;
+
+    let mut builder = Path::builder();
+    builder.begin(point(10.0, 5.0));
+    builder.line_to(point(30.0, 5.0));
+    builder.quadratic_bezier_to(point(40.0, 15.0), point(30.0, 25.0));
+    builder.line_to(point(10.0, 25.0));
+    builder.close();
+    let path = builder.build();
+
+    let mut tess = FillTessellator::new();
+    for orientation in [Orientation::Vertical, Orientation::Horizontal] {
+        for max_edge_length in [f32::INFINITY, 4.0] {
+            let options = FillOptions::default()
+                .with_sweep_orientation(orientation)
+                .with_max_triangle_edge_length(max_edge_length)
+                .with_uv_mapping(UvMapping::BoundingBox);
+
+            let mut buffers: VertexBuffers<(Point, Point), u16> = VertexBuffers::new();
+            tess.tessellate_path(
+                &path,
+                &options,
+                &mut BuffersBuilder::new(&mut buffers, |v: FillVertex| (v.position(), v.uv())),
+            )
+            .unwrap();
+
+            let bounds = Box2D::from_points(buffers.vertices.iter().map(|v| v.0));
+            let size = bounds.size();
+            for (position, uv) in &buffers.vertices {
+                let expected = point(
+                    (position.x - bounds.min.x) / size.width,
+                    (position.y - bounds.min.y) / size.height,
+                );
+                assert!(
+                    (*uv - expected).length() < 0.0001,
+                    "{:?} {:?}",
+                    uv,
+                    expected
+                );
+            }
+        }
+    }
+
+    let transform = Transform::scale(0.5, 2.0).then_translate(vector(1.0, 0.0));
+    let mut buffers: VertexBuffers<(Point, Point), u16> = VertexBuffers::new();
+    tess.tessellate_rectangle(
+        &Box2D::new(point(0.0, 0.0), point(4.0, 3.0)),
+        &FillOptions::default().with_uv_mapping(UvMapping::Transform(transform)),
+        &mut BuffersBuilder::new(&mut buffers, |v: FillVertex| (v.position(), v.uv())),
+    )
+    .unwrap();
+
+    for (position, uv) in &buffers.vertices {
+        assert_eq!(*uv, transform.transform_point(*position));
+    }
+}
+
+#[test]
+fn fill_clip_sub_paths() {
+    use crate::geometry_builder::{simple_builder, VertexBuffers};
+    use crate::path::Path;
+
+    fn area(buffers: &VertexBuffers<Point, u16>) -> f32 {
+        let mut area = 0.0;
+        for tri in buffers.indices.chunks(3) {
+            let a = buffers.vertices[tri[0] as usize];
+            let b = buffers.vertices[tri[1] as usize];
+            let c = buffers.vertices[tri[2] as usize];
+            area += (b - a).cross(c - a).abs() * 0.5;
+        }
+
+        area
+    }
+
+    // Attribute 0 marks clip sub-paths.
+    let mut builder = Path::builder_with_attributes(1);
+    // Painted: two overlapping squares.
+    builder.add_rectangle(
+        &Box2D::new(point(0.0, 0.0), point(10.0, 10.0)),
+        Winding::Positive,
+        &[0.0],
+    );
+    builder.add_rectangle(
+        &Box2D::new(point(2.0, 2.0), point(12.0, 12.0)),
+        Winding::Positive,
+        &[0.0],
+    );
+    // Clip: a triangle and a square.
+    builder.begin(point(5.0, -5.0), &[1.0]);
+    builder.line_to(point(20.0, 10.0), &[1.0]);
+    builder.line_to(point(5.0, 10.0), &[1.0]);
+    builder.end(true);
+    builder.add_rectangle(
+        &Box2D::new(point(0.0, 0.0), point(1.0, 1.0)),
+        Winding::Negative,
+        &[1.0],
+    );
+    let path = builder.build();
+
+    let mut tess = FillTessellator::new();
+
+    for orientation in [Orientation::Vertical, Orientation::Horizontal] {
+        let options = FillOptions::non_zero()
+            .with_sweep_orientation(orientation)
+            .with_clip_attribute(0);
+
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        tess.tessellate_path(&path, &options, &mut simple_builder(&mut buffers))
+            .unwrap();
+
+        // The clip triangle covers the first square right of x = 5 (50) and the part of
+        // the second square that is right of x = 10 and below y = 10 (16). The clip square
+        // covers one unit of the first square.
+        assert!((area(&buffers) - 67.0).abs() < 0.001, "{}", area(&buffers));
+
+        // Same with the builder.
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        let mut output = simple_builder(&mut buffers);
+        let mut builder = tess.builder_with_attributes(1, &options, &mut output);
+        for evt in path.iter_with_attributes() {
+            match evt {
+                crate::path::Event::Begin { at } => {
+                    builder.begin(at.0, at.1);
+                }
+                crate::path::Event::Line { to, .. } => {
+                    builder.line_to(to.0, to.1);
+                }
+                crate::path::Event::End { close, .. } => {
+                    builder.end(close);
+                }
+                _ => unimplemented!(),
+            }
+        }
+        builder.build().unwrap();
+
+        assert!((area(&buffers) - 67.0).abs() < 0.001, "{}", area(&buffers));
+    }
+
+    // Without a clip attribute, everything is painted.
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    tess.tessellate_path(
+        &path,
+        &FillOptions::non_zero(),
+        &mut simple_builder(&mut buffers),
+    )
+    .unwrap();
+    assert!(area(&buffers) > 170.0);
+
+    // The paint and clip winding numbers don't overflow into each other.
+    let mut builder = Path::builder_with_attributes(1);
+    for _ in 0..200 {
+        builder.add_rectangle(
+            &Box2D::new(point(0.0, 0.0), point(10.0, 10.0)),
+            Winding::Positive,
+            &[0.0],
+        );
+    }
+    builder.add_rectangle(
+        &Box2D::new(point(0.0, 0.0), point(5.0, 10.0)),
+        Winding::Positive,
+        &[1.0],
+    );
+    let path = builder.build();
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    tess.tessellate_path(
+        &path,
+        &FillOptions::non_zero().with_clip_attribute(0),
+        &mut simple_builder(&mut buffers),
+    )
+    .unwrap();
+    assert!((area(&buffers) - 50.0).abs() < 0.001, "{}", area(&buffers));
+}
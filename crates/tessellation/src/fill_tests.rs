@@ -2,7 +2,10 @@ use crate::extra::rust_logo::build_logo_path;
 use crate::geometry_builder::*;
 use crate::math::*;
 use crate::path::{Path, PathSlice};
-use crate::{FillOptions, FillRule, FillTessellator, FillVertex, TessellationError, VertexId};
+use crate::{
+    FillOptions, FillRule, FillTessellator, FillVertex, TessellationError, UnsupportedParamater,
+    VertexId,
+};
 
 use std::env;
 
@@ -2498,3 +2501,388 @@ fn test_triangle_winding() {
     )
     .unwrap();
 }
+
+#[test]
+fn sub_path_order() {
+    use crate::path::builder::PathBuilder;
+    use crate::path::Winding;
+
+    let mut builder = Path::builder_with_attributes(1).with_sub_path_order();
+    let order_attribute = builder.order_attribute();
+    builder.set_order(1.0);
+    builder.add_rectangle(
+        &Box2D {
+            min: point(0.0, 0.0),
+            max: point(1.0, 1.0),
+        },
+        Winding::Positive,
+        &[],
+    );
+    builder.set_order(2.0);
+    builder.add_rectangle(
+        &Box2D {
+            min: point(10.0, 0.0),
+            max: point(11.0, 1.0),
+        },
+        Winding::Positive,
+        &[],
+    );
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<(Point, f32), u16> = VertexBuffers::new();
+    FillTessellator::new()
+        .tessellate_path(
+            &path,
+            &FillOptions::default(),
+            &mut BuffersBuilder::new(&mut buffers, |v: FillVertex| {
+                (v.position(), v.sub_path_order(order_attribute))
+            }),
+        )
+        .unwrap();
+
+    assert_eq!(buffers.vertices.len(), 8);
+    for (position, order) in &buffers.vertices {
+        let expected = if position.x < 5.0 { 1.0 } else { 2.0 };
+        assert_eq!(*order, expected);
+    }
+}
+
+#[test]
+fn max_triangle_edge_length() {
+    // The custom attribute is the x coordinate.
+    let mut builder = Path::builder_with_attributes(1);
+    builder.begin(point(0.0, 0.0), &[0.0]);
+    builder.line_to(point(100.0, 0.0), &[100.0]);
+    builder.line_to(point(100.0, 40.0), &[100.0]);
+    builder.line_to(point(0.0, 40.0), &[0.0]);
+    builder.end(true);
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<(Point, f32), u16> = VertexBuffers::new();
+    let mut output =
+        FillBoundaryEdges::new(BuffersBuilder::new(&mut buffers, |mut v: FillVertex| {
+            (v.position(), v.interpolated_attributes()[0])
+        }));
+    FillTessellator::new()
+        .tessellate_path(
+            &path,
+            &FillOptions::default().with_max_triangle_edge_length(10.0),
+            &mut output,
+        )
+        .unwrap();
+    let boundary = output.into_edges();
+
+    let mut area = 0.0;
+    for tri in buffers.indices.chunks(3) {
+        let a = buffers.vertices[tri[0] as usize].0;
+        let b = buffers.vertices[tri[1] as usize].0;
+        let c = buffers.vertices[tri[2] as usize].0;
+        for (p0, p1) in [(a, b), (b, c), (c, a)] {
+            assert!((p1 - p0).length() <= 10.0);
+        }
+        area += (b - a).cross(c - a).abs() * 0.5;
+    }
+    assert!((area - 4000.0).abs() < 0.01);
+
+    for (position, x) in &buffers.vertices {
+        assert!((position.x - x).abs() < 0.001);
+    }
+
+    // No T-junction: all boundary edges are on the outline of the rectangle.
+    for (a, b) in boundary {
+        let a = buffers.vertices[a.to_usize()].0;
+        let b = buffers.vertices[b.to_usize()].0;
+        let on_outline = |p: Point| p.x == 0.0 || p.x == 100.0 || p.y == 0.0 || p.y == 40.0;
+        assert!(on_outline(a) && on_outline(b));
+        assert!(a.x == b.x || a.y == b.y);
+    }
+}
+
+#[test]
+fn max_triangle_edge_length_limits() {
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(100.0, 0.0));
+    builder.line_to(point(100.0, 40.0));
+    builder.end(true);
+    let path = builder.build();
+
+    let mut tess = FillTessellator::new();
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    for length in [0.0, -1.0, f32::NAN] {
+        let result = tess.tessellate_path(
+            &path,
+            &FillOptions::default().with_max_triangle_edge_length(length),
+            &mut simple_builder(&mut buffers),
+        );
+        assert_eq!(
+            result,
+            Err(TessellationError::UnsupportedParamater(
+                UnsupportedParamater::MaxTriangleEdgeLengthIsNotPositive
+            ))
+        );
+    }
+
+    // The subdivision depth is bounded.
+    let mut buffers: VertexBuffers<Point, u32> = VertexBuffers::new();
+    tess.tessellate_path(
+        &path,
+        &FillOptions::default().with_max_triangle_edge_length(f32::MIN_POSITIVE),
+        &mut BuffersBuilder::new(&mut buffers, Positions),
+    )
+    .unwrap();
+    assert_eq!(
+        buffers.indices.len(),
+        3 << FillOptions::MAX_SUBDIVISION_DEPTH
+    );
+}
+
+#[test]
+fn memory_limit() {
+    let mut builder = Path::builder().with_svg();
+    build_logo_path(&mut builder);
+    let path = builder.build();
+
+    let mut tess = FillTessellator::new();
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+
+    let result = tess.tessellate_path(
+        &path,
+        &FillOptions::tolerance(0.05).with_memory_limit(1024),
+        &mut simple_builder(&mut buffers),
+    );
+    assert_eq!(result, Err(TessellationError::MemoryLimitExceeded));
+
+    // The same applies to the builder interface.
+    let options = FillOptions::tolerance(0.05).with_memory_limit(1024);
+    let mut output = simple_builder(&mut buffers);
+    let mut builder = tess.builder(&options, &mut output);
+    for event in path.iter() {
+        builder.path_event(event);
+    }
+    assert_eq!(builder.build(), Err(TessellationError::MemoryLimitExceeded));
+
+    // The tessellator can be reused after a failure.
+    buffers.vertices.clear();
+    buffers.indices.clear();
+    tess.tessellate_path(
+        &path,
+        &FillOptions::tolerance(0.05).with_memory_limit(1024 * 1024),
+        &mut simple_builder(&mut buffers),
+    )
+    .unwrap();
+    assert!(!buffers.indices.is_empty());
+}
+
+#[test]
+fn memory_limit_active_edges() {
+    // Each tooth of the comb only has a few events but they all cross the sweep line at
+    // the same time.
+    let mut builder = Path::builder();
+    for i in 0..100 {
+        let x = i as f32 * 2.0;
+        builder.begin(point(x, 0.0));
+        builder.line_to(point(x + 1.0, 0.0));
+        builder.line_to(point(x + 1.0, 100.0));
+        builder.line_to(point(x, 100.0));
+        builder.end(true);
+    }
+    let path = builder.build();
+
+    let mut tess = FillTessellator::new();
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+
+    // Enough for the event queue but not for the active edges and the spans.
+    let events_size = 500 * crate::event_queue::EVENT_MEMORY_SIZE;
+    let result = tess.tessellate_path(
+        &path,
+        &FillOptions::default().with_memory_limit(events_size),
+        &mut simple_builder(&mut buffers),
+    );
+    assert_eq!(result, Err(TessellationError::MemoryLimitExceeded));
+
+    tess.tessellate_path(
+        &path,
+        &FillOptions::default().with_memory_limit(events_size * 4),
+        &mut simple_builder(&mut buffers),
+    )
+    .unwrap();
+}
+
+#[test]
+fn attribute_interpolation() {
+    let mut builder = Path::builder_with_attributes(1);
+    builder.begin(point(0.0, 0.0), &[0.0]);
+    builder.quadratic_bezier_to(point(50.0, 50.0), point(100.0, 0.0), &[1.0]);
+    builder.end(true);
+    let path = builder.build();
+
+    let attributes = |options: &FillOptions| {
+        let mut buffers: VertexBuffers<f32, u16> = VertexBuffers::new();
+        FillTessellator::new()
+            .tessellate_path(
+                &path,
+                options,
+                &mut BuffersBuilder::new(&mut buffers, |mut v: FillVertex| {
+                    v.interpolated_attributes()[0]
+                }),
+            )
+            .unwrap();
+        buffers.vertices
+    };
+
+    // The vertices along the curve are interpolated linearly by default.
+    let linear = attributes(&FillOptions::default());
+    assert!(linear.len() > 3);
+    assert!(linear.iter().any(|a| *a > 0.0 && *a < 1.0));
+
+    // Snap to the closest endpoint instead.
+    let step = attributes(&FillOptions::default().with_attribute_interpolation(
+        |_, from, to, t| {
+            if t < 0.5 {
+                from
+            } else {
+                to
+            }
+        },
+    ));
+    assert_eq!(step.len(), linear.len());
+    assert!(step.iter().all(|a| *a == 0.0 || *a == 1.0));
+    assert!(step.contains(&0.0) && step.contains(&1.0));
+}
+
+#[test]
+fn triangle_winding() {
+    use crate::{TriangleWinding, YAxisDirection};
+
+    let mut builder = Path::builder().with_svg();
+    build_logo_path(&mut builder);
+    let path = builder.build();
+
+    // Signed areas of the triangles.
+    let areas = |options: &FillOptions| {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        let mut tess = FillTessellator::new();
+        tess.tessellate_path(&path, options, &mut simple_builder(&mut buffers))
+            .unwrap();
+        let rect = Box2D {
+            min: point(0.0, 0.0),
+            max: point(10.0, 5.0),
+        };
+        tess.tessellate_rectangle(&rect, options, &mut simple_builder(&mut buffers))
+            .unwrap();
+        tess.tessellate_circle(
+            point(0.0, 0.0),
+            5.0,
+            options,
+            &mut simple_builder(&mut buffers),
+        )
+        .unwrap();
+        buffers
+            .indices
+            .chunks(3)
+            .map(|tri| {
+                let a = buffers.vertices[tri[0] as usize];
+                let b = buffers.vertices[tri[1] as usize];
+                let c = buffers.vertices[tri[2] as usize];
+                (b - a).cross(c - a)
+            })
+            .filter(|area| area.abs() > 1e-5)
+            .collect::<Vec<f32>>()
+    };
+
+    // Counter-clockwise on screen with the y axis pointing down.
+    let default = areas(&FillOptions::default());
+    assert!(default.iter().all(|area| *area < 0.0));
+
+    let options = FillOptions::default().with_triangle_winding(TriangleWinding::Clockwise);
+    assert!(areas(&options).iter().all(|area| *area > 0.0));
+
+    // Counter-clockwise with the y axis pointing up.
+    let options = FillOptions::default().with_y_axis_direction(YAxisDirection::Up);
+    let y_up = areas(&options);
+    assert_eq!(y_up.len(), default.len());
+    assert!(y_up.iter().all(|area| *area > 0.0));
+
+    let options = options.with_triangle_winding(TriangleWinding::Clockwise);
+    assert!(areas(&options).iter().all(|area| *area < 0.0));
+}
+
+#[test]
+fn tessellate_from_path_storage() {
+    use crate::path::commands::PathCommands;
+    use crate::path::{ControlPointId, EndpointId};
+    use crate::{FillGeometryBuilder, Orientation, TessellationResult};
+
+    let points = [
+        point(0.0, 0.0),
+        point(10.0, 1.0),
+        point(14.0, 8.0),
+        point(2.0, 10.0),
+        point(5.0, 4.0),
+    ];
+
+    let mut cmds = PathCommands::builder();
+    cmds.begin(EndpointId(0));
+    cmds.line_to(EndpointId(1));
+    cmds.quadratic_bezier_to(ControlPointId(2), EndpointId(3));
+    cmds.line_to(EndpointId(4));
+    cmds.end(true);
+    let cmds = cmds.build();
+
+    let mut builder = Path::builder();
+    builder.begin(points[0]);
+    builder.line_to(points[1]);
+    builder.quadratic_bezier_to(points[2], points[3]);
+    builder.line_to(points[4]);
+    builder.end(true);
+    let path = builder.build();
+
+    type Tessellate<'l> = &'l dyn Fn(
+        &mut FillTessellator,
+        &FillOptions,
+        &mut dyn FillGeometryBuilder,
+    ) -> TessellationResult;
+
+    let tessellate = |options: &FillOptions, f: Tessellate| {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        f(
+            &mut FillTessellator::new(),
+            options,
+            &mut simple_builder(&mut buffers),
+        )
+        .unwrap();
+        buffers
+    };
+
+    let num_vertices = tessellate(&FillOptions::tolerance(0.001), &|tess, options, output| {
+        tess.tessellate(path.iter(), options, output)
+    })
+    .vertices
+    .len() as u32;
+
+    // The path slice and the commands are tessellated from their storage, also when
+    // the output limits require another attempt with a coarser tolerance.
+    for options in &[
+        FillOptions::tolerance(0.001),
+        FillOptions::tolerance(0.001).with_sweep_orientation(Orientation::Horizontal),
+        FillOptions::tolerance(0.001).with_max_vertices(num_vertices / 2),
+    ] {
+        let expected = tessellate(options, &|tess, options, output| {
+            tess.tessellate(path.iter(), options, output)
+        });
+        assert!(!expected.indices.is_empty());
+
+        let from_path = tessellate(options, &|tess, options, output| {
+            tess.tessellate_path(&path, options, output)
+        });
+        assert_eq!(from_path.vertices, expected.vertices);
+        assert_eq!(from_path.indices, expected.indices);
+
+        let from_commands = tessellate(options, &|tess, options, output| {
+            tess.tessellate_commands(&cmds, &(&points[..], &points[..]), None, options, output)
+        });
+        assert_eq!(from_commands.vertices, expected.vertices);
+        assert_eq!(from_commands.indices, expected.indices);
+    }
+}
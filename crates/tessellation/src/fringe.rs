@@ -0,0 +1,333 @@
+//! Anti-aliasing fringes around strokes.
+//!
+//! The stroke is tessellated as usual into a recording geometry builder. Once it is
+//! complete, each boundary edge of the output (an edge that belongs to a single triangle)
+//! is extruded outwards into a quad whose outer vertices have a coverage of zero.
+
+use crate::geom::euclid::default::Point2D;
+use crate::geometry_builder::{GeometryBuilder, GeometryBuilderError, StrokeGeometryBuilder};
+use crate::math::{vector, Point, Vector};
+use crate::path::{AttributeStore, Side};
+use crate::stroke::StrokeVertexData;
+use crate::{
+    AttributeInterpolation, Order, StrokePart, StrokeVertex, TessellationResult, VertexId,
+    VertexSource,
+};
+
+use std::collections::HashMap;
+
+/// The attributes of a vertex produced by the stroke tessellator.
+#[derive(Copy, Clone)]
+struct RecordedVertex {
+    id: VertexId,
+    position_on_path: Point,
+    half_width: f32,
+    normal: Vector,
+    advancement: f32,
+    side: Side,
+    convex_side: Option<Side>,
+    order: Option<Order>,
+    src: VertexSource,
+}
+
+impl RecordedVertex {
+    fn position(&self) -> Point {
+        self.position_on_path + self.normal * self.half_width
+    }
+}
+
+/// A stroke geometry builder that forwards the geometry to another one and adds the
+/// anti-aliasing fringe when calling `finish`.
+pub(crate) struct StrokeFringe<'l> {
+    output: &'l mut dyn StrokeGeometryBuilder,
+    width: f32,
+    interpolation: Option<AttributeInterpolation>,
+    vertices: Vec<RecordedVertex>,
+    ids: HashMap<VertexId, usize>,
+    triangles: Vec<[VertexId; 3]>,
+}
+
+impl<'l> StrokeFringe<'l> {
+    pub fn new(
+        output: &'l mut dyn StrokeGeometryBuilder,
+        width: f32,
+        interpolation: Option<AttributeInterpolation>,
+    ) -> Self {
+        StrokeFringe {
+            output,
+            width,
+            interpolation,
+            vertices: Vec::new(),
+            ids: HashMap::new(),
+            triangles: Vec::new(),
+        }
+    }
+
+    /// Adds the fringe and ends the geometry.
+    ///
+    /// Must be called after the stroke was successfully tessellated.
+    pub fn finish(mut self, attributes: &dyn AttributeStore) -> TessellationResult {
+        if let Err(e) = self.add_fringe(attributes) {
+            self.output.abort_geometry();
+            return Err(e.into());
+        }
+
+        self.output.end_geometry();
+
+        Ok(())
+    }
+
+    fn add_fringe(&mut self, attributes: &dyn AttributeStore) -> Result<(), GeometryBuilderError> {
+        // Count the triangles on each side of the edges.
+        let mut edges: HashMap<(VertexId, VertexId), (u32, [VertexId; 3])> =
+            HashMap::with_capacity(self.triangles.len() * 3);
+        for &[a, b, c] in &self.triangles {
+            for (from, to, opposite) in [(a, b, c), (b, c, a), (c, a, b)] {
+                let key = if from.0 < to.0 {
+                    (from, to)
+                } else {
+                    (to, from)
+                };
+                edges.entry(key).or_insert((0, [from, to, opposite])).0 += 1;
+            }
+        }
+
+        // The boundary edges with their outward normal.
+        let mut boundary: Vec<(usize, usize, Vector)> = Vec::new();
+        for (count, [from, to, opposite]) in edges.into_values() {
+            if count != 1 {
+                continue;
+            }
+            let from = self.ids[&from];
+            let to = self.ids[&to];
+            let a = self.vertices[from].position();
+            let b = self.vertices[to].position();
+            let c = self.vertices[self.ids[&opposite]].position();
+            let mut normal = match vector(a.y - b.y, b.x - a.x).try_normalize() {
+                Some(normal) => normal,
+                None => continue,
+            };
+            if normal.dot(c - a) > 0.0 {
+                normal = -normal;
+            }
+            boundary.push((from, to, normal));
+        }
+        // Process the edges in a deterministic order.
+        boundary.sort_by_key(|&(from, to, _)| (from, to));
+
+        // Offset the vertices along the average normal of their boundary edges, making
+        // the fringe wider at corners (up to a limit) so that it keeps a constant width.
+        let mut directions = vec![vector(0.0, 0.0); self.vertices.len()];
+        for &(from, to, normal) in &boundary {
+            directions[from] += normal;
+            directions[to] += normal;
+        }
+        let mut min_dot = vec![1.0f32; self.vertices.len()];
+        for direction in &mut directions {
+            *direction = direction.try_normalize().unwrap_or(vector(0.0, 0.0));
+        }
+        for &(from, to, normal) in &boundary {
+            min_dot[from] = min_dot[from].min(directions[from].dot(normal));
+            min_dot[to] = min_dot[to].min(directions[to].dot(normal));
+        }
+
+        let mut fringe_ids = vec![VertexId::INVALID; self.vertices.len()];
+        let mut buffer = vec![0.0; attributes.num_attributes()];
+        for &(from, to, _) in &boundary {
+            for idx in [from, to] {
+                if fringe_ids[idx] != VertexId::INVALID {
+                    continue;
+                }
+
+                let v = self.vertices[idx];
+                let mut direction = directions[idx];
+                if direction == vector(0.0, 0.0) {
+                    // The boundary edges cancel each other out, fall back to the normal of
+                    // the stroke.
+                    direction = v.normal.try_normalize().unwrap_or(vector(0.0, 0.0));
+                }
+                let offset = direction * (self.width / min_dot[idx].max(0.5));
+
+                // Zero-width vertices can't be displaced along their normal.
+                let half_width = if v.half_width > 0.0 {
+                    v.half_width
+                } else {
+                    1.0
+                };
+                let mut data = StrokeVertexData {
+                    position_on_path: v.position_on_path,
+                    half_width,
+                    normal: (v.position() + offset - v.position_on_path) / half_width,
+                    advancement: v.advancement,
+                    side: v.side,
+                    convex_side: v.convex_side,
+                    order: v.order,
+                    src: v.src,
+                    coverage: 0.0,
+                    buffer: &mut buffer,
+                    buffer_is_valid: false,
+                    interpolation: self.interpolation,
+                    origin: Point2D::origin(),
+                    position_on_path_f64: None,
+                };
+                fringe_ids[idx] = self
+                    .output
+                    .add_stroke_vertex(StrokeVertex(&mut data, attributes))?;
+            }
+        }
+
+        self.output.set_stroke_part(StrokePart::Fringe);
+        for &(from, to, _) in &boundary {
+            let a = self.vertices[from].id;
+            let b = self.vertices[to].id;
+            // Same orientation as the triangle of the boundary edge.
+            self.output
+                .add_triangle(a, fringe_ids[from], fringe_ids[to]);
+            self.output.add_triangle(a, fringe_ids[to], b);
+        }
+
+        Ok(())
+    }
+}
+
+impl<'l> GeometryBuilder for StrokeFringe<'l> {
+    fn begin_geometry(&mut self) {
+        self.vertices.clear();
+        self.ids.clear();
+        self.triangles.clear();
+        self.output.begin_geometry();
+    }
+
+    // The geometry is ended in `finish`.
+    fn end_geometry(&mut self) {}
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        self.triangles.push([a, b, c]);
+        self.output.add_triangle(a, b, c);
+    }
+
+    fn abort_geometry(&mut self) {
+        self.output.abort_geometry();
+    }
+}
+
+impl<'l> StrokeGeometryBuilder for StrokeFringe<'l> {
+    fn add_stroke_vertex(
+        &mut self,
+        vertex: StrokeVertex,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        let mut recorded = RecordedVertex {
+            id: VertexId::INVALID,
+            position_on_path: vertex.0.position_on_path,
+            half_width: vertex.0.half_width,
+            normal: vertex.0.normal,
+            advancement: vertex.0.advancement,
+            side: vertex.0.side,
+            convex_side: vertex.0.convex_side,
+            order: vertex.0.order,
+            src: vertex.0.src,
+        };
+        recorded.id = self.output.add_stroke_vertex(vertex)?;
+        self.ids.insert(recorded.id, self.vertices.len());
+        self.vertices.push(recorded);
+
+        Ok(recorded.id)
+    }
+
+    fn set_stroke_part(&mut self, part: StrokePart) {
+        self.output.set_stroke_part(part);
+    }
+}
+
+#[test]
+fn stroke_fringe() {
+    use crate::math::point;
+    use crate::path::Path;
+    use crate::{BuffersBuilder, LineJoin, StrokeOptions, StrokeTessellator, VertexBuffers};
+
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.end(false);
+    let path = builder.build();
+
+    let tessellate = |path: &Path, options: &StrokeOptions| {
+        let mut buffers: VertexBuffers<(Point, f32), u16> = VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate_path(
+                path,
+                options,
+                &mut BuffersBuilder::new(&mut buffers, |v: StrokeVertex| {
+                    (v.position(), v.coverage())
+                }),
+            )
+            .unwrap();
+        buffers
+    };
+
+    let options = StrokeOptions::default().with_line_width(2.0);
+    let stroke = tessellate(&path, &options);
+    let with_fringe = tessellate(&path, &options.with_anti_aliasing_fringe(1.0));
+
+    // The stroke is unchanged and followed by the fringe.
+    let num_vertices = stroke.vertices.len();
+    assert_eq!(with_fringe.vertices[..num_vertices], stroke.vertices[..]);
+    assert_eq!(
+        with_fringe.indices[..stroke.indices.len()],
+        stroke.indices[..]
+    );
+
+    // One vertex per corner of the rectangle, displaced diagonally so that the fringe
+    // has the same width everywhere, and two triangles per side.
+    let mut fringe: Vec<Point> = with_fringe.vertices[num_vertices..]
+        .iter()
+        .map(|&(position, coverage)| {
+            assert_eq!(coverage, 0.0);
+            position.round()
+        })
+        .collect();
+    fringe.sort_by(|a, b| (a.x, a.y).partial_cmp(&(b.x, b.y)).unwrap());
+    assert_eq!(
+        fringe,
+        [
+            point(-1.0, -2.0),
+            point(-1.0, 2.0),
+            point(11.0, -2.0),
+            point(11.0, 2.0)
+        ]
+    );
+    assert_eq!(with_fringe.indices.len() - stroke.indices.len(), 8 * 3);
+    assert!(stroke.vertices.iter().all(|(_, coverage)| *coverage == 1.0));
+
+    // Joins and caps get a fringe too.
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.end(false);
+    let path = builder.build();
+    let options = StrokeOptions::default()
+        .with_line_width(2.0)
+        .with_line_join(LineJoin::Round)
+        .with_line_cap(crate::LineCap::Round)
+        .with_anti_aliasing_fringe(0.5);
+    let buffers = tessellate(&path, &options);
+    assert!(buffers
+        .vertices
+        .iter()
+        .any(|(_, coverage)| *coverage == 0.0));
+    for &(position, coverage) in &buffers.vertices {
+        if coverage == 0.0 {
+            // Outer vertices are about the width of the fringe away from the stroke.
+            let on_segments = [
+                (position.x.clamp(0.0, 10.0), 0.0),
+                (10.0, position.y.clamp(0.0, 10.0)),
+            ];
+            let d = on_segments
+                .iter()
+                .map(|&(x, y)| (position - point(x, y)).length())
+                .fold(f32::MAX, f32::min);
+            assert!(d > 1.2 && d < 1.6, "{:?} {:?}", position, d);
+        }
+    }
+}
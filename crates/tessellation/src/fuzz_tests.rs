@@ -990,3 +990,108 @@ fn fuzzing_test_case_26() {
     // SVG path syntax:
     // "M 338.79803 124.2612 L 93.79423 293.62488 L 519.7297 207.82262 L 109.83586 935.8234 L 473.3689 544.36597 L 228.83612 487.7857 L 844.8811 759.98035 L 204.86772 447.48807 L 762.8039 447.4875 L 717.20856 187.1466 L 725.5502 718.1507 L 354.4551 133.0471 L 79.57357 924.2797 L 113.65747 7.6993704 ZM 473.30374 933.1941 L 413.74387 585.3911 L 383.29245 86.36898 L 608.89056 708.4878 L 613.6966 557.6661 L 700.585 93.54353 L 726.2056 41.609226 L 886.1893 601.08905 L 448.21848 686.62616 L 416.16428 715.7101 L 210.18976 694.7306 L 80.262665 297.84662 L 325.09494 679.4497 L 404.01047 309.32385 L 866.0461 808.1123 L 466.7661 404.0181 L 908.7635 77.231766 L 3.31223 60.035408 L 919.3632 703.6854 L 910.4674 612.88336 L 966.38995 761.61743 L 815.1502 654.41504 L 179.95155 904.1613 Z"
 }
+
+#[test]
+fn reference_fill_area() {
+    // Compare the area covered by the fill tessellator with the slow reference
+    // tessellator on generated paths. Very small features are sensitive to the
+    // precision of the tessellator, hence the absolute slack.
+    use lyon_extra::fuzzing::{PathGenerator, PathKind};
+    use lyon_extra::reference;
+
+    let kinds = [
+        PathKind::Random,
+        PathKind::Spiral,
+        PathKind::Star,
+        PathKind::NearDegenerate,
+        PathKind::CoincidentPoints,
+    ];
+
+    let mut tessellator = FillTessellator::new();
+    for seed in 0..200 {
+        let mut generator = PathGenerator::new(seed);
+        for &kind in &kinds {
+            let path = generator.path(kind);
+            for &fill_rule in &[FillRule::EvenOdd, FillRule::NonZero] {
+                let options = FillOptions::tolerance(0.05).with_fill_rule(fill_rule);
+                let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+                tessellator
+                    .tessellate_path(&path, &options, &mut simple_builder(&mut buffers))
+                    .unwrap();
+
+                let triangles: Vec<reference::Triangle> = buffers
+                    .indices
+                    .chunks(3)
+                    .map(|t| {
+                        let v = |i: u16| buffers.vertices[i as usize];
+                        [v(t[0]), v(t[1]), v(t[2])]
+                    })
+                    .collect();
+
+                let area = reference::area(&triangles);
+                let expected = reference::area(&reference::fill(path.iter(), 0.05, fill_rule));
+                assert!(
+                    (area - expected).abs() <= expected * 0.01 + 1.0,
+                    "seed {} {:?} {:?}: area {} expected {}",
+                    seed,
+                    kind,
+                    fill_rule,
+                    area,
+                    expected,
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn reference_fill_area_negative_zero() {
+    // Found by reference_fill_area. The closing edge of the first sub-path goes from -0.0
+    // to 0.0, which used to give it a negative infinite slope and broke the order of the
+    // edges below (-50, 0).
+    use lyon_extra::reference;
+
+    let mut builder = Path::builder();
+    builder.begin(point(-50.0, 0.0));
+    builder.line_to(point(0.0, 25.0));
+    builder.line_to(point(-25.0, 0.0));
+    builder.line_to(point(0.0, 25.0));
+    builder.line_to(point(-25.0, -100.0));
+    builder.line_to(point(-25.0, -0.0));
+    builder.close();
+    builder.begin(point(-25.0, -100.0));
+    builder.line_to(point(-50.0, 0.0));
+    builder.line_to(point(-50.0, 0.0));
+    builder.line_to(point(-50.0, 25.0));
+    builder.close();
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    FillTessellator::new()
+        .tessellate_path(
+            &path,
+            &FillOptions::non_zero(),
+            &mut simple_builder(&mut buffers),
+        )
+        .unwrap();
+
+    let triangles: Vec<reference::Triangle> = buffers
+        .indices
+        .chunks(3)
+        .map(|t| {
+            let v = |i: u16| buffers.vertices[i as usize];
+            [v(t[0]), v(t[1]), v(t[2])]
+        })
+        .collect();
+
+    let area = reference::area(&triangles);
+    let expected = reference::area(&reference::fill(path.iter(), 0.1, FillRule::NonZero));
+    assert!(
+        (area - expected).abs() < 0.01,
+        "area {} expected {}",
+        area,
+        expected
+    );
+
+    test_path(path.as_slice());
+}
@@ -16,6 +16,7 @@
 //! * [`GeometryBuilder`](trait.GeometryBuilder.html)
 //! * [`FillGeometryBuilder`](trait.FillGeometryBuilder.html)
 //! * [`StrokeGeometryBuilder`](trait.StrokeGeometryBuilder.html)
+//! * [`LineGeometryBuilder`](trait.LineGeometryBuilder.html)
 //!
 //! The traits above are what the tessellators interface with. It is very common to push
 //! vertices and indices into a pair of vectors, so to facilitate this pattern this module
@@ -35,6 +36,22 @@
 //!   This separates the construction of vertex values from the assembly of the vertex buffers.
 //!   Another, simpler example of vertex constructor is the [`Positions`](struct.Positions.html)
 //!   constructor which just returns the vertex position untransformed.
+//! * The [`SolidColor`](struct.SolidColor.html) and [`ColorAttribute`](struct.ColorAttribute.html)
+//!   vertex constructors which produce a [`ColoredVertex`](struct.ColoredVertex.html) from a
+//!   constant color or from colors stored in custom attributes (see the
+//!   [`color`](../color/index.html) module).
+//! * The [`StrokePartRanges`](struct.StrokePartRanges.html) wrapper which records the index
+//!   ranges of the edges, joins and caps produced by the stroke tessellator.
+//! * The [`StrokeQuads`](struct.StrokeQuads.html) wrapper which groups the triangles of each
+//!   segment of a stroke into quads.
+//! * The [`SkipDegenerateTriangles`](struct.SkipDegenerateTriangles.html) wrapper which
+//!   drops zero or near-zero area triangles before they reach the geometry builder.
+//! * The [`FillBoundaryEdges`](struct.FillBoundaryEdges.html) wrapper which records the
+//!   edges on the boundary of the filled shape.
+//! * The [`FillBoundaryNormals`](struct.FillBoundaryNormals.html) wrapper which computes
+//!   outward normals at the vertices on the boundary of the filled shape.
+//! * The [`TriangleListBuilder`](struct.TriangleListBuilder.html) which writes a flat,
+//!   non-indexed list of triangles into a `Vec`, duplicating shared vertices.
 //!
 //! Geometry builders are a practical way to add one last step to the tessellation pipeline,
 //! such as applying a transform or clipping the geometry.
@@ -189,11 +206,18 @@
 //! ```
 //!
 
-use crate::math::Point;
-use crate::{FillVertex, Index, StrokeVertex, VertexId};
+use crate::color::Color;
+use crate::math::{Point, Transform, Vector};
+use crate::path::{simd, AttributeIndex, AttributeStore};
+use crate::{
+    inverts_winding, lerp_attribute, FillOptions, FillVertex, Index, Side, StrokeOptions,
+    StrokeVertex, VertexId, VertexSource, YAxisDirection,
+};
 
+use std::collections::HashMap;
 use std::convert::From;
-use std::ops::Add;
+use std::hash::Hash;
+use std::ops::{Add, Range};
 use thiserror::Error;
 
 /// An error that can happen while generating geometry.
@@ -260,6 +284,45 @@ pub trait StrokeGeometryBuilder: GeometryBuilder {
     /// This method can only be called between begin_geometry and end_geometry.
     fn add_stroke_vertex(&mut self, vertex: StrokeVertex)
         -> Result<VertexId, GeometryBuilderError>;
+
+    /// Called by the tessellator before it adds the triangles of an edge, a join or a cap.
+    ///
+    /// All triangles added until the next call belong to `part`. Each join and each cap
+    /// is preceded by its own call. The default implementation does nothing.
+    fn set_stroke_part(&mut self, _part: StrokePart) {}
+}
+
+/// A Geometry builder that receives line segments instead of triangles, for renderers that
+/// draw line primitives (such as `GL_LINES`).
+///
+/// See `StrokeTessellator::tessellate_hairline_with_ids`. The vertices are added with
+/// `add_stroke_vertex`, their half line width and their normal are zero. `add_triangle` is
+/// not called.
+pub trait LineGeometryBuilder: StrokeGeometryBuilder {
+    /// Insert a line segment made of vertices that were added after the last call to
+    /// begin_geometry.
+    ///
+    /// This method can only be called between begin_geometry and end_geometry.
+    fn add_line(&mut self, a: VertexId, b: VertexId);
+}
+
+/// The part of a stroke that triangles passed to a `StrokeGeometryBuilder` belong to.
+///
+/// Square caps are produced by extending the first and last edges of a sub-path rather
+/// than with triangles of their own, so the triangles of these edges are reported as
+/// `StrokePart::StartCap` and `StrokePart::EndCap`. If a sub-path has a single edge and
+/// both of its caps are square, the edge is reported as `StrokePart::StartCap`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum StrokePart {
+    Edge,
+    Join,
+    StartCap,
+    EndCap,
+    /// The anti-aliasing fringe, see `StrokeOptions::anti_aliasing_fringe`.
+    Fringe,
+    /// The dots of `StrokeTessellator::tessellate_dots_with_ids`.
+    Dot,
 }
 
 /// Structure that holds the vertex and index data.
@@ -287,6 +350,203 @@ impl<OutputVertex, OutputIndex> VertexBuffers<OutputVertex, OutputIndex> {
     }
 }
 
+impl<OutputVertex, OutputIndex> VertexBuffers<OutputVertex, OutputIndex>
+where
+    OutputIndex: Copy + Eq + Hash + Ord,
+{
+    /// Computes an index buffer in the "triangles with adjacency" layout used by geometry
+    /// shaders (for example `GL_TRIANGLES_ADJACENCY`).
+    ///
+    /// Each triangle `(a, b, c)` of `indices` produces six indices `a, ab, b, bc, c, ca`
+    /// where `ab` is the vertex opposite to the edge `a-b` in the neighboring triangle.
+    /// Edges on the boundary of the mesh have no neighbor, in which case the opposite vertex
+    /// of the triangle itself is used (`c` for the edge `a-b`).
+    ///
+    /// This can be used for silhouette detection or to draw wireframes on the GPU.
+    pub fn triangles_with_adjacency(&self) -> Vec<OutputIndex> {
+        let triangles = self.indices.chunks_exact(3);
+
+        // For each undirected edge, the triangles it belongs to and their opposite vertex.
+        let mut edges: HashMap<(OutputIndex, OutputIndex), Vec<(usize, OutputIndex)>> =
+            HashMap::with_capacity(self.indices.len());
+        for (tri_idx, tri) in triangles.clone().enumerate() {
+            for i in 0..3 {
+                let (a, b, c) = (tri[i], tri[(i + 1) % 3], tri[(i + 2) % 3]);
+                edges
+                    .entry((a.min(b), a.max(b)))
+                    .or_default()
+                    .push((tri_idx, c));
+            }
+        }
+
+        let mut result = Vec::with_capacity(self.indices.len() * 2);
+        for (tri_idx, tri) in triangles.enumerate() {
+            for i in 0..3 {
+                let (a, b, c) = (tri[i], tri[(i + 1) % 3], tri[(i + 2) % 3]);
+                let opposite = edges[&(a.min(b), a.max(b))]
+                    .iter()
+                    .find(|(other, _)| *other != tri_idx)
+                    .map(|(_, vertex)| *vertex)
+                    .unwrap_or(c);
+                result.push(a);
+                result.push(opposite);
+            }
+        }
+
+        result
+    }
+}
+
+impl<OutputVertex, OutputIndex> VertexBuffers<OutputVertex, OutputIndex>
+where
+    OutputVertex: Clone,
+    OutputIndex: Copy + Into<VertexId>,
+{
+    /// Returns the triangles as a flat, non-indexed list of vertices.
+    ///
+    /// Every three vertices form a triangle. Vertices shared by several triangles are
+    /// duplicated. See also `TriangleListBuilder` which produces this layout directly.
+    pub fn to_triangle_list(&self) -> Vec<OutputVertex> {
+        self.indices
+            .iter()
+            .map(|&index| self.vertices[index.into().to_usize()].clone())
+            .collect()
+    }
+}
+
+impl<OutputVertex, OutputIndex> VertexBuffers<OutputVertex, OutputIndex>
+where
+    OutputIndex: Copy + Into<VertexId>,
+{
+    /// Computes the gradient (derivatives along x and y) of custom attributes at each vertex.
+    ///
+    /// `position` and `attribute` read the position and the custom attributes of a vertex.
+    /// The gradient of an attribute is constant over each triangle. The gradient at a vertex
+    /// is the average of the gradients of its incident triangles, weighted by their area.
+    ///
+    /// The result contains `num_attributes` gradients per vertex, in the same order as
+    /// `vertices`: the gradient of attribute `a` at vertex `v` is at index
+    /// `v * num_attributes + a`. Vertices that are not part of any triangle with a non-zero
+    /// area have a zero gradient.
+    ///
+    /// Storing the gradients in the vertices lets shaders compute analytic anti-aliasing for
+    /// patterns driven by custom attributes.
+    pub fn attribute_gradients(
+        &self,
+        num_attributes: usize,
+        position: impl Fn(&OutputVertex) -> Point,
+        attribute: impl Fn(&OutputVertex, AttributeIndex) -> f32,
+    ) -> Vec<Vector> {
+        let mut gradients = vec![Vector::zero(); self.vertices.len() * num_attributes];
+        let mut weights = vec![0.0; self.vertices.len()];
+
+        for tri in self.indices.chunks_exact(3) {
+            let ids = [
+                tri[0].into().to_usize(),
+                tri[1].into().to_usize(),
+                tri[2].into().to_usize(),
+            ];
+            let v = ids.map(|id| &self.vertices[id]);
+            let p0 = position(v[0]);
+            let e1 = position(v[1]) - p0;
+            let e2 = position(v[2]) - p0;
+            let det = e1.cross(e2);
+            if det == 0.0 {
+                continue;
+            }
+
+            let weight = det.abs();
+            for &id in &ids {
+                weights[id] += weight;
+            }
+
+            for a in 0..num_attributes {
+                let f0 = attribute(v[0], a);
+                let d1 = attribute(v[1], a) - f0;
+                let d2 = attribute(v[2], a) - f0;
+                let gradient = Vector::new(d1 * e2.y - d2 * e1.y, d2 * e1.x - d1 * e2.x) / det;
+                for &id in &ids {
+                    gradients[id * num_attributes + a] += gradient * weight;
+                }
+            }
+        }
+
+        for (id, weight) in weights.iter().enumerate() {
+            if *weight > 0.0 {
+                for gradient in &mut gradients[id * num_attributes..(id + 1) * num_attributes] {
+                    *gradient /= *weight;
+                }
+            }
+        }
+
+        gradients
+    }
+}
+
+impl<OutputVertex, OutputIndex> VertexBuffers<OutputVertex, OutputIndex>
+where
+    OutputVertex: Clone,
+    OutputIndex: Copy + Into<VertexId> + From<VertexId> + MaxIndex,
+{
+    /// Appends the geometry to `output` with a transform applied to the vertex positions.
+    ///
+    /// `position` gives access to the position of a vertex. The indices are offset to
+    /// refer to the appended vertices.
+    ///
+    /// This lets renderers that can't set a transform per draw call move cached
+    /// tessellations around without tessellating them again.
+    pub fn append_transformed_with(
+        &self,
+        transform: &Transform,
+        output: &mut Self,
+        position: impl Fn(&mut OutputVertex) -> &mut Point,
+    ) -> Result<(), GeometryBuilderError> {
+        let first_vertex = self.append_to(output)?;
+        for vertex in &mut output.vertices[first_vertex..] {
+            let p = position(vertex);
+            *p = transform.transform_point(*p);
+        }
+
+        Ok(())
+    }
+
+    fn append_to(&self, output: &mut Self) -> Result<usize, GeometryBuilderError> {
+        let first_vertex = output.vertices.len();
+        if first_vertex + self.vertices.len() > OutputIndex::MAX {
+            return Err(GeometryBuilderError::TooManyVertices);
+        }
+
+        output.vertices.extend_from_slice(&self.vertices);
+        output.indices.extend(
+            self.indices
+                .iter()
+                .map(|&index| OutputIndex::from(index.into() + first_vertex as Index)),
+        );
+
+        Ok(first_vertex)
+    }
+}
+
+impl<OutputIndex> VertexBuffers<Point, OutputIndex>
+where
+    OutputIndex: Copy + Into<VertexId> + From<VertexId> + MaxIndex,
+{
+    /// Appends the geometry to `output` with a transform applied to the vertex positions.
+    ///
+    /// The positions are transformed with the vectorized kernels of `lyon_path::simd`.
+    /// See `append_transformed_with` for other vertex types.
+    pub fn append_transformed(
+        &self,
+        transform: &Transform,
+        output: &mut Self,
+    ) -> Result<(), GeometryBuilderError> {
+        let first_vertex = self.append_to(output)?;
+        simd::transform_points(&mut output.vertices[first_vertex..], transform);
+
+        Ok(())
+    }
+}
+
 /// A temporary view on a `VertexBuffers` object which facilitate the population of vertex and index
 /// data.
 ///
@@ -375,6 +635,720 @@ impl<B: StrokeGeometryBuilder> StrokeGeometryBuilder for InvertWinding<B> {
     ) -> Result<VertexId, GeometryBuilderError> {
         self.0.add_stroke_vertex(vertex)
     }
+
+    #[inline]
+    fn set_stroke_part(&mut self, part: StrokePart) {
+        self.0.set_stroke_part(part);
+    }
+}
+
+/// Adapts the output of the tessellators to the coordinate system and triangle winding
+/// requested in the options.
+pub(crate) struct Oriented<'l, B: ?Sized> {
+    output: &'l mut B,
+    invert_winding: bool,
+    swap_sides: bool,
+}
+
+impl<'l, B: ?Sized> Oriented<'l, B> {
+    pub(crate) fn fill(options: &FillOptions, output: &'l mut B) -> Self {
+        Oriented {
+            output,
+            invert_winding: inverts_winding(options.y_axis_direction, options.triangle_winding),
+            swap_sides: false,
+        }
+    }
+
+    pub(crate) fn stroke(options: &StrokeOptions, output: &'l mut B) -> Self {
+        Oriented {
+            output,
+            invert_winding: inverts_winding(options.y_axis_direction, options.triangle_winding),
+            swap_sides: options.y_axis_direction == YAxisDirection::Up,
+        }
+    }
+}
+
+impl<'l, B: GeometryBuilder + ?Sized> GeometryBuilder for Oriented<'l, B> {
+    fn begin_geometry(&mut self) {
+        self.output.begin_geometry();
+    }
+
+    fn end_geometry(&mut self) {
+        self.output.end_geometry()
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        if self.invert_winding {
+            self.output.add_triangle(a, c, b);
+        } else {
+            self.output.add_triangle(a, b, c);
+        }
+    }
+
+    fn abort_geometry(&mut self) {
+        self.output.abort_geometry();
+    }
+}
+
+impl<'l, B: FillGeometryBuilder + ?Sized> FillGeometryBuilder for Oriented<'l, B> {
+    #[inline]
+    fn add_fill_vertex(&mut self, vertex: FillVertex) -> Result<VertexId, GeometryBuilderError> {
+        self.output.add_fill_vertex(vertex)
+    }
+}
+
+impl<'l, B: StrokeGeometryBuilder + ?Sized> StrokeGeometryBuilder for Oriented<'l, B> {
+    fn add_stroke_vertex(
+        &mut self,
+        vertex: StrokeVertex,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        if !self.swap_sides {
+            return self.output.add_stroke_vertex(vertex);
+        }
+
+        let StrokeVertex(data, attributes) = vertex;
+        let (side, convex_side) = (data.side, data.convex_side);
+        data.side = side.opposite();
+        data.convex_side = convex_side.map(Side::opposite);
+        let result = self
+            .output
+            .add_stroke_vertex(StrokeVertex(&mut *data, attributes));
+        data.side = side;
+        data.convex_side = convex_side;
+
+        result
+    }
+
+    #[inline]
+    fn set_stroke_part(&mut self, part: StrokePart) {
+        self.output.set_stroke_part(part);
+    }
+}
+
+/// The range of indices produced for a part of a stroke.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct StrokePartRange {
+    pub part: StrokePart,
+    pub indices: Range<u32>,
+}
+
+/// A wrapper for stroke geometry builders that records the index range of each join and
+/// cap, as well as of consecutive edges.
+///
+/// This lets applications re-color or omit joins and caps (for example to hide caps under
+/// markers) by drawing sub-ranges of the index buffer, without re-tessellating.
+///
+/// Index ranges count from zero at the first triangle added to this builder. When writing
+/// into a `VertexBuffers` that already contains geometry, use `with_index_offset` with the
+/// length of the index buffer.
+pub struct StrokePartRanges<B> {
+    builder: B,
+    ranges: Vec<StrokePartRange>,
+    num_indices: u32,
+    current: StrokePart,
+    split: bool,
+    geometry_start: (usize, u32),
+}
+
+impl<B: StrokeGeometryBuilder> StrokePartRanges<B> {
+    pub fn new(builder: B) -> Self {
+        StrokePartRanges {
+            builder,
+            ranges: Vec::new(),
+            num_indices: 0,
+            current: StrokePart::Edge,
+            split: true,
+            geometry_start: (0, 0),
+        }
+    }
+
+    /// Offsets the recorded index ranges.
+    pub fn with_index_offset(mut self, offset: u32) -> Self {
+        self.num_indices = offset;
+        self.geometry_start.1 = offset;
+
+        self
+    }
+
+    /// The index ranges recorded so far, in the order they were produced.
+    pub fn ranges(&self) -> &[StrokePartRange] {
+        &self.ranges
+    }
+
+    /// Consumes the builder and returns the recorded index ranges.
+    pub fn into_ranges(self) -> Vec<StrokePartRange> {
+        self.ranges
+    }
+}
+
+impl<B: StrokeGeometryBuilder> GeometryBuilder for StrokePartRanges<B> {
+    fn begin_geometry(&mut self) {
+        self.geometry_start = (self.ranges.len(), self.num_indices);
+        self.split = true;
+        self.builder.begin_geometry();
+    }
+
+    fn end_geometry(&mut self) {
+        self.builder.end_geometry();
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        self.builder.add_triangle(a, b, c);
+
+        let start = self.num_indices;
+        self.num_indices += 3;
+
+        if !self.split {
+            if let Some(last) = self.ranges.last_mut() {
+                if last.part == self.current && last.indices.end == start {
+                    last.indices.end = self.num_indices;
+                    return;
+                }
+            }
+        }
+
+        self.split = false;
+        self.ranges.push(StrokePartRange {
+            part: self.current,
+            indices: start..self.num_indices,
+        });
+    }
+
+    fn abort_geometry(&mut self) {
+        let (num_ranges, num_indices) = self.geometry_start;
+        self.ranges.truncate(num_ranges);
+        self.num_indices = num_indices;
+        self.builder.abort_geometry();
+    }
+}
+
+impl<B: StrokeGeometryBuilder> StrokeGeometryBuilder for StrokePartRanges<B> {
+    #[inline]
+    fn add_stroke_vertex(
+        &mut self,
+        vertex: StrokeVertex,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        self.builder.add_stroke_vertex(vertex)
+    }
+
+    fn set_stroke_part(&mut self, part: StrokePart) {
+        // Consecutive edges are merged into a single range.
+        self.split = part != StrokePart::Edge || self.current != StrokePart::Edge;
+        self.current = part;
+        self.builder.set_stroke_part(part);
+    }
+}
+
+/// The two triangles of a segment of a stroke, with the geometry of the segment.
+///
+/// See `StrokeQuads`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct StrokeQuad {
+    /// The vertices of the quad, in order around it.
+    ///
+    /// The two triangles of the quad are `[0, 1, 3]` and `[1, 2, 3]` (indices in this
+    /// array), which have the same orientation as the triangles produced by the stroke
+    /// tessellator.
+    pub vertices: [VertexId; 4],
+    /// Position on the path at the start of the segment.
+    pub from: Point,
+    /// Position on the path at the end of the segment.
+    pub to: Point,
+    /// Half of the line width at the start of the segment.
+    pub from_half_width: f32,
+    /// Half of the line width at the end of the segment.
+    pub to_half_width: f32,
+    /// Advancement at the start of the segment.
+    pub from_advancement: f32,
+    /// Advancement at the end of the segment.
+    pub to_advancement: f32,
+}
+
+/// A wrapper for stroke geometry builders that groups the two triangles of each segment of
+/// the stroke into a `StrokeQuad`.
+///
+/// The vertices and the triangles are forwarded to the wrapped builder as usual, and the
+/// callback is called with the quads of each successful tessellation once it ends. This
+/// is useful for renderers that compute their own anti-aliasing or effects per segment,
+/// for example using signed distance fields. Joins and caps are not grouped into quads;
+/// square caps are part of the quads of the first and last segments (see `StrokePart`).
+///
+/// The quads are not necessarily in the order of the segments along the path. Use the
+/// advancement to sort them if needed.
+pub struct StrokeQuads<B, F> {
+    builder: B,
+    callback: F,
+    vertices: HashMap<VertexId, (Point, f32, f32)>,
+    quads: Vec<StrokeQuad>,
+    edge_triangle: Option<[VertexId; 3]>,
+    current: StrokePart,
+}
+
+impl<B, F> StrokeQuads<B, F>
+where
+    B: StrokeGeometryBuilder,
+    F: FnMut(&StrokeQuad),
+{
+    pub fn new(builder: B, callback: F) -> Self {
+        StrokeQuads {
+            builder,
+            callback,
+            vertices: HashMap::new(),
+            quads: Vec::new(),
+            edge_triangle: None,
+            current: StrokePart::Edge,
+        }
+    }
+
+    /// Consumes the wrapper and returns the wrapped builder.
+    pub fn into_inner(self) -> B {
+        self.builder
+    }
+
+    fn add_quad(&mut self, first: [VertexId; 3], second: [VertexId; 3]) {
+        // The vertex of the first triangle that is not on the shared diagonal starts the
+        // quad.
+        let start = match (0..3).find(|i| !second.contains(&first[*i])) {
+            Some(i) => i,
+            None => return,
+        };
+        let a = first[start];
+        let b = first[(start + 1) % 3];
+        let c = first[(start + 2) % 3];
+        let d = match second.iter().find(|v| !first.contains(v)) {
+            Some(d) => *d,
+            None => return,
+        };
+        let vertices = [a, b, d, c];
+
+        let mut info = [(Point::zero(), 0.0, 0.0); 4];
+        for (id, info) in vertices.iter().zip(&mut info) {
+            *info = match self.vertices.get(id) {
+                Some(v) => *v,
+                None => return,
+            };
+        }
+        let mut from = info[0];
+        let mut to = info[0];
+        for v in &info[1..] {
+            if v.2 < from.2 {
+                from = *v;
+            }
+            if v.2 > to.2 {
+                to = *v;
+            }
+        }
+        let (from, from_half_width, from_advancement) = from;
+        let (to, to_half_width, to_advancement) = to;
+
+        self.quads.push(StrokeQuad {
+            vertices,
+            from,
+            to,
+            from_half_width,
+            to_half_width,
+            from_advancement,
+            to_advancement,
+        });
+    }
+}
+
+impl<B, F> GeometryBuilder for StrokeQuads<B, F>
+where
+    B: StrokeGeometryBuilder,
+    F: FnMut(&StrokeQuad),
+{
+    fn begin_geometry(&mut self) {
+        self.vertices.clear();
+        self.quads.clear();
+        self.edge_triangle = None;
+        self.builder.begin_geometry();
+    }
+
+    fn end_geometry(&mut self) {
+        self.builder.end_geometry();
+        for quad in &self.quads {
+            (self.callback)(quad);
+        }
+        self.quads.clear();
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        self.builder.add_triangle(a, b, c);
+
+        if self.current != StrokePart::Edge {
+            return;
+        }
+
+        // Each segment is preceded by its own `StrokePart::Edge` and has two triangles.
+        match self.edge_triangle.take() {
+            Some(first) => self.add_quad(first, [a, b, c]),
+            None => self.edge_triangle = Some([a, b, c]),
+        }
+    }
+
+    fn abort_geometry(&mut self) {
+        self.quads.clear();
+        self.builder.abort_geometry();
+    }
+}
+
+impl<B, F> StrokeGeometryBuilder for StrokeQuads<B, F>
+where
+    B: StrokeGeometryBuilder,
+    F: FnMut(&StrokeQuad),
+{
+    fn add_stroke_vertex(
+        &mut self,
+        vertex: StrokeVertex,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        let info = (
+            vertex.position_on_path(),
+            vertex.line_width() * 0.5,
+            vertex.advancement(),
+        );
+        let id = self.builder.add_stroke_vertex(vertex)?;
+        self.vertices.insert(id, info);
+
+        Ok(id)
+    }
+
+    fn set_stroke_part(&mut self, part: StrokePart) {
+        self.current = part;
+        self.edge_triangle = None;
+        self.builder.set_stroke_part(part);
+    }
+}
+
+/// A wrapper for stroke and fill geometry builders that skips triangles with an area
+/// smaller than or equal to a given epsilon.
+///
+/// The tessellators can produce zero-area triangles, for example collinear triangles in
+/// the back side of stroke joins. They are harmless for rendering but some mesh processing
+/// tools reject them.
+pub struct SkipDegenerateTriangles<B> {
+    builder: B,
+    epsilon: f32,
+    positions: Vec<Point>,
+    first_vertex: Option<Index>,
+    num_skipped: u32,
+}
+
+impl<B: GeometryBuilder> SkipDegenerateTriangles<B> {
+    pub fn new(builder: B, epsilon: f32) -> Self {
+        SkipDegenerateTriangles {
+            builder,
+            epsilon,
+            positions: Vec::new(),
+            first_vertex: None,
+            num_skipped: 0,
+        }
+    }
+
+    /// Number of triangles that were skipped so far.
+    pub fn num_skipped_triangles(&self) -> u32 {
+        self.num_skipped
+    }
+
+    fn record_position(&mut self, id: VertexId, position: Point) {
+        let first = *self.first_vertex.get_or_insert(id.offset());
+        if id.offset() < first {
+            return;
+        }
+        let idx = (id.offset() - first) as usize;
+        if idx >= self.positions.len() {
+            self.positions
+                .resize(idx + 1, Point::new(f32::NAN, f32::NAN));
+        }
+        self.positions[idx] = position;
+    }
+
+    fn position(&self, id: VertexId) -> Option<Point> {
+        let first = self.first_vertex?;
+        if id.offset() < first {
+            return None;
+        }
+        self.positions.get((id.offset() - first) as usize).cloned()
+    }
+
+    fn is_degenerate(&self, a: VertexId, b: VertexId, c: VertexId) -> bool {
+        match (self.position(a), self.position(b), self.position(c)) {
+            (Some(a), Some(b), Some(c)) => (b - a).cross(c - a).abs() * 0.5 <= self.epsilon,
+            // Keep triangles we don't know the vertices of.
+            _ => false,
+        }
+    }
+}
+
+impl<B: GeometryBuilder> GeometryBuilder for SkipDegenerateTriangles<B> {
+    fn begin_geometry(&mut self) {
+        self.positions.clear();
+        self.first_vertex = None;
+        self.builder.begin_geometry();
+    }
+
+    fn end_geometry(&mut self) {
+        self.builder.end_geometry();
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        if self.is_degenerate(a, b, c) {
+            self.num_skipped += 1;
+            return;
+        }
+
+        self.builder.add_triangle(a, b, c);
+    }
+
+    fn abort_geometry(&mut self) {
+        self.builder.abort_geometry();
+    }
+}
+
+impl<B: FillGeometryBuilder> FillGeometryBuilder for SkipDegenerateTriangles<B> {
+    fn add_fill_vertex(&mut self, vertex: FillVertex) -> Result<VertexId, GeometryBuilderError> {
+        let position = vertex.position();
+        let id = self.builder.add_fill_vertex(vertex)?;
+        self.record_position(id, position);
+
+        Ok(id)
+    }
+}
+
+impl<B: StrokeGeometryBuilder> StrokeGeometryBuilder for SkipDegenerateTriangles<B> {
+    fn add_stroke_vertex(
+        &mut self,
+        vertex: StrokeVertex,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        let position = vertex.position();
+        let id = self.builder.add_stroke_vertex(vertex)?;
+        self.record_position(id, position);
+
+        Ok(id)
+    }
+
+    #[inline]
+    fn set_stroke_part(&mut self, part: StrokePart) {
+        self.builder.set_stroke_part(part);
+    }
+}
+
+/// A wrapper for fill geometry builders that records the boundary edges of the tessellated
+/// shape.
+///
+/// Boundary edges are the triangle edges that separate the filled area from the outside. They
+/// are recorded as pairs of vertex ids in the order the triangles are produced, oriented
+/// following the winding of their triangle. Edges that are interior to the fill, including
+/// path edges with filled area on both sides, are not recorded.
+///
+/// This can be used to draw outlines, build constrained triangulations or run anti-aliasing
+/// passes that only touch the shape boundaries.
+pub struct FillBoundaryEdges<B> {
+    builder: B,
+    triangles: Vec<[VertexId; 3]>,
+    edges: Vec<(VertexId, VertexId)>,
+}
+
+impl<B: FillGeometryBuilder> FillBoundaryEdges<B> {
+    pub fn new(builder: B) -> Self {
+        FillBoundaryEdges {
+            builder,
+            triangles: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    /// The boundary edges of the geometries that were ended so far.
+    pub fn edges(&self) -> &[(VertexId, VertexId)] {
+        &self.edges
+    }
+
+    /// Consumes the builder and returns the boundary edges.
+    pub fn into_edges(self) -> Vec<(VertexId, VertexId)> {
+        self.edges
+    }
+}
+
+impl<B: FillGeometryBuilder> GeometryBuilder for FillBoundaryEdges<B> {
+    fn begin_geometry(&mut self) {
+        self.triangles.clear();
+        self.builder.begin_geometry();
+    }
+
+    fn end_geometry(&mut self) {
+        let key = |a: VertexId, b: VertexId| (a.0.min(b.0), a.0.max(b.0));
+
+        let mut edge_count: HashMap<(Index, Index), u32> =
+            HashMap::with_capacity(self.triangles.len() * 2);
+        for tri in &self.triangles {
+            for i in 0..3 {
+                *edge_count.entry(key(tri[i], tri[(i + 1) % 3])).or_insert(0) += 1;
+            }
+        }
+
+        for tri in &self.triangles {
+            for i in 0..3 {
+                let (a, b) = (tri[i], tri[(i + 1) % 3]);
+                if edge_count[&key(a, b)] == 1 {
+                    self.edges.push((a, b));
+                }
+            }
+        }
+
+        self.triangles.clear();
+        self.builder.end_geometry();
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        self.triangles.push([a, b, c]);
+        self.builder.add_triangle(a, b, c);
+    }
+
+    fn abort_geometry(&mut self) {
+        self.triangles.clear();
+        self.builder.abort_geometry();
+    }
+}
+
+impl<B: FillGeometryBuilder> FillGeometryBuilder for FillBoundaryEdges<B> {
+    #[inline]
+    fn add_fill_vertex(&mut self, vertex: FillVertex) -> Result<VertexId, GeometryBuilderError> {
+        self.builder.add_fill_vertex(vertex)
+    }
+}
+
+/// A wrapper for fill geometry builders that computes outward normals at the vertices on the
+/// boundary of the tessellated shape.
+///
+/// The normal of a boundary vertex is the normalized average of the outward unit normals of
+/// the boundary edges it belongs to (see `FillBoundaryEdges`). It points away from the filled
+/// area, including on the boundary of holes, independently of the triangle winding. Vertices
+/// inside the fill have a zero normal.
+///
+/// The normals are indexed by vertex id and can be written into a vertex attribute after
+/// the tessellation, for example to extrude the outline of the shape in a vertex shader
+/// for glow or outline effects without stroking the path.
+pub struct FillBoundaryNormals<B> {
+    builder: B,
+    positions: Vec<(VertexId, Point)>,
+    triangles: Vec<[VertexId; 3]>,
+    normals: Vec<Vector>,
+}
+
+impl<B: FillGeometryBuilder> FillBoundaryNormals<B> {
+    pub fn new(builder: B) -> Self {
+        FillBoundaryNormals {
+            builder,
+            positions: Vec::new(),
+            triangles: Vec::new(),
+            normals: Vec::new(),
+        }
+    }
+
+    /// The normal at a given vertex.
+    ///
+    /// Returns a zero vector for vertices inside the fill and unknown vertices.
+    pub fn normal(&self, id: VertexId) -> Vector {
+        self.normals
+            .get(id.to_usize())
+            .cloned()
+            .unwrap_or_else(Vector::zero)
+    }
+
+    /// The normals of the geometries that were ended so far, indexed by vertex id.
+    pub fn normals(&self) -> &[Vector] {
+        &self.normals
+    }
+
+    /// Consumes the builder and returns the normals, indexed by vertex id.
+    pub fn into_normals(self) -> Vec<Vector> {
+        self.normals
+    }
+}
+
+impl<B: FillGeometryBuilder> GeometryBuilder for FillBoundaryNormals<B> {
+    fn begin_geometry(&mut self) {
+        self.positions.clear();
+        self.triangles.clear();
+        self.builder.begin_geometry();
+    }
+
+    fn end_geometry(&mut self) {
+        let key = |a: VertexId, b: VertexId| (a.0.min(b.0), a.0.max(b.0));
+
+        let mut edge_count: HashMap<(Index, Index), u32> =
+            HashMap::with_capacity(self.triangles.len() * 2);
+        for tri in &self.triangles {
+            for i in 0..3 {
+                *edge_count.entry(key(tri[i], tri[(i + 1) % 3])).or_insert(0) += 1;
+            }
+        }
+
+        let positions: HashMap<VertexId, Point> = self.positions.iter().cloned().collect();
+        let mut sums: HashMap<VertexId, Vector> = HashMap::new();
+        for tri in &self.triangles {
+            for i in 0..3 {
+                let (a, b, c) = (tri[i], tri[(i + 1) % 3], tri[(i + 2) % 3]);
+                if edge_count[&key(a, b)] != 1 {
+                    continue;
+                }
+
+                let (pa, pb, pc) = (positions[&a], positions[&b], positions[&c]);
+                let edge = pb - pa;
+                let mut n = Vector::new(edge.y, -edge.x);
+                let side = n.dot(pc - pa);
+                if side == 0.0 || edge.square_length() == 0.0 {
+                    // Degenerate triangle, the outward direction is unknown.
+                    continue;
+                }
+                if side > 0.0 {
+                    n = -n;
+                }
+                let n = n.normalize();
+
+                *sums.entry(a).or_insert_with(Vector::zero) += n;
+                *sums.entry(b).or_insert_with(Vector::zero) += n;
+            }
+        }
+
+        for (id, sum) in sums {
+            let idx = id.to_usize();
+            if self.normals.len() <= idx {
+                self.normals.resize(idx + 1, Vector::zero());
+            }
+            if sum.square_length() > 0.0 {
+                self.normals[idx] = sum.normalize();
+            }
+        }
+
+        self.positions.clear();
+        self.triangles.clear();
+        self.builder.end_geometry();
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        self.triangles.push([a, b, c]);
+        self.builder.add_triangle(a, b, c);
+    }
+
+    fn abort_geometry(&mut self) {
+        self.positions.clear();
+        self.triangles.clear();
+        self.builder.abort_geometry();
+    }
+}
+
+impl<B: FillGeometryBuilder> FillGeometryBuilder for FillBoundaryNormals<B> {
+    #[inline]
+    fn add_fill_vertex(&mut self, vertex: FillVertex) -> Result<VertexId, GeometryBuilderError> {
+        let position = vertex.position();
+        let id = self.builder.add_fill_vertex(vertex)?;
+        self.positions.push((id, position));
+
+        Ok(id)
+    }
 }
 
 /// A trait specifying how to create vertex values.
@@ -420,6 +1394,62 @@ where
     }
 }
 
+/// A vertex containing a position and a color.
+///
+/// Produced by the `SolidColor` and `ColorAttribute` vertex constructors.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct ColoredVertex {
+    pub position: [f32; 2],
+    pub color: [f32; 4],
+}
+
+/// A vertex constructor that assigns the same color to all vertices.
+pub struct SolidColor(pub Color);
+
+impl FillVertexConstructor<ColoredVertex> for SolidColor {
+    fn new_vertex(&mut self, vertex: FillVertex) -> ColoredVertex {
+        ColoredVertex {
+            position: vertex.position().to_array(),
+            color: self.0.to_array(),
+        }
+    }
+}
+
+impl StrokeVertexConstructor<ColoredVertex> for SolidColor {
+    fn new_vertex(&mut self, vertex: StrokeVertex) -> ColoredVertex {
+        ColoredVertex {
+            position: vertex.position().to_array(),
+            color: self.0.to_array(),
+        }
+    }
+}
+
+/// A vertex constructor that reads the color from four consecutive custom attributes
+/// starting at the provided index.
+///
+/// See the [`color`](../color/index.html) module.
+pub struct ColorAttribute(pub AttributeIndex);
+
+impl FillVertexConstructor<ColoredVertex> for ColorAttribute {
+    fn new_vertex(&mut self, mut vertex: FillVertex) -> ColoredVertex {
+        ColoredVertex {
+            position: vertex.position().to_array(),
+            color: Color::from_attributes(vertex.interpolated_attributes(), self.0).to_array(),
+        }
+    }
+}
+
+impl StrokeVertexConstructor<ColoredVertex> for ColorAttribute {
+    fn new_vertex(&mut self, mut vertex: StrokeVertex) -> ColoredVertex {
+        ColoredVertex {
+            position: vertex.position().to_array(),
+            color: Color::from_attributes(vertex.interpolated_attributes(), self.0).to_array(),
+        }
+    }
+}
+
 /// A `BuffersBuilder` that takes the actual vertex type as input.
 pub type SimpleBuffersBuilder<'l> = BuffersBuilder<'l, Point, u16, Positions>;
 
@@ -506,6 +1536,156 @@ where
     }
 }
 
+impl<'l, OutputVertex, OutputIndex, Ctor> LineGeometryBuilder
+    for BuffersBuilder<'l, OutputVertex, OutputIndex, Ctor>
+where
+    OutputVertex: 'l,
+    OutputIndex: Add + From<VertexId> + MaxIndex,
+    Ctor: StrokeVertexConstructor<OutputVertex>,
+{
+    fn add_line(&mut self, a: VertexId, b: VertexId) {
+        debug_assert!(a != b);
+        debug_assert!(a != VertexId::INVALID);
+        debug_assert!(b != VertexId::INVALID);
+        self.buffers.indices.push((a + self.vertex_offset).into());
+        self.buffers.indices.push((b + self.vertex_offset).into());
+    }
+}
+
+/// Computes the custom attributes of tessellated vertices from their sources.
+///
+/// This is an alternative to reading the interpolated attributes in a custom
+/// vertex constructor: the output only needs to store each vertex's `VertexSource`
+/// (see `StrokeVertex::source` and `FillVertex::sources`), and the attributes are
+/// resolved afterwards from a table of per-endpoint attributes, for example an
+/// `AttributeSlice`.
+///
+/// Appends `attributes.num_attributes()` values per source to `output`. Attributes
+/// of sources on an edge are linearly interpolated between the edge's endpoints.
+pub fn resolve_vertex_attributes(
+    sources: impl IntoIterator<Item = VertexSource>,
+    attributes: &dyn AttributeStore,
+    output: &mut Vec<f32>,
+) {
+    let sources = sources.into_iter();
+    output.reserve(sources.size_hint().0 * attributes.num_attributes());
+    for src in sources {
+        match src {
+            VertexSource::Endpoint { id } => {
+                output.extend_from_slice(attributes.get(id));
+            }
+            VertexSource::Edge { from, to, t } => {
+                let a = attributes.get(from);
+                let b = attributes.get(to);
+                output.extend(a.iter().zip(b).map(|(a, b)| lerp_attribute(*a, *b, t)));
+            }
+        }
+    }
+}
+
+/// A geometry builder that writes a flat, non-indexed list of triangles.
+///
+/// Every three vertices of the output form a triangle, and vertices shared by several
+/// triangles are duplicated. This is the layout expected by some minimal rendering
+/// backends, and by consumers that work on triangle soups rather than meshes.
+///
+/// The vertices are produced by a vertex constructor, like with `BuffersBuilder`. They are
+/// stored in a scratch buffer until the end of the geometry, so a builder can be reused
+/// across tessellations to avoid allocations.
+///
+/// ```
+/// use lyon_tessellation::geometry_builder::{Positions, TriangleListBuilder};
+/// use lyon_tessellation::math::{point, Box2D, Point};
+/// use lyon_tessellation::{FillOptions, FillTessellator};
+///
+/// let mut triangles: Vec<Point> = Vec::new();
+/// FillTessellator::new().tessellate_rectangle(
+///     &Box2D { min: point(0.0, 0.0), max: point(1.0, 1.0) },
+///     &FillOptions::default(),
+///     &mut TriangleListBuilder::new(&mut triangles, Positions),
+/// ).unwrap();
+///
+/// assert_eq!(triangles.len(), 6);
+/// ```
+pub struct TriangleListBuilder<'l, OutputVertex, Ctor> {
+    output: &'l mut Vec<OutputVertex>,
+    vertices: Vec<OutputVertex>,
+    first_vertex: usize,
+    vertex_constructor: Ctor,
+}
+
+impl<'l, OutputVertex, Ctor> TriangleListBuilder<'l, OutputVertex, Ctor> {
+    pub fn new(output: &'l mut Vec<OutputVertex>, ctor: Ctor) -> Self {
+        let first_vertex = output.len();
+        TriangleListBuilder {
+            output,
+            vertices: Vec::new(),
+            first_vertex,
+            vertex_constructor: ctor,
+        }
+    }
+
+    fn push_vertex(&mut self, vertex: OutputVertex) -> Result<VertexId, GeometryBuilderError> {
+        if self.vertices.len() >= u32::MAX as usize {
+            return Err(GeometryBuilderError::TooManyVertices);
+        }
+        self.vertices.push(vertex);
+        Ok(VertexId((self.vertices.len() - 1) as Index))
+    }
+}
+
+impl<'l, OutputVertex: Clone, Ctor> GeometryBuilder
+    for TriangleListBuilder<'l, OutputVertex, Ctor>
+{
+    fn begin_geometry(&mut self) {
+        self.vertices.clear();
+        self.first_vertex = self.output.len();
+    }
+
+    fn end_geometry(&mut self) {
+        self.vertices.clear();
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        debug_assert!(a != b);
+        debug_assert!(a != c);
+        debug_assert!(b != c);
+        for id in [a, b, c] {
+            self.output.push(self.vertices[id.to_usize()].clone());
+        }
+    }
+
+    fn abort_geometry(&mut self) {
+        self.vertices.clear();
+        self.output.truncate(self.first_vertex);
+    }
+}
+
+impl<'l, OutputVertex: Clone, Ctor> FillGeometryBuilder
+    for TriangleListBuilder<'l, OutputVertex, Ctor>
+where
+    Ctor: FillVertexConstructor<OutputVertex>,
+{
+    fn add_fill_vertex(&mut self, vertex: FillVertex) -> Result<VertexId, GeometryBuilderError> {
+        let vertex = self.vertex_constructor.new_vertex(vertex);
+        self.push_vertex(vertex)
+    }
+}
+
+impl<'l, OutputVertex: Clone, Ctor> StrokeGeometryBuilder
+    for TriangleListBuilder<'l, OutputVertex, Ctor>
+where
+    Ctor: StrokeVertexConstructor<OutputVertex>,
+{
+    fn add_stroke_vertex(
+        &mut self,
+        vertex: StrokeVertex,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        let vertex = self.vertex_constructor.new_vertex(vertex);
+        self.push_vertex(vertex)
+    }
+}
+
 /// A geometry builder that does not output any geometry.
 ///
 /// Mostly useful for testing.
@@ -593,3 +1773,420 @@ impl MaxIndex for usize {
 impl MaxIndex for isize {
     const MAX: usize = std::u32::MAX as usize;
 }
+
+#[test]
+fn color_attribute_constructor() {
+    use crate::math::point;
+    use crate::path::builder::PathBuilder;
+    use crate::path::{Path, Winding};
+    use crate::{FillOptions, FillTessellator};
+
+    let red = Color::new(1.0, 0.0, 0.0, 1.0);
+    let mut attributes = [0.0; 4];
+    red.write_attributes(&mut attributes, 0);
+
+    let mut builder = Path::builder_with_attributes(4);
+    builder.add_circle(point(0.0, 0.0), 10.0, Winding::Positive, &attributes);
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<ColoredVertex, u16> = VertexBuffers::new();
+    FillTessellator::new()
+        .tessellate_path(
+            &path,
+            &FillOptions::default(),
+            &mut BuffersBuilder::new(&mut buffers, ColorAttribute(0)),
+        )
+        .unwrap();
+
+    assert!(!buffers.vertices.is_empty());
+    for vertex in &buffers.vertices {
+        assert_eq!(vertex.color, red.to_array());
+    }
+}
+
+#[test]
+fn skip_degenerate_triangles() {
+    use crate::math::point;
+    use crate::path::Path;
+    use crate::{LineJoin, StrokeOptions, StrokeTessellator};
+
+    // A stroke that folds back on itself produces collinear triangles.
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(20.0, 0.0));
+    builder.line_to(point(5.0, 0.0));
+    builder.end(false);
+    let path = builder.build();
+    let options = StrokeOptions::default().with_line_join(LineJoin::Bevel);
+
+    let area = |buffers: &VertexBuffers<Point, u16>, tri: &[u16]| {
+        let a = buffers.vertices[tri[0] as usize];
+        let b = buffers.vertices[tri[1] as usize];
+        let c = buffers.vertices[tri[2] as usize];
+        (b - a).cross(c - a).abs() * 0.5
+    };
+
+    let mut all: VertexBuffers<Point, u16> = VertexBuffers::new();
+    StrokeTessellator::new()
+        .tessellate_path(
+            &path,
+            &options,
+            &mut BuffersBuilder::new(&mut all, Positions),
+        )
+        .unwrap();
+    let num_degenerate = all
+        .indices
+        .chunks(3)
+        .filter(|tri| area(&all, tri) <= 0.001)
+        .count();
+    assert!(num_degenerate > 0);
+
+    let mut filtered: VertexBuffers<Point, u16> = VertexBuffers::new();
+    let mut output =
+        SkipDegenerateTriangles::new(BuffersBuilder::new(&mut filtered, Positions), 0.001);
+    StrokeTessellator::new()
+        .tessellate_path(&path, &options, &mut output)
+        .unwrap();
+    assert_eq!(output.num_skipped_triangles() as usize, num_degenerate);
+
+    assert_eq!(
+        filtered.indices.len(),
+        all.indices.len() - num_degenerate * 3
+    );
+    for tri in filtered.indices.chunks(3) {
+        assert!(area(&filtered, tri) > 0.001);
+    }
+}
+
+#[test]
+fn triangle_list_builder() {
+    use crate::math::point;
+    use crate::path::Path;
+    use crate::{StrokeOptions, StrokeTessellator};
+
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.end(false);
+    let path = builder.build();
+
+    let mut tessellator = StrokeTessellator::new();
+    let options = StrokeOptions::default();
+    let mut indexed: VertexBuffers<Point, u16> = VertexBuffers::new();
+    tessellator
+        .tessellate_path(&path, &options, &mut simple_builder(&mut indexed))
+        .unwrap();
+
+    // Some existing content is preserved.
+    let mut triangles = vec![point(-1.0, -1.0)];
+    let mut builder = TriangleListBuilder::new(&mut triangles, Positions);
+    tessellator
+        .tessellate_path(&path, &options, &mut builder)
+        .unwrap();
+    tessellator
+        .tessellate_path(&path, &options, &mut builder)
+        .unwrap();
+
+    let expected = indexed.to_triangle_list();
+    assert_eq!(expected.len(), indexed.indices.len());
+    assert_eq!(triangles.len(), 1 + expected.len() * 2);
+    assert_eq!(&triangles[1..expected.len() + 1], &expected[..]);
+    assert_eq!(&triangles[expected.len() + 1..], &expected[..]);
+}
+
+#[test]
+fn triangles_with_adjacency() {
+    let buffers: VertexBuffers<(), u16> = VertexBuffers {
+        vertices: vec![(); 4],
+        indices: vec![0, 1, 2, 0, 2, 3],
+    };
+
+    assert_eq!(
+        buffers.triangles_with_adjacency(),
+        vec![0, 2, 1, 0, 2, 3, 0, 1, 2, 0, 3, 2],
+    );
+}
+
+#[test]
+fn fill_boundary_edges() {
+    use crate::math::point;
+    use crate::path::{Path, Winding};
+    use crate::{FillOptions, FillTessellator};
+
+    // A square with a square hole.
+    let mut builder = Path::builder();
+    builder.add_rectangle(
+        &crate::math::Box2D::new(point(0.0, 0.0), point(10.0, 10.0)),
+        Winding::Positive,
+    );
+    builder.add_rectangle(
+        &crate::math::Box2D::new(point(3.0, 3.0), point(7.0, 7.0)),
+        Winding::Negative,
+    );
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    let mut output = FillBoundaryEdges::new(BuffersBuilder::new(&mut buffers, Positions));
+    FillTessellator::new()
+        .tessellate_path(&path, &FillOptions::default(), &mut output)
+        .unwrap();
+    let edges = output.into_edges();
+
+    assert_eq!(edges.len(), 8);
+    for (a, b) in edges {
+        let a = buffers.vertices[a.to_usize()];
+        let b = buffers.vertices[b.to_usize()];
+        // All boundary edges are axis-aligned sides of one of the two squares.
+        assert!(a.x == b.x || a.y == b.y);
+        assert!((a - b).length() == 10.0 || (a - b).length() == 4.0);
+    }
+}
+
+#[test]
+fn fill_boundary_normals() {
+    use crate::math::point;
+    use crate::path::{Path, Winding};
+    use crate::{FillOptions, FillTessellator};
+
+    // A square with a square hole, subdivided so that there are vertices along the sides
+    // and inside the fill.
+    let mut builder = Path::builder();
+    builder.add_rectangle(
+        &crate::math::Box2D::new(point(0.0, 0.0), point(10.0, 10.0)),
+        Winding::Positive,
+    );
+    builder.add_rectangle(
+        &crate::math::Box2D::new(point(3.0, 3.0), point(7.0, 7.0)),
+        Winding::Negative,
+    );
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    let mut output = FillBoundaryNormals::new(BuffersBuilder::new(&mut buffers, Positions));
+    FillTessellator::new()
+        .tessellate_path(
+            &path,
+            &FillOptions::default().with_max_triangle_edge_length(2.0),
+            &mut output,
+        )
+        .unwrap();
+    let normals = output.into_normals();
+
+    let center = point(5.0, 5.0);
+    let on_side = |x: f32| x == 0.0 || x == 3.0 || x == 7.0 || x == 10.0;
+    let mut interior = 0;
+    for (i, p) in buffers.vertices.iter().enumerate() {
+        let n = normals.get(i).cloned().unwrap_or_else(Vector::zero);
+        let outer = p.x == 0.0 || p.x == 10.0 || p.y == 0.0 || p.y == 10.0;
+        let inner = (3.0..=7.0).contains(&p.x)
+            && (3.0..=7.0).contains(&p.y)
+            && (on_side(p.x) || on_side(p.y));
+        if !outer && !inner {
+            assert_eq!(n, Vector::zero(), "{:?}", p);
+            interior += 1;
+            continue;
+        }
+
+        assert!((n.length() - 1.0).abs() < 1e-5, "{:?} {:?}", p, n);
+        // Away from the filled area: away from the center on the outer square, toward it
+        // inside of the hole.
+        let d = (*p - center).dot(n);
+        if outer {
+            assert!(d > 0.0, "{:?} {:?}", p, n);
+        } else {
+            assert!(d < 0.0, "{:?} {:?}", p, n);
+        }
+
+        let corner = on_side(p.x) && on_side(p.y);
+        if corner {
+            // Average of the two sides.
+            assert!((n.x.abs() - n.y.abs()).abs() < 1e-5, "{:?} {:?}", p, n);
+        } else {
+            assert!(n.x == 0.0 || n.y == 0.0, "{:?} {:?}", p, n);
+        }
+    }
+
+    assert!(interior > 0);
+}
+
+#[test]
+fn attribute_gradients() {
+    use crate::math::{point, vector};
+
+    // Attribute 0 is 2x + y, attribute 1 is constant.
+    let f = |p: Point| [2.0 * p.x + p.y, 3.0];
+    let positions = [
+        point(0.0, 0.0),
+        point(10.0, 0.0),
+        point(10.0, 5.0),
+        point(0.0, 5.0),
+    ];
+    let buffers: VertexBuffers<(Point, [f32; 2]), u16> = VertexBuffers {
+        vertices: positions.iter().map(|p| (*p, f(*p))).collect(),
+        indices: vec![0, 1, 2, 0, 2, 3],
+    };
+
+    let gradients = buffers.attribute_gradients(2, |v| v.0, |v, a| v.1[a]);
+
+    assert_eq!(gradients.len(), 8);
+    for vertex in 0..4 {
+        assert!((gradients[vertex * 2] - vector(2.0, 1.0)).length() < 0.0001);
+        assert_eq!(gradients[vertex * 2 + 1], vector(0.0, 0.0));
+    }
+}
+
+#[test]
+fn resolve_attributes_from_sources() {
+    use crate::math::point;
+    use crate::path::traits::{Build, PathBuilder};
+    use crate::path::AttributeSlice;
+    use crate::{StrokeOptions, StrokeTessellator};
+
+    let mut buffers: VertexBuffers<([f32; 2], VertexSource), u16> = VertexBuffers::new();
+    let mut output = BuffersBuilder::new(&mut buffers, |mut v: StrokeVertex| {
+        let src = v.source();
+        let a = v.interpolated_attributes();
+        ([a[0], a[1]], src)
+    });
+    let options = StrokeOptions::tolerance(0.1);
+    let mut tessellator = StrokeTessellator::new();
+    let mut builder = tessellator.builder_with_attributes(2, &options, &mut output);
+    builder.begin(point(0.0, 0.0), &[0.0, 1.0]);
+    builder.quadratic_bezier_to(point(10.0, 0.0), point(10.0, 10.0), &[1.0, 2.0]);
+    builder.line_to(point(0.0, 10.0), &[2.0, 4.0]);
+    builder.end(true);
+    builder.build().unwrap();
+
+    // The per-endpoint table, indexed by endpoint id.
+    let table = [0.0, 1.0, 1.0, 2.0, 2.0, 4.0];
+    let mut resolved = Vec::new();
+    resolve_vertex_attributes(
+        buffers.vertices.iter().map(|v| v.1),
+        &AttributeSlice::new(&table, 2),
+        &mut resolved,
+    );
+
+    assert_eq!(resolved.len(), buffers.vertices.len() * 2);
+    assert!(buffers.vertices.iter().any(|v| v.1.is_edge()));
+    for (vertex, attributes) in buffers.vertices.iter().zip(resolved.chunks(2)) {
+        assert!((vertex.0[0] - attributes[0]).abs() < 0.0001);
+        assert!((vertex.0[1] - attributes[1]).abs() < 0.0001);
+    }
+}
+
+#[test]
+fn append_transformed() {
+    use crate::math::{point, Box2D};
+    use crate::{FillOptions, FillTessellator};
+
+    let mut cached: VertexBuffers<Point, u16> = VertexBuffers::new();
+    FillTessellator::new()
+        .tessellate_rectangle(
+            &Box2D {
+                min: point(0.0, 0.0),
+                max: point(1.0, 2.0),
+            },
+            &FillOptions::default(),
+            &mut simple_builder(&mut cached),
+        )
+        .unwrap();
+
+    let transform = Transform::scale(2.0, 3.0).then_translate(crate::math::vector(10.0, 0.0));
+    let mut output: VertexBuffers<Point, u16> = VertexBuffers::new();
+    cached.append_transformed(&transform, &mut output).unwrap();
+    cached.append_transformed(&transform, &mut output).unwrap();
+
+    let n = cached.vertices.len();
+    assert_eq!(output.vertices.len(), n * 2);
+    assert_eq!(output.indices.len(), cached.indices.len() * 2);
+    for (i, p) in cached.vertices.iter().enumerate() {
+        assert_eq!(output.vertices[i], transform.transform_point(*p));
+        assert_eq!(output.vertices[n + i], transform.transform_point(*p));
+    }
+    for (i, index) in cached.indices.iter().enumerate() {
+        assert_eq!(output.indices[i], *index);
+        assert_eq!(output.indices[cached.indices.len() + i], *index + n as u16);
+    }
+
+    // Custom vertex types go through the position accessor.
+    let colored: VertexBuffers<(Point, u32), u16> = VertexBuffers {
+        vertices: cached.vertices.iter().map(|p| (*p, 7)).collect(),
+        indices: cached.indices.clone(),
+    };
+    let mut output = VertexBuffers::new();
+    colored
+        .append_transformed_with(&transform, &mut output, |v| &mut v.0)
+        .unwrap();
+    assert_eq!(
+        output.vertices[1],
+        (transform.transform_point(cached.vertices[1]), 7)
+    );
+}
+
+#[test]
+fn stroke_quads() {
+    use crate::math::point;
+    use crate::path::Path;
+    use crate::{LineJoin, StrokeOptions, StrokeTessellator};
+
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.end(false);
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    let mut quads = Vec::new();
+    let mut output = StrokeQuads::new(simple_builder(&mut buffers), |quad: &StrokeQuad| {
+        quads.push(*quad)
+    });
+    StrokeTessellator::new()
+        .tessellate_path(
+            &path,
+            &StrokeOptions::default()
+                .with_line_width(2.0)
+                .with_line_join(LineJoin::Round),
+            &mut output,
+        )
+        .unwrap();
+    drop(output);
+    quads.sort_by(|a, b| a.from_advancement.partial_cmp(&b.from_advancement).unwrap());
+
+    // One quad per segment, the round join is not part of any quad.
+    assert_eq!(quads.len(), 2);
+    assert_eq!(quads[0].from, point(0.0, 0.0));
+    assert_eq!(quads[0].to, point(10.0, 0.0));
+    assert_eq!(quads[1].from, point(10.0, 0.0));
+    assert_eq!(quads[1].to, point(10.0, 10.0));
+    assert_eq!(quads[1].from_advancement, 10.0);
+    assert_eq!(quads[1].to_advancement, 20.0);
+    assert_eq!(quads[0].from_half_width, 1.0);
+
+    for quad in &quads {
+        // The vertices are in order around the quad, which is convex.
+        let p: Vec<Point> = quad
+            .vertices
+            .iter()
+            .map(|v| buffers.vertices[v.to_usize()])
+            .collect();
+        for i in 0..4 {
+            let e0 = p[(i + 1) % 4] - p[i];
+            let e1 = p[(i + 2) % 4] - p[(i + 1) % 4];
+            assert!(e0.cross(e1) < 0.0, "{:?}", p);
+        }
+
+        // Both triangles are in the index buffer, with the same orientation.
+        let has_triangle = |a: usize, b: usize, c: usize| {
+            let [a, b, c] = [a, b, c].map(|i| quad.vertices[i].0 as u16);
+            buffers
+                .indices
+                .chunks(3)
+                .any(|t| t == [a, b, c] || t == [b, c, a] || t == [c, a, b])
+        };
+        assert!(has_triangle(0, 1, 3));
+        assert!(has_triangle(1, 2, 3));
+    }
+}
@@ -0,0 +1,512 @@
+//! Export tessellated meshes to glTF 2.0.
+//!
+//! This module is only available with the `gltf` feature.
+//!
+//! [`GltfExporter`](struct.GltfExporter.html) writes the content of a `VertexBuffers` into the
+//! buffers and accessors of a glTF document containing a single triangle mesh, so that
+//! tessellated vector art can be loaded into 3D engines and content creation tools. The
+//! document can be produced as a binary `.glb` file or as a `.gltf` JSON file referring to a
+//! separate `.bin` file.
+//!
+//! Positions are required and are exported as `POSITION` with a zero z coordinate. glTF
+//! doesn't allow non-finite values: they are written to the buffer as is but are left out
+//! of the bounds of the accessor so that the JSON stays valid. Other
+//! attributes are chosen by the application and are exported as float scalars or vectors.
+//! Their names must follow the glTF conventions: standard attributes such as `TEXCOORD_0` or
+//! `COLOR_0`, or application-specific ones starting with an underscore.
+//!
+//! glTF uses a right-handed coordinate system with the y axis pointing up. If the geometry was
+//! produced with the y axis pointing down, the position callback can flip it, in which case the
+//! winding of the triangles should be inverted as well so that they are not culled. The
+//! simplest way to get a consistent result is to tessellate with
+//! `FillOptions::with_y_axis_direction(YAxisDirection::Up)` (or the `StrokeOptions`
+//! equivalent), or to request a winding explicitly with `with_triangle_winding`.
+//!
+//! # Example
+//!
+//! ```
+//! use lyon_tessellation::geometry_builder::simple_builder;
+//! use lyon_tessellation::gltf::GltfExporter;
+//! use lyon_tessellation::math::{point, Box2D, Point};
+//! use lyon_tessellation::{FillOptions, FillTessellator, VertexBuffers};
+//!
+//! let rect = Box2D { min: point(0.0, 0.0), max: point(4.0, 2.0) };
+//! let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+//! FillTessellator::new().tessellate_rectangle(
+//!     &rect,
+//!     &FillOptions::default(),
+//!     &mut simple_builder(&mut buffers),
+//! ).unwrap();
+//!
+//! let glb = GltfExporter::new(|p: &Point| *p)
+//!     // Texture coordinates that map the rectangle to the whole texture.
+//!     .with_attribute("TEXCOORD_0", 2, |p, uv| {
+//!         uv[0] = p.x / 4.0;
+//!         uv[1] = p.y / 2.0;
+//!     })
+//!     .to_glb(&buffers);
+//!
+//! assert_eq!(&glb[0..4], b"glTF");
+//! // std::fs::write("rect.glb", glb).unwrap();
+//! ```
+
+use crate::geometry_builder::VertexBuffers;
+use crate::math::Point;
+use crate::VertexId;
+
+use std::fmt::Write;
+
+const GLB_MAGIC: u32 = 0x4654_6C67;
+const GLB_VERSION: u32 = 2;
+const CHUNK_JSON: u32 = 0x4E4F_534A;
+const CHUNK_BIN: u32 = 0x004E_4942;
+
+const COMPONENT_UNSIGNED_SHORT: u32 = 5123;
+const COMPONENT_UNSIGNED_INT: u32 = 5125;
+const COMPONENT_FLOAT: u32 = 5126;
+
+const TARGET_ARRAY_BUFFER: u32 = 34962;
+const TARGET_ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+type PositionFn<'l, V> = Box<dyn Fn(&V) -> Point + 'l>;
+type AttributeFn<'l, V> = Box<dyn Fn(&V, &mut [f32]) + 'l>;
+
+struct Attribute<'l, V> {
+    name: String,
+    components: usize,
+    read: AttributeFn<'l, V>,
+}
+
+/// Writes vertex and index buffers as a glTF 2.0 mesh.
+///
+/// See the [module documentation](index.html).
+pub struct GltfExporter<'l, V> {
+    position: PositionFn<'l, V>,
+    attributes: Vec<Attribute<'l, V>>,
+    name: Option<String>,
+}
+
+/// A glTF document and the binary buffer it refers to, see
+/// [`GltfExporter::to_gltf`](struct.GltfExporter.html#method.to_gltf).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GltfFiles {
+    /// The content of the `.gltf` file.
+    pub json: String,
+    /// The content of the `.bin` file.
+    pub bin: Vec<u8>,
+}
+
+impl<'l, V> GltfExporter<'l, V> {
+    /// Creates an exporter that reads the position of each vertex with the provided callback.
+    pub fn new(position: impl Fn(&V) -> Point + 'l) -> Self {
+        GltfExporter {
+            position: Box::new(position),
+            attributes: Vec::new(),
+            name: None,
+        }
+    }
+
+    /// Exports an additional vertex attribute.
+    ///
+    /// The attribute has between one and four float components, which the callback writes into
+    /// the provided slice.
+    ///
+    /// Panics if the name is not an application-specific name (starting with an underscore) or
+    /// one of the standard attributes that can be stored as floats with the given number of
+    /// components: `NORMAL` (3), `TANGENT` (4), `TEXCOORD_n` (2), `COLOR_n` (3 or 4) or
+    /// `WEIGHTS_n` (4).
+    pub fn with_attribute(
+        mut self,
+        name: &str,
+        components: usize,
+        read: impl Fn(&V, &mut [f32]) + 'l,
+    ) -> Self {
+        assert!((1..=4).contains(&components));
+        assert!(
+            is_valid_attribute(name, components),
+            "Invalid glTF attribute {:?} with {} components",
+            name,
+            components
+        );
+        self.attributes.push(Attribute {
+            name: name.to_string(),
+            components,
+            read: Box::new(read),
+        });
+
+        self
+    }
+
+    /// Sets the name of the exported mesh.
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+
+        self
+    }
+
+    /// Produces the content of a binary `.glb` file.
+    pub fn to_glb<I>(&self, buffers: &VertexBuffers<V, I>) -> Vec<u8>
+    where
+        I: Copy + Into<VertexId>,
+    {
+        let (json, bin) = self.export(buffers, None);
+        let mut json = json.into_bytes();
+        while json.len() % 4 != 0 {
+            json.push(b' ');
+        }
+
+        let bin_chunk_size = if bin.is_empty() { 0 } else { 8 + bin.len() };
+        let total_size = 12 + 8 + json.len() + bin_chunk_size;
+
+        let mut glb = Vec::with_capacity(total_size);
+        write_u32(&mut glb, GLB_MAGIC);
+        write_u32(&mut glb, GLB_VERSION);
+        write_u32(&mut glb, total_size as u32);
+        write_u32(&mut glb, json.len() as u32);
+        write_u32(&mut glb, CHUNK_JSON);
+        glb.extend_from_slice(&json);
+        if !bin.is_empty() {
+            write_u32(&mut glb, bin.len() as u32);
+            write_u32(&mut glb, CHUNK_BIN);
+            glb.extend_from_slice(&bin);
+        }
+
+        glb
+    }
+
+    /// Produces the content of a `.gltf` file and of the `.bin` file it refers to.
+    ///
+    /// `bin_uri` is the location of the `.bin` file relative to the `.gltf` file.
+    pub fn to_gltf<I>(&self, buffers: &VertexBuffers<V, I>, bin_uri: &str) -> GltfFiles
+    where
+        I: Copy + Into<VertexId>,
+    {
+        let (json, bin) = self.export(buffers, Some(bin_uri));
+
+        GltfFiles { json, bin }
+    }
+
+    fn export<I>(&self, buffers: &VertexBuffers<V, I>, bin_uri: Option<&str>) -> (String, Vec<u8>)
+    where
+        I: Copy + Into<VertexId>,
+    {
+        let mut json = String::new();
+        json.push_str("{\"asset\":{\"version\":\"2.0\",\"generator\":\"lyon\"}");
+
+        if buffers.indices.is_empty() {
+            json.push_str(",\"scene\":0,\"scenes\":[{\"nodes\":[]}]}");
+            return (json, Vec::new());
+        }
+
+        let mut bin = Vec::new();
+        let mut views = Vec::new();
+        let mut accessors = Vec::new();
+        let count = buffers.vertices.len();
+
+        // Vertex attributes come first so that all of the float data is aligned.
+        let mut min = [f32::MAX; 2];
+        let mut max = [f32::MIN; 2];
+        let offset = bin.len();
+        for vertex in &buffers.vertices {
+            let p = (self.position)(vertex);
+            // Non-finite values can't be represented in JSON, keep them out of the bounds.
+            if p.x.is_finite() {
+                min[0] = min[0].min(p.x);
+                max[0] = max[0].max(p.x);
+            }
+            if p.y.is_finite() {
+                min[1] = min[1].min(p.y);
+                max[1] = max[1].max(p.y);
+            }
+            write_f32(&mut bin, p.x);
+            write_f32(&mut bin, p.y);
+            write_f32(&mut bin, 0.0);
+        }
+        for i in 0..2 {
+            if min[i] > max[i] {
+                min[i] = 0.0;
+                max[i] = 0.0;
+            }
+        }
+        views.push(buffer_view(offset, bin.len() - offset, TARGET_ARRAY_BUFFER));
+        accessors.push(format!(
+            "{{\"bufferView\":0,\"componentType\":{},\"count\":{},\"type\":\"VEC3\",\
+             \"min\":[{:?},{:?},0.0],\"max\":[{:?},{:?},0.0]}}",
+            COMPONENT_FLOAT, count, min[0], min[1], max[0], max[1],
+        ));
+
+        let mut primitive_attributes = vec!["\"POSITION\":0".to_string()];
+        let mut values = [0.0; 4];
+        for attribute in &self.attributes {
+            let offset = bin.len();
+            for vertex in &buffers.vertices {
+                let values = &mut values[..attribute.components];
+                for value in values.iter_mut() {
+                    *value = 0.0;
+                }
+                (attribute.read)(vertex, values);
+                for value in values {
+                    write_f32(&mut bin, *value);
+                }
+            }
+            views.push(buffer_view(offset, bin.len() - offset, TARGET_ARRAY_BUFFER));
+            accessors.push(format!(
+                "{{\"bufferView\":{},\"componentType\":{},\"count\":{},\"type\":\"{}\"}}",
+                views.len() - 1,
+                COMPONENT_FLOAT,
+                count,
+                accessor_type(attribute.components),
+            ));
+            primitive_attributes.push(format!(
+                "\"{}\":{}",
+                escape(&attribute.name),
+                accessors.len() - 1
+            ));
+        }
+
+        // Use 16 bits indices when possible. The maximum value of the index type is reserved
+        // for primitive restart, so it can't be used as an index.
+        let offset = bin.len();
+        let component_type = if count <= u16::MAX as usize {
+            for index in &buffers.indices {
+                bin.extend_from_slice(&((*index).into().0 as u16).to_le_bytes());
+            }
+            COMPONENT_UNSIGNED_SHORT
+        } else {
+            for index in &buffers.indices {
+                write_u32(&mut bin, (*index).into().0);
+            }
+            COMPONENT_UNSIGNED_INT
+        };
+        views.push(buffer_view(
+            offset,
+            bin.len() - offset,
+            TARGET_ELEMENT_ARRAY_BUFFER,
+        ));
+        accessors.push(format!(
+            "{{\"bufferView\":{},\"componentType\":{},\"count\":{},\"type\":\"SCALAR\"}}",
+            views.len() - 1,
+            component_type,
+            buffers.indices.len(),
+        ));
+
+        while bin.len() % 4 != 0 {
+            bin.push(0);
+        }
+
+        json.push_str(",\"scene\":0,\"scenes\":[{\"nodes\":[0]}],\"nodes\":[{\"mesh\":0}]");
+        json.push_str(",\"meshes\":[{");
+        if let Some(name) = &self.name {
+            let _ = write!(json, "\"name\":\"{}\",", escape(name));
+        }
+        let _ = write!(
+            json,
+            "\"primitives\":[{{\"attributes\":{{{}}},\"indices\":{},\"mode\":4}}]}}]",
+            primitive_attributes.join(","),
+            accessors.len() - 1,
+        );
+        let _ = write!(json, ",\"accessors\":[{}]", accessors.join(","));
+        let _ = write!(json, ",\"bufferViews\":[{}]", views.join(","));
+        match bin_uri {
+            Some(uri) => {
+                let _ = write!(
+                    json,
+                    ",\"buffers\":[{{\"uri\":\"{}\",\"byteLength\":{}}}]",
+                    escape(uri),
+                    bin.len()
+                );
+            }
+            None => {
+                let _ = write!(json, ",\"buffers\":[{{\"byteLength\":{}}}]", bin.len());
+            }
+        }
+        json.push('}');
+
+        (json, bin)
+    }
+}
+
+fn is_valid_attribute(name: &str, components: usize) -> bool {
+    if name.starts_with('_') {
+        return true;
+    }
+
+    let set_index = |prefix: &str| {
+        name.strip_prefix(prefix)
+            .map(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+            .unwrap_or(false)
+    };
+
+    match name {
+        "NORMAL" => components == 3,
+        "TANGENT" => components == 4,
+        _ if set_index("TEXCOORD_") => components == 2,
+        _ if set_index("COLOR_") => components == 3 || components == 4,
+        _ if set_index("WEIGHTS_") => components == 4,
+        _ => false,
+    }
+}
+
+fn buffer_view(offset: usize, length: usize, target: u32) -> String {
+    format!(
+        "{{\"buffer\":0,\"byteOffset\":{},\"byteLength\":{},\"target\":{}}}",
+        offset, length, target
+    )
+}
+
+fn accessor_type(components: usize) -> &'static str {
+    match components {
+        1 => "SCALAR",
+        2 => "VEC2",
+        3 => "VEC3",
+        _ => "VEC4",
+    }
+}
+
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+fn write_u32(output: &mut Vec<u8>, value: u32) {
+    output.extend_from_slice(&value.to_le_bytes());
+}
+
+fn write_f32(output: &mut Vec<u8>, value: f32) {
+    output.extend_from_slice(&value.to_le_bytes());
+}
+
+#[cfg(test)]
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    let mut b = [0; 4];
+    b.copy_from_slice(&bytes[offset..offset + 4]);
+    u32::from_le_bytes(b)
+}
+
+#[cfg(test)]
+fn read_f32(bytes: &[u8], offset: usize) -> f32 {
+    f32::from_bits(read_u32(bytes, offset))
+}
+
+#[cfg(test)]
+fn triangle() -> VertexBuffers<[f32; 3], u16> {
+    VertexBuffers {
+        vertices: vec![[0.0, 0.0, 0.5], [2.0, 0.0, 1.0], [0.0, 3.0, 0.25]],
+        indices: vec![0, 1, 2],
+    }
+}
+
+#[test]
+fn glb_layout() {
+    let buffers = triangle();
+    let glb = GltfExporter::new(|v: &[f32; 3]| crate::math::point(v[0], v[1]))
+        .with_attribute("_OPACITY", 1, |v, out| out[0] = v[2])
+        .with_name("tri\"angle")
+        .to_glb(&buffers);
+
+    assert_eq!(&glb[0..4], b"glTF");
+    assert_eq!(read_u32(&glb, 4), 2);
+    assert_eq!(read_u32(&glb, 8) as usize, glb.len());
+
+    let json_len = read_u32(&glb, 12) as usize;
+    assert_eq!(read_u32(&glb, 16), CHUNK_JSON);
+    assert_eq!(json_len % 4, 0);
+    let json = std::str::from_utf8(&glb[20..20 + json_len]).unwrap();
+    assert!(json.contains("\"attributes\":{\"POSITION\":0,\"_OPACITY\":1}"));
+    assert!(json.contains("\"min\":[0.0,0.0,0.0],\"max\":[2.0,3.0,0.0]"));
+    assert!(json.contains("\"name\":\"tri\\\"angle\""));
+    assert!(json.contains("\"componentType\":5123,\"count\":3,\"type\":\"SCALAR\""));
+
+    let bin_start = 20 + json_len;
+    let bin_len = read_u32(&glb, bin_start) as usize;
+    assert_eq!(read_u32(&glb, bin_start + 4), CHUNK_BIN);
+    let bin = &glb[bin_start + 8..];
+    assert_eq!(bin.len(), bin_len);
+    // Positions, opacities, and indices padded to 4 bytes.
+    assert_eq!(bin_len, 36 + 12 + 8);
+    assert_eq!(read_f32(bin, 12), 2.0);
+    assert_eq!(read_f32(bin, 28), 3.0);
+    assert_eq!(read_f32(bin, 32), 0.0);
+    assert_eq!(read_f32(bin, 36 + 4), 1.0);
+    assert_eq!(&bin[48..54], &[0, 0, 1, 0, 2, 0]);
+}
+
+#[test]
+fn gltf_files() {
+    let buffers = triangle();
+    let files = GltfExporter::new(|v: &[f32; 3]| crate::math::point(v[0], v[1]))
+        .with_attribute("COLOR_0", 4, |v, out| out[3] = v[2])
+        .to_gltf(&buffers, "triangle.bin");
+
+    assert!(files
+        .json
+        .contains("\"buffers\":[{\"uri\":\"triangle.bin\",\"byteLength\":92}]"));
+    assert!(files.json.contains("\"type\":\"VEC4\""));
+    assert_eq!(files.bin.len(), 92);
+    // The unwritten components are zero.
+    assert_eq!(read_f32(&files.bin, 36 + 16), 0.0);
+    assert_eq!(read_f32(&files.bin, 36 + 16 + 12), 1.0);
+
+    let empty: VertexBuffers<[f32; 3], u16> = VertexBuffers::new();
+    let files = GltfExporter::new(|v: &[f32; 3]| crate::math::point(v[0], v[1]))
+        .to_gltf(&empty, "empty.bin");
+    assert!(files.bin.is_empty());
+    assert!(!files.json.contains("buffers"));
+}
+
+#[test]
+fn index_type() {
+    // The largest index of a 16 bits index buffer must be at most 65534.
+    for (count, component_type) in [(65535, 5123), (65536, 5125)] {
+        let buffers: VertexBuffers<[f32; 3], u32> = VertexBuffers {
+            vertices: vec![[0.0; 3]; count],
+            indices: vec![0, 1, count as u32 - 1],
+        };
+        let files = GltfExporter::new(|v: &[f32; 3]| crate::math::point(v[0], v[1]))
+            .to_gltf(&buffers, "mesh.bin");
+        let expected = format!("\"componentType\":{},\"count\":3,", component_type);
+        assert!(files.json.contains(&expected));
+    }
+}
+
+#[test]
+fn non_finite_positions() {
+    let buffers: VertexBuffers<[f32; 3], u16> = VertexBuffers {
+        vertices: vec![
+            [f32::NAN, 1.0, 0.0],
+            [2.0, f32::INFINITY, 0.0],
+            [f32::NAN; 3],
+        ],
+        indices: vec![0, 1, 2],
+    };
+    let files = GltfExporter::new(|v: &[f32; 3]| crate::math::point(v[0], v[1]))
+        .to_gltf(&buffers, "mesh.bin");
+    assert!(files
+        .json
+        .contains("\"min\":[2.0,1.0,0.0],\"max\":[2.0,1.0,0.0]"));
+    assert!(!files.json.contains("NaN") && !files.json.contains("inf"));
+}
+
+#[test]
+fn attribute_names() {
+    assert!(is_valid_attribute("_ANYTHING", 1));
+    assert!(is_valid_attribute("TEXCOORD_1", 2));
+    assert!(is_valid_attribute("COLOR_0", 3));
+    assert!(is_valid_attribute("NORMAL", 3));
+    assert!(!is_valid_attribute("POSITION", 3));
+    assert!(!is_valid_attribute("OPACITY", 1));
+    assert!(!is_valid_attribute("TEXCOORD_", 2));
+    assert!(!is_valid_attribute("TEXCOORD_0", 3));
+    assert!(!is_valid_attribute("COLOR_0", 2));
+}
@@ -191,12 +191,24 @@ use lyon_extra as extra;
 pub extern crate serde;
 
 mod basic_shapes;
+mod budget;
+mod clip;
+pub mod color;
 mod event_queue;
 mod fill;
+mod fringe;
 pub mod geometry_builder;
+#[cfg(feature = "gltf")]
+pub mod gltf;
 mod math_utils;
 mod monotone;
+pub mod packed;
+mod pool;
+mod refine;
+pub mod snapshot;
 mod stroke;
+mod stroke_f64;
+mod trace;
 
 #[cfg(test)]
 #[rustfmt::skip]
@@ -210,23 +222,39 @@ pub use crate::path::math;
 
 pub use crate::path::geom;
 
+#[doc(inline)]
+pub use crate::clip::*;
+
 #[doc(inline)]
 pub use crate::event_queue::*;
 
 #[doc(inline)]
 pub use crate::fill::*;
 
+#[doc(inline)]
+pub use crate::pool::*;
+
+#[doc(inline)]
+pub use crate::refine::*;
+
 #[doc(inline)]
 pub use crate::stroke::*;
 
+#[doc(inline)]
+pub use crate::stroke_f64::StrokeTessellatorF64;
+
 #[doc(inline)]
 pub use crate::geometry_builder::{
-    BuffersBuilder, FillGeometryBuilder, FillVertexConstructor, GeometryBuilder,
-    GeometryBuilderError, StrokeGeometryBuilder, StrokeVertexConstructor, VertexBuffers,
+    resolve_vertex_attributes, BuffersBuilder, FillBoundaryEdges, FillBoundaryNormals,
+    FillGeometryBuilder, FillVertexConstructor, GeometryBuilder, GeometryBuilderError,
+    LineGeometryBuilder, SkipDegenerateTriangles, StrokeGeometryBuilder, StrokePart,
+    StrokePartRange, StrokePartRanges, StrokeVertexConstructor, TriangleListBuilder, VertexBuffers,
 };
 
 pub use crate::path::{AttributeIndex, Attributes, FillRule, LineCap, LineJoin, Side};
 
+use crate::budget::OutputLimits;
+use crate::math::{Box2D, Transform};
 use crate::path::EndpointId;
 
 use std::ops::{Add, Sub};
@@ -236,6 +264,64 @@ use thiserror::Error;
 /// The fill tessellator's result type.
 pub type TessellationResult = Result<(), TessellationError>;
 
+/// A function computing the value of a custom attribute between the two endpoints of
+/// an edge.
+///
+/// The function is called with the index of the attribute, its values at the start and at
+/// the end of the edge and the parameter `t` of the vertex along the edge (between 0 and 1).
+/// It returns the value of the attribute at the vertex.
+///
+/// See `FillOptions::attribute_interpolation` and `StrokeOptions::attribute_interpolation`.
+#[derive(Copy, Clone)]
+pub struct AttributeInterpolation(pub fn(AttributeIndex, f32, f32, f32) -> f32);
+
+impl PartialEq for AttributeInterpolation {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 as usize == other.0 as usize
+    }
+}
+
+impl std::fmt::Debug for AttributeInterpolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "AttributeInterpolation({:p})", self.0 as *const ())
+    }
+}
+
+#[inline]
+pub(crate) fn interpolate_attributes(
+    interpolation: Option<AttributeInterpolation>,
+    from: Attributes,
+    to: Attributes,
+    t: f32,
+    output: &mut [f32],
+) {
+    match interpolation {
+        Some(AttributeInterpolation(interpolate)) => {
+            for i in 0..output.len() {
+                output[i] = interpolate(i as AttributeIndex, from[i], to[i], t);
+            }
+        }
+        None => {
+            for i in 0..output.len() {
+                output[i] = lerp_attribute(from[i], to[i], t);
+            }
+        }
+    }
+}
+
+/// Linearly interpolates a custom attribute.
+///
+/// If both values are the same, the exact bits are preserved so that attributes that store
+/// packed data such as `Color::to_packed_attribute` stay intact where they don't change.
+#[inline]
+pub(crate) fn lerp_attribute(from: f32, to: f32, t: f32) -> f32 {
+    if from.to_bits() == to.to_bits() {
+        return from;
+    }
+
+    from * (1.0 - t) + to * t
+}
+
 /// Describes an unexpected error happening during tessellation.
 ///
 /// If you run into one of these, please consider
@@ -257,7 +343,10 @@ pub enum InternalError {
 }
 
 /// The fill tessellator's error enumeration.
+///
+/// New variants may be added in minor releases, matches must have a wildcard arm.
 #[derive(Error, Clone, Debug, PartialEq)]
+#[non_exhaustive]
 pub enum TessellationError {
     // TODO Paramater typo
     #[error("Unsupported parameter: {0}")]
@@ -266,20 +355,34 @@ pub enum TessellationError {
     GeometryBuilder(#[from] GeometryBuilderError),
     #[error("Internal error: {0}")]
     Internal(#[from] InternalError),
+    #[error("Memory limit exceeded")]
+    MemoryLimitExceeded,
+    #[error("Output limit exceeded")]
+    OutputLimitExceeded,
 }
 
+/// The parameters that the tessellators reject.
+///
+/// New variants may be added in minor releases, matches must have a wildcard arm.
 #[derive(Error, Clone, Debug, PartialEq)]
+#[non_exhaustive]
 pub enum UnsupportedParamater {
     #[error("Position is not a number")]
     PositionIsNaN,
     #[error("Tolerance threshold is not a number")]
     ToleranceIsNaN,
+    #[error("Maximum triangle edge length is not a positive number")]
+    MaxTriangleEdgeLengthIsNotPositive,
+    #[error("Dot spacing is not a positive number")]
+    DotSpacingIsNotPositive,
+    #[error("Stroke option {0} is not supported by this tessellator")]
+    StrokeOptionIsNotSupported(&'static str),
 }
 
 /// Before or After. Used to describe position relative to a join.
 #[derive(Copy, Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
-pub(crate) enum Order {
+pub enum Order {
     Before,
     After,
 }
@@ -332,6 +435,51 @@ impl VertexSource {
     }
 }
 
+/// Direction of the y axis of the coordinate system in which the geometry is rendered.
+///
+/// See `FillOptions::y_axis_direction` and `StrokeOptions::y_axis_direction`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum YAxisDirection {
+    /// The y axis points down, as in SVG and most UI toolkits.
+    Down,
+    /// The y axis points up, as in OpenGL's normalized device coordinates.
+    Up,
+}
+
+/// Orientation of the vertices of the produced triangles, as seen on screen.
+///
+/// See `FillOptions::triangle_winding` and `StrokeOptions::triangle_winding`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum TriangleWinding {
+    Clockwise,
+    CounterClockwise,
+}
+
+/// Shape of the dots produced by `StrokeTessellator::tessellate_dots_with_ids`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum DotShape {
+    Circle,
+    /// A square aligned with the direction of the path.
+    Square,
+}
+
+/// Whether the triangles produced by the tessellators must be inverted to get the
+/// requested winding.
+///
+/// The tessellators produce triangles with a negative signed area, which appear
+/// counter-clockwise with the y axis pointing down.
+pub(crate) fn inverts_winding(y_axis: YAxisDirection, winding: TriangleWinding) -> bool {
+    let native = match y_axis {
+        YAxisDirection::Down => TriangleWinding::CounterClockwise,
+        YAxisDirection::Up => TriangleWinding::Clockwise,
+    };
+
+    winding != native
+}
+
 /// Vertical or Horizontal.
 #[derive(Copy, Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
@@ -340,6 +488,91 @@ pub enum Orientation {
     Vertical,
 }
 
+/// The lengths of the dashes and gaps of a dashed stroke.
+///
+/// The lengths alternate between dashes and gaps, starting with a dash. As in SVG, a list
+/// with an odd number of lengths is repeated to get an even number of lengths.
+///
+/// The lengths are stored inline so that `StrokeOptions` can stay `Copy`, which limits
+/// them to `DashArray::MAX_LENGTHS` after repetition.
+///
+/// See the [SVG specification](https://svgwg.org/specs/strokes/#StrokeDasharrayProperty).
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct DashArray {
+    lengths: [f32; DashArray::MAX_LENGTHS],
+    len: u8,
+}
+
+/// The error returned by `DashArray::new`.
+#[derive(Error, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DashArrayError {
+    #[error("Dash length is negative or not a number")]
+    InvalidLength,
+    #[error("Too many dash lengths")]
+    TooManyLengths,
+}
+
+impl DashArray {
+    /// Maximum number of lengths in a dash array, after repeating odd lists.
+    pub const MAX_LENGTHS: usize = 32;
+
+    /// No dashes: the stroke is solid.
+    pub const NONE: Self = DashArray {
+        lengths: [0.0; DashArray::MAX_LENGTHS],
+        len: 0,
+    };
+
+    /// Creates a dash array from a list of lengths.
+    ///
+    /// Fails if a length is negative or NaN, or if there are more than `MAX_LENGTHS`
+    /// lengths once odd lists are repeated. In SVG both cases are errors that make the
+    /// stroke solid, which is what using `DashArray::NONE` instead does.
+    pub fn new(lengths: &[f32]) -> Result<Self, DashArrayError> {
+        let len = if lengths.len() % 2 == 1 {
+            lengths.len() * 2
+        } else {
+            lengths.len()
+        };
+        if len > Self::MAX_LENGTHS {
+            return Err(DashArrayError::TooManyLengths);
+        }
+
+        let mut dashes = DashArray::NONE;
+        for (i, length) in lengths.iter().chain(lengths.iter()).take(len).enumerate() {
+            if !(*length >= 0.0) {
+                return Err(DashArrayError::InvalidLength);
+            }
+            dashes.lengths[i] = *length;
+        }
+        dashes.len = len as u8;
+
+        Ok(dashes)
+    }
+
+    #[inline]
+    pub fn lengths(&self) -> &[f32] {
+        &self.lengths[..self.len as usize]
+    }
+
+    /// The length of the whole pattern.
+    pub fn total_length(&self) -> f32 {
+        self.lengths().iter().sum()
+    }
+
+    /// Whether the dash array produces a solid stroke, which is the case if it is empty
+    /// or if all of its lengths are zero.
+    pub fn is_solid(&self) -> bool {
+        self.total_length() <= 0.0
+    }
+}
+
+impl Default for DashArray {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
 /// Parameters for the tessellator.
 #[derive(Copy, Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
@@ -371,17 +604,179 @@ pub struct StrokeOptions {
     /// Default value: `None`.
     pub variable_line_width: Option<AttributeIndex>,
 
+    /// Whether the variable line width is continuous across the seam of closed sub-paths.
+    ///
+    /// Closed sub-paths often end with an endpoint at the same position as their first
+    /// endpoint, for example when the line width attribute is animated around a ring. The
+    /// two endpoints can have different widths, which makes the stroke jump at the seam.
+    /// If this option is set, both endpoints use the average of their widths instead.
+    ///
+    /// Only taken into account with a variable line width (including tapering), by
+    /// `tessellate_with_ids` and `tessellate_path`.
+    ///
+    /// Default value: `false`.
+    pub periodic_line_width: bool,
+
+    /// Line width at the start of each sub-path.
+    ///
+    /// If `start_width` or `end_width` is set, the line width varies linearly with the
+    /// distance along each sub-path, from `start_width` to `end_width` (using `line_width`
+    /// for the one that is not set). Within a curve, the width is interpolated along the
+    /// curve parameter. If `variable_line_width` is also set, its factor is applied to the
+    /// tapered width.
+    ///
+    /// Tapering requires the length of each sub-path, so it is only taken into account by
+    /// `tessellate`, `tessellate_with_ids`, `tessellate_path` and `tessellate_polygon`.
+    /// The interpolated custom attributes of the vertices then have an extra value at
+    /// the end, containing the line width.
+    ///
+    /// Default value: `None`.
+    pub start_width: Option<f32>,
+
+    /// Line width at the end of each sub-path.
+    ///
+    /// See `start_width`.
+    ///
+    /// Default value: `None`.
+    pub end_width: Option<f32>,
+
     /// See the SVG specification.
     ///
     /// Must be greater than or equal to 1.0.
     /// Default value: `StrokeOptions::DEFAULT_MITER_LIMIT`.
     pub miter_limit: f32,
 
+    /// Distance between the endpoint and the tip of `LineCap::Arrow` caps, expressed as a
+    /// multiple of the line width.
+    ///
+    /// Default value: `StrokeOptions::DEFAULT_ARROW_LENGTH`.
+    pub arrow_length: f32,
+
+    /// Width of the base of `LineCap::Arrow` caps, expressed as a multiple of the line width.
+    ///
+    /// The base of the arrow is never narrower than the stroke.
+    /// Default value: `StrokeOptions::DEFAULT_ARROW_WIDTH`.
+    pub arrow_width: f32,
+
+    /// Distance between the join position and the clip line of `LineJoin::MiterClip`
+    /// joins, expressed as a multiple of the half line width.
+    ///
+    /// The miter limit decides whether a join is clipped, this decides where.
+    /// If `None`, the clip distance is derived from the miter limit (`miter_limit / 2`).
+    /// Default value: `None`.
+    pub miter_clip_distance: Option<f32>,
+
     /// Maximum allowed distance to the path when building an approximation.
     ///
     /// See [Flattening and tolerance](index.html#flattening-and-tolerance).
     /// Default value: `StrokeOptions::DEFAULT_TOLERANCE`.
     pub tolerance: f32,
+
+    /// Number of segments of a half circle in round joins and caps.
+    ///
+    /// If set, round joins and caps are subdivided independently of the tolerance and of
+    /// the line width, so that the geometry doesn't change with the scale, for example for
+    /// meshes that are cached across zoom levels. Joins get a number of segments
+    /// proportional to their angle (at least one). Caps are made of two quarter circles,
+    /// so odd numbers are rounded up for them.
+    ///
+    /// Default value: `None` (derived from the tolerance).
+    pub round_segments: Option<u32>,
+
+    /// Maximum number of vertices produced by the tessellation.
+    ///
+    /// See `budget_policy` for what happens when the limit is exceeded. Only taken into
+    /// account by `tessellate`, `tessellate_with_ids` and `tessellate_path`.
+    ///
+    /// Default value: `u32::MAX` (no limit).
+    pub max_vertices: u32,
+
+    /// Maximum number of triangles produced by the tessellation.
+    ///
+    /// See `max_vertices`.
+    ///
+    /// Default value: `u32::MAX` (no limit).
+    pub max_triangles: u32,
+
+    /// What to do when the tessellation exceeds `max_vertices` or `max_triangles`.
+    ///
+    /// Default value: `BudgetPolicy::DEFAULT`.
+    pub budget_policy: BudgetPolicy,
+
+    /// Lengths of the dashes and gaps of the stroke.
+    ///
+    /// The pattern starts over at the beginning of each sub-path. Each dash has caps and
+    /// the joins within a dash are preserved. The advancement of the vertices is the
+    /// distance along the sub-path, including the gaps.
+    ///
+    /// Default value: `DashArray::NONE` (solid stroke).
+    pub dash_array: DashArray,
+
+    /// Distance into the dash pattern at the start of each sub-path.
+    ///
+    /// Default value: `0.0`.
+    pub dash_offset: f32,
+
+    /// Whether the advancement of the vertices is divided by the length of their sub-path,
+    /// so that it goes from 0 to 1 along each sub-path instead of measuring the distance
+    /// along the whole path.
+    ///
+    /// The length of each sub-path is measured with an additional tessellation pass.
+    /// Only taken into account by `tessellate`, `tessellate_with_ids`, `tessellate_path`
+    /// and `tessellate_polygon`.
+    ///
+    /// Default value: `false`.
+    pub normalized_advancement: bool,
+
+    /// Width of an anti-aliasing fringe added around the stroke.
+    ///
+    /// If set, each edge on the boundary of the stroke, including joins and caps, is
+    /// extended outwards by a quad of this width. The coverage of the vertices
+    /// (see `StrokeVertex::coverage`) goes from one on the stroke to zero on the outer
+    /// edge of the fringe, which renderers without multi-sampling can use as alpha to
+    /// smooth the edges. The width is in the coordinate space of the path, so a fringe of
+    /// one pixel is the inverse of the scale applied when rendering.
+    ///
+    /// Parts of the stroke that overlap each other (for example at sharp turns) can get
+    /// fringes inside the stroke, which are only invisible when the stroke is opaque.
+    /// Output limits don't account for the vertices and triangles of the fringe.
+    /// Only taken into account by `tessellate`, `tessellate_with_ids`, `tessellate_path`
+    /// and `tessellate_polygon`.
+    ///
+    /// Default value: `None`.
+    pub anti_aliasing_fringe: Option<f32>,
+
+    /// How the custom attributes of the vertices produced along edges are computed.
+    ///
+    /// By default the attributes are interpolated linearly between the endpoints of the
+    /// edge (along the curve parameter for curves). A custom function can for example
+    /// apply an easing curve or clamp the values. Vertices on endpoints use the attributes
+    /// of the endpoint.
+    ///
+    /// This option is not serialized.
+    ///
+    /// Default value: `None` (linear interpolation).
+    #[cfg_attr(feature = "serialization", serde(skip))]
+    pub attribute_interpolation: Option<AttributeInterpolation>,
+
+    /// Direction of the y axis of the coordinate system in which the stroke is rendered.
+    ///
+    /// Together with `triangle_winding`, this decides the orientation of the produced
+    /// triangles. With `YAxisDirection::Up`, the sides of the stroke are also swapped so
+    /// that `Side::Positive` is the right side of the path as seen on screen, as it is
+    /// with `YAxisDirection::Down` (see `StrokeVertex::side`).
+    ///
+    /// Default value: `YAxisDirection::Down`.
+    pub y_axis_direction: YAxisDirection,
+
+    /// Orientation of the produced triangles, as seen on screen in the coordinate system
+    /// described by `y_axis_direction`.
+    ///
+    /// Triangles that fold over themselves at sharp joins can have the opposite
+    /// orientation.
+    ///
+    /// Default value: `TriangleWinding::CounterClockwise`.
+    pub triangle_winding: TriangleWinding,
 }
 
 impl StrokeOptions {
@@ -397,6 +792,8 @@ impl StrokeOptions {
     pub const DEFAULT_LINE_JOIN: LineJoin = LineJoin::Miter;
     pub const DEFAULT_LINE_WIDTH: f32 = 1.0;
     pub const DEFAULT_TOLERANCE: f32 = 0.1;
+    pub const DEFAULT_ARROW_LENGTH: f32 = 2.0;
+    pub const DEFAULT_ARROW_WIDTH: f32 = 3.0;
 
     pub const DEFAULT: Self = StrokeOptions {
         start_cap: Self::DEFAULT_LINE_CAP,
@@ -404,8 +801,25 @@ impl StrokeOptions {
         line_join: Self::DEFAULT_LINE_JOIN,
         line_width: Self::DEFAULT_LINE_WIDTH,
         variable_line_width: None,
+        periodic_line_width: false,
+        start_width: None,
+        end_width: None,
         miter_limit: Self::DEFAULT_MITER_LIMIT,
+        arrow_length: Self::DEFAULT_ARROW_LENGTH,
+        arrow_width: Self::DEFAULT_ARROW_WIDTH,
+        miter_clip_distance: None,
         tolerance: Self::DEFAULT_TOLERANCE,
+        round_segments: None,
+        max_vertices: u32::MAX,
+        max_triangles: u32::MAX,
+        budget_policy: BudgetPolicy::DEFAULT,
+        dash_array: DashArray::NONE,
+        dash_offset: 0.0,
+        normalized_advancement: false,
+        anti_aliasing_fringe: None,
+        attribute_interpolation: None,
+        y_axis_direction: YAxisDirection::Down,
+        triangle_winding: TriangleWinding::CounterClockwise,
     };
 
     #[inline]
@@ -457,11 +871,136 @@ impl StrokeOptions {
         self
     }
 
+    #[inline]
+    pub fn with_miter_clip_distance(mut self, distance: f32) -> Self {
+        assert!(distance >= 0.0);
+        self.miter_clip_distance = Some(distance);
+        self
+    }
+
+    #[inline]
+    pub fn with_arrow_length(mut self, length: f32) -> Self {
+        self.arrow_length = length;
+        self
+    }
+
+    #[inline]
+    pub fn with_arrow_width(mut self, width: f32) -> Self {
+        self.arrow_width = width;
+        self
+    }
+
+    /// The distance between the join position and the clip line of `LineJoin::MiterClip`
+    /// joins, as a multiple of the half line width.
+    #[inline]
+    pub fn effective_miter_clip_distance(&self) -> f32 {
+        self.miter_clip_distance.unwrap_or(self.miter_limit * 0.5)
+    }
+
     #[inline]
     pub fn with_variable_line_width(mut self, idx: AttributeIndex) -> Self {
         self.variable_line_width = Some(idx);
         self
     }
+
+    #[inline]
+    pub fn with_periodic_line_width(mut self, periodic: bool) -> Self {
+        self.periodic_line_width = periodic;
+        self
+    }
+
+    /// See `StrokeOptions::start_width`.
+    #[inline]
+    pub fn with_start_width(mut self, width: f32) -> Self {
+        self.start_width = Some(width);
+        self
+    }
+
+    /// See `StrokeOptions::start_width`.
+    #[inline]
+    pub fn with_end_width(mut self, width: f32) -> Self {
+        self.end_width = Some(width);
+        self
+    }
+
+    #[inline]
+    pub fn with_round_segments(mut self, segments: u32) -> Self {
+        assert!(segments > 0);
+        self.round_segments = Some(segments);
+        self
+    }
+
+    #[inline]
+    pub fn with_max_vertices(mut self, max: u32) -> Self {
+        self.max_vertices = max;
+        self
+    }
+
+    #[inline]
+    pub fn with_max_triangles(mut self, max: u32) -> Self {
+        self.max_triangles = max;
+        self
+    }
+
+    #[inline]
+    pub fn with_budget_policy(mut self, policy: BudgetPolicy) -> Self {
+        self.budget_policy = policy;
+        self
+    }
+
+    #[inline]
+    pub fn with_dash_array(mut self, dash_array: DashArray) -> Self {
+        self.dash_array = dash_array;
+        self
+    }
+
+    #[inline]
+    pub fn with_dash_offset(mut self, offset: f32) -> Self {
+        self.dash_offset = offset;
+        self
+    }
+
+    #[inline]
+    pub fn with_normalized_advancement(mut self, normalized: bool) -> Self {
+        self.normalized_advancement = normalized;
+        self
+    }
+
+    #[inline]
+    pub fn with_attribute_interpolation(
+        mut self,
+        interpolation: fn(AttributeIndex, f32, f32, f32) -> f32,
+    ) -> Self {
+        self.attribute_interpolation = Some(AttributeInterpolation(interpolation));
+        self
+    }
+
+    #[inline]
+    pub fn with_y_axis_direction(mut self, direction: YAxisDirection) -> Self {
+        self.y_axis_direction = direction;
+        self
+    }
+
+    #[inline]
+    pub fn with_triangle_winding(mut self, winding: TriangleWinding) -> Self {
+        self.triangle_winding = winding;
+        self
+    }
+
+    #[inline]
+    pub fn with_anti_aliasing_fringe(mut self, width: f32) -> Self {
+        assert!(width > 0.0);
+        self.anti_aliasing_fringe = Some(width);
+        self
+    }
+
+    pub(crate) fn output_limits(&self) -> OutputLimits {
+        OutputLimits {
+            max_vertices: self.max_vertices,
+            max_triangles: self.max_triangles,
+            policy: self.budget_policy,
+        }
+    }
 }
 
 impl Default for StrokeOptions {
@@ -470,6 +1009,64 @@ impl Default for StrokeOptions {
     }
 }
 
+/// Stroke options that can be set for individual events of a path.
+///
+/// See `StrokeTessellator::tessellate_commands_with_overrides`. Options that are not set
+/// use the value of the `StrokeOptions` of the tessellation.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct StrokeOptionsOverride {
+    /// Line width of an edge.
+    pub line_width: Option<f32>,
+    /// Join at the end of an edge.
+    pub line_join: Option<LineJoin>,
+    /// Cap at the start of a sub-path.
+    pub start_cap: Option<LineCap>,
+    /// Cap at the end of a sub-path.
+    pub end_cap: Option<LineCap>,
+}
+
+impl StrokeOptionsOverride {
+    pub const NONE: Self = StrokeOptionsOverride {
+        line_width: None,
+        line_join: None,
+        start_cap: None,
+        end_cap: None,
+    };
+
+    #[inline]
+    pub fn with_line_width(mut self, width: f32) -> Self {
+        self.line_width = Some(width);
+        self
+    }
+
+    #[inline]
+    pub fn with_line_join(mut self, join: LineJoin) -> Self {
+        self.line_join = Some(join);
+        self
+    }
+
+    #[inline]
+    pub fn with_start_cap(mut self, cap: LineCap) -> Self {
+        self.start_cap = Some(cap);
+        self
+    }
+
+    #[inline]
+    pub fn with_end_cap(mut self, cap: LineCap) -> Self {
+        self.end_cap = Some(cap);
+        self
+    }
+
+    #[inline]
+    pub fn with_line_cap(mut self, cap: LineCap) -> Self {
+        self.start_cap = Some(cap);
+        self.end_cap = Some(cap);
+        self
+    }
+}
+
 /// Parameters for the fill tessellator.
 #[derive(Copy, Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
@@ -504,6 +1101,106 @@ pub struct FillOptions {
     ///
     /// Default value: `true`.
     pub handle_intersections: bool,
+
+    /// Subdivide the produced triangles so that none of their edges is longer than this value.
+    ///
+    /// This is useful when the vertices of large filled areas are transformed individually,
+    /// for example with per-vertex lighting or when projecting shapes onto a sphere. Edges are
+    /// split at their midpoint. The inserted vertices have no source (see
+    /// `FillVertex::sources`) and their custom attributes are interpolated from the vertices
+    /// of the edge they are inserted on.
+    ///
+    /// Each triangle produced by the tessellator is split at most
+    /// `FillOptions::MAX_SUBDIVISION_DEPTH` times, so some edges can remain longer than this
+    /// value if it is very small compared to the size of the path.
+    ///
+    /// Only taken into account when tessellating paths. Must be positive, otherwise the
+    /// tessellation fails with `UnsupportedParamater::MaxTriangleEdgeLengthIsNotPositive`.
+    ///
+    /// Default value: `f32::INFINITY` (no subdivision).
+    pub max_triangle_edge_length: f32,
+
+    /// Approximate maximum amount of memory in bytes that the tessellator may use for
+    /// its internal buffers.
+    ///
+    /// The limit covers the event queue (the path's flattened edges and the intersections
+    /// found during the sweep), the edges crossing the sweep line and the monotone polygons
+    /// being tessellated, each counted with a fixed size per entry. It is checked while the
+    /// path is added to the event queue and after each step of the sweep. The vertices and
+    /// triangles that each monotone polygon buffers until it is complete, temporary buffers
+    /// proportional to the number of edges crossing the sweep line, and the memory used by
+    /// the geometry builder are not counted.
+    ///
+    /// If the limit is exceeded, the tessellation fails with
+    /// `TessellationError::MemoryLimitExceeded` instead of growing the allocations. This is
+    /// useful in environments with strict memory budgets such as WebAssembly.
+    ///
+    /// Default value: `usize::MAX` (no limit).
+    pub memory_limit: usize,
+
+    /// How to compute the texture coordinates returned by `FillVertex::uv`.
+    ///
+    /// Default value: `None` (the texture coordinates are equal to the position).
+    pub uv_mapping: Option<UvMapping>,
+
+    /// Index of the custom attribute that marks clip sub-paths.
+    ///
+    /// Sub-paths for which this attribute is not zero at their first endpoint are clip
+    /// sub-paths, the others are painted. If the path contains clip sub-paths, the output
+    /// is restricted to the intersection of the painted area and the clipped area, each of
+    /// them being computed with the fill rule.
+    ///
+    /// Only taken into account when tessellating paths with custom attributes.
+    ///
+    /// Default value: `None`.
+    pub clip_attribute: Option<AttributeIndex>,
+
+    /// Maximum number of vertices produced by the tessellation.
+    ///
+    /// See `budget_policy` for what happens when the limit is exceeded. Only taken into
+    /// account by `tessellate`, `tessellate_with_ids` and `tessellate_path`.
+    ///
+    /// Default value: `u32::MAX` (no limit).
+    pub max_vertices: u32,
+
+    /// Maximum number of triangles produced by the tessellation.
+    ///
+    /// See `max_vertices`.
+    ///
+    /// Default value: `u32::MAX` (no limit).
+    pub max_triangles: u32,
+
+    /// What to do when the tessellation exceeds `max_vertices` or `max_triangles`.
+    ///
+    /// Default value: `BudgetPolicy::DEFAULT`.
+    pub budget_policy: BudgetPolicy,
+
+    /// How the custom attributes of the vertices produced along edges are computed.
+    ///
+    /// By default the attributes are interpolated linearly between the endpoints of the
+    /// edge (along the curve parameter for curves). A custom function can for example
+    /// apply an easing curve or clamp the values. Vertices on endpoints use the attributes
+    /// of the endpoint, and vertices at intersections average the values of their sources.
+    ///
+    /// This option is not serialized.
+    ///
+    /// Default value: `None` (linear interpolation).
+    #[cfg_attr(feature = "serialization", serde(skip))]
+    pub attribute_interpolation: Option<AttributeInterpolation>,
+
+    /// Direction of the y axis of the coordinate system in which the fill is rendered.
+    ///
+    /// Together with `triangle_winding`, this decides the orientation of the produced
+    /// triangles.
+    ///
+    /// Default value: `YAxisDirection::Down`.
+    pub y_axis_direction: YAxisDirection,
+
+    /// Orientation of the produced triangles, as seen on screen in the coordinate system
+    /// described by `y_axis_direction`.
+    ///
+    /// Default value: `TriangleWinding::CounterClockwise`.
+    pub triangle_winding: TriangleWinding,
 }
 
 impl FillOptions {
@@ -511,6 +1208,8 @@ impl FillOptions {
     pub const DEFAULT_TOLERANCE: f32 = 0.1;
     /// Default Fill rule.
     pub const DEFAULT_FILL_RULE: FillRule = FillRule::EvenOdd;
+    /// Maximum number of times each triangle is split to honor `max_triangle_edge_length`.
+    pub const MAX_SUBDIVISION_DEPTH: u32 = 16;
     /// Default orientation.
     pub const DEFAULT_SWEEP_ORIENTATION: Orientation = Orientation::Vertical;
 
@@ -519,6 +1218,16 @@ impl FillOptions {
         fill_rule: Self::DEFAULT_FILL_RULE,
         sweep_orientation: Self::DEFAULT_SWEEP_ORIENTATION,
         handle_intersections: true,
+        max_triangle_edge_length: f32::INFINITY,
+        memory_limit: usize::MAX,
+        uv_mapping: None,
+        clip_attribute: None,
+        max_vertices: u32::MAX,
+        max_triangles: u32::MAX,
+        budget_policy: BudgetPolicy::DEFAULT,
+        attribute_interpolation: None,
+        y_axis_direction: YAxisDirection::Down,
+        triangle_winding: TriangleWinding::CounterClockwise,
     };
 
     #[inline]
@@ -561,6 +1270,138 @@ impl FillOptions {
         self.handle_intersections = intersections;
         self
     }
+
+    #[inline]
+    pub fn with_max_triangle_edge_length(mut self, length: f32) -> Self {
+        self.max_triangle_edge_length = length;
+        self
+    }
+
+    #[inline]
+    pub fn with_memory_limit(mut self, bytes: usize) -> Self {
+        self.memory_limit = bytes;
+        self
+    }
+
+    #[inline]
+    pub fn with_uv_mapping(mut self, mapping: UvMapping) -> Self {
+        self.uv_mapping = Some(mapping);
+        self
+    }
+
+    #[inline]
+    pub fn with_clip_attribute(mut self, attribute: AttributeIndex) -> Self {
+        self.clip_attribute = Some(attribute);
+        self
+    }
+
+    #[inline]
+    pub fn with_attribute_interpolation(
+        mut self,
+        interpolation: fn(AttributeIndex, f32, f32, f32) -> f32,
+    ) -> Self {
+        self.attribute_interpolation = Some(AttributeInterpolation(interpolation));
+        self
+    }
+
+    #[inline]
+    pub fn with_y_axis_direction(mut self, direction: YAxisDirection) -> Self {
+        self.y_axis_direction = direction;
+        self
+    }
+
+    #[inline]
+    pub fn with_triangle_winding(mut self, winding: TriangleWinding) -> Self {
+        self.triangle_winding = winding;
+        self
+    }
+
+    #[inline]
+    pub fn with_max_vertices(mut self, max: u32) -> Self {
+        self.max_vertices = max;
+        self
+    }
+
+    #[inline]
+    pub fn with_max_triangles(mut self, max: u32) -> Self {
+        self.max_triangles = max;
+        self
+    }
+
+    #[inline]
+    pub fn with_budget_policy(mut self, policy: BudgetPolicy) -> Self {
+        self.budget_policy = policy;
+        self
+    }
+
+    pub(crate) fn output_limits(&self) -> OutputLimits {
+        OutputLimits {
+            max_vertices: self.max_vertices,
+            max_triangles: self.max_triangles,
+            policy: self.budget_policy,
+        }
+    }
+}
+
+/// What the tessellators do when their output exceeds the `max_vertices` or
+/// `max_triangles` options.
+///
+/// In both cases the geometry produced by the failed attempts is discarded.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum BudgetPolicy {
+    /// Fail with `TessellationError::OutputLimitExceeded`.
+    Fail,
+    /// Tessellate again with the tolerance multiplied by `factor`, up to `max_retries`
+    /// times, then fail with `TessellationError::OutputLimitExceeded`.
+    ///
+    /// A coarser tolerance only reduces the number of vertices produced to approximate
+    /// curves (and round joins and caps), so this can't help with paths made of many
+    /// line segments.
+    CoarsenTolerance { factor: f32, max_retries: u32 },
+}
+
+impl BudgetPolicy {
+    pub const DEFAULT: Self = BudgetPolicy::CoarsenTolerance {
+        factor: 2.0,
+        max_retries: 4,
+    };
+}
+
+impl Default for BudgetPolicy {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Computes texture coordinates from the position of the vertices produced by the fill
+/// tessellator.
+///
+/// See `FillOptions::uv_mapping` and `FillVertex::uv`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum UvMapping {
+    /// Maps the bounding box of the tessellated geometry to the unit square: its minimum
+    /// corner to `(0, 0)` and its maximum corner to `(1, 1)`.
+    BoundingBox,
+    /// Applies an affine transform to the positions.
+    Transform(Transform),
+}
+
+impl UvMapping {
+    /// Returns the transform from positions to texture coordinates for geometry
+    /// contained in `bounds`.
+    pub fn to_transform(&self, bounds: &Box2D) -> Transform {
+        match self {
+            UvMapping::BoundingBox => {
+                let size = bounds.size();
+                let inv = |v: f32| if v > 0.0 { 1.0 / v } else { 0.0 };
+                Transform::translation(-bounds.min.x, -bounds.min.y)
+                    .then_scale(inv(size.width), inv(size.height))
+            }
+            UvMapping::Transform(transform) => *transform,
+        }
+    }
 }
 
 impl Default for FillOptions {
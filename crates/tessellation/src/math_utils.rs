@@ -1,5 +1,8 @@
 //! Various math tools that are mostly usefull for the tessellators.
 
+use crate::geom::euclid::default::Vector2D;
+use crate::geom::Scalar;
+#[cfg(test)]
 use crate::math::*;
 
 /// Compute a normal vector at a point P such that ```x ---e1----> P ---e2---> x```
@@ -10,22 +13,22 @@ use crate::math::*;
 /// The normal points towards the positive side of e1.
 ///
 /// v1 and v2 are expected to be normalized.
-pub fn compute_normal(v1: Vector, v2: Vector) -> Vector {
+pub fn compute_normal<S: Scalar>(v1: Vector2D<S>, v2: Vector2D<S>) -> Vector2D<S> {
     //debug_assert!((v1.length() - 1.0).abs() < 0.001, "v1 should be normalized ({})", v1.length());
     //debug_assert!((v2.length() - 1.0).abs() < 0.001, "v2 should be normalized ({})", v2.length());
 
-    let epsilon = 1e-4;
+    let epsilon = S::value(1e-4);
 
-    let n1 = vector(-v1.y, v1.x);
+    let n1 = Vector2D::new(-v1.y, v1.x);
 
     let v12 = v1 + v2;
 
     if v12.square_length() < epsilon {
-        return vector(0.0, 0.0);
+        return Vector2D::zero();
     }
 
     let tangent = v12.normalize();
-    let n = vector(-tangent.y, tangent.x);
+    let n = Vector2D::new(-tangent.y, tangent.x);
 
     let inv_len = n.dot(n1);
 
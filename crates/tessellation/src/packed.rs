@@ -0,0 +1,385 @@
+//! Compact vertex formats.
+//!
+//! Positions and normals are produced as 32 bits floats, which is more precision than
+//! needed for most 2D content. On bandwidth-constrained GPUs (typically mobile), storing
+//! them as half-floats or normalized integers halves the size of the vertex buffers.
+//!
+//! This module provides the conversion functions as well as vertex types and a vertex
+//! constructor (see `PackedVertices`) that can be used with `BuffersBuilder`:
+//!
+//! | Vertex type          | Position    | Normal               | Size     |
+//! |----------------------|-------------|----------------------|----------|
+//! | `Point`              | 2 x f32     | none                 | 8 bytes  |
+//! | `PackedNormalVertex` | 2 x f32     | snorm 10-10-10-2     | 12 bytes |
+//! | `HalfVertex`         | 2 x f16     | 2 x f16              | 8 bytes  |
+//! | `HalfSnorm8Vertex`   | 2 x f16     | 2 x snorm8           | 6 bytes  |
+//!
+//! Half-floats have 11 bits of precision, which gives sub-pixel accuracy for coordinates
+//! up to 2048 in absolute value. Snorm formats can only represent values between -1 and
+//! 1 while stroke normals get longer than 1 at miter joins, so normals are divided by a
+//! `normal_scale` (for example the miter limit) which the vertex shader must multiply
+//! them by. Fill vertices have a zero normal.
+//!
+//! # Example
+//!
+//! ```
+//! use lyon_tessellation::*;
+//! use lyon_tessellation::packed::{f16_to_f32, HalfVertex, PackedVertices};
+//! use lyon_tessellation::math::point;
+//! use lyon_tessellation::path::Path;
+//!
+//! let mut builder = Path::builder();
+//! builder.begin(point(0.0, 0.0));
+//! builder.line_to(point(10.0, 0.0));
+//! builder.end(false);
+//! let path = builder.build();
+//!
+//! let options = StrokeOptions::default();
+//! let mut buffers: VertexBuffers<HalfVertex, u16> = VertexBuffers::new();
+//! StrokeTessellator::new().tessellate_path(
+//!     &path,
+//!     &options,
+//!     &mut BuffersBuilder::new(&mut buffers, PackedVertices::new(options.miter_limit)),
+//! ).unwrap();
+//!
+//! assert_eq!(std::mem::size_of::<HalfVertex>(), 8);
+//! for vertex in &buffers.vertices {
+//!     let x = f16_to_f32(vertex.position[0]);
+//!     assert!(x == 0.0 || x == 10.0);
+//! }
+//! ```
+
+use crate::geometry_builder::{FillVertexConstructor, StrokeVertexConstructor};
+use crate::math::{vector, Point, Vector};
+use crate::{FillVertex, StrokeVertex};
+
+/// Converts a float to the bits of the nearest half-float.
+///
+/// Values that are too large become infinite.
+pub fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+
+    if exponent == 0xff {
+        // Infinity or NaN.
+        let nan_bit = if mantissa != 0 { 0x200 } else { 0 };
+        return sign | 0x7c00 | nan_bit;
+    }
+
+    let half_exponent = exponent - 127 + 15;
+    if half_exponent >= 0x1f {
+        return sign | 0x7c00;
+    }
+
+    // Rounds `m >> shift` to the nearest integer, ties to even.
+    let round = |m: u32, shift: u32| {
+        let truncated = m >> shift;
+        let remainder = m & ((1 << shift) - 1);
+        let half = 1 << (shift - 1);
+        if remainder > half || (remainder == half && truncated & 1 != 0) {
+            truncated + 1
+        } else {
+            truncated
+        }
+    };
+
+    if half_exponent <= 0 {
+        // Subnormal half-float, or zero.
+        if half_exponent < -10 {
+            return sign;
+        }
+        let m = mantissa | 0x80_0000;
+        return sign | round(m, (14 - half_exponent) as u32) as u16;
+    }
+
+    // A carry out of the mantissa correctly increments the exponent.
+    sign | round(((half_exponent as u32) << 23) | mantissa, 13) as u16
+}
+
+/// Converts the bits of a half-float to a float.
+pub fn f16_to_f32(half: u16) -> f32 {
+    let sign = ((half & 0x8000) as u32) << 16;
+    let exponent = ((half >> 10) & 0x1f) as u32;
+    let mantissa = (half & 0x3ff) as u32;
+
+    let bits = match exponent {
+        0 => {
+            let value = mantissa as f32 / (1 << 24) as f32;
+            return if sign != 0 { -value } else { value };
+        }
+        0x1f => sign | 0x7f80_0000 | (mantissa << 13),
+        _ => sign | ((exponent + 127 - 15) << 23) | (mantissa << 13),
+    };
+
+    f32::from_bits(bits)
+}
+
+/// Converts a value between -1 and 1 to a signed normalized integer of `bits` bits.
+///
+/// The value is clamped.
+#[inline]
+fn to_snorm(value: f32, bits: u32) -> i32 {
+    let max = ((1 << (bits - 1)) - 1) as f32;
+    (value.clamp(-1.0, 1.0) * max).round() as i32
+}
+
+#[inline]
+fn from_snorm(value: i32, bits: u32) -> f32 {
+    let max = ((1 << (bits - 1)) - 1) as f32;
+    (value as f32 / max).max(-1.0)
+}
+
+/// Packs `normal / scale` into two snorm8 values.
+#[inline]
+pub fn pack_normal_snorm8(normal: Vector, scale: f32) -> [i8; 2] {
+    [
+        to_snorm(normal.x / scale, 8) as i8,
+        to_snorm(normal.y / scale, 8) as i8,
+    ]
+}
+
+/// Unpacks a normal packed with `pack_normal_snorm8`.
+#[inline]
+pub fn unpack_normal_snorm8(packed: [i8; 2], scale: f32) -> Vector {
+    vector(
+        from_snorm(packed[0] as i32, 8),
+        from_snorm(packed[1] as i32, 8),
+    ) * scale
+}
+
+/// Packs `normal / scale` into the x and y components of a snorm 10-10-10-2 value.
+///
+/// The x component is stored in the lowest bits, which matches the layout of the
+/// `INT_2_10_10_10_REV` (OpenGL), `A2B10G10R10_SNORM_PACK32` (Vulkan) and
+/// `Snorm1010102` (most other APIs) formats. The z and w components are zero.
+#[inline]
+pub fn pack_normal_10_10_10_2(normal: Vector, scale: f32) -> u32 {
+    let x = to_snorm(normal.x / scale, 10) as u32 & 0x3ff;
+    let y = to_snorm(normal.y / scale, 10) as u32 & 0x3ff;
+
+    x | (y << 10)
+}
+
+/// Unpacks a normal packed with `pack_normal_10_10_10_2`.
+#[inline]
+pub fn unpack_normal_10_10_10_2(packed: u32, scale: f32) -> Vector {
+    // Shift the 10 bits components to the top of an i32 and back to sign-extend them.
+    let x = ((packed << 22) as i32) >> 22;
+    let y = ((packed << 12) as i32) >> 22;
+
+    vector(from_snorm(x, 10), from_snorm(y, 10)) * scale
+}
+
+#[inline]
+fn half_point(p: Point) -> [u16; 2] {
+    [f32_to_f16(p.x), f32_to_f16(p.y)]
+}
+
+/// A vertex with a 32 bits float position and a snorm 10-10-10-2 packed normal.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct PackedNormalVertex {
+    pub position: [f32; 2],
+    /// See `pack_normal_10_10_10_2`.
+    pub normal: u32,
+}
+
+/// A vertex with a half-float position and normal.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct HalfVertex {
+    /// The bits of two half-floats.
+    pub position: [u16; 2],
+    /// The bits of two half-floats.
+    pub normal: [u16; 2],
+}
+
+/// A vertex with a half-float position and a snorm8 normal.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct HalfSnorm8Vertex {
+    /// The bits of two half-floats.
+    pub position: [u16; 2],
+    /// See `pack_normal_snorm8`.
+    pub normal: [i8; 2],
+}
+
+/// A vertex constructor producing the compact vertex types of this module.
+///
+/// The output vertex type is selected by the type of the vertex buffers.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PackedVertices {
+    /// Normals are divided by this value before being packed into snorm formats.
+    ///
+    /// Half-float normals are not scaled.
+    pub normal_scale: f32,
+}
+
+impl PackedVertices {
+    pub fn new(normal_scale: f32) -> Self {
+        PackedVertices { normal_scale }
+    }
+}
+
+impl Default for PackedVertices {
+    fn default() -> Self {
+        PackedVertices::new(crate::StrokeOptions::DEFAULT_MITER_LIMIT)
+    }
+}
+
+impl FillVertexConstructor<PackedNormalVertex> for PackedVertices {
+    fn new_vertex(&mut self, vertex: FillVertex) -> PackedNormalVertex {
+        PackedNormalVertex {
+            position: vertex.position().to_array(),
+            normal: 0,
+        }
+    }
+}
+
+impl StrokeVertexConstructor<PackedNormalVertex> for PackedVertices {
+    fn new_vertex(&mut self, vertex: StrokeVertex) -> PackedNormalVertex {
+        PackedNormalVertex {
+            position: vertex.position().to_array(),
+            normal: pack_normal_10_10_10_2(vertex.normal(), self.normal_scale),
+        }
+    }
+}
+
+impl FillVertexConstructor<HalfVertex> for PackedVertices {
+    fn new_vertex(&mut self, vertex: FillVertex) -> HalfVertex {
+        HalfVertex {
+            position: half_point(vertex.position()),
+            normal: [0, 0],
+        }
+    }
+}
+
+impl StrokeVertexConstructor<HalfVertex> for PackedVertices {
+    fn new_vertex(&mut self, vertex: StrokeVertex) -> HalfVertex {
+        HalfVertex {
+            position: half_point(vertex.position()),
+            normal: half_point(vertex.normal().to_point()),
+        }
+    }
+}
+
+impl FillVertexConstructor<HalfSnorm8Vertex> for PackedVertices {
+    fn new_vertex(&mut self, vertex: FillVertex) -> HalfSnorm8Vertex {
+        HalfSnorm8Vertex {
+            position: half_point(vertex.position()),
+            normal: [0, 0],
+        }
+    }
+}
+
+impl StrokeVertexConstructor<HalfSnorm8Vertex> for PackedVertices {
+    fn new_vertex(&mut self, vertex: StrokeVertex) -> HalfSnorm8Vertex {
+        HalfSnorm8Vertex {
+            position: half_point(vertex.position()),
+            normal: pack_normal_snorm8(vertex.normal(), self.normal_scale),
+        }
+    }
+}
+
+#[test]
+fn half_float_conversions() {
+    for (value, bits) in [
+        (0.0, 0x0000),
+        (-0.0, 0x8000),
+        (1.0, 0x3c00),
+        (-2.0, 0xc000),
+        (0.5, 0x3800),
+        (65504.0, 0x7bff),
+        (1e6, 0x7c00),
+        (f32::INFINITY, 0x7c00),
+        // Smallest subnormal.
+        (5.960_464_5e-8, 0x0001),
+        // Rounds to nearest, ties to even.
+        (1.0 + 1.0 / 2048.0, 0x3c00),
+        (1.0 + 3.0 / 2048.0, 0x3c02),
+    ] {
+        assert_eq!(f32_to_f16(value), bits, "{}", value);
+    }
+    assert!(f16_to_f32(f32_to_f16(f32::NAN)).is_nan());
+
+    for value in [0.0, 1.0, -3.25, 1024.5, 6.1e-5, 3.0e-7] {
+        let half = f32_to_f16(value);
+        let error = (f16_to_f32(half) - value).abs();
+        assert!(error <= value.abs() / 1024.0 + 6.0e-8, "{}", value);
+    }
+}
+
+#[test]
+fn packed_normals() {
+    let n = vector(0.5, -2.0);
+    let packed = pack_normal_10_10_10_2(n, 4.0);
+    assert_eq!(packed >> 20, 0);
+    assert!((unpack_normal_10_10_10_2(packed, 4.0) - n).length() < 0.01);
+
+    let packed = pack_normal_snorm8(n, 4.0);
+    assert!((unpack_normal_snorm8(packed, 4.0) - n).length() < 0.05);
+
+    // Out of range normals are clamped.
+    assert_eq!(pack_normal_snorm8(vector(8.0, -8.0), 4.0), [127, -127]);
+    let clamped = unpack_normal_10_10_10_2(pack_normal_10_10_10_2(vector(8.0, -8.0), 4.0), 4.0);
+    assert_eq!(clamped, vector(4.0, -4.0));
+}
+
+#[test]
+fn packed_stroke_vertices() {
+    use crate::geometry_builder::{BuffersBuilder, VertexBuffers};
+    use crate::math::point;
+    use crate::path::Path;
+    use crate::{StrokeOptions, StrokeTessellator};
+
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.end(false);
+    let path = builder.build();
+
+    let options = StrokeOptions::default().with_line_width(2.0);
+    let mut tessellator = StrokeTessellator::new();
+    let mut reference: VertexBuffers<(Point, Vector), u16> = VertexBuffers::new();
+    tessellator
+        .tessellate_path(
+            &path,
+            &options,
+            &mut BuffersBuilder::new(&mut reference, |v: StrokeVertex| (v.position(), v.normal())),
+        )
+        .unwrap();
+
+    let ctor = PackedVertices::new(options.miter_limit);
+    let mut packed: VertexBuffers<PackedNormalVertex, u16> = VertexBuffers::new();
+    tessellator
+        .tessellate_path(&path, &options, &mut BuffersBuilder::new(&mut packed, ctor))
+        .unwrap();
+    let mut half: VertexBuffers<HalfSnorm8Vertex, u16> = VertexBuffers::new();
+    tessellator
+        .tessellate_path(&path, &options, &mut BuffersBuilder::new(&mut half, ctor))
+        .unwrap();
+
+    assert_eq!(std::mem::size_of::<HalfSnorm8Vertex>(), 6);
+    assert_eq!(packed.indices, reference.indices);
+    assert_eq!(half.indices, reference.indices);
+    for ((position, normal), (p, h)) in reference
+        .vertices
+        .iter()
+        .zip(packed.vertices.iter().zip(half.vertices.iter()))
+    {
+        assert_eq!(p.position, position.to_array());
+        let n = unpack_normal_10_10_10_2(p.normal, options.miter_limit);
+        assert!((n - *normal).length() < 0.01);
+
+        let hp = point(f16_to_f32(h.position[0]), f16_to_f32(h.position[1]));
+        assert!((hp - *position).length() < 0.01);
+        let n = unpack_normal_snorm8(h.normal, options.miter_limit);
+        assert!((n - *normal).length() < 0.05);
+    }
+}
@@ -0,0 +1,182 @@
+use crate::{FillTessellator, StrokeTessellator};
+
+use std::ops::{Deref, DerefMut};
+use std::sync::Mutex;
+
+/// A fill tessellator, a stroke tessellator and some user-defined scratch data that are
+/// checked out together from a [`TessellatorPool`](struct.TessellatorPool.html).
+pub struct Tessellators<Scratch = ()> {
+    pub fill: FillTessellator,
+    pub stroke: StrokeTessellator,
+    /// Extra data such as vertex buffers that can be reused across tessellations.
+    pub scratch: Scratch,
+}
+
+impl<Scratch: Default> Tessellators<Scratch> {
+    pub fn new() -> Self {
+        Tessellators {
+            fill: FillTessellator::new(),
+            stroke: StrokeTessellator::new(),
+            scratch: Scratch::default(),
+        }
+    }
+}
+
+impl<Scratch: Default> Default for Tessellators<Scratch> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A thread-safe pool of tessellators.
+///
+/// Tessellators keep their internal allocations between uses, so reusing them is
+/// cheaper than creating new ones for each path. This pool makes it easy to do so when
+/// tessellating in parallel (for example one tile per task): each thread checks out
+/// its own instances, which are returned to the pool when the guard is dropped.
+///
+/// # Example
+///
+/// ```
+/// use lyon_tessellation::{FillOptions, TessellatorPool, VertexBuffers, BuffersBuilder, FillVertex};
+/// use lyon_tessellation::math::{point, Box2D, Point};
+///
+/// let pool: TessellatorPool<VertexBuffers<Point, u16>> = TessellatorPool::new();
+///
+/// std::thread::scope(|s| {
+///     for i in 0..4 {
+///         let pool = &pool;
+///         s.spawn(move || {
+///             let mut tess = pool.get();
+///             let tess = &mut *tess;
+///             tess.scratch.vertices.clear();
+///             tess.scratch.indices.clear();
+///             let rect = Box2D { min: point(0.0, 0.0), max: point(i as f32 + 1.0, 1.0) };
+///             tess.fill.tessellate_rectangle(
+///                 &rect,
+///                 &FillOptions::default(),
+///                 &mut BuffersBuilder::new(&mut tess.scratch, |v: FillVertex| v.position()),
+///             ).unwrap();
+///         });
+///     }
+/// });
+/// ```
+pub struct TessellatorPool<Scratch = ()> {
+    available: Mutex<Vec<Tessellators<Scratch>>>,
+}
+
+impl<Scratch: Default> TessellatorPool<Scratch> {
+    /// Creates an empty pool.
+    pub fn new() -> Self {
+        TessellatorPool {
+            available: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Creates a pool with `count` pre-allocated instances.
+    pub fn with_capacity(count: usize) -> Self {
+        let mut available = Vec::with_capacity(count);
+        available.resize_with(count, Tessellators::new);
+        TessellatorPool {
+            available: Mutex::new(available),
+        }
+    }
+
+    /// Checks out tessellators from the pool, creating new ones if none is available.
+    ///
+    /// The tessellators go back to the pool when the returned guard is dropped.
+    pub fn get(&self) -> PooledTessellators<'_, Scratch> {
+        let tessellators = self.lock().pop().unwrap_or_default();
+
+        PooledTessellators {
+            tessellators: Some(tessellators),
+            pool: self,
+        }
+    }
+
+    /// Runs the callback with tessellators checked out from the pool.
+    pub fn with<R>(&self, callback: impl FnOnce(&mut Tessellators<Scratch>) -> R) -> R {
+        callback(&mut self.get())
+    }
+
+    /// Number of instances currently available in the pool.
+    pub fn available(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Drops all of the available instances.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Tessellators<Scratch>>> {
+        // The pool is only ever modified by push and pop which can't leave it in an
+        // inconsistent state, so it is fine to ignore poisoning.
+        self.available
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<Scratch: Default> Default for TessellatorPool<Scratch> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Tessellators checked out from a [`TessellatorPool`](struct.TessellatorPool.html).
+///
+/// They are returned to the pool on drop.
+pub struct PooledTessellators<'l, Scratch: Default = ()> {
+    tessellators: Option<Tessellators<Scratch>>,
+    pool: &'l TessellatorPool<Scratch>,
+}
+
+impl<'l, Scratch: Default> Deref for PooledTessellators<'l, Scratch> {
+    type Target = Tessellators<Scratch>;
+    fn deref(&self) -> &Tessellators<Scratch> {
+        self.tessellators.as_ref().unwrap()
+    }
+}
+
+impl<'l, Scratch: Default> DerefMut for PooledTessellators<'l, Scratch> {
+    fn deref_mut(&mut self) -> &mut Tessellators<Scratch> {
+        self.tessellators.as_mut().unwrap()
+    }
+}
+
+impl<'l, Scratch: Default> Drop for PooledTessellators<'l, Scratch> {
+    fn drop(&mut self) {
+        if let Some(tessellators) = self.tessellators.take() {
+            self.pool.lock().push(tessellators);
+        }
+    }
+}
+
+#[test]
+fn pool_reuse() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<TessellatorPool<Vec<f32>>>();
+    fn assert_send<T: Send>() {}
+    assert_send::<Tessellators<Vec<f32>>>();
+
+    let pool: TessellatorPool<Vec<u32>> = TessellatorPool::with_capacity(1);
+    assert_eq!(pool.available(), 1);
+    {
+        let mut a = pool.get();
+        a.scratch.push(1);
+        assert_eq!(pool.available(), 0);
+
+        // Another instance is created if none is available.
+        let b = pool.get();
+        assert!(b.scratch.is_empty());
+    }
+    assert_eq!(pool.available(), 2);
+
+    let len = pool.with(|t| t.scratch.len());
+    assert!(len <= 1);
+    assert_eq!(pool.available(), 2);
+
+    pool.clear();
+    assert_eq!(pool.available(), 0);
+}
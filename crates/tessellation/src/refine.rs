@@ -0,0 +1,680 @@
+//! Delaunay refinement of triangle meshes.
+//!
+//! The fill tessellator favors speed over triangle quality and tends to produce long and thin
+//! triangles. This is fine for rendering but some applications, for example vertex shader
+//! deformations, work better with well-shaped triangles. `refine_mesh` post-processes a mesh
+//! toward a conforming Delaunay triangulation with a minimum angle and maximum area criterion,
+//! following Ruppert's algorithm.
+
+use crate::geometry_builder::{GeometryBuilderError, MaxIndex, VertexBuffers};
+use crate::math::Point;
+use crate::VertexId;
+
+const NONE: u32 = u32::MAX;
+
+/// Parameters for `refine_mesh`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct RefineOptions {
+    /// Triangles with an angle smaller than this value (in degrees) are refined.
+    ///
+    /// Values above 20 degrees may prevent the refinement from converging, in which case it
+    /// stops after `max_added_vertices` vertices were inserted.
+    ///
+    /// Default value: `RefineOptions::DEFAULT_MIN_ANGLE`.
+    pub min_angle: f32,
+
+    /// Triangles with an area larger than this value are refined.
+    ///
+    /// Default value: `f32::INFINITY`.
+    pub max_area: f32,
+
+    /// Maximum number of vertices the refinement is allowed to insert.
+    ///
+    /// Input angles smaller than `min_angle` between boundary edges cannot be removed, and
+    /// would otherwise cause the refinement to insert vertices indefinitely.
+    ///
+    /// Default value: `RefineOptions::DEFAULT_MAX_ADDED_VERTICES`.
+    pub max_added_vertices: u32,
+}
+
+impl RefineOptions {
+    pub const DEFAULT_MIN_ANGLE: f32 = 20.0;
+    pub const DEFAULT_MAX_ADDED_VERTICES: u32 = 4096;
+
+    pub const DEFAULT: Self = RefineOptions {
+        min_angle: Self::DEFAULT_MIN_ANGLE,
+        max_area: f32::INFINITY,
+        max_added_vertices: Self::DEFAULT_MAX_ADDED_VERTICES,
+    };
+
+    #[inline]
+    pub fn min_angle(min_angle: f32) -> Self {
+        Self::DEFAULT.with_min_angle(min_angle)
+    }
+
+    #[inline]
+    pub const fn with_min_angle(mut self, min_angle: f32) -> Self {
+        self.min_angle = min_angle;
+        self
+    }
+
+    #[inline]
+    pub const fn with_max_area(mut self, max_area: f32) -> Self {
+        self.max_area = max_area;
+        self
+    }
+
+    #[inline]
+    pub const fn with_max_added_vertices(mut self, max: u32) -> Self {
+        self.max_added_vertices = max;
+        self
+    }
+}
+
+impl Default for RefineOptions {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Refines a triangle mesh toward a conforming Delaunay triangulation.
+///
+/// Edges that belong to a single triangle are considered to be the boundary of the shape.
+/// They are preserved, although they may be split into several edges. Interior edges are
+/// flipped until the mesh is Delaunay, then vertices are inserted at the circumcenter of
+/// the triangles that do not satisfy the criteria of `options`.
+///
+/// The triangle winding of the input is preserved. New vertices are appended to
+/// `buffers.vertices` and `buffers.indices` is rewritten.
+///
+/// The cost of each insertion is linear with the size of the mesh, which makes this
+/// suitable for meshes of moderate size.
+pub fn refine_mesh<OutputIndex>(
+    buffers: &mut VertexBuffers<Point, OutputIndex>,
+    options: &RefineOptions,
+) -> Result<(), GeometryBuilderError>
+where
+    OutputIndex: Copy + Into<VertexId> + MaxIndex,
+    VertexId: Into<OutputIndex>,
+{
+    let mut mesh = Mesh::new(buffers);
+    mesh.make_delaunay();
+    let result = mesh.refine(options, OutputIndex::MAX);
+
+    buffers.vertices = mesh.positions;
+    buffers.indices.clear();
+    for tri in &mesh.triangles {
+        let (b, c) = if mesh.flipped { (2, 1) } else { (1, 2) };
+        buffers.indices.push(VertexId(tri[0]).into());
+        buffers.indices.push(VertexId(tri[b]).into());
+        buffers.indices.push(VertexId(tri[c]).into());
+    }
+
+    result
+}
+
+struct Mesh {
+    positions: Vec<Point>,
+    // Counter-clockwise triangles (in a y-up coordinate system).
+    triangles: Vec<[u32; 3]>,
+    // Neighbor triangle across the edge (tri[i], tri[i + 1]), or NONE on the boundary.
+    neighbors: Vec<[u32; 3]>,
+    // Whether the input triangles were wound the other way.
+    flipped: bool,
+}
+
+impl Mesh {
+    fn new<I: Copy + Into<VertexId>>(buffers: &VertexBuffers<Point, I>) -> Self {
+        let positions = buffers.vertices.clone();
+        let mut triangles: Vec<[u32; 3]> = buffers
+            .indices
+            .chunks_exact(3)
+            .map(|tri| [tri[0].into().0, tri[1].into().0, tri[2].into().0])
+            .collect();
+
+        // Use the orientation of the largest triangle to detect the winding of the input.
+        let mut largest = 0.0;
+        let mut flipped = false;
+        for tri in &triangles {
+            let area = orient(
+                positions[tri[0] as usize],
+                positions[tri[1] as usize],
+                positions[tri[2] as usize],
+            );
+            if area.abs() > largest {
+                largest = area.abs();
+                flipped = area < 0.0;
+            }
+        }
+        if flipped {
+            for tri in &mut triangles {
+                tri.swap(1, 2);
+            }
+        }
+
+        let mut edges = std::collections::HashMap::with_capacity(triangles.len() * 3);
+        for (t, tri) in triangles.iter().enumerate() {
+            for i in 0..3 {
+                edges.insert((tri[i], tri[(i + 1) % 3]), (t as u32, i));
+            }
+        }
+        let mut neighbors = vec![[NONE; 3]; triangles.len()];
+        for (t, tri) in triangles.iter().enumerate() {
+            for i in 0..3 {
+                if let Some(&(other, _)) = edges.get(&(tri[(i + 1) % 3], tri[i])) {
+                    neighbors[t][i] = other;
+                }
+            }
+        }
+
+        Mesh {
+            positions,
+            triangles,
+            neighbors,
+            flipped,
+        }
+    }
+
+    fn position(&self, vertex: u32) -> Point {
+        self.positions[vertex as usize]
+    }
+
+    fn points(&self, t: u32) -> [Point; 3] {
+        let tri = self.triangles[t as usize];
+        [
+            self.position(tri[0]),
+            self.position(tri[1]),
+            self.position(tri[2]),
+        ]
+    }
+
+    fn make_delaunay(&mut self) {
+        let mut stack = Vec::new();
+        for t in 0..self.triangles.len() as u32 {
+            stack.push((t, 0));
+            stack.push((t, 1));
+            stack.push((t, 2));
+        }
+        self.legalize(&mut stack);
+    }
+
+    /// Flips edges until all of the edges in the stack are locally Delaunay.
+    fn legalize(&mut self, stack: &mut Vec<(u32, usize)>) {
+        // Prevent infinite loops caused by co-circular points and float precision.
+        let mut budget = 16 * self.triangles.len() + 64;
+        while let Some((t, i)) = stack.pop() {
+            let u = self.neighbors[t as usize][i];
+            if u == NONE {
+                continue;
+            }
+
+            let [a, b, c] = rotate(self.points(t), i);
+            let j = self.edge_index(u, t);
+            let d = self.position(self.triangles[u as usize][(j + 2) % 3]);
+            if in_circle(a, b, c, d) <= 0.0 {
+                continue;
+            }
+
+            // Only flip if the resulting quadrilateral diagonal is valid.
+            if orient(a, d, c) <= 0.0 || orient(d, b, c) <= 0.0 {
+                continue;
+            }
+
+            if budget == 0 {
+                return;
+            }
+            budget -= 1;
+
+            // Check the four outer edges of the flipped quadrilateral.
+            self.flip(t, i);
+            stack.push((t, 0));
+            stack.push((t, 2));
+            stack.push((u, 0));
+            stack.push((u, 1));
+        }
+    }
+
+    /// Index of the edge of triangle `t` shared with triangle `neighbor`.
+    fn edge_index(&self, t: u32, neighbor: u32) -> usize {
+        let n = &self.neighbors[t as usize];
+        (0..3).find(|&i| n[i] == neighbor).unwrap()
+    }
+
+    fn replace_neighbor(&mut self, t: u32, old: u32, new: u32) {
+        if t == NONE {
+            return;
+        }
+        for n in &mut self.neighbors[t as usize] {
+            if *n == old {
+                *n = new;
+            }
+        }
+    }
+
+    /// Flips the edge `i` of triangle `t`.
+    ///
+    /// The vertex of `t` opposite to the edge ends up at index 2 in both resulting
+    /// triangles, and the edges to legalize next are at index 0.
+    fn flip(&mut self, t: u32, i: usize) {
+        let u = self.neighbors[t as usize][i];
+        let j = self.edge_index(u, t);
+
+        let [a, b, c] = rotate(self.triangles[t as usize], i);
+        let [n_ab, n_bc, n_ca] = rotate(self.neighbors[t as usize], i);
+        debug_assert_eq!(n_ab, u);
+        let [_, _, d] = rotate(self.triangles[u as usize], j);
+        let [_, n_ad, n_db] = rotate(self.neighbors[u as usize], j);
+
+        self.triangles[t as usize] = [a, d, c];
+        self.neighbors[t as usize] = [n_ad, u, n_ca];
+        self.triangles[u as usize] = [d, b, c];
+        self.neighbors[u as usize] = [n_db, n_bc, t];
+
+        self.replace_neighbor(n_ad, u, t);
+        self.replace_neighbor(n_bc, t, u);
+    }
+
+    fn add_vertex(&mut self, position: Point) -> u32 {
+        self.positions.push(position);
+        (self.positions.len() - 1) as u32
+    }
+
+    fn add_triangle(&mut self, tri: [u32; 3], neighbors: [u32; 3]) -> u32 {
+        self.triangles.push(tri);
+        self.neighbors.push(neighbors);
+        (self.triangles.len() - 1) as u32
+    }
+
+    /// Inserts a vertex strictly inside of triangle `t`.
+    fn split_triangle(&mut self, t: u32, position: Point) {
+        let p = self.add_vertex(position);
+        let [a, b, c] = self.triangles[t as usize];
+        let [n_ab, n_bc, n_ca] = self.neighbors[t as usize];
+
+        let t1 = self.triangles.len() as u32;
+        let t2 = t1 + 1;
+        self.triangles[t as usize] = [a, b, p];
+        self.neighbors[t as usize] = [n_ab, t1, t2];
+        self.add_triangle([b, c, p], [n_bc, t2, t]);
+        self.add_triangle([c, a, p], [n_ca, t, t1]);
+
+        self.replace_neighbor(n_bc, t, t1);
+        self.replace_neighbor(n_ca, t, t2);
+
+        self.legalize(&mut vec![(t, 0), (t1, 0), (t2, 0)]);
+    }
+
+    /// Inserts a vertex on the edge `i` of triangle `t`.
+    fn split_edge(&mut self, t: u32, i: usize, position: Point) {
+        let p = self.add_vertex(position);
+        let u = self.neighbors[t as usize][i];
+
+        let [a, b, c] = rotate(self.triangles[t as usize], i);
+        let [_, n_bc, n_ca] = rotate(self.neighbors[t as usize], i);
+
+        let t1 = self.triangles.len() as u32;
+        let (u0, u1) = if u == NONE { (NONE, NONE) } else { (u, t1 + 1) };
+
+        self.triangles[t as usize] = [c, a, p];
+        self.neighbors[t as usize] = [n_ca, u1, t1];
+        self.add_triangle([b, c, p], [n_bc, t, u0]);
+        self.replace_neighbor(n_bc, t, t1);
+
+        let mut stack = vec![(t, 0), (t1, 0)];
+
+        if u != NONE {
+            let j = self.edge_index(u, t);
+            let [_, _, d] = rotate(self.triangles[u as usize], j);
+            let [_, n_ad, n_db] = rotate(self.neighbors[u as usize], j);
+
+            self.triangles[u as usize] = [d, b, p];
+            self.neighbors[u as usize] = [n_db, t1, u1];
+            self.add_triangle([a, d, p], [n_ad, u0, t]);
+            self.replace_neighbor(n_ad, u, u1);
+
+            stack.push((u0, 0));
+            stack.push((u1, 0));
+        }
+
+        self.legalize(&mut stack);
+    }
+
+    /// Whether the boundary edge `i` of triangle `t` is encroached by `p`, that is whether
+    /// `p` lies inside of the diametral circle of the edge.
+    fn is_encroached(&self, t: u32, i: usize, p: Point) -> bool {
+        let [a, b, _] = rotate(self.points(t), i);
+        (a - p).dot(b - p) < 0.0
+    }
+
+    fn is_bad(&self, t: u32, min_angle_ratio: f32, max_area: f32) -> bool {
+        let [a, b, c] = self.points(t);
+        let area = orient(a, b, c) * 0.5;
+        if area <= 0.0 {
+            return false;
+        }
+        if area > max_area {
+            return true;
+        }
+
+        // The ratio between the circumradius and the shortest edge is 1 / (2 sin(min_angle)).
+        let ab = (b - a).square_length();
+        let bc = (c - b).square_length();
+        let ca = (a - c).square_length();
+        let shortest = ab.min(bc).min(ca);
+        let circumradius_sq = ab * bc * ca / (16.0 * area * area);
+
+        circumradius_sq > min_angle_ratio * min_angle_ratio * shortest
+    }
+
+    fn refine(
+        &mut self,
+        options: &RefineOptions,
+        max_index: usize,
+    ) -> Result<(), GeometryBuilderError> {
+        let sin = options.min_angle.to_radians().sin();
+        let min_angle_ratio = if sin > 0.0 { 0.5 / sin } else { f32::INFINITY };
+        let max_vertices = self
+            .positions
+            .len()
+            .saturating_add(options.max_added_vertices as usize);
+
+        // Triangles that could not be refined.
+        let mut skip = vec![false; self.triangles.len()];
+
+        loop {
+            if self.positions.len() >= max_vertices {
+                return Ok(());
+            }
+            if self.positions.len() > max_index {
+                return Err(GeometryBuilderError::TooManyVertices);
+            }
+
+            skip.resize(self.triangles.len(), false);
+
+            // First split boundary edges encroached by the opposite vertex of their triangle.
+            if let Some((t, i)) = self.find_encroached_edge() {
+                let [a, b, _] = rotate(self.points(t), i);
+                self.split_edge(t, i, a.lerp(b, 0.5));
+                continue;
+            }
+
+            let bad = (0..self.triangles.len() as u32)
+                .find(|&t| !skip[t as usize] && self.is_bad(t, min_angle_ratio, options.max_area));
+            let t = match bad {
+                Some(t) => t,
+                None => return Ok(()),
+            };
+
+            let center = circumcenter(self.points(t));
+
+            // If the circumcenter encroaches upon boundary edges, split them instead.
+            let mut split_boundary = false;
+            for u in 0..self.triangles.len() as u32 {
+                for i in 0..3 {
+                    if self.neighbors[u as usize][i] == NONE && self.is_encroached(u, i, center) {
+                        let [a, b, _] = rotate(self.points(u), i);
+                        self.split_edge(u, i, a.lerp(b, 0.5));
+                        split_boundary = true;
+                        break;
+                    }
+                }
+                if split_boundary {
+                    break;
+                }
+            }
+            if split_boundary {
+                continue;
+            }
+
+            match self.locate(t, center) {
+                Location::Inside(u) => self.split_triangle(u, center),
+                Location::OnEdge(u, i) => self.split_edge(u, i, center),
+                Location::Outside(u, i) => {
+                    let [a, b, _] = rotate(self.points(u), i);
+                    self.split_edge(u, i, a.lerp(b, 0.5));
+                }
+                Location::Unknown => {
+                    skip[t as usize] = true;
+                }
+            }
+        }
+    }
+
+    fn find_encroached_edge(&self) -> Option<(u32, usize)> {
+        for t in 0..self.triangles.len() as u32 {
+            for i in 0..3 {
+                if self.neighbors[t as usize][i] != NONE {
+                    continue;
+                }
+                let opposite = self.position(self.triangles[t as usize][(i + 2) % 3]);
+                if self.is_encroached(t, i, opposite) {
+                    return Some((t, i));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Walks from triangle `t` toward `p`.
+    fn locate(&self, mut t: u32, p: Point) -> Location {
+        for _ in 0..self.triangles.len() + 3 {
+            let pts = self.points(t);
+            let mut next = None;
+            let mut on_edge = None;
+            for i in 0..3 {
+                let o = orient(pts[i], pts[(i + 1) % 3], p);
+                let len = (pts[(i + 1) % 3] - pts[i]).length();
+                if o < -1e-5 * len {
+                    next = Some(i);
+                    break;
+                }
+                if o <= 1e-5 * len {
+                    on_edge = Some(i);
+                }
+            }
+
+            match (next, on_edge) {
+                (Some(i), _) => {
+                    let n = self.neighbors[t as usize][i];
+                    if n == NONE {
+                        return Location::Outside(t, i);
+                    }
+                    t = n;
+                }
+                (None, Some(i)) => {
+                    return Location::OnEdge(t, i);
+                }
+                (None, None) => {
+                    return Location::Inside(t);
+                }
+            }
+        }
+
+        Location::Unknown
+    }
+}
+
+enum Location {
+    Inside(u32),
+    OnEdge(u32, usize),
+    Outside(u32, usize),
+    Unknown,
+}
+
+fn rotate<T: Copy>(v: [T; 3], i: usize) -> [T; 3] {
+    [v[i], v[(i + 1) % 3], v[(i + 2) % 3]]
+}
+
+fn orient(a: Point, b: Point, c: Point) -> f32 {
+    (b - a).cross(c - a)
+}
+
+/// Positive if `d` is inside of the circumcircle of the counter-clockwise triangle `a, b, c`.
+fn in_circle(a: Point, b: Point, c: Point, d: Point) -> f64 {
+    let (ax, ay) = (a.x as f64 - d.x as f64, a.y as f64 - d.y as f64);
+    let (bx, by) = (b.x as f64 - d.x as f64, b.y as f64 - d.y as f64);
+    let (cx, cy) = (c.x as f64 - d.x as f64, c.y as f64 - d.y as f64);
+
+    let det = (ax * ax + ay * ay) * (bx * cy - cx * by) - (bx * bx + by * by) * (ax * cy - cx * ay)
+        + (cx * cx + cy * cy) * (ax * by - bx * ay);
+
+    // Treat nearly co-circular points as not inside to avoid flipping back and forth.
+    let scale = (ax * ax + ay * ay)
+        .max(bx * bx + by * by)
+        .max(cx * cx + cy * cy);
+    if det > scale * scale * 1e-10 {
+        det
+    } else {
+        0.0
+    }
+}
+
+fn circumcenter([a, b, c]: [Point; 3]) -> Point {
+    let b = b - a;
+    let c = c - a;
+    let d = 2.0 * b.cross(c);
+    let b2 = b.square_length();
+    let c2 = c.square_length();
+
+    Point::new(
+        a.x + (c.y * b2 - b.y * c2) / d,
+        a.y + (b.x * c2 - c.x * b2) / d,
+    )
+}
+
+#[cfg(test)]
+fn min_angle(buffers: &VertexBuffers<Point, u32>) -> f32 {
+    let mut result = f32::INFINITY;
+    for tri in buffers.indices.chunks(3) {
+        for i in 0..3 {
+            let a = buffers.vertices[tri[i] as usize];
+            let b = buffers.vertices[tri[(i + 1) % 3] as usize];
+            let c = buffers.vertices[tri[(i + 2) % 3] as usize];
+            result = result.min((b - a).angle_to(c - a).radians.abs().to_degrees());
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+fn total_area(buffers: &VertexBuffers<Point, u32>) -> f32 {
+    buffers
+        .indices
+        .chunks(3)
+        .map(|tri| {
+            let a = buffers.vertices[tri[0] as usize];
+            let b = buffers.vertices[tri[1] as usize];
+            let c = buffers.vertices[tri[2] as usize];
+            orient(a, b, c) * 0.5
+        })
+        .sum()
+}
+
+#[test]
+fn refine_rectangle() {
+    use crate::geometry_builder::Positions;
+    use crate::math::{point, Box2D};
+    use crate::path::{Path, Winding};
+    use crate::{BuffersBuilder, FillOptions, FillTessellator};
+
+    let mut builder = Path::builder();
+    builder.add_rectangle(
+        &Box2D::new(point(0.0, 0.0), point(100.0, 5.0)),
+        Winding::Positive,
+    );
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<Point, u32> = VertexBuffers::new();
+    FillTessellator::new()
+        .tessellate_path(
+            &path,
+            &FillOptions::default(),
+            &mut BuffersBuilder::new(&mut buffers, Positions),
+        )
+        .unwrap();
+
+    let area = total_area(&buffers);
+    assert!(min_angle(&buffers) < 20.0);
+
+    refine_mesh(&mut buffers, &RefineOptions::DEFAULT).unwrap();
+
+    assert!(min_angle(&buffers) >= 20.0);
+    // Same winding and same covered area.
+    assert!((total_area(&buffers) - area).abs() < 0.01);
+    assert!(buffers.vertices.len() > 4);
+}
+
+#[test]
+fn refine_max_area() {
+    let mut buffers: VertexBuffers<Point, u32> = VertexBuffers {
+        vertices: vec![
+            Point::new(0.0, 0.0),
+            Point::new(10.0, 0.0),
+            Point::new(10.0, 10.0),
+            Point::new(0.0, 10.0),
+        ],
+        indices: vec![0, 1, 2, 0, 2, 3],
+    };
+
+    refine_mesh(&mut buffers, &RefineOptions::DEFAULT.with_max_area(5.0)).unwrap();
+
+    assert!((total_area(&buffers) - 100.0).abs() < 0.01);
+    for tri in buffers.indices.chunks(3) {
+        let a = buffers.vertices[tri[0] as usize];
+        let b = buffers.vertices[tri[1] as usize];
+        let c = buffers.vertices[tri[2] as usize];
+        assert!(orient(a, b, c) * 0.5 <= 5.0);
+    }
+}
+
+#[test]
+fn refine_vertex_budget() {
+    let mut buffers: VertexBuffers<Point, u32> = VertexBuffers {
+        vertices: vec![
+            Point::new(0.0, 0.0),
+            Point::new(10.0, 0.0),
+            Point::new(10.0, 10.0),
+            Point::new(0.0, 10.0),
+        ],
+        indices: vec![0, 1, 2, 0, 2, 3],
+    };
+
+    let options = RefineOptions::DEFAULT
+        .with_max_area(0.0001)
+        .with_max_added_vertices(10);
+    refine_mesh(&mut buffers, &options).unwrap();
+
+    assert_eq!(buffers.vertices.len(), 14);
+}
+
+#[test]
+fn refine_circle_with_hole() {
+    use crate::geometry_builder::Positions;
+    use crate::math::point;
+    use crate::path::{Path, Winding};
+    use crate::{BuffersBuilder, FillOptions, FillTessellator};
+
+    let mut builder = Path::builder();
+    builder.add_circle(point(0.0, 0.0), 50.0, Winding::Positive);
+    builder.add_circle(point(10.0, 5.0), 20.0, Winding::Negative);
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<Point, u32> = VertexBuffers::new();
+    FillTessellator::new()
+        .tessellate_path(
+            &path,
+            &FillOptions::tolerance(0.5),
+            &mut BuffersBuilder::new(&mut buffers, Positions),
+        )
+        .unwrap();
+
+    let area = total_area(&buffers);
+    refine_mesh(&mut buffers, &RefineOptions::DEFAULT.with_max_area(50.0)).unwrap();
+
+    assert!(min_angle(&buffers) >= 20.0);
+    assert!((total_area(&buffers) - area).abs() < area.abs() * 0.0001);
+}
@@ -0,0 +1,381 @@
+//! Snapshots of tessellated meshes for regression tests.
+//!
+//! The exact output of the tessellators can change in insignificant ways: vertices and
+//! triangles may be produced in a different order and positions may differ by tiny amounts
+//! due to floating point rounding. A [`MeshSnapshot`](struct.MeshSnapshot.html) normalizes
+//! a mesh so that such changes don't cause spurious test failures:
+//!
+//! - positions are quantized to a grid of a configurable step,
+//! - each triangle is rotated to start with its smallest vertex, preserving its winding,
+//! - triangles are sorted.
+//!
+//! Snapshots are stored in a simple text format (one triangle per line) that produces
+//! readable diffs in version control, and can be compared against a stored "golden" file
+//! with [`check_golden`](fn.check_golden.html). When a snapshot does not match,
+//! [`SnapshotDiff`](struct.SnapshotDiff.html) lists the triangles that are missing and the
+//! ones that are unexpected.
+//!
+//! # Example
+//!
+//! ```
+//! use lyon_tessellation::geometry_builder::simple_builder;
+//! use lyon_tessellation::math::{point, Box2D, Point};
+//! use lyon_tessellation::snapshot::MeshSnapshot;
+//! use lyon_tessellation::{FillOptions, FillTessellator, VertexBuffers};
+//!
+//! let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+//! FillTessellator::new().tessellate_rectangle(
+//!     &Box2D { min: point(0.0, 0.0), max: point(1.0, 1.0) },
+//!     &FillOptions::default(),
+//!     &mut simple_builder(&mut buffers),
+//! ).unwrap();
+//!
+//! let snapshot = MeshSnapshot::from_buffers(&buffers, |p| *p, 0.01);
+//! assert_eq!(snapshot.triangles().len(), 2);
+//!
+//! // Snapshots can be stored as text and parsed back.
+//! let text = snapshot.to_string();
+//! assert_eq!(text.parse::<MeshSnapshot>().unwrap(), snapshot);
+//!
+//! // In a test:
+//! // check_golden(&snapshot, "tests/goldens/rectangle.txt").unwrap();
+//! ```
+
+use crate::geometry_builder::VertexBuffers;
+use crate::math::{point, Point};
+use crate::VertexId;
+
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+const HEADER: &str = "lyon mesh snapshot v1";
+
+/// The environment variable that makes `check_golden` overwrite the golden files.
+pub const UPDATE_GOLDENS_VAR: &str = "LYON_UPDATE_GOLDENS";
+
+/// A vertex position, quantized to the grid of a snapshot.
+pub type QuantizedPoint = [i32; 2];
+
+/// A triangle with quantized vertex positions.
+pub type QuantizedTriangle = [QuantizedPoint; 3];
+
+/// A normalized representation of a mesh.
+///
+/// See the [module documentation](index.html).
+#[derive(Clone, Debug, PartialEq)]
+pub struct MeshSnapshot {
+    step: f32,
+    triangles: Vec<QuantizedTriangle>,
+}
+
+impl MeshSnapshot {
+    /// Creates a snapshot from a list of triangles.
+    ///
+    /// Positions are quantized to a grid of size `step`, which should be small compared
+    /// to the size of the geometry but large compared to numerical errors, for example
+    /// a tenth of the tessellation tolerance.
+    pub fn new(step: f32, triangles: impl IntoIterator<Item = [Point; 3]>) -> Self {
+        assert!(step > 0.0);
+        let quantize = |p: Point| [(p.x / step).round() as i32, (p.y / step).round() as i32];
+        let mut triangles: Vec<QuantizedTriangle> = triangles
+            .into_iter()
+            .map(|tri| normalize(tri.map(quantize)))
+            .collect();
+        triangles.sort_unstable();
+
+        MeshSnapshot { step, triangles }
+    }
+
+    /// Creates a snapshot from vertex and index buffers.
+    ///
+    /// `position` reads the position of a vertex.
+    pub fn from_buffers<V, I>(
+        buffers: &VertexBuffers<V, I>,
+        position: impl Fn(&V) -> Point,
+        step: f32,
+    ) -> Self
+    where
+        I: Copy + Into<VertexId>,
+    {
+        let p = |index: I| position(&buffers.vertices[index.into().to_usize()]);
+        MeshSnapshot::new(
+            step,
+            buffers
+                .indices
+                .chunks_exact(3)
+                .map(|tri| [p(tri[0]), p(tri[1]), p(tri[2])]),
+        )
+    }
+
+    /// The size of the quantization grid.
+    #[inline]
+    pub fn step(&self) -> f32 {
+        self.step
+    }
+
+    /// The normalized triangles, in sorted order.
+    #[inline]
+    pub fn triangles(&self) -> &[QuantizedTriangle] {
+        &self.triangles
+    }
+
+    /// Converts a quantized position back to a position.
+    #[inline]
+    pub fn position(&self, p: QuantizedPoint) -> Point {
+        point(p[0] as f32 * self.step, p[1] as f32 * self.step)
+    }
+
+    /// Computes the differences between this snapshot and an expected one.
+    pub fn diff(&self, expected: &MeshSnapshot) -> SnapshotDiff {
+        let mut diff = SnapshotDiff {
+            step_mismatch: self.step != expected.step,
+            missing: Vec::new(),
+            unexpected: Vec::new(),
+        };
+
+        // Both lists are sorted.
+        let (mut i, mut j) = (0, 0);
+        while i < self.triangles.len() || j < expected.triangles.len() {
+            match (self.triangles.get(i), expected.triangles.get(j)) {
+                (Some(a), Some(b)) if a == b => {
+                    i += 1;
+                    j += 1;
+                }
+                (Some(a), Some(b)) if a < b => {
+                    diff.unexpected.push(*a);
+                    i += 1;
+                }
+                (Some(a), None) => {
+                    diff.unexpected.push(*a);
+                    i += 1;
+                }
+                (_, Some(b)) => {
+                    diff.missing.push(*b);
+                    j += 1;
+                }
+                (None, None) => unreachable!(),
+            }
+        }
+
+        diff
+    }
+}
+
+/// Rotates a triangle so that it starts with its smallest vertex.
+fn normalize(tri: QuantizedTriangle) -> QuantizedTriangle {
+    let mut first = 0;
+    for i in 1..3 {
+        if tri[i] < tri[first] {
+            first = i;
+        }
+    }
+
+    [tri[first], tri[(first + 1) % 3], tri[(first + 2) % 3]]
+}
+
+impl fmt::Display for MeshSnapshot {
+    /// Writes the snapshot in its text format.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", HEADER)?;
+        writeln!(f, "step {:?}", self.step)?;
+        for tri in &self.triangles {
+            writeln!(
+                f,
+                "{} {} {} {} {} {}",
+                tri[0][0], tri[0][1], tri[1][0], tri[1][1], tri[2][0], tri[2][1]
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for MeshSnapshot {
+    type Err = SnapshotError;
+
+    fn from_str(text: &str) -> Result<Self, SnapshotError> {
+        let mut lines = text.lines().enumerate();
+        let error = |line: usize| SnapshotError::Parse { line: line + 1 };
+
+        match lines.next() {
+            Some((_, line)) if line.trim() == HEADER => {}
+            _ => return Err(error(0)),
+        }
+
+        let step = match lines.next() {
+            Some((n, line)) => line
+                .trim()
+                .strip_prefix("step ")
+                .and_then(|step| step.parse::<f32>().ok())
+                .filter(|step| *step > 0.0)
+                .ok_or_else(|| error(n))?,
+            None => return Err(error(1)),
+        };
+
+        let mut triangles = Vec::new();
+        for (n, line) in lines {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut values = [0; 6];
+            let mut tokens = line.split_whitespace();
+            for value in &mut values {
+                *value = tokens
+                    .next()
+                    .and_then(|token| token.parse().ok())
+                    .ok_or_else(|| error(n))?;
+            }
+            if tokens.next().is_some() {
+                return Err(error(n));
+            }
+            triangles.push(normalize([
+                [values[0], values[1]],
+                [values[2], values[3]],
+                [values[4], values[5]],
+            ]));
+        }
+        triangles.sort_unstable();
+
+        Ok(MeshSnapshot { step, triangles })
+    }
+}
+
+/// The differences between two snapshots.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SnapshotDiff {
+    /// Whether the snapshots use a different quantization step, in which case all of their
+    /// triangles are likely to differ.
+    pub step_mismatch: bool,
+    /// Triangles of the expected snapshot that are not in the actual one.
+    pub missing: Vec<QuantizedTriangle>,
+    /// Triangles of the actual snapshot that are not in the expected one.
+    pub unexpected: Vec<QuantizedTriangle>,
+}
+
+impl SnapshotDiff {
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        !self.step_mismatch && self.missing.is_empty() && self.unexpected.is_empty()
+    }
+}
+
+impl fmt::Display for SnapshotDiff {
+    /// Summarizes the differences, listing at most 20 triangles of each kind.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        const MAX_LISTED: usize = 20;
+        if self.step_mismatch {
+            writeln!(f, "the quantization steps differ")?;
+        }
+        for (sign, name, triangles) in [
+            ('-', "missing", &self.missing),
+            ('+', "unexpected", &self.unexpected),
+        ] {
+            if triangles.is_empty() {
+                continue;
+            }
+            writeln!(f, "{} {} triangles:", triangles.len(), name)?;
+            for tri in triangles.iter().take(MAX_LISTED) {
+                writeln!(f, "{} {:?} {:?} {:?}", sign, tri[0], tri[1], tri[2])?;
+            }
+            if triangles.len() > MAX_LISTED {
+                writeln!(f, "{} ...", sign)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Errors returned when reading or comparing snapshots.
+#[derive(Error, Debug)]
+pub enum SnapshotError {
+    #[error("Invalid snapshot at line {line}")]
+    Parse { line: usize },
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("The snapshot does not match the golden file:\n{0}")]
+    Mismatch(SnapshotDiff),
+}
+
+/// Compares a snapshot with the golden file at `path`.
+///
+/// If the file does not exist or if the `LYON_UPDATE_GOLDENS` environment variable is set,
+/// the golden file is written instead (creating the parent directories if needed).
+pub fn check_golden(
+    snapshot: &MeshSnapshot,
+    path: impl AsRef<std::path::Path>,
+) -> Result<(), SnapshotError> {
+    let path = path.as_ref();
+    if std::env::var_os(UPDATE_GOLDENS_VAR).is_some() || !path.exists() {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, snapshot.to_string())?;
+        return Ok(());
+    }
+
+    let expected: MeshSnapshot = std::fs::read_to_string(path)?.parse()?;
+    let diff = snapshot.diff(&expected);
+    if !diff.is_empty() {
+        return Err(SnapshotError::Mismatch(diff));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn snapshot_normalization() {
+    let a = point(0.0, 0.0);
+    let b = point(1.0, 0.0);
+    let c = point(1.0, 1.0);
+    let d = point(0.0, 1.0);
+
+    let snapshot = MeshSnapshot::new(0.1, [[a, b, c], [c, d, a]]);
+
+    // Different order, rotated triangles and small numerical differences.
+    let eps = point(0.001, -0.002).to_vector();
+    let other = MeshSnapshot::new(0.1, [[d, a, c + eps], [b, c, a]]);
+    assert_eq!(snapshot, other);
+    assert!(snapshot.diff(&other).is_empty());
+
+    // The winding is preserved.
+    let flipped = MeshSnapshot::new(0.1, [[a, c, b], [c, d, a]]);
+    let diff = flipped.diff(&snapshot);
+    assert_eq!(diff.missing, vec![[[0, 0], [10, 0], [10, 10]]]);
+    assert_eq!(diff.unexpected, vec![[[0, 0], [10, 10], [10, 0]]]);
+    assert!(diff.to_string().contains("1 missing triangles"));
+
+    let parsed: MeshSnapshot = snapshot.to_string().parse().unwrap();
+    assert_eq!(parsed, snapshot);
+    assert_eq!(parsed.position([10, 10]), point(1.0, 1.0));
+    assert!(matches!(
+        "lyon mesh snapshot v1\nstep 0.1\n1 2 3".parse::<MeshSnapshot>(),
+        Err(SnapshotError::Parse { line: 3 })
+    ));
+}
+
+#[test]
+fn golden_files() {
+    let dir = std::env::temp_dir().join(format!("lyon-snapshot-{}", std::process::id()));
+    let path = dir.join("goldens/triangle.txt");
+    let a = point(0.0, 0.0);
+    let b = point(1.0, 0.0);
+    let c = point(1.0, 1.0);
+
+    let snapshot = MeshSnapshot::new(0.01, [[a, b, c]]);
+    // Written the first time.
+    check_golden(&snapshot, &path).unwrap();
+    check_golden(&snapshot, &path).unwrap();
+
+    let other = MeshSnapshot::new(0.01, [[a, b, point(2.0, 1.0)]]);
+    match check_golden(&other, &path) {
+        Err(SnapshotError::Mismatch(diff)) => {
+            assert_eq!(diff.missing.len(), 1);
+            assert_eq!(diff.unexpected.len(), 1);
+        }
+        _ => panic!(),
+    }
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -1,26 +1,38 @@
 // There's a number of cases in this file where this lint just complicates the code.
 #![allow(clippy::needless_range_loop)]
 
+use crate::budget::tessellate_within_limits;
+use crate::fringe::StrokeFringe;
 use crate::geom::arrayvec::ArrayVec;
+use crate::geom::euclid::default::{Point2D, Vector2D};
 use crate::geom::utils::tangent;
-use crate::geom::{CubicBezierSegment, Line, LineSegment, QuadraticBezierSegment};
+use crate::geom::{CubicBezierSegment, Line, LineSegment, QuadraticBezierSegment, Scalar};
+use crate::geometry_builder::{MaxIndex, Oriented};
 use crate::math::*;
 use crate::math_utils::compute_normal;
 use crate::path::builder::{Build, NoAttributes, PathBuilder};
+use crate::path::commands::PathCommands;
 use crate::path::polygon::Polygon;
 use crate::path::private::DebugValidator;
 use crate::path::{
-    AttributeStore, Attributes, EndpointId, IdEvent, PathEvent, PathSlice, PositionStore, Winding,
+    AttributeStore, Attributes, EndpointId, EventId, IdEvent, PathEvent, PathSlice, PositionStore,
+    Winding,
 };
+use crate::trace::{trace_output_span, trace_span};
 use crate::{
-    LineCap, LineJoin, Side, SimpleAttributeStore, StrokeGeometryBuilder, StrokeOptions,
-    TessellationError, TessellationResult, VertexId, VertexSource,
+    interpolate_attributes, AttributeIndex, AttributeInterpolation, BuffersBuilder, DashArray,
+    DotShape, FillGeometryBuilder, FillOptions, FillRule, FillTessellator, GeometryBuilder,
+    GeometryBuilderError, LineCap, LineGeometryBuilder, LineJoin, Order, Side,
+    SimpleAttributeStore, StrokeGeometryBuilder, StrokeOptions, StrokeOptionsOverride, StrokePart,
+    TessellationError, TessellationResult, UnsupportedParamater, VertexBuffers, VertexId,
+    VertexSource,
 };
 
 use std::f32::consts::PI;
+use std::ops::Add;
 
-const SIDE_POSITIVE: usize = 0;
-const SIDE_NEGATIVE: usize = 1;
+pub(crate) const SIDE_POSITIVE: usize = 0;
+pub(crate) const SIDE_NEGATIVE: usize = 1;
 
 macro_rules! nan_check {
     ($($v:expr),+) => { $(debug_assert!(!$v.is_nan());)+ };
@@ -41,7 +53,8 @@ macro_rules! nan_check {
 /// be the desired behavior. This needs to be kept in mind when rendering transparent
 /// SVG strokes since the spec mandates that each point along a semi-transparent path
 /// is shaded once no matter how many times the path overlaps with itself at this
-/// location.
+/// location. Use [`tessellate_non_overlapping`](#method.tessellate_non_overlapping)
+/// for this case.
 ///
 /// `StrokeTessellator` exposes a similar interface to its
 /// [fill equivalent](struct.FillTessellator.html).
@@ -104,6 +117,9 @@ macro_rules! nan_check {
 pub struct StrokeTessellator {
     attrib_buffer: Vec<f32>,
     builder_attrib_store: SimpleAttributeStore,
+    // Used by `tessellate_non_overlapping`.
+    fill_tessellator: FillTessellator,
+    stroke_triangles: VertexBuffers<Point, u32>,
 }
 
 impl StrokeTessellator {
@@ -111,6 +127,8 @@ impl StrokeTessellator {
         StrokeTessellator {
             attrib_buffer: Vec::new(),
             builder_attrib_store: SimpleAttributeStore::new(0),
+            fill_tessellator: FillTessellator::new(),
+            stroke_triangles: VertexBuffers::new(),
         }
     }
 
@@ -126,10 +144,32 @@ impl StrokeTessellator {
             "Varible line width requires custom attributes. Try tessellate_with_ids or tessellate_path",
         );
 
-        let mut buffer = Vec::new();
-        let stroker = StrokeBuilderImpl::new(options, &mut buffer, builder);
+        if needs_endpoint_ids(options) {
+            // Tapering needs endpoint ids to attach the line width to, normalizing the
+            // advancement needs to go over the events twice and the anti-aliasing fringe
+            // needs the attributes of the vertices after the tessellation.
+            let path: crate::path::Path = input.into_iter().collect();
+            return self.tessellate_with_ids(path.id_iter(), &path, None, options, builder);
+        }
 
-        stroker.tessellate_fw(input)
+        trace_output_span!("stroke", builder: dyn StrokeGeometryBuilder);
+
+        let limits = options.output_limits();
+        if limits.is_unlimited() {
+            let mut buffer = Vec::new();
+            let stroker = StrokeBuilderImpl::new(options, &mut buffer, builder);
+
+            return stroker.tessellate_fw(input);
+        }
+
+        let events: Vec<PathEvent> = input.into_iter().collect();
+        tessellate_within_limits(limits, options.tolerance, builder, |tolerance, output| {
+            let options = options.with_tolerance(tolerance);
+            let mut buffer = Vec::new();
+            let stroker = StrokeBuilderImpl::new(&options, &mut buffer, output);
+
+            stroker.tessellate_fw(events.iter().cloned())
+        })
     }
 
     /// Compute the tessellation from a path iterator.
@@ -143,14 +183,290 @@ impl StrokeTessellator {
     ) -> TessellationResult {
         let custom_attributes = custom_attributes.unwrap_or(&());
 
+        if is_tapered(options) {
+            let events: Vec<IdEvent> = path.into_iter().collect();
+            let attributes =
+                WidthAttributeStore::tapered(&events, positions, custom_attributes, options);
+            let options = StrokeOptions {
+                line_width: 1.0,
+                variable_line_width: Some(attributes.width_index()),
+                start_width: None,
+                end_width: None,
+                ..*options
+            };
+            return self.tessellate_with_ids(
+                events,
+                positions,
+                Some(&attributes),
+                &options,
+                output,
+            );
+        }
+
+        if options.periodic_line_width && options.variable_line_width.is_some() {
+            let events: Vec<IdEvent> = path.into_iter().collect();
+            let attributes =
+                SeamAttributeStore::new(&events, positions, custom_attributes, options);
+            let options = options.with_periodic_line_width(false);
+            return self.tessellate_with_ids(
+                events,
+                positions,
+                Some(&attributes),
+                &options,
+                output,
+            );
+        }
+
+        if let Some(width) = options.anti_aliasing_fringe {
+            let mut fringe = StrokeFringe::new(output, width, options.attribute_interpolation);
+            let options = StrokeOptions {
+                anti_aliasing_fringe: None,
+                ..*options
+            };
+            self.tessellate_with_ids(
+                path,
+                positions,
+                Some(custom_attributes),
+                &options,
+                &mut fringe,
+            )?;
+
+            return fringe.finish(custom_attributes);
+        }
+
+        trace_output_span!("stroke", output: dyn StrokeGeometryBuilder);
+
         self.attrib_buffer.clear();
         for _ in 0..custom_attributes.num_attributes() {
             self.attrib_buffer.push(0.0);
         }
 
-        let stroker = StrokeBuilderImpl::new(options, &mut self.attrib_buffer, output);
+        let limits = options.output_limits();
+
+        if options.normalized_advancement {
+            let events: Vec<IdEvent> = path.into_iter().collect();
+            let options = options.with_normalized_advancement(false);
+            let attrib_buffer = &mut self.attrib_buffer;
+            let mut tessellate = |tolerance: f32, output: &mut dyn StrokeGeometryBuilder| {
+                tessellate_normalized(
+                    &events,
+                    positions,
+                    custom_attributes,
+                    &options.with_tolerance(tolerance),
+                    attrib_buffer,
+                    output,
+                )
+            };
+            if limits.is_unlimited() {
+                return tessellate(options.tolerance, output);
+            }
+            return tessellate_within_limits(limits, options.tolerance, output, |t, o| {
+                tessellate(t, o)
+            });
+        }
+
+        if limits.is_unlimited() {
+            let stroker = StrokeBuilderImpl::new(options, &mut self.attrib_buffer, output);
+
+            return stroker.tessellate_with_ids(path, positions, custom_attributes);
+        }
+
+        let events: Vec<IdEvent> = path.into_iter().collect();
+        tessellate_within_limits(limits, options.tolerance, output, |tolerance, output| {
+            let options = options.with_tolerance(tolerance);
+            let stroker = StrokeBuilderImpl::new(&options, &mut self.attrib_buffer, output);
+
+            stroker.tessellate_with_ids(events.iter().cloned(), positions, custom_attributes)
+        })
+    }
+
+    /// Compute the tessellation of path commands, overriding some options for individual
+    /// events.
+    ///
+    /// This renders parts of a path with different styles in a single tessellation.
+    /// `overrides` is called once for each event of the path:
+    ///
+    /// - For `Begin` events, `start_cap` sets the cap at the start of the sub-path.
+    /// - For edges, `line_width` sets the width of the edge and `line_join` the join at
+    ///   its end.
+    /// - For `End` events, `end_cap` sets the cap at the end of the sub-path. If the
+    ///   sub-path is closed, `line_width` and `line_join` apply to the closing edge.
+    ///
+    /// The other options of each event are ignored.
+    ///
+    /// The line width of a stroke is continuous: it is set at the start of each edge and
+    /// changes linearly along the edge towards the width of the next one. The last endpoint
+    /// of an open sub-path uses the width of the last edge. If `variable_line_width` is set,
+    /// its factor is applied to the width.
+    ///
+    /// Tapering, normalized advancement, periodic line width and the anti-aliasing fringe
+    /// are not supported by this method, it returns
+    /// `UnsupportedParamater::StrokeOptionIsNotSupported` if any of them is set.
+    pub fn tessellate_commands_with_overrides(
+        &mut self,
+        commands: &PathCommands,
+        positions: &impl PositionStore,
+        custom_attributes: Option<&dyn AttributeStore>,
+        options: &StrokeOptions,
+        overrides: &dyn Fn(EventId) -> Option<StrokeOptionsOverride>,
+        output: &mut dyn StrokeGeometryBuilder,
+    ) -> TessellationResult {
+        check_unsupported_options(
+            options,
+            &[
+                StrokeFeature::Tapering,
+                StrokeFeature::NormalizedAdvancement,
+                StrokeFeature::PeriodicLineWidth,
+                StrokeFeature::AntiAliasingFringe,
+            ],
+        )?;
+
+        let custom_attributes = custom_attributes.unwrap_or(&());
+
+        let mut events = Vec::new();
+        let mut event_overrides = Vec::new();
+        let mut id = commands.iter().next().map(|_| EventId(0));
+        while let Some(event_id) = id {
+            events.push(commands.event(event_id));
+            event_overrides.push(overrides(event_id).unwrap_or(StrokeOptionsOverride::NONE));
+            id = commands.next_event_id_in_path(event_id);
+        }
+
+        // Resolve the width of each endpoint, and the join and caps of each event.
+        let mut widths = WidthAttributeStore::new(custom_attributes.num_attributes());
+        let mut styles =
+            vec![(options.line_join, options.start_cap, options.end_cap); events.len()];
+        let mut set_width = |id: EndpointId, width: Option<f32>| {
+            let source = custom_attributes.get(id);
+            let mut width = width.unwrap_or(options.line_width);
+            if let Some(idx) = options.variable_line_width {
+                width *= source[idx];
+            }
+            // The stroker divides by the line width.
+            widths.set(id, source, width.max(options.tolerance * 0.01));
+        };
+        let mut begin = 0;
+        for (idx, event) in events.iter().enumerate() {
+            let (last, first, close) = match *event {
+                IdEvent::Begin { .. } => {
+                    begin = idx;
+                    continue;
+                }
+                IdEvent::End { last, first, close } => (last, first, close),
+                _ => continue,
+            };
+
+            let end_overrides = &event_overrides[idx];
+            let start_cap = event_overrides[begin]
+                .start_cap
+                .unwrap_or(options.start_cap);
+            let end_cap = end_overrides.end_cap.unwrap_or(options.end_cap);
+            let mut last_width = None;
+            for i in begin..=idx {
+                let line_join = match events[i] {
+                    IdEvent::Line { from, .. }
+                    | IdEvent::Quadratic { from, .. }
+                    | IdEvent::Cubic { from, .. } => {
+                        last_width = event_overrides[i].line_width;
+                        set_width(from, last_width);
+                        event_overrides[i].line_join
+                    }
+                    // The join at the start of a closed sub-path is the one at the end of
+                    // the closing edge.
+                    IdEvent::Begin { .. } if close => end_overrides.line_join,
+                    _ => None,
+                };
+                styles[i] = (line_join.unwrap_or(options.line_join), start_cap, end_cap);
+            }
+            if close {
+                set_width(last, end_overrides.line_width);
+            } else if idx == begin + 1 {
+                set_width(first, None);
+            } else {
+                set_width(last, last_width);
+            }
+        }
+
+        let options = StrokeOptions {
+            line_width: 1.0,
+            variable_line_width: Some(widths.width_index()),
+            ..*options
+        };
+        self.attrib_buffer.clear();
+        self.attrib_buffer.resize(widths.num_attributes(), 0.0);
+        let attrib_buffer = &mut self.attrib_buffer;
+        let mut tessellate = |options: &StrokeOptions, output: &mut dyn StrokeGeometryBuilder| {
+            StrokeBuilderImpl::new(options, attrib_buffer, output).tessellate_with_styles(
+                events.iter().cloned(),
+                positions,
+                &widths,
+                |idx, options| {
+                    let (line_join, start_cap, end_cap) = styles[idx];
+                    options.line_join = line_join;
+                    options.start_cap = start_cap;
+                    options.end_cap = end_cap;
+                },
+            )
+        };
+
+        let limits = options.output_limits();
+        if limits.is_unlimited() {
+            return tessellate(&options, output);
+        }
+
+        tessellate_within_limits(limits, options.tolerance, output, |tolerance, output| {
+            tessellate(&options.with_tolerance(tolerance), output)
+        })
+    }
+
+    /// Compute the tessellation from a path iterator, with custom geometry for the joins
+    /// and caps.
+    ///
+    /// If `joins` is set, the joins are tessellated as bevel joins and the builder adds the
+    /// geometry of their outer side, see [`JoinBuilder`](trait.JoinBuilder.html). If `caps`
+    /// is set, the caps are tessellated as butt caps and the builder adds the geometry beyond
+    /// them, at both ends of each open sub-path and of each dash, see
+    /// [`CapBuilder`](trait.CapBuilder.html). Otherwise the joins and caps of the options
+    /// are used.
+    ///
+    /// Sub-paths made of a single point don't get custom caps. Variable line width, tapering,
+    /// normalized advancement, the anti-aliasing fringe and output limits are not supported
+    /// by this method, it returns `UnsupportedParamater::StrokeOptionIsNotSupported` if any
+    /// of them is set.
+    pub fn tessellate_with_custom_shapes(
+        &mut self,
+        input: impl IntoIterator<Item = PathEvent>,
+        options: &StrokeOptions,
+        joins: Option<&mut dyn JoinBuilder>,
+        caps: Option<&mut dyn CapBuilder>,
+        output: &mut dyn StrokeGeometryBuilder,
+    ) -> TessellationResult {
+        check_unsupported_options(
+            options,
+            &[
+                StrokeFeature::VariableLineWidth,
+                StrokeFeature::Tapering,
+                StrokeFeature::NormalizedAdvancement,
+                StrokeFeature::AntiAliasingFringe,
+                StrokeFeature::OutputLimits,
+            ],
+        )?;
+
+        let mut options = *options;
+        if joins.is_some() {
+            options.line_join = LineJoin::Bevel;
+        }
+        if caps.is_some() {
+            options.start_cap = LineCap::Butt;
+            options.end_cap = LineCap::Butt;
+        }
 
-        stroker.tessellate_with_ids(path, positions, custom_attributes)
+        let mut buffer = Vec::new();
+        let mut stroker = StrokeBuilderImpl::new(&options, &mut buffer, output);
+        stroker.join_builder = joins.map(|builder| -> &mut dyn JoinBuilder { builder });
+        stroker.cap_builder = caps.map(|builder| -> &mut dyn CapBuilder { builder });
+
+        stroker.tessellate_fw(input)
     }
 
     /// Compute the tessellation from a path slice.
@@ -172,6 +488,191 @@ impl StrokeTessellator {
         }
     }
 
+    /// Compute evenly spaced dots along a path, instead of a connected stroke.
+    ///
+    /// The dots are filled circles or squares with the line width of the options as
+    /// diameter or side, placed every `spacing` along each sub-path starting at its first
+    /// point. For closed sub-paths the spacing is adjusted so that the dots are evenly
+    /// distributed all around the sub-path, which is useful for dotted borders and focus
+    /// rings. Sub-paths that are a single point get a single dot.
+    ///
+    /// Curves are flattened with the tolerance of the options and circles are flattened
+    /// like round caps. The position on the path, the advancement and the custom attributes
+    /// of the vertices of each dot are the ones of its center, with `StrokePart::Dot`. The
+    /// other options, including the joins, the caps and the dashes, are ignored.
+    ///
+    /// Fails with `UnsupportedParamater::DotSpacingIsNotPositive` if `spacing` is not greater
+    /// than zero.
+    #[allow(clippy::too_many_arguments)]
+    pub fn tessellate_dots_with_ids(
+        &mut self,
+        path: impl IntoIterator<Item = IdEvent>,
+        positions: &impl PositionStore,
+        custom_attributes: Option<&dyn AttributeStore>,
+        options: &StrokeOptions,
+        spacing: f32,
+        shape: DotShape,
+        output: &mut dyn StrokeGeometryBuilder,
+    ) -> TessellationResult {
+        if spacing.is_nan() || spacing <= 0.0 {
+            return Err(TessellationError::UnsupportedParamater(
+                UnsupportedParamater::DotSpacingIsNotPositive,
+            ));
+        }
+
+        let attributes = custom_attributes.unwrap_or(&());
+        self.attrib_buffer.clear();
+        self.attrib_buffer.resize(attributes.num_attributes(), 0.0);
+
+        let output: &mut dyn StrokeGeometryBuilder = &mut Oriented::stroke(options, output);
+        output.begin_geometry();
+        output.set_stroke_part(StrokePart::Dot);
+        let half_width = options.line_width * 0.5;
+        let circle_segments = match options.round_segments {
+            Some(n) => n * 2,
+            None => {
+                (2.0 * PI / circle_flattening_step(half_width, options.tolerance)).ceil() as u32
+            }
+        };
+        let mut dots = Dots {
+            vertex: StrokeVertexData {
+                position_on_path: point(0.0, 0.0),
+                half_width,
+                normal: vector(0.0, 0.0),
+                advancement: 0.0,
+                side: Side::Positive,
+                convex_side: None,
+                order: None,
+                src: VertexSource::Endpoint {
+                    id: EndpointId::INVALID,
+                },
+                coverage: 1.0,
+                buffer: &mut self.attrib_buffer,
+                buffer_is_valid: false,
+                interpolation: options.attribute_interpolation,
+                origin: Point2D::origin(),
+                position_on_path_f64: None,
+            },
+            attributes,
+            output,
+            spacing,
+            shape,
+            circle_segments: circle_segments.clamp(3, 256),
+            pieces: Vec::new(),
+        };
+
+        if let Err(e) = dots.tessellate(path, positions, options.tolerance) {
+            dots.output.abort_geometry();
+            return Err(e.into());
+        }
+
+        dots.output.end_geometry();
+
+        Ok(())
+    }
+
+    /// Compute evenly spaced dots along a path slice, instead of a connected stroke.
+    ///
+    /// See `tessellate_dots_with_ids`.
+    pub fn tessellate_dots_path<'l>(
+        &'l mut self,
+        path: impl Into<PathSlice<'l>>,
+        options: &'l StrokeOptions,
+        spacing: f32,
+        shape: DotShape,
+        output: &'l mut dyn StrokeGeometryBuilder,
+    ) -> TessellationResult {
+        let path = path.into();
+        let attributes: Option<&dyn AttributeStore> = if path.num_attributes() > 0 {
+            Some(&path)
+        } else {
+            None
+        };
+
+        self.tessellate_dots_with_ids(
+            path.id_iter(),
+            &path,
+            attributes,
+            options,
+            spacing,
+            shape,
+            output,
+        )
+    }
+
+    /// Compute hairlines (line segments) from a path, instead of triangles.
+    ///
+    /// Curves are flattened with the tolerance of the options. The advancement of the
+    /// vertices and their custom attributes are computed as in the tessellation, and
+    /// closed sub-paths get a line segment back to their first vertex. The other options,
+    /// including the line width, the joins, the caps and the dashes, are ignored.
+    pub fn tessellate_hairline_with_ids(
+        &mut self,
+        path: impl IntoIterator<Item = IdEvent>,
+        positions: &impl PositionStore,
+        custom_attributes: Option<&dyn AttributeStore>,
+        options: &StrokeOptions,
+        output: &mut dyn LineGeometryBuilder,
+    ) -> TessellationResult {
+        let attributes = custom_attributes.unwrap_or(&());
+        self.attrib_buffer.clear();
+        self.attrib_buffer.resize(attributes.num_attributes(), 0.0);
+
+        output.begin_geometry();
+        let mut hairline = Hairline {
+            vertex: StrokeVertexData {
+                position_on_path: point(0.0, 0.0),
+                half_width: 0.0,
+                normal: vector(0.0, 0.0),
+                advancement: 0.0,
+                side: Side::Positive,
+                convex_side: None,
+                order: None,
+                src: VertexSource::Endpoint {
+                    id: EndpointId::INVALID,
+                },
+                coverage: 1.0,
+                buffer: &mut self.attrib_buffer,
+                buffer_is_valid: false,
+                interpolation: options.attribute_interpolation,
+                origin: Point2D::origin(),
+                position_on_path_f64: None,
+            },
+            attributes,
+            output,
+            first: VertexId::INVALID,
+            previous: VertexId::INVALID,
+        };
+
+        if let Err(e) = hairline.tessellate(path, positions, options.tolerance) {
+            hairline.output.abort_geometry();
+            return Err(e.into());
+        }
+
+        hairline.output.end_geometry();
+
+        Ok(())
+    }
+
+    /// Compute hairlines (line segments) from a path slice, instead of triangles.
+    ///
+    /// See `tessellate_hairline_with_ids`.
+    pub fn tessellate_hairline_path<'l>(
+        &'l mut self,
+        path: impl Into<PathSlice<'l>>,
+        options: &'l StrokeOptions,
+        output: &'l mut dyn LineGeometryBuilder,
+    ) -> TessellationResult {
+        let path = path.into();
+        let attributes: Option<&dyn AttributeStore> = if path.num_attributes() > 0 {
+            Some(&path)
+        } else {
+            None
+        };
+
+        self.tessellate_hairline_with_ids(path.id_iter(), &path, attributes, options, output)
+    }
+
     /// Tessellate directly from a sequence of `PathBuilder` commands, without
     /// creating an intermediate path data structure.
     ///
@@ -248,13 +749,212 @@ impl StrokeTessellator {
     }
 
     /// Tessellate the stroke for a `Polygon`.
+    ///
+    /// The points are read directly from the slice without going through path events,
+    /// which makes this the fastest way to stroke polylines (for example GPS traces).
     pub fn tessellate_polygon(
         &mut self,
         polygon: Polygon<Point>,
         options: &StrokeOptions,
         output: &mut dyn StrokeGeometryBuilder,
     ) -> TessellationResult {
-        self.tessellate(polygon.path_events(), options, output)
+        debug_assert!(
+            options.variable_line_width.is_none(),
+            "Varible line width requires custom attributes. Try tessellate_with_ids or tessellate_path",
+        );
+
+        if needs_endpoint_ids(options) {
+            return self.tessellate_with_ids(polygon.id_iter(), &polygon, None, options, output);
+        }
+
+        let mut buffer = Vec::new();
+        let stroker = StrokeBuilderImpl::new(options, &mut buffer, output);
+
+        stroker.tessellate_polyline_fw(polygon.points, polygon.closed)
+    }
+
+    /// Tessellate the stroke for a polyline.
+    ///
+    /// Equivalent to `tessellate_polygon` with a `Polygon` made of the points. The line
+    /// segments are stroked directly, without building path events or flattening curves.
+    pub fn tessellate_polyline(
+        &mut self,
+        points: &[Point],
+        closed: bool,
+        options: &StrokeOptions,
+        output: &mut dyn StrokeGeometryBuilder,
+    ) -> TessellationResult {
+        self.tessellate_polygon(Polygon { points, closed }, options, output)
+    }
+
+    /// Tessellate the stroke for an open polyline from a stream of points and their
+    /// custom attributes.
+    ///
+    /// Unlike `builder_with_attributes`, the attributes are not accumulated in a store:
+    /// only the ones of the first and of the last few points are kept, which makes it
+    /// suitable for long polylines generated on the fly (for example live plots).
+    ///
+    /// Each slice of attributes must contain `num_attributes` values. If
+    /// `options.variable_line_width` is set, the line width is read from the attributes.
+    pub fn tessellate_polyline_with_attributes<'a>(
+        &mut self,
+        points: impl IntoIterator<Item = (Point, &'a [f32])>,
+        num_attributes: usize,
+        options: &StrokeOptions,
+        output: &mut dyn StrokeGeometryBuilder,
+    ) -> TessellationResult {
+        self.attrib_buffer.clear();
+        for _ in 0..num_attributes {
+            self.attrib_buffer.push(0.0);
+        }
+
+        let mut attributes = StreamingAttributeStore::new(num_attributes);
+        let stroker = StrokeBuilderImpl::new(options, &mut self.attrib_buffer, output);
+
+        stroker.tessellate_streaming_polyline(points, &mut attributes)
+    }
+
+    /// Tessellate the outline of a path for selection marquee ("marching ants") rendering.
+    ///
+    /// The advancement starts at zero for each sub-path. For closed sub-paths it is scaled
+    /// so that the length of the sub-path is a whole multiple of `pattern_length`: a dash
+    /// pattern of that length animated with an offset then lines up where the sub-path
+    /// closes instead of popping at the seam. The advancement of open sub-paths is not
+    /// scaled.
+    pub fn tessellate_marquee<OutputIndex>(
+        &mut self,
+        path: impl IntoIterator<Item = PathEvent>,
+        pattern_length: f32,
+        options: &StrokeOptions,
+        output: &mut VertexBuffers<MarqueeVertex, OutputIndex>,
+    ) -> TessellationResult
+    where
+        OutputIndex: Add + From<VertexId> + MaxIndex,
+    {
+        let mut sub_path = Vec::new();
+        for evt in path {
+            sub_path.push(evt);
+            let close = match evt {
+                PathEvent::End { close, .. } => close,
+                _ => continue,
+            };
+
+            let first_vertex = output.vertices.len();
+            self.tessellate(
+                sub_path.drain(..),
+                options,
+                &mut BuffersBuilder::new(output, |vertex: StrokeVertex| MarqueeVertex {
+                    position: vertex.position(),
+                    advancement: vertex.advancement(),
+                }),
+            )?;
+
+            if !close {
+                continue;
+            }
+
+            let vertices = &mut output.vertices[first_vertex..];
+            let length = vertices
+                .iter()
+                .fold(0.0f32, |length, vertex| length.max(vertex.advancement));
+            if length <= 0.0 {
+                continue;
+            }
+
+            let num_patterns = (length / pattern_length).round().max(1.0);
+            let scale = num_patterns * pattern_length / length;
+            for vertex in vertices {
+                vertex.advancement *= scale;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Tessellates the stroke of a path into triangles that don't overlap.
+    ///
+    /// The regular stroke tessellation produces overlapping triangles where the path
+    /// intersects itself, which causes semi-transparent strokes to be shaded more than once
+    /// in these regions. This method instead fills the outline of the stroke with the
+    /// non-zero fill rule, so that each point is covered exactly once, as the SVG
+    /// specification requires.
+    ///
+    /// This runs `tessellate` first, then fills the union of the produced triangles: each
+    /// triangle with a non-zero area becomes a closed sub-path of three edges. The fill
+    /// therefore processes about three times as many edges as the regular stroke has
+    /// triangles, and all of their intersections, which makes this several times slower than
+    /// `tessellate`. The intermediate buffers and the fill tessellator are kept in the
+    /// `StrokeTessellator` and reused across calls.
+    ///
+    /// The vertices are produced by the fill tessellator so stroke-specific information such
+    /// as the advancement or the normal is not available.
+    pub fn tessellate_non_overlapping(
+        &mut self,
+        path: impl IntoIterator<Item = PathEvent>,
+        options: &StrokeOptions,
+        output: &mut dyn FillGeometryBuilder,
+    ) -> TessellationResult {
+        // The output limits apply to the fill, and the orientation of the triangles is
+        // normalized below.
+        let stroke_options = StrokeOptions {
+            max_vertices: u32::MAX,
+            max_triangles: u32::MAX,
+            ..*options
+        };
+        let mut triangles = std::mem::take(&mut self.stroke_triangles);
+        triangles.vertices.clear();
+        triangles.indices.clear();
+        let result = self.tessellate(
+            path,
+            &stroke_options,
+            &mut BuffersBuilder::new(&mut triangles, |vertex: StrokeVertex| vertex.position()),
+        );
+        if result.is_err() {
+            self.stroke_triangles = triangles;
+            return result;
+        }
+
+        // The union of the triangles, each of them with a positive winding order.
+        let vertices = &triangles.vertices;
+        let outline = triangles
+            .indices
+            .chunks_exact(3)
+            .filter_map(|triangle| {
+                let a = vertices[triangle[0] as usize];
+                let b = vertices[triangle[1] as usize];
+                let c = vertices[triangle[2] as usize];
+                let area = (b - a).cross(c - a);
+                if area == 0.0 || area.is_nan() {
+                    return None;
+                }
+                Some(if area > 0.0 { [a, b, c] } else { [a, c, b] })
+            })
+            .flat_map(|[a, b, c]| {
+                [
+                    PathEvent::Begin { at: a },
+                    PathEvent::Line { from: a, to: b },
+                    PathEvent::Line { from: b, to: c },
+                    PathEvent::End {
+                        last: c,
+                        first: a,
+                        close: true,
+                    },
+                ]
+            });
+
+        let fill_options = FillOptions::tolerance(options.tolerance)
+            .with_fill_rule(FillRule::NonZero)
+            .with_y_axis_direction(options.y_axis_direction)
+            .with_triangle_winding(options.triangle_winding)
+            .with_max_vertices(options.max_vertices)
+            .with_max_triangles(options.max_triangles)
+            .with_budget_policy(options.budget_policy);
+        let result = self
+            .fill_tessellator
+            .tessellate(outline, &fill_options, output);
+        self.stroke_triangles = triangles;
+
+        result
     }
 
     /// Tessellate the stroke for an axis-aligned rectangle.
@@ -303,6 +1003,15 @@ impl StrokeTessellator {
     }
 }
 
+/// A vertex produced by `StrokeTessellator::tessellate_marquee`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MarqueeVertex {
+    pub position: Point,
+    /// Distance along the sub-path, scaled so that dash patterns wrap around closed
+    /// sub-paths.
+    pub advancement: f32,
+}
+
 #[derive(Copy, Clone, Debug)]
 pub(crate) struct SidePoints {
     prev: Point,
@@ -322,6 +1031,10 @@ pub(crate) struct EndpointData {
     pub side_points: [SidePoints; 2],
     pub fold: [bool; 2],
     pub is_flattening_step: bool,
+    /// Signed curvature of the incoming and outgoing edges at this point, zero for
+    /// straight edges. Only used by `LineJoin::Arcs`.
+    pub prev_curvature: f32,
+    pub next_curvature: f32,
 }
 
 impl Default for EndpointData {
@@ -343,22 +1056,445 @@ impl Default for EndpointData {
             }; 2],
             fold: [false, false],
             is_flattening_step: false,
+            prev_curvature: 0.0,
+            next_curvature: 0.0,
         }
     }
 }
 
-/// A builder object that tessellates a stroked path via the `PathBuilder`
-/// interface.
+/// Keeps the attributes of the first endpoint of a polyline and of the last few
+/// ones, which are the only ones the stroker looks up while streaming.
 ///
-/// Can be created using `StrokeTessellator::builder_with_attributes`.
-pub struct StrokeBuilder<'l> {
-    builder: StrokeBuilderImpl<'l>,
-    attrib_store: &'l mut SimpleAttributeStore,
-    validator: DebugValidator,
-    prev: (Point, EndpointId, f32),
+/// Endpoint `0` is the first point, the others are stored in a ring buffer.
+pub(crate) struct StreamingAttributeStore {
+    data: Vec<f32>,
+    num_attributes: usize,
 }
 
-impl<'l> StrokeBuilder<'l> {
+impl StreamingAttributeStore {
+    /// The stroker only refers to the endpoint being added and to the two previous
+    /// ones (in addition to the first).
+    const RING_SIZE: usize = 3;
+
+    pub(crate) fn new(num_attributes: usize) -> Self {
+        StreamingAttributeStore {
+            data: vec![0.0; num_attributes * (Self::RING_SIZE + 1)],
+            num_attributes,
+        }
+    }
+
+    fn slot(id: EndpointId) -> usize {
+        if id.0 == 0 {
+            0
+        } else {
+            1 + id.0 as usize % Self::RING_SIZE
+        }
+    }
+
+    pub(crate) fn set(&mut self, id: EndpointId, attributes: Attributes) {
+        assert_eq!(attributes.len(), self.num_attributes);
+        let start = Self::slot(id) * self.num_attributes;
+        self.data[start..start + self.num_attributes].copy_from_slice(attributes);
+    }
+}
+
+impl AttributeStore for StreamingAttributeStore {
+    fn get(&self, id: EndpointId) -> Attributes<'_> {
+        let start = Self::slot(id) * self.num_attributes;
+        &self.data[start..start + self.num_attributes]
+    }
+
+    fn num_attributes(&self) -> usize {
+        self.num_attributes
+    }
+}
+
+// Tessellates each sub-path twice: once to measure its length and once to produce the
+// geometry with an advancement divided by the length.
+fn tessellate_normalized(
+    events: &[IdEvent],
+    positions: &impl PositionStore,
+    attributes: &dyn AttributeStore,
+    options: &StrokeOptions,
+    attrib_buffer: &mut Vec<f32>,
+    output: &mut dyn StrokeGeometryBuilder,
+) -> TessellationResult {
+    output.begin_geometry();
+
+    let mut start = 0;
+    for (idx, event) in events.iter().enumerate() {
+        if !matches!(event, IdEvent::End { .. }) {
+            continue;
+        }
+        let sub_path = &events[start..=idx];
+        start = idx + 1;
+
+        let mut measure = SubPathLength {
+            length: 0.0,
+            num_vertices: 0,
+        };
+        let result = StrokeBuilderImpl::new(options, attrib_buffer, &mut measure)
+            .tessellate_with_ids(sub_path.iter().cloned(), positions, attributes);
+        if let Err(e) = result {
+            output.abort_geometry();
+            return Err(e);
+        }
+
+        let scale = if measure.length > 0.0 {
+            1.0 / measure.length
+        } else {
+            0.0
+        };
+        // Aborts the output if the sub-path fails.
+        StrokeBuilderImpl::new(
+            options,
+            attrib_buffer,
+            &mut ScaledAdvancement { output, scale },
+        )
+        .tessellate_with_ids(sub_path.iter().cloned(), positions, attributes)?;
+    }
+
+    output.end_geometry();
+
+    Ok(())
+}
+
+/// Measures the length of a sub-path from the advancement of its vertices.
+struct SubPathLength {
+    length: f32,
+    num_vertices: u32,
+}
+
+impl GeometryBuilder for SubPathLength {
+    fn add_triangle(&mut self, _: VertexId, _: VertexId, _: VertexId) {}
+}
+
+impl StrokeGeometryBuilder for SubPathLength {
+    fn add_stroke_vertex(
+        &mut self,
+        vertex: StrokeVertex,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        self.length = self.length.max(vertex.advancement());
+        self.num_vertices += 1;
+
+        Ok(VertexId(self.num_vertices - 1))
+    }
+}
+
+/// Forwards the geometry of a sub-path to the output with a scaled advancement.
+struct ScaledAdvancement<'l> {
+    output: &'l mut dyn StrokeGeometryBuilder,
+    scale: f32,
+}
+
+impl<'l> GeometryBuilder for ScaledAdvancement<'l> {
+    // The geometry of the whole path is started and ended by `tessellate_normalized`.
+    fn begin_geometry(&mut self) {}
+    fn end_geometry(&mut self) {}
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        self.output.add_triangle(a, b, c);
+    }
+
+    fn abort_geometry(&mut self) {
+        self.output.abort_geometry();
+    }
+}
+
+impl<'l> StrokeGeometryBuilder for ScaledAdvancement<'l> {
+    fn add_stroke_vertex(
+        &mut self,
+        vertex: StrokeVertex,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        let StrokeVertex(data, attributes) = vertex;
+        let advancement = data.advancement;
+        data.advancement *= self.scale;
+        let result = self
+            .output
+            .add_stroke_vertex(StrokeVertex(&mut *data, attributes));
+        data.advancement = advancement;
+
+        result
+    }
+
+    fn set_stroke_part(&mut self, part: StrokePart) {
+        self.output.set_stroke_part(part);
+    }
+}
+
+// Squared distance under which consecutive points are merged.
+//
+// Ideally we'd use the bounding rect of the path as an indication
+// of what is considered a very small distance between two points,
+// but we don't have this information so we use a combination of the
+// tolerance threshold and, in case the latter is high to get "low-poly"
+// curves, the line width.
+pub(crate) fn square_merge_threshold(options: &StrokeOptions) -> f32 {
+    (options.tolerance * options.tolerance * 0.5)
+        .min(options.line_width * options.line_width * 0.05)
+        .max(1e-8)
+}
+
+// Whether the options are only supported by `tessellate_with_ids`.
+fn needs_endpoint_ids(options: &StrokeOptions) -> bool {
+    is_tapered(options) || options.normalized_advancement || options.anti_aliasing_fringe.is_some()
+}
+
+fn is_tapered(options: &StrokeOptions) -> bool {
+    options.start_width.is_some() || options.end_width.is_some()
+}
+
+/// Stroke options that only some of the tessellation methods support.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) enum StrokeFeature {
+    VariableLineWidth,
+    PeriodicLineWidth,
+    Tapering,
+    NormalizedAdvancement,
+    AntiAliasingFringe,
+    OutputLimits,
+    ArcsJoin,
+}
+
+impl StrokeFeature {
+    fn is_set(self, options: &StrokeOptions) -> bool {
+        match self {
+            StrokeFeature::VariableLineWidth => options.variable_line_width.is_some(),
+            StrokeFeature::PeriodicLineWidth => options.periodic_line_width,
+            StrokeFeature::Tapering => is_tapered(options),
+            StrokeFeature::NormalizedAdvancement => options.normalized_advancement,
+            StrokeFeature::AntiAliasingFringe => options.anti_aliasing_fringe.is_some(),
+            StrokeFeature::OutputLimits => !options.output_limits().is_unlimited(),
+            StrokeFeature::ArcsJoin => options.line_join == LineJoin::Arcs,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            StrokeFeature::VariableLineWidth => "variable_line_width",
+            StrokeFeature::PeriodicLineWidth => "periodic_line_width",
+            StrokeFeature::Tapering => "start_width/end_width",
+            StrokeFeature::NormalizedAdvancement => "normalized_advancement",
+            StrokeFeature::AntiAliasingFringe => "anti_aliasing_fringe",
+            StrokeFeature::OutputLimits => "max_vertices/max_triangles",
+            StrokeFeature::ArcsJoin => "LineJoin::Arcs",
+        }
+    }
+}
+
+/// Returns `UnsupportedParamater::StrokeOptionIsNotSupported` with the name of the first
+/// of the `unsupported` features that is set in the options.
+pub(crate) fn check_unsupported_options(
+    options: &StrokeOptions,
+    unsupported: &[StrokeFeature],
+) -> TessellationResult {
+    match unsupported.iter().find(|feature| feature.is_set(options)) {
+        Some(feature) => Err(TessellationError::UnsupportedParamater(
+            UnsupportedParamater::StrokeOptionIsNotSupported(feature.name()),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Copies the attributes of a path and appends a line width to each endpoint.
+struct WidthAttributeStore {
+    data: Vec<f32>,
+    num_attributes: usize,
+}
+
+impl WidthAttributeStore {
+    fn new(num_source_attributes: usize) -> Self {
+        WidthAttributeStore {
+            data: Vec::new(),
+            num_attributes: num_source_attributes + 1,
+        }
+    }
+
+    fn set(&mut self, id: EndpointId, source: Attributes, width: f32) {
+        let n = self.num_attributes;
+        let start = id.to_usize() * n;
+        if self.data.len() < start + n {
+            self.data.resize(start + n, 0.0);
+        }
+        self.data[start..start + n - 1].copy_from_slice(source);
+        self.data[start + n - 1] = width;
+    }
+
+    /// The line width of tapered strokes, which depends on the distance of each endpoint
+    /// along its sub-path.
+    fn tapered(
+        events: &[IdEvent],
+        positions: &impl PositionStore,
+        attributes: &dyn AttributeStore,
+        options: &StrokeOptions,
+    ) -> Self {
+        let mut store = WidthAttributeStore::new(attributes.num_attributes());
+        let start_width = options.start_width.unwrap_or(options.line_width);
+        let end_width = options.end_width.unwrap_or(options.line_width);
+
+        // The endpoints of the current sub-path and their distance from its start.
+        let mut sub_path = Vec::new();
+        let mut length = 0.0;
+        for event in events {
+            match *event {
+                IdEvent::Begin { at } => {
+                    sub_path.clear();
+                    length = 0.0;
+                    sub_path.push((at, 0.0));
+                    continue;
+                }
+                IdEvent::Line { from, to } => {
+                    length += (positions.get_endpoint(to) - positions.get_endpoint(from)).length();
+                    sub_path.push((to, length));
+                    continue;
+                }
+                IdEvent::Quadratic { from, ctrl, to } => {
+                    let curve = QuadraticBezierSegment {
+                        from: positions.get_endpoint(from),
+                        ctrl: positions.get_control_point(ctrl),
+                        to: positions.get_endpoint(to),
+                    };
+                    length += curve.length();
+                    sub_path.push((to, length));
+                    continue;
+                }
+                IdEvent::Cubic {
+                    from,
+                    ctrl1,
+                    ctrl2,
+                    to,
+                } => {
+                    let curve = CubicBezierSegment {
+                        from: positions.get_endpoint(from),
+                        ctrl1: positions.get_control_point(ctrl1),
+                        ctrl2: positions.get_control_point(ctrl2),
+                        to: positions.get_endpoint(to),
+                    };
+                    length += curve.approximate_length(options.tolerance);
+                    sub_path.push((to, length));
+                    continue;
+                }
+                IdEvent::End { last, first, close } => {
+                    if close {
+                        length +=
+                            (positions.get_endpoint(first) - positions.get_endpoint(last)).length();
+                    }
+                }
+            }
+
+            for &(id, distance) in &sub_path {
+                let t = if length > 0.0 { distance / length } else { 0.0 };
+                let source = attributes.get(id);
+                let mut width = start_width + (end_width - start_width) * t;
+                if let Some(idx) = options.variable_line_width {
+                    width *= source[idx];
+                }
+                // The stroker divides by the line width, so tapering to a point ends with a
+                // width that is negligible instead of zero.
+                width = width.max(options.tolerance * 0.01);
+
+                store.set(id, source, width);
+            }
+        }
+
+        store
+    }
+
+    fn width_index(&self) -> AttributeIndex {
+        self.num_attributes - 1
+    }
+}
+
+impl AttributeStore for WidthAttributeStore {
+    fn get(&self, id: EndpointId) -> Attributes<'_> {
+        let start = id.to_usize() * self.num_attributes;
+        &self.data[start..start + self.num_attributes]
+    }
+
+    fn num_attributes(&self) -> usize {
+        self.num_attributes
+    }
+}
+
+/// Overrides the line width of the endpoints on the seam of closed sub-paths with
+/// the average of their widths.
+struct SeamAttributeStore<'l> {
+    source: &'l dyn AttributeStore,
+    /// The overridden endpoints and the index of their attributes in `data`, sorted by id.
+    ids: Vec<(u32, usize)>,
+    data: Vec<f32>,
+}
+
+impl<'l> SeamAttributeStore<'l> {
+    fn new(
+        events: &[IdEvent],
+        positions: &impl PositionStore,
+        source: &'l dyn AttributeStore,
+        options: &StrokeOptions,
+    ) -> Self {
+        let width_index = options.variable_line_width.unwrap();
+        let threshold = square_merge_threshold(options);
+        let mut ids = Vec::new();
+        let mut data = Vec::new();
+        for event in events {
+            if let IdEvent::End {
+                last,
+                first,
+                close: true,
+            } = *event
+            {
+                let distance = positions.get_endpoint(last) - positions.get_endpoint(first);
+                if last == first || distance.square_length() >= threshold {
+                    // The closing segment already interpolates between the two widths.
+                    continue;
+                }
+                let width = (source.get(last)[width_index] + source.get(first)[width_index]) * 0.5;
+                for id in [last, first] {
+                    let start = data.len();
+                    data.extend_from_slice(source.get(id));
+                    data[start + width_index] = width;
+                    ids.push((id.0, ids.len()));
+                }
+            }
+        }
+
+        // The sort is stable, if an endpoint is on several seams the first one is kept.
+        ids.sort_by_key(|(id, _)| *id);
+        ids.dedup_by_key(|(id, _)| *id);
+
+        SeamAttributeStore { source, ids, data }
+    }
+}
+
+impl<'l> AttributeStore for SeamAttributeStore<'l> {
+    fn get(&self, id: EndpointId) -> Attributes<'_> {
+        match self.ids.binary_search_by_key(&id.0, |(seam, _)| *seam) {
+            Ok(idx) => {
+                let idx = self.ids[idx].1;
+                let n = self.num_attributes();
+                &self.data[idx * n..(idx + 1) * n]
+            }
+            Err(_) => self.source.get(id),
+        }
+    }
+
+    fn num_attributes(&self) -> usize {
+        self.source.num_attributes()
+    }
+}
+
+/// A builder object that tessellates a stroked path via the `PathBuilder`
+/// interface.
+///
+/// Can be created using `StrokeTessellator::builder_with_attributes`.
+pub struct StrokeBuilder<'l> {
+    builder: StrokeBuilderImpl<'l>,
+    attrib_store: &'l mut SimpleAttributeStore,
+    validator: DebugValidator,
+    prev: (Point, EndpointId, f32),
+}
+
+impl<'l> StrokeBuilder<'l> {
     pub(crate) fn new(
         options: &StrokeOptions,
         attrib_buffer: &'l mut Vec<f32>,
@@ -393,6 +1529,11 @@ impl<'l> StrokeBuilder<'l> {
         self.builder.options.miter_limit = limit;
     }
 
+    #[inline]
+    pub fn set_miter_clip_distance(&mut self, distance: Option<f32>) {
+        self.builder.options.miter_clip_distance = distance;
+    }
+
     fn get_width(&self, attributes: Attributes) -> f32 {
         if let Some(idx) = self.builder.options.variable_line_width {
             self.builder.options.line_width * attributes[idx]
@@ -573,7 +1714,7 @@ impl<'l> Build for StrokeBuilder<'l> {
 pub(crate) struct StrokeBuilderImpl<'l> {
     options: StrokeOptions,
     pub(crate) error: Option<TessellationError>,
-    pub(crate) output: &'l mut dyn StrokeGeometryBuilder,
+    pub(crate) output: Oriented<'l, dyn StrokeGeometryBuilder + 'l>,
     vertex: StrokeVertexData<'l>,
     point_buffer: PointBuffer,
     firsts: ArrayVec<EndpointData, 2>,
@@ -581,6 +1722,10 @@ pub(crate) struct StrokeBuilderImpl<'l> {
     sub_path_start_advancement: f32,
     square_merge_threshold: f32,
     may_need_empty_cap: bool,
+    dash: Option<DashState>,
+    join_builder: Option<&'l mut dyn JoinBuilder>,
+    cap_builder: Option<&'l mut dyn CapBuilder>,
+    custom_vertices: Vec<(VertexId, Point)>,
 }
 
 impl<'l> StrokeBuilderImpl<'l> {
@@ -591,20 +1736,13 @@ impl<'l> StrokeBuilderImpl<'l> {
     ) -> Self {
         output.begin_geometry();
 
-        // Ideally we'd use the bounding rect of the path as an indication
-        // of what is considered a very small distance between two points,
-        // but we don't have this information so we use a combination of the
-        // tolerance threshold and, in case the latter is high to get "low-poly"
-        // curves, the line width.
-        let square_merge_threshold = (options.tolerance * options.tolerance * 0.5)
-            .min(options.line_width * options.line_width * 0.05)
-            .max(1e-8);
+        let square_merge_threshold = square_merge_threshold(options);
 
         let zero = Point::new(0.0, 0.0);
         StrokeBuilderImpl {
             options: *options,
             error: None,
-            output,
+            output: Oriented::stroke(options, output),
             vertex: StrokeVertexData {
                 position_on_path: zero,
                 normal: vector(0.0, 0.0),
@@ -612,10 +1750,16 @@ impl<'l> StrokeBuilderImpl<'l> {
                 advancement: 0.0,
                 buffer: attrib_buffer,
                 side: Side::Negative,
+                convex_side: None,
+                order: None,
                 src: VertexSource::Endpoint {
                     id: EndpointId::INVALID,
                 },
+                coverage: 1.0,
                 buffer_is_valid: false,
+                interpolation: options.attribute_interpolation,
+                origin: Point2D::origin(),
+                position_on_path_f64: None,
             },
             point_buffer: PointBuffer::new(),
             firsts: ArrayVec::new(),
@@ -623,6 +1767,14 @@ impl<'l> StrokeBuilderImpl<'l> {
             sub_path_start_advancement: 0.0,
             square_merge_threshold,
             may_need_empty_cap: false,
+            dash: if options.dash_array.is_solid() {
+                None
+            } else {
+                Some(DashState::new(options.dash_array, options.dash_offset))
+            },
+            join_builder: None,
+            cap_builder: None,
+            custom_vertices: Vec::new(),
         }
     }
 
@@ -640,17 +1792,31 @@ impl<'l> StrokeBuilderImpl<'l> {
         attributes: &dyn AttributeStore,
     ) -> TessellationResult {
         if self.options.variable_line_width.is_some() {
-            self.tessellate_with_ids_vw(path, positions, attributes)
+            self.tessellate_with_ids_vw(path, positions, attributes, |_, _| {})
         } else {
             self.tessellate_with_ids_fw(path, positions, attributes)
         }
     }
 
+    /// Same as `tessellate_with_ids` with a variable line width, calling `style` with the
+    /// index of each event before processing it so that it can change the options.
+    pub(crate) fn tessellate_with_styles(
+        self,
+        path: impl IntoIterator<Item = IdEvent>,
+        positions: &impl PositionStore,
+        attributes: &dyn AttributeStore,
+        style: impl FnMut(usize, &mut StrokeOptions),
+    ) -> TessellationResult {
+        debug_assert!(self.options.variable_line_width.is_some());
+        self.tessellate_with_ids_vw(path, positions, attributes, style)
+    }
+
     fn tessellate_with_ids_vw(
         mut self,
         path: impl IntoIterator<Item = IdEvent>,
         positions: &impl PositionStore,
         attributes: &dyn AttributeStore,
+        mut style: impl FnMut(usize, &mut StrokeOptions),
     ) -> TessellationResult {
         let base_width = self.options.line_width;
         let attrib_index = self.options.variable_line_width.unwrap();
@@ -660,7 +1826,8 @@ impl<'l> StrokeBuilderImpl<'l> {
         let mut current_endpoint = EndpointId(std::u32::MAX);
         let mut current_position = point(std::f32::NAN, std::f32::NAN);
 
-        for evt in path.into_iter() {
+        for (idx, evt) in path.into_iter().enumerate() {
+            style(idx, &mut self.options);
             match evt {
                 IdEvent::Begin { at } => {
                     validator.begin();
@@ -879,71 +2046,150 @@ impl<'l> StrokeBuilderImpl<'l> {
         let mut current_position = point(std::f32::NAN, std::f32::NAN);
 
         for evt in input {
-            match evt {
-                PathEvent::Begin { at } => {
-                    validator.begin();
-                    current_position = at;
-                    self.begin_fw(at, id, &());
-                    id.0 += 1;
-                }
-                PathEvent::Line { to, .. } => {
-                    validator.edge();
-                    current_position = to;
-                    self.line_to_fw(to, id, &());
-                    id.0 += 1;
-                }
-                PathEvent::Quadratic { ctrl, to, .. } => {
-                    validator.edge();
+            self.fw_event(evt, &mut id, &mut current_position, &mut validator);
 
-                    let from = current_position;
-                    current_position = to;
-                    let prev_id = EndpointId(id.0 - 1);
+            if let Some(err) = self.error {
+                self.output.abort_geometry();
+                return Err(err);
+            }
+        }
 
-                    self.quadratic_bezier_to_fw(
-                        &QuadraticBezierSegment { from, ctrl, to },
-                        prev_id,
-                        id,
-                        &(),
-                    );
+        validator.build();
+        self.build()
+    }
 
-                    id.0 += 1;
-                }
-                PathEvent::Cubic {
-                    ctrl1, ctrl2, to, ..
-                } => {
-                    validator.edge();
-                    let prev_id = EndpointId(id.0 - 1);
+    fn fw_event(
+        &mut self,
+        evt: PathEvent,
+        id: &mut EndpointId,
+        current_position: &mut Point,
+        validator: &mut DebugValidator,
+    ) {
+        match evt {
+            PathEvent::Begin { at } => {
+                validator.begin();
+                *current_position = at;
+                self.begin_fw(at, *id, &());
+                id.0 += 1;
+            }
+            PathEvent::Line { to, .. } => {
+                validator.edge();
+                *current_position = to;
+                self.line_to_fw(to, *id, &());
+                id.0 += 1;
+            }
+            PathEvent::Quadratic { ctrl, to, .. } => {
+                validator.edge();
+
+                let from = *current_position;
+                *current_position = to;
+                let prev_id = EndpointId(id.0 - 1);
+
+                self.quadratic_bezier_to_fw(
+                    &QuadraticBezierSegment { from, ctrl, to },
+                    prev_id,
+                    *id,
+                    &(),
+                );
 
-                    let from = current_position;
-                    current_position = to;
+                id.0 += 1;
+            }
+            PathEvent::Cubic {
+                ctrl1, ctrl2, to, ..
+            } => {
+                validator.edge();
+                let prev_id = EndpointId(id.0 - 1);
 
-                    self.cubic_bezier_to_fw(
-                        &CubicBezierSegment {
-                            from,
-                            ctrl1,
-                            ctrl2,
-                            to,
-                        },
-                        prev_id,
-                        id,
-                        &(),
-                    );
+                let from = *current_position;
+                *current_position = to;
 
-                    id.0 += 1;
-                }
-                PathEvent::End { close, .. } => {
-                    validator.end();
-                    self.end(close, &());
+                self.cubic_bezier_to_fw(
+                    &CubicBezierSegment {
+                        from,
+                        ctrl1,
+                        ctrl2,
+                        to,
+                    },
+                    prev_id,
+                    *id,
+                    &(),
+                );
+
+                id.0 += 1;
+            }
+            PathEvent::End { close, .. } => {
+                validator.end();
+                self.end(close, &());
+            }
+        }
+    }
+
+    pub(crate) fn tessellate_polyline_fw(
+        mut self,
+        points: &[Point],
+        closed: bool,
+    ) -> TessellationResult {
+        self.options.variable_line_width = None;
+
+        if let Some((first, rest)) = points.split_first() {
+            self.begin_fw(*first, EndpointId(0), &());
+            for (i, to) in rest.iter().enumerate() {
+                self.line_to_fw(*to, EndpointId(i as u32 + 1), &());
+
+                if let Some(err) = self.error {
+                    self.output.abort_geometry();
+                    return Err(err);
                 }
             }
+            self.end(closed, &());
+        }
+
+        self.build()
+    }
+
+    pub(crate) fn tessellate_streaming_polyline<'a>(
+        mut self,
+        points: impl IntoIterator<Item = (Point, &'a [f32])>,
+        attributes: &mut StreamingAttributeStore,
+    ) -> TessellationResult {
+        let base_width = self.options.line_width;
+        let mut id = EndpointId(0);
+        for (position, attrs) in points {
+            attributes.set(id, attrs);
+            let width = self
+                .options
+                .variable_line_width
+                .map(|idx| base_width * attrs[idx]);
+
+            match (id.0, width) {
+                (0, Some(width)) => self.begin(position, id, width, attributes),
+                (0, None) => self.begin_fw(position, id, attributes),
+                (_, Some(width)) => self.line_to(position, id, width, attributes),
+                (_, None) => self.line_to_fw(position, id, attributes),
+            }
 
             if let Some(err) = self.error {
                 self.output.abort_geometry();
                 return Err(err);
             }
+
+            // Points that are merged with the previous one are never referred to,
+            // so their slot in the ring buffer can be reused.
+            let accepted = id.0 == 0
+                || self.dash.is_some()
+                || matches!(
+                    self.point_buffer.last().src,
+                    VertexSource::Endpoint { id: last } if last == id
+                );
+            if accepted {
+                id.0 += 1;
+            }
+        }
+
+        if id.0 > 0 {
+            self.end(false, attributes);
         }
 
-        validator.build();
         self.build()
     }
 
@@ -989,6 +2235,19 @@ impl<'l> StrokeBuilderImpl<'l> {
         );
     }
 
+    // With `LineJoin::Arcs`, records the curvature at the start of a curve on the current
+    // endpoint and returns the curvature at the end of the curve.
+    fn start_curvature(&mut self, curvature: impl Fn(f32) -> f32) -> f32 {
+        if self.options.line_join != LineJoin::Arcs || self.dash.is_some() {
+            return 0.0;
+        }
+        if self.point_buffer.count() > 0 {
+            self.point_buffer.last_mut().next_curvature = curvature(0.0);
+        }
+
+        curvature(1.0)
+    }
+
     pub(crate) fn quadratic_bezier_to(
         &mut self,
         curve: &QuadraticBezierSegment<f32>,
@@ -998,6 +2257,8 @@ impl<'l> StrokeBuilderImpl<'l> {
         end_width: f32,
         attributes: &dyn AttributeStore,
     ) {
+        trace_span!("flatten");
+        let end_curvature = self.start_curvature(|t| quadratic_curvature(curve, t));
         flatten_quad(
             curve,
             self.options.tolerance,
@@ -1019,6 +2280,7 @@ impl<'l> StrokeBuilderImpl<'l> {
                         line_join: self.options.line_join,
                         src,
                         is_flattening_step,
+                        prev_curvature: if t == 1.0 { end_curvature } else { 0.0 },
                         ..Default::default()
                     },
                     attributes,
@@ -1036,6 +2298,8 @@ impl<'l> StrokeBuilderImpl<'l> {
         end_width: f32,
         attributes: &dyn AttributeStore,
     ) {
+        trace_span!("flatten");
+        let end_curvature = self.start_curvature(|t| cubic_curvature(curve, t));
         curve.for_each_flattened_with_t(self.options.tolerance, &mut |line, t| {
             let is_flattening_step = t.end != 1.0;
             let src = if is_flattening_step {
@@ -1055,6 +2319,11 @@ impl<'l> StrokeBuilderImpl<'l> {
                     line_join: self.options.line_join,
                     src,
                     is_flattening_step,
+                    prev_curvature: if is_flattening_step {
+                        0.0
+                    } else {
+                        end_curvature
+                    },
                     ..Default::default()
                 },
                 attributes,
@@ -1108,7 +2377,9 @@ impl<'l> StrokeBuilderImpl<'l> {
         to_id: EndpointId,
         attributes: &dyn AttributeStore,
     ) {
+        trace_span!("flatten");
         let half_width = self.options.line_width * 0.5;
+        let end_curvature = self.start_curvature(|t| quadratic_curvature(curve, t));
         flatten_quad(
             curve,
             self.options.tolerance,
@@ -1130,6 +2401,7 @@ impl<'l> StrokeBuilderImpl<'l> {
                         line_join: self.options.line_join,
                         src,
                         is_flattening_step,
+                        prev_curvature: if t == 1.0 { end_curvature } else { 0.0 },
                         ..Default::default()
                     },
                     attributes,
@@ -1145,7 +2417,9 @@ impl<'l> StrokeBuilderImpl<'l> {
         to_id: EndpointId,
         attributes: &dyn AttributeStore,
     ) {
+        trace_span!("flatten");
         let half_width = self.options.line_width * 0.5;
+        let end_curvature = self.start_curvature(|t| cubic_curvature(curve, t));
         curve.for_each_flattened_with_t(self.options.tolerance, &mut |line, t| {
             let is_flattening_step = t.end != 1.0;
             let src = if is_flattening_step {
@@ -1165,6 +2439,11 @@ impl<'l> StrokeBuilderImpl<'l> {
                     line_join: self.options.line_join,
                     src,
                     is_flattening_step,
+                    prev_curvature: if is_flattening_step {
+                        0.0
+                    } else {
+                        end_curvature
+                    },
                     ..Default::default()
                 },
                 attributes,
@@ -1173,6 +2452,16 @@ impl<'l> StrokeBuilderImpl<'l> {
     }
 
     pub(crate) fn end(&mut self, close: bool, attributes: &dyn AttributeStore) {
+        if let Some(mut dash) = self.dash.take() {
+            self.dashed_end(&mut dash, close, attributes);
+            self.dash = Some(dash);
+            return;
+        }
+
+        self.end_sub_path(close, attributes);
+    }
+
+    fn end_sub_path(&mut self, close: bool, attributes: &dyn AttributeStore) {
         self.may_need_empty_cap |= close && self.point_buffer.count() == 1;
         let e = if close && self.point_buffer.count() > 2 {
             self.close(attributes)
@@ -1188,7 +2477,7 @@ impl<'l> StrokeBuilderImpl<'l> {
         self.firsts.clear();
     }
 
-    pub(crate) fn build(self) -> TessellationResult {
+    pub(crate) fn build(mut self) -> TessellationResult {
         if let Some(err) = self.error {
             self.output.abort_geometry();
             return Err(err);
@@ -1215,6 +2504,11 @@ impl<'l> StrokeBuilderImpl<'l> {
         // beginning of the sub-path.
         let advancement = p.advancement;
         p.advancement = std::f32::NAN;
+        // If the sub-path ends at its first point, the last point is the join and the edge
+        // after it is the first edge.
+        if self.points_are_too_close(self.point_buffer.last().position, p.position) {
+            self.point_buffer.last_mut().next_curvature = p.next_curvature;
+        }
         if self.options.variable_line_width.is_some() {
             self.step_impl(p, attributes)?;
         } else {
@@ -1256,7 +2550,7 @@ impl<'l> StrokeBuilderImpl<'l> {
                 p0.side_points[side].next_vertex = vertex;
             }
 
-            add_edge_triangles(p0, p1, self.output);
+            add_edge_triangles(p0, p1, StrokePart::Edge, &mut self.output);
         }
 
         Ok(())
@@ -1267,6 +2561,7 @@ impl<'l> StrokeBuilderImpl<'l> {
         &mut self,
         attributes: &dyn AttributeStore,
     ) -> Result<(), TessellationError> {
+        trace_span!("cap");
         let point = self.point_buffer.get(0);
         self.vertex.advancement = point.advancement;
         self.vertex.src = point.src;
@@ -1280,7 +2575,7 @@ impl<'l> StrokeBuilderImpl<'l> {
                     point.position,
                     &mut self.vertex,
                     attributes,
-                    self.output,
+                    &mut self.output,
                 )?;
             }
             LineCap::Round => {
@@ -1290,7 +2585,7 @@ impl<'l> StrokeBuilderImpl<'l> {
                     &self.options,
                     &mut self.vertex,
                     attributes,
-                    self.output,
+                    &mut self.output,
                 )?;
             }
             _ => {}
@@ -1304,6 +2599,7 @@ impl<'l> StrokeBuilderImpl<'l> {
     }
 
     fn end_with_caps(&mut self, attributes: &dyn AttributeStore) -> Result<(), TessellationError> {
+        trace_span!("cap");
         let count = self.point_buffer.count();
 
         if self.may_need_empty_cap && count == 1 {
@@ -1337,9 +2633,26 @@ impl<'l> StrokeBuilderImpl<'l> {
                 &self.options,
                 &mut self.vertex,
                 attributes,
-                self.output,
+                &mut self.output,
             )?;
 
+            if let Some(builder) = self.cap_builder.as_deref_mut() {
+                tessellate_custom_cap(
+                    builder,
+                    &p1,
+                    p1.position - p0.position,
+                    false,
+                    [
+                        p1.side_points[SIDE_POSITIVE].prev_vertex,
+                        p1.side_points[SIDE_NEGATIVE].prev_vertex,
+                    ],
+                    &mut self.custom_vertices,
+                    &mut self.vertex,
+                    attributes,
+                    &mut self.output,
+                )?;
+            }
+
             self.sub_path_start_advancement = p1.advancement;
 
             if count > 2 {
@@ -1351,15 +2664,33 @@ impl<'l> StrokeBuilderImpl<'l> {
             tessellate_first_edge(
                 &mut p0,
                 &p1,
+                is_first,
                 &self.options,
                 &mut self.vertex,
                 attributes,
-                self.output,
+                &mut self.output,
             )?;
-        }
-
-        Ok(())
-    }
+
+            if let Some(builder) = self.cap_builder.as_deref_mut() {
+                tessellate_custom_cap(
+                    builder,
+                    &p0,
+                    p0.position - p1.position,
+                    true,
+                    [
+                        p0.side_points[SIDE_NEGATIVE].next_vertex,
+                        p0.side_points[SIDE_POSITIVE].next_vertex,
+                    ],
+                    &mut self.custom_vertices,
+                    &mut self.vertex,
+                    attributes,
+                    &mut self.output,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
 
     pub(crate) fn step_impl(
         &mut self,
@@ -1406,6 +2737,8 @@ impl<'l> StrokeBuilderImpl<'l> {
             self.vertex.half_width = join.half_width;
             self.vertex.advancement = join.advancement;
             self.vertex.buffer_is_valid = false;
+            self.vertex.convex_side =
+                Some(convex_side(prev.position, join.position, next.position));
             // We can take the fast path if the join is a flattening step and
             // not at a sharp turn.
             let fast_path = if join.is_flattening_step {
@@ -1424,43 +2757,31 @@ impl<'l> StrokeBuilderImpl<'l> {
                     &mut next,
                     &mut self.vertex,
                     attributes,
-                    self.output,
+                    &mut self.output,
                 )?;
             } else {
-                compute_join_side_positions(
-                    prev,
-                    join,
-                    &next,
-                    self.options.miter_limit,
-                    SIDE_POSITIVE,
-                );
-                compute_join_side_positions(
-                    prev,
-                    join,
-                    &next,
-                    self.options.miter_limit,
-                    SIDE_NEGATIVE,
-                );
+                compute_join_side_positions(prev, join, &next, &self.options, SIDE_POSITIVE);
+                compute_join_side_positions(prev, join, &next, &self.options, SIDE_NEGATIVE);
 
                 add_join_base_vertices(
                     join,
                     &mut self.vertex,
                     attributes,
-                    self.output,
+                    &mut self.output,
                     Side::Negative,
                 )?;
                 add_join_base_vertices(
                     join,
                     &mut self.vertex,
                     attributes,
-                    self.output,
+                    &mut self.output,
                     Side::Positive,
                 )?;
             }
 
             if !skip {
                 if count > 2 {
-                    add_edge_triangles(prev, join, self.output);
+                    add_edge_triangles(prev, join, StrokePart::Edge, &mut self.output);
                 }
 
                 tessellate_join(
@@ -1468,14 +2789,29 @@ impl<'l> StrokeBuilderImpl<'l> {
                     &self.options,
                     &mut self.vertex,
                     attributes,
-                    self.output,
+                    &mut self.output,
                 )?;
 
+                if let Some(builder) = self.join_builder.as_deref_mut() {
+                    tessellate_custom_join(
+                        builder,
+                        prev.position,
+                        join,
+                        next.position,
+                        &mut self.custom_vertices,
+                        &mut self.vertex,
+                        attributes,
+                        &mut self.output,
+                    )?;
+                }
+
                 if count == 2 {
                     self.firsts.push(*prev);
                     self.firsts.push(*join);
                 }
             }
+
+            self.vertex.convex_side = None;
         }
 
         if skip {
@@ -1489,6 +2825,12 @@ impl<'l> StrokeBuilderImpl<'l> {
 
     #[cfg_attr(feature = "profiling", inline(never))]
     pub(crate) fn step(&mut self, next: EndpointData, attributes: &dyn AttributeStore) {
+        if let Some(mut dash) = self.dash.take() {
+            self.dashed_step(&mut dash, next, attributes);
+            self.dash = Some(dash);
+            return;
+        }
+
         if let Err(e) = self.step_impl(next, attributes) {
             self.error(e);
         }
@@ -1539,6 +2881,8 @@ impl<'l> StrokeBuilderImpl<'l> {
             self.vertex.position_on_path = join.position;
             self.vertex.half_width = join.half_width;
             self.vertex.buffer_is_valid = false;
+            self.vertex.convex_side =
+                Some(convex_side(prev.position, join.position, next.position));
             // We can take the fast path if the join is a flattening step and
             // not at a sharp turn.
             let fast_path = if join.is_flattening_step {
@@ -1557,14 +2901,14 @@ impl<'l> StrokeBuilderImpl<'l> {
                     &mut next,
                     &mut self.vertex,
                     attributes,
-                    self.output,
+                    &mut self.output,
                 )?;
             } else {
                 compute_join_side_positions_fixed_width(
                     prev,
                     join,
                     &next,
-                    self.options.miter_limit,
+                    &self.options,
                     &mut self.vertex,
                 )?;
 
@@ -1572,20 +2916,20 @@ impl<'l> StrokeBuilderImpl<'l> {
                     join,
                     &mut self.vertex,
                     attributes,
-                    self.output,
+                    &mut self.output,
                     Side::Negative,
                 )?;
                 add_join_base_vertices(
                     join,
                     &mut self.vertex,
                     attributes,
-                    self.output,
+                    &mut self.output,
                     Side::Positive,
                 )?;
             }
 
             if count > 2 {
-                add_edge_triangles(prev, join, self.output);
+                add_edge_triangles(prev, join, StrokePart::Edge, &mut self.output);
             }
 
             tessellate_join(
@@ -1593,13 +2937,28 @@ impl<'l> StrokeBuilderImpl<'l> {
                 &self.options,
                 &mut self.vertex,
                 attributes,
-                self.output,
+                &mut self.output,
             )?;
 
+            if let Some(builder) = self.join_builder.as_deref_mut() {
+                tessellate_custom_join(
+                    builder,
+                    prev.position,
+                    join,
+                    next.position,
+                    &mut self.custom_vertices,
+                    &mut self.vertex,
+                    attributes,
+                    &mut self.output,
+                )?;
+            }
+
             if count == 2 {
                 self.firsts.push(*prev);
                 self.firsts.push(*join);
             }
+
+            self.vertex.convex_side = None;
         }
 
         self.point_buffer.push(next);
@@ -1609,10 +2968,287 @@ impl<'l> StrokeBuilderImpl<'l> {
 
     #[cfg_attr(feature = "profiling", inline(never))]
     pub(crate) fn fixed_width_step(&mut self, next: EndpointData, attributes: &dyn AttributeStore) {
+        if let Some(mut dash) = self.dash.take() {
+            self.dashed_step(&mut dash, next, attributes);
+            self.dash = Some(dash);
+            return;
+        }
+
         if let Err(e) = self.fixed_width_step_impl(next, attributes) {
             self.error(e);
         }
     }
+
+    // Dashing is applied on top of the flattened sub-paths: each dash is tessellated as an
+    // open sub-path made of the points of the original sub-path that it contains, plus
+    // its two ends.
+
+    fn dashed_step(
+        &mut self,
+        dash: &mut DashState,
+        next: EndpointData,
+        attributes: &dyn AttributeStore,
+    ) {
+        let prev = match dash.prev {
+            Some(prev) => prev,
+            None => {
+                // Beginning of a sub-path.
+                dash.restart();
+                dash.first = Some(next);
+                dash.prev = Some(next);
+                dash.start_advancement = next.advancement;
+                if dash.is_on() {
+                    dash.in_first_dash = true;
+                    self.dash_start(dash, next, attributes);
+                }
+                return;
+            }
+        };
+
+        let length = (next.position - prev.position).length();
+        let mut offset = 0.0;
+        while dash.remaining < length - offset {
+            offset += dash.remaining;
+            let mut point = interpolate_endpoints(&prev, &next, offset / length);
+            point.advancement = dash.start_advancement + dash.distance + offset;
+            if dash.is_on() {
+                self.dash_end(dash, point, attributes);
+            } else {
+                self.dash_start(dash, point, attributes);
+            }
+            dash.next_interval();
+        }
+        dash.remaining -= length - offset;
+
+        if dash.is_on() {
+            self.dash_point(dash, next, attributes);
+        }
+
+        dash.distance += length;
+        dash.prev = Some(next);
+    }
+
+    fn dashed_end(&mut self, dash: &mut DashState, close: bool, attributes: &dyn AttributeStore) {
+        let first = match dash.first {
+            Some(first) => first,
+            None => {
+                return;
+            }
+        };
+
+        if close {
+            self.dashed_step(
+                dash,
+                EndpointData {
+                    advancement: f32::NAN,
+                    is_flattening_step: false,
+                    ..first
+                },
+                attributes,
+            );
+        }
+
+        if close && dash.in_first_dash {
+            // The sub-path is entirely covered by a single dash, it is closed normally.
+            dash.first_dash.pop();
+            self.may_need_empty_cap = false;
+            for point in dash.first_dash.drain(..) {
+                self.dash_inner_step(point, attributes);
+            }
+            self.end_sub_path(true, attributes);
+        } else if close && dash.is_on() && !dash.first_dash.is_empty() {
+            // The last dash continues into the first one.
+            for point in dash.first_dash.drain(1..) {
+                self.dash_inner_step(
+                    EndpointData {
+                        advancement: f32::NAN,
+                        ..point
+                    },
+                    attributes,
+                );
+            }
+            self.end_sub_path(false, attributes);
+        } else {
+            if dash.is_on() && !dash.in_first_dash {
+                self.end_sub_path(false, attributes);
+            }
+            if !dash.first_dash.is_empty() {
+                self.may_need_empty_cap = false;
+                for point in dash.first_dash.drain(..) {
+                    self.dash_inner_step(point, attributes);
+                }
+                self.end_sub_path(false, attributes);
+            }
+        }
+
+        self.sub_path_start_advancement = dash.start_advancement + dash.distance;
+        dash.first_dash.clear();
+        dash.first = None;
+        dash.prev = None;
+        dash.in_first_dash = false;
+    }
+
+    fn dash_start(
+        &mut self,
+        dash: &mut DashState,
+        point: EndpointData,
+        attributes: &dyn AttributeStore,
+    ) {
+        if !dash.in_first_dash {
+            self.may_need_empty_cap = false;
+        }
+        self.dash_point(dash, point, attributes);
+    }
+
+    fn dash_end(
+        &mut self,
+        dash: &mut DashState,
+        point: EndpointData,
+        attributes: &dyn AttributeStore,
+    ) {
+        self.dash_point(dash, point, attributes);
+        if dash.in_first_dash {
+            dash.in_first_dash = false;
+        } else {
+            self.end_sub_path(false, attributes);
+        }
+    }
+
+    fn dash_point(
+        &mut self,
+        dash: &mut DashState,
+        point: EndpointData,
+        attributes: &dyn AttributeStore,
+    ) {
+        if dash.in_first_dash {
+            // Kept until the end of the sub-path, in case it is closed and the first dash
+            // continues the last one.
+            dash.first_dash.push(point);
+        } else {
+            self.dash_inner_step(point, attributes);
+        }
+    }
+
+    fn dash_inner_step(&mut self, point: EndpointData, attributes: &dyn AttributeStore) {
+        let result = if self.options.variable_line_width.is_some() {
+            self.step_impl(point, attributes)
+        } else {
+            self.fixed_width_step_impl(point, attributes)
+        };
+
+        if let Err(e) = result {
+            self.error(e);
+        }
+    }
+}
+
+/// The position in the dash pattern along the current sub-path.
+struct DashState {
+    pattern: DashArray,
+    offset: f32,
+    /// Index of the current interval of the pattern. Even intervals are dashes.
+    index: usize,
+    /// Distance left until the end of the current interval.
+    remaining: f32,
+    /// Distance along the current sub-path.
+    distance: f32,
+    start_advancement: f32,
+    first: Option<EndpointData>,
+    prev: Option<EndpointData>,
+    /// Points of the dash at the start of the sub-path.
+    first_dash: Vec<EndpointData>,
+    in_first_dash: bool,
+}
+
+impl DashState {
+    fn new(pattern: DashArray, offset: f32) -> Self {
+        DashState {
+            pattern,
+            offset,
+            index: 0,
+            remaining: 0.0,
+            distance: 0.0,
+            start_advancement: 0.0,
+            first: None,
+            prev: None,
+            first_dash: Vec::new(),
+            in_first_dash: false,
+        }
+    }
+
+    fn restart(&mut self) {
+        self.distance = 0.0;
+        self.index = 0;
+        self.remaining = self.pattern.lengths()[0];
+
+        let mut offset = self.offset.rem_euclid(self.pattern.total_length());
+        // Zero length dashes at the start of the sub-path are not skipped.
+        while offset > 0.0 && offset >= self.remaining {
+            offset -= self.remaining;
+            self.next_interval();
+        }
+        self.remaining -= offset;
+    }
+
+    fn next_interval(&mut self) {
+        let lengths = self.pattern.lengths();
+        self.index = (self.index + 1) % lengths.len();
+        self.remaining = lengths[self.index];
+    }
+
+    fn is_on(&self) -> bool {
+        self.index & 1 == 0
+    }
+}
+
+fn interpolate_endpoints(a: &EndpointData, b: &EndpointData, t: f32) -> EndpointData {
+    EndpointData {
+        position: a.position.lerp(b.position, t),
+        half_width: a.half_width + (b.half_width - a.half_width) * t,
+        line_join: b.line_join,
+        src: interpolate_source(a.src, b.src, t),
+        ..Default::default()
+    }
+}
+
+/// The source of a point at `t` between two points with sources `a` and `b`.
+pub(crate) fn interpolate_source(a: VertexSource, b: VertexSource, t: f32) -> VertexSource {
+    match (a, b) {
+        (VertexSource::Endpoint { id: from }, VertexSource::Endpoint { id: to }) => {
+            VertexSource::Edge { from, to, t }
+        }
+        (
+            VertexSource::Endpoint { id },
+            VertexSource::Edge {
+                from, to, t: t1, ..
+            },
+        ) if id == from => VertexSource::Edge {
+            from,
+            to,
+            t: t * t1,
+        },
+        (VertexSource::Edge { from, to, t: t0 }, VertexSource::Endpoint { id }) if id == to => {
+            VertexSource::Edge {
+                from,
+                to,
+                t: t0 + (1.0 - t0) * t,
+            }
+        }
+        (VertexSource::Edge { from, to, t: t0 }, VertexSource::Edge { t: t1, .. }) => {
+            VertexSource::Edge {
+                from,
+                to,
+                t: t0 + (t1 - t0) * t,
+            }
+        }
+        _ => {
+            if t < 0.5 {
+                a
+            } else {
+                b
+            }
+        }
+    }
 }
 
 #[cfg_attr(feature = "profiling", inline(never))]
@@ -1620,7 +3256,7 @@ fn compute_join_side_positions_fixed_width(
     prev: &EndpointData,
     join: &mut EndpointData,
     next: &EndpointData,
-    miter_limit: f32,
+    options: &StrokeOptions,
     vertex: &mut StrokeVertexData,
 ) -> Result<(), TessellationError> {
     let prev_tangent = join.position - prev.position;
@@ -1677,15 +3313,25 @@ fn compute_join_side_positions_fixed_width(
         ];
 
         join.side_points[back_side].single_vertex = Some(miter_pos[back_side]);
-        if (join.line_join == LineJoin::Miter || join.line_join == LineJoin::MiterClip)
-            && !miter_limit_is_exceeded(front_normal, miter_limit)
-        {
+        let is_miter = match join.line_join {
+            LineJoin::Miter | LineJoin::MiterClip => true,
+            LineJoin::Arcs => {
+                arcs_join_intersection(join, front_side, options.miter_limit).is_none()
+            }
+            _ => false,
+        };
+        if is_miter && !miter_limit_is_exceeded(front_normal, options.miter_limit) {
             join.side_points[front_side].single_vertex = Some(miter_pos[front_side]);
         } else if join.line_join == LineJoin::MiterClip {
             let n0 = join.side_points[front_side].prev - join.position;
             let n1 = join.side_points[front_side].next - join.position;
-            let (prev_normal, next_normal) =
-                get_clip_intersections(n0, n1, front_normal, miter_limit * 0.5 * vertex.half_width);
+            let (prev_normal, next_normal) = get_clip_intersections(
+                n0,
+                n1,
+                front_normal * vertex.half_width,
+                prev_tangent,
+                options.effective_miter_clip_distance() * vertex.half_width,
+            );
             join.side_points[front_side].prev = join.position + prev_normal;
             join.side_points[front_side].next = join.position + next_normal;
         }
@@ -1813,7 +3459,7 @@ fn compute_side_attachment_positions(
         p1.half_width
     );
 
-    let nl = side_sign(side);
+    let nl: f32 = side_sign(side);
 
     let normal_angle = edge_angle + nl * (PI * 0.5 + vwidth_angle);
     let normal = vector(normal_angle.cos(), normal_angle.sin());
@@ -1831,6 +3477,7 @@ fn compute_side_attachment_positions(
 fn add_edge_triangles(
     p0: &EndpointData,
     p1: &EndpointData,
+    part: StrokePart,
     output: &mut dyn StrokeGeometryBuilder,
 ) {
     let mut p0_neg = p0.side_points[SIDE_NEGATIVE].next_vertex;
@@ -1851,6 +3498,7 @@ fn add_edge_triangles(
         p1_pos = p1.side_points[SIDE_NEGATIVE].next_vertex;
     }
 
+    output.set_stroke_part(part);
     output.add_triangle(p0_neg, p0_pos, p1_pos);
 
     output.add_triangle(p0_neg, p1_pos, p1_neg);
@@ -1864,11 +3512,15 @@ fn tessellate_join(
     attributes: &dyn AttributeStore,
     output: &mut dyn StrokeGeometryBuilder,
 ) -> Result<(), TessellationError> {
+    trace_span!("join");
+
     let side_needs_join = [
         join.side_points[SIDE_POSITIVE].single_vertex.is_none(),
         join.side_points[SIDE_NEGATIVE].single_vertex.is_none(),
     ];
 
+    output.set_stroke_part(StrokePart::Join);
+
     if !join.fold[SIDE_POSITIVE] && !join.fold[SIDE_NEGATIVE] {
         // Tessellate the interior of the join.
         match side_needs_join {
@@ -1911,12 +3563,107 @@ fn tessellate_join(
 
         if join.line_join == LineJoin::Round {
             tessellate_round_join(join, side, options, vertex, attributes, output)?;
+        } else if join.line_join == LineJoin::Arcs {
+            if let Some(x) = arcs_join_intersection(join, side, options.miter_limit) {
+                tessellate_arcs_join(join, side, x, options, vertex, attributes, output)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Calls the custom join builder for the sides of a join that aren't connected with a
+// single vertex.
+fn tessellate_custom_join(
+    builder: &mut dyn JoinBuilder,
+    prev: Point,
+    join: &EndpointData,
+    next: Point,
+    vertices: &mut Vec<(VertexId, Point)>,
+    vertex: &mut StrokeVertexData,
+    attributes: &dyn AttributeStore,
+    output: &mut dyn StrokeGeometryBuilder,
+) -> Result<(), TessellationError> {
+    let prev_tangent = (join.position - prev).normalize();
+    let next_tangent = (next - join.position).normalize();
+    for (side, side_id) in [
+        (SIDE_POSITIVE, Side::Positive),
+        (SIDE_NEGATIVE, Side::Negative),
+    ] {
+        let side_points = &join.side_points[side];
+        if side_points.single_vertex.is_some() {
+            continue;
         }
+
+        vertices.clear();
+        vertices.push((side_points.prev_vertex, side_points.prev));
+        vertices.push((side_points.next_vertex, side_points.next));
+        vertex.side = side_id;
+        output.set_stroke_part(StrokePart::Join);
+
+        builder.add_join(
+            join.position,
+            prev_tangent,
+            next_tangent,
+            side_id,
+            &mut CustomShapeOutput {
+                vertex,
+                attributes,
+                output,
+                vertices,
+                side: Some(side_id),
+                positive_normal: vector(0.0, 0.0),
+            },
+        )?;
     }
 
     Ok(())
 }
 
+// Calls the custom cap builder at an endpoint, where `direction` points away from the
+// stroke and `base` contains the vertices of the butt cap on the left and right of it.
+fn tessellate_custom_cap(
+    builder: &mut dyn CapBuilder,
+    endpoint: &EndpointData,
+    direction: Vector,
+    is_start: bool,
+    base: [VertexId; 2],
+    vertices: &mut Vec<(VertexId, Point)>,
+    vertex: &mut StrokeVertexData,
+    attributes: &dyn AttributeStore,
+    output: &mut dyn StrokeGeometryBuilder,
+) -> Result<(), TessellationError> {
+    let tangent = direction.normalize();
+    let normal = vector(-tangent.y, tangent.x);
+    let positive_normal = if is_start { -normal } else { normal };
+    let half_width = endpoint.half_width;
+
+    vertices.clear();
+    vertices.push((base[0], endpoint.position + normal * half_width));
+    vertices.push((base[1], endpoint.position - normal * half_width));
+    output.set_stroke_part(if is_start {
+        StrokePart::StartCap
+    } else {
+        StrokePart::EndCap
+    });
+
+    builder.add_cap(
+        endpoint.position,
+        tangent,
+        &mut CustomShapeOutput {
+            vertex,
+            attributes,
+            output,
+            vertices,
+            side: None,
+            positive_normal,
+        },
+    )?;
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "profiling", inline(never))]
 fn tessellate_round_join(
     join: &mut EndpointData,
@@ -1951,10 +3698,18 @@ fn tessellate_round_join(
         std::mem::swap(&mut start_vertex, &mut end_vertex);
     }
 
-    // Compute the required number of subdivisions,
-    let step = circle_flattening_step(radius, options.tolerance);
-    let num_segments = (diff.radians.abs() / step).ceil();
-    let num_subdivisions = num_segments.log2().round() as u32;
+    // Compute the required number of segments from the actual angle of the join
+    // rather than rounding to a power of two, which could produce up to twice as
+    // many vertices as needed (or not enough).
+    let num_segments = match options.round_segments {
+        Some(n) => (diff.radians.abs() / PI * n as f32).ceil(),
+        None => (diff.radians.abs() / circle_flattening_step(radius, options.tolerance)).ceil(),
+    };
+    let num_segments = if num_segments.is_finite() {
+        num_segments.max(1.0) as u32
+    } else {
+        1
+    };
 
     vertex.side = if side == SIDE_POSITIVE {
         Side::Positive
@@ -1962,74 +3717,300 @@ fn tessellate_round_join(
         Side::Negative
     };
 
-    crate::stroke::tessellate_arc(
+    tessellate_arc_fan(
         (start_angle.radians, end_angle.radians),
-        radius,
         start_vertex,
         end_vertex,
-        num_subdivisions,
+        num_segments,
         vertex,
         attributes,
         output,
     )
 }
 
-#[cfg_attr(feature = "profiling", inline(never))]
-fn add_join_base_vertices(
-    join: &mut EndpointData,
+/// Tessellates the area between an arc and its chord with a triangle fan, splitting
+/// the arc into `num_segments` segments of equal angle.
+fn tessellate_arc_fan(
+    angle: (f32, f32),
+    va: VertexId,
+    vb: VertexId,
+    num_segments: u32,
     vertex: &mut StrokeVertexData,
     attributes: &dyn AttributeStore,
     output: &mut dyn StrokeGeometryBuilder,
-    side: Side,
 ) -> Result<(), TessellationError> {
-    vertex.side = side;
+    let step = (angle.1 - angle.0) / num_segments as f32;
+    let mut prev = None;
+    for i in 1..num_segments {
+        let a = angle.0 + step * i as f32;
+        vertex.normal = vector(a.cos(), a.sin());
+        let vertex_id = output.add_stroke_vertex(StrokeVertex(vertex, attributes))?;
+
+        if let Some(prev) = prev {
+            output.add_triangle(va, prev, vertex_id);
+        }
+        prev = Some(vertex_id);
+    }
 
-    let side = match side {
-        Side::Positive => SIDE_POSITIVE,
-        Side::Negative => SIDE_NEGATIVE,
-    };
+    if let Some(prev) = prev {
+        output.add_triangle(va, prev, vb);
+    }
 
-    if let Some(pos) = join.side_points[side].single_vertex {
-        vertex.normal = (pos - join.position) / join.half_width;
-        let vertex = output.add_stroke_vertex(StrokeVertex(vertex, attributes))?;
-        join.side_points[side].prev_vertex = vertex;
-        join.side_points[side].next_vertex = vertex;
-    } else {
-        vertex.normal = (join.side_points[side].prev - join.position) / join.half_width;
-        let prev_vertex = output.add_stroke_vertex(StrokeVertex(vertex, attributes))?;
+    Ok(())
+}
 
-        vertex.normal = (join.side_points[side].next - join.position) / join.half_width;
-        let next_vertex = output.add_stroke_vertex(StrokeVertex(vertex, attributes))?;
+// Returns the intersection of the arcs of a `LineJoin::Arcs` join on a side, or `None` if
+// the join falls back to a miter join.
+//
+// Each arc starts at a side point, is tangent to the outer edge of the stroke there and
+// has the curvature of the edge's offset, which shares the center of curvature of the path.
+fn arcs_join_intersection(join: &EndpointData, side: usize, miter_limit: f32) -> Option<Point> {
+    if join.prev_curvature == 0.0 && join.next_curvature == 0.0 {
+        return None;
+    }
 
-        join.side_points[side].prev_vertex = prev_vertex;
-        join.side_points[side].next_vertex = next_vertex;
+    let sign: f32 = side_sign(side);
+    let arc = |side_point: Point, curvature: f32, forward: bool| {
+        let normal = (side_point - join.position) * sign;
+        let tangent = vector(normal.y, -normal.x).normalize();
+        let direction = if forward { tangent } else { -tangent };
+        let center = if curvature.abs() > 1e-6 {
+            Some(join.position + normal.normalize() / curvature)
+        } else {
+            None
+        };
+        (side_point, direction, center)
+    };
+    let arcs = [
+        arc(join.side_points[side].prev, join.prev_curvature, true),
+        arc(join.side_points[side].next, join.next_curvature, false),
+    ];
+
+    let mut candidates: ArrayVec<Point, 2> = ArrayVec::new();
+    match (arcs[0], arcs[1]) {
+        ((p0, d0, None), (p1, d1, None)) => {
+            let denominator = d0.cross(d1);
+            if denominator.abs() > 1e-6 {
+                candidates.push(p0 + d0 * ((p1 - p0).cross(d1) / denominator));
+            }
+        }
+        ((p, d, None), (_, _, Some(center))) | ((_, _, Some(center)), (p, d, None)) => {
+            let other = if arcs[0].2.is_none() {
+                arcs[1].0
+            } else {
+                arcs[0].0
+            };
+            let radius = (other - center).length();
+            let f = p - center;
+            let b = f.dot(d);
+            let discriminant = b * b - f.square_length() + radius * radius;
+            if discriminant >= 0.0 {
+                let root = discriminant.sqrt();
+                candidates.push(p + d * (-b - root));
+                candidates.push(p + d * (-b + root));
+            }
+        }
+        ((p0, _, Some(c0)), (p1, _, Some(c1))) => {
+            let r0 = (p0 - c0).length();
+            let r1 = (p1 - c1).length();
+            let v = c1 - c0;
+            let d = v.length();
+            if d > 1e-6 && d <= r0 + r1 && d >= (r0 - r1).abs() {
+                let a = (r0 * r0 - r1 * r1 + d * d) / (2.0 * d);
+                let h = (r0 * r0 - a * a).max(0.0).sqrt();
+                let m = c0 + v * (a / d);
+                let n = vector(-v.y, v.x) / d;
+                candidates.push(m + n * h);
+                candidates.push(m - n * h);
+            }
+        }
     }
 
-    Ok(())
+    // Only keep the intersections that are in front of both side points and pick the one
+    // closest to the join.
+    let intersection = candidates
+        .iter()
+        .filter(|x| arcs.iter().all(|(p, d, _)| (**x - *p).dot(*d) > 0.0))
+        .min_by(|a, b| {
+            let a = (**a - join.position).square_length();
+            let b = (**b - join.position).square_length();
+            a.partial_cmp(&b).unwrap()
+        })
+        .cloned()?;
+
+    let limit = miter_limit * 0.5 * join.half_width;
+    if (intersection - join.position).square_length() > limit * limit {
+        return None;
+    }
+
+    Some(intersection)
 }
 
-// TODO: the naming is a bit confusing. We do half of the work to compute the join's side positions
-// in compute_side_attachment_positions.
+// Tessellates the area between the chord and the two arcs of a `LineJoin::Arcs` join.
 #[cfg_attr(feature = "profiling", inline(never))]
-fn compute_join_side_positions(
-    prev: &EndpointData,
-    join: &mut EndpointData,
-    next: &EndpointData,
-    miter_limit: f32,
+fn tessellate_arcs_join(
+    join: &EndpointData,
     side: usize,
-) {
-    nan_check!(join.position);
-    nan_check!(prev.side_points[side].next);
-    nan_check!(join.side_points[side].next);
+    intersection: Point,
+    options: &StrokeOptions,
+    vertex: &mut StrokeVertexData,
+    attributes: &dyn AttributeStore,
+    output: &mut dyn StrokeGeometryBuilder,
+) -> Result<(), TessellationError> {
+    vertex.side = if side == SIDE_POSITIVE {
+        Side::Positive
+    } else {
+        Side::Negative
+    };
 
-    let sign = side_sign(side);
-    let v0 = (join.side_points[side].prev - prev.side_points[side].next).normalize();
-    let v1 = (next.side_points[side].prev - join.side_points[side].next).normalize();
-    let inward = v0.cross(v1) * sign > 0.0;
-    let forward = v0.dot(v1) > 0.0;
+    let sign: f32 = side_sign(side);
+    let side_points = &join.side_points[side];
+    let mut add_vertex = |position: Point| -> Result<(VertexId, Point), TessellationError> {
+        vertex.normal = (position - join.position) / join.half_width;
+        let id = output.add_stroke_vertex(StrokeVertex(vertex, attributes))?;
+        Ok((id, position))
+    };
 
-    let normal = compute_normal(v0, v1) * sign;
-    let path_v0 = (join.position - prev.position).normalize();
+    let x = add_vertex(intersection)?;
+    let mut arcs = [
+        vec![(side_points.prev_vertex, side_points.prev)],
+        vec![(side_points.next_vertex, side_points.next)],
+    ];
+    let curvatures = [join.prev_curvature, join.next_curvature];
+    let mut points = Vec::new();
+    for i in 0..2 {
+        let start = arcs[i][0].1;
+        if curvatures[i].abs() > 1e-6 {
+            let normal = (start - join.position) * sign;
+            let center = join.position + normal.normalize() / curvatures[i];
+            let tangent = vector(normal.y, -normal.x);
+            let direction = if i == 0 { tangent } else { -tangent };
+            points.clear();
+            arc_points(center, start, intersection, direction, options, &mut points);
+            for p in &points {
+                arcs[i].push(add_vertex(*p)?);
+            }
+        }
+        arcs[i].push(x);
+    }
+
+    // The triangles have the same orientation as the rest of the stroke.
+    let mut add_triangle = |a: (VertexId, Point), b: (VertexId, Point), c: (VertexId, Point)| {
+        if (b.1 - a.1).cross(c.1 - a.1) > 0.0 {
+            output.add_triangle(a.0, c.0, b.0);
+        } else {
+            output.add_triangle(a.0, b.0, c.0);
+        }
+    };
+    add_triangle(arcs[0][0], x, arcs[1][0]);
+    for arc in &arcs {
+        for pair in arc[1..].windows(2) {
+            add_triangle(arc[0], pair[0], pair[1]);
+        }
+    }
+
+    Ok(())
+}
+
+// Pushes the points between `from` and `to` on the circle of the given center, going around
+// it in the direction that `direction` points to at `from`.
+fn arc_points(
+    center: Point,
+    from: Point,
+    to: Point,
+    direction: Vector,
+    options: &StrokeOptions,
+    output: &mut Vec<Point>,
+) {
+    let v0 = from - center;
+    let v1 = to - center;
+    let radius = v0.length();
+    let mut angle = v0.angle_to(v1).radians;
+    if v0.cross(direction) > 0.0 {
+        if angle < 0.0 {
+            angle += 2.0 * PI;
+        }
+    } else if angle > 0.0 {
+        angle -= 2.0 * PI;
+    }
+
+    let num_segments = match options.round_segments {
+        Some(n) => (angle.abs() / PI * n as f32).ceil(),
+        None => (angle.abs() / circle_flattening_step(radius, options.tolerance)).ceil(),
+    };
+    let num_segments = if num_segments.is_finite() {
+        num_segments.clamp(1.0, 64.0) as u32
+    } else {
+        1
+    };
+
+    let start = v0.angle_from_x_axis().radians;
+    let step = angle / num_segments as f32;
+    for i in 1..num_segments {
+        let a = start + step * i as f32;
+        output.push(center + vector(a.cos(), a.sin()) * radius);
+    }
+}
+
+#[cfg_attr(feature = "profiling", inline(never))]
+fn add_join_base_vertices(
+    join: &mut EndpointData,
+    vertex: &mut StrokeVertexData,
+    attributes: &dyn AttributeStore,
+    output: &mut dyn StrokeGeometryBuilder,
+    side: Side,
+) -> Result<(), TessellationError> {
+    vertex.side = side;
+
+    let side = match side {
+        Side::Positive => SIDE_POSITIVE,
+        Side::Negative => SIDE_NEGATIVE,
+    };
+
+    if let Some(pos) = join.side_points[side].single_vertex {
+        vertex.normal = (pos - join.position) / join.half_width;
+        let vertex = output.add_stroke_vertex(StrokeVertex(vertex, attributes))?;
+        join.side_points[side].prev_vertex = vertex;
+        join.side_points[side].next_vertex = vertex;
+    } else {
+        vertex.normal = (join.side_points[side].prev - join.position) / join.half_width;
+        vertex.order = Some(Order::Before);
+        let prev_vertex = output.add_stroke_vertex(StrokeVertex(vertex, attributes))?;
+
+        vertex.normal = (join.side_points[side].next - join.position) / join.half_width;
+        vertex.order = Some(Order::After);
+        let next_vertex = output.add_stroke_vertex(StrokeVertex(vertex, attributes))?;
+        vertex.order = None;
+
+        join.side_points[side].prev_vertex = prev_vertex;
+        join.side_points[side].next_vertex = next_vertex;
+    }
+
+    Ok(())
+}
+
+// TODO: the naming is a bit confusing. We do half of the work to compute the join's side positions
+// in compute_side_attachment_positions.
+#[cfg_attr(feature = "profiling", inline(never))]
+fn compute_join_side_positions(
+    prev: &EndpointData,
+    join: &mut EndpointData,
+    next: &EndpointData,
+    options: &StrokeOptions,
+    side: usize,
+) {
+    nan_check!(join.position);
+    nan_check!(prev.side_points[side].next);
+    nan_check!(join.side_points[side].next);
+
+    let sign: f32 = side_sign(side);
+    let v0 = (join.side_points[side].prev - prev.side_points[side].next).normalize();
+    let v1 = (next.side_points[side].prev - join.side_points[side].next).normalize();
+    let inward = v0.cross(v1) * sign > 0.0;
+    let forward = v0.dot(v1) > 0.0;
+
+    let normal = compute_normal(v0, v1) * sign;
+    let path_v0 = (join.position - prev.position).normalize();
     let path_v1 = (next.position - join.position).normalize();
 
     nan_check!(v0, v1);
@@ -2059,10 +4040,12 @@ fn compute_join_side_positions(
     // For concave sides we'll simply connect at the intersection of the two side edges.
     let concave = inward && normal_same_side && !join.fold[side];
 
-    if concave
-        || ((join.line_join == LineJoin::Miter || join.line_join == LineJoin::MiterClip)
-            && !miter_limit_is_exceeded(normal, miter_limit))
-    {
+    let is_miter = match join.line_join {
+        LineJoin::Miter | LineJoin::MiterClip => true,
+        LineJoin::Arcs => arcs_join_intersection(join, side, options.miter_limit).is_none(),
+        _ => false,
+    };
+    if concave || (is_miter && !miter_limit_is_exceeded(normal, options.miter_limit)) {
         let p = join.position + normal * join.half_width;
         join.side_points[side].single_vertex = Some(p);
     } else if join.line_join == LineJoin::MiterClip {
@@ -2071,8 +4054,13 @@ fn compute_join_side_positions(
         // This way the rest of the code doesn't differentiate between miter and miter-clip.
         let n0 = join.side_points[side].prev - join.position;
         let n1 = join.side_points[side].next - join.position;
-        let (prev_normal, next_normal) =
-            get_clip_intersections(n0, n1, normal, miter_limit * 0.5 * join.half_width);
+        let (prev_normal, next_normal) = get_clip_intersections(
+            n0,
+            n1,
+            normal * join.half_width,
+            path_v0,
+            options.effective_miter_clip_distance() * join.half_width,
+        );
         join.side_points[side].prev = join.position + prev_normal;
         join.side_points[side].next = join.position + next_normal;
         nan_check!(n0, n1, prev_normal, next_normal);
@@ -2102,6 +4090,21 @@ fn tessellate_last_edge(
     let sides = [Side::Positive, Side::Negative];
 
     for side in 0..2 {
+        if let Some(clip) = cap_clip_distance(options.end_cap, p1.half_width) {
+            let side_position = p1.side_points[side].prev;
+            let normal = v.normalize();
+            let clip_line = Line {
+                point: p1.position + normal * clip,
+                vector: tangent(normal),
+            };
+            let side_line = Line {
+                point: side_position,
+                vector: side_position - p0.side_points[side].next,
+            };
+
+            p1.side_points[side].prev = clip_line.intersection(&side_line).unwrap_or(side_position);
+        }
+
         vertex.side = sides[side];
         vertex.normal = (p1.side_points[side].prev - p1.position) / p1.half_width;
         let prev_vertex = output.add_stroke_vertex(StrokeVertex(vertex, attributes))?;
@@ -2110,11 +4113,12 @@ fn tessellate_last_edge(
 
     // Skip the edge triangles if it is also the first edge (tessellate_first_edge will do it).
     if !is_first_edge {
-        add_edge_triangles(p0, p1, output);
+        let part = square_cap_edge_part(options.end_cap, StrokePart::EndCap);
+        add_edge_triangles(p0, p1, part, output);
     }
 
-    if options.end_cap == LineCap::Round {
-        crate::stroke::tessellate_round_cap(
+    match options.end_cap {
+        LineCap::Round => crate::stroke::tessellate_round_cap(
             p1.position,
             p1.half_width,
             p1.side_points[SIDE_POSITIVE].prev - p1.position,
@@ -2126,15 +4130,29 @@ fn tessellate_last_edge(
             vertex,
             attributes,
             output,
-        )?;
+        ),
+        LineCap::Arrow => crate::stroke::tessellate_arrow_cap(
+            p1.position,
+            p1.half_width,
+            v,
+            p1.side_points[SIDE_POSITIVE].prev - p1.position,
+            p1.side_points[SIDE_POSITIVE].prev_vertex,
+            p1.side_points[SIDE_NEGATIVE].prev_vertex,
+            (options.arrow_length, options.arrow_width),
+            false,
+            vertex,
+            attributes,
+            output,
+        ),
+        _ => Ok(()),
     }
-
-    Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn tessellate_first_edge(
     first: &mut EndpointData,
     second: &EndpointData,
+    is_last_edge: bool,
     options: &StrokeOptions,
     vertex: &mut StrokeVertexData,
     attributes: &dyn AttributeStore,
@@ -2150,13 +4168,7 @@ fn tessellate_first_edge(
 
     for side in 0..2 {
         let mut side_position = first.side_points[side].next;
-        let clip = match options.start_cap {
-            LineCap::Square => Some(first.half_width),
-            LineCap::Butt => Some(0.0),
-            _ => None,
-        };
-
-        if let Some(clip) = clip {
+        if let Some(clip) = cap_clip_distance(options.start_cap, first.half_width) {
             let normal = (first.position - second.position).normalize();
             let clip_line = Line {
                 point: first.position + normal * clip,
@@ -2180,7 +4192,11 @@ fn tessellate_first_edge(
     }
 
     // Tessellate the edge between prev and join.
-    add_edge_triangles(first, second, output);
+    let mut part = square_cap_edge_part(options.start_cap, StrokePart::StartCap);
+    if is_last_edge && part == StrokePart::Edge {
+        part = square_cap_edge_part(options.end_cap, StrokePart::EndCap);
+    }
+    add_edge_triangles(first, second, part, output);
 
     match options.start_cap {
         LineCap::Round => crate::stroke::tessellate_round_cap(
@@ -2196,53 +4212,115 @@ fn tessellate_first_edge(
             attributes,
             output,
         ),
+        LineCap::Arrow => crate::stroke::tessellate_arrow_cap(
+            first.position,
+            first.half_width,
+            first.position - second.position,
+            first.side_points[SIDE_NEGATIVE].next - first.position,
+            first.side_points[SIDE_NEGATIVE].next_vertex,
+            first.side_points[SIDE_POSITIVE].next_vertex,
+            (options.arrow_length, options.arrow_width),
+            true,
+            vertex,
+            attributes,
+            output,
+        ),
         _ => Ok(()),
     }
 }
 
-#[cfg_attr(feature = "profiling", inline(never))]
-fn get_clip_intersections(
-    previous_normal: Vector,
-    next_normal: Vector,
-    normal: Vector,
-    clip_distance: f32,
-) -> (Vector, Vector) {
-    let clip_line = Line {
-        point: normal.normalize().to_point() * clip_distance,
-        vector: tangent(normal),
-    };
+/// The part that the triangles of the first or last edge of a sub-path are reported as.
+///
+/// Square caps are produced by extending the edge rather than with triangles of their own,
+/// so the triangles of the extended edge are reported as the cap. If the sub-path has a
+/// single edge and both caps are square, it is reported as the start cap.
+pub(crate) fn square_cap_edge_part(cap: LineCap, cap_part: StrokePart) -> StrokePart {
+    match cap {
+        LineCap::Square => cap_part,
+        _ => StrokePart::Edge,
+    }
+}
+
+/// How far beyond the endpoint the sides of the stroke extend, or `None` for round caps
+/// which are tessellated around the unclipped side points.
+fn cap_clip_distance(cap: LineCap, half_width: f32) -> Option<f32> {
+    match cap {
+        LineCap::Square => Some(half_width),
+        LineCap::Butt | LineCap::Arrow => Some(0.0),
+        LineCap::Round => None,
+    }
+}
 
-    let prev_line = Line {
-        point: previous_normal.to_point(),
-        vector: tangent(previous_normal),
+#[cfg_attr(feature = "profiling", inline(never))]
+/// Computes where the two side edges of a join intersect the clip line of a
+/// miter-clip join.
+///
+/// - `n0` and `n1` are the offsets of the side points from the join position.
+/// - `miter` is the offset of the (unclipped) miter point, which is zero for hairpin joins.
+/// - `prev_tangent` is the direction of the incoming edge, used as the clip direction
+///   when the miter is degenerate.
+pub(crate) fn get_clip_intersections<S: Scalar>(
+    n0: Vector2D<S>,
+    n1: Vector2D<S>,
+    miter: Vector2D<S>,
+    prev_tangent: Vector2D<S>,
+    clip_distance: S,
+) -> (Vector2D<S>, Vector2D<S>) {
+    let miter_length = miter.length();
+    let (direction, max_distance) = if miter_length > S::value(1e-5) {
+        (miter / miter_length, miter_length)
+    } else {
+        // Hairpin join: the two edges are (almost) parallel and the miter goes
+        // to infinity in the direction of the incoming edge.
+        (prev_tangent.normalize(), S::infinity())
     };
 
-    let next_line = Line {
-        point: next_normal.to_point(),
-        vector: tangent(next_normal),
+    // Don't clip beyond the miter point or behind the side points.
+    let clip_distance = clip_distance
+        .min(max_distance)
+        .max(n0.dot(direction))
+        .max(n1.dot(direction));
+
+    // Intersect the line going through the side point `n` with direction `tangent(n)`
+    // with the clip line. If they are parallel there is nothing to clip.
+    let intersect = |n: Vector2D<S>| {
+        let t = tangent(n);
+        let t_dot_d = t.dot(direction);
+        if t_dot_d.abs() < S::value(1e-6) {
+            return n;
+        }
+        let s = (clip_distance - n.dot(direction)) / t_dot_d;
+        let p = n + t * s;
+        if p.x.is_finite() && p.y.is_finite() {
+            p
+        } else {
+            n
+        }
     };
 
-    let i1 = clip_line
-        .intersection(&prev_line)
-        .unwrap_or_else(|| normal.to_point())
-        .to_vector();
-    let i2 = clip_line
-        .intersection(&next_line)
-        .unwrap_or_else(|| normal.to_point())
-        .to_vector();
+    (intersect(n0), intersect(n1))
+}
 
-    (i1, i2)
+/// The outer side of the turn between the edges before and after `join`.
+pub(crate) fn convex_side<S: Scalar>(prev: Point2D<S>, join: Point2D<S>, next: Point2D<S>) -> Side {
+    if (join - prev).cross(next - join) >= S::ZERO {
+        Side::Negative
+    } else {
+        Side::Positive
+    }
 }
 
-fn miter_limit_is_exceeded(normal: Vector, miter_limit: f32) -> bool {
-    normal.square_length() > miter_limit * miter_limit * 0.25
+pub(crate) fn miter_limit_is_exceeded<S: Scalar>(normal: Vector2D<S>, miter_limit: S) -> bool {
+    // A zero normal means that the join is a hairpin turn, where the miter is infinitely long.
+    let sq_len = normal.square_length();
+    sq_len > miter_limit * miter_limit * S::value(0.25) || sq_len < S::value(1e-10)
 }
 
-fn side_sign(side: usize) -> f32 {
+pub(crate) fn side_sign<S: Scalar>(side: usize) -> S {
     if side == SIDE_NEGATIVE {
-        -1.0
+        -S::ONE
     } else {
-        1.0
+        S::ONE
     }
 }
 
@@ -2405,6 +4483,8 @@ pub(crate) fn tessellate_round_cap(
     let step = circle_flattening_step(radius, options.tolerance);
     let num_segments = (diff.radians.abs() / step).ceil();
     let num_subdivisions = num_segments.log2().round() as u32;
+    // or use a fixed number of segments on each side.
+    let fixed_segments = options.round_segments.map(|n| n.div_ceil(2));
 
     vertex.position_on_path = center;
     vertex.half_width = radius;
@@ -2413,31 +4493,115 @@ pub(crate) fn tessellate_round_cap(
     vertex.normal = edge_normal.normalize();
     let mid_vertex = output.add_stroke_vertex(StrokeVertex(vertex, attributes))?;
 
+    output.set_stroke_part(if is_start {
+        StrokePart::StartCap
+    } else {
+        StrokePart::EndCap
+    });
     output.add_triangle(start_vertex, mid_vertex, end_vertex);
 
-    tessellate_arc(
-        (start_angle.radians, mid_angle.radians),
-        radius,
-        start_vertex,
-        mid_vertex,
-        num_subdivisions,
-        vertex,
-        attributes,
-        output,
-    )?;
+    if let Some(num_segments) = fixed_segments {
+        tessellate_arc_fan(
+            (start_angle.radians, mid_angle.radians),
+            start_vertex,
+            mid_vertex,
+            num_segments,
+            vertex,
+            attributes,
+            output,
+        )?;
+    } else {
+        tessellate_arc(
+            (start_angle.radians, mid_angle.radians),
+            start_vertex,
+            mid_vertex,
+            num_subdivisions,
+            vertex,
+            attributes,
+            output,
+        )?;
+    }
 
     vertex.side = first_side.opposite();
 
-    tessellate_arc(
-        (mid_angle.radians, end_angle.radians),
-        radius,
-        mid_vertex,
-        end_vertex,
-        num_subdivisions,
-        vertex,
-        attributes,
-        output,
-    )?;
+    if let Some(num_segments) = fixed_segments {
+        tessellate_arc_fan(
+            (mid_angle.radians, end_angle.radians),
+            mid_vertex,
+            end_vertex,
+            num_segments,
+            vertex,
+            attributes,
+            output,
+        )?;
+    } else {
+        tessellate_arc(
+            (mid_angle.radians, end_angle.radians),
+            mid_vertex,
+            end_vertex,
+            num_subdivisions,
+            vertex,
+            attributes,
+            output,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Adds a triangular arrowhead between the two side vertices at the end of a sub-path.
+///
+/// - `direction` points away from the sub-path.
+/// - `start_normal` is the offset of `start_vertex` from `center`.
+/// - `size` is the length and width of the arrow in multiples of the stroke width.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn tessellate_arrow_cap(
+    center: Point,
+    half_width: f32,
+    direction: Vector,
+    start_normal: Vector,
+    start_vertex: VertexId,
+    end_vertex: VertexId,
+    size: (f32, f32),
+    is_start: bool,
+    vertex: &mut StrokeVertexData,
+    attributes: &dyn AttributeStore,
+    output: &mut dyn StrokeGeometryBuilder,
+) -> Result<(), TessellationError> {
+    // Normals are expressed in multiples of the half width, so the half width of the base of
+    // the arrow is `width` and its length `2 * length`.
+    let (length, width) = size;
+    let start_normal = start_normal.normalize();
+    let width = width.max(1.0);
+
+    vertex.position_on_path = center;
+    vertex.half_width = half_width;
+
+    vertex.side = Side::Positive;
+    vertex.normal = direction.normalize() * length.max(0.0) * 2.0;
+    let tip = output.add_stroke_vertex(StrokeVertex(vertex, attributes))?;
+
+    let start_side = if is_start {
+        Side::Negative
+    } else {
+        Side::Positive
+    };
+    vertex.side = start_side;
+    vertex.normal = start_normal * width;
+    let start_base = output.add_stroke_vertex(StrokeVertex(vertex, attributes))?;
+    vertex.side = start_side.opposite();
+    vertex.normal = -start_normal * width;
+    let end_base = output.add_stroke_vertex(StrokeVertex(vertex, attributes))?;
+
+    output.set_stroke_part(if is_start {
+        StrokePart::StartCap
+    } else {
+        StrokePart::EndCap
+    });
+    // The side vertices of the stroke lie on the base of the arrow.
+    output.add_triangle(start_base, tip, start_vertex);
+    output.add_triangle(start_vertex, tip, end_vertex);
+    output.add_triangle(end_vertex, tip, end_base);
 
     Ok(())
 }
@@ -2470,6 +4634,7 @@ pub(crate) fn tessellate_empty_square_cap(
 
     let d = output.add_stroke_vertex(StrokeVertex(vertex, attributes))?;
 
+    output.set_stroke_part(StrokePart::StartCap);
     output.add_triangle(a, b, c);
     output.add_triangle(a, c, d);
 
@@ -2527,10 +4692,14 @@ pub(crate) fn tessellate_empty_round_cap(
     Ok(())
 }
 
+/// Maximum subdivision depth of `tessellate_arc` and of the circles of the fill
+/// tessellator, beyond which the number of vertices would exceed the range of vertex
+/// ids anyway.
+pub(crate) const MAX_ARC_RECURSIONS: u32 = 32;
+
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn tessellate_arc(
     angle: (f32, f32),
-    radius: f32,
     va: VertexId,
     vb: VertexId,
     num_recursions: u32,
@@ -2538,63 +4707,481 @@ pub(crate) fn tessellate_arc(
     attributes: &dyn AttributeStore,
     output: &mut dyn StrokeGeometryBuilder,
 ) -> Result<(), TessellationError> {
-    if num_recursions == 0 {
-        return Ok(());
-    }
+    // Subdivide with an explicit stack rather than recursively to keep stack usage
+    // bounded. The second half is pushed first so that vertices are produced in the
+    // same order as a depth-first recursion, which needs at most one entry per level
+    // plus one.
+    let mut stack: ArrayVec<_, { MAX_ARC_RECURSIONS as usize + 1 }> = ArrayVec::new();
+    stack.push((angle, va, vb, num_recursions.min(MAX_ARC_RECURSIONS)));
+    while let Some((angle, va, vb, num_recursions)) = stack.pop() {
+        if num_recursions == 0 {
+            continue;
+        }
 
-    let mid_angle = (angle.0 + angle.1) * 0.5;
+        let mid_angle = (angle.0 + angle.1) * 0.5;
 
-    let normal = vector(mid_angle.cos(), mid_angle.sin());
+        let normal = vector(mid_angle.cos(), mid_angle.sin());
 
-    vertex.normal = normal;
+        vertex.normal = normal;
 
-    let vertex_id = output.add_stroke_vertex(StrokeVertex(vertex, attributes))?;
+        let vertex_id = output.add_stroke_vertex(StrokeVertex(vertex, attributes))?;
 
-    output.add_triangle(va, vertex_id, vb);
+        output.add_triangle(va, vertex_id, vb);
 
-    tessellate_arc(
-        (angle.0, mid_angle),
-        radius,
-        va,
-        vertex_id,
-        num_recursions - 1,
-        vertex,
-        attributes,
-        output,
-    )?;
-    tessellate_arc(
-        (mid_angle, angle.1),
-        radius,
-        vertex_id,
-        vb,
-        num_recursions - 1,
-        vertex,
-        attributes,
-        output,
-    )
+        stack.push(((mid_angle, angle.1), vertex_id, vb, num_recursions - 1));
+        stack.push(((angle.0, mid_angle), va, vertex_id, num_recursions - 1));
+    }
+
+    Ok(())
 }
 
-/// Extra vertex information from the `StrokeTessellator`.
-pub(crate) struct StrokeVertexData<'l> {
-    pub(crate) position_on_path: Point,
-    pub(crate) half_width: f32,
-    pub(crate) normal: Vector,
-    pub(crate) advancement: f32,
-    pub(crate) side: Side,
-    pub(crate) src: VertexSource,
-    pub(crate) buffer: &'l mut [f32],
-    pub(crate) buffer_is_valid: bool,
+/// Produces the line segments of `StrokeTessellator::tessellate_hairline_with_ids`.
+struct Hairline<'a, 'l> {
+    vertex: StrokeVertexData<'a>,
+    attributes: &'a dyn AttributeStore,
+    output: &'l mut dyn LineGeometryBuilder,
+    first: VertexId,
+    previous: VertexId,
 }
 
-/// Extra vertex information from the `StrokeTessellator` accessible when building vertices.
-pub struct StrokeVertex<'a, 'b>(
-    pub(crate) &'b mut StrokeVertexData<'a>,
-    pub(crate) &'b dyn AttributeStore,
-);
+impl<'a, 'l> Hairline<'a, 'l> {
+    fn tessellate(
+        &mut self,
+        path: impl IntoIterator<Item = IdEvent>,
+        positions: &impl PositionStore,
+        tolerance: f32,
+    ) -> Result<(), GeometryBuilderError> {
+        for event in path {
+            match event {
+                IdEvent::Begin { at } => {
+                    self.previous = VertexId::INVALID;
+                    let position = positions.get_endpoint(at);
+                    self.first =
+                        self.line_to(position, VertexSource::Endpoint { id: at }, position)?;
+                }
+                IdEvent::Line { from, to } => {
+                    let from = positions.get_endpoint(from);
+                    let src = VertexSource::Endpoint { id: to };
+                    self.line_to(positions.get_endpoint(to), src, from)?;
+                }
+                IdEvent::Quadratic { from, ctrl, to } => {
+                    let curve = QuadraticBezierSegment {
+                        from: positions.get_endpoint(from),
+                        ctrl: positions.get_control_point(ctrl),
+                        to: positions.get_endpoint(to),
+                    };
+                    let mut result = Ok(VertexId::INVALID);
+                    curve.for_each_flattened_with_t(tolerance, &mut |line, t| {
+                        if result.is_ok() {
+                            let src = edge_source(from, to, t.end);
+                            result = self.line_to(line.to, src, line.from);
+                        }
+                    });
+                    result?;
+                }
+                IdEvent::Cubic {
+                    from,
+                    ctrl1,
+                    ctrl2,
+                    to,
+                } => {
+                    let curve = CubicBezierSegment {
+                        from: positions.get_endpoint(from),
+                        ctrl1: positions.get_control_point(ctrl1),
+                        ctrl2: positions.get_control_point(ctrl2),
+                        to: positions.get_endpoint(to),
+                    };
+                    let mut result = Ok(VertexId::INVALID);
+                    curve.for_each_flattened_with_t(tolerance, &mut |line, t| {
+                        if result.is_ok() {
+                            let src = edge_source(from, to, t.end);
+                            result = self.line_to(line.to, src, line.from);
+                        }
+                    });
+                    result?;
+                }
+                IdEvent::End { close, .. } => {
+                    if close && self.previous != self.first {
+                        self.output.add_line(self.previous, self.first);
+                    }
+                }
+            }
+        }
 
-impl<'a, 'b> StrokeVertex<'a, 'b> {
-    /// The vertex position.
-    #[inline]
+        Ok(())
+    }
+
+    // Adds a vertex and the line segment from the previous one, if any.
+    fn line_to(
+        &mut self,
+        position: Point,
+        src: VertexSource,
+        previous_position: Point,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        if self.previous != VertexId::INVALID && position == previous_position {
+            return Ok(self.previous);
+        }
+
+        if self.previous != VertexId::INVALID {
+            self.vertex.advancement += (position - previous_position).length();
+        }
+        self.vertex.position_on_path = position;
+        self.vertex.src = src;
+        self.vertex.buffer_is_valid = false;
+        let id = self
+            .output
+            .add_stroke_vertex(StrokeVertex(&mut self.vertex, self.attributes))?;
+        if self.previous != VertexId::INVALID {
+            self.output.add_line(self.previous, id);
+        }
+        self.previous = id;
+
+        Ok(id)
+    }
+}
+
+/// A flattened piece of a sub-path for `StrokeTessellator::tessellate_dots_with_ids`.
+struct DotPiece {
+    segment: LineSegment<f32>,
+    from: EndpointId,
+    to: EndpointId,
+    t: (f32, f32),
+}
+
+/// Produces the dots of `StrokeTessellator::tessellate_dots_with_ids`.
+struct Dots<'a, 'l> {
+    vertex: StrokeVertexData<'a>,
+    attributes: &'a dyn AttributeStore,
+    output: &'l mut dyn StrokeGeometryBuilder,
+    spacing: f32,
+    shape: DotShape,
+    circle_segments: u32,
+    pieces: Vec<DotPiece>,
+}
+
+impl<'a, 'l> Dots<'a, 'l> {
+    fn tessellate(
+        &mut self,
+        path: impl IntoIterator<Item = IdEvent>,
+        positions: &impl PositionStore,
+        tolerance: f32,
+    ) -> Result<(), GeometryBuilderError> {
+        let mut first = EndpointId::INVALID;
+        for event in path {
+            match event {
+                IdEvent::Begin { at } => {
+                    first = at;
+                    self.pieces.clear();
+                }
+                IdEvent::Line { from, to } => {
+                    let segment = LineSegment {
+                        from: positions.get_endpoint(from),
+                        to: positions.get_endpoint(to),
+                    };
+                    self.add_piece(segment, from, to, (0.0, 1.0));
+                }
+                IdEvent::Quadratic { from, ctrl, to } => {
+                    let curve = QuadraticBezierSegment {
+                        from: positions.get_endpoint(from),
+                        ctrl: positions.get_control_point(ctrl),
+                        to: positions.get_endpoint(to),
+                    };
+                    curve.for_each_flattened_with_t(tolerance, &mut |line, t| {
+                        self.add_piece(*line, from, to, (t.start, t.end));
+                    });
+                }
+                IdEvent::Cubic {
+                    from,
+                    ctrl1,
+                    ctrl2,
+                    to,
+                } => {
+                    let curve = CubicBezierSegment {
+                        from: positions.get_endpoint(from),
+                        ctrl1: positions.get_control_point(ctrl1),
+                        ctrl2: positions.get_control_point(ctrl2),
+                        to: positions.get_endpoint(to),
+                    };
+                    curve.for_each_flattened_with_t(tolerance, &mut |line, t| {
+                        self.add_piece(*line, from, to, (t.start, t.end));
+                    });
+                }
+                IdEvent::End { last, close, .. } => {
+                    if close {
+                        let segment = LineSegment {
+                            from: positions.get_endpoint(last),
+                            to: positions.get_endpoint(first),
+                        };
+                        self.add_piece(segment, last, first, (0.0, 1.0));
+                    }
+                    if self.pieces.is_empty() {
+                        self.vertex.src = VertexSource::Endpoint { id: first };
+                        self.add_dot(positions.get_endpoint(first), vector(1.0, 0.0))?;
+                    } else {
+                        self.add_sub_path_dots(close)?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn add_piece(
+        &mut self,
+        segment: LineSegment<f32>,
+        from: EndpointId,
+        to: EndpointId,
+        t: (f32, f32),
+    ) {
+        if segment.from != segment.to {
+            self.pieces.push(DotPiece {
+                segment,
+                from,
+                to,
+                t,
+            });
+        }
+    }
+
+    fn add_sub_path_dots(&mut self, close: bool) -> Result<(), GeometryBuilderError> {
+        let length: f32 = self.pieces.iter().map(|piece| piece.segment.length()).sum();
+        let (spacing, count) = if close {
+            let count = (length / self.spacing).round().max(1.0);
+            (length / count, count as u32)
+        } else {
+            // Tolerate a bit of imprecision so that a dot lands on the last point when the
+            // length is a multiple of the spacing.
+            let count = (length / self.spacing + 1e-3).floor();
+            (self.spacing, count as u32 + 1)
+        };
+
+        let start_advancement = self.vertex.advancement;
+        let mut piece_idx = 0;
+        let mut piece_start = 0.0;
+        for i in 0..count {
+            let distance = (spacing * i as f32).min(length);
+            let mut piece_length = self.pieces[piece_idx].segment.length();
+            while distance > piece_start + piece_length && piece_idx + 1 < self.pieces.len() {
+                piece_start += piece_length;
+                piece_idx += 1;
+                piece_length = self.pieces[piece_idx].segment.length();
+            }
+
+            let piece = &self.pieces[piece_idx];
+            let s = ((distance - piece_start) / piece_length).clamp(0.0, 1.0);
+            let position = piece.segment.sample(s);
+            let tangent = piece.segment.to_vector() / piece_length;
+            let t = piece.t.0 + (piece.t.1 - piece.t.0) * s;
+            self.vertex.src = edge_source(piece.from, piece.to, t);
+            self.vertex.advancement = start_advancement + distance;
+            self.add_dot(position, tangent)?;
+        }
+        self.vertex.advancement = start_advancement + length;
+
+        Ok(())
+    }
+
+    // Adds the triangles of a dot, with a negative signed area like the rest of the stroke.
+    fn add_dot(&mut self, center: Point, tangent: Vector) -> Result<(), GeometryBuilderError> {
+        self.vertex.position_on_path = center;
+        self.vertex.buffer_is_valid = false;
+
+        match self.shape {
+            DotShape::Circle => {
+                let center = self.add_vertex(vector(0.0, 0.0), tangent)?;
+                let step = 2.0 * PI / self.circle_segments as f32;
+                let first = self.add_vertex(tangent, tangent)?;
+                let mut previous = first;
+                for i in 1..self.circle_segments {
+                    let normal = Rotation::radians(step * i as f32).transform_vector(tangent);
+                    let vertex = self.add_vertex(normal, tangent)?;
+                    self.output.add_triangle(center, vertex, previous);
+                    previous = vertex;
+                }
+                self.output.add_triangle(center, first, previous);
+            }
+            DotShape::Square => {
+                let normal = vector(-tangent.y, tangent.x);
+                let a = self.add_vertex(-tangent - normal, tangent)?;
+                let b = self.add_vertex(tangent - normal, tangent)?;
+                let c = self.add_vertex(tangent + normal, tangent)?;
+                let d = self.add_vertex(normal - tangent, tangent)?;
+                self.output.add_triangle(a, c, b);
+                self.output.add_triangle(a, d, c);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn add_vertex(
+        &mut self,
+        normal: Vector,
+        tangent: Vector,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        self.vertex.normal = normal;
+        self.vertex.side = if tangent.cross(normal) >= 0.0 {
+            Side::Positive
+        } else {
+            Side::Negative
+        };
+
+        self.output
+            .add_stroke_vertex(StrokeVertex(&mut self.vertex, self.attributes))
+    }
+}
+
+fn edge_source(from: EndpointId, to: EndpointId, t: f32) -> VertexSource {
+    if t == 1.0 {
+        VertexSource::Endpoint { id: to }
+    } else {
+        VertexSource::Edge { from, to, t }
+    }
+}
+
+/// Custom geometry for the joins of a stroke, see
+/// [`StrokeTessellator::tessellate_with_custom_shapes`](struct.StrokeTessellator.html#method.tessellate_with_custom_shapes).
+pub trait JoinBuilder {
+    /// Adds the geometry of the outer side of a join.
+    ///
+    /// The join is tessellated as a bevel join and this is called for its outer `side`, with
+    /// the position of the join on the path and the normalized tangents of the edges before
+    /// and after it. The start and end vertices of the output are the corners of the bevel
+    /// on that side and the added geometry should cover the area beyond the bevel.
+    fn add_join(
+        &mut self,
+        position: Point,
+        prev_tangent: Vector,
+        next_tangent: Vector,
+        side: Side,
+        output: &mut CustomShapeOutput,
+    ) -> Result<(), GeometryBuilderError>;
+}
+
+/// Custom geometry for the caps of a stroke, see
+/// [`StrokeTessellator::tessellate_with_custom_shapes`](struct.StrokeTessellator.html#method.tessellate_with_custom_shapes).
+pub trait CapBuilder {
+    /// Adds the geometry of a cap.
+    ///
+    /// The cap is tessellated as a butt cap and this is called with the position of the
+    /// endpoint and the normalized tangent pointing away from the stroke. The start and end
+    /// vertices of the output are the corners of the butt cap, respectively on the left and
+    /// on the right of the tangent in a y-up coordinate system, and the added geometry should
+    /// cover the area beyond them.
+    fn add_cap(
+        &mut self,
+        position: Point,
+        tangent: Vector,
+        output: &mut CustomShapeOutput,
+    ) -> Result<(), GeometryBuilderError>;
+}
+
+/// Adds the vertices and triangles of a custom join or cap, see
+/// [`JoinBuilder`](trait.JoinBuilder.html) and [`CapBuilder`](trait.CapBuilder.html).
+///
+/// The vertices have the attributes of the join or cap's position on the path and a normal
+/// pointing from there to their position.
+pub struct CustomShapeOutput<'a, 'l> {
+    vertex: &'a mut StrokeVertexData<'l>,
+    attributes: &'a dyn AttributeStore,
+    output: &'a mut dyn StrokeGeometryBuilder,
+    vertices: &'a mut Vec<(VertexId, Point)>,
+    side: Option<Side>,
+    positive_normal: Vector,
+}
+
+impl<'a, 'l> CustomShapeOutput<'a, 'l> {
+    /// Half of the line width at the join or cap.
+    pub fn half_width(&self) -> f32 {
+        self.vertex.half_width
+    }
+
+    /// The first existing vertex the shape is attached to.
+    pub fn start_vertex(&self) -> VertexId {
+        self.vertices[0].0
+    }
+
+    /// The last existing vertex the shape is attached to.
+    pub fn end_vertex(&self) -> VertexId {
+        self.vertices[1].0
+    }
+
+    /// The position of a vertex of this shape, including the start and end vertices.
+    pub fn vertex_position(&self, id: VertexId) -> Option<Point> {
+        self.vertices
+            .iter()
+            .find(|(vertex, _)| *vertex == id)
+            .map(|(_, position)| *position)
+    }
+
+    /// Adds a vertex at the given position.
+    pub fn add_vertex(&mut self, position: Point) -> Result<VertexId, GeometryBuilderError> {
+        let normal = position - self.vertex.position_on_path;
+        self.vertex.side = self
+            .side
+            .unwrap_or(if normal.dot(self.positive_normal) >= 0.0 {
+                Side::Positive
+            } else {
+                Side::Negative
+            });
+        self.vertex.normal = normal / self.vertex.half_width;
+        let id = self
+            .output
+            .add_stroke_vertex(StrokeVertex(self.vertex, self.attributes))?;
+        self.vertices.push((id, position));
+
+        Ok(id)
+    }
+
+    /// Adds a triangle.
+    ///
+    /// The vertices can be in any order, the triangle is flipped if needed to get the winding
+    /// of the rest of the stroke.
+    pub fn add_triangle(&mut self, a: VertexId, mut b: VertexId, mut c: VertexId) {
+        if let (Some(pa), Some(pb), Some(pc)) = (
+            self.vertex_position(a),
+            self.vertex_position(b),
+            self.vertex_position(c),
+        ) {
+            if (pb - pa).cross(pc - pa) > 0.0 {
+                std::mem::swap(&mut b, &mut c);
+            }
+        }
+        self.output.add_triangle(a, b, c);
+    }
+}
+
+/// Extra vertex information from the `StrokeTessellator`.
+pub(crate) struct StrokeVertexData<'l> {
+    pub(crate) position_on_path: Point,
+    pub(crate) half_width: f32,
+    pub(crate) normal: Vector,
+    pub(crate) advancement: f32,
+    pub(crate) side: Side,
+    pub(crate) convex_side: Option<Side>,
+    pub(crate) order: Option<Order>,
+    pub(crate) src: VertexSource,
+    pub(crate) coverage: f32,
+    pub(crate) buffer: &'l mut [f32],
+    pub(crate) buffer_is_valid: bool,
+    pub(crate) interpolation: Option<AttributeInterpolation>,
+    /// The double precision position that the other positions are relative to, see
+    /// `StrokeTessellatorF64`.
+    pub(crate) origin: Point2D<f64>,
+    /// The double precision position on the path, only set by `StrokeTessellatorF64`.
+    pub(crate) position_on_path_f64: Option<Point2D<f64>>,
+}
+
+/// Extra vertex information from the `StrokeTessellator` accessible when building vertices.
+pub struct StrokeVertex<'a, 'b>(
+    pub(crate) &'b mut StrokeVertexData<'a>,
+    pub(crate) &'b dyn AttributeStore,
+);
+
+impl<'a, 'b> StrokeVertex<'a, 'b> {
+    /// The vertex position.
+    #[inline]
     pub fn position(&self) -> Point {
         self.0.position_on_path + self.0.normal * self.0.half_width
     }
@@ -2614,6 +5201,30 @@ impl<'a, 'b> StrokeVertex<'a, 'b> {
         self.0.position_on_path
     }
 
+    /// The origin of the positions, in double precision.
+    ///
+    /// This is the first point of the sub-path with `StrokeTessellatorF64` and zero with
+    /// the other tessellators.
+    #[inline]
+    pub fn origin(&self) -> Point2D<f64> {
+        self.0.origin
+    }
+
+    /// The vertex position, in double precision.
+    #[inline]
+    pub fn position_f64(&self) -> Point2D<f64> {
+        self.position_on_path_f64() + (self.0.normal * self.0.half_width).to_f64()
+    }
+
+    /// Position of this vertex on the path, in double precision.
+    #[inline]
+    pub fn position_on_path_f64(&self) -> Point2D<f64> {
+        match self.0.position_on_path_f64 {
+            Some(position) => position,
+            None => self.0.origin + self.0.position_on_path.to_f64().to_vector(),
+        }
+    }
+
     /// The line width at this vertex.
     ///
     /// If a line width modifier is set via `StrokeOptions::variable_line_width`, the
@@ -2630,18 +5241,78 @@ impl<'a, 'b> StrokeVertex<'a, 'b> {
         self.0.advancement
     }
 
+    /// Texture coordinates of this vertex along and across the stroke.
+    ///
+    /// `u` is the advancement and `v` is the distance from the path divided by the half
+    /// line width, signed by the side of the vertex: `-1.0` on the negative edge, `1.0` on
+    /// the positive edge. Vertices displaced further than the half line width (for example
+    /// the tip of a miter join) are clamped to `[-1, 1]`.
+    #[inline]
+    pub fn uv(&self) -> Point {
+        let v = self.0.side.to_f32() * self.0.normal.length().min(1.0);
+
+        point(self.0.advancement, v)
+    }
+
+    /// Fraction of the vertex covered by the stroke.
+    ///
+    /// `1.0` for all vertices, except the outer vertices of the anti-aliasing fringe (see
+    /// `StrokeOptions::anti_aliasing_fringe`) which have a coverage of `0.0`. Interpolating
+    /// it over the triangles gives an alpha value that fades out the edges of the stroke.
+    #[inline]
+    pub fn coverage(&self) -> f32 {
+        self.0.coverage
+    }
+
     /// Whether the vertex is on the positive or negative side of the path.
     #[inline]
     pub fn side(&self) -> Side {
         self.0.side
     }
 
+    /// Whether the vertex is on the convex (outer) side of a join.
+    ///
+    /// Returns `Some(false)` on the concave (inner) side, and `None` for vertices that
+    /// are not part of a join, for example on caps.
+    #[inline]
+    pub fn is_on_convex_side(&self) -> Option<bool> {
+        self.0.convex_side.map(|side| side == self.0.side)
+    }
+
+    /// Whether a join vertex is attached to the edge before or after the join.
+    ///
+    /// Returns `None` if the vertex is shared by both edges or is not part of a join.
+    #[inline]
+    pub fn join_order(&self) -> Option<Order> {
+        self.0.order
+    }
+
     /// Returns the source of this vertex.
     #[inline]
     pub fn source(&self) -> VertexSource {
         self.0.src
     }
 
+    /// Returns the order of the sub-path this vertex belongs to.
+    ///
+    /// `attribute` is the index of the custom attribute containing the order (see
+    /// `lyon_path::builder::WithSubPathOrder`).
+    ///
+    /// Returns `0.0` if the path does not have custom attributes.
+    #[inline]
+    pub fn sub_path_order(&self, attribute: AttributeIndex) -> f32 {
+        if self.1.num_attributes() == 0 {
+            return 0.0;
+        }
+
+        let id = match self.0.src {
+            VertexSource::Endpoint { id } => id,
+            VertexSource::Edge { from, .. } => from,
+        };
+
+        self.1.get(id)[attribute]
+    }
+
     /// Computes and returns the custom attributes for this vertex.
     ///
     /// The attributes are interpolated along the edges on which this vertex is.
@@ -2657,9 +5328,7 @@ impl<'a, 'b> StrokeVertex<'a, 'b> {
             VertexSource::Edge { from, to, t } => {
                 let a = self.1.get(from);
                 let b = self.1.get(to);
-                for i in 0..self.0.buffer.len() {
-                    self.0.buffer[i] = a[i] * (1.0 - t) + b[i] * t;
-                }
+                interpolate_attributes(self.0.interpolation, a, b, t, self.0.buffer);
                 self.0.buffer_is_valid = true;
 
                 &self.0.buffer[..]
@@ -2668,10 +5337,33 @@ impl<'a, 'b> StrokeVertex<'a, 'b> {
     }
 }
 
-pub(crate) fn circle_flattening_step(radius: f32, mut tolerance: f32) -> f32 {
+fn quadratic_curvature(curve: &QuadraticBezierSegment<f32>, t: f32) -> f32 {
+    let second_derivative =
+        (curve.to.to_vector() - curve.ctrl.to_vector() * 2.0 + curve.from.to_vector()) * 2.0;
+    curvature(curve.derivative(t), second_derivative)
+}
+
+fn cubic_curvature(curve: &CubicBezierSegment<f32>, t: f32) -> f32 {
+    let a = curve.ctrl2.to_vector() - curve.ctrl1.to_vector() * 2.0 + curve.from.to_vector();
+    let b = curve.to.to_vector() - curve.ctrl2.to_vector() * 2.0 + curve.ctrl1.to_vector();
+    curvature(curve.derivative(t), (a * (1.0 - t) + b * t) * 6.0)
+}
+
+// Signed curvature from the first and second derivatives of a curve, positive when the
+// curve turns towards the positive side.
+fn curvature(first_derivative: Vector, second_derivative: Vector) -> f32 {
+    let length = first_derivative.length();
+    if length < 1e-6 {
+        return 0.0;
+    }
+
+    first_derivative.cross(second_derivative) / (length * length * length)
+}
+
+pub(crate) fn circle_flattening_step<S: Scalar>(radius: S, mut tolerance: S) -> S {
     // Don't allow high tolerance values (compared to the radius) to avoid edge cases.
-    tolerance = f32::min(tolerance, radius);
-    2.0 * ((radius - tolerance) / radius).acos()
+    tolerance = S::min(tolerance, radius);
+    S::TWO * ((radius - tolerance) / radius).acos()
 }
 
 fn flatten_quad<F>(curve: &QuadraticBezierSegment<f32>, tolerance: f32, cb: &mut F)
@@ -2704,6 +5396,8 @@ where
 use crate::geometry_builder::*;
 #[cfg(test)]
 use crate::path::Path;
+#[cfg(test)]
+use crate::DashArrayError;
 
 #[cfg(test)]
 fn test_path(path: PathSlice, options: &StrokeOptions, expected_triangle_count: Option<u32>) {
@@ -2745,6 +5439,7 @@ fn test_path(path: PathSlice, options: &StrokeOptions, expected_triangle_count:
             assert!(!attributes.normal().y.is_nan());
             assert!(attributes.normal().square_length() != 0.0);
             assert!(!attributes.advancement().is_nan());
+            assert!(attributes.uv().y.abs() <= 1.0);
             self.builder.add_stroke_vertex(attributes)
         }
     }
@@ -2770,6 +5465,133 @@ fn test_path(path: PathSlice, options: &StrokeOptions, expected_triangle_count:
     }
 }
 
+#[test]
+fn variable_width_along_curves() {
+    let mut builder = Path::builder_with_attributes(1);
+    builder.begin(point(0.0, 0.0), &[1.0]);
+    builder.quadratic_bezier_to(point(50.0, 50.0), point(100.0, 0.0), &[5.0]);
+    builder.cubic_bezier_to(
+        point(120.0, -20.0),
+        point(140.0, 20.0),
+        point(160.0, 0.0),
+        &[2.0],
+    );
+    builder.end(false);
+    let path = builder.build();
+
+    let options = StrokeOptions::default()
+        .with_line_width(2.0)
+        .with_variable_line_width(0)
+        .with_line_cap(LineCap::Butt)
+        .with_tolerance(0.05);
+
+    let mut buffers: VertexBuffers<(f32, f32), u16> = VertexBuffers::new();
+    StrokeTessellator::new()
+        .tessellate_path(
+            &path,
+            &options,
+            &mut BuffersBuilder::new(&mut buffers, |v: StrokeVertex| {
+                (v.advancement(), v.line_width())
+            }),
+        )
+        .unwrap();
+
+    let quad_length = QuadraticBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl: point(50.0, 50.0),
+        to: point(100.0, 0.0),
+    }
+    .length();
+
+    let mut vertices = buffers.vertices.clone();
+    vertices.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+    // The width is interpolated along the flattened curves instead of jumping at the
+    // endpoints: it grows from 2 to 10 along the quadratic and shrinks back to 4 along
+    // the cubic.
+    let epsilon = 0.01;
+    let mut intermediate = 0;
+    for pair in vertices.windows(2) {
+        let ((adv_a, w_a), (adv_b, w_b)) = (pair[0], pair[1]);
+        if adv_b <= quad_length - epsilon {
+            assert!(w_b >= w_a - epsilon, "{:?}", pair);
+        } else if adv_a >= quad_length + epsilon {
+            assert!(w_b <= w_a + epsilon, "{:?}", pair);
+        }
+    }
+    for &(_, w) in &vertices {
+        assert!(w >= 2.0 - epsilon && w <= 10.0 + epsilon, "{}", w);
+        if (w - 2.0).abs() > epsilon && (w - 10.0).abs() > epsilon && (w - 4.0).abs() > epsilon {
+            intermediate += 1;
+        }
+    }
+    assert!(intermediate > 4);
+
+    let first = vertices.first().unwrap();
+    let last = vertices.last().unwrap();
+    assert!((first.1 - 2.0).abs() < epsilon);
+    assert!((last.1 - 4.0).abs() < epsilon);
+
+    // The widest point is the endpoint shared by the two curves. The flattened length
+    // of the quadratic is slightly shorter than its exact length.
+    let widest = vertices
+        .iter()
+        .cloned()
+        .fold((0.0, 0.0), |a: (f32, f32), b| if b.1 > a.1 { b } else { a });
+    assert!((widest.1 - 10.0).abs() < epsilon, "{:?}", widest);
+    assert!((widest.0 - quad_length).abs() < 0.5, "{:?}", widest);
+}
+
+#[test]
+fn variable_width_square_caps() {
+    let mut builder = Path::builder_with_attributes(1);
+    builder.begin(point(0.0, 0.0), &[1.0]);
+    builder.line_to(point(10.0, 0.0), &[1.0]);
+    builder.end(false);
+    builder.begin(point(0.0, 10.0), &[1.0]);
+    builder.line_to(point(5.0, 10.0), &[2.0]);
+    builder.end(false);
+    let path = builder.build();
+
+    // Square caps extend the stroke by half of its width at both ends, with and without
+    // the fixed width fast path.
+    let fixed = StrokeOptions::default()
+        .with_line_width(2.0)
+        .with_line_cap(LineCap::Square);
+    let variable = fixed.with_variable_line_width(0);
+    for options in [fixed, variable] {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate_path(&path, &options, &mut simple_builder(&mut buffers))
+            .unwrap();
+        let xs: Vec<f32> = buffers
+            .vertices
+            .iter()
+            .filter(|v| v.y.abs() < 2.0)
+            .map(|v| v.x)
+            .collect();
+        assert!(xs.iter().all(|x| *x == -1.0 || *x == 11.0), "{:?}", xs);
+    }
+
+    // The end cap follows the width at the end of the sub-path.
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    StrokeTessellator::new()
+        .tessellate_path(&path, &variable, &mut simple_builder(&mut buffers))
+        .unwrap();
+    let xs: Vec<f32> = buffers
+        .vertices
+        .iter()
+        .filter(|v| v.y > 5.0)
+        .map(|v| v.x)
+        .collect();
+    assert!(
+        xs.iter()
+            .all(|x| (*x + 1.0).abs() < 1e-4 || (*x - 7.0).abs() < 1e-4),
+        "{:?}",
+        xs
+    );
+}
+
 #[test]
 fn test_square() {
     let mut builder = Path::builder_with_attributes(1);
@@ -3044,6 +5866,81 @@ fn stroke_vertex_source_01() {
     }
 }
 
+#[test]
+fn stroke_vertex_source_joins() {
+    // Attributes are interpolated at the joins and along flattened curves, with fixed
+    // or variable line widths.
+    let mut path = crate::path::Path::builder_with_attributes(1);
+    let endpoints = vec![
+        (path.begin(point(0.0, 0.0), &[1.0]), 1.0),
+        (path.line_to(point(10.0, 10.0), &[2.0]), 2.0),
+        (
+            path.quadratic_bezier_to(point(10.0, 20.0), point(0.0, 20.0), &[3.0]),
+            3.0,
+        ),
+        (path.line_to(point(-5.0, 5.0), &[4.0]), 4.0),
+    ];
+    path.end(false);
+    let path = path.build();
+
+    struct CheckAttributes {
+        endpoints: Vec<(EndpointId, f32)>,
+        num_vertices: u32,
+    }
+
+    impl GeometryBuilder for CheckAttributes {
+        fn abort_geometry(&mut self) {}
+        fn add_triangle(&mut self, _: VertexId, _: VertexId, _: VertexId) {}
+    }
+
+    impl StrokeGeometryBuilder for CheckAttributes {
+        fn add_stroke_vertex(
+            &mut self,
+            mut vertex: StrokeVertex,
+        ) -> Result<VertexId, GeometryBuilderError> {
+            let expected = match vertex.source() {
+                VertexSource::Endpoint { id } => self
+                    .endpoints
+                    .iter()
+                    .find(|(endpoint, _)| *endpoint == id)
+                    .map(|(_, value)| *value),
+                VertexSource::Edge { .. } => None,
+            };
+            let attributes = vertex.interpolated_attributes();
+            assert_eq!(attributes.len(), 1);
+            match expected {
+                Some(value) => assert_eq!(attributes[0], value),
+                None => assert!(attributes[0] > 1.0 && attributes[0] < 4.0),
+            }
+
+            self.num_vertices += 1;
+            Ok(VertexId(self.num_vertices - 1))
+        }
+    }
+
+    let mut tess = StrokeTessellator::new();
+    for join in [
+        LineJoin::Miter,
+        LineJoin::MiterClip,
+        LineJoin::Round,
+        LineJoin::Bevel,
+    ] {
+        for variable_line_width in [None, Some(0)] {
+            let mut options = StrokeOptions::default()
+                .with_line_width(2.0)
+                .with_line_join(join);
+            options.variable_line_width = variable_line_width;
+            let mut output = CheckAttributes {
+                endpoints: endpoints.clone(),
+                num_vertices: 0,
+            };
+            tess.tessellate_with_ids(path.id_iter(), &path, Some(&path), &options, &mut output)
+                .unwrap();
+            assert!(output.num_vertices > 0);
+        }
+    }
+}
+
 #[test]
 fn test_line_width() {
     use crate::geom::euclid::approxeq::ApproxEq;
@@ -3206,3 +6103,1659 @@ fn single_segment_closed() {
 
     assert!(output.indices.len() > 0);
 }
+
+#[test]
+fn sub_path_order() {
+    let mut builder = Path::builder_with_attributes(1).with_sub_path_order();
+    let order_attribute = builder.order_attribute();
+    builder.set_order(1.0);
+    builder.begin(point(0.0, 0.0), &[]);
+    builder.quadratic_bezier_to(point(5.0, 5.0), point(10.0, 0.0), &[]);
+    builder.end(false);
+    builder.set_order(2.0);
+    builder.begin(point(0.0, 20.0), &[]);
+    builder.line_to(point(10.0, 20.0), &[]);
+    builder.end(true);
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<(Point, f32), u16> = VertexBuffers::new();
+    StrokeTessellator::new()
+        .tessellate_path(
+            &path,
+            &StrokeOptions::default(),
+            &mut BuffersBuilder::new(&mut buffers, |v: StrokeVertex| {
+                (v.position_on_path(), v.sub_path_order(order_attribute))
+            }),
+        )
+        .unwrap();
+
+    for (position, order) in &buffers.vertices {
+        let expected = if position.y < 10.0 { 1.0 } else { 2.0 };
+        assert_eq!(*order, expected);
+    }
+}
+
+#[test]
+fn stroke_part_ranges() {
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(20.0, 10.0));
+    builder.end(false);
+    let path = builder.build();
+
+    for cap in [LineCap::Round, LineCap::Square] {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        let mut recorder = StrokePartRanges::new(simple_builder(&mut buffers));
+        StrokeTessellator::new()
+            .tessellate_path(
+                &path,
+                &StrokeOptions::default()
+                    .with_line_cap(cap)
+                    .with_line_join(LineJoin::Round),
+                &mut recorder,
+            )
+            .unwrap();
+        let ranges = recorder.into_ranges();
+
+        let count = |part| ranges.iter().filter(|r| r.part == part).count();
+        assert_eq!(count(StrokePart::StartCap), 1);
+        assert_eq!(count(StrokePart::EndCap), 1);
+        assert_eq!(count(StrokePart::Join), 2);
+
+        // The ranges are contiguous and cover the whole index buffer.
+        let mut end = 0;
+        for range in &ranges {
+            assert_eq!(range.indices.start, end);
+            assert!(range.indices.end > range.indices.start);
+            end = range.indices.end;
+        }
+        assert_eq!(end as usize, buffers.indices.len());
+
+        // Only the caps go beyond the endpoints.
+        for range in &ranges {
+            let indices =
+                &buffers.indices[range.indices.start as usize..range.indices.end as usize];
+            let xs = indices.iter().map(|idx| buffers.vertices[*idx as usize].x);
+            let (min, max) = xs.fold((f32::MAX, f32::MIN), |(min, max), x| {
+                (min.min(x), max.max(x))
+            });
+            match range.part {
+                StrokePart::StartCap => assert_eq!(min, -0.5),
+                StrokePart::EndCap => assert_eq!(max, 20.5),
+                _ => assert!(min >= 0.0 && max <= 20.0),
+            }
+        }
+    }
+
+    // With a single edge, the square caps are reported as the start cap.
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.end(false);
+    let path = builder.build();
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    let mut recorder = StrokePartRanges::new(simple_builder(&mut buffers));
+    StrokeTessellator::new()
+        .tessellate_path(
+            &path,
+            &StrokeOptions::default().with_line_cap(LineCap::Square),
+            &mut recorder,
+        )
+        .unwrap();
+    let ranges = recorder.into_ranges();
+    assert_eq!(ranges.len(), 1);
+    assert_eq!(ranges[0].part, StrokePart::StartCap);
+    assert_eq!(ranges[0].indices, 0..6);
+}
+
+#[test]
+fn round_join_segment_count() {
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(1000.0, 0.0));
+    builder.line_to(point(1000.0, 1000.0));
+    builder.end(false);
+    let path = builder.build();
+
+    let vertex_count = |join| {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate_path(
+                &path,
+                &StrokeOptions::default()
+                    .with_line_width(100.0)
+                    .with_tolerance(0.1)
+                    .with_line_join(join),
+                &mut simple_builder(&mut buffers),
+            )
+            .unwrap();
+        buffers.vertices.len()
+    };
+
+    // A 90° join with a radius of 50 needs 13 segments to stay within the tolerance,
+    // so 12 vertices are added along the arc (rather than 15 with a power of two).
+    let step = circle_flattening_step(50.0, 0.1);
+    assert_eq!((PI * 0.5 / step).ceil(), 13.0);
+    assert_eq!(
+        vertex_count(LineJoin::Round) - vertex_count(LineJoin::Bevel),
+        12
+    );
+}
+
+#[test]
+fn fixed_round_segments() {
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.end(false);
+    let path = builder.build();
+
+    let tessellate = |options: &StrokeOptions| {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate_path(&path, options, &mut simple_builder(&mut buffers))
+            .unwrap();
+        buffers
+    };
+
+    let butt = StrokeOptions::default().with_round_segments(8);
+    let round_join = butt.with_line_join(LineJoin::Round);
+    let round_caps = butt.with_line_cap(LineCap::Round);
+
+    // The 90° join gets a quarter of the 8 segments, so 3 vertices along the arc.
+    // Each cap gets 8 segments: a vertex in the middle and 3 more on each side.
+    let bevel = tessellate(&butt.with_line_join(LineJoin::Bevel))
+        .vertices
+        .len();
+    assert_eq!(tessellate(&round_join).vertices.len() - bevel, 3);
+    assert_eq!(
+        tessellate(&round_caps).vertices.len() - tessellate(&butt).vertices.len(),
+        14
+    );
+
+    // The geometry doesn't depend on the tolerance or the line width.
+    for options in &[round_join, round_caps] {
+        let count = tessellate(options).vertices.len();
+        let options = options.with_tolerance(0.001).with_line_width(50.0);
+        assert_eq!(tessellate(&options).vertices.len(), count);
+    }
+
+    // The vertices of the caps are on the circle.
+    let buffers = tessellate(&round_caps.with_line_width(2.0));
+    for v in &buffers.vertices {
+        let d = (*v - point(0.0, 0.0))
+            .length()
+            .min((*v - point(10.0, 10.0)).length());
+        assert!(
+            v.x > 0.0 && v.y < 10.0 || (d - 1.0).abs() < 0.001,
+            "{:?}",
+            v
+        );
+    }
+}
+
+#[test]
+fn arcs_join() {
+    let tessellate = |path: &Path, options: &StrokeOptions| {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        let mut output = StrokePartRanges::new(simple_builder(&mut buffers));
+        StrokeTessellator::new()
+            .tessellate_path(path, options, &mut output)
+            .unwrap();
+        let ranges = output.into_ranges();
+        (buffers.vertices, buffers.indices, ranges)
+    };
+    let miter = StrokeOptions::default()
+        .with_line_width(4.0)
+        .with_tolerance(0.01)
+        .with_miter_limit(10.0);
+    let arcs = miter.with_line_join(LineJoin::Arcs);
+
+    // Between straight segments, the arcs are lines.
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(5.0, 8.0));
+    builder.end(true);
+    let polygon = builder.build();
+    assert_eq!(
+        tessellate(&polygon, &arcs).0[..],
+        tessellate(&polygon, &miter).0[..]
+    );
+
+    // A sharp corner between two curves.
+    let join = point(10.0, 0.0);
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.quadratic_bezier_to(point(6.0, -6.0), join);
+    builder.quadratic_bezier_to(point(4.0, 6.0), point(0.0, 8.0));
+    builder.end(false);
+    let path = builder.build();
+
+    for options in &[arcs, arcs.with_start_width(4.0).with_end_width(4.0)] {
+        let (vertices, indices, ranges) = tessellate(&path, options);
+        assert!(vertices.len() > tessellate(&path, &miter).0.len());
+
+        let triangles: Vec<[Point; 3]> = ranges
+            .iter()
+            .filter(|range| range.part == StrokePart::Join)
+            .flat_map(|range| {
+                indices[range.indices.start as usize..range.indices.end as usize].chunks(3)
+            })
+            .map(|t| [0, 1, 2].map(|i| vertices[t[i] as usize]))
+            .collect();
+        assert!(triangles.len() > 2);
+        let bisector = (vector(4.0, 6.0).normalize() - vector(-6.0, 6.0).normalize()).normalize();
+        let mut extent: f32 = 0.0;
+        for [a, b, c] in &triangles {
+            // Same orientation as the rest of the stroke.
+            assert!((*b - *a).cross(*c - *a) <= 0.0);
+            for p in &[a, b, c] {
+                extent = extent.max((**p - join).dot(bisector));
+            }
+        }
+
+        // The offset curves bend inwards, so the join is a bit shorter than the miter,
+        // which is 2.586 away from the join along the bisector.
+        assert!(extent > 2.5 && extent < 2.58, "{}", extent);
+    }
+
+    // Past the miter limit, fall back to a miter join which itself falls back to a bevel.
+    assert_eq!(
+        tessellate(&path, &arcs.with_miter_limit(1.0)).0,
+        tessellate(&path, &miter.with_miter_limit(1.0)).0
+    );
+}
+
+#[test]
+fn miter_clip_hairpin() {
+    // Returns the maximum distance of the stroke's vertices to the join, measured
+    // along the outward bisector of the join.
+    let max_extent = |end_y: f32, options: &StrokeOptions| {
+        let join = point(100.0, 0.0);
+        let end = point(0.0, end_y);
+        let mut builder = Path::builder();
+        builder.begin(point(0.0, 0.0));
+        builder.line_to(join);
+        builder.line_to(end);
+        builder.end(false);
+        let path = builder.build();
+
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate_path(&path, options, &mut simple_builder(&mut buffers))
+            .unwrap();
+
+        let bisector = (vector(1.0, 0.0) - (end - join).normalize()).normalize();
+        let mut max = f32::MIN;
+        for v in &buffers.vertices {
+            assert!(v.x.is_finite() && v.y.is_finite());
+            max = max.max((*v - join).dot(bisector));
+        }
+        max
+    };
+
+    let options = StrokeOptions::default()
+        .with_line_width(10.0)
+        .with_line_join(LineJoin::MiterClip);
+
+    // Exact and almost exact hairpins fold and produce a flat end at the join.
+    for end_y in [0.0, 0.01] {
+        let d = max_extent(end_y, &options);
+        assert!(d.abs() < 0.01);
+    }
+
+    // Sharp joins exceeding the miter limit are clipped at the clip distance, which
+    // defaults to miter_limit / 2 half line widths.
+    for end_y in [10.0, 20.0, 40.0] {
+        let d = max_extent(end_y, &options);
+        assert!((d - 2.0 * 5.0).abs() < 0.01);
+    }
+
+    let options = options.with_miter_clip_distance(1.5);
+    for end_y in [10.0, 20.0, 40.0] {
+        let d = max_extent(end_y, &options);
+        assert!((d - 1.5 * 5.0).abs() < 0.01);
+    }
+}
+
+#[test]
+fn bevel_join_normals_match_cpu_extrusion() {
+    // Extruding the vertices of a stroke along their normals on the GPU must produce
+    // the same geometry as tessellating with the corresponding line width on the CPU.
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(100.0, 0.0));
+    builder.line_to(point(150.0, 80.0));
+    builder.line_to(point(20.0, 100.0));
+    builder.line_to(point(60.0, 40.0));
+    builder.close();
+    let path = builder.build();
+
+    let tessellate = |line_width: f32| {
+        let mut buffers: VertexBuffers<(Point, Vector, Point), u16> = VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate_path(
+                &path,
+                &StrokeOptions::default()
+                    .with_line_width(line_width)
+                    .with_line_join(LineJoin::Bevel),
+                &mut BuffersBuilder::new(&mut buffers, |v: StrokeVertex| {
+                    (v.position_on_path(), v.normal(), v.position())
+                }),
+            )
+            .unwrap();
+        buffers
+    };
+
+    let thin = tessellate(1.0);
+    let thick = tessellate(7.0);
+    assert_eq!(thin.vertices.len(), thick.vertices.len());
+    assert_eq!(thin.indices, thick.indices);
+
+    for (&(on_path, normal, _), &(_, _, position)) in
+        thin.vertices.iter().zip(thick.vertices.iter())
+    {
+        let extruded = on_path + normal * 3.5;
+        assert!((extruded - position).length() < 0.001);
+    }
+}
+
+#[test]
+fn polygon_matches_path_events() {
+    let points = [
+        point(0.0, 0.0),
+        point(10.0, 0.0),
+        point(12.0, 8.0),
+        point(3.0, 10.0),
+        point(-2.0, 4.0),
+    ];
+
+    for closed in [false, true] {
+        for join in [LineJoin::Miter, LineJoin::Round] {
+            let polygon = Polygon {
+                points: &points,
+                closed,
+            };
+            let options = StrokeOptions::default()
+                .with_line_width(2.0)
+                .with_line_join(join)
+                .with_line_cap(LineCap::Round);
+
+            let mut expected: VertexBuffers<Point, u16> = VertexBuffers::new();
+            StrokeTessellator::new()
+                .tessellate(
+                    polygon.path_events(),
+                    &options,
+                    &mut simple_builder(&mut expected),
+                )
+                .unwrap();
+
+            let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+            StrokeTessellator::new()
+                .tessellate_polygon(polygon, &options, &mut simple_builder(&mut buffers))
+                .unwrap();
+
+            assert_eq!(buffers.vertices, expected.vertices);
+            assert_eq!(buffers.indices, expected.indices);
+        }
+    }
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    StrokeTessellator::new()
+        .tessellate_polygon(
+            Polygon {
+                points: &[],
+                closed: true,
+            },
+            &StrokeOptions::default(),
+            &mut simple_builder(&mut buffers),
+        )
+        .unwrap();
+    assert!(buffers.vertices.is_empty());
+}
+
+#[test]
+fn polyline_with_attributes_matches_builder() {
+    let mut points = Vec::new();
+    for i in 0..40 {
+        let x = i as f32 * 3.0;
+        points.push((point(x, (x * 0.3).sin() * 10.0), [1.0 + i as f32 * 0.1, x]));
+        if i == 20 {
+            // A duplicate point that the stroker discards.
+            points.push((point(x, (x * 0.3).sin() * 10.0), [100.0, 100.0]));
+        }
+    }
+
+    for variable_line_width in [None, Some(0)] {
+        let mut options = StrokeOptions::default()
+            .with_line_width(2.0)
+            .with_line_join(LineJoin::Round)
+            .with_line_cap(LineCap::Round);
+        options.variable_line_width = variable_line_width;
+
+        let mut expected: VertexBuffers<(Point, [f32; 2]), u16> = VertexBuffers::new();
+        let mut tessellator = StrokeTessellator::new();
+        let mut output = BuffersBuilder::new(&mut expected, |mut v: StrokeVertex| {
+            let position = v.position();
+            let a = v.interpolated_attributes();
+            (position, [a[0], a[1]])
+        });
+        let mut builder = tessellator.builder_with_attributes(2, &options, &mut output);
+        builder.begin(points[0].0, &points[0].1);
+        for (p, attributes) in &points[1..] {
+            builder.line_to(*p, attributes);
+        }
+        builder.end(false);
+        builder.build().unwrap();
+
+        let mut buffers: VertexBuffers<(Point, [f32; 2]), u16> = VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate_polyline_with_attributes(
+                points.iter().map(|(p, a)| (*p, &a[..])),
+                2,
+                &options,
+                &mut BuffersBuilder::new(&mut buffers, |mut v: StrokeVertex| {
+                    let position = v.position();
+                    let a = v.interpolated_attributes();
+                    (position, [a[0], a[1]])
+                }),
+            )
+            .unwrap();
+
+        assert_eq!(buffers.vertices, expected.vertices);
+        assert_eq!(buffers.indices, expected.indices);
+    }
+}
+
+#[test]
+fn join_convexity_and_order() {
+    // A right turn (in y-down coordinates) at (10, 0).
+    let mut builder = crate::path::Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.end(false);
+    let path = builder.build();
+
+    for join in [LineJoin::Miter, LineJoin::Bevel, LineJoin::Round] {
+        let mut buffers: VertexBuffers<(Point, Option<bool>, Option<Order>), u16> =
+            VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate_path(
+                &path,
+                &StrokeOptions::default()
+                    .with_line_width(2.0)
+                    .with_line_join(join),
+                &mut BuffersBuilder::new(&mut buffers, |v: StrokeVertex| {
+                    (v.position(), v.is_on_convex_side(), v.join_order())
+                }),
+            )
+            .unwrap();
+
+        let mut num_convex = 0;
+        let mut num_before = 0;
+        let mut num_after = 0;
+        for (position, convex, order) in &buffers.vertices {
+            match convex {
+                // The outer side of the turn is up and to the right of the corner.
+                Some(true) => {
+                    assert!(position.x > 10.0 || position.y < 0.0, "{:?}", position);
+                    num_convex += 1;
+                }
+                Some(false) => {
+                    assert!(position.x < 10.0 && position.y > 0.0, "{:?}", position);
+                    assert_eq!(*order, None);
+                }
+                None => {
+                    assert_eq!(*order, None);
+                    assert!(position.x < 5.0 || position.y > 5.0, "{:?}", position);
+                }
+            }
+
+            match order {
+                Some(Order::Before) => {
+                    assert_eq!(*position, point(10.0, -1.0));
+                    num_before += 1;
+                }
+                Some(Order::After) => {
+                    assert_eq!(*position, point(11.0, 0.0));
+                    num_after += 1;
+                }
+                None => {}
+            }
+        }
+
+        assert!(num_convex >= 1);
+        let expected = if join == LineJoin::Miter { 0 } else { 1 };
+        assert_eq!(num_before, expected);
+        assert_eq!(num_after, expected);
+    }
+}
+
+#[test]
+fn marquee_advancement_wraps() {
+    let mut builder = Path::builder();
+    builder.add_rectangle(
+        &Box2D {
+            min: point(0.0, 0.0),
+            max: point(10.0, 10.0),
+        },
+        Winding::Positive,
+    );
+    builder.begin(point(20.0, 0.0));
+    builder.line_to(point(20.0, 5.0));
+    builder.end(false);
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<MarqueeVertex, u16> = VertexBuffers::new();
+    StrokeTessellator::new()
+        .tessellate_marquee(&path, 6.0, &StrokeOptions::default(), &mut buffers)
+        .unwrap();
+
+    let (rect, line): (Vec<MarqueeVertex>, Vec<MarqueeVertex>) = buffers
+        .vertices
+        .iter()
+        .partition(|vertex| vertex.position.x < 15.0);
+
+    // The rectangle's perimeter (40) is stretched to seven patterns of length 6.
+    let max = |vertices: &[MarqueeVertex]| {
+        vertices
+            .iter()
+            .fold(0.0f32, |max, vertex| max.max(vertex.advancement))
+    };
+    let min = |vertices: &[MarqueeVertex]| {
+        vertices
+            .iter()
+            .fold(f32::MAX, |min, vertex| min.min(vertex.advancement))
+    };
+    assert!((max(&rect) - 42.0).abs() < 0.001);
+    assert_eq!(min(&rect), 0.0);
+
+    // The open sub-path restarts at zero and isn't scaled.
+    assert_eq!(min(&line), 0.0);
+    assert!((max(&line) - 5.0).abs() < 0.001);
+}
+
+#[test]
+fn dashes() {
+    fn stroke(path: &Path, options: &StrokeOptions) -> VertexBuffers<MarqueeVertex, u16> {
+        let mut buffers: VertexBuffers<MarqueeVertex, u16> = VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate_path(
+                path,
+                options,
+                &mut BuffersBuilder::new(&mut buffers, |vertex: StrokeVertex| MarqueeVertex {
+                    position: vertex.position(),
+                    advancement: vertex.advancement(),
+                }),
+            )
+            .unwrap();
+
+        buffers
+    }
+
+    // Each dash is a separate set of connected triangles.
+    fn count_dashes(buffers: &VertexBuffers<MarqueeVertex, u16>) -> usize {
+        let mut parents: Vec<usize> = (0..buffers.vertices.len()).collect();
+        fn root(parents: &mut [usize], mut i: usize) -> usize {
+            while parents[i] != i {
+                i = parents[i];
+            }
+            i
+        }
+        for triangle in buffers.indices.chunks(3) {
+            let a = root(&mut parents, triangle[0] as usize);
+            for &idx in &triangle[1..] {
+                let b = root(&mut parents, idx as usize);
+                parents[b] = a;
+            }
+        }
+
+        (0..parents.len()).filter(|&i| parents[i] == i).count()
+    }
+
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(100.0, 0.0));
+    builder.end(false);
+    let line = builder.build();
+
+    let options = StrokeOptions::default().with_dash_array(DashArray::new(&[10.0, 10.0]).unwrap());
+    let buffers = stroke(&line, &options);
+    assert_eq!(count_dashes(&buffers), 5);
+    for vertex in &buffers.vertices {
+        // Vertices are inside of the dashes and the advancement includes the gaps.
+        let x = vertex.position.x;
+        assert!((x + 0.001) % 20.0 <= 10.002);
+        assert!((vertex.advancement - x).abs() < 0.001);
+    }
+
+    // An odd number of lengths is repeated and the offset shifts the pattern.
+    let options = StrokeOptions::default()
+        .with_dash_array(DashArray::new(&[10.0]).unwrap())
+        .with_dash_offset(5.0);
+    let buffers = stroke(&line, &options);
+    assert_eq!(count_dashes(&buffers), 6);
+    assert!(buffers
+        .vertices
+        .iter()
+        .any(|v| v.position.x == 5.0 && v.advancement == 5.0));
+
+    // Zero length dashes with round caps produce dots, at 0, 25, 50 and 75.
+    let options = StrokeOptions::default()
+        .with_dash_array(DashArray::new(&[0.0, 25.0]).unwrap())
+        .with_line_cap(LineCap::Round);
+    assert_eq!(count_dashes(&stroke(&line, &options)), 4);
+
+    let mut builder = Path::builder();
+    builder.add_rectangle(
+        &Box2D {
+            min: point(0.0, 0.0),
+            max: point(10.0, 10.0),
+        },
+        Winding::Positive,
+    );
+    let square = builder.build();
+
+    // A dash longer than the sub-path is the same as a solid stroke.
+    let solid = stroke(&square, &StrokeOptions::default());
+    let options = StrokeOptions::default().with_dash_array(DashArray::new(&[100.0, 1.0]).unwrap());
+    let dashed = stroke(&square, &options);
+    assert_eq!(dashed.vertices.len(), solid.vertices.len());
+    assert_eq!(dashed.indices.len(), solid.indices.len());
+
+    // On [0, 12], [18, 30] and [36, 40], the last dash continues into the first one
+    // through the start of the sub-path, with a join at the corner.
+    let options = StrokeOptions::default()
+        .with_dash_array(DashArray::new(&[12.0, 6.0]).unwrap())
+        .with_line_join(LineJoin::Round);
+    let buffers = stroke(&square, &options);
+    assert_eq!(count_dashes(&buffers), 2);
+    let max_advancement = buffers
+        .vertices
+        .iter()
+        .fold(0.0f32, |max, v| max.max(v.advancement));
+    assert!((max_advancement - 52.0).abs() < 0.001);
+}
+
+#[test]
+fn dash_array_errors() {
+    // 17 lengths are repeated into 34.
+    let lengths = [1.0; 17];
+    assert_eq!(
+        DashArray::new(&lengths),
+        Err(DashArrayError::TooManyLengths)
+    );
+    assert_eq!(DashArray::new(&lengths[..16]).unwrap().lengths().len(), 16);
+    assert_eq!(DashArray::new(&[1.0; 15]).unwrap().lengths().len(), 30);
+    assert_eq!(
+        DashArray::new(&[1.0, -1.0]),
+        Err(DashArrayError::InvalidLength)
+    );
+    assert_eq!(
+        DashArray::new(&[f32::NAN]),
+        Err(DashArrayError::InvalidLength)
+    );
+}
+
+#[test]
+fn non_overlapping() {
+    use crate::geometry_builder::simple_builder;
+    use lyon_extra::reference;
+
+    // Crosses itself at (10, 10).
+    let mut builder = crate::path::Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(20.0, 20.0));
+    builder.line_to(point(20.0, 0.0));
+    builder.line_to(point(0.0, 20.0));
+    builder.end(false);
+    let path = builder.build();
+
+    let options = StrokeOptions::default()
+        .with_line_width(2.0)
+        .with_line_join(LineJoin::Round);
+    let triangles = |buffers: &VertexBuffers<Point, u16>| -> Vec<[Point; 3]> {
+        buffers
+            .indices
+            .chunks_exact(3)
+            .map(|t| {
+                let v = |i: u16| buffers.vertices[i as usize];
+                [v(t[0]), v(t[1]), v(t[2])]
+            })
+            .collect()
+    };
+
+    let mut tess = StrokeTessellator::new();
+    let mut overlapping: VertexBuffers<Point, u16> = VertexBuffers::new();
+    tess.tessellate_path(&path, &options, &mut simple_builder(&mut overlapping))
+        .unwrap();
+    let mut non_overlapping: VertexBuffers<Point, u16> = VertexBuffers::new();
+    tess.tessellate_non_overlapping(&path, &options, &mut simple_builder(&mut non_overlapping))
+        .unwrap();
+
+    let overlapping = triangles(&overlapping);
+    let non_overlapping = triangles(&non_overlapping);
+    let center = point(10.03, 10.07);
+    assert!(reference::coverage(&overlapping, center) >= 2);
+    assert_eq!(reference::coverage(&non_overlapping, center), 1);
+
+    // Same footprint, covered once.
+    for i in 0..50 {
+        for j in 0..50 {
+            let p = point(i as f32 * 0.47 - 1.3, j as f32 * 0.47 - 1.1);
+            let expected = reference::coverage(&overlapping, p).min(1);
+            assert_eq!(
+                reference::coverage(&non_overlapping, p),
+                expected,
+                "{:?}",
+                p
+            );
+        }
+    }
+    assert!(reference::area(&non_overlapping) < reference::area(&overlapping));
+
+    // The output limits apply to the filled outline.
+    let mut limited: VertexBuffers<Point, u16> = VertexBuffers::new();
+    let result = tess.tessellate_non_overlapping(
+        &path,
+        &options
+            .with_max_triangles(non_overlapping.len() as u32 - 1)
+            .with_budget_policy(crate::BudgetPolicy::Fail),
+        &mut simple_builder(&mut limited),
+    );
+    assert_eq!(result, Err(TessellationError::OutputLimitExceeded));
+}
+
+#[test]
+fn arrow_caps() {
+    let mut builder = Path::builder_with_attributes(1);
+    builder.begin(point(0.0, 0.0), &[1.0]);
+    builder.line_to(point(10.0, 0.0), &[1.0]);
+    builder.end(false);
+    let path = builder.build();
+
+    let fixed = StrokeOptions::default().with_line_width(2.0);
+    let variable = fixed.with_variable_line_width(0);
+    for options in [fixed, variable] {
+        let options = options
+            .with_line_cap(LineCap::Arrow)
+            .with_arrow_length(2.0)
+            .with_arrow_width(3.0);
+        test_path(path.as_slice(), &options, Some(8));
+
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate_path(&path, &options, &mut simple_builder(&mut buffers))
+            .unwrap();
+        for expected in [
+            point(14.0, 0.0),
+            point(10.0, 3.0),
+            point(10.0, -3.0),
+            point(-4.0, 0.0),
+            point(0.0, 3.0),
+            point(0.0, -3.0),
+        ] {
+            assert!(
+                buffers
+                    .vertices
+                    .iter()
+                    .any(|v| (*v - expected).length() < 1e-4),
+                "missing {:?} in {:?}",
+                expected,
+                buffers.vertices
+            );
+        }
+
+        // The arrowheads don't overlap the rest of the stroke.
+        let area: f32 = buffers
+            .indices
+            .chunks(3)
+            .map(|tri| {
+                let a = buffers.vertices[tri[0] as usize];
+                let b = buffers.vertices[tri[1] as usize];
+                let c = buffers.vertices[tri[2] as usize];
+                (b - a).cross(c - a).abs() * 0.5
+            })
+            .sum();
+        assert!((area - (20.0 + 2.0 * 12.0)).abs() < 1e-3, "{:?}", area);
+    }
+
+    // Zero-length sub-paths have no direction and no arrowhead.
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.end(true);
+    test_path(
+        builder.build().as_slice(),
+        &StrokeOptions::default().with_line_cap(LineCap::Arrow),
+        Some(0),
+    );
+}
+
+#[test]
+fn tapered_stroke() {
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.end(false);
+    let path = builder.build();
+
+    let widths = |options: &StrokeOptions, path: &Path| {
+        let mut buffers: VertexBuffers<(Point, f32), u16> = VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate_path(
+                path,
+                options,
+                &mut BuffersBuilder::new(&mut buffers, |v: StrokeVertex| {
+                    (v.position_on_path(), v.line_width())
+                }),
+            )
+            .unwrap();
+        buffers.vertices
+    };
+
+    let options = StrokeOptions::default()
+        .with_line_width(2.0)
+        .with_start_width(4.0)
+        .with_end_width(0.0)
+        .with_line_join(LineJoin::Bevel);
+    for (position, width) in widths(&options, &path) {
+        let expected = if position == point(0.0, 0.0) {
+            4.0
+        } else if position == point(10.0, 0.0) {
+            2.0
+        } else {
+            0.0
+        };
+        assert!(
+            (width - expected).abs() < 0.01,
+            "{:?} {:?}",
+            position,
+            width
+        );
+    }
+    test_path(path.as_slice(), &options, None);
+
+    // Only one end set: the other uses the line width.
+    let options = StrokeOptions::default()
+        .with_line_width(2.0)
+        .with_start_width(4.0);
+    for (position, width) in widths(&options, &path) {
+        if position == point(10.0, 10.0) {
+            assert_eq!(width, 2.0);
+        }
+    }
+
+    // The variable line width is applied on top of the taper.
+    let mut builder = Path::builder_with_attributes(1);
+    builder.begin(point(0.0, 0.0), &[0.5]);
+    builder.line_to(point(10.0, 0.0), &[1.0]);
+    builder.end(false);
+    let path = builder.build();
+
+    let options = StrokeOptions::default()
+        .with_variable_line_width(0)
+        .with_start_width(4.0)
+        .with_end_width(2.0);
+    for (position, width) in widths(&options, &path) {
+        // 4.0 * 0.5 at the start and 2.0 * 1.0 at the end.
+        assert_eq!(width, 2.0, "{:?}", position);
+    }
+}
+
+#[test]
+fn normalized_advancement() {
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.end(false);
+    builder.begin(point(0.0, 20.0));
+    builder.line_to(point(40.0, 20.0));
+    builder.end(false);
+    let path = builder.build();
+
+    let advancements = |options: &StrokeOptions| {
+        let mut buffers: VertexBuffers<(Point, f32), u16> = VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate_path(
+                &path,
+                options,
+                &mut BuffersBuilder::new(&mut buffers, |v: StrokeVertex| {
+                    (v.position_on_path(), v.advancement())
+                }),
+            )
+            .unwrap();
+        buffers.vertices
+    };
+
+    let absolute = advancements(&StrokeOptions::default());
+    let normalized = advancements(&StrokeOptions::default().with_normalized_advancement(true));
+    assert_eq!(absolute.len(), normalized.len());
+
+    for (position, advancement) in normalized {
+        let expected = if position.y == 20.0 {
+            position.x / 40.0
+        } else {
+            (position.x + position.y) / 20.0
+        };
+        assert!(
+            (advancement - expected).abs() < 0.001,
+            "{:?} {:?}",
+            position,
+            advancement
+        );
+    }
+
+    // Without the option, the advancement carries over to the next sub-path.
+    assert!(absolute.iter().any(|(_, a)| *a > 50.0));
+
+    test_path(
+        path.as_slice(),
+        &StrokeOptions::default().with_normalized_advancement(true),
+        None,
+    );
+}
+
+#[test]
+fn stroke_uv() {
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.end(false);
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<(Point, Point, Point), u16> = VertexBuffers::new();
+    StrokeTessellator::new()
+        .tessellate_path(
+            &path,
+            &StrokeOptions::default()
+                .with_line_width(2.0)
+                .with_line_join(LineJoin::Miter),
+            &mut BuffersBuilder::new(&mut buffers, |v: StrokeVertex| {
+                (v.position(), v.position_on_path(), v.uv())
+            }),
+        )
+        .unwrap();
+
+    for (position, on_path, uv) in buffers.vertices {
+        // All vertices are on the edges of the stroke, including the miter join.
+        assert_eq!(uv.y.abs(), 1.0);
+        let expected_u = (on_path.x + on_path.y).abs();
+        assert!((uv.x - expected_u).abs() < 0.001, "{:?}", uv);
+        // The positive side is the right side in a y-down coordinate system.
+        let offset = position - on_path;
+        if on_path.y == 0.0 && on_path.x < 10.0 {
+            assert_eq!(uv.y, offset.y);
+        }
+    }
+}
+
+#[test]
+fn periodic_line_width() {
+    // A ring with an explicit last endpoint on its first endpoint.
+    let mut builder = Path::builder_with_attributes(1);
+    builder.begin(point(0.0, 0.0), &[1.0]);
+    builder.line_to(point(10.0, 0.0), &[2.0]);
+    builder.line_to(point(10.0, 10.0), &[2.0]);
+    builder.line_to(point(0.0, 10.0), &[2.0]);
+    builder.line_to(point(0.0, 0.0), &[3.0]);
+    builder.end(true);
+    let path = builder.build();
+
+    let seam_widths = |options: &StrokeOptions| {
+        let mut buffers: VertexBuffers<(Point, f32), u16> = VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate_path(
+                &path,
+                options,
+                &mut BuffersBuilder::new(&mut buffers, |v: StrokeVertex| {
+                    (v.position_on_path(), v.line_width())
+                }),
+            )
+            .unwrap();
+        let mut widths: Vec<f32> = buffers
+            .vertices
+            .iter()
+            .filter(|(p, _)| *p == point(0.0, 0.0))
+            .map(|(_, w)| *w)
+            .collect();
+        widths.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        widths.dedup();
+        widths
+    };
+
+    let options = StrokeOptions::default().with_variable_line_width(0);
+    // By default the seam uses the width of the last endpoint, although the first edge
+    // starts with the width of the first endpoint.
+    assert_eq!(seam_widths(&options), [3.0]);
+    assert_eq!(seam_widths(&options.with_periodic_line_width(true)), [2.0]);
+
+    test_path(
+        path.as_slice(),
+        &options.with_periodic_line_width(true),
+        None,
+    );
+}
+
+#[test]
+fn periodic_line_width_sub_paths() {
+    // Each ring has its own seam widths.
+    let mut builder = Path::builder_with_attributes(1);
+    for i in 0..20 {
+        let x = i as f32 * 20.0;
+        builder.begin(point(x, 0.0), &[1.0]);
+        builder.line_to(point(x + 10.0, 0.0), &[2.0]);
+        builder.line_to(point(x + 10.0, 10.0), &[2.0]);
+        builder.line_to(point(x, 0.0), &[1.0 + i as f32]);
+        builder.end(true);
+    }
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<(Point, f32), u16> = VertexBuffers::new();
+    StrokeTessellator::new()
+        .tessellate_path(
+            &path,
+            &StrokeOptions::default()
+                .with_variable_line_width(0)
+                .with_periodic_line_width(true),
+            &mut BuffersBuilder::new(&mut buffers, |v: StrokeVertex| {
+                (v.position_on_path(), v.line_width())
+            }),
+        )
+        .unwrap();
+
+    for i in 0..20 {
+        let seam = point(i as f32 * 20.0, 0.0);
+        let expected = (2.0 + i as f32) * 0.5;
+        let widths: Vec<f32> = buffers
+            .vertices
+            .iter()
+            .filter(|(p, _)| *p == seam)
+            .map(|(_, w)| *w)
+            .collect();
+        assert!(!widths.is_empty());
+        assert!(widths.iter().all(|w| *w == expected), "{:?}", widths);
+    }
+}
+
+#[test]
+fn stroke_option_overrides() {
+    use crate::path::commands::PathCommands;
+
+    let endpoints = [point(0.0, 0.0), point(10.0, 0.0), point(10.0, 10.0)];
+    let mut builder = PathCommands::builder();
+    builder.begin(EndpointId(0));
+    let first_edge = builder.line_to(EndpointId(1));
+    let second_edge = builder.line_to(EndpointId(2));
+    let end = builder.end(false).unwrap();
+    let commands = builder.build();
+
+    let tessellate = |overrides: &dyn Fn(EventId) -> Option<StrokeOptionsOverride>| {
+        let mut buffers: VertexBuffers<(Point, f32), u16> = VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate_commands_with_overrides(
+                &commands,
+                &(&endpoints[..], &[][..]),
+                None,
+                &StrokeOptions::default().with_line_width(2.0),
+                overrides,
+                &mut BuffersBuilder::new(&mut buffers, |v: StrokeVertex| {
+                    (v.position_on_path(), v.line_width())
+                }),
+            )
+            .unwrap();
+        buffers
+    };
+
+    let reference = tessellate(&|_| None);
+    let widths = |buffers: &VertexBuffers<(Point, f32), u16>, at: Point| {
+        let mut widths: Vec<f32> = buffers
+            .vertices
+            .iter()
+            .filter(|(p, _)| *p == at)
+            .map(|(_, w)| *w)
+            .collect();
+        widths.dedup();
+        widths
+    };
+    assert_eq!(widths(&reference, point(10.0, 10.0)), [2.0]);
+
+    // The second edge is wider, the stroke gets wider along the first edge.
+    let wide = tessellate(&|id| {
+        if id == second_edge {
+            Some(StrokeOptionsOverride::NONE.with_line_width(6.0))
+        } else {
+            None
+        }
+    });
+    assert_eq!(widths(&wide, point(0.0, 0.0)), [2.0]);
+    assert_eq!(widths(&wide, point(10.0, 0.0)), [6.0]);
+    assert_eq!(widths(&wide, point(10.0, 10.0)), [6.0]);
+
+    // A round cap at the end of the sub-path adds vertices.
+    let round_cap = tessellate(&|id| {
+        if id == end {
+            Some(StrokeOptionsOverride::NONE.with_end_cap(LineCap::Round))
+        } else {
+            None
+        }
+    });
+    assert!(round_cap.vertices.len() > reference.vertices.len());
+
+    // So does a round join at the end of the first edge.
+    let round_join = tessellate(&|id| {
+        if id == first_edge {
+            Some(StrokeOptionsOverride::NONE.with_line_join(LineJoin::Round))
+        } else {
+            None
+        }
+    });
+    assert!(round_join.vertices.len() > reference.vertices.len());
+}
+
+#[test]
+fn stroke_option_overrides_unsupported_options() {
+    use crate::path::commands::PathCommands;
+
+    let endpoints = [point(0.0, 0.0), point(10.0, 0.0)];
+    let mut builder = PathCommands::builder();
+    builder.begin(EndpointId(0));
+    builder.line_to(EndpointId(1));
+    builder.end(false);
+    let commands = builder.build();
+
+    let base = StrokeOptions::default();
+    for (options, name) in [
+        (base.with_start_width(2.0), "start_width/end_width"),
+        (
+            base.with_normalized_advancement(true),
+            "normalized_advancement",
+        ),
+        (base.with_periodic_line_width(true), "periodic_line_width"),
+        (base.with_anti_aliasing_fringe(1.0), "anti_aliasing_fringe"),
+    ] {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        let result = StrokeTessellator::new().tessellate_commands_with_overrides(
+            &commands,
+            &(&endpoints[..], &[][..]),
+            None,
+            &options,
+            &|_| None,
+            &mut simple_builder(&mut buffers),
+        );
+        assert_eq!(
+            result,
+            Err(TessellationError::UnsupportedParamater(
+                UnsupportedParamater::StrokeOptionIsNotSupported(name)
+            ))
+        );
+        assert!(buffers.vertices.is_empty());
+    }
+}
+
+#[test]
+fn stroke_polyline() {
+    let points = [point(0.0, 0.0), point(10.0, 0.0), point(10.0, 10.0)];
+
+    for closed in [false, true] {
+        let mut builder = Path::builder();
+        builder.begin(points[0]);
+        builder.line_to(points[1]);
+        builder.line_to(points[2]);
+        builder.end(closed);
+        let path = builder.build();
+
+        let options = StrokeOptions::default()
+            .with_line_width(2.0)
+            .with_line_join(LineJoin::Round);
+        let mut tessellator = StrokeTessellator::new();
+        let mut expected: VertexBuffers<Point, u16> = VertexBuffers::new();
+        tessellator
+            .tessellate_path(&path, &options, &mut simple_builder(&mut expected))
+            .unwrap();
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        tessellator
+            .tessellate_polyline(&points, closed, &options, &mut simple_builder(&mut buffers))
+            .unwrap();
+
+        assert_eq!(buffers.vertices, expected.vertices);
+        assert_eq!(buffers.indices, expected.indices);
+    }
+}
+
+#[test]
+fn stroke_attribute_interpolation() {
+    let mut builder = Path::builder_with_attributes(1);
+    builder.begin(point(0.0, 0.0), &[0.0]);
+    builder.quadratic_bezier_to(point(50.0, 50.0), point(100.0, 0.0), &[1.0]);
+    builder.end(false);
+    let path = builder.build();
+
+    let attributes = |options: &StrokeOptions| {
+        let mut buffers: VertexBuffers<f32, u16> = VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate_path(
+                &path,
+                options,
+                &mut BuffersBuilder::new(&mut buffers, |mut v: StrokeVertex| {
+                    v.interpolated_attributes()[0]
+                }),
+            )
+            .unwrap();
+        buffers.vertices
+    };
+
+    let linear = attributes(&StrokeOptions::default());
+    assert!(linear.iter().any(|a| *a > 0.0 && *a < 1.0));
+
+    // Clamp the interpolated values.
+    let clamped = attributes(
+        &StrokeOptions::default()
+            .with_attribute_interpolation(|_, from, to, t| (from + (to - from) * t).min(0.5)),
+    );
+    assert_eq!(clamped.len(), linear.len());
+    for (clamped, linear) in clamped.iter().zip(&linear) {
+        // Endpoints are not interpolated.
+        if *linear != 1.0 {
+            assert_eq!(*clamped, linear.min(0.5));
+        }
+    }
+    assert!(clamped.contains(&1.0));
+}
+
+#[test]
+fn stroke_y_axis_direction() {
+    use crate::{TriangleWinding, YAxisDirection};
+
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.end(false);
+    let path = builder.build();
+
+    let tessellate = |options: &StrokeOptions| {
+        let mut buffers: VertexBuffers<(Point, Side), u16> = VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate_path(
+                &path,
+                options,
+                &mut BuffersBuilder::new(&mut buffers, |v: StrokeVertex| (v.position(), v.side())),
+            )
+            .unwrap();
+        let areas: Vec<f32> = buffers
+            .indices
+            .chunks(3)
+            .map(|tri| {
+                let a = buffers.vertices[tri[0] as usize].0;
+                let b = buffers.vertices[tri[1] as usize].0;
+                let c = buffers.vertices[tri[2] as usize].0;
+                (b - a).cross(c - a)
+            })
+            .filter(|area| area.abs() > 1e-5)
+            .collect();
+        (buffers.vertices, areas)
+    };
+
+    // The first segment goes towards positive x. With the y axis pointing down, its right
+    // side is towards positive y.
+    let options = StrokeOptions::default().with_line_width(2.0);
+    let (vertices, areas) = tessellate(&options);
+    assert!(areas.iter().all(|area| *area < 0.0));
+    for (position, side) in &vertices {
+        if position.x < 9.0 {
+            assert_eq!(position.y > 0.0, side.is_positive());
+        }
+    }
+
+    // With the y axis pointing up the right side is towards negative y.
+    let options = options.with_y_axis_direction(YAxisDirection::Up);
+    let (vertices, areas) = tessellate(&options);
+    assert!(areas.iter().all(|area| *area > 0.0));
+    for (position, side) in &vertices {
+        if position.x < 9.0 {
+            assert_eq!(position.y < 0.0, side.is_positive());
+        }
+    }
+
+    let options = options.with_triangle_winding(TriangleWinding::Clockwise);
+    let (_, areas) = tessellate(&options.with_anti_aliasing_fringe(0.5));
+    assert!(areas.iter().all(|area| *area < 0.0));
+
+    // Same orientations without overlapping triangles.
+    let non_overlapping = |options: &StrokeOptions| {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate_non_overlapping(path.iter(), options, &mut simple_builder(&mut buffers))
+            .unwrap();
+        let areas: Vec<f32> = buffers
+            .indices
+            .chunks(3)
+            .map(|tri| {
+                let a = buffers.vertices[tri[0] as usize];
+                let b = buffers.vertices[tri[1] as usize];
+                let c = buffers.vertices[tri[2] as usize];
+                (b - a).cross(c - a)
+            })
+            .filter(|area| area.abs() > 1e-5)
+            .collect();
+        assert!(!areas.is_empty());
+        areas
+    };
+    let options = StrokeOptions::default().with_line_width(2.0);
+    assert!(non_overlapping(&options).iter().all(|area| *area < 0.0));
+    let options = options.with_y_axis_direction(YAxisDirection::Up);
+    assert!(non_overlapping(&options).iter().all(|area| *area > 0.0));
+    let options = options.with_triangle_winding(TriangleWinding::Clockwise);
+    assert!(non_overlapping(&options).iter().all(|area| *area < 0.0));
+}
+
+#[test]
+fn hairline() {
+    let mut builder = Path::builder_with_attributes(1);
+    builder.begin(point(0.0, 0.0), &[0.0]);
+    builder.line_to(point(10.0, 0.0), &[1.0]);
+    builder.quadratic_bezier_to(point(20.0, 0.0), point(20.0, 10.0), &[2.0]);
+    builder.end(false);
+    builder.begin(point(0.0, 20.0), &[3.0]);
+    builder.line_to(point(10.0, 20.0), &[3.0]);
+    builder.line_to(point(10.0, 30.0), &[3.0]);
+    builder.end(true);
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<(Point, f32, f32), u16> = VertexBuffers::new();
+    StrokeTessellator::new()
+        .tessellate_hairline_path(
+            &path,
+            &StrokeOptions::tolerance(0.01).with_line_width(5.0),
+            &mut BuffersBuilder::new(&mut buffers, |mut v: StrokeVertex| {
+                assert_eq!(v.position(), v.position_on_path());
+                (
+                    v.position(),
+                    v.advancement(),
+                    v.interpolated_attributes()[0],
+                )
+            }),
+        )
+        .unwrap();
+
+    let vertices = &buffers.vertices;
+    assert!(vertices.len() > 8);
+    assert_eq!(buffers.indices.len() % 2, 0);
+    let lines: Vec<(usize, usize)> = buffers
+        .indices
+        .chunks(2)
+        .map(|line| (line[0] as usize, line[1] as usize))
+        .collect();
+
+    // The first sub-path is a strip, the curve is flattened with increasing attributes.
+    let first_sub_path = vertices.len() - 3;
+    for i in 0..first_sub_path - 1 {
+        assert_eq!(lines[i], (i, i + 1));
+        assert!(vertices[i + 1].1 > vertices[i].1);
+        assert!(vertices[i + 1].2 >= vertices[i].2);
+    }
+    assert_eq!(vertices[first_sub_path - 1].0, point(20.0, 10.0));
+    assert_eq!(vertices[first_sub_path - 1].2, 2.0);
+
+    // The closed sub-path has three line segments.
+    let first = first_sub_path;
+    assert_eq!(
+        lines[first_sub_path - 1..],
+        [
+            (first, first + 1),
+            (first + 1, first + 2),
+            (first + 2, first)
+        ]
+    );
+    assert_eq!(vertices[first].0, point(0.0, 20.0));
+    assert_eq!(vertices[first].2, 3.0);
+}
+
+#[test]
+fn dots() {
+    let mut builder = Path::builder_with_attributes(1);
+    builder.begin(point(0.0, 0.0), &[0.0]);
+    builder.line_to(point(10.0, 0.0), &[1.0]);
+    builder.line_to(point(10.0, 5.0), &[2.0]);
+    builder.end(false);
+    builder.begin(point(0.0, 20.0), &[0.0]);
+    builder.line_to(point(10.0, 20.0), &[0.0]);
+    builder.line_to(point(10.0, 30.0), &[0.0]);
+    builder.line_to(point(0.0, 30.0), &[0.0]);
+    builder.end(true);
+    builder.begin(point(50.0, 50.0), &[0.0]);
+    builder.end(false);
+    let path = builder.build();
+
+    let tessellate = |shape| {
+        let mut buffers: VertexBuffers<(Point, Point, f32, f32), u16> = VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate_dots_path(
+                &path,
+                &StrokeOptions::default()
+                    .with_line_width(2.0)
+                    .with_round_segments(4),
+                5.0,
+                shape,
+                &mut BuffersBuilder::new(&mut buffers, |mut v: StrokeVertex| {
+                    (
+                        v.position(),
+                        v.position_on_path(),
+                        v.advancement(),
+                        v.interpolated_attributes()[0],
+                    )
+                }),
+            )
+            .unwrap();
+        buffers
+    };
+
+    // Four dots on the open sub-path, eight on the closed one and one on the last one.
+    let squares = tessellate(DotShape::Square);
+    assert_eq!(squares.vertices.len(), 13 * 4);
+    assert_eq!(squares.indices.len(), 13 * 6);
+    let centers: Vec<(Point, f32, f32)> = squares
+        .vertices
+        .chunks(4)
+        .map(|dot| (dot[0].1, dot[0].2, dot[0].3))
+        .collect();
+    assert_eq!(
+        centers[..4],
+        [
+            (point(0.0, 0.0), 0.0, 0.0),
+            (point(5.0, 0.0), 5.0, 0.5),
+            (point(10.0, 0.0), 10.0, 1.0),
+            (point(10.0, 5.0), 15.0, 2.0)
+        ]
+    );
+    assert_eq!(centers[4].0, point(0.0, 20.0));
+    assert_eq!(centers[5].0, point(5.0, 20.0));
+    assert_eq!(centers[11].0, point(0.0, 25.0));
+    assert_eq!(centers[12].0, point(50.0, 50.0));
+
+    // The squares are aligned with the path.
+    assert_eq!(squares.vertices[4 * 5 + 2].0, point(6.0, 21.0));
+
+    let circles = tessellate(DotShape::Circle);
+    assert_eq!(circles.vertices.len(), 13 * 9);
+    assert_eq!(circles.indices.len(), 13 * 8 * 3);
+    for v in &circles.vertices {
+        let d = (v.0 - v.1).length();
+        assert!(d < 0.001 || (d - 1.0).abs() < 0.001);
+    }
+
+    // Same orientation as the other stroke triangles.
+    for buffers in &[squares, circles] {
+        for t in buffers.indices.chunks(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| buffers.vertices[t[i] as usize].0);
+            assert!((b - a).cross(c - a) < 0.0);
+        }
+    }
+}
+
+#[test]
+fn dots_invalid_spacing() {
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.end(false);
+    let path = builder.build();
+
+    for spacing in [0.0, -1.0, f32::NAN] {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        let result = StrokeTessellator::new().tessellate_dots_path(
+            &path,
+            &StrokeOptions::default(),
+            spacing,
+            DotShape::Circle,
+            &mut simple_builder(&mut buffers),
+        );
+        assert_eq!(
+            result,
+            Err(TessellationError::UnsupportedParamater(
+                UnsupportedParamater::DotSpacingIsNotPositive
+            ))
+        );
+        assert!(buffers.vertices.is_empty());
+    }
+}
+
+#[test]
+fn custom_shapes() {
+    struct MiterJoin(u32);
+    impl JoinBuilder for MiterJoin {
+        fn add_join(
+            &mut self,
+            _: Point,
+            prev_tangent: Vector,
+            next_tangent: Vector,
+            _: Side,
+            output: &mut CustomShapeOutput,
+        ) -> Result<(), GeometryBuilderError> {
+            self.0 += 1;
+            let (start, end) = (output.start_vertex(), output.end_vertex());
+            let a = output.vertex_position(start).unwrap();
+            let b = output.vertex_position(end).unwrap();
+            let t = (b - a).cross(next_tangent) / prev_tangent.cross(next_tangent);
+            let miter = output.add_vertex(a + prev_tangent * t)?;
+            output.add_triangle(start, end, miter);
+
+            Ok(())
+        }
+    }
+
+    struct SquareCap(u32);
+    impl CapBuilder for SquareCap {
+        fn add_cap(
+            &mut self,
+            _: Point,
+            tangent: Vector,
+            output: &mut CustomShapeOutput,
+        ) -> Result<(), GeometryBuilderError> {
+            self.0 += 1;
+            let (start, end) = (output.start_vertex(), output.end_vertex());
+            let offset = tangent * output.half_width();
+            let a = output.vertex_position(start).unwrap() + offset;
+            let b = output.vertex_position(end).unwrap() + offset;
+            let a = output.add_vertex(a)?;
+            let b = output.add_vertex(b)?;
+            output.add_triangle(start, end, b);
+            output.add_triangle(start, b, a);
+
+            Ok(())
+        }
+    }
+
+    let mut builder = crate::path::Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.end(false);
+    let path = builder.build();
+
+    let options = StrokeOptions::default().with_line_width(2.0);
+    let mut joins = MiterJoin(0);
+    let mut caps = SquareCap(0);
+    let mut custom: VertexBuffers<Point, u16> = VertexBuffers::new();
+    StrokeTessellator::new()
+        .tessellate_with_custom_shapes(
+            path.iter(),
+            &options,
+            Some(&mut joins),
+            Some(&mut caps),
+            &mut BuffersBuilder::new(&mut custom, |v: StrokeVertex| v.position()),
+        )
+        .unwrap();
+    assert_eq!(joins.0, 1);
+    assert_eq!(caps.0, 2);
+
+    let mut reference: VertexBuffers<Point, u16> = VertexBuffers::new();
+    StrokeTessellator::new()
+        .tessellate_path(
+            &path,
+            &options
+                .with_line_join(LineJoin::Miter)
+                .with_line_cap(LineCap::Square),
+            &mut BuffersBuilder::new(&mut reference, |v: StrokeVertex| v.position()),
+        )
+        .unwrap();
+
+    // Same area, and the same orientation as the other stroke triangles.
+    let area = |buffers: &VertexBuffers<Point, u16>| {
+        let mut area = 0.0;
+        for t in buffers.indices.chunks(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| buffers.vertices[t[i] as usize]);
+            let cross = (b - a).cross(c - a);
+            assert!(cross < 0.0);
+            area -= cross * 0.5;
+        }
+        area
+    };
+    assert!((area(&custom) - 44.0).abs() < 0.001);
+    assert!((area(&reference) - 44.0).abs() < 0.001);
+}
+
+#[test]
+fn custom_shapes_unsupported_options() {
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.end(false);
+    let path = builder.build();
+
+    let base = StrokeOptions::default();
+    for (options, name) in [
+        (base.with_variable_line_width(0), "variable_line_width"),
+        (base.with_start_width(2.0), "start_width/end_width"),
+        (
+            base.with_normalized_advancement(true),
+            "normalized_advancement",
+        ),
+        (base.with_anti_aliasing_fringe(1.0), "anti_aliasing_fringe"),
+        (base.with_max_vertices(10), "max_vertices/max_triangles"),
+    ] {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        let result = StrokeTessellator::new().tessellate_with_custom_shapes(
+            path.iter(),
+            &options,
+            None,
+            None,
+            &mut simple_builder(&mut buffers),
+        );
+        assert_eq!(
+            result,
+            Err(TessellationError::UnsupportedParamater(
+                UnsupportedParamater::StrokeOptionIsNotSupported(name)
+            ))
+        );
+        assert!(buffers.vertices.is_empty());
+    }
+}
@@ -0,0 +1,1324 @@
+//! Double precision stroke tessellation.
+//!
+//! The sub-paths are flattened, offset and joined in double precision. Vertices only carry
+//! two single precision values: their normals, which are expressed in multiples of the
+//! half line width, and their positions relative to the first point of their sub-path.
+
+use crate::geom::euclid::default::{Point2D, Vector2D};
+use crate::geom::{CubicBezierSegment, LineSegment, QuadraticBezierSegment};
+use crate::geometry_builder::Oriented;
+use crate::math::{point, vector};
+use crate::math_utils::compute_normal;
+use crate::path::{EndpointId, Event};
+use crate::stroke::{
+    check_unsupported_options, circle_flattening_step, convex_side, get_clip_intersections,
+    interpolate_source, miter_limit_is_exceeded, square_cap_edge_part, StrokeFeature,
+    StrokeVertexData, SIDE_NEGATIVE, SIDE_POSITIVE,
+};
+use crate::{
+    GeometryBuilder, LineCap, LineJoin, Order, Side, StrokeGeometryBuilder, StrokeOptions,
+    StrokePart, StrokeVertex, TessellationError, TessellationResult, VertexId, VertexSource,
+};
+
+use std::f64::consts::PI;
+use std::ops::Range;
+
+/// A stroke tessellator for paths with double precision coordinates.
+///
+/// Single precision floats have about seven significant digits, so at large coordinates, as
+/// is common with CAD drawings or maps, the joins and caps computed by `StrokeTessellator`
+/// visibly wobble. This tessellator flattens the path and computes the sides, joins and
+/// caps of the stroke in double precision.
+///
+/// The vertices are produced through a `StrokeGeometryBuilder` like with `StrokeTessellator`.
+/// [`StrokeVertex::position_f64`](struct.StrokeVertex.html#method.position_f64) provides
+/// their exact positions. The single precision positions of
+/// [`StrokeVertex::position`](struct.StrokeVertex.html#method.position) are relative to
+/// [`StrokeVertex::origin`](struct.StrokeVertex.html#method.origin), the first point of
+/// their sub-path, so renderers can keep them and add the difference between the origin and
+/// the camera position in the vertex shader.
+///
+/// Variable line width, tapering, normalized advancement, the anti-aliasing fringe, output
+/// limits and `LineJoin::Arcs` are not supported. `tessellate` returns
+/// `UnsupportedParamater::StrokeOptionIsNotSupported` if any of them is set.
+///
+/// # Example
+///
+/// ```
+/// use lyon_tessellation::geom::euclid::default::Point2D;
+/// use lyon_tessellation::path::Event;
+/// use lyon_tessellation::*;
+///
+/// let p = |x: f64, y: f64| Point2D::new(x, y);
+/// let events = [
+///     Event::Begin { at: p(1e8, 1e8) },
+///     Event::Line { from: p(1e8, 1e8), to: p(1e8 + 10.0, 1e8) },
+///     Event::Line { from: p(1e8 + 10.0, 1e8), to: p(1e8 + 10.0, 1e8 + 0.5) },
+///     Event::End { last: p(1e8 + 10.0, 1e8 + 0.5), first: p(1e8, 1e8), close: false },
+/// ];
+///
+/// let mut buffers: VertexBuffers<Point2D<f64>, u16> = VertexBuffers::new();
+/// StrokeTessellatorF64::new().tessellate(
+///     events.iter().cloned(),
+///     &StrokeOptions::default().with_line_width(0.25),
+///     &mut BuffersBuilder::new(&mut buffers, |v: StrokeVertex| v.position_f64()),
+/// ).unwrap();
+///
+/// assert!(buffers.vertices.iter().all(|v| v.y < 1e8 + 0.75));
+/// ```
+#[derive(Default)]
+pub struct StrokeTessellatorF64 {
+    points: Vec<PathPoint>,
+    polyline: Vec<PathPoint>,
+    dash: Vec<PathPoint>,
+    first_dash: Vec<PathPoint>,
+    joins: Vec<Join>,
+}
+
+impl StrokeTessellatorF64 {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compute the tessellation from an iterator of path events with double precision
+    /// positions.
+    pub fn tessellate(
+        &mut self,
+        input: impl IntoIterator<Item = Event<Point2D<f64>, Point2D<f64>>>,
+        options: &StrokeOptions,
+        builder: &mut dyn StrokeGeometryBuilder,
+    ) -> TessellationResult {
+        check_unsupported_options(
+            options,
+            &[
+                StrokeFeature::VariableLineWidth,
+                StrokeFeature::Tapering,
+                StrokeFeature::NormalizedAdvancement,
+                StrokeFeature::AntiAliasingFringe,
+                StrokeFeature::OutputLimits,
+                StrokeFeature::ArcsJoin,
+            ],
+        )?;
+
+        builder.begin_geometry();
+
+        let mut stroker = Stroker {
+            options: *options,
+            half_width: options.line_width as f64 * 0.5,
+            square_merge_threshold: crate::stroke::square_merge_threshold(options) as f64,
+            start_advancement: 0.0,
+            output: Oriented::stroke(options, builder),
+            vertex: StrokeVertexData {
+                position_on_path: point(0.0, 0.0),
+                half_width: options.line_width * 0.5,
+                normal: vector(0.0, 0.0),
+                advancement: 0.0,
+                side: Side::Negative,
+                convex_side: None,
+                order: None,
+                src: VertexSource::Endpoint {
+                    id: EndpointId::INVALID,
+                },
+                coverage: 1.0,
+                buffer: &mut [],
+                buffer_is_valid: false,
+                interpolation: None,
+                origin: Point2D::origin(),
+                position_on_path_f64: None,
+            },
+            scratch: self,
+        };
+
+        let result = stroker.tessellate(input);
+        if result.is_err() {
+            stroker.output.abort_geometry();
+        } else {
+            stroker.output.end_geometry();
+        }
+
+        result
+    }
+}
+
+/// A point of a flattened sub-path.
+#[derive(Copy, Clone, Debug)]
+struct PathPoint {
+    position: Point2D<f64>,
+    /// Distance along the path.
+    advancement: f64,
+    src: VertexSource,
+    /// Whether the point is within a flattened curve rather than on an endpoint.
+    is_flattening_step: bool,
+}
+
+/// The side of the stroke at a point of a sub-path.
+///
+/// The normals are the offsets of the side vertices from the point, in multiples of the
+/// half line width.
+#[derive(Copy, Clone, Debug)]
+struct JoinSide {
+    /// Normal of the vertex attached to the previous edge.
+    prev: Vector2D<f64>,
+    /// Normal of the vertex attached to the next edge.
+    next: Vector2D<f64>,
+    /// Whether both edges are attached to a single vertex.
+    single_vertex: bool,
+    prev_vertex: VertexId,
+    next_vertex: VertexId,
+}
+
+impl JoinSide {
+    fn new(prev: Vector2D<f64>, next: Vector2D<f64>) -> Self {
+        JoinSide {
+            prev,
+            next,
+            single_vertex: false,
+            prev_vertex: VertexId::INVALID,
+            next_vertex: VertexId::INVALID,
+        }
+    }
+
+    fn set_single_vertex(&mut self, normal: Vector2D<f64>) {
+        self.prev = normal;
+        self.next = normal;
+        self.single_vertex = true;
+    }
+}
+
+/// The sides of the stroke at a point of a sub-path, indexed with `SIDE_POSITIVE` and
+/// `SIDE_NEGATIVE`.
+#[derive(Copy, Clone, Debug)]
+struct Join {
+    sides: [JoinSide; 2],
+    /// Whether the join folds on a side, see `compute_join_side_positions_fixed_width`.
+    fold: [bool; 2],
+    line_join: LineJoin,
+}
+
+impl Join {
+    fn new(positive: Vector2D<f64>, negative: Vector2D<f64>) -> Self {
+        Join {
+            sides: [
+                JoinSide::new(positive, positive),
+                JoinSide::new(negative, negative),
+            ],
+            fold: [false; 2],
+            line_join: LineJoin::Bevel,
+        }
+    }
+}
+
+struct Stroker<'l> {
+    options: StrokeOptions,
+    half_width: f64,
+    square_merge_threshold: f64,
+    /// Advancement at the start of the current sub-path.
+    start_advancement: f64,
+    output: Oriented<'l, dyn StrokeGeometryBuilder + 'l>,
+    vertex: StrokeVertexData<'l>,
+    scratch: &'l mut StrokeTessellatorF64,
+}
+
+impl<'l> Stroker<'l> {
+    fn tessellate(
+        &mut self,
+        input: impl IntoIterator<Item = Event<Point2D<f64>, Point2D<f64>>>,
+    ) -> TessellationResult {
+        let tolerance = self.options.tolerance as f64;
+        let mut id = EndpointId(0);
+        let mut current = Point2D::origin();
+
+        for evt in input {
+            match evt {
+                Event::Begin { at } => {
+                    self.scratch.points.clear();
+                    self.vertex.origin = at;
+                    self.scratch.points.push(PathPoint {
+                        position: at,
+                        advancement: self.start_advancement,
+                        src: VertexSource::Endpoint { id },
+                        is_flattening_step: false,
+                    });
+                    current = at;
+                    id.0 += 1;
+                }
+                Event::Line { to, .. } => {
+                    self.push_point(to, VertexSource::Endpoint { id }, false);
+                    current = to;
+                    id.0 += 1;
+                }
+                Event::Quadratic { ctrl, to, .. } => {
+                    let curve = QuadraticBezierSegment {
+                        from: current,
+                        ctrl,
+                        to,
+                    };
+                    let from_id = EndpointId(id.0 - 1);
+                    curve.for_each_flattened_with_t(tolerance, &mut |segment, t| {
+                        self.push_flattened_point(segment, t, from_id, id);
+                    });
+                    current = to;
+                    id.0 += 1;
+                }
+                Event::Cubic {
+                    ctrl1, ctrl2, to, ..
+                } => {
+                    let curve = CubicBezierSegment {
+                        from: current,
+                        ctrl1,
+                        ctrl2,
+                        to,
+                    };
+                    let from_id = EndpointId(id.0 - 1);
+                    curve.for_each_flattened_with_t(tolerance, &mut |segment, t| {
+                        self.push_flattened_point(segment, t, from_id, id);
+                    });
+                    current = to;
+                    id.0 += 1;
+                }
+                Event::End { close, .. } => {
+                    let mut points = std::mem::take(&mut self.scratch.points);
+                    if close {
+                        let first = points[0];
+                        self.push_to(&mut points, first.position, first.src, false);
+                    }
+
+                    let result = if self.options.dash_array.is_solid() {
+                        self.stroke_polyline(&points, close)
+                    } else {
+                        self.stroke_dashes(&points, close)
+                    };
+
+                    self.start_advancement = points.last().unwrap().advancement;
+                    self.scratch.points = points;
+                    result?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn push_flattened_point(
+        &mut self,
+        segment: &LineSegment<f64>,
+        t: Range<f64>,
+        from: EndpointId,
+        to: EndpointId,
+    ) {
+        if t.end >= 1.0 {
+            self.push_point(segment.to, VertexSource::Endpoint { id: to }, false);
+        } else {
+            let src = VertexSource::Edge {
+                from,
+                to,
+                t: t.end as f32,
+            };
+            self.push_point(segment.to, src, true);
+        }
+    }
+
+    fn push_point(&mut self, position: Point2D<f64>, src: VertexSource, is_flattening_step: bool) {
+        let mut points = std::mem::take(&mut self.scratch.points);
+        self.push_to(&mut points, position, src, is_flattening_step);
+        self.scratch.points = points;
+    }
+
+    fn push_to(
+        &self,
+        points: &mut Vec<PathPoint>,
+        position: Point2D<f64>,
+        src: VertexSource,
+        is_flattening_step: bool,
+    ) {
+        let prev = points.last().unwrap();
+        points.push(PathPoint {
+            position,
+            advancement: prev.advancement + (position - prev.position).length(),
+            src,
+            is_flattening_step,
+        });
+    }
+
+    fn points_are_too_close(&self, p0: Point2D<f64>, p1: Point2D<f64>) -> bool {
+        (p0 - p1).square_length() < self.square_merge_threshold
+    }
+
+    /// Splits a sub-path into dashes and strokes them.
+    ///
+    /// If the sub-path is closed, `points` ends with its first point.
+    fn stroke_dashes(&mut self, points: &[PathPoint], close: bool) -> TessellationResult {
+        let dash_array = self.options.dash_array;
+        let lengths = dash_array.lengths();
+        let mut index = 0;
+        let mut remaining = lengths[0] as f64;
+        let mut offset =
+            (self.options.dash_offset as f64).rem_euclid(dash_array.total_length() as f64);
+        // Zero length dashes at the start of the sub-path are not skipped.
+        while offset > 0.0 && offset >= remaining {
+            offset -= remaining;
+            index = (index + 1) % lengths.len();
+            remaining = lengths[index] as f64;
+        }
+        remaining -= offset;
+
+        let mut dash = std::mem::take(&mut self.scratch.dash);
+        let mut first_dash = std::mem::take(&mut self.scratch.first_dash);
+        dash.clear();
+        first_dash.clear();
+
+        // The dash at the start of the sub-path is kept until the end, in case the sub-path
+        // is closed and the last dash continues into it.
+        let mut in_first_dash = index % 2 == 0;
+        if in_first_dash {
+            first_dash.push(points[0]);
+        }
+
+        let mut result = Ok(());
+        for edge in points.windows(2) {
+            let (a, b) = (edge[0], edge[1]);
+            let length = (b.position - a.position).length();
+            let mut distance = 0.0;
+            while remaining < length - distance {
+                distance += remaining;
+                let t = distance / length;
+                let split = PathPoint {
+                    position: a.position.lerp(b.position, t),
+                    advancement: a.advancement + distance,
+                    src: interpolate_source(a.src, b.src, t as f32),
+                    is_flattening_step: false,
+                };
+                if index % 2 == 1 {
+                    dash.push(split);
+                } else if in_first_dash {
+                    first_dash.push(split);
+                    in_first_dash = false;
+                } else {
+                    dash.push(split);
+                    result = result.and(self.stroke_polyline(&dash, false));
+                    dash.clear();
+                }
+                index = (index + 1) % lengths.len();
+                remaining = lengths[index] as f64;
+            }
+            remaining -= length - distance;
+
+            if index % 2 == 0 {
+                if in_first_dash {
+                    first_dash.push(b);
+                } else {
+                    dash.push(b);
+                }
+            }
+        }
+
+        let is_on = index % 2 == 0;
+        if close && in_first_dash {
+            // The sub-path is entirely covered by a single dash, it is closed normally.
+            result = result.and(self.stroke_polyline(&first_dash, true));
+        } else if close && is_on && !first_dash.is_empty() {
+            // The last dash continues into the first one.
+            for p in &first_dash[1..] {
+                let prev = dash.last().unwrap();
+                let advancement = prev.advancement + (p.position - prev.position).length();
+                dash.push(PathPoint { advancement, ..*p });
+            }
+            result = result.and(self.stroke_polyline(&dash, false));
+        } else {
+            if is_on && !in_first_dash {
+                result = result.and(self.stroke_polyline(&dash, false));
+            }
+            if !first_dash.is_empty() {
+                result = result.and(self.stroke_polyline(&first_dash, false));
+            }
+        }
+
+        self.scratch.dash = dash;
+        self.scratch.first_dash = first_dash;
+
+        result
+    }
+
+    /// Strokes a polyline with caps, or joins all around if it is closed.
+    ///
+    /// If the polyline is closed, `points` ends with its first point.
+    fn stroke_polyline(&mut self, points: &[PathPoint], close: bool) -> TessellationResult {
+        let (closing, open_points) = match points.split_last() {
+            Some((closing, rest)) if close => (Some(*closing), rest),
+            _ => (None, points),
+        };
+
+        let mut polyline = std::mem::take(&mut self.scratch.polyline);
+        polyline.clear();
+        for p in open_points {
+            match polyline.last() {
+                Some(last) if self.points_are_too_close(last.position, p.position) => {}
+                _ => polyline.push(*p),
+            }
+        }
+
+        let result = match closing {
+            Some(closing) if polyline.len() > 2 => {
+                if self
+                    .points_are_too_close(polyline[0].position, polyline.last().unwrap().position)
+                {
+                    polyline.pop();
+                }
+                self.tessellate_closed(&polyline, closing.advancement)
+            }
+            _ if polyline.len() == 1 => {
+                // Sub-paths that collapse into a point get a dot, unless they only have a
+                // single point and aren't closed.
+                if points.len() > 1 {
+                    self.tessellate_empty_cap(&polyline[0])
+                } else {
+                    Ok(())
+                }
+            }
+            _ => self.tessellate_open(&polyline),
+        };
+
+        self.scratch.polyline = polyline;
+
+        result
+    }
+
+    fn tessellate_open(&mut self, points: &[PathPoint]) -> TessellationResult {
+        let mut joins = std::mem::take(&mut self.scratch.joins);
+        joins.clear();
+
+        let result = self.tessellate_open_impl(points, &mut joins);
+        self.scratch.joins = joins;
+
+        result
+    }
+
+    fn tessellate_open_impl(
+        &mut self,
+        points: &[PathPoint],
+        joins: &mut Vec<Join>,
+    ) -> TessellationResult {
+        let n = points.len();
+        let (first, second) = (&points[0], &points[1]);
+        let (before_last, last) = (&points[n - 2], &points[n - 1]);
+
+        let start_direction = (first.position - second.position).normalize();
+        let end_direction = (last.position - before_last.position).normalize();
+        let start_cap =
+            self.add_cap_vertices(first, start_direction, self.options.start_cap, true)?;
+        joins.push(start_cap);
+        for i in 1..(n - 1) {
+            joins.push(self.add_join(&points[i - 1], &points[i], &points[i + 1])?);
+        }
+        let end_cap = self.add_cap_vertices(last, end_direction, self.options.end_cap, false)?;
+        joins.push(end_cap);
+
+        let start_part = square_cap_edge_part(self.options.start_cap, StrokePart::StartCap);
+        let end_part = square_cap_edge_part(self.options.end_cap, StrokePart::EndCap);
+        for i in 1..n {
+            let part = if i == 1 && start_part != StrokePart::Edge {
+                start_part
+            } else if i == n - 1 {
+                end_part
+            } else {
+                StrokePart::Edge
+            };
+            self.add_edge_triangles(&joins[i - 1], &joins[i], part);
+            if i < n - 1 {
+                self.add_join_triangles(&points[i], &joins[i])?;
+            }
+        }
+
+        let cap = &joins[0];
+        self.tessellate_cap(
+            first,
+            start_direction,
+            self.options.start_cap,
+            (
+                cap.sides[SIDE_NEGATIVE].next,
+                cap.sides[SIDE_NEGATIVE].next_vertex,
+            ),
+            cap.sides[SIDE_POSITIVE].next_vertex,
+            true,
+        )?;
+        let cap = &joins[n - 1];
+        self.tessellate_cap(
+            last,
+            end_direction,
+            self.options.end_cap,
+            (
+                cap.sides[SIDE_POSITIVE].prev,
+                cap.sides[SIDE_POSITIVE].prev_vertex,
+            ),
+            cap.sides[SIDE_NEGATIVE].prev_vertex,
+            false,
+        )
+    }
+
+    fn tessellate_closed(
+        &mut self,
+        points: &[PathPoint],
+        end_advancement: f64,
+    ) -> TessellationResult {
+        let mut joins = std::mem::take(&mut self.scratch.joins);
+        joins.clear();
+
+        let result = self.tessellate_closed_impl(points, end_advancement, &mut joins);
+        self.scratch.joins = joins;
+
+        result
+    }
+
+    fn tessellate_closed_impl(
+        &mut self,
+        points: &[PathPoint],
+        end_advancement: f64,
+        joins: &mut Vec<Join>,
+    ) -> TessellationResult {
+        let n = points.len();
+
+        // The join at the first point gets the advancement of the end of the sub-path and
+        // the first edge starts with extra vertices at the advancement of the start.
+        let first = points[0];
+        let closing = PathPoint {
+            advancement: end_advancement,
+            ..first
+        };
+        joins.push(self.add_join(&points[n - 1], &closing, &points[1])?);
+        for i in 1..n {
+            joins.push(self.add_join(&points[i - 1], &points[i], &points[(i + 1) % n])?);
+        }
+
+        let mut start = joins[0];
+        self.vertex.convex_side = None;
+        for (side, side_id) in [
+            (SIDE_POSITIVE, Side::Positive),
+            (SIDE_NEGATIVE, Side::Negative),
+        ] {
+            start.sides[side].next_vertex =
+                self.add_vertex(&first, start.sides[side].next, side_id)?;
+        }
+
+        for i in 1..n {
+            let prev = if i == 1 { &start } else { &joins[i - 1] };
+            self.add_edge_triangles(prev, &joins[i], StrokePart::Edge);
+            self.add_join_triangles(&points[i], &joins[i])?;
+        }
+        self.add_edge_triangles(&joins[n - 1], &joins[0], StrokePart::Edge);
+        self.add_join_triangles(&closing, &joins[0])
+    }
+
+    fn add_vertex(
+        &mut self,
+        point: &PathPoint,
+        normal: Vector2D<f64>,
+        side: Side,
+    ) -> Result<VertexId, TessellationError> {
+        self.vertex.position_on_path = (point.position - self.vertex.origin).to_f32().to_point();
+        self.vertex.position_on_path_f64 = Some(point.position);
+        self.vertex.normal = normal.to_f32();
+        self.vertex.advancement = point.advancement as f32;
+        self.vertex.src = point.src;
+        self.vertex.side = side;
+
+        Ok(self
+            .output
+            .add_stroke_vertex(StrokeVertex(&mut self.vertex, &()))?)
+    }
+
+    /// Adds the vertices at the start or end of an open sub-path.
+    ///
+    /// `direction` is the normalized direction pointing away from the sub-path. Square caps
+    /// move the vertices by a half width in that direction.
+    fn add_cap_vertices(
+        &mut self,
+        point: &PathPoint,
+        direction: Vector2D<f64>,
+        cap: LineCap,
+        is_start: bool,
+    ) -> Result<Join, TessellationError> {
+        let tangent = if is_start { -direction } else { direction };
+        let normal = Vector2D::new(-tangent.y, tangent.x);
+        let extension = match cap {
+            LineCap::Square => direction,
+            _ => Vector2D::zero(),
+        };
+
+        let mut join = Join::new(normal + extension, -normal + extension);
+        self.vertex.convex_side = None;
+        self.vertex.order = None;
+        for (side, side_id) in [
+            (SIDE_POSITIVE, Side::Positive),
+            (SIDE_NEGATIVE, Side::Negative),
+        ] {
+            let id = self.add_vertex(point, join.sides[side].next, side_id)?;
+            join.sides[side].prev_vertex = id;
+            join.sides[side].next_vertex = id;
+        }
+
+        Ok(join)
+    }
+
+    /// Computes the sides of the stroke at a join and adds their vertices.
+    ///
+    /// This is the double precision counterpart of `compute_join_side_positions_fixed_width`
+    /// and `add_join_base_vertices`.
+    fn add_join(
+        &mut self,
+        prev: &PathPoint,
+        join: &PathPoint,
+        next: &PathPoint,
+    ) -> Result<Join, TessellationError> {
+        let prev_tangent = join.position - prev.position;
+        let next_tangent = next.position - join.position;
+        let prev_length = prev_tangent.length();
+        let next_length = next_tangent.length();
+        let prev_tangent = prev_tangent / prev_length;
+        let next_tangent = next_tangent / next_length;
+
+        // Flattened curves are joined with miters unless they have a sharp turn.
+        let line_join = if join.is_flattening_step && prev_tangent.dot(next_tangent) > 0.0 {
+            LineJoin::Miter
+        } else {
+            self.options.line_join
+        };
+
+        let normal = compute_normal(prev_tangent, next_tangent);
+        let (front_side, front_normal) = if prev_tangent.cross(next_tangent) >= 0.0 {
+            (SIDE_NEGATIVE, -normal)
+        } else {
+            (SIDE_POSITIVE, normal)
+        };
+        let back_side = 1 - front_side;
+
+        let n0 = Vector2D::new(-prev_tangent.y, prev_tangent.x);
+        let n1 = Vector2D::new(-next_tangent.y, next_tangent.x);
+        let mut result = Join::new(n0, -n0);
+        result.sides[SIDE_POSITIVE].next = n1;
+        result.sides[SIDE_NEGATIVE].next = -n1;
+        result.line_join = line_join;
+
+        if next_tangent.dot(prev_tangent) < 0.0 {
+            // Project the back vertex on the previous and next edges and subtract the edge
+            // length to see if the back vertex ends up further than the opposite endpoint of
+            // the edge. If so, fold the join instead of creating a spike.
+            let extruded_normal = front_normal * self.half_width;
+            let d_next = extruded_normal.dot(-next_tangent) - next_length;
+            let d_prev = extruded_normal.dot(prev_tangent) - prev_length;
+            if d_next.min(d_prev) > 0.0 || normal.square_length() < 1e-5 {
+                result.fold[front_side] = true;
+            }
+        }
+
+        if !result.fold[front_side] {
+            let miter = [normal, -normal];
+            result.sides[back_side].set_single_vertex(miter[back_side]);
+            let is_miter = matches!(line_join, LineJoin::Miter | LineJoin::MiterClip);
+            let miter_limit = self.options.miter_limit as f64;
+            if is_miter && !miter_limit_is_exceeded(front_normal, miter_limit) {
+                result.sides[front_side].set_single_vertex(miter[front_side]);
+            } else if line_join == LineJoin::MiterClip {
+                let side = &mut result.sides[front_side];
+                let (prev_normal, next_normal) = get_clip_intersections(
+                    side.prev,
+                    side.next,
+                    front_normal,
+                    prev_tangent,
+                    self.options.effective_miter_clip_distance() as f64,
+                );
+                side.prev = prev_normal;
+                side.next = next_normal;
+            }
+        }
+
+        self.vertex.convex_side = Some(convex_side(prev.position, join.position, next.position));
+        for (side, side_id) in [
+            (SIDE_NEGATIVE, Side::Negative),
+            (SIDE_POSITIVE, Side::Positive),
+        ] {
+            let s = result.sides[side];
+            if s.single_vertex {
+                self.vertex.order = None;
+                let id = self.add_vertex(join, s.prev, side_id)?;
+                result.sides[side].prev_vertex = id;
+                result.sides[side].next_vertex = id;
+            } else {
+                self.vertex.order = Some(Order::Before);
+                result.sides[side].prev_vertex = self.add_vertex(join, s.prev, side_id)?;
+                self.vertex.order = Some(Order::After);
+                result.sides[side].next_vertex = self.add_vertex(join, s.next, side_id)?;
+            }
+        }
+        self.vertex.order = None;
+
+        Ok(result)
+    }
+
+    /// See `add_edge_triangles` in the stroke module.
+    fn add_edge_triangles(&mut self, p0: &Join, p1: &Join, part: StrokePart) {
+        let mut p0_neg = p0.sides[SIDE_NEGATIVE].next_vertex;
+        let mut p0_pos = p0.sides[SIDE_POSITIVE].next_vertex;
+        let mut p1_neg = p1.sides[SIDE_NEGATIVE].prev_vertex;
+        let mut p1_pos = p1.sides[SIDE_POSITIVE].prev_vertex;
+
+        if p0.fold[SIDE_POSITIVE] {
+            p0_neg = p0.sides[SIDE_POSITIVE].prev_vertex;
+        }
+        if p0.fold[SIDE_NEGATIVE] {
+            p0_pos = p0.sides[SIDE_NEGATIVE].prev_vertex;
+        }
+        if p1.fold[SIDE_POSITIVE] {
+            p1_neg = p1.sides[SIDE_POSITIVE].next_vertex;
+        }
+        if p1.fold[SIDE_NEGATIVE] {
+            p1_pos = p1.sides[SIDE_NEGATIVE].next_vertex;
+        }
+
+        self.output.set_stroke_part(part);
+        self.output.add_triangle(p0_neg, p0_pos, p1_pos);
+        self.output.add_triangle(p0_neg, p1_pos, p1_neg);
+    }
+
+    /// See `tessellate_join` in the stroke module.
+    fn add_join_triangles(&mut self, point: &PathPoint, join: &Join) -> TessellationResult {
+        let pos = &join.sides[SIDE_POSITIVE];
+        let neg = &join.sides[SIDE_NEGATIVE];
+
+        self.output.set_stroke_part(StrokePart::Join);
+        if !join.fold[SIDE_POSITIVE] && !join.fold[SIDE_NEGATIVE] {
+            match (pos.single_vertex, neg.single_vertex) {
+                (false, false) => {
+                    self.output
+                        .add_triangle(pos.prev_vertex, pos.next_vertex, neg.next_vertex);
+                    self.output
+                        .add_triangle(pos.prev_vertex, neg.next_vertex, neg.prev_vertex);
+                }
+                (true, false) => {
+                    self.output
+                        .add_triangle(neg.prev_vertex, pos.prev_vertex, neg.next_vertex);
+                }
+                (false, true) => {
+                    self.output
+                        .add_triangle(neg.prev_vertex, pos.prev_vertex, pos.next_vertex);
+                }
+                (true, true) => {}
+            }
+        }
+
+        if join.line_join == LineJoin::Round {
+            for side in [SIDE_POSITIVE, SIDE_NEGATIVE] {
+                if !join.sides[side].single_vertex {
+                    self.tessellate_round_join(point, join, side)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// See `tessellate_round_join` in the stroke module.
+    fn tessellate_round_join(
+        &mut self,
+        point: &PathPoint,
+        join: &Join,
+        side: usize,
+    ) -> TessellationResult {
+        let s = &join.sides[side];
+        let mut start_vertex = s.prev_vertex;
+        let mut end_vertex = s.next_vertex;
+
+        let angle_sign = if side == SIDE_NEGATIVE { 1.0 } else { -1.0 };
+        let mut start_angle = s.prev.angle_from_x_axis();
+        let mut diff = start_angle.angle_to(s.next.angle_from_x_axis());
+        // If the angle doesn't have the desired sign, adjust it.
+        if diff.radians * angle_sign < 0.0 {
+            diff.radians = angle_sign * (2.0 * PI - diff.radians.abs());
+        }
+        let mut end_angle = start_angle + diff;
+
+        if side == SIDE_NEGATIVE {
+            // Flip to keep consistent winding order.
+            std::mem::swap(&mut start_angle, &mut end_angle);
+            std::mem::swap(&mut start_vertex, &mut end_vertex);
+        }
+
+        let num_segments = self.num_arc_segments(diff.radians.abs(), 1);
+        let side = if side == SIDE_POSITIVE {
+            Side::Positive
+        } else {
+            Side::Negative
+        };
+
+        self.tessellate_arc_fan(
+            point,
+            (start_angle.radians, end_angle.radians),
+            start_vertex,
+            end_vertex,
+            num_segments,
+            side,
+        )
+    }
+
+    /// Number of segments of an arc of the given angle, with at least `min` segments.
+    fn num_arc_segments(&self, angle: f64, min: u32) -> u32 {
+        let num_segments = match self.options.round_segments {
+            Some(n) => (angle / PI * n as f64).ceil(),
+            None => (angle
+                / circle_flattening_step(self.half_width, self.options.tolerance as f64))
+            .ceil(),
+        };
+
+        if num_segments.is_finite() {
+            (num_segments as u32).max(min)
+        } else {
+            min
+        }
+    }
+
+    /// See `tessellate_arc_fan` in the stroke module.
+    fn tessellate_arc_fan(
+        &mut self,
+        point: &PathPoint,
+        angle: (f64, f64),
+        va: VertexId,
+        vb: VertexId,
+        num_segments: u32,
+        side: Side,
+    ) -> TessellationResult {
+        let step = (angle.1 - angle.0) / num_segments as f64;
+        let mut prev = None;
+        for i in 1..num_segments {
+            let a = angle.0 + step * i as f64;
+            let vertex_id = self.add_vertex(point, Vector2D::new(a.cos(), a.sin()), side)?;
+            if let Some(prev) = prev {
+                self.output.add_triangle(va, prev, vertex_id);
+            }
+            prev = Some(vertex_id);
+        }
+
+        if let Some(prev) = prev {
+            self.output.add_triangle(va, prev, vb);
+        }
+
+        Ok(())
+    }
+
+    /// Adds the geometry of a round or arrow cap, square caps extend the edge instead.
+    ///
+    /// `start` is the normal and the vertex of the side where the cap starts and `end_vertex`
+    /// is the vertex of the other side.
+    fn tessellate_cap(
+        &mut self,
+        point: &PathPoint,
+        direction: Vector2D<f64>,
+        cap: LineCap,
+        start: (Vector2D<f64>, VertexId),
+        end_vertex: VertexId,
+        is_start: bool,
+    ) -> TessellationResult {
+        let part = if is_start {
+            StrokePart::StartCap
+        } else {
+            StrokePart::EndCap
+        };
+
+        match cap {
+            LineCap::Round => {
+                self.tessellate_round_cap(point, direction, start, end_vertex, is_start)
+            }
+            LineCap::Arrow => {
+                let (start_normal, start_vertex) = start;
+                let (length, width) = (self.options.arrow_length, self.options.arrow_width);
+                let width = width.max(1.0) as f64;
+                self.vertex.convex_side = None;
+
+                let tip = direction * (length.max(0.0) as f64 * 2.0);
+                let tip = self.add_vertex(point, tip, Side::Positive)?;
+                let start_side = if is_start {
+                    Side::Negative
+                } else {
+                    Side::Positive
+                };
+                let start_base = self.add_vertex(point, start_normal * width, start_side)?;
+                let end_base =
+                    self.add_vertex(point, -start_normal * width, start_side.opposite())?;
+
+                self.output.set_stroke_part(part);
+                self.output.add_triangle(start_base, tip, start_vertex);
+                self.output.add_triangle(start_vertex, tip, end_vertex);
+                self.output.add_triangle(end_vertex, tip, end_base);
+
+                Ok(())
+            }
+            LineCap::Butt | LineCap::Square => Ok(()),
+        }
+    }
+
+    /// See `tessellate_round_cap` in the stroke module.
+    fn tessellate_round_cap(
+        &mut self,
+        point: &PathPoint,
+        direction: Vector2D<f64>,
+        start: (Vector2D<f64>, VertexId),
+        end_vertex: VertexId,
+        is_start: bool,
+    ) -> TessellationResult {
+        if self.half_width < self.options.tolerance as f64 {
+            return Ok(());
+        }
+
+        let (start_normal, start_vertex) = start;
+        let first_side = if is_start ^ (direction.cross(start_normal) >= 0.0) {
+            Side::Positive
+        } else {
+            Side::Negative
+        };
+
+        let start_angle = start_normal.angle_from_x_axis();
+        let diff = start_angle.angle_to(direction.angle_from_x_axis());
+        let mid_angle = start_angle + diff;
+        let end_angle = mid_angle + diff;
+
+        // Caps are made of two quarter circles, so odd numbers of segments are rounded up.
+        let num_segments = match self.options.round_segments {
+            Some(n) => n.div_ceil(2).max(1),
+            None => self.num_arc_segments(diff.radians.abs(), 1),
+        };
+
+        self.vertex.convex_side = None;
+        let mid_vertex = self.add_vertex(point, direction, first_side)?;
+
+        self.output.set_stroke_part(if is_start {
+            StrokePart::StartCap
+        } else {
+            StrokePart::EndCap
+        });
+        self.output
+            .add_triangle(start_vertex, mid_vertex, end_vertex);
+
+        self.tessellate_arc_fan(
+            point,
+            (start_angle.radians, mid_angle.radians),
+            start_vertex,
+            mid_vertex,
+            num_segments,
+            first_side,
+        )?;
+        self.tessellate_arc_fan(
+            point,
+            (mid_angle.radians, end_angle.radians),
+            mid_vertex,
+            end_vertex,
+            num_segments,
+            first_side.opposite(),
+        )
+    }
+
+    /// The cap of a sub-path that has collapsed into a single point.
+    fn tessellate_empty_cap(&mut self, point: &PathPoint) -> TessellationResult {
+        self.vertex.convex_side = None;
+        match self.options.start_cap {
+            LineCap::Square => {
+                let a = self.add_vertex(point, Vector2D::new(1.0, 1.0), Side::Negative)?;
+                let b = self.add_vertex(point, Vector2D::new(1.0, -1.0), Side::Positive)?;
+                let c = self.add_vertex(point, Vector2D::new(-1.0, -1.0), Side::Positive)?;
+                let d = self.add_vertex(point, Vector2D::new(-1.0, 1.0), Side::Negative)?;
+
+                self.output.set_stroke_part(StrokePart::StartCap);
+                self.output.add_triangle(a, b, c);
+                self.output.add_triangle(a, c, d);
+
+                Ok(())
+            }
+            LineCap::Round => {
+                let left = Vector2D::new(-1.0, 0.0);
+                let right = Vector2D::new(1.0, 0.0);
+                let left_id = self.add_vertex(point, left, Side::Positive)?;
+                let right_id = self.add_vertex(point, right, Side::Negative)?;
+
+                self.tessellate_round_cap(
+                    point,
+                    Vector2D::new(0.0, 1.0),
+                    (left, left_id),
+                    right_id,
+                    true,
+                )?;
+                self.tessellate_round_cap(
+                    point,
+                    Vector2D::new(0.0, -1.0),
+                    (right, right_id),
+                    left_id,
+                    false,
+                )
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+use crate::geometry_builder::{BuffersBuilder, VertexBuffers};
+#[cfg(test)]
+use crate::math::Point;
+#[cfg(test)]
+use crate::path::{Path, PathEvent};
+#[cfg(test)]
+use crate::{DashArray, StrokeTessellator, UnsupportedParamater};
+
+#[cfg(test)]
+fn to_f64_events(
+    path: &Path,
+    offset: Vector2D<f64>,
+) -> impl Iterator<Item = Event<Point2D<f64>, Point2D<f64>>> + '_ {
+    let p = move |p: Point| p.to_f64() + offset;
+    path.iter().map(move |event| match event {
+        PathEvent::Begin { at } => Event::Begin { at: p(at) },
+        PathEvent::Line { from, to } => Event::Line {
+            from: p(from),
+            to: p(to),
+        },
+        PathEvent::Quadratic { from, ctrl, to } => Event::Quadratic {
+            from: p(from),
+            ctrl: p(ctrl),
+            to: p(to),
+        },
+        PathEvent::Cubic {
+            from,
+            ctrl1,
+            ctrl2,
+            to,
+        } => Event::Cubic {
+            from: p(from),
+            ctrl1: p(ctrl1),
+            ctrl2: p(ctrl2),
+            to: p(to),
+        },
+        PathEvent::End { last, first, close } => Event::End {
+            last: p(last),
+            first: p(first),
+            close,
+        },
+    })
+}
+
+#[cfg(test)]
+fn triangles_area<V: Copy>(buffers: &VertexBuffers<V, u16>, position: impl Fn(V) -> Point) -> f32 {
+    let mut area = 0.0;
+    for tri in buffers.indices.chunks(3) {
+        let a = position(buffers.vertices[tri[0] as usize]);
+        let b = position(buffers.vertices[tri[1] as usize]);
+        let c = position(buffers.vertices[tri[2] as usize]);
+        area += (b - a).cross(c - a).abs() * 0.5;
+    }
+
+    area
+}
+
+#[test]
+fn map_scale_miter_join() {
+    // At this scale single precision floats are 8 units apart.
+    let o = Point2D::new(123_456_789.0, -98_765_432.0);
+    let events = [
+        Event::Begin { at: o },
+        Event::Line {
+            from: o,
+            to: o + Vector2D::new(1e6, 0.0),
+        },
+        Event::Line {
+            from: o + Vector2D::new(1e6, 0.0),
+            to: o + Vector2D::new(1e6, 1e6),
+        },
+        Event::End {
+            last: o + Vector2D::new(1e6, 1e6),
+            first: o,
+            close: false,
+        },
+    ];
+
+    let mut buffers: VertexBuffers<(Point2D<f64>, Point2D<f64>), u16> = VertexBuffers::new();
+    StrokeTessellatorF64::new()
+        .tessellate(
+            events.iter().cloned(),
+            &StrokeOptions::default()
+                .with_line_width(0.25)
+                .with_line_join(LineJoin::Miter),
+            &mut BuffersBuilder::new(&mut buffers, |v: StrokeVertex| {
+                (v.position_f64(), v.origin())
+            }),
+        )
+        .unwrap();
+
+    let expected = [
+        (0.0, -0.125),
+        (0.0, 0.125),
+        (1e6 + 0.125, -0.125),
+        (1e6 - 0.125, 0.125),
+        (1e6 + 0.125, 1e6),
+        (1e6 - 0.125, 1e6),
+    ];
+    assert_eq!(buffers.vertices.len(), expected.len());
+    assert_eq!(buffers.indices.len(), 12);
+    for (x, y) in expected {
+        let expected = o + Vector2D::new(x, y);
+        assert!(
+            buffers
+                .vertices
+                .iter()
+                .any(|v| (v.0 - expected).length() < 1e-6),
+            "missing vertex {:?} in {:?}",
+            expected,
+            buffers.vertices
+        );
+    }
+    assert!(buffers.vertices.iter().all(|v| v.1 == o));
+}
+
+#[test]
+fn same_area_as_single_precision() {
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.quadratic_bezier_to(point(10.5, 3.0), point(0.5, 3.5));
+    builder.cubic_bezier_to(point(-5.0, 4.0), point(0.0, 8.0), point(4.0, 7.0));
+    builder.line_to(point(3.0, 5.0));
+    builder.end(false);
+    builder.begin(point(20.0, 0.0));
+    builder.line_to(point(30.0, 0.0));
+    builder.line_to(point(25.0, 6.0));
+    builder.line_to(point(26.0, 1.0));
+    builder.end(true);
+    builder.begin(point(40.0, 0.0));
+    builder.end(true);
+    let path = builder.build();
+
+    let offset = Vector2D::new(1e7, -1e7);
+    let base = StrokeOptions::default()
+        .with_line_width(0.5)
+        .with_arrow_length(1.0)
+        .with_arrow_width(2.0);
+    let dashes = DashArray::new(&[2.0, 1.0]).unwrap();
+    for join in [
+        LineJoin::Miter,
+        LineJoin::MiterClip,
+        LineJoin::Round,
+        LineJoin::Bevel,
+    ] {
+        for cap in [
+            LineCap::Butt,
+            LineCap::Square,
+            LineCap::Round,
+            LineCap::Arrow,
+        ] {
+            for dash_array in [DashArray::default(), dashes] {
+                let options = base
+                    .with_line_join(join)
+                    .with_line_cap(cap)
+                    .with_dash_array(dash_array)
+                    .with_dash_offset(0.5);
+
+                let mut reference: VertexBuffers<Point, u16> = VertexBuffers::new();
+                StrokeTessellator::new()
+                    .tessellate_path(
+                        &path,
+                        &options,
+                        &mut BuffersBuilder::new(&mut reference, |v: StrokeVertex| v.position()),
+                    )
+                    .unwrap();
+
+                let mut buffers: VertexBuffers<Point2D<f64>, u16> = VertexBuffers::new();
+                StrokeTessellatorF64::new()
+                    .tessellate(
+                        to_f64_events(&path, offset),
+                        &options,
+                        &mut BuffersBuilder::new(&mut buffers, |v: StrokeVertex| v.position_f64()),
+                    )
+                    .unwrap();
+
+                // Same topology as the single precision tessellation near the origin.
+                assert_eq!(buffers.vertices.len(), reference.vertices.len());
+                assert_eq!(buffers.indices.len(), reference.indices.len());
+                let expected = triangles_area(&reference, |p| p);
+                let area = triangles_area(&buffers, |p| (p - offset).to_f32());
+                assert!(
+                    (area - expected).abs() < expected * 1e-4,
+                    "{:?} {:?} {:?}: {} != {}",
+                    join,
+                    cap,
+                    dash_array,
+                    area,
+                    expected
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn dashes() {
+    let o = Point2D::new(1e9, 1e9);
+    let to = o + Vector2D::new(10.0, 0.0);
+    let events = [
+        Event::Begin { at: o },
+        Event::Line { from: o, to },
+        Event::End {
+            last: to,
+            first: o,
+            close: false,
+        },
+    ];
+
+    let mut buffers: VertexBuffers<Point2D<f64>, u16> = VertexBuffers::new();
+    StrokeTessellatorF64::new()
+        .tessellate(
+            events.iter().cloned(),
+            &StrokeOptions::default().with_dash_array(DashArray::new(&[2.0, 2.0]).unwrap()),
+            &mut BuffersBuilder::new(&mut buffers, |v: StrokeVertex| v.position_f64()),
+        )
+        .unwrap();
+
+    // Three dashes of four vertices, starting at 0, 4 and 8.
+    assert_eq!(buffers.vertices.len(), 12);
+    assert_eq!(buffers.indices.len(), 18);
+    for x in [0.0, 2.0, 4.0, 6.0, 8.0, 10.0] {
+        assert!(buffers.vertices.iter().any(|v| v.x == o.x + x));
+    }
+}
+
+#[test]
+fn unsupported_options() {
+    let events = [
+        Event::Begin {
+            at: Point2D::new(0.0, 0.0),
+        },
+        Event::End {
+            last: Point2D::new(0.0, 0.0),
+            first: Point2D::new(0.0, 0.0),
+            close: true,
+        },
+    ];
+
+    let base = StrokeOptions::default();
+    for options in [
+        base.with_variable_line_width(0),
+        base.with_start_width(2.0),
+        base.with_normalized_advancement(true),
+        base.with_anti_aliasing_fringe(1.0),
+        base.with_max_vertices(10),
+        base.with_line_join(LineJoin::Arcs),
+    ] {
+        let mut buffers: VertexBuffers<Point2D<f64>, u16> = VertexBuffers::new();
+        let result = StrokeTessellatorF64::new().tessellate(
+            events.iter().cloned(),
+            &options,
+            &mut BuffersBuilder::new(&mut buffers, |v: StrokeVertex| v.position_f64()),
+        );
+
+        assert!(matches!(
+            result,
+            Err(TessellationError::UnsupportedParamater(
+                UnsupportedParamater::StrokeOptionIsNotSupported(_)
+            ))
+        ));
+        assert!(buffers.vertices.is_empty());
+    }
+}
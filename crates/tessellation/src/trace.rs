@@ -0,0 +1,255 @@
+//! Spans reported to the `tracing` crate when the `tracing` feature is enabled.
+//!
+//! The tessellators enter `trace` level spans named after their phases:
+//!
+//! - `event_queue`: flattening the path and sorting its edges for the fill tessellator,
+//! - `sweep`: the fill tessellator's sweep line,
+//! - `stroke`: stroking a path, including flattening, joins and caps,
+//! - `flatten`: flattening a curve,
+//! - `join` and `cap`: generating a stroke join or the caps of a sub-path.
+//!
+//! The `sweep` and `stroke` spans record the amount of geometry produced by successful
+//! tessellations in their `vertices` and `triangles` fields.
+//!
+//! Without the feature, the macros expand to nothing.
+
+#[cfg(feature = "tracing")]
+use crate::geometry_builder::{
+    FillGeometryBuilder, GeometryBuilder, GeometryBuilderError, StrokeGeometryBuilder, StrokePart,
+};
+#[cfg(feature = "tracing")]
+use crate::{FillVertex, StrokeVertex, VertexId};
+
+/// Enters a span until the end of the current scope, if tracing is enabled.
+macro_rules! trace_span {
+    ($name:literal) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!($name).entered();
+    };
+}
+
+/// Enters a span until the end of the current scope and shadows `$output` with a
+/// geometry builder that records the number of vertices and triangles in the span,
+/// if tracing is enabled.
+macro_rules! trace_output_span {
+    ($name:literal, $output:ident: $builder:ty) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!(
+            $name,
+            vertices = tracing::field::Empty,
+            triangles = tracing::field::Empty
+        )
+        .entered();
+        #[cfg(feature = "tracing")]
+        let $output: &mut $builder =
+            &mut crate::trace::OutputCounter::new($output, tracing::Span::clone(&_span));
+    };
+}
+
+pub(crate) use trace_output_span;
+pub(crate) use trace_span;
+
+/// A geometry builder wrapper that records the amount of geometry produced in a span.
+#[cfg(feature = "tracing")]
+pub(crate) struct OutputCounter<'l, B: ?Sized> {
+    output: &'l mut B,
+    span: tracing::Span,
+    num_vertices: u32,
+    num_triangles: u32,
+}
+
+#[cfg(feature = "tracing")]
+impl<'l, B: ?Sized> OutputCounter<'l, B> {
+    pub fn new(output: &'l mut B, span: tracing::Span) -> Self {
+        OutputCounter {
+            output,
+            span,
+            num_vertices: 0,
+            num_triangles: 0,
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl<'l, B: ?Sized + GeometryBuilder> GeometryBuilder for OutputCounter<'l, B> {
+    fn begin_geometry(&mut self) {
+        self.num_vertices = 0;
+        self.num_triangles = 0;
+        self.output.begin_geometry();
+    }
+
+    fn end_geometry(&mut self) {
+        self.span.record("vertices", &self.num_vertices);
+        self.span.record("triangles", &self.num_triangles);
+        self.output.end_geometry();
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        self.num_triangles += 1;
+        self.output.add_triangle(a, b, c);
+    }
+
+    fn abort_geometry(&mut self) {
+        self.output.abort_geometry();
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl<'l, B: ?Sized + FillGeometryBuilder> FillGeometryBuilder for OutputCounter<'l, B> {
+    fn add_fill_vertex(&mut self, vertex: FillVertex) -> Result<VertexId, GeometryBuilderError> {
+        let id = self.output.add_fill_vertex(vertex)?;
+        self.num_vertices += 1;
+
+        Ok(id)
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl<'l, B: ?Sized + StrokeGeometryBuilder> StrokeGeometryBuilder for OutputCounter<'l, B> {
+    fn add_stroke_vertex(
+        &mut self,
+        vertex: StrokeVertex,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        let id = self.output.add_stroke_vertex(vertex)?;
+        self.num_vertices += 1;
+
+        Ok(id)
+    }
+
+    fn set_stroke_part(&mut self, part: StrokePart) {
+        self.output.set_stroke_part(part);
+    }
+}
+
+#[cfg(feature = "tracing")]
+#[test]
+fn tracing_spans() {
+    use crate::geometry_builder::{simple_builder, VertexBuffers};
+    use crate::math::{point, Point};
+    use crate::path::Path;
+    use crate::{FillOptions, FillTessellator, LineJoin, StrokeOptions, StrokeTessellator};
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    #[derive(Debug, PartialEq)]
+    enum Trace {
+        Enter(&'static str),
+        Exit(&'static str),
+        Record(&'static str, String, u64),
+    }
+
+    // Records the spans and their fields. Span ids are indices into `names`, plus one.
+    #[derive(Clone, Default)]
+    struct Recorder {
+        names: Arc<Mutex<Vec<&'static str>>>,
+        traces: Arc<Mutex<Vec<Trace>>>,
+    }
+
+    impl Recorder {
+        fn name(&self, span: &Id) -> &'static str {
+            self.names.lock().unwrap()[span.into_u64() as usize - 1]
+        }
+    }
+
+    struct FieldRecorder<'l>(&'static str, &'l mut Vec<Trace>);
+
+    impl<'l> Visit for FieldRecorder<'l> {
+        fn record_u64(&mut self, field: &Field, value: u64) {
+            self.1
+                .push(Trace::Record(self.0, field.name().to_string(), value));
+        }
+        fn record_debug(&mut self, _: &Field, _: &dyn std::fmt::Debug) {}
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _: &Metadata) -> bool {
+            true
+        }
+        fn new_span(&self, span: &Attributes) -> Id {
+            let mut names = self.names.lock().unwrap();
+            names.push(span.metadata().name());
+            Id::from_u64(names.len() as u64)
+        }
+        fn record(&self, span: &Id, values: &Record) {
+            let name = self.name(span);
+            values.record(&mut FieldRecorder(name, &mut self.traces.lock().unwrap()));
+        }
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+        fn event(&self, _: &Event) {}
+        fn enter(&self, span: &Id) {
+            let name = self.name(span);
+            self.traces.lock().unwrap().push(Trace::Enter(name));
+        }
+        fn exit(&self, span: &Id) {
+            let name = self.name(span);
+            self.traces.lock().unwrap().push(Trace::Exit(name));
+        }
+    }
+
+    let recorder = Recorder::default();
+    let _guard = tracing::subscriber::set_default(recorder.clone());
+
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.quadratic_bezier_to(point(10.0, 10.0), point(0.0, 10.0));
+    builder.end(false);
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    FillTessellator::new()
+        .tessellate_path(
+            &path,
+            &FillOptions::default(),
+            &mut simple_builder(&mut buffers),
+        )
+        .unwrap();
+
+    {
+        let traces = recorder.traces.lock().unwrap();
+        assert_eq!(traces[0], Trace::Enter("event_queue"));
+        assert_eq!(traces[1], Trace::Enter("flatten"));
+        assert!(traces.contains(&Trace::Exit("event_queue")));
+        assert!(traces.contains(&Trace::Enter("sweep")));
+        assert!(traces.contains(&Trace::Record(
+            "sweep",
+            "vertices".to_string(),
+            buffers.vertices.len() as u64
+        )));
+        assert!(traces.contains(&Trace::Record(
+            "sweep",
+            "triangles".to_string(),
+            buffers.indices.len() as u64 / 3
+        )));
+        assert_eq!(traces.last(), Some(&Trace::Exit("sweep")));
+    }
+    recorder.traces.lock().unwrap().clear();
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    StrokeTessellator::new()
+        .tessellate_path(
+            &path,
+            &StrokeOptions::default().with_line_join(LineJoin::Round),
+            &mut simple_builder(&mut buffers),
+        )
+        .unwrap();
+
+    let traces = recorder.traces.lock().unwrap();
+    assert_eq!(traces.first(), Some(&Trace::Enter("stroke")));
+    assert_eq!(traces.last(), Some(&Trace::Exit("stroke")));
+    assert!(traces.contains(&Trace::Enter("flatten")));
+    assert!(traces.contains(&Trace::Enter("join")));
+    assert!(traces.contains(&Trace::Exit("cap")));
+    assert!(traces.contains(&Trace::Record(
+        "stroke",
+        "vertices".to_string(),
+        buffers.vertices.len() as u64
+    )));
+    assert!(traces.contains(&Trace::Record(
+        "stroke",
+        "triangles".to_string(),
+        buffers.indices.len() as u64 / 3
+    )));
+}